@@ -0,0 +1,79 @@
+//! Compaction of many small documents against the same large context, with
+//! and without reusing the processed context.
+use criterion::{criterion_group, criterion_main, Criterion};
+use futures::executor::block_on;
+use json_ld::{
+	syntax::{context, TryFromJson},
+	JsonLdProcessor, NoLoader, Options, Process, RemoteContextReference, RemoteDocument,
+};
+
+/// Number of terms defined by the context.
+const TERMS: usize = 1500;
+
+fn context() -> context::Context {
+	let mut definition = json_syntax::Object::new();
+	definition.insert("@vocab".into(), "https://example.com/vocab#".into());
+	for i in 0..TERMS {
+		definition.insert(
+			format!("term{i}").into(),
+			json_syntax::json!({ "@id": format!("https://example.com/vocab#term{i}"), "@type": "@id" }),
+		);
+	}
+
+	context::Context::try_from_json(definition.into()).unwrap()
+}
+
+fn document(i: usize) -> RemoteDocument {
+	RemoteDocument::new(
+		Some(iref::IriBuf::new(format!("https://example.com/documents/{i}")).unwrap()),
+		None,
+		json_syntax::json!({
+			"@id": format!("https://example.com/documents/{i}#it"),
+			"https://example.com/vocab#term0": { "@id": "https://example.com/a" },
+			"https://example.com/vocab#term1": "value"
+		}),
+	)
+}
+
+fn compact(c: &mut Criterion) {
+	let context = context();
+	let documents: Vec<_> = (0..10).map(document).collect();
+
+	let mut group = c.benchmark_group("compact");
+
+	group.bench_function("compact", |b| {
+		b.iter(|| {
+			for document in &documents {
+				block_on(document.compact(
+					RemoteContextReference::Loaded(RemoteDocument::new(
+						None,
+						None,
+						context.clone(),
+					)),
+					&NoLoader,
+				))
+				.unwrap();
+			}
+		})
+	});
+
+	group.bench_function("compact_with_processed", |b| {
+		let processed = block_on(context.process(&mut (), &NoLoader, None)).unwrap();
+		b.iter(|| {
+			for document in &documents {
+				block_on(document.compact_with_processed(
+					&mut (),
+					processed.as_ref(),
+					&NoLoader,
+					Options::default(),
+				))
+				.unwrap();
+			}
+		})
+	});
+
+	group.finish()
+}
+
+criterion_group!(benches, compact);
+criterion_main!(benches);
@@ -0,0 +1,314 @@
+//! Build script helper embedding JSON-LD contexts at compile time.
+//!
+//! This crate is meant to be used from a `build.rs` script. It takes
+//! directories of context files, validates every context, and generates Rust
+//! code registering them in a [`StaticLoader`]. Invalid contexts make the
+//! build fail, instead of failing at runtime.
+//!
+//! ```no_run
+//! // build.rs
+//! use iref::IriBuf;
+//!
+//! fn main() {
+//!     json_ld_build::Builder::new()
+//!         .mount(
+//!             IriBuf::new("https://example.com/contexts/".to_owned()).unwrap(),
+//!             "contexts",
+//!         )
+//!         .build("contexts.rs")
+//! }
+//! ```
+//!
+//! The generated file then provides a `loader` function returning the
+//! [`StaticLoader`]:
+//!
+//! ```ignore
+//! include!(concat!(env!("OUT_DIR"), "/contexts.rs"));
+//!
+//! let loader = loader();
+//! ```
+//!
+//! [`StaticLoader`]: json_ld_core::StaticLoader
+use futures::executor::block_on;
+use iref::{IriBuf, IriRefBuf};
+use json_ld_context_processing::Process;
+use json_ld_core::{ExtractContext, ExtractContextError, RemoteDocument};
+use json_syntax::Parse;
+use std::{
+	collections::HashMap,
+	fmt::Write,
+	fs,
+	path::{Path, PathBuf},
+};
+
+/// Build error.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+	#[error("unable to read `{0}`: {1}")]
+	IO(PathBuf, std::io::Error),
+
+	#[error("`{0}` cannot be mapped to an IRI")]
+	InvalidPath(PathBuf),
+
+	#[error("`{0}` is not a valid JSON document: {1}")]
+	Parse(PathBuf, json_syntax::parse::Error),
+
+	#[error("`{0}` is not a valid JSON-LD context document: {1}")]
+	InvalidContext(PathBuf, ExtractContextError),
+
+	#[error("processing `{0}` failed: {1}")]
+	Processing(PathBuf, json_ld_context_processing::Error),
+
+	#[error("unable to write `{0}`: {1}")]
+	Write(PathBuf, std::io::Error),
+}
+
+/// Embedded context file.
+struct Entry {
+	url: IriBuf,
+	path: PathBuf,
+	document: json_syntax::Value,
+}
+
+/// Static loader generator.
+pub struct Builder {
+	mount_points: Vec<(IriBuf, PathBuf)>,
+	crate_path: String,
+	process: bool,
+}
+
+impl Default for Builder {
+	fn default() -> Self {
+		Self {
+			mount_points: Vec::new(),
+			crate_path: "::json_ld".to_owned(),
+			process: true,
+		}
+	}
+}
+
+impl Builder {
+	/// Creates a new builder with no mounted directory.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Embeds every file of the directory at `path` (recursively).
+	///
+	/// Each file is registered with the IRI obtained by resolving its path
+	/// relative to the directory against `url`, in the same way as the
+	/// `FsLoader` mount points. For instance, if `url` is
+	/// `https://example.com/contexts/`, the file `path/v1/main.jsonld` is
+	/// registered as `https://example.com/contexts/v1/main.jsonld`.
+	pub fn mount(mut self, url: IriBuf, path: impl AsRef<Path>) -> Self {
+		self.mount_points.push((url, path.as_ref().to_owned()));
+		self
+	}
+
+	/// Sets the path of the crate providing the `loader` module in the
+	/// generated code.
+	///
+	/// Defaults to `::json_ld`. Use `::json_ld_core` if the generated code
+	/// is compiled in a crate depending only on `json-ld-core`.
+	pub fn with_crate_path(mut self, path: impl Into<String>) -> Self {
+		self.crate_path = path.into();
+		self
+	}
+
+	/// Sets whether or not contexts are processed during validation.
+	///
+	/// By default every context is processed, which ensures that it is
+	/// fully valid. Remote contexts imported by the embedded contexts must
+	/// then be embedded as well. When disabled, only the syntax of the
+	/// contexts is checked.
+	pub fn with_processing(mut self, process: bool) -> Self {
+		self.process = process;
+		self
+	}
+
+	/// Validates the contexts and generates the code of the `loader`
+	/// function.
+	pub fn generate(&self) -> Result<String, Error> {
+		let entries = self.entries()?;
+
+		if self.process {
+			let loader: HashMap<IriBuf, RemoteDocument> = entries
+				.iter()
+				.map(|e| {
+					let document =
+						RemoteDocument::new(Some(e.url.clone()), None, e.document.clone());
+					(e.url.clone(), document)
+				})
+				.collect();
+
+			for e in &entries {
+				let context = e
+					.document
+					.clone()
+					.into_ld_context()
+					.map_err(|err| Error::InvalidContext(e.path.clone(), err))?;
+
+				block_on(context.process(&mut (), &loader, Some(e.url.clone())))
+					.map_err(|err| Error::Processing(e.path.clone(), err))?;
+			}
+		}
+
+		let mut code = String::new();
+		writeln!(code, "// Generated by `json-ld-build`. Do not edit.").unwrap();
+		writeln!(code).unwrap();
+		writeln!(
+			code,
+			"/// Returns a loader serving the contexts embedded at build time."
+		)
+		.unwrap();
+		writeln!(
+			code,
+			"pub fn loader() -> {}::loader::StaticLoader {{",
+			self.crate_path
+		)
+		.unwrap();
+		writeln!(
+			code,
+			"\tlet mut loader = {}::loader::StaticLoader::new();",
+			self.crate_path
+		)
+		.unwrap();
+		for e in &entries {
+			writeln!(
+				code,
+				"\tloader.insert({:?}.parse().unwrap(), include_str!({:?}));",
+				e.url.as_str(),
+				e.path.display().to_string()
+			)
+			.unwrap();
+		}
+		writeln!(code, "\tloader").unwrap();
+		writeln!(code, "}}").unwrap();
+
+		Ok(code)
+	}
+
+	/// Validates the contexts and writes the generated code to `path`.
+	pub fn write_to(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+		let code = self.generate()?;
+		fs::write(path.as_ref(), code).map_err(|e| Error::Write(path.as_ref().to_owned(), e))
+	}
+
+	/// Validates the contexts and writes the generated code to `file_name`
+	/// in the `OUT_DIR` directory.
+	///
+	/// This function must be called from a build script. It also instructs
+	/// Cargo to rerun the build script when a mounted directory changes.
+	///
+	/// # Panics
+	///
+	/// Panics if a context is invalid, failing the build with the error
+	/// message.
+	pub fn build(&self, file_name: &str) {
+		for (_, path) in &self.mount_points {
+			println!("cargo:rerun-if-changed={}", path.display());
+		}
+
+		let out_dir = std::env::var_os("OUT_DIR").expect("`OUT_DIR` is not set");
+		if let Err(e) = self.write_to(Path::new(&out_dir).join(file_name)) {
+			panic!("invalid JSON-LD context: {e}")
+		}
+	}
+
+	/// Collects and parses every embedded file.
+	fn entries(&self) -> Result<Vec<Entry>, Error> {
+		let mut entries = Vec::new();
+
+		for (url, dir) in &self.mount_points {
+			let mut files = Vec::new();
+			list_files(dir, &mut files)?;
+
+			for path in files {
+				let path = path
+					.canonicalize()
+					.map_err(|e| Error::IO(path.clone(), e))?;
+				let relative = path
+					.strip_prefix(dir.canonicalize().map_err(|e| Error::IO(dir.clone(), e))?)
+					.ok()
+					.and_then(|relative| {
+						let segments: Vec<_> = relative
+							.components()
+							.map(|c| c.as_os_str().to_str())
+							.collect::<Option<_>>()?;
+						IriRefBuf::new(segments.join("/")).ok()
+					})
+					.ok_or_else(|| Error::InvalidPath(path.clone()))?;
+
+				let content = fs::read_to_string(&path).map_err(|e| Error::IO(path.clone(), e))?;
+				let (document, _) = json_syntax::Value::parse_str(&content)
+					.map_err(|e| Error::Parse(path.clone(), e))?;
+
+				entries.push(Entry {
+					url: relative.resolved(url),
+					path,
+					document,
+				})
+			}
+		}
+
+		Ok(entries)
+	}
+}
+
+/// Lists every file in `dir`, recursively, in a deterministic order.
+fn list_files(dir: &Path, result: &mut Vec<PathBuf>) -> Result<(), Error> {
+	let mut paths = fs::read_dir(dir)
+		.map_err(|e| Error::IO(dir.to_owned(), e))?
+		.map(|entry| entry.map(|e| e.path()))
+		.collect::<Result<Vec<_>, _>>()
+		.map_err(|e| Error::IO(dir.to_owned(), e))?;
+	paths.sort();
+
+	for path in paths {
+		if path.is_dir() {
+			list_files(&path, result)?
+		} else {
+			result.push(path)
+		}
+	}
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn generate() {
+		let dir = std::env::temp_dir().join(format!("json-ld-build-test-{}", std::process::id()));
+		fs::create_dir_all(dir.join("v1")).unwrap();
+		fs::write(
+			dir.join("v1/main.jsonld"),
+			r#"{ "@context": [ "base.jsonld", { "name": "https://schema.org/name" } ] }"#,
+		)
+		.unwrap();
+		fs::write(
+			dir.join("v1/base.jsonld"),
+			r#"{ "@context": { "@vocab": "https://schema.org/" } }"#,
+		)
+		.unwrap();
+
+		let builder = Builder::new().mount(
+			IriBuf::new("https://example.com/contexts/".to_owned()).unwrap(),
+			&dir,
+		);
+		let code = builder.generate().unwrap();
+		assert!(code.contains("\"https://example.com/contexts/v1/main.jsonld\""));
+		assert!(code.contains("\"https://example.com/contexts/v1/base.jsonld\""));
+
+		fs::write(
+			dir.join("v1/main.jsonld"),
+			r#"{ "@context": [ "missing.jsonld" ] }"#,
+		)
+		.unwrap();
+		assert!(matches!(builder.generate(), Err(Error::Processing(_, _))));
+
+		fs::remove_dir_all(dir).unwrap()
+	}
+}
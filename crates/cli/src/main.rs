@@ -2,10 +2,13 @@ use std::path::PathBuf;
 use std::str::FromStr;
 
 use clap::Parser;
-use contextual::WithContext;
 use iref::IriBuf;
-use json_ld::{syntax::Parse, JsonLdProcessor, Print, RemoteDocument, RemoteDocumentReference};
-use rdf_types::vocabulary::{IriIndex, IriVocabulary, IriVocabularyMut};
+use json_ld::{
+	print::{Format, Indent},
+	syntax::{IntoJsonWithContext, Parse},
+	Dataset, JsonLdProcessor, RemoteDocument, RemoteDocumentReference,
+};
+use rdf_types::vocabulary::{BlankIdVocabulary, IriIndex, IriVocabulary, IriVocabularyMut};
 
 #[derive(Parser)]
 #[clap(name="json-ld", author, version, about, long_about = None)]
@@ -14,6 +17,18 @@ struct Args {
 	#[clap(short, long = "verbose", parse(from_occurrences))]
 	verbosity: usize,
 
+	/// Prints the output without any whitespace.
+	#[clap(long, global = true)]
+	compact: bool,
+
+	/// Number of spaces used to indent the output.
+	#[clap(long, global = true, default_value = "2")]
+	indent: u8,
+
+	/// Sorts the entries of the output objects by key.
+	#[clap(long = "sort-keys", global = true)]
+	sort_keys: bool,
+
 	#[clap(subcommand)]
 	command: Command,
 }
@@ -62,6 +77,18 @@ pub enum Command {
 		#[clap(short, long)]
 		base_url: Option<IriBuf>,
 	},
+
+	/// Print statistics about the RDF dataset of the given JSON-LD document.
+	Stats {
+		/// URL or file path of the document.
+		///
+		/// Of none, the standard input is used.
+		url_or_path: Option<IriOrPath>,
+
+		/// Base URL to use when reading from the standard input or file system.
+		#[clap(short, long)]
+		base_url: Option<IriBuf>,
+	},
 }
 
 pub enum IriOrPath {
@@ -88,6 +115,13 @@ async fn main() {
 	// Init logger.
 	stderrlog::new().verbosity(args.verbosity).init().unwrap();
 
+	let format = if args.compact {
+		Format::compact()
+	} else {
+		Format::pretty().with_indent(Indent::Spaces(args.indent))
+	}
+	.with_sorted_keys(args.sort_keys);
+
 	let mut vocabulary: rdf_types::vocabulary::IndexVocabulary =
 		rdf_types::vocabulary::IndexVocabulary::new();
 	let loader = json_ld::loader::ReqwestLoader::new();
@@ -105,7 +139,7 @@ async fn main() {
 						vocabulary.iri(remote_document.url().unwrap()).unwrap()
 					);
 
-					println!("{}", remote_document.document().pretty_print())
+					println!("{}", format.print(remote_document.document()))
 				}
 				Err(e) => {
 					eprintln!("error: {e}");
@@ -132,6 +166,7 @@ async fn main() {
 						json_ld::expansion::Action::Keep
 					},
 					allow_undefined: !no_undef,
+					..Default::default()
 				},
 				..Default::default()
 			};
@@ -154,7 +189,7 @@ async fn main() {
 						expanded.canonicalize()
 					}
 
-					println!("{}", expanded.with(&vocabulary).pretty_print())
+					println!("{}", format.print(&expanded.into_json_with(&vocabulary)))
 				}
 				Err(e) => {
 					eprintln!("error: {e}");
@@ -175,7 +210,7 @@ async fn main() {
 				.await
 			{
 				Ok(flattened) => {
-					println!("{}", flattened.with(&vocabulary).pretty_print())
+					println!("{}", format.print(&flattened))
 				}
 				Err(e) => {
 					eprintln!("error: {e}");
@@ -183,6 +218,67 @@ async fn main() {
 				}
 			}
 		}
+		Command::Stats {
+			url_or_path,
+			base_url,
+		} => {
+			let remote_document = get_remote_document(&mut vocabulary, url_or_path, base_url);
+
+			let mut generator = rdf_types::generator::Blank::new_with_prefix("b".to_string());
+
+			let expanded = match remote_document.expand_with(&mut vocabulary, &loader).await {
+				Ok(expanded) => expanded,
+				Err(e) => {
+					eprintln!("error: {e}");
+					std::process::exit(1);
+				}
+			};
+
+			let dataset = match Dataset::from_expanded_document_with(
+				&mut vocabulary,
+				&mut generator,
+				expanded,
+			) {
+				Ok(dataset) => dataset,
+				Err(e) => {
+					eprintln!("error: {e}");
+					std::process::exit(1);
+				}
+			};
+
+			let summary = dataset.summary_with(&mut vocabulary, &mut generator);
+
+			let types = summary.types.iter().map(|(ty, count)| {
+				let ty = match ty {
+					rdf_types::Id::Iri(iri) => vocabulary.iri(iri).unwrap().to_string(),
+					rdf_types::Id::Blank(b) => vocabulary.blank_id(b).unwrap().to_string(),
+				};
+
+				(ty, *count)
+			});
+
+			let datatypes = summary
+				.datatypes
+				.iter()
+				.map(|(ty, count)| (vocabulary.iri(ty).unwrap().to_string(), *count));
+
+			let languages = summary
+				.languages
+				.iter()
+				.map(|(tag, count)| (tag.to_string(), *count));
+
+			let mut report = json_ld::syntax::Object::new();
+			report.push("nodes".into(), (summary.nodes as u64).into());
+			report.push("quads".into(), (summary.quads as u64).into());
+			report.push("graphs".into(), (summary.graphs as u64).into());
+			report.push("predicates".into(), (summary.predicates as u64).into());
+			report.push("blankNodes".into(), (summary.blank_nodes as u64).into());
+			report.push("types".into(), histogram(types));
+			report.push("datatypes".into(), histogram(datatypes));
+			report.push("languages".into(), histogram(languages));
+
+			println!("{}", format.print(&json_ld::syntax::Value::Object(report)))
+		}
 	}
 }
 
@@ -240,3 +336,16 @@ fn get_remote_document(
 		}
 	}
 }
+
+/// Turns the given counts into a JSON object, by decreasing count.
+fn histogram(entries: impl Iterator<Item = (String, usize)>) -> json_ld::syntax::Value {
+	let mut entries: Vec<_> = entries.collect();
+	entries.sort_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then_with(|| a.cmp(b)));
+
+	let mut object = json_ld::syntax::Object::new();
+	for (key, count) in entries {
+		object.push(key.as_str().into(), (count as u64).into());
+	}
+
+	json_ld::syntax::Value::Object(object)
+}
@@ -0,0 +1,167 @@
+use contextual::WithContext;
+use json_ld_core::{object::node::Multiset, Indexed, IndexedNode, IndexedObject, Node, Object};
+use json_ld_syntax::IntoJsonWithContext;
+use json_syntax::Print;
+use rdf_types::Vocabulary;
+use std::hash::Hash;
+
+/// Puts the given expanded objects into canonical form, for the canonical
+/// compaction mode.
+///
+/// Unordered collections (top-level objects, types, property values, reverse
+/// property values, `@graph` and `@included` entries) are sorted by the
+/// canonical JSON form of their items. Lists keep their order. Literals are
+/// put into canonical form.
+pub(crate) fn canonical_objects<'a, N, I, B>(
+	vocabulary: &N,
+	objects: impl IntoIterator<Item = &'a IndexedObject<I, B>>,
+) -> Vec<IndexedObject<I, B>>
+where
+	N: Vocabulary<Iri = I, BlankId = B>,
+	I: 'a + Clone + Eq + Hash,
+	B: 'a + Clone + Eq + Hash,
+{
+	let mut buffer = ryu_js::Buffer::new();
+	sorted(
+		vocabulary,
+		objects.into_iter().cloned().map(|mut object| {
+			canonicalize_object(vocabulary, &mut buffer, &mut object);
+			object
+		}),
+	)
+}
+
+/// Puts the given flattened nodes into canonical form, for the canonical
+/// compaction mode.
+///
+/// See [`canonical_objects`].
+pub(crate) fn canonical_nodes<'a, N, I, B>(
+	vocabulary: &N,
+	nodes: impl IntoIterator<Item = &'a IndexedNode<I, B>>,
+) -> Vec<IndexedNode<I, B>>
+where
+	N: Vocabulary<Iri = I, BlankId = B>,
+	I: 'a + Clone + Eq + Hash,
+	B: 'a + Clone + Eq + Hash,
+{
+	let mut buffer = ryu_js::Buffer::new();
+	sorted(
+		vocabulary,
+		nodes.into_iter().cloned().map(|mut node| {
+			canonicalize_indexed_node(vocabulary, &mut buffer, &mut node);
+			node
+		}),
+	)
+}
+
+fn canonicalize_object<N, I, B>(
+	vocabulary: &N,
+	buffer: &mut ryu_js::Buffer,
+	object: &mut IndexedObject<I, B>,
+) where
+	N: Vocabulary<Iri = I, BlankId = B>,
+	I: Clone + Eq + Hash,
+	B: Clone + Eq + Hash,
+{
+	match object.inner_mut() {
+		Object::Value(value) => value.canonicalize_with(buffer),
+		Object::List(list) => {
+			for item in list.iter_mut() {
+				canonicalize_object(vocabulary, buffer, item)
+			}
+		}
+		Object::Node(node) => canonicalize_node(vocabulary, buffer, node),
+	}
+}
+
+fn canonicalize_node<N, I, B>(vocabulary: &N, buffer: &mut ryu_js::Buffer, node: &mut Node<I, B>)
+where
+	N: Vocabulary<Iri = I, BlankId = B>,
+	I: Clone + Eq + Hash,
+	B: Clone + Eq + Hash,
+{
+	node.types_mut()
+		.sort_by_cached_key(|ty| ty.with(vocabulary).as_str().to_owned());
+
+	for (_, objects) in node.properties_mut() {
+		*objects = sorted_multiset(
+			vocabulary,
+			std::mem::take(objects).into_iter().map(|mut object| {
+				canonicalize_object(vocabulary, buffer, &mut object);
+				object
+			}),
+		)
+	}
+
+	if let Some(reverse_properties) = node.reverse_properties_mut() {
+		for (_, nodes) in reverse_properties.iter_mut() {
+			*nodes = sorted_multiset(
+				vocabulary,
+				std::mem::take(nodes).into_iter().map(|mut node| {
+					canonicalize_indexed_node(vocabulary, buffer, &mut node);
+					node
+				}),
+			)
+		}
+	}
+
+	if let Some(graph) = node.graph_mut() {
+		*graph = sorted(
+			vocabulary,
+			std::mem::take(graph).into_iter().map(|mut object| {
+				canonicalize_object(vocabulary, buffer, &mut object);
+				object
+			}),
+		)
+		.into_iter()
+		.collect()
+	}
+
+	if let Some(included) = node.included_mut() {
+		*included = sorted(
+			vocabulary,
+			std::mem::take(included).into_iter().map(|mut node| {
+				canonicalize_indexed_node(vocabulary, buffer, &mut node);
+				node
+			}),
+		)
+		.into_iter()
+		.collect()
+	}
+}
+
+fn canonicalize_indexed_node<N, I, B>(
+	vocabulary: &N,
+	buffer: &mut ryu_js::Buffer,
+	node: &mut IndexedNode<I, B>,
+) where
+	N: Vocabulary<Iri = I, BlankId = B>,
+	I: Clone + Eq + Hash,
+	B: Clone + Eq + Hash,
+{
+	canonicalize_node(vocabulary, buffer, node.inner_mut())
+}
+
+/// Sorts the given items by their canonical JSON form.
+fn sorted<N, T>(vocabulary: &N, items: impl IntoIterator<Item = Indexed<T>>) -> Vec<Indexed<T>>
+where
+	Indexed<T>: Clone + IntoJsonWithContext<N>,
+{
+	let mut items: Vec<_> = items.into_iter().collect();
+	items.sort_by_cached_key(|item| {
+		let mut json = item.clone().into_json_with(vocabulary);
+		json.canonicalize();
+		json.compact_print().to_string()
+	});
+	items
+}
+
+fn sorted_multiset<N, T>(
+	vocabulary: &N,
+	items: impl IntoIterator<Item = Indexed<T>>,
+) -> Multiset<Indexed<T>>
+where
+	Indexed<T>: Clone + Hash + IntoJsonWithContext<N>,
+{
+	sorted(vocabulary, items).into_iter().collect()
+}
@@ -1,11 +1,19 @@
-use json_ld_core::{ExpandedDocument, FlattenedDocument, Loader, Term};
-use json_ld_syntax::{IntoJson, Keyword};
+use json_ld_core::{
+	object::{FragmentRef, Ref},
+	progress::{Progress, Yielder},
+	ExpandedDocument, FlattenedDocument, Id, InvalidIriPolicy, Loader, ProcessingMode, Term, Value,
+};
+use json_ld_syntax::{
+	context::{definition::Version, Definition},
+	ContextEntry, IntoJson, Keyword,
+};
 use rdf_types::{vocabulary, Vocabulary};
 use std::hash::Hash;
 
 use crate::{
+	canonical_nodes, canonical_objects, compact_collection_with,
 	iri::{compact_iri, IriConfusedWithPrefix},
-	CompactFragment,
+	VersionEntry,
 };
 
 pub type CompactDocumentResult = Result<json_syntax::Value, crate::Error>;
@@ -47,6 +55,33 @@ pub trait Compact<I, B> {
 		B: Clone + Hash + Eq,
 		L: Loader;
 
+	/// Compacts the input document with full options, reporting the progress
+	/// of the compaction to `progress`.
+	///
+	/// The progress is reported, and the cancellation checked, after each
+	/// top-level object of the document. If the compaction is
+	/// [cancelled](Progress::cancel), [`Error::Cancelled`](crate::Error::Cancelled)
+	/// is returned. The default implementation ignores the progress.
+	#[allow(async_fn_in_trait)]
+	async fn compact_full_with_progress<'a, N, L>(
+		&'a self,
+		vocabulary: &'a mut N,
+		context: json_ld_context_processing::ProcessedRef<'a, 'a, I, B>,
+		loader: &'a L,
+		options: crate::Options,
+		progress: &'a Progress,
+	) -> CompactDocumentResult
+	where
+		N: rdf_types::VocabularyMut<Iri = I, BlankId = B>,
+		I: Clone + Hash + Eq,
+		B: Clone + Hash + Eq,
+		L: Loader,
+	{
+		let _ = progress;
+		self.compact_full(vocabulary, context, loader, options)
+			.await
+	}
+
 	/// Compacts the input document with the given `vocabulary` to
 	/// interpret identifiers.
 	#[allow(async_fn_in_trait)]
@@ -98,20 +133,75 @@ impl<I, B> Compact<I, B> for ExpandedDocument<I, B> {
 		B: Clone + Hash + Eq,
 		L: Loader,
 	{
-		let mut compacted_output = self
-			.objects()
-			.compact_fragment_full(
-				vocabulary,
-				context.processed(),
-				context.processed(),
-				None,
-				loader,
-				options,
-			)
-			.await?;
+		self.compact_full_with_progress(vocabulary, context, loader, options, &Progress::new())
+			.await
+	}
+
+	async fn compact_full_with_progress<'a, N, L>(
+		&'a self,
+		vocabulary: &'a mut N,
+		context: json_ld_context_processing::ProcessedRef<'a, 'a, I, B>,
+		loader: &'a L,
+		options: crate::Options,
+		progress: &'a Progress,
+	) -> CompactDocumentResult
+	where
+		N: rdf_types::VocabularyMut<Iri = I, BlankId = B>,
+		I: Clone + Hash + Eq,
+		B: Clone + Hash + Eq,
+		L: Loader,
+	{
+		let invalid_id = match options.on_invalid_iri {
+			InvalidIriPolicy::PassThrough => None,
+			_ => self.find_invalid_id(),
+		};
+
+		// The document is only copied if it has invalid identifiers to skip.
+		let skipped;
+		let document = match invalid_id {
+			None => self,
+			Some(id) if options.on_invalid_iri == InvalidIriPolicy::Error => {
+				return Err(crate::Error::InvalidIri(id))
+			}
+			Some(_) => {
+				let mut document = self.clone();
+				document.remove_invalid_ids();
+				skipped = document;
+				&skipped
+			}
+		};
+
+		let canonical_document;
+		let (document, options) = if options.canonical {
+			canonical_document = canonical_objects(&*vocabulary, document.objects())
+				.into_iter()
+				.collect::<ExpandedDocument<I, B>>();
+			(&canonical_document, options.canonical_order())
+		} else {
+			(document, options)
+		};
+
+		check_processing_mode(context.unprocessed(), document.traverse(), options)?;
+
+		let mut compacted_output = compact_collection_with(
+			vocabulary,
+			document.objects().iter(),
+			context.processed(),
+			context.processed(),
+			None,
+			loader,
+			options,
+			&Yielder::new(options.yield_every),
+			Some(progress),
+		)
+		.await?;
 
 		compacted_output.embed_context(vocabulary, context, options)?;
 
+		if options.canonical {
+			compacted_output.canonicalize()
+		}
+
 		Ok(compacted_output)
 	}
 }
@@ -130,19 +220,87 @@ impl<I, B> Compact<I, B> for FlattenedDocument<I, B> {
 		B: Clone + Hash + Eq,
 		L: Loader,
 	{
-		let mut compacted_output = self
-			.compact_fragment_full(
-				vocabulary,
-				context.processed(),
-				context.processed(),
-				None,
-				loader,
-				options,
-			)
-			.await?;
+		self.compact_full_with_progress(vocabulary, context, loader, options, &Progress::new())
+			.await
+	}
+
+	async fn compact_full_with_progress<'a, N, L>(
+		&'a self,
+		vocabulary: &'a mut N,
+		context: json_ld_context_processing::ProcessedRef<'a, 'a, I, B>,
+		loader: &'a L,
+		options: crate::Options,
+		progress: &'a Progress,
+	) -> CompactDocumentResult
+	where
+		N: rdf_types::VocabularyMut<Iri = I, BlankId = B>,
+		I: Clone + Hash + Eq,
+		B: Clone + Hash + Eq,
+		L: Loader,
+	{
+		let invalid_id = match options.on_invalid_iri {
+			InvalidIriPolicy::PassThrough => None,
+			_ => self
+				.iter()
+				.flat_map(|node| node.traverse())
+				.filter_map(|fragment| fragment.into_id())
+				.find_map(|id| match id {
+					Id::Invalid(id) => Some(id),
+					Id::Valid(_) => None,
+				}),
+		};
+
+		// The document is only copied if it has invalid identifiers to skip.
+		let skipped;
+		let document = match invalid_id {
+			None => self,
+			Some(id) if options.on_invalid_iri == InvalidIriPolicy::Error => {
+				return Err(crate::Error::InvalidIri(id))
+			}
+			Some(_) => {
+				let mut document = self.clone();
+				document.retain_mut(|node| {
+					node.remove_invalid_ids();
+					!node.has_invalid_id()
+				});
+				skipped = document;
+				&skipped
+			}
+		};
+
+		let canonical_document;
+		let (document, options) = if options.canonical {
+			canonical_document = canonical_nodes(&*vocabulary, document);
+			(&canonical_document, options.canonical_order())
+		} else {
+			(document, options)
+		};
+
+		check_processing_mode(
+			context.unprocessed(),
+			document.iter().flat_map(|node| node.traverse()),
+			options,
+		)?;
+
+		let mut compacted_output = compact_collection_with(
+			vocabulary,
+			document.iter(),
+			context.processed(),
+			context.processed(),
+			None,
+			loader,
+			options,
+			&Yielder::new(options.yield_every),
+			Some(progress),
+		)
+		.await?;
 
 		compacted_output.embed_context(vocabulary, context, options)?;
 
+		if options.canonical {
+			compacted_output.canonicalize()
+		}
+
 		Ok(compacted_output)
 	}
 }
@@ -185,7 +343,9 @@ impl EmbedContext for json_syntax::Value {
 		};
 
 		if let Some(mut obj) = obj {
-			let json_context = IntoJson::into_json(context.unprocessed().clone());
+			let mut unprocessed = context.unprocessed().clone();
+			set_version_entry(&mut unprocessed, options);
+			let json_context = IntoJson::into_json(unprocessed);
 
 			if !obj.is_empty()
 				&& !json_context.is_null()
@@ -200,3 +360,735 @@ impl EmbedContext for json_syntax::Value {
 		Ok(())
 	}
 }
+
+/// Fails if the compacted document requires JSON-LD 1.1 while the
+/// processing mode is JSON-LD 1.0.
+///
+/// This is the case if the context requires JSON-LD 1.1, or if the document
+/// contains JSON literals, base directions or included nodes.
+fn check_processing_mode<'a, T: 'a, B: 'a>(
+	context: &json_ld_syntax::context::Context,
+	mut fragments: impl Iterator<Item = FragmentRef<'a, T, B>>,
+	options: crate::Options,
+) -> Result<(), crate::Error> {
+	if options.processing_mode == ProcessingMode::JsonLd1_0
+		&& (context.required_version() != Version::V1_0
+			|| fragments.any(|fragment| match fragment.into_ref() {
+				Some(Ref::Value(value)) => {
+					matches!(value, Value::Json(_)) || value.direction().is_some()
+				}
+				Some(Ref::Node(node)) => node.included().is_some(),
+				_ => false,
+			})) {
+		Err(crate::Error::ProcessingModeConflict)
+	} else {
+		Ok(())
+	}
+}
+
+/// Applies the [`VersionEntry`] option to the context to embed.
+fn set_version_entry(context: &mut json_ld_syntax::context::Context, options: crate::Options) {
+	let entries = match context {
+		json_ld_syntax::context::Context::One(entry) => std::slice::from_mut(entry),
+		json_ld_syntax::context::Context::Many(entries) => entries.as_mut_slice(),
+	};
+
+	match options.version_entry {
+		VersionEntry::Preserve => (),
+		VersionEntry::Include => {
+			if options.processing_mode == ProcessingMode::JsonLd1_0
+				|| entries
+					.iter()
+					.all(|entry| matches!(entry, ContextEntry::Null))
+			{
+				return;
+			}
+
+			let mut definitions = entries.iter_mut().filter_map(|entry| match entry {
+				ContextEntry::Definition(def) => Some(def),
+				_ => None,
+			});
+
+			let version = options.processing_mode.version();
+			match definitions.next() {
+				Some(first) => {
+					if first.version.is_none() && definitions.all(|def| def.version.is_none()) {
+						first.version = Some(version)
+					}
+				}
+				None => {
+					let def = Definition::new().with_version(version);
+					*context = json_ld_syntax::context::Context::Many(
+						std::iter::once(ContextEntry::Definition(def))
+							.chain(std::mem::take(context))
+							.collect(),
+					)
+				}
+			}
+		}
+		VersionEntry::Omit => {
+			for entry in entries {
+				if let ContextEntry::Definition(def) = entry {
+					def.version = None
+				}
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Options;
+	use iref::IriBuf;
+	use json_ld_context_processing::ProcessedRef;
+	use json_ld_core::{Context, NoLoader};
+	use json_ld_syntax::IntoJsonWithContext;
+
+	fn document() -> ExpandedDocument {
+		use json_ld_core::TryFromJson;
+
+		ExpandedDocument::try_from_json_in(
+			&mut (),
+			json_syntax::json!([
+				{
+					"@id": "a b",
+					"https://example.com/p": [{ "@value": true }]
+				},
+				{
+					"@id": "https://example.com/a",
+					"https://example.com/p": [{ "@id": "a b" }, { "@value": true }]
+				}
+			]),
+		)
+		.unwrap()
+	}
+
+	fn compact(policy: InvalidIriPolicy) -> CompactDocumentResult {
+		let unprocessed = json_ld_syntax::context::Context::Many(Vec::new());
+		let processed = Context::default();
+		let options = Options {
+			on_invalid_iri: policy,
+			..Options::default()
+		};
+
+		futures::executor::block_on(document().compact_full(
+			&mut (),
+			ProcessedRef::new(&unprocessed, &processed),
+			&NoLoader,
+			options,
+		))
+	}
+
+	fn keys(value: &json_syntax::Value, result: &mut Vec<String>) {
+		match value {
+			json_syntax::Value::Array(items) => items.iter().for_each(|item| keys(item, result)),
+			json_syntax::Value::Object(object) => {
+				for entry in object {
+					if entry.key.as_str() != "@context" {
+						result.push(entry.key.to_string());
+						keys(&entry.value, result)
+					}
+				}
+			}
+			_ => (),
+		}
+	}
+
+	fn compact_aliased(use_aliases: bool) -> Vec<String> {
+		let compacted = compact_expanded_with(
+			json_syntax::json!({
+				"id": "@id",
+				"type": "@type",
+				"value": "@value",
+				"lang": "@language",
+				"list": "@list",
+				"graph": "@graph",
+				"p": "https://example.com/p",
+				"g": "https://example.com/g"
+			}),
+			json_syntax::json!([{
+				"@id": "https://example.com/a",
+				"@type": ["https://example.com/T"],
+				"https://example.com/p": [{
+					"@list": [{ "@value": "x", "@language": "en" }]
+				}],
+				"https://example.com/g": [{
+					"@graph": [{ "@id": "https://example.com/b" }]
+				}]
+			}]),
+			Options {
+				use_aliases,
+				..Options::default()
+			},
+		)
+		.unwrap();
+
+		let mut result = Vec::new();
+		keys(&compacted, &mut result);
+		result.sort_unstable();
+		result
+	}
+
+	#[test]
+	fn progress() {
+		use json_ld_core::TryFromJson;
+		use std::sync::{Arc, Mutex};
+
+		let document = ExpandedDocument::try_from_json_in(
+			&mut (),
+			json_syntax::json!([
+				{ "@id": "https://example.com/a" },
+				{ "@id": "https://example.com/b" }
+			]),
+		)
+		.unwrap();
+		let unprocessed = json_ld_syntax::context::Context::Many(Vec::new());
+		let processed = Context::default();
+
+		let compact = |progress: &Progress| {
+			futures::executor::block_on(document.compact_full_with_progress(
+				&mut (),
+				ProcessedRef::new(&unprocessed, &processed),
+				&NoLoader,
+				Options::default(),
+				progress,
+			))
+		};
+
+		let reports = Arc::new(Mutex::new(Vec::new()));
+		let progress = Progress::with_callback({
+			let reports = reports.clone();
+			move |processed, total| reports.lock().unwrap().push((processed, total))
+		});
+		assert!(compact(&progress).is_ok());
+		assert_eq!(*reports.lock().unwrap(), [(1, 2), (2, 2)]);
+
+		progress.cancel();
+		assert!(matches!(compact(&progress), Err(crate::Error::Cancelled)))
+	}
+
+	#[test]
+	fn keyword_aliases() {
+		assert_eq!(
+			compact_aliased(true),
+			["g", "graph", "id", "id", "lang", "list", "p", "type", "value"]
+		);
+		assert_eq!(
+			compact_aliased(false),
+			[
+				"@graph",
+				"@id",
+				"@id",
+				"@language",
+				"@list",
+				"@type",
+				"@value",
+				"g",
+				"p"
+			]
+		)
+	}
+
+	#[test]
+	fn vocab_type_coercion() {
+		let context = json_syntax::json!({
+			"@vocab": "https://example.com/",
+			"Foo": "https://example.com/vocab#Foo",
+			"p": { "@id": "https://example.com/p", "@type": "@vocab" }
+		});
+
+		let expanded = expand(
+			context.clone(),
+			json_syntax::json!({ "p": ["Foo", "Bar", "https://example.org/Baz"] }),
+		);
+
+		let node = expanded.objects().iter().next().unwrap().as_node().unwrap();
+		let p = Id::iri(IriBuf::new("https://example.com/p".to_owned()).unwrap());
+		let ids: Vec<_> = node
+			.get(&p)
+			.map(|object| object.as_node().unwrap().id.as_ref().unwrap().as_str())
+			.collect();
+		assert_eq!(
+			ids,
+			[
+				"https://example.com/vocab#Foo",
+				"https://example.com/Bar",
+				"https://example.org/Baz"
+			]
+		);
+
+		assert_eq!(
+			compact_expanded(context, expanded.into_json_with(&())),
+			json_syntax::json!({ "p": ["Foo", "Bar", "https://example.org/Baz"] })
+		)
+	}
+
+	#[test]
+	fn none_index() {
+		let context = json_syntax::json!({
+			"@vocab": "https://example.com/",
+			"none": "@none",
+			"label": { "@id": "https://example.com/label" },
+			"byLabel": {
+				"@id": "https://example.com/p",
+				"@container": "@index",
+				"@index": "label"
+			},
+			"byLang": { "@id": "https://example.com/name", "@container": "@language" },
+			"byId": { "@id": "https://example.com/q", "@container": "@id" },
+			"graphs": { "@id": "https://example.com/g", "@container": ["@graph", "@index"] }
+		});
+
+		let mut expanded = expand(
+			context.clone(),
+			json_syntax::json!({
+				"byLabel": {
+					"a": { "@id": "https://example.com/a" },
+					"none": { "@id": "https://example.com/b" },
+					"@none": { "@id": "https://example.com/c", "label": { "@id": "https://example.com/l" } }
+				},
+				"byLang": { "en": "Name", "none": "Nom" },
+				"byId": { "none": { "label": "x" } },
+				"graphs": { "none": { "@id": "https://example.com/d" } }
+			}),
+		);
+
+		let node = expanded.objects().iter().next().unwrap().as_node().unwrap();
+		let iri = |s: &str| Id::iri(IriBuf::new(format!("https://example.com/{s}")).unwrap());
+		assert_eq!(node.get(&iri("p")).count(), 3);
+		assert!(node.get(&iri("name")).any(|o| o.language().is_none()));
+		assert!(node.get(&iri("q")).all(|o| o.id().is_none()));
+		assert!(node.get(&iri("g")).all(|o| o.index().is_none()));
+
+		let compacted = compact_expanded(context.clone(), expanded.clone().into_json_with(&()));
+		let compacted = compacted.as_object().unwrap();
+		let map = |key: &str| {
+			let mut keys: Vec<_> = compacted
+				.get_unique(key)
+				.ok()
+				.unwrap()
+				.unwrap()
+				.as_object()
+				.unwrap()
+				.iter()
+				.map(|entry| entry.key.to_string())
+				.collect();
+			keys.sort_unstable();
+			keys
+		};
+
+		assert_eq!(map("byLabel"), ["a", "none"]);
+		assert_eq!(map("byLang"), ["en", "none"]);
+		assert_eq!(map("byId"), ["none"]);
+		assert_eq!(map("graphs"), ["none"]);
+
+		let mut reexpanded = expand(context, json_syntax::Value::Object(compacted.clone()));
+		reexpanded.canonicalize();
+		expanded.canonicalize();
+		assert_eq!(reexpanded, expanded)
+	}
+
+	#[test]
+	fn property_index() {
+		let context = json_syntax::json!({
+			"@vocab": "https://example.com/",
+			"label": { "@id": "https://example.com/label" },
+			"byLabel": {
+				"@id": "https://example.com/p",
+				"@container": "@index",
+				"@index": "https://example.com/label"
+			}
+		});
+
+		let expanded = expand(
+			context.clone(),
+			json_syntax::json!({
+				"byLabel": {
+					"a": { "@id": "https://example.com/a", "label": "b" },
+					"c": [{ "@id": "https://example.com/c" }, { "@id": "https://example.com/d" }]
+				}
+			}),
+		);
+
+		let iri = |s: &str| Id::iri(IriBuf::new(format!("https://example.com/{s}")).unwrap());
+		let node = expanded.objects().iter().next().unwrap().as_node().unwrap();
+		let a = node
+			.get(&iri("p"))
+			.find(|o| o.id() == Some(&iri("a")))
+			.unwrap();
+		let labels: Vec<_> = a
+			.as_node()
+			.unwrap()
+			.get(&iri("label"))
+			.map(|o| o.as_value().unwrap().as_str().unwrap())
+			.collect();
+		assert_eq!(labels, ["a", "b"]);
+
+		assert_eq!(
+			compact_expanded(context, expanded.into_json_with(&())),
+			json_syntax::json!({
+				"byLabel": {
+					"a": { "@id": "https://example.com/a", "label": "b" },
+					"c": [{ "@id": "https://example.com/c" }, { "@id": "https://example.com/d" }]
+				}
+			})
+		)
+	}
+
+	#[test]
+	fn language_map_case() {
+		assert_eq!(
+			compact_expanded(
+				json_syntax::json!({
+					"names": { "@id": "https://example.com/names", "@container": "@language" }
+				}),
+				json_syntax::json!([{
+					"https://example.com/names": [
+						{ "@value": "a", "@language": "en-US" },
+						{ "@value": "b", "@language": "EN-us" }
+					]
+				}])
+			),
+			json_syntax::json!({ "names": { "en-US": ["a", "b"] } })
+		)
+	}
+
+	#[test]
+	fn version_entry() {
+		let compact_with = |context: json_syntax::Value, options: Options| {
+			compact_expanded_with(
+				context,
+				json_syntax::json!([{ "https://example.com/p": [{ "@value": "x" }] }]),
+				options,
+			)
+			.map(|compacted| {
+				compacted
+					.as_object()
+					.unwrap()
+					.get_unique("@context")
+					.ok()
+					.unwrap()
+					.cloned()
+			})
+		};
+
+		let included = compact_with(
+			json_syntax::json!({ "p": "https://example.com/p" }),
+			Options {
+				version_entry: VersionEntry::Include,
+				..Options::default()
+			},
+		)
+		.unwrap();
+		assert_eq!(
+			included,
+			Some(json_syntax::json!({ "@version": 1.1, "p": "https://example.com/p" }))
+		);
+
+		let omitted = compact_with(
+			json_syntax::json!({ "@version": 1.1, "p": "https://example.com/p" }),
+			Options {
+				version_entry: VersionEntry::Omit,
+				..Options::default()
+			},
+		)
+		.unwrap();
+		assert_eq!(
+			omitted,
+			Some(json_syntax::json!({ "p": "https://example.com/p" }))
+		);
+
+		let conflict = compact_with(
+			json_syntax::json!({ "p": { "@id": "https://example.com/p", "@protected": true } }),
+			Options {
+				processing_mode: ProcessingMode::JsonLd1_0,
+				..Options::default()
+			},
+		);
+		assert!(matches!(
+			conflict,
+			Err(crate::Error::ProcessingModeConflict)
+		))
+	}
+
+	#[test]
+	fn compact_to_native() {
+		let xsd = |name: &str| format!("http://www.w3.org/2001/XMLSchema#{name}");
+		let compact_with = |compact_to_native: bool| {
+			let mut compacted = compact_expanded_with(
+				json_syntax::json!({
+					"xsd": "http://www.w3.org/2001/XMLSchema#",
+					"b": { "@id": "https://example.com/b", "@type": "xsd:boolean" },
+					"i": { "@id": "https://example.com/i", "@type": "xsd:integer" },
+					"d": { "@id": "https://example.com/d", "@type": "xsd:double" }
+				}),
+				json_syntax::json!([{
+					"https://example.com/b": [{ "@value": "true", "@type": xsd("boolean") }],
+					"https://example.com/i": [
+						{ "@value": "42", "@type": xsd("integer") },
+						{ "@value": "1.0", "@type": xsd("integer") }
+					],
+					"https://example.com/d": [{ "@value": "1.5", "@type": xsd("double") }]
+				}]),
+				Options {
+					compact_to_native,
+					..Options::default()
+				},
+			)
+			.unwrap();
+			compacted.as_object_mut().unwrap().remove("@context");
+			compacted
+		};
+
+		assert_eq!(
+			compact_with(false),
+			json_syntax::json!({ "b": "true", "i": ["42", "1.0"], "d": "1.5" })
+		);
+		assert_eq!(
+			compact_with(true),
+			json_syntax::json!({ "b": true, "i": [42, "1.0"], "d": 1.5 })
+		)
+	}
+
+	#[test]
+	fn invalid_iri_policies() {
+		let compacted = compact(InvalidIriPolicy::PassThrough).unwrap();
+		let graph = compacted
+			.as_object()
+			.unwrap()
+			.get_unique("@graph")
+			.ok()
+			.unwrap()
+			.unwrap();
+		assert_eq!(graph.as_array().unwrap().len(), 2);
+
+		// The invalid node and the reference to it are removed, not emptied.
+		assert_eq!(
+			compact(InvalidIriPolicy::Skip).unwrap(),
+			json_syntax::json!({
+				"@id": "https://example.com/a",
+				"https://example.com/p": true
+			})
+		);
+
+		assert!(matches!(
+			compact(InvalidIriPolicy::Error),
+			Err(crate::Error::InvalidIri(id)) if id == "a b"
+		))
+	}
+
+	#[test]
+	fn canonical() {
+		use json_syntax::Print;
+
+		let compact = |json| {
+			compact_expanded_with(
+				json_syntax::json!({
+					"@vocab": "https://example.com/",
+					"list": { "@container": "@list" }
+				}),
+				json,
+				Options::default().canonical(),
+			)
+			.unwrap()
+			.compact_print()
+			.to_string()
+		};
+
+		let a = compact(json_syntax::json!([{
+			"https://example.com/z": [{ "@value": 1.0E2 }, { "@value": "a" }],
+			"https://example.com/list": [{ "@list": [{ "@value": "y" }, { "@value": "x" }] }],
+			"@type": ["https://example.com/T", "https://example.com/S"]
+		}]));
+		let b = compact(json_syntax::json!([{
+			"@type": ["https://example.com/S", "https://example.com/T"],
+			"https://example.com/list": [{ "@list": [{ "@value": "y" }, { "@value": "x" }] }],
+			"https://example.com/z": [{ "@value": "a" }, { "@value": 100 }]
+		}]));
+
+		assert_eq!(a, b);
+		assert_eq!(
+			a,
+			r#"{"@context":{"@vocab":"https://example.com/","list":{"@container":"@list"}},"@type":["S","T"],"list":["y","x"],"z":["a",100]}"#
+		)
+	}
+
+	/// Expands the given document with the given context.
+	fn expand(context: json_syntax::Value, input: json_syntax::Value) -> ExpandedDocument {
+		use json_ld_context_processing::Process;
+		use json_ld_expansion::Expand;
+		use json_ld_syntax::TryFromJson;
+
+		let context = json_ld_syntax::context::Context::try_from_json(context).unwrap();
+		let processed = futures::executor::block_on(context.process(&mut (), &NoLoader, None))
+			.ok()
+			.unwrap();
+
+		futures::executor::block_on(input.expand_full(
+			&mut (),
+			processed.into_processed(),
+			None,
+			&NoLoader,
+			json_ld_expansion::Options::default(),
+			(),
+		))
+		.unwrap()
+	}
+
+	/// Compacts the given expanded document with the given options.
+	fn compact_expanded_with(
+		context: json_syntax::Value,
+		document: json_syntax::Value,
+		options: Options,
+	) -> CompactDocumentResult {
+		use json_ld_context_processing::Process;
+		use json_ld_core::TryFromJson;
+		use json_ld_syntax::TryFromJson as _;
+
+		let context = json_ld_syntax::context::Context::try_from_json(context).unwrap();
+		let processed = futures::executor::block_on(context.process(&mut (), &NoLoader, None))
+			.ok()
+			.unwrap();
+		let document = ExpandedDocument::try_from_json_in(&mut (), document).unwrap();
+
+		futures::executor::block_on(document.compact_full(
+			&mut (),
+			processed.as_ref(),
+			&NoLoader,
+			options,
+		))
+	}
+
+	/// Compacts the given expanded document, and removes the `@context` entry
+	/// from the result.
+	fn compact_expanded(
+		context: json_syntax::Value,
+		document: json_syntax::Value,
+	) -> json_syntax::Value {
+		let mut compacted = compact_expanded_with(context, document, Options::default()).unwrap();
+		compacted.as_object_mut().unwrap().remove("@context");
+		compacted
+	}
+
+	#[test]
+	fn non_string_language_map_value() {
+		// Only strings can appear in a language map.
+		assert_eq!(
+			compact_expanded(
+				json_syntax::json!({
+					"names": { "@id": "https://example.com/names", "@container": "@language" }
+				}),
+				json_syntax::json!([{ "https://example.com/names": [{ "@value": true }] }])
+			),
+			json_syntax::json!({ "https://example.com/names": true })
+		)
+	}
+
+	#[test]
+	fn unresolvable_relative_iri() {
+		// Relative to `https://example.com/a`, the empty reference would
+		// resolve to the base IRI itself.
+		assert_eq!(
+			compact_expanded(
+				json_syntax::json!({ "@base": "https://example.com/a" }),
+				json_syntax::json!([{ "@id": "https://example.com" }])
+			),
+			json_syntax::json!({ "@id": "https://example.com" })
+		)
+	}
+
+	#[test]
+	fn json_literal_arrays() {
+		// JSON literal arrays are not merged into the property value, even
+		// when they are empty or have a single item.
+		let compact = |value: json_syntax::Value| {
+			compact_expanded(
+				json_syntax::json!({
+					"data": { "@id": "https://example.com/data", "@type": "@json" }
+				}),
+				json_syntax::json!([{
+					"https://example.com/data": [{ "@value": value, "@type": "@json" }]
+				}]),
+			)
+		};
+
+		assert_eq!(
+			compact(json_syntax::json!([])),
+			json_syntax::json!({ "data": [] })
+		);
+		assert_eq!(
+			compact(json_syntax::json!([1])),
+			json_syntax::json!({ "data": [1] })
+		);
+		assert_eq!(
+			compact(json_syntax::json!([[1, 2]])),
+			json_syntax::json!({ "data": [[1, 2]] })
+		)
+	}
+
+	#[test]
+	fn indexed_list_in_index_map() {
+		// The index of a list object is used as its key in the index map.
+		assert_eq!(
+			compact_expanded(
+				json_syntax::json!({
+					"lists": { "@id": "https://example.com/lists", "@container": "@index" }
+				}),
+				json_syntax::json!([{
+					"https://example.com/lists": [
+						{ "@list": [{ "@value": "x" }], "@index": "a" },
+						{ "@list": [{ "@value": "y" }] }
+					]
+				}])
+			),
+			json_syntax::json!({
+				"lists": {
+					"a": { "@list": ["x"] },
+					"@none": { "@list": ["y"] }
+				}
+			})
+		)
+	}
+
+	#[test]
+	fn yield_every() {
+		use futures::FutureExt;
+		use json_ld_core::TryFromJson;
+
+		// Many small arrays: items are counted over the whole document.
+		let document = ExpandedDocument::try_from_json_in(
+			&mut (),
+			json_syntax::json!([{
+				"https://example.com/p": [{ "@value": "A" }],
+				"https://example.com/q": [{ "@value": "B" }],
+				"https://example.com/r": [{ "@value": "C" }]
+			}]),
+		)
+		.unwrap();
+
+		let unprocessed = json_ld_syntax::context::Context::Many(Vec::new());
+		let processed = Context::default();
+		let (document, unprocessed, processed) = (&document, &unprocessed, &processed);
+		let compact = |yield_every| async move {
+			document
+				.compact_full(
+					&mut (),
+					ProcessedRef::new(unprocessed, processed),
+					&NoLoader,
+					Options {
+						yield_every,
+						..Options::default()
+					},
+				)
+				.await
+		};
+
+		assert!(compact(None).now_or_never().is_some());
+		assert!(compact(Some(2)).now_or_never().is_none());
+		assert_eq!(
+			futures::executor::block_on(compact(Some(2))).unwrap(),
+			futures::executor::block_on(compact(None)).unwrap()
+		)
+	}
+}
@@ -104,6 +104,12 @@ where
 		return Ok(None);
 	}
 
+	if let Term::Keyword(keyword) = var {
+		if !options.use_aliases {
+			return Ok(Some(keyword.into_str().to_owned()));
+		}
+	}
+
 	if vocab {
 		if let Some(entry) = active_context.inverse().get(var) {
 			// Initialize containers to an empty array.
@@ -266,8 +272,11 @@ where
 								ty.as_syntax_type().cloned(),
 							)))
 						} else {
-							is_simple_value =
-								v.direction().is_none() && v.language().is_none() && !has_index
+							// Only strings can be put in a language map
+							// (under the `@none` key).
+							is_simple_value = v.direction().is_none()
+								&& v.language().is_none()
+								&& v.as_str().is_some() && !has_index
 						}
 
 						containers.push(Container::Set)
@@ -451,7 +460,8 @@ where
 								|| (candidate_def.is_some()
 									&& candidate_def
 										.and_then(|def| def.value())
-										.map_or(false, |v| v == var) && value.is_none()))
+										.map_or(false, |v| v == var)
+									&& value.is_none()))
 						{
 							compact_iri = candidate
 						}
@@ -486,9 +496,15 @@ where
 			let base_iri = vocabulary.iri(base_iri).unwrap();
 			if let Some(iri) = var.as_iri() {
 				let iri = vocabulary.iri(iri).unwrap();
-				return Ok(Some(disambiguate_keyword(
-					iri.relative_to(base_iri).as_str().into(),
-				)));
+				let relative = iri.relative_to(base_iri);
+
+				// The relative reference is only used if it resolves back
+				// to the same IRI.
+				if relative.resolved(base_iri) == iri {
+					return Ok(Some(disambiguate_keyword(relative.as_str().into())));
+				}
+
+				return Ok(Some(iri.as_str().to_owned()));
 			}
 		}
 	}
@@ -0,0 +1,217 @@
+use futures::Stream;
+use json_ld_core::{
+	progress::Yielder, Context, ExpandedDocument, IndexedObject, InvalidIriPolicy, Loader, Term,
+};
+use json_ld_syntax::Keyword;
+use rdf_types::{Vocabulary, VocabularyMut};
+use std::hash::Hash;
+
+use crate::{iri::compact_iri, CompactFragment, Error, Options};
+
+/// Lazy compaction of the top-level objects of a document.
+///
+/// This trait provides the `compact_iter` method returning a [`CompactIter`],
+/// that compacts the top-level objects of the document one at a time.
+pub trait CompactItems<I, B> {
+	/// Returns an iterator compacting each top-level object of the document
+	/// lazily.
+	///
+	/// Fails right away if the document contains an invalid IRI while
+	/// [`Options::on_invalid_iri`] is set to [`InvalidIriPolicy::Error`].
+	fn compact_iter<'a, N, L>(
+		&'a self,
+		vocabulary: &'a mut N,
+		context: json_ld_context_processing::ProcessedRef<'a, 'a, I, B>,
+		loader: &'a L,
+		options: Options,
+	) -> Result<CompactIter<'a, N, L>, Error>
+	where
+		N: Vocabulary<Iri = I, BlankId = B>,
+		I: Clone + Hash + Eq,
+		B: Clone + Hash + Eq;
+}
+
+impl<I, B> CompactItems<I, B> for ExpandedDocument<I, B> {
+	fn compact_iter<'a, N, L>(
+		&'a self,
+		vocabulary: &'a mut N,
+		context: json_ld_context_processing::ProcessedRef<'a, 'a, I, B>,
+		loader: &'a L,
+		options: Options,
+	) -> Result<CompactIter<'a, N, L>, Error>
+	where
+		N: Vocabulary<Iri = I, BlankId = B>,
+		I: Clone + Hash + Eq,
+		B: Clone + Hash + Eq,
+	{
+		if options.on_invalid_iri == InvalidIriPolicy::Error {
+			if let Some(id) = self.find_invalid_id() {
+				return Err(Error::InvalidIri(id));
+			}
+		}
+
+		Ok(CompactIter {
+			vocabulary,
+			objects: self.objects().iter(),
+			context: context.processed(),
+			loader,
+			options,
+			yielder: Yielder::new(options.yield_every),
+		})
+	}
+}
+
+/// Iterator compacting the top-level objects of a document one at a time.
+///
+/// Contrarily to [`Compact`](crate::Compact), the compacted objects are not
+/// collected into an array, and no `@context` is embedded. This allows
+/// serializing the compacted document as the objects become ready, without
+/// ever holding the whole compacted output in memory: the caller writes the
+/// `@context` (the unprocessed context) and the [`Self::graph_key`] entry
+/// itself, followed by the array of compacted objects.
+///
+/// Objects compacting to `null` are skipped. Since the objects are always
+/// compacted as members of the top-level `@graph` array, the
+/// [`Options::compact_arrays`] option does not apply to it.
+pub struct CompactIter<'a, N: Vocabulary, L> {
+	vocabulary: &'a mut N,
+	objects: indexmap::set::Iter<'a, IndexedObject<N::Iri, N::BlankId>>,
+	context: &'a Context<N::Iri, N::BlankId>,
+	loader: &'a L,
+	options: Options,
+	yielder: Yielder,
+}
+
+impl<'a, N: Vocabulary, L> CompactIter<'a, N, L> {
+	/// Returns the compacted `@graph` keyword, that is the key of the entry
+	/// whose value is the array of compacted objects.
+	pub fn graph_key(&self) -> Result<String, Error>
+	where
+		N::Iri: Clone + Hash + Eq,
+		N::BlankId: Clone + Hash + Eq,
+	{
+		Ok(compact_iri(
+			self.vocabulary,
+			self.context,
+			&Term::Keyword(Keyword::Graph),
+			true,
+			false,
+			self.options,
+		)?
+		.unwrap())
+	}
+
+	/// Compacts the next top-level object.
+	pub async fn next(&mut self) -> Option<Result<json_syntax::Value, Error>>
+	where
+		N: VocabularyMut,
+		N::Iri: Clone + Hash + Eq,
+		N::BlankId: Clone + Hash + Eq,
+		L: Loader,
+	{
+		for object in self.objects.by_ref() {
+			let skipped;
+			let object = if self.options.on_invalid_iri == InvalidIriPolicy::Skip {
+				let mut object = object.clone();
+				object.remove_invalid_ids();
+				skipped = object;
+				&skipped
+			} else {
+				object
+			};
+
+			let compacted = object
+				.compact_fragment_full_yielding(
+					self.vocabulary,
+					self.context,
+					self.context,
+					None,
+					self.loader,
+					self.options,
+					&self.yielder,
+				)
+				.await;
+
+			match compacted {
+				Ok(value) if value.is_null() => (),
+				result => return Some(result),
+			}
+		}
+
+		None
+	}
+
+	/// Turns this iterator into a stream of compacted objects.
+	pub fn into_stream(self) -> impl 'a + Stream<Item = Result<json_syntax::Value, Error>>
+	where
+		N: VocabularyMut,
+		N::Iri: Clone + Hash + Eq,
+		N::BlankId: Clone + Hash + Eq,
+		L: Loader,
+	{
+		futures::stream::unfold(self, |mut iter| async move {
+			let item = iter.next().await?;
+			Some((item, iter))
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use futures::StreamExt;
+	use iref::IriBuf;
+	use json_ld_context_processing::ProcessedRef;
+	use json_ld_core::{Id, NoLoader, Node, Object};
+
+	#[test]
+	fn compact_iter() {
+		let name = Id::iri(IriBuf::new("https://schema.org/name".to_owned()).unwrap());
+
+		let mut document = ExpandedDocument::new();
+		for n in ["a", "b"] {
+			let mut node = Node::new();
+			node.insert(
+				name.clone(),
+				IndexedObject::none(Object::Value(json_ld_core::Value::Literal(
+					json_ld_core::object::Literal::String(n.into()),
+					None,
+				))),
+			);
+			document.insert(IndexedObject::none(Object::node(node)));
+		}
+
+		let json_context = json_syntax::json!({ "name": "https://schema.org/name" });
+		let unprocessed: json_ld_syntax::context::Context =
+			json_ld_syntax::TryFromJson::try_from_json(json_context).unwrap();
+		let processed = futures::executor::block_on(json_ld_context_processing::Process::process(
+			&unprocessed,
+			&mut (),
+			&NoLoader,
+			None,
+		))
+		.unwrap();
+
+		let mut vocabulary = ();
+		let iter = document
+			.compact_iter(
+				&mut vocabulary,
+				ProcessedRef::new(&unprocessed, &processed),
+				&NoLoader,
+				Options::default(),
+			)
+			.unwrap();
+		assert_eq!(iter.graph_key().unwrap(), "@graph");
+
+		let mut compacted: Vec<_> =
+			futures::executor::block_on(iter.into_stream().map(Result::unwrap).collect());
+		compacted.sort_by_key(|v| v.to_string());
+		assert_eq!(
+			compacted,
+			[
+				json_syntax::json!({ "name": "a" }),
+				json_syntax::json!({ "name": "b" })
+			]
+		)
+	}
+}
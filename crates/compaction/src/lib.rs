@@ -4,12 +4,14 @@
 //! # Usage
 //!
 //! The compaction algorithm is provided by the [`Compact`] trait.
+//! Top-level objects can also be compacted lazily using [`CompactItems`].
 use indexmap::IndexSet;
-use json_ld_context_processing::{Options as ProcessingOptions, Process};
+use json_ld_context_processing::{algorithm::process_scoped_context, Options as ProcessingOptions};
 use json_ld_core::{
 	context::inverse::{LangSelection, TypeSelection},
 	object::Any,
-	Context, Indexed, Loader, ProcessingMode, Term, Value,
+	progress::{Cancelled, Progress, Yielder},
+	Context, Indexed, InvalidIriPolicy, Loader, ProcessingMode, Term, Value,
 };
 use json_ld_syntax::{ContainerKind, ErrorCode, Keyword};
 use json_syntax::object::Entry;
@@ -17,16 +19,22 @@ use mown::Mown;
 use rdf_types::{vocabulary, VocabularyMut};
 use std::hash::Hash;
 
+mod canonical;
 mod document;
 mod iri;
+mod iter;
 mod node;
 mod property;
+mod term;
 mod value;
 
+use canonical::*;
 pub use document::*;
 pub(crate) use iri::*;
+pub use iter::*;
 use node::*;
 use property::*;
+pub use term::CompactTerm;
 use value::*;
 
 #[derive(Debug, thiserror::Error)]
@@ -37,8 +45,17 @@ pub enum Error {
 	#[error("Invalid `@nest` value")]
 	InvalidNestValue,
 
+	#[error("Invalid IRI `{0}`")]
+	InvalidIri(String),
+
+	#[error("Processing mode conflict: the output requires JSON-LD 1.1")]
+	ProcessingModeConflict,
+
 	#[error("Context processing failed: {0}")]
 	ContextProcessing(json_ld_context_processing::Error),
+
+	#[error("Compaction cancelled")]
+	Cancelled,
 }
 
 impl Error {
@@ -46,17 +63,29 @@ impl Error {
 		match self {
 			Self::IriConfusedWithPrefix => ErrorCode::IriConfusedWithPrefix,
 			Self::InvalidNestValue => ErrorCode::InvalidNestValue,
+			Self::InvalidIri(_) => ErrorCode::InvalidIdValue,
+			Self::ProcessingModeConflict => ErrorCode::ProcessingModeConflict,
 			Self::ContextProcessing(e) => e.code(),
+			Self::Cancelled => ErrorCode::OperationCancelled,
 		}
 	}
 }
 
+#[cfg(feature = "miette")]
+json_ld_syntax::impl_diagnostic!(Error);
+
 impl From<json_ld_context_processing::Error> for Error {
 	fn from(e: json_ld_context_processing::Error) -> Self {
 		Self::ContextProcessing(e)
 	}
 }
 
+impl From<Cancelled> for Error {
+	fn from(_: Cancelled) -> Self {
+		Self::Cancelled
+	}
+}
+
 impl From<IriConfusedWithPrefix> for Error {
 	fn from(_: IriConfusedWithPrefix) -> Self {
 		Self::IriConfusedWithPrefix
@@ -81,6 +110,67 @@ pub struct Options {
 	/// If set to `true`, properties are processed by lexical order.
 	/// If `false`, order is not considered in processing.
 	pub ordered: bool,
+
+	/// Determines what to do with invalid identifiers.
+	pub on_invalid_iri: InvalidIriPolicy,
+
+	/// If set to `true`, keywords are replaced by their aliases defined in the
+	/// context, if any.
+	/// If set to `false`, keywords are always output as is.
+	pub use_aliases: bool,
+
+	/// Determines how the `@version` entry of the embedded context is
+	/// handled.
+	pub version_entry: VersionEntry,
+
+	/// If set to `true`, values typed `xsd:boolean`, `xsd:integer` or
+	/// `xsd:double` matching the type mapping of their term are compacted
+	/// into native JSON booleans and numbers, when their lexical form allows
+	/// it.
+	/// If set to `false`, their lexical form is kept as a string.
+	pub compact_to_native: bool,
+
+	/// If set to `true`, the output is in canonical form: two processors
+	/// compacting the same expanded document with the same context produce
+	/// the same JSON value, and the same bytes once printed with
+	/// [`compact_print`](json_syntax::Print::compact_print). This is useful to
+	/// cache or sign compacted documents.
+	///
+	/// Unordered arrays (every array but lists) are sorted, properties are
+	/// processed by lexical order, and the output is put in
+	/// [RFC 8785](https://www.rfc-editor.org/rfc/rfc8785) canonical form
+	/// (sorted keys and canonical numbers).
+	pub canonical: bool,
+
+	/// If set, the compaction yields to the async executor every time this
+	/// number of array items is compacted.
+	///
+	/// Compaction is CPU-bound: on very large documents, yielding prevents it
+	/// from starving the other tasks of the executor. Items are counted over
+	/// the whole document, not per array.
+	///
+	/// Defaults to `None` (never yield).
+	pub yield_every: Option<usize>,
+}
+
+/// Handling of the `@version` entry of the context embedded in the
+/// compacted document.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum VersionEntry {
+	/// The context is embedded as is.
+	#[default]
+	Preserve,
+
+	/// A `@version` entry is added to the embedded context if it has none,
+	/// so that JSON-LD 1.0 processors reject the document instead of
+	/// misinterpreting it.
+	///
+	/// Nothing is added in the JSON-LD 1.0 processing mode.
+	Include,
+
+	/// The top-level `@version` entries are removed from the embedded
+	/// context.
+	Omit,
 }
 
 impl Options {
@@ -90,14 +180,29 @@ impl Options {
 			..self
 		}
 	}
+
+	/// Returns these options with the canonical output mode enabled.
+	pub fn canonical(self) -> Self {
+		Self {
+			canonical: true,
+			..self
+		}
+	}
+
+	/// Options used to compact a document put into canonical form.
+	fn canonical_order(self) -> Self {
+		Self {
+			ordered: true,
+			..self
+		}
+	}
 }
 
 impl From<Options> for json_ld_context_processing::Options {
 	fn from(options: Options) -> json_ld_context_processing::Options {
-		json_ld_context_processing::Options {
-			processing_mode: options.processing_mode,
-			..Default::default()
-		}
+		let mut result = json_ld_context_processing::Options::default();
+		result.processing_mode = options.processing_mode;
+		result
 	}
 }
 
@@ -118,12 +223,19 @@ impl Default for Options {
 			compact_to_relative: true,
 			compact_arrays: true,
 			ordered: false,
+			on_invalid_iri: InvalidIriPolicy::default(),
+			use_aliases: true,
+			version_entry: VersionEntry::default(),
+			compact_to_native: false,
+			canonical: false,
+			yield_every: None,
 		}
 	}
 }
 
 pub trait CompactFragment<I, B> {
 	#[allow(async_fn_in_trait)]
+	#[allow(clippy::too_many_arguments)]
 	async fn compact_fragment_full<'a, N, L>(
 		&'a self,
 		vocabulary: &'a mut N,
@@ -139,6 +251,40 @@ pub trait CompactFragment<I, B> {
 		B: Clone + Hash + Eq,
 		L: Loader;
 
+	/// Compacts the fragment with full options, periodically yielding to the
+	/// executor through `yielder`.
+	///
+	/// The default implementation never yields.
+	#[allow(async_fn_in_trait)]
+	#[allow(clippy::too_many_arguments)]
+	async fn compact_fragment_full_yielding<'a, N, L>(
+		&'a self,
+		vocabulary: &'a mut N,
+		active_context: &'a Context<I, B>,
+		type_scoped_context: &'a Context<I, B>,
+		active_property: Option<&'a str>,
+		loader: &'a L,
+		options: Options,
+		yielder: &'a Yielder,
+	) -> CompactFragmentResult
+	where
+		N: VocabularyMut<Iri = I, BlankId = B>,
+		I: Clone + Hash + Eq,
+		B: Clone + Hash + Eq,
+		L: Loader,
+	{
+		let _ = yielder;
+		self.compact_fragment_full(
+			vocabulary,
+			active_context,
+			type_scoped_context,
+			active_property,
+			loader,
+			options,
+		)
+		.await
+	}
+
 	#[allow(async_fn_in_trait)]
 	#[inline(always)]
 	async fn compact_fragment_with<'a, N, L>(
@@ -213,6 +359,42 @@ pub trait CompactIndexedFragment<I, B> {
 		I: Clone + Hash + Eq,
 		B: Clone + Hash + Eq,
 		L: Loader;
+
+	/// Compacts the fragment with the given index, periodically yielding to
+	/// the executor through `yielder`.
+	///
+	/// The default implementation never yields.
+	#[allow(async_fn_in_trait)]
+	#[allow(clippy::too_many_arguments)]
+	async fn compact_indexed_fragment_yielding<'a, N, L>(
+		&'a self,
+		vocabulary: &'a mut N,
+		index: Option<&'a str>,
+		active_context: &'a Context<I, B>,
+		type_scoped_context: &'a Context<I, B>,
+		active_property: Option<&'a str>,
+		loader: &'a L,
+		options: Options,
+		yielder: &'a Yielder,
+	) -> CompactFragmentResult
+	where
+		N: VocabularyMut<Iri = I, BlankId = B>,
+		I: Clone + Hash + Eq,
+		B: Clone + Hash + Eq,
+		L: Loader,
+	{
+		let _ = yielder;
+		self.compact_indexed_fragment(
+			vocabulary,
+			index,
+			active_context,
+			type_scoped_context,
+			active_property,
+			loader,
+			options,
+		)
+		.await
+	}
 }
 
 impl<I, B, T: CompactIndexedFragment<I, B>> CompactFragment<I, B> for Indexed<T> {
@@ -225,6 +407,34 @@ impl<I, B, T: CompactIndexedFragment<I, B>> CompactFragment<I, B> for Indexed<T>
 		loader: &'a L,
 		options: Options,
 	) -> CompactFragmentResult
+	where
+		N: VocabularyMut<Iri = I, BlankId = B>,
+		I: Clone + Hash + Eq,
+		B: Clone + Hash + Eq,
+		L: Loader,
+	{
+		self.compact_fragment_full_yielding(
+			vocabulary,
+			active_context,
+			type_scoped_context,
+			active_property,
+			loader,
+			options,
+			&Yielder::default(),
+		)
+		.await
+	}
+
+	async fn compact_fragment_full_yielding<'a, N, L>(
+		&'a self,
+		vocabulary: &'a mut N,
+		active_context: &'a Context<I, B>,
+		type_scoped_context: &'a Context<I, B>,
+		active_property: Option<&'a str>,
+		loader: &'a L,
+		options: Options,
+		yielder: &'a Yielder,
+	) -> CompactFragmentResult
 	where
 		N: VocabularyMut<Iri = I, BlankId = B>,
 		I: Clone + Hash + Eq,
@@ -232,7 +442,7 @@ impl<I, B, T: CompactIndexedFragment<I, B>> CompactFragment<I, B> for Indexed<T>
 		L: Loader,
 	{
 		self.inner()
-			.compact_indexed_fragment(
+			.compact_indexed_fragment_yielding(
 				vocabulary,
 				self.index(),
 				active_context,
@@ -240,6 +450,7 @@ impl<I, B, T: CompactIndexedFragment<I, B>> CompactFragment<I, B> for Indexed<T>
 				active_property,
 				loader,
 				options,
+				yielder,
 			)
 			.await
 	}
@@ -256,6 +467,36 @@ impl<I, B, T: Any<I, B>> CompactIndexedFragment<I, B> for T {
 		loader: &'a L,
 		options: Options,
 	) -> CompactFragmentResult
+	where
+		N: VocabularyMut<Iri = I, BlankId = B>,
+		I: Clone + Hash + Eq,
+		B: Clone + Hash + Eq,
+		L: Loader,
+	{
+		self.compact_indexed_fragment_yielding(
+			vocabulary,
+			index,
+			active_context,
+			type_scoped_context,
+			active_property,
+			loader,
+			options,
+			&Yielder::default(),
+		)
+		.await
+	}
+
+	async fn compact_indexed_fragment_yielding<'a, N, L>(
+		&'a self,
+		vocabulary: &'a mut N,
+		index: Option<&'a str>,
+		active_context: &'a Context<I, B>,
+		type_scoped_context: &'a Context<I, B>,
+		active_property: Option<&'a str>,
+		loader: &'a L,
+		options: Options,
+		yielder: &'a Yielder,
+	) -> CompactFragmentResult
 	where
 		N: VocabularyMut<Iri = I, BlankId = B>,
 		I: Clone + Hash + Eq,
@@ -286,6 +527,7 @@ impl<I, B, T: Any<I, B>> CompactIndexedFragment<I, B> for T {
 					active_property,
 					loader,
 					options,
+					yielder,
 				)
 				.await
 			}
@@ -302,6 +544,7 @@ impl<I, B, T: Any<I, B>> CompactIndexedFragment<I, B> for T {
 				// If the term definition for active property in active context has a local context:
 				// FIXME https://github.com/w3c/json-ld-api/issues/502
 				//       Seems that the term definition should be looked up in `type_scoped_context`.
+				let property_scoped;
 				let mut active_context = Mown::Borrowed(active_context);
 				let mut list_container = false;
 				if let Some(active_property) = active_property {
@@ -309,18 +552,16 @@ impl<I, B, T: Any<I, B>> CompactIndexedFragment<I, B> for T {
 						type_scoped_context.get(active_property)
 					{
 						if let Some(local_context) = active_property_definition.context() {
-							active_context = Mown::Owned(
-								local_context
-									.process_with(
-										vocabulary,
-										active_context.as_ref(),
-										loader,
-										active_property_definition.base_url().cloned(),
-										ProcessingOptions::from(options).with_override(),
-									)
-									.await?
-									.into_processed(),
+							property_scoped = process_scoped_context(
+								vocabulary,
+								active_context.as_ref(),
+								local_context,
+								loader,
+								active_property_definition.base_url().cloned(),
+								ProcessingOptions::from(options).with_override(),
 							)
+							.await?;
+							active_context = Mown::Borrowed(property_scoped.as_ref())
 						}
 
 						list_container = active_property_definition
@@ -338,6 +579,8 @@ impl<I, B, T: Any<I, B>> CompactIndexedFragment<I, B> for T {
 						active_property,
 						loader,
 						options,
+						yielder,
+						None,
 					)
 					.await
 				} else {
@@ -351,6 +594,7 @@ impl<I, B, T: Any<I, B>> CompactIndexedFragment<I, B> for T {
 						loader,
 						false,
 						options,
+						yielder,
 					)
 					.await?;
 
@@ -398,6 +642,40 @@ impl<I, B, T: Any<I, B>> CompactIndexedFragment<I, B> for T {
 
 /// Default value of `as_array` is false.
 fn add_value(map: &mut json_syntax::Object, key: &str, value: json_syntax::Value, as_array: bool) {
+	match value {
+		json_syntax::Value::Array(values) => {
+			prepare_value_entry(map, key, as_array);
+			for value in values {
+				add_value(map, key, value, false)
+			}
+		}
+		value => add_single_value(map, key, value, as_array),
+	}
+}
+
+/// Adds `value` to the `key` entry of `map` as a single value, even if it is
+/// an array.
+///
+/// This is used for JSON literals, whose compacted form may be an array.
+fn add_single_value(
+	map: &mut json_syntax::Object,
+	key: &str,
+	value: json_syntax::Value,
+	as_array: bool,
+) {
+	prepare_value_entry(map, key, as_array);
+
+	if let Some(array) = map.get_unique_mut(key).ok().unwrap() {
+		array.as_array_mut().unwrap().push(value);
+		return;
+	}
+
+	map.insert(key.into(), value);
+}
+
+/// Turns the `key` entry of `map` into an array if it already exists, or if
+/// `as_array` is true.
+fn prepare_value_entry(map: &mut json_syntax::Object, key: &str, as_array: bool) {
 	match map
 		.get_unique(key)
 		.ok()
@@ -413,22 +691,6 @@ fn add_value(map: &mut json_syntax::Object, key: &str, value: json_syntax::Value
 		}
 		_ => (),
 	}
-
-	match value {
-		json_syntax::Value::Array(values) => {
-			for value in values {
-				add_value(map, key, value, false)
-			}
-		}
-		value => {
-			if let Some(array) = map.get_unique_mut(key).ok().unwrap() {
-				array.as_array_mut().unwrap().push(value);
-				return;
-			}
-
-			map.insert(key.into(), value);
-		}
-	}
 }
 
 /// Get the `@value` field of a value object.
@@ -446,7 +708,12 @@ fn value_value<I>(value: &Value<I>) -> json_syntax::Value {
 	}
 }
 
-async fn compact_collection_with<'a, N, L, O, T>(
+/// Compacts a collection of items.
+///
+/// If a `progress` handle is given, the cancellation is checked before, and
+/// the progress reported after, each item.
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn compact_collection_with<'a, N, L, O, T>(
 	vocabulary: &'a mut N,
 	items: O,
 	active_context: &'a Context<N::Iri, N::BlankId>,
@@ -454,31 +721,45 @@ async fn compact_collection_with<'a, N, L, O, T>(
 	active_property: Option<&'a str>,
 	loader: &'a L,
 	options: Options,
+	yielder: &'a Yielder,
+	progress: Option<&Progress>,
 ) -> CompactFragmentResult
 where
 	N: VocabularyMut,
 	N::Iri: Clone + Hash + Eq,
 	N::BlankId: Clone + Hash + Eq,
 	T: 'a + CompactFragment<N::Iri, N::BlankId>,
-	O: 'a + Iterator<Item = &'a T>,
+	O: 'a + ExactSizeIterator<Item = &'a T>,
 	L: Loader,
 {
 	let mut result = Vec::new();
 
-	for item in items {
-		let compacted_item = Box::pin(item.compact_fragment_full(
+	let total = items.len();
+	for (i, item) in items.enumerate() {
+		if let Some(progress) = progress {
+			progress.check()?;
+		}
+
+		let compacted_item = Box::pin(item.compact_fragment_full_yielding(
 			vocabulary,
 			active_context,
 			type_scoped_context,
 			active_property,
 			loader,
 			options,
+			yielder,
 		))
 		.await?;
 
 		if !compacted_item.is_null() {
 			result.push(compacted_item)
 		}
+
+		if let Some(progress) = progress {
+			progress.report(i + 1, total)
+		}
+
+		yielder.tick().await
 	}
 
 	let mut list_or_set = false;
@@ -516,6 +797,34 @@ impl<T: CompactFragment<I, B>, I, B> CompactFragment<I, B> for IndexSet<T> {
 		loader: &'a L,
 		options: Options,
 	) -> CompactFragmentResult
+	where
+		N: VocabularyMut<Iri = I, BlankId = B>,
+		I: Clone + Hash + Eq,
+		B: Clone + Hash + Eq,
+		L: Loader,
+	{
+		self.compact_fragment_full_yielding(
+			vocabulary,
+			active_context,
+			type_scoped_context,
+			active_property,
+			loader,
+			options,
+			&Yielder::default(),
+		)
+		.await
+	}
+
+	async fn compact_fragment_full_yielding<'a, N, L>(
+		&'a self,
+		vocabulary: &'a mut N,
+		active_context: &'a Context<I, B>,
+		type_scoped_context: &'a Context<I, B>,
+		active_property: Option<&'a str>,
+		loader: &'a L,
+		options: Options,
+		yielder: &'a Yielder,
+	) -> CompactFragmentResult
 	where
 		N: VocabularyMut<Iri = I, BlankId = B>,
 		I: Clone + Hash + Eq,
@@ -530,6 +839,8 @@ impl<T: CompactFragment<I, B>, I, B> CompactFragment<I, B> for IndexSet<T> {
 			active_property,
 			loader,
 			options,
+			yielder,
+			None,
 		)
 		.await
 	}
@@ -545,6 +856,34 @@ impl<T: CompactFragment<I, B>, I, B> CompactFragment<I, B> for Vec<T> {
 		loader: &'a L,
 		options: Options,
 	) -> CompactFragmentResult
+	where
+		N: VocabularyMut<Iri = I, BlankId = B>,
+		I: Clone + Hash + Eq,
+		B: Clone + Hash + Eq,
+		L: Loader,
+	{
+		self.compact_fragment_full_yielding(
+			vocabulary,
+			active_context,
+			type_scoped_context,
+			active_property,
+			loader,
+			options,
+			&Yielder::default(),
+		)
+		.await
+	}
+
+	async fn compact_fragment_full_yielding<'a, N, L>(
+		&'a self,
+		vocabulary: &'a mut N,
+		active_context: &'a Context<I, B>,
+		type_scoped_context: &'a Context<I, B>,
+		active_property: Option<&'a str>,
+		loader: &'a L,
+		options: Options,
+		yielder: &'a Yielder,
+	) -> CompactFragmentResult
 	where
 		N: VocabularyMut<Iri = I, BlankId = B>,
 		I: Clone + Hash + Eq,
@@ -559,6 +898,8 @@ impl<T: CompactFragment<I, B>, I, B> CompactFragment<I, B> for Vec<T> {
 			active_property,
 			loader,
 			options,
+			yielder,
+			None,
 		)
 		.await
 	}
@@ -574,6 +915,34 @@ impl<T: CompactFragment<I, B> + Send + Sync, I, B> CompactFragment<I, B> for [T]
 		loader: &'a L,
 		options: Options,
 	) -> CompactFragmentResult
+	where
+		N: VocabularyMut<Iri = I, BlankId = B>,
+		I: Clone + Hash + Eq,
+		B: Clone + Hash + Eq,
+		L: Loader,
+	{
+		self.compact_fragment_full_yielding(
+			vocabulary,
+			active_context,
+			type_scoped_context,
+			active_property,
+			loader,
+			options,
+			&Yielder::default(),
+		)
+		.await
+	}
+
+	async fn compact_fragment_full_yielding<'a, N, L>(
+		&'a self,
+		vocabulary: &'a mut N,
+		active_context: &'a Context<I, B>,
+		type_scoped_context: &'a Context<I, B>,
+		active_property: Option<&'a str>,
+		loader: &'a L,
+		options: Options,
+		yielder: &'a Yielder,
+	) -> CompactFragmentResult
 	where
 		N: VocabularyMut<Iri = I, BlankId = B>,
 		I: Clone + Hash + Eq,
@@ -588,6 +957,8 @@ impl<T: CompactFragment<I, B> + Send + Sync, I, B> CompactFragment<I, B> for [T]
 			active_property,
 			loader,
 			options,
+			yielder,
+			None,
 		)
 		.await
 	}
@@ -1,7 +1,11 @@
 use crate::{add_value, compact_iri, compact_property, Error, Options};
 use contextual::WithContext;
-use json_ld_context_processing::{Options as ProcessingOptions, Process, ProcessingMode};
-use json_ld_core::{Container, ContainerKind, Context, Id, Loader, Node, Term, Type};
+use json_ld_context_processing::{
+	algorithm::process_scoped_context, Options as ProcessingOptions, ProcessingMode,
+};
+use json_ld_core::{
+	progress::Yielder, Container, ContainerKind, Context, Id, Loader, Node, Term, Type,
+};
 use json_ld_syntax::Keyword;
 use mown::Mown;
 use rdf_types::VocabularyMut;
@@ -23,6 +27,7 @@ pub async fn compact_indexed_node_with<N, L>(
 	active_property: Option<&str>,
 	loader: &L,
 	options: Options,
+	yielder: &Yielder,
 ) -> Result<json_syntax::Value, Error>
 where
 	N: VocabularyMut,
@@ -44,26 +49,27 @@ where
 	// If the term definition for active property in active context has a local context:
 	// FIXME https://github.com/w3c/json-ld-api/issues/502
 	//       Seems that the term definition should be looked up in `type_scoped_context`.
-	let mut active_context = Mown::Borrowed(active_context);
+	let property_scoped;
+	let mut active_context = active_context;
 	if let Some(active_property) = active_property {
 		if let Some(active_property_definition) = type_scoped_context.get(active_property) {
 			if let Some(local_context) = active_property_definition.context() {
-				active_context = Mown::Owned(
-					local_context
-						.process_with(
-							vocabulary,
-							active_context.as_ref(),
-							loader,
-							active_property_definition.base_url().cloned(),
-							ProcessingOptions::from(options).with_override(),
-						)
-						.await?
-						.into_processed(),
+				property_scoped = process_scoped_context(
+					vocabulary,
+					active_context,
+					local_context,
+					loader,
+					active_property_definition.base_url().cloned(),
+					ProcessingOptions::from(options).with_override(),
 				)
+				.await?;
+				active_context = property_scoped.as_ref()
 			}
 		}
 	}
 
+	let mut type_scoped = None;
+
 	// let inside_reverse = active_property == Some("@reverse");
 	let mut result = json_syntax::Object::default();
 
@@ -92,23 +98,25 @@ where
 			{
 				if let Some(local_context) = term_definition.context() {
 					let processing_options = ProcessingOptions::from(options).without_propagation();
-					active_context = Mown::Owned(
-						local_context
-							.process_with(
-								vocabulary,
-								active_context.as_ref(),
-								loader,
-								term_definition.base_url().cloned(),
-								processing_options,
-							)
-							.await?
-							.into_processed(),
-					)
+					type_scoped = Some(
+						process_scoped_context(
+							vocabulary,
+							type_scoped.as_deref().unwrap_or(active_context),
+							local_context,
+							loader,
+							term_definition.base_url().cloned(),
+							processing_options,
+						)
+						.await?,
+					);
 				}
 			}
 		}
 	}
 
+	let reverse_scoped;
+	let mut active_context = Mown::Borrowed(type_scoped.as_deref().unwrap_or(active_context));
+
 	// For each key expanded property and value expanded value in element, ordered
 	// lexicographically by expanded property if ordered is true:
 	let mut expanded_entries: Vec<_> = node.properties().iter().collect();
@@ -147,26 +155,14 @@ where
 				None => None,
 			};
 
-			if type_mapping == Some(&Type::Id) {
-				let compacted_value = compact_iri(
-					vocabulary,
-					active_context.as_ref(),
-					&id,
-					false,
-					false,
-					options,
-				)?;
-				return Ok(optional_string(compacted_value));
-			}
-
 			// Otherwise, if the type mapping of active property is set to @vocab,
 			// set result to the result of IRI compacting the value associated with the @id entry.
-			if type_mapping == Some(&Type::Vocab) {
+			if let Some(vocab) = type_mapping.and_then(Type::node_reference_vocab) {
 				let compacted_value = compact_iri(
 					vocabulary,
 					active_context.as_ref(),
 					&id,
-					true,
+					vocab,
 					false,
 					options,
 				)?;
@@ -220,18 +216,16 @@ where
 			let active_property = "@reverse";
 			if let Some(active_property_definition) = active_context.get(active_property) {
 				if let Some(local_context) = active_property_definition.context() {
-					active_context = Mown::Owned(
-						local_context
-							.process_with(
-								vocabulary,
-								active_context.as_ref(),
-								loader,
-								active_property_definition.base_url().cloned(),
-								ProcessingOptions::from(options).with_override(),
-							)
-							.await?
-							.into_processed(),
+					reverse_scoped = process_scoped_context(
+						vocabulary,
+						active_context.as_ref(),
+						local_context,
+						loader,
+						active_property_definition.base_url().cloned(),
+						ProcessingOptions::from(options).with_override(),
 					)
+					.await?;
+					active_context = Mown::Borrowed(reverse_scoped.as_ref())
 				}
 			}
 
@@ -246,6 +240,7 @@ where
 					loader,
 					true,
 					options,
+					yielder,
 				)
 				.await?;
 			}
@@ -334,6 +329,7 @@ where
 			loader,
 			false,
 			options,
+			yielder,
 		)
 		.await?
 	}
@@ -348,6 +344,7 @@ where
 			loader,
 			false,
 			options,
+			yielder,
 		)
 		.await?
 	}
@@ -362,6 +359,7 @@ where
 			loader,
 			false,
 			options,
+			yielder,
 		)
 		.await?
 	}
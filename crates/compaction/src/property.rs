@@ -1,13 +1,15 @@
 use crate::{
-	add_value, compact_collection_with, compact_iri, compact_iri_with, compact_key, value_value,
-	CompactFragment, CompactIndexedFragment, Error, Options,
+	add_single_value, add_value, compact_collection_with, compact_iri, compact_iri_with,
+	compact_key, value_value, CompactFragment, CompactIndexedFragment, Error, Options,
 };
 
 use json_ld_core::{
 	context::Nest,
 	object::{self, List},
-	Container, ContainerKind, Context, Id, Indexed, Loader, Node, Object, Term,
+	progress::Yielder,
+	Container, ContainerKind, Context, Indexed, Loader, Node, Object, Term,
 };
+use json_ld_expansion::ExpandTerm;
 use json_ld_syntax::Keyword;
 use rdf_types::VocabularyMut;
 use std::hash::Hash;
@@ -24,6 +26,7 @@ async fn compact_property_list<N, L>(
 	active_context: &Context<N::Iri, N::BlankId>,
 	loader: &L,
 	options: Options,
+	yielder: &Yielder,
 ) -> Result<(), Error>
 where
 	N: VocabularyMut,
@@ -40,6 +43,8 @@ where
 		Some(item_active_property),
 		loader,
 		options,
+		yielder,
+		None,
 	))
 	.await?;
 
@@ -67,28 +72,19 @@ where
 		let mut compacted_item_list_object = json_syntax::Object::default();
 		compacted_item_list_object.insert(key.unwrap(), compacted_item);
 
-		// If `expanded_item` contains the entry @index-value,
-		// then add an entry to compacted item where the key is
-		// the result of IRI compacting @index and value is value.
-		if let Some(index) = expanded_index {
-			let key = compact_key(
-				vocabulary,
-				active_context,
-				&Term::Keyword(Keyword::Index),
-				true,
-				false,
-				options,
-			)?;
-
-			compacted_item_list_object
-				.insert(key.unwrap(), json_syntax::Value::String(index.into()));
-		}
-
-		compacted_item = json_syntax::Value::Object(compacted_item_list_object);
-
 		// Use add value to add `compacted_item` to
 		// the `item_active_property` entry in `nest_result` using `as_array`.
-		add_value(nest_result, item_active_property, compacted_item, as_array)
+		add_indexed_value(
+			vocabulary,
+			nest_result,
+			compacted_item_list_object,
+			expanded_index,
+			container,
+			as_array,
+			item_active_property,
+			active_context,
+			options,
+		)?
 	} else {
 		// Otherwise, set the value of the item active property entry in nest result to compacted item.
 		nest_result.insert(item_active_property.into(), compacted_item);
@@ -109,6 +105,7 @@ async fn compact_property_graph<N, L>(
 	active_context: &Context<N::Iri, N::BlankId>,
 	loader: &L,
 	options: Options,
+	yielder: &Yielder,
 ) -> Result<(), Error>
 where
 	N: VocabularyMut,
@@ -117,13 +114,14 @@ where
 	L: Loader,
 {
 	// If expanded item is a graph object
-	let mut compacted_item = Box::pin(node.graph().unwrap().compact_fragment_full(
+	let mut compacted_item = Box::pin(node.graph().unwrap().compact_fragment_full_yielding(
 		vocabulary,
 		active_context,
 		active_context,
 		Some(item_active_property),
 		loader,
 		options,
+		yielder,
 	))
 	.await?;
 
@@ -193,12 +191,23 @@ where
 		let map_object = map_object.as_object_mut().unwrap();
 
 		// Initialize `map_key` the value of @index in `expanded_item`
-		// or @none, if no such value exists.
-		let map_key = expanded_index.unwrap_or("@none");
+		// or the result of IRI compacting @none, if no such value exists.
+		let map_key = match expanded_index {
+			Some(index) => index.to_owned(),
+			None => compact_iri(
+				vocabulary,
+				active_context,
+				&Term::Keyword(Keyword::None),
+				true,
+				false,
+				options,
+			)?
+			.unwrap(),
+		};
 
 		// Use `add_value` to add `compacted_item` to
 		// the `map_key` entry in `map_object` using `as_array`.
-		add_value(map_object, map_key, compacted_item, as_array)
+		add_value(map_object, &map_key, compacted_item, as_array)
 	} else if container.contains(ContainerKind::Graph) && node.is_simple_graph() {
 		// Otherwise, if `container` includes @graph and
 		// `expanded_item` is a simple graph object
@@ -275,26 +284,118 @@ where
 			);
 		}
 
-		// If `expanded_item` contains an @index entry,
-		// add an entry in `compacted_item` using the key from
-		// IRI compacting @index and the value of @index in `expanded_item`.
-		if let Some(index_entry) = expanded_index {
-			let key = compact_iri(
+		// Use `add_value` to add `compacted_item` to the
+		// `item_active_property` entry in `nest_result` using `as_array`.
+		add_indexed_value(
+			vocabulary,
+			nest_result,
+			map,
+			expanded_index,
+			container,
+			as_array,
+			item_active_property,
+			active_context,
+			options,
+		)?
+	}
+
+	Ok(())
+}
+
+/// Adds a compacted list or graph object to `nest_result`.
+///
+/// If `expanded_index` is given, an @index entry is added to
+/// `compacted_item`, unless `container` includes @index (but not @graph).
+/// In that case the entry of `item_active_property` is an index map, and
+/// `compacted_item` is added to this map under its index (or @none) instead
+/// of replacing it.
+#[allow(clippy::too_many_arguments)]
+fn add_indexed_value<N>(
+	vocabulary: &N,
+	nest_result: &mut json_syntax::Object,
+	mut compacted_item: json_syntax::Object,
+	expanded_index: Option<&str>,
+	container: Container,
+	as_array: bool,
+	item_active_property: &str,
+	active_context: &Context<N::Iri, N::BlankId>,
+	options: Options,
+) -> Result<(), Error>
+where
+	N: VocabularyMut,
+	N::Iri: Clone + Hash + Eq,
+	N::BlankId: Clone + Hash + Eq,
+{
+	let is_index_map =
+		container.contains(ContainerKind::Index) && !container.contains(ContainerKind::Graph);
+
+	// Property-valued indexes cannot be represented in list or graph
+	// objects, so the @index entry is kept.
+	let map_key = match active_context.get(item_active_property) {
+		Some(def) if is_index_map && def.index().is_none() => expanded_index,
+		_ => None,
+	};
+
+	// If `expanded_item` contains an @index entry,
+	// add an entry in `compacted_item` using the key from
+	// IRI compacting @index and the value of @index in `expanded_item`.
+	if map_key.is_none() {
+		if let Some(index) = expanded_index {
+			let key = compact_key(
 				vocabulary,
 				active_context,
 				&Term::Keyword(Keyword::Index),
 				true,
 				false,
 				options,
+			)?;
+
+			compacted_item.insert(key.unwrap(), json_syntax::Value::String(index.into()));
+		}
+	}
+
+	if is_index_map {
+		let map_key = match map_key {
+			Some(key) => key.to_owned(),
+			None => compact_iri(
+				vocabulary,
+				active_context,
+				&Term::Keyword(Keyword::None),
+				true,
+				false,
+				options,
 			)?
-			.unwrap();
-			map.insert(key.into(), index_entry.into());
+			.unwrap(),
+		};
+
+		if nest_result
+			.get_unique(item_active_property)
+			.ok()
+			.unwrap()
+			.is_none()
+		{
+			nest_result.insert(
+				item_active_property.into(),
+				json_syntax::Object::default().into(),
+			);
 		}
 
-		// Use `add_value` to add `compacted_item` to the
-		// `item_active_property` entry in `nest_result` using `as_array`.
-		let compacted_item = json_syntax::Value::Object(map);
-		add_value(nest_result, item_active_property, compacted_item, as_array)
+		let map_object = nest_result
+			.get_unique_mut(item_active_property)
+			.ok()
+			.unwrap()
+			.unwrap()
+			.as_object_mut()
+			.unwrap();
+
+		add_value(map_object, &map_key, compacted_item.into(), as_array)
+	} else {
+		add_value(
+			nest_result,
+			item_active_property,
+			compacted_item.into(),
+			as_array,
+		)
 	}
 
 	Ok(())
@@ -392,6 +493,7 @@ pub async fn compact_property<'a, N, L, O, T>(
 	loader: &L,
 	inside_reverse: bool,
 	options: Options,
+	yielder: &Yielder,
 ) -> Result<(), Error>
 where
 	N: VocabularyMut,
@@ -406,6 +508,8 @@ where
 	// For each item `expanded_item` in `expanded value`
 	for expanded_item in expanded_value {
 		is_empty = false;
+		yielder.tick().await;
+
 		// Initialize `item_active_property` by IRI compacting `expanded_property`
 		// using `expanded_item` for value and `inside_reverse` for `reverse`.
 		let item_active_property = compact_iri_with(
@@ -448,6 +552,7 @@ where
 						active_context,
 						loader,
 						options,
+						yielder,
 					)
 					.await?
 				}
@@ -463,19 +568,29 @@ where
 						active_context,
 						loader,
 						options,
+						yielder,
 					)
 					.await?
 				}
 				_ => {
-					let mut compacted_item = Box::pin(expanded_item.compact_fragment_full(
-						vocabulary,
-						active_context,
-						active_context,
-						Some(&item_active_property),
-						loader,
-						options,
-					))
-					.await?;
+					// JSON literals may be compacted into an array, that must not
+					// be merged with the other values.
+					let is_json = matches!(
+						expanded_item.inner().as_ref(),
+						object::Ref::Value(object::Value::Json(_))
+					);
+
+					let mut compacted_item =
+						Box::pin(expanded_item.compact_fragment_full_yielding(
+							vocabulary,
+							active_context,
+							active_context,
+							Some(&item_active_property),
+							loader,
+							options,
+							yielder,
+						))
+						.await?;
 
 					// if container includes @language, @index, @id,
 					// or @type and container does not include @graph:
@@ -548,7 +663,15 @@ where
 								compacted_item = value_value(value)
 							}
 
-							expanded_item.language().map(|lang| lang.to_string())
+							// Language tags are case-insensitive: values are
+							// added to the entry of an equivalent tag, if any.
+							expanded_item.language().map(|lang| {
+								map_object
+									.iter()
+									.find(|entry| entry.key.eq_ignore_ascii_case(lang.as_str()))
+									.map(|entry| entry.key.to_string())
+									.unwrap_or_else(|| lang.to_string())
+							})
 						} else if container_type == ContainerKind::Index {
 							match index_key {
 								Some(index_key) => {
@@ -556,11 +679,18 @@ where
 									// `index_key` is not @index:
 
 									// Reinitialize `container_key` by
-									// IRI compacting `index_key`.
+									// IRI compacting `index_key` after first
+									// IRI expanding it.
+									let expanded_index_key = active_context.expand_iri_with(
+										vocabulary,
+										index_key.as_str(),
+										true,
+										false,
+									);
 									container_key = compact_iri(
 										vocabulary,
 										active_context,
-										&Term::Id(Id::Invalid(index_key.to_string())),
+										&expanded_index_key,
 										true,
 										false,
 										options,
@@ -584,13 +714,23 @@ where
 													json_syntax::Value::Array(values) => {
 														let mut values = values.into_iter();
 														match values.next() {
-															Some(first_value) => (
-																first_value
-																	.as_str()
-																	.map(|v| v.to_string()),
+															Some(json_syntax::Value::String(
+																first_value,
+															)) => (
+																Some(first_value.to_string()),
 																values.collect(),
 															),
-															None => (None, values.collect()),
+															// A value that is not a string
+															// cannot be used as map key: the
+															// item is put under @none and
+															// keeps all its values.
+															Some(first_value) => (
+																None,
+																std::iter::once(first_value)
+																	.chain(values)
+																	.collect(),
+															),
+															None => (None, Vec::new()),
 														}
 													}
 													other => (None, vec![other]),
@@ -705,16 +845,18 @@ where
 									let obj = Object::node(Node::with_id(
 										expanded_item.id().unwrap().clone(),
 									));
-									compacted_item = Box::pin(obj.compact_indexed_fragment(
-										vocabulary,
-										None,
-										active_context,
-										active_context,
-										Some(&item_active_property),
-										loader,
-										options,
-									))
-									.await?
+									compacted_item =
+										Box::pin(obj.compact_indexed_fragment_yielding(
+											vocabulary,
+											None,
+											active_context,
+											active_context,
+											Some(&item_active_property),
+											loader,
+											options,
+											yielder,
+										))
+										.await?
 								}
 							}
 
@@ -740,7 +882,18 @@ where
 
 						// Use `add_value` to add `compacted_item` to
 						// the `map_key` entry in `map_object` using `as_array`.
-						add_value(map_object, &map_key, compacted_item, as_array)
+						if is_json {
+							add_single_value(map_object, &map_key, compacted_item, as_array)
+						} else {
+							add_value(map_object, &map_key, compacted_item, as_array)
+						}
+					} else if is_json {
+						add_single_value(
+							nest_result,
+							&item_active_property,
+							compacted_item,
+							as_array,
+						)
 					} else {
 						// Otherwise, use `add_value` to add `compacted_item` to the
 						// `item_active_property` entry in `nest_result` using `as_array`.
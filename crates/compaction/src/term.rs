@@ -0,0 +1,121 @@
+use crate::{compact_iri, Error, Options};
+use json_ld_core::{Context, Term};
+use rdf_types::Vocabulary;
+use std::hash::Hash;
+
+/// Compaction of single IRIs against a processed context.
+///
+/// This gives access to the IRI compaction algorithm used by the document
+/// compaction, so that IRIs can be presented with the same terms, compact
+/// IRIs or relative IRI references as in compacted documents.
+pub trait CompactTerm<T, B> {
+	/// Compacts the given IRI, blank node identifier or keyword with the
+	/// given `vocabulary`.
+	///
+	/// If `vocab` is `true`, the IRI is compacted as a property or type: the
+	/// best matching term is selected using the inverse context, then the
+	/// vocabulary mapping and prefixes are tried. Otherwise it is compacted
+	/// as a node identifier, relative to the base IRI of the context if
+	/// [`Options::compact_to_relative`] is set.
+	///
+	/// Returns `None` if `iri` is [`Term::Null`].
+	/// See <https://www.w3.org/TR/json-ld11-api/#iri-compaction>.
+	fn compact_iri_with<N>(
+		&self,
+		vocabulary: &N,
+		iri: &Term<T, B>,
+		vocab: bool,
+		options: Options,
+	) -> Result<Option<String>, Error>
+	where
+		N: Vocabulary<Iri = T, BlankId = B>,
+		T: Clone + Hash + Eq,
+		B: Clone + Hash + Eq;
+
+	/// Compacts the given IRI, blank node identifier or keyword.
+	///
+	/// See [`CompactTerm::compact_iri_with`].
+	fn compact_iri(
+		&self,
+		iri: &Term<T, B>,
+		vocab: bool,
+		options: Options,
+	) -> Result<Option<String>, Error>
+	where
+		(): Vocabulary<Iri = T, BlankId = B>,
+		T: Clone + Hash + Eq,
+		B: Clone + Hash + Eq,
+	{
+		self.compact_iri_with(&(), iri, vocab, options)
+	}
+}
+
+impl<T, B> CompactTerm<T, B> for Context<T, B> {
+	fn compact_iri_with<N>(
+		&self,
+		vocabulary: &N,
+		iri: &Term<T, B>,
+		vocab: bool,
+		options: Options,
+	) -> Result<Option<String>, Error>
+	where
+		N: Vocabulary<Iri = T, BlankId = B>,
+		T: Clone + Hash + Eq,
+		B: Clone + Hash + Eq,
+	{
+		Ok(compact_iri(vocabulary, self, iri, vocab, false, options)?)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use iref::IriBuf;
+	use json_ld_context_processing::Process;
+	use json_ld_core::{Id, NoLoader};
+	use json_ld_syntax::{Keyword, TryFromJson};
+	use rdf_types::BlankIdBuf;
+
+	#[test]
+	fn compact_term() {
+		let context = json_ld_syntax::context::Context::try_from_json(json_syntax::json!({
+			"@base": "https://example.com/",
+			"schema": "https://schema.org/",
+			"name": "https://schema.org/name",
+			"id": "@id"
+		}))
+		.unwrap();
+		let context: Context<IriBuf, BlankIdBuf> =
+			futures::executor::block_on(context.process(&mut (), &NoLoader, None))
+				.ok()
+				.unwrap()
+				.into_processed();
+
+		let compact = |iri: &str, vocab| {
+			let iri = Term::Id(Id::iri(IriBuf::new(iri.to_owned()).unwrap()));
+			context
+				.compact_iri(&iri, vocab, Options::default())
+				.ok()
+				.unwrap()
+				.unwrap()
+		};
+
+		assert_eq!(compact("https://schema.org/name", true), "name");
+		assert_eq!(compact("https://schema.org/age", true), "schema:age");
+		assert_eq!(compact("https://example.com/alice", false), "alice");
+		assert_eq!(
+			context
+				.compact_iri(&Term::Keyword(Keyword::Id), true, Options::default())
+				.ok()
+				.unwrap(),
+			Some("id".to_owned())
+		);
+		assert_eq!(
+			context
+				.compact_iri(&Term::Null, true, Options::default())
+				.ok()
+				.unwrap(),
+			None
+		)
+	}
+}
@@ -1,10 +1,37 @@
 use crate::{compact_iri, compact_key, Error, Options};
-use json_ld_context_processing::{Options as ProcessingOptions, Process};
-use json_ld_core::{object, Container, ContainerKind, Context, Id, Loader, Term, Type, Value};
+use json_ld_context_processing::{algorithm::process_scoped_context, Options as ProcessingOptions};
+use json_ld_core::{
+	object,
+	rdf::{XSD_BOOLEAN, XSD_DOUBLE, XSD_INTEGER},
+	Container, ContainerKind, Context, Id, Loader, Term, Type, Value,
+};
 use json_ld_syntax::Keyword;
 use mown::Mown;
-use rdf_types::VocabularyMut;
-use std::hash::Hash;
+use rdf_types::{vocabulary::IriVocabulary, VocabularyMut};
+use std::{hash::Hash, str::FromStr};
+
+/// Converts the lexical form of an `xsd:boolean`, `xsd:integer` or
+/// `xsd:double` value into the equivalent native JSON value, if any.
+fn native_value<N: IriVocabulary>(
+	vocabulary: &N,
+	ty: &N::Iri,
+	value: &str,
+) -> Option<json_syntax::Value> {
+	let ty = vocabulary.iri(ty)?;
+	if ty == XSD_BOOLEAN {
+		match value {
+			"true" => Some(json_syntax::Value::Boolean(true)),
+			"false" => Some(json_syntax::Value::Boolean(false)),
+			_ => None,
+		}
+	} else if (ty == XSD_INTEGER && !value.contains(['.', 'e', 'E'])) || ty == XSD_DOUBLE {
+		json_syntax::NumberBuf::from_str(value)
+			.ok()
+			.map(json_syntax::Value::Number)
+	} else {
+		None
+	}
+}
 
 /// Compact the given indexed value.
 pub async fn compact_indexed_value_with<N, L>(
@@ -23,22 +50,21 @@ where
 	L: Loader,
 {
 	// If the term definition for active property in active context has a local context:
+	let property_scoped;
 	let mut active_context = Mown::Borrowed(active_context);
 	if let Some(active_property) = active_property {
 		if let Some(active_property_definition) = active_context.get(active_property) {
 			if let Some(local_context) = active_property_definition.context() {
-				active_context = Mown::Owned(
-					local_context
-						.process_with(
-							vocabulary,
-							active_context.as_ref(),
-							loader,
-							active_property_definition.base_url().cloned(),
-							ProcessingOptions::from(options).with_override(),
-						)
-						.await?
-						.into_processed(),
+				property_scoped = process_scoped_context(
+					vocabulary,
+					active_context.as_ref(),
+					local_context,
+					loader,
+					active_property_definition.base_url().cloned(),
+					ProcessingOptions::from(options).with_override(),
 				)
+				.await?;
+				active_context = Mown::Borrowed(property_scoped.as_ref())
 			}
 		}
 	}
@@ -114,6 +140,14 @@ where
 					Literal::Number(n) => return Ok(json_syntax::Value::Number(n.clone())),
 					Literal::String(s) => {
 						if ty.is_some() || (language.is_none() && direction.is_none()) {
+							if options.compact_to_native {
+								if let Some(value) =
+									ty.as_ref().and_then(|ty| native_value(vocabulary, ty, s))
+								{
+									return Ok(value);
+								}
+							}
+
 							return Ok(json_syntax::Value::String(s.as_str().into()));
 						} else {
 							let compact_key = compact_key(
@@ -665,16 +665,24 @@ where
 						// protected.
 						// If any error is detected, an invalid scoped context error has been
 						// detected and processing is aborted.
-						Box::pin(super::process_context(
-							env,
-							active_context,
-							context,
-							remote_contexts.clone(),
-							base_url.clone(),
-							options.with_override(),
-						))
-						.await
-						.map_err(|_| Error::InvalidScopedContext)?;
+						// Remote contexts already included are not processed further.
+						//
+						// With lazy scoped contexts, validation is left to the first use.
+						if !options.lazy_scoped_contexts {
+							Box::pin(super::process_context(
+								env,
+								active_context,
+								context,
+								remote_contexts.clone(),
+								base_url.clone(),
+								options
+									.with_override()
+									.with_reject_recursive_contexts(false),
+								None,
+							))
+							.await
+							.map_err(|_| Error::InvalidScopedContext)?;
+						}
 
 						// Set the local context of definition to context, and base URL to base URL.
 						definition.context = Some(Box::new(context.clone()));
@@ -207,7 +207,7 @@ where
 	Term::Id(Id::Invalid(value))
 }
 
-#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Action {
 	#[default]
 	Keep,
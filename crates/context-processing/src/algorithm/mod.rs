@@ -1,12 +1,16 @@
-use std::hash::Hash;
+use std::{hash::Hash, sync::Arc};
 
 use crate::{
-	Error, Options, Process, Processed, ProcessingResult, ProcessingStack, WarningHandler,
+	cache::is_cacheable, Error, Options, Process, Processed, ProcessingResult, ProcessingStack,
+	SharedContextCache, WarningHandler,
 };
 use iref::IriRef;
 use json_ld_core::{Context, Environment, ExtractContext, Loader, ProcessingMode, Term};
 use json_ld_syntax::{self as syntax, Nullable};
-use rdf_types::{vocabulary::IriVocabularyMut, VocabularyMut};
+use rdf_types::{
+	vocabulary::{IriVocabulary, IriVocabularyMut},
+	VocabularyMut,
+};
 
 mod define;
 mod iri;
@@ -45,11 +49,105 @@ impl Process for syntax::context::Context {
 			ProcessingStack::default(),
 			base_url,
 			options,
+			None,
 		)
 		.await
 	}
 }
 
+/// Processes the scoped `context` of a term definition of `active_context`.
+///
+/// The result is shared through [`Context::insert_scoped_context`]: processing
+/// the same scoped context against the same active context, base URL and
+/// options again returns the previously processed context instead. Scoped
+/// contexts processed without propagation are marked as
+/// [type-scoped](Context::is_type_scoped).
+pub async fn process_scoped_context<N, L>(
+	vocabulary: &mut N,
+	active_context: &Context<N::Iri, N::BlankId>,
+	context: &syntax::context::Context,
+	loader: &L,
+	base_url: Option<N::Iri>,
+	options: Options,
+) -> Result<Arc<Context<N::Iri, N::BlankId>>, Error>
+where
+	N: VocabularyMut,
+	N::Iri: Clone + Eq + Hash,
+	N::BlankId: Clone + PartialEq,
+	L: Loader,
+{
+	if let Some(processed) = active_context.scoped_context(context, base_url.as_ref(), &options) {
+		return Ok(processed);
+	}
+
+	let mut processed = context
+		.process_with(
+			vocabulary,
+			active_context,
+			loader,
+			base_url.clone(),
+			options,
+		)
+		.await?
+		.into_processed();
+
+	if !options.propagate {
+		processed.set_type_scoped(true)
+	}
+
+	let processed = Arc::new(processed);
+	active_context.insert_scoped_context(context.clone(), base_url, options, processed.clone());
+	Ok(processed)
+}
+
+/// Processes the scoped contexts of the given terms of `active_context`.
+///
+/// Unless [`Options::lazy_scoped_contexts`] is set, scoped contexts are
+/// validated when their term is defined. Otherwise, this function can be used
+/// to process the scoped contexts of chosen terms ahead of time, so that they
+/// are reported invalid before any document is expanded. Unknown terms and
+/// terms without scoped context are ignored.
+///
+/// Each scoped context is processed with [`process_scoped_context`], as a
+/// property-scoped context of `active_context`: the result is kept in the
+/// memo of `active_context`, and reused when the term is used as a property
+/// of a node expanded against `active_context` with the same `options`.
+pub async fn process_scoped_contexts<'t, N, L>(
+	vocabulary: &mut N,
+	active_context: &Context<N::Iri, N::BlankId>,
+	terms: impl IntoIterator<Item = &'t str>,
+	loader: &L,
+	options: Options,
+) -> Result<(), Error>
+where
+	N: VocabularyMut,
+	N::Iri: Clone + Eq + Hash,
+	N::BlankId: Clone + PartialEq,
+	L: Loader,
+{
+	for term in terms {
+		let definition = match active_context.get_normal(term) {
+			Some(definition) => definition,
+			None => continue,
+		};
+
+		if let Some(context) = &definition.context {
+			process_scoped_context(
+				vocabulary,
+				active_context,
+				context,
+				loader,
+				definition.base_url().cloned(),
+				options.with_override(),
+			)
+			.await
+			.map_err(|_| Error::InvalidScopedContext)?;
+		}
+	}
+
+	Ok(())
+}
+
 /// Resolve `iri_ref` against the given base IRI.
 fn resolve_iri<I>(
 	vocabulary: &mut impl IriVocabularyMut<Iri = I>,
@@ -65,18 +163,36 @@ fn resolve_iri<I>(
 	}
 }
 
+/// Adds the remote context `iri` to the remote context chain of `error`.
+///
+/// The chain is left untouched if `iri` is unknown to the vocabulary.
+fn remote_context_error<N: IriVocabulary>(
+	vocabulary: &N,
+	iri: &N::Iri,
+	error: impl Into<Error>,
+) -> Error {
+	let error = error.into();
+	match vocabulary.iri(iri) {
+		Some(iri) => error.in_remote_context(iri.to_owned()),
+		None => error,
+	}
+}
+
 // This function tries to follow the recommended context processing algorithm.
 // See `https://www.w3.org/TR/json-ld11-api/#context-processing-algorithm`.
 //
 // The recommended default value for `remote_contexts` is the empty set,
 // `false` for `override_protected`, and `true` for `propagate`.
-async fn process_context<'l: 'a, 'a, N, L, W>(
+//
+// If a `cache` is given, remote contexts are looked up in (and added to) it.
+pub(crate) async fn process_context<'l: 'a, 'a, N, L, W>(
 	mut env: Environment<'a, N, L, W>,
 	active_context: &'a Context<N::Iri, N::BlankId>,
 	local_context: &'l syntax::context::Context,
 	mut remote_contexts: ProcessingStack<N::Iri>,
 	base_url: Option<N::Iri>,
 	mut options: Options,
+	cache: Option<&SharedContextCache<N::Iri, N::BlankId>>,
 ) -> ProcessingResult<'l, N::Iri, N::BlankId>
 where
 	N: VocabularyMut,
@@ -85,6 +201,11 @@ where
 	L: Loader,
 	W: WarningHandler<N>,
 {
+	// Remote contexts being processed, this one included. Unlike
+	// `remote_contexts`, it does not contain the remote contexts previously
+	// processed by the same local context.
+	let ancestors = remote_contexts.clone();
+
 	// 1) Initialize result to the result of cloning active context.
 	let mut result = active_context.clone();
 
@@ -121,18 +242,7 @@ where
 					// Otherwise, initialize result as a newly-initialized active context, setting
 					// previous_context in result to the previous value of result if propagate is
 					// false. Continue with the next context.
-					let previous_result = result;
-
-					// Initialize `result` as a newly-initialized active context, setting both
-					// `base_iri` and `original_base_url` to the value of `original_base_url` in
-					// active context, ...
-					result = Context::new(active_context.original_base_url().cloned());
-
-					// ... and, if `propagate` is `false`, `previous_context` in `result` to the
-					// previous value of `result`.
-					if !options.propagate {
-						result.set_previous_context(previous_result);
-					}
+					result.reset(options.propagate);
 				}
 			}
 
@@ -165,14 +275,42 @@ where
 				// If the document has no top-level map with an @context entry, an invalid remote
 				// context has been detected and processing is aborted.
 				// Set loaded context to the value of that entry.
-				if remote_contexts.push(context_iri.clone()) {
+				// If context is already being processed, a recursive context inclusion
+				// has been detected.
+				if let Some(cycle) = ancestors.cycle_path(&context_iri) {
+					if !options.reject_recursive_contexts {
+						continue;
+					}
+
+					// IRIs unknown to the vocabulary are left out of the cycle.
+					let cycle = cycle
+						.iter()
+						.filter_map(|iri| env.vocabulary.iri(iri).map(ToOwned::to_owned))
+						.collect();
+					return Err(Error::RecursiveContextInclusion(cycle));
+				}
+
+				let cache = cache.filter(|_| is_cacheable(&result, &remote_contexts));
+				if let Some(processed) =
+					cache.and_then(|cache| cache.get(&context_iri, &result, options))
+				{
+					remote_contexts.push(context_iri);
+					result = processed;
+				} else if remote_contexts.push(context_iri.clone()) {
 					let loaded_context = env
 						.loader
 						.load_with(env.vocabulary, context_iri.clone())
-						.await?
+						.await
+						.map_err(|e| remote_context_error(env.vocabulary, &context_iri, e))?
 						.into_document()
 						.into_ld_context()
-						.map_err(Error::ContextExtractionFailed)?;
+						.map_err(|e| {
+							remote_context_error(
+								env.vocabulary,
+								&context_iri,
+								Error::ContextExtractionFailed(e),
+							)
+						})?;
 
 					// Set result to the result of recursively calling this algorithm, passing result
 					// for active context, loaded context for local context, the documentUrl of context
@@ -182,8 +320,13 @@ where
 						override_protected: false,
 						propagate: true,
 						vocab: options.vocab,
+						reject_recursive_contexts: options.reject_recursive_contexts,
+						lazy_scoped_contexts: options.lazy_scoped_contexts,
 					};
 
+					let mut nested_remote_contexts = ancestors.clone();
+					nested_remote_contexts.push(context_iri.clone());
+
 					let r = Box::pin(process_context(
 						Environment {
 							vocabulary: env.vocabulary,
@@ -192,20 +335,33 @@ where
 						},
 						&result,
 						&loaded_context,
-						remote_contexts.clone(),
-						Some(context_iri),
+						nested_remote_contexts,
+						Some(context_iri.clone()),
 						new_options,
+						cache,
 					))
-					.await?;
+					.await
+					.map_err(|e| remote_context_error(env.vocabulary, &context_iri, e))?;
+
+					let processed = r.into_processed();
+					if let Some(cache) = cache {
+						cache.insert(context_iri, &result, options, &processed)
+					}
 
-					result = r.into_processed();
+					result = processed;
 				}
 			}
 
 			// 5.4) Context definition.
 			syntax::ContextEntry::Definition(context) => {
 				// 5.5) If context has a @version entry:
-				if context.version.is_some() {
+				if let Some(version) = &context.version {
+					// 5.5.1) If the associated value is not 1.1, an invalid @version value has
+					// been detected.
+					if *version != syntax::context::definition::Version::V1_1 {
+						return Err(Error::InvalidVersionValue(version.clone()));
+					}
+
 					// 5.5.2) If processing mode is set to json-ld-1.0, a processing mode conflict
 					// error has been detected.
 					if options.processing_mode == ProcessingMode::JsonLd1_0 {
@@ -235,10 +391,17 @@ where
 						let import_context = env
 							.loader
 							.load_with(env.vocabulary, import.clone())
-							.await?
+							.await
+							.map_err(|e| remote_context_error(env.vocabulary, &import, e))?
 							.into_document()
 							.into_ld_context()
-							.map_err(Error::ContextExtractionFailed)?;
+							.map_err(|e| {
+								remote_context_error(
+									env.vocabulary,
+									&import,
+									Error::ContextExtractionFailed(e),
+								)
+							})?;
 
 						// If the dereferenced document has no top-level map with an @context
 						// entry, or if the value of @context is not a context definition
@@ -252,11 +415,19 @@ where
 								// If `import_context` has a @import entry, an invalid context entry
 								// error has been detected and processing is aborted.
 								if import_context_def.import.is_some() {
-									return Err(Error::InvalidContextEntry);
+									return Err(remote_context_error(
+										env.vocabulary,
+										&import,
+										Error::InvalidContextEntry,
+									));
 								}
 							}
 							_ => {
-								return Err(Error::InvalidRemoteContext);
+								return Err(remote_context_error(
+									env.vocabulary,
+									&import,
+									Error::InvalidRemoteContext,
+								));
 							}
 						}
 
@@ -412,3 +583,189 @@ where
 
 	Ok(Processed::new(local_context, result))
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use iref::IriBuf;
+	use json_ld_core::RemoteDocument;
+	use json_ld_syntax::ErrorCode;
+	use rdf_types::BlankIdBuf;
+	use std::collections::HashMap;
+
+	/// Builds a loader serving the given documents.
+	fn loader<const N: usize>(
+		documents: [(&IriBuf, json_syntax::Value); N],
+	) -> HashMap<IriBuf, RemoteDocument> {
+		documents
+			.into_iter()
+			.map(|(url, document)| {
+				(
+					url.clone(),
+					RemoteDocument::new(Some(url.clone()), None, document),
+				)
+			})
+			.collect()
+	}
+
+	/// Processes `context` on top of an empty active context.
+	fn process<'l>(
+		context: &'l syntax::context::Context,
+		loader: &HashMap<IriBuf, RemoteDocument>,
+		options: Options,
+	) -> Result<Processed<'l, IriBuf, BlankIdBuf>, Error> {
+		futures::executor::block_on(context.process_full(
+			&mut (),
+			&Context::default(),
+			loader,
+			None,
+			options,
+			(),
+		))
+	}
+
+	#[test]
+	fn remote_context_chain() {
+		let a = IriBuf::new("https://example.com/a.jsonld".to_owned()).unwrap();
+		let b = IriBuf::new("https://example.com/b.jsonld".to_owned()).unwrap();
+		let c = IriBuf::new("https://example.com/c.jsonld".to_owned()).unwrap();
+
+		let loader = loader([
+			(&a, json_syntax::json!({ "@context": "b.jsonld" })),
+			(
+				&b,
+				json_syntax::json!({ "@context": { "@version": 1.1, "@import": "c.jsonld" } }),
+			),
+		]);
+
+		let context = syntax::context::Context::from(a.clone());
+		let error = process(&context, &loader, Options::default())
+			.err()
+			.unwrap();
+
+		assert_eq!(error.remote_context_chain(), [a, b, c]);
+		assert!(matches!(error.inner(), Error::ContextLoadingFailed(_)));
+		assert_eq!(error.code(), ErrorCode::LoadingRemoteContextFailed)
+	}
+
+	#[test]
+	fn recursive_context_inclusion() {
+		let a = IriBuf::new("https://example.com/a.jsonld".to_owned()).unwrap();
+		let b = IriBuf::new("https://example.com/b.jsonld".to_owned()).unwrap();
+
+		let loader = loader([
+			(
+				&a,
+				json_syntax::json!({ "@context": [ "b.jsonld", { "name": "https://schema.org/name" } ] }),
+			),
+			(&b, json_syntax::json!({ "@context": "a.jsonld" })),
+		]);
+
+		let context = syntax::context::Context::from(a.clone());
+
+		// By default, the recursive inclusion is ignored.
+		let processed = process(&context, &loader, Options::default()).ok().unwrap();
+		assert_eq!(processed.len(), 1);
+
+		let options = Options::default().with_reject_recursive_contexts(true);
+		let error = process(&context, &loader, options).err().unwrap();
+		assert_eq!(error.code(), ErrorCode::RecursiveContextInclusion);
+		match error.inner() {
+			Error::RecursiveContextInclusion(cycle) => {
+				assert_eq!(*cycle, [a.clone(), b, a])
+			}
+			_ => panic!("unexpected error"),
+		}
+	}
+
+	#[test]
+	fn shared_scoped_contexts() {
+		use json_ld_syntax::TryFromJson;
+		use std::sync::Arc;
+
+		let scoped = json_syntax::json!({ "name": "https://schema.org/name" });
+		let context = syntax::context::Context::try_from_json(json_syntax::json!({
+			"a": { "@id": "https://example.com/a", "@context": scoped.clone() },
+			"b": { "@id": "https://example.com/b", "@context": scoped },
+			"c": { "@id": "https://example.com/c", "@context": { "name": "https://example.com/name" } }
+		}))
+		.unwrap();
+
+		let loader = loader([]);
+		let mut processed = process(&context, &loader, Options::default())
+			.ok()
+			.unwrap()
+			.into_processed();
+
+		let process_scoped = |active_context: &Context, term: &str| {
+			let definition = active_context.get_normal(term).unwrap();
+			futures::executor::block_on(process_scoped_context(
+				&mut (),
+				active_context,
+				definition.context.as_ref().unwrap(),
+				&loader,
+				None,
+				Options::default().with_override(),
+			))
+			.ok()
+			.unwrap()
+		};
+
+		let a = process_scoped(&processed, "a");
+		assert!(a.get_normal("name").is_some());
+		assert!(Arc::ptr_eq(&a, &process_scoped(&processed, "b")));
+		assert!(!Arc::ptr_eq(&a, &process_scoped(&processed, "c")));
+
+		// Processed contexts depend on the active context.
+		assert!(!Arc::ptr_eq(&a, &process_scoped(&processed.clone(), "b")));
+		processed.set_default_language(None);
+		assert!(!Arc::ptr_eq(&a, &process_scoped(&processed, "b")))
+	}
+
+	#[test]
+	fn lazy_scoped_contexts() {
+		use json_ld_syntax::TryFromJson;
+
+		let context = syntax::context::Context::try_from_json(json_syntax::json!({
+			"a": { "@id": "https://example.com/a", "@context": { "x": "@context" } },
+			"b": { "@id": "https://example.com/b", "@context": { "name": "https://schema.org/name" } }
+		}))
+		.unwrap();
+
+		let loader = loader([]);
+		let error = process(&context, &loader, Options::default())
+			.err()
+			.unwrap();
+		assert!(matches!(error, Error::InvalidScopedContext));
+
+		let options = Options::default().with_lazy_scoped_contexts(true);
+		let processed = process(&context, &loader, options).ok().unwrap();
+
+		let prewarm = |terms: &[&str]| {
+			futures::executor::block_on(process_scoped_contexts(
+				&mut (),
+				&processed,
+				terms.iter().copied(),
+				&loader,
+				options,
+			))
+		};
+
+		assert!(prewarm(&["b", "unknown"]).is_ok());
+
+		// The processed scoped context is kept for the expansion.
+		let b = processed.get_normal("b").unwrap();
+		assert!(processed
+			.scoped_context(
+				b.context.as_deref().unwrap(),
+				b.base_url(),
+				&options.with_override()
+			)
+			.is_some());
+
+		assert!(matches!(
+			prewarm(&["a", "b"]),
+			Err(Error::InvalidScopedContext)
+		))
+	}
+}
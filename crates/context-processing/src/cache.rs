@@ -0,0 +1,281 @@
+use crate::{algorithm, Error, Options, Processed, ProcessingStack, WarningHandler};
+use iref::IriBuf;
+use json_ld_core::{Context, Environment, Loader};
+use rdf_types::{BlankIdBuf, VocabularyMut};
+use std::{
+	collections::HashMap,
+	hash::Hash,
+	sync::{
+		atomic::{AtomicU64, Ordering},
+		Arc, RwLock, RwLockReadGuard, RwLockWriteGuard,
+	},
+};
+
+/// Thread-safe cache of processed remote contexts.
+///
+/// This cache can be cheaply cloned and shared between threads (for instance
+/// between the request handlers of a web service), so that concurrent context
+/// processing tasks reuse the remote contexts already processed by others.
+///
+/// A remote context is cached when it is processed on top of an active
+/// context without term definitions nor previous context, outside of any
+/// other remote context. This covers the common case of documents whose
+/// `@context` references well-known remote contexts. Warnings emitted while
+/// processing a remote context are not replayed on cache hits.
+pub struct SharedContextCache<T = IriBuf, B = BlankIdBuf> {
+	inner: Arc<Inner<T, B>>,
+}
+
+struct Inner<T, B> {
+	entries: RwLock<HashMap<T, Vec<Entry<T, B>>>>,
+	hits: AtomicU64,
+	misses: AtomicU64,
+}
+
+struct Entry<T, B> {
+	options: Options,
+	active_context: Context<T, B>,
+	processed: Context<T, B>,
+}
+
+/// Cache statistics.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct CacheStats {
+	/// Number of lookups that found a processed context.
+	pub hits: u64,
+
+	/// Number of lookups that did not find a processed context.
+	pub misses: u64,
+
+	/// Number of processed contexts currently stored.
+	pub entries: usize,
+}
+
+impl CacheStats {
+	/// Returns the ratio of lookups that found a processed context, or `0.0`
+	/// if there was no lookup.
+	pub fn hit_ratio(&self) -> f64 {
+		let lookups = self.hits + self.misses;
+		if lookups == 0 {
+			0.0
+		} else {
+			self.hits as f64 / lookups as f64
+		}
+	}
+}
+
+impl<T, B> SharedContextCache<T, B> {
+	/// Creates a new empty cache.
+	pub fn new() -> Self {
+		Self {
+			inner: Arc::new(Inner {
+				entries: RwLock::new(HashMap::new()),
+				hits: AtomicU64::new(0),
+				misses: AtomicU64::new(0),
+			}),
+		}
+	}
+
+	/// Returns the current cache statistics.
+	pub fn stats(&self) -> CacheStats {
+		CacheStats {
+			hits: self.inner.hits.load(Ordering::Relaxed),
+			misses: self.inner.misses.load(Ordering::Relaxed),
+			entries: self.len(),
+		}
+	}
+
+	/// Returns the number of processed contexts stored in the cache.
+	pub fn len(&self) -> usize {
+		self.read().values().map(Vec::len).sum()
+	}
+
+	/// Checks if the cache is empty.
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	/// Removes every processed context from the cache and resets the
+	/// statistics.
+	pub fn clear(&self) {
+		self.write().clear();
+		self.inner.hits.store(0, Ordering::Relaxed);
+		self.inner.misses.store(0, Ordering::Relaxed);
+	}
+
+	fn read(&self) -> RwLockReadGuard<'_, HashMap<T, Vec<Entry<T, B>>>> {
+		// A panic while holding the lock cannot leave the map in an
+		// inconsistent state, so poisoning is ignored.
+		self.inner.entries.read().unwrap_or_else(|e| e.into_inner())
+	}
+
+	fn write(&self) -> RwLockWriteGuard<'_, HashMap<T, Vec<Entry<T, B>>>> {
+		self.inner
+			.entries
+			.write()
+			.unwrap_or_else(|e| e.into_inner())
+	}
+
+	/// Returns the result of processing the remote context `url` on top of
+	/// `active_context` with the given `options`, if any.
+	pub(crate) fn get(
+		&self,
+		url: &T,
+		active_context: &Context<T, B>,
+		options: Options,
+	) -> Option<Context<T, B>>
+	where
+		T: Clone + Eq + Hash,
+		B: Clone + PartialEq,
+	{
+		let result = self.read().get(url).and_then(|entries| {
+			entries
+				.iter()
+				.find(|e| e.options == options && e.active_context == *active_context)
+				.map(|e| e.processed.clone())
+		});
+
+		let counter = if result.is_some() {
+			&self.inner.hits
+		} else {
+			&self.inner.misses
+		};
+		counter.fetch_add(1, Ordering::Relaxed);
+
+		result
+	}
+
+	/// Stores the result of processing the remote context `url` on top of
+	/// `active_context` with the given `options`.
+	pub(crate) fn insert(
+		&self,
+		url: T,
+		active_context: &Context<T, B>,
+		options: Options,
+		processed: &Context<T, B>,
+	) where
+		T: Clone + Eq + Hash,
+		B: Clone + PartialEq,
+	{
+		let mut entries = self.write();
+		let entries = entries.entry(url).or_default();
+		if !entries
+			.iter()
+			.any(|e| e.options == options && e.active_context == *active_context)
+		{
+			entries.push(Entry {
+				options,
+				active_context: active_context.clone(),
+				processed: processed.clone(),
+			})
+		}
+	}
+
+	/// Processes the given local context using this cache.
+	///
+	/// This is equivalent to
+	/// [`Process::process_full`](crate::Process::process_full), except that
+	/// remote contexts are looked up in (and added to) this cache.
+	#[allow(clippy::too_many_arguments)]
+	pub async fn process_full<'l, N, L, W>(
+		&self,
+		context: &'l json_ld_syntax::context::Context,
+		vocabulary: &mut N,
+		active_context: &Context<T, B>,
+		loader: &L,
+		base_url: Option<T>,
+		options: Options,
+		mut warnings: W,
+	) -> Result<Processed<'l, T, B>, Error>
+	where
+		N: VocabularyMut<Iri = T, BlankId = B>,
+		T: Clone + Eq + Hash,
+		B: Clone + PartialEq,
+		L: Loader,
+		W: WarningHandler<N>,
+	{
+		algorithm::process_context(
+			Environment {
+				vocabulary,
+				loader,
+				warnings: &mut warnings,
+			},
+			active_context,
+			context,
+			ProcessingStack::default(),
+			base_url,
+			options,
+			Some(self),
+		)
+		.await
+	}
+}
+
+/// Checks if the result of processing a remote context on top of
+/// `active_context` can be cached.
+pub(crate) fn is_cacheable<T, B, I>(
+	active_context: &Context<T, B>,
+	remote_contexts: &ProcessingStack<I>,
+) -> bool {
+	remote_contexts.is_empty()
+		&& active_context.is_empty()
+		&& active_context.previous_context().is_none()
+}
+
+impl<T, B> Clone for SharedContextCache<T, B> {
+	fn clone(&self) -> Self {
+		Self {
+			inner: self.inner.clone(),
+		}
+	}
+}
+
+impl<T, B> Default for SharedContextCache<T, B> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use json_ld_core::RemoteDocument;
+	use std::collections::HashMap;
+
+	#[test]
+	fn shared_remote_context() {
+		let url = IriBuf::new("https://example.com/context.jsonld".to_owned()).unwrap();
+		let mut loader = HashMap::new();
+		loader.insert(
+			url.clone(),
+			RemoteDocument::new(
+				Some(url.clone()),
+				None,
+				json_syntax::json!({ "@context": { "name": "https://schema.org/name" } }),
+			),
+		);
+
+		let context = json_ld_syntax::context::Context::from(url);
+		let cache = SharedContextCache::new();
+
+		for _ in 0..2 {
+			let processed = futures::executor::block_on(cache.clone().process_full(
+				&context,
+				&mut (),
+				&Context::default(),
+				&loader,
+				None,
+				Options::default(),
+				(),
+			))
+			.ok()
+			.unwrap();
+			assert_eq!(processed.len(), 1)
+		}
+
+		let stats = cache.stats();
+		assert_eq!(stats.hits, 1);
+		assert_eq!(stats.misses, 1);
+		assert_eq!(stats.entries, 1)
+	}
+}
@@ -1,5 +1,6 @@
 //! JSON-LD context processing types and algorithms.
 use algorithm::{Action, RejectVocab};
+use iref::IriBuf;
 pub use json_ld_core::{warning, Context, ProcessingMode};
 use json_ld_core::{ExtractContextError, LoadError, Loader};
 use json_ld_syntax::ErrorCode;
@@ -7,9 +8,11 @@ use rdf_types::VocabularyMut;
 use std::{fmt, hash::Hash};
 
 pub mod algorithm;
+mod cache;
 mod processed;
 mod stack;
 
+pub use cache::{CacheStats, SharedContextCache};
 pub use processed::*;
 pub use stack::ProcessingStack;
 
@@ -52,6 +55,9 @@ pub enum Error {
 	#[error("Processing mode conflict")]
 	ProcessingModeConflict,
 
+	#[error("Invalid `@version` value `{0}`")]
+	InvalidVersionValue(json_ld_syntax::context::definition::Version),
+
 	#[error("Invalid `@context` entry")]
 	InvalidContextEntry,
 
@@ -108,6 +114,30 @@ pub enum Error {
 
 	#[error("Use of forbidden `@vocab`")]
 	ForbiddenVocab,
+
+	/// A remote context includes itself, directly or through other remote
+	/// contexts.
+	///
+	/// Holds the IRIs of the remote contexts forming the cycle, starting and
+	/// ending with the same IRI.
+	#[error("Recursive context inclusion: {}", format_chain(.0))]
+	RecursiveContextInclusion(Vec<IriBuf>),
+
+	/// Error raised while dereferencing or processing a remote context.
+	///
+	/// The chain lists the IRIs of the remote contexts (loaded from a context
+	/// IRI or with `@import`) being dereferenced when the error occurred, from
+	/// the outermost one to the one that failed.
+	#[error("{error} (remote context chain: {})", format_chain(.chain))]
+	RemoteContext {
+		chain: Vec<IriBuf>,
+		error: Box<Error>,
+	},
+}
+
+fn format_chain(chain: &[IriBuf]) -> String {
+	let iris: Vec<_> = chain.iter().map(|iri| format!("`{iri}`")).collect();
+	iris.join(" -> ")
 }
 
 impl From<RejectVocab> for Error {
@@ -117,11 +147,46 @@ impl From<RejectVocab> for Error {
 }
 
 impl Error {
+	/// Adds the given remote context IRI at the front of the remote context
+	/// chain of this error.
+	pub(crate) fn in_remote_context(self, iri: IriBuf) -> Self {
+		match self {
+			Self::RemoteContext { mut chain, error } => {
+				chain.insert(0, iri);
+				Self::RemoteContext { chain, error }
+			}
+			error => Self::RemoteContext {
+				chain: vec![iri],
+				error: Box::new(error),
+			},
+		}
+	}
+
+	/// Returns the chain of remote contexts being dereferenced when this
+	/// error occurred, from the outermost one to the one that failed.
+	///
+	/// The chain is empty if the error did not occur in a remote context.
+	pub fn remote_context_chain(&self) -> &[IriBuf] {
+		match self {
+			Self::RemoteContext { chain, .. } => chain,
+			_ => &[],
+		}
+	}
+
+	/// Returns the underlying error, without the remote context chain.
+	pub fn inner(&self) -> &Self {
+		match self {
+			Self::RemoteContext { error, .. } => error,
+			error => error,
+		}
+	}
+
 	pub fn code(&self) -> ErrorCode {
 		match self {
 			Self::InvalidContextNullification => ErrorCode::InvalidContextNullification,
 			Self::LoadingDocumentFailed => ErrorCode::LoadingDocumentFailed,
 			Self::ProcessingModeConflict => ErrorCode::ProcessingModeConflict,
+			Self::InvalidVersionValue(_) => ErrorCode::InvalidVersionValue,
 			Self::InvalidContextEntry => ErrorCode::InvalidContextEntry,
 			Self::InvalidImportValue => ErrorCode::InvalidImportValue,
 			Self::InvalidRemoteContext => ErrorCode::InvalidRemoteContext,
@@ -141,10 +206,15 @@ impl Error {
 			Self::ContextLoadingFailed(_) => ErrorCode::LoadingRemoteContextFailed,
 			Self::ContextExtractionFailed(_) => ErrorCode::LoadingRemoteContextFailed,
 			Self::ForbiddenVocab => ErrorCode::InvalidVocabMapping,
+			Self::RecursiveContextInclusion(_) => ErrorCode::RecursiveContextInclusion,
+			Self::RemoteContext { error, .. } => error.code(),
 		}
 	}
 }
 
+#[cfg(feature = "miette")]
+json_ld_syntax::impl_diagnostic!(Error);
+
 /// Result of context processing functions.
 pub type ProcessingResult<'a, T, B> = Result<Processed<'a, T, B>, Error>;
 
@@ -224,7 +294,7 @@ pub trait Process {
 }
 
 /// Options of the Context Processing Algorithm.
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Options {
 	/// The processing mode
 	pub processing_mode: ProcessingMode,
@@ -237,6 +307,16 @@ pub struct Options {
 
 	/// Forbid the use of `@vocab` to expand terms.
 	pub vocab: Action,
+
+	/// Reject recursive context inclusions.
+	///
+	/// See [`Self::reject_recursive_contexts`].
+	reject_recursive_contexts: bool,
+
+	/// Process scoped contexts on first use.
+	///
+	/// See [`Self::lazy_scoped_contexts`].
+	lazy_scoped_contexts: bool,
 }
 
 impl Options {
@@ -263,6 +343,52 @@ impl Options {
 		opt.propagate = false;
 		opt
 	}
+
+	/// Checks if recursive context inclusions are rejected.
+	///
+	/// If `true`, a remote context that is already being processed
+	/// (recursive context inclusion) raises a
+	/// [`Error::RecursiveContextInclusion`] error. Otherwise it is ignored.
+	///
+	/// Defaults to `false`.
+	pub fn reject_recursive_contexts(&self) -> bool {
+		self.reject_recursive_contexts
+	}
+
+	/// Return the same set of options, but with `reject_recursive_contexts`
+	/// set to the given value.
+	#[must_use]
+	pub fn with_reject_recursive_contexts(&self, value: bool) -> Options {
+		let mut opt = *self;
+		opt.reject_recursive_contexts = value;
+		opt
+	}
+
+	/// Checks if scoped contexts are processed lazily.
+	///
+	/// If `true`, the scoped contexts of term definitions are not processed
+	/// when the term is defined, but only when the term is used during
+	/// expansion.
+	///
+	/// This lowers the cost of processing large contexts defining many
+	/// scoped contexts that documents rarely use, but invalid scoped contexts
+	/// are only reported on first use (or never, if unused). Scoped contexts
+	/// can still be processed ahead of time with
+	/// [`algorithm::process_scoped_contexts`].
+	///
+	/// Defaults to `false`.
+	pub fn lazy_scoped_contexts(&self) -> bool {
+		self.lazy_scoped_contexts
+	}
+
+	/// Return the same set of options, but with `lazy_scoped_contexts` set to
+	/// the given value.
+	#[must_use]
+	pub fn with_lazy_scoped_contexts(&self, value: bool) -> Options {
+		let mut opt = *self;
+		opt.lazy_scoped_contexts = value;
+		opt
+	}
 }
 
 impl Default for Options {
@@ -272,6 +398,8 @@ impl Default for Options {
 			override_protected: false,
 			propagate: true,
 			vocab: Action::Keep,
+			reject_recursive_contexts: false,
+			lazy_scoped_contexts: false,
 		}
 	}
 }
@@ -63,6 +63,30 @@ impl<I> ProcessingStack<I> {
 		}
 	}
 
+	/// Returns the cycle formed by pushing the given URL, if it is already in
+	/// the stack.
+	///
+	/// The cycle starts with the first occurrence of the URL, lists the URLs
+	/// pushed after it, and ends with the URL again.
+	pub fn cycle_path(&self, url: &I) -> Option<Vec<I>>
+	where
+		I: Clone + PartialEq,
+	{
+		let mut path = vec![url.clone()];
+		let mut node = self.head.as_deref();
+		while let Some(n) = node {
+			path.push(n.url.clone());
+			if n.url == *url {
+				path.reverse();
+				return Some(path);
+			}
+
+			node = n.previous.as_deref();
+		}
+
+		None
+	}
+
 	/// Push a new URL to the stack, unless it is already in the stack.
 	///
 	/// Returns `true` if the URL was successfully added or
@@ -0,0 +1,154 @@
+//! Generates the schema.org vocabulary of the `schema` module, when the
+//! `schema-org` feature is enabled.
+use std::{env, fmt::Write, fs, path::Path};
+
+const NAMESPACE: &str = "https://schema.org/";
+
+fn main() {
+	println!("cargo:rerun-if-changed=build.rs");
+	println!("cargo:rerun-if-changed=schema.org");
+
+	if env::var_os("CARGO_FEATURE_SCHEMA_ORG").is_none() {
+		return;
+	}
+
+	let types = read_terms("schema.org/types.txt");
+	let properties = read_terms("schema.org/properties.txt");
+
+	let mut out = String::new();
+
+	for property in &properties {
+		write_constant(&mut out, property);
+	}
+
+	writeln!(out, "/// schema.org type IRIs.").unwrap();
+	writeln!(out, "pub mod types {{").unwrap();
+	writeln!(out, "use iref::Iri;").unwrap();
+	for ty in &types {
+		write_constant(&mut out, ty);
+	}
+	writeln!(out, "}}").unwrap();
+
+	write_enum(&mut out, "Type", "schema.org type.", "types::", &types);
+	write_enum(
+		&mut out,
+		"Property",
+		"schema.org property.",
+		"",
+		&properties,
+	);
+
+	let path = Path::new(&env::var("OUT_DIR").unwrap()).join("schema.rs");
+	fs::write(path, out).unwrap();
+}
+
+/// Reads a list of terms, one per line, ignoring empty lines and `#`
+/// comments.
+fn read_terms(path: &str) -> Vec<String> {
+	fs::read_to_string(path)
+		.unwrap()
+		.lines()
+		.map(str::trim)
+		.filter(|line| !line.is_empty() && !line.starts_with('#'))
+		.map(ToOwned::to_owned)
+		.collect()
+}
+
+/// Converts a camel case term into a screaming snake case constant name.
+fn constant_name(term: &str) -> String {
+	let chars: Vec<char> = term.chars().collect();
+	let mut result = String::new();
+
+	for (i, c) in chars.iter().enumerate() {
+		if i > 0 && c.is_ascii_uppercase() {
+			let prev = chars[i - 1];
+			let next_lowercase = chars.get(i + 1).is_some_and(char::is_ascii_lowercase);
+			if !prev.is_ascii_uppercase() || next_lowercase {
+				result.push('_')
+			}
+		}
+
+		result.push(c.to_ascii_uppercase())
+	}
+
+	result
+}
+
+/// Converts a camel case term into a Pascal case variant name.
+fn variant_name(term: &str) -> String {
+	let mut chars = term.chars();
+	let first = chars.next().unwrap().to_ascii_uppercase();
+	std::iter::once(first).chain(chars).collect()
+}
+
+fn write_constant(out: &mut String, term: &str) {
+	writeln!(out, "/// `{NAMESPACE}{term}`.").unwrap();
+	writeln!(
+		out,
+		"pub const {}: &Iri = static_iref::iri!(\"{NAMESPACE}{term}\");",
+		constant_name(term)
+	)
+	.unwrap();
+}
+
+fn write_enum(out: &mut String, name: &str, doc: &str, prefix: &str, terms: &[String]) {
+	writeln!(out, "/// {doc}").unwrap();
+	writeln!(out, "#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]").unwrap();
+	writeln!(out, "#[allow(clippy::upper_case_acronyms)]").unwrap();
+	writeln!(out, "pub enum {name} {{").unwrap();
+	for term in terms {
+		writeln!(out, "/// `{NAMESPACE}{term}`.").unwrap();
+		writeln!(out, "{},", variant_name(term)).unwrap();
+	}
+	writeln!(out, "}}").unwrap();
+
+	writeln!(out, "impl {name} {{").unwrap();
+
+	writeln!(out, "/// Every {}.", name.to_lowercase()).unwrap();
+	writeln!(out, "pub const ALL: &'static [Self] = &[").unwrap();
+	for term in terms {
+		writeln!(out, "Self::{},", variant_name(term)).unwrap();
+	}
+	writeln!(out, "];").unwrap();
+
+	writeln!(out, "/// Returns the schema.org term.").unwrap();
+	writeln!(out, "pub fn name(&self) -> &'static str {{").unwrap();
+	writeln!(out, "match self {{").unwrap();
+	for term in terms {
+		writeln!(out, "Self::{} => \"{term}\",", variant_name(term)).unwrap();
+	}
+	writeln!(out, "}}").unwrap();
+	writeln!(out, "}}").unwrap();
+
+	writeln!(out, "/// Returns the IRI of the term.").unwrap();
+	writeln!(out, "pub fn iri(&self) -> &'static Iri {{").unwrap();
+	writeln!(out, "match self {{").unwrap();
+	for term in terms {
+		writeln!(
+			out,
+			"Self::{} => {prefix}{},",
+			variant_name(term),
+			constant_name(term)
+		)
+		.unwrap();
+	}
+	writeln!(out, "}}").unwrap();
+	writeln!(out, "}}").unwrap();
+
+	writeln!(
+		out,
+		"/// Returns the {} with the given schema.org term, if any.",
+		name.to_lowercase()
+	)
+	.unwrap();
+	writeln!(out, "pub fn from_name(name: &str) -> Option<Self> {{").unwrap();
+	writeln!(out, "match name {{").unwrap();
+	for term in terms {
+		writeln!(out, "\"{term}\" => Some(Self::{}),", variant_name(term)).unwrap();
+	}
+	writeln!(out, "_ => None,").unwrap();
+	writeln!(out, "}}").unwrap();
+	writeln!(out, "}}").unwrap();
+
+	writeln!(out, "}}").unwrap();
+}
@@ -0,0 +1,121 @@
+use arbitrary::{Arbitrary, Unstructured};
+use json_ld_syntax::{context::InvalidContext, TryFromJson};
+use json_syntax::{Object, Print, Value};
+use std::fmt;
+
+use super::{CONTAINERS, IRIS, LANGUAGES, TERMS, TERM_IRI_PREFIX, TYPE_MAPPINGS, VOCABS};
+
+/// Random local context.
+///
+/// The context is a single context definition, possibly setting `@vocab` and
+/// `@language`, and defining a random subset of a fixed list of terms with
+/// simple or expanded term definitions.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Context(Value);
+
+impl Context {
+	/// Returns the JSON representation of this context.
+	pub fn as_json(&self) -> &Value {
+		&self.0
+	}
+
+	/// Turns this context into its JSON representation.
+	pub fn into_json(self) -> Value {
+		self.0
+	}
+
+	/// Parses this context.
+	pub fn to_syntax(&self) -> Result<json_ld_syntax::context::Context, InvalidContext> {
+		json_ld_syntax::context::Context::try_from_json(self.0.clone())
+	}
+}
+
+impl Default for Context {
+	/// Creates an empty context.
+	fn default() -> Self {
+		Self(Value::Object(Object::new()))
+	}
+}
+
+impl fmt::Debug for Context {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		fmt::Display::fmt(&self.0.compact_print(), f)
+	}
+}
+
+impl<'a> Arbitrary<'a> for Context {
+	fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+		let mut object = Object::new();
+
+		if u.ratio(1, 2)? {
+			object.insert("@vocab".into(), (*u.choose(VOCABS)?).into());
+		}
+
+		if u.ratio(1, 4)? {
+			object.insert("@language".into(), (*u.choose(LANGUAGES)?).into());
+		}
+
+		if u.ratio(1, 8)? {
+			object.insert("@protected".into(), true.into());
+		}
+
+		for term in TERMS {
+			if u.arbitrary()? {
+				object.insert((*term).into(), term_definition(u, term)?);
+			}
+		}
+
+		Ok(Self(Value::Object(object)))
+	}
+}
+
+fn term_definition(u: &mut Unstructured, term: &str) -> arbitrary::Result<Value> {
+	let id: Value = (*u.choose(IRIS)?).into();
+
+	if u.arbitrary()? {
+		return Ok(id);
+	}
+
+	let mut object = Object::new();
+	object.insert("@id".into(), id);
+
+	let type_mapping = if u.ratio(1, 3)? {
+		Some(*u.choose(TYPE_MAPPINGS)?)
+	} else {
+		None
+	};
+
+	if let Some(type_mapping) = type_mapping {
+		object.insert("@type".into(), type_mapping.into());
+	}
+
+	let container = if type_mapping != Some("@json") && u.ratio(1, 3)? {
+		Some(*u.choose(CONTAINERS)?)
+	} else {
+		None
+	};
+
+	if let Some(container) = container {
+		object.insert("@container".into(), container.into());
+	}
+
+	// A property can only be compacted into a single JSON literal (an array
+	// is read back as a single literal) or a single list. Terms holding such
+	// values get their own IRI so that no other term can add values to the
+	// same property. For the same reason `@json` terms have no container.
+	if type_mapping == Some("@json") || container == Some("@list") {
+		object.insert("@id".into(), format!("{TERM_IRI_PREFIX}{term}").into());
+	}
+
+	if u.ratio(1, 4)? {
+		let language = if u.arbitrary()? {
+			(*u.choose(LANGUAGES)?).into()
+		} else {
+			Value::Null
+		};
+
+		object.insert("@language".into(), language);
+	}
+
+	Ok(Value::Object(object))
+}
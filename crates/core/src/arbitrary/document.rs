@@ -0,0 +1,170 @@
+use arbitrary::{Arbitrary, Unstructured};
+use json_syntax::{Array, Object, Print, Value};
+use std::fmt;
+
+use super::{Context, IDS, LANGUAGES, MAX_DEPTH, NUMBERS, PROPERTIES, STRINGS, TYPES};
+
+/// Random JSON-LD document.
+///
+/// The document body is a node object or an array of node objects, with an
+/// optional embedded [`Context`]. Nodes may have an `@id`, an `@type`,
+/// properties whose values are literals, value objects, lists, sets,
+/// references or nested nodes, and `@graph` or `@included` entries.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Document {
+	/// Embedded context.
+	pub context: Option<Context>,
+
+	/// Document body, without the `@context` entry.
+	pub body: Value,
+}
+
+impl Document {
+	/// Returns the JSON representation of this document.
+	///
+	/// If the body is an array, the context is embedded by wrapping it into
+	/// a `@graph` entry.
+	pub fn to_json(&self) -> Value {
+		match (&self.context, &self.body) {
+			(None, body) => body.clone(),
+			(Some(context), Value::Object(body)) => {
+				let mut object = body.clone();
+				object.insert_front("@context".into(), context.as_json().clone());
+				Value::Object(object)
+			}
+			(Some(context), body) => {
+				let mut object = Object::new();
+				object.insert("@context".into(), context.as_json().clone());
+				object.insert("@graph".into(), body.clone());
+				Value::Object(object)
+			}
+		}
+	}
+
+	/// Returns the context used to compact this document, which is the
+	/// embedded context, or an empty context.
+	pub fn compaction_context(&self) -> Context {
+		self.context.clone().unwrap_or_default()
+	}
+}
+
+impl fmt::Debug for Document {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		fmt::Display::fmt(&self.to_json().compact_print(), f)
+	}
+}
+
+impl<'a> Arbitrary<'a> for Document {
+	fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+		let context = if u.ratio(3, 4)? {
+			Some(Context::arbitrary(u)?)
+		} else {
+			None
+		};
+
+		let body = if u.ratio(1, 4)? {
+			nodes(u, MAX_DEPTH)?
+		} else {
+			node(u, MAX_DEPTH)?
+		};
+
+		Ok(Self { context, body })
+	}
+}
+
+fn string(u: &mut Unstructured, items: &[&str]) -> arbitrary::Result<Value> {
+	Ok((*u.choose(items)?).into())
+}
+
+fn nodes(u: &mut Unstructured, depth: usize) -> arbitrary::Result<Value> {
+	let len = u.int_in_range(0..=2)?;
+	let mut array = Array::with_capacity(len);
+	for _ in 0..len {
+		array.push(node(u, depth)?)
+	}
+
+	Ok(Value::Array(array))
+}
+
+fn node(u: &mut Unstructured, depth: usize) -> arbitrary::Result<Value> {
+	let mut object = Object::new();
+
+	if u.arbitrary()? {
+		object.insert("@id".into(), string(u, IDS)?);
+	}
+
+	if u.ratio(1, 3)? {
+		object.insert("@type".into(), string(u, TYPES)?);
+	}
+
+	for _ in 0..u.int_in_range(0..=3)? {
+		let property = *u.choose(PROPERTIES)?;
+		let value = value(u, depth, false)?;
+		object.insert(property.into(), value);
+	}
+
+	if depth > 0 {
+		if u.ratio(1, 8)? {
+			object.insert("@graph".into(), nodes(u, depth - 1)?);
+		}
+
+		if u.ratio(1, 10)? {
+			object.insert("@included".into(), nodes(u, depth - 1)?);
+		}
+	}
+
+	Ok(Value::Object(object))
+}
+
+fn values(u: &mut Unstructured, depth: usize, in_list: bool) -> arbitrary::Result<Value> {
+	let len = u.int_in_range(0..=2)?;
+	let mut array = Array::with_capacity(len);
+	for _ in 0..len {
+		array.push(value(u, depth, in_list)?)
+	}
+
+	Ok(Value::Array(array))
+}
+
+/// Generates a property value.
+///
+/// Lists of lists are not generated (`in_list` is set inside lists):
+/// compaction uses `@list` as active property for the items of a nested list,
+/// whereas expansion uses the enclosing property, so type and language
+/// mappings are not applied the same way.
+fn value(u: &mut Unstructured, depth: usize, in_list: bool) -> arbitrary::Result<Value> {
+	let mut object = Object::new();
+
+	match u.int_in_range(0..=11)? {
+		0 => return string(u, STRINGS),
+		1 => return Ok(u.choose(NUMBERS)?.parse().unwrap()),
+		2 => return Ok(Value::Boolean(u.arbitrary()?)),
+		3 => return Ok(Value::Null),
+		4 => {
+			object.insert("@value".into(), string(u, STRINGS)?);
+			object.insert("@language".into(), string(u, LANGUAGES)?);
+		}
+		5 => {
+			object.insert("@value".into(), string(u, STRINGS)?);
+			object.insert("@type".into(), string(u, TYPES)?);
+		}
+		6 => {
+			object.insert("@value".into(), string(u, STRINGS)?);
+			object.insert("@type".into(), "@json".into());
+		}
+		7 => {
+			object.insert("@id".into(), string(u, IDS)?);
+		}
+		8 if depth > 0 && !in_list => {
+			object.insert("@list".into(), values(u, depth - 1, true)?);
+		}
+		9 if depth > 0 => {
+			object.insert("@set".into(), values(u, depth - 1, in_list)?);
+		}
+		10 if depth > 0 && !in_list => return values(u, depth - 1, false),
+		11 if depth > 0 => return node(u, depth - 1),
+		_ => return string(u, STRINGS),
+	}
+
+	Ok(Value::Object(object))
+}
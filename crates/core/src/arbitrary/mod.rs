@@ -0,0 +1,369 @@
+//! Random JSON-LD data for property testing and fuzzing.
+//!
+//! Implements [`Arbitrary`] for the expanded document model, to property-test
+//! code consuming JSON-LD. The generated structures are valid expanded
+//! JSON-LD:
+//!   - identifiers are IRIs or blank node identifiers, never invalid
+//!     references;
+//!   - value objects are never `null` (except JSON literals), and language
+//!     strings have a language tag or a direction;
+//!   - top-level objects, graph entries, included nodes and reverse property
+//!     values are node objects.
+//!
+//! IRIs and blank node identifiers are taken from a small set, so that
+//! different parts of a structure refer to the same nodes and properties.
+//!
+//! The [`Context`] and [`Document`] types are random (unexpanded) JSON-LD
+//! contexts and documents, to test the JSON-LD algorithms themselves. They
+//! can be used directly as input of
+//! [`cargo fuzz`](https://github.com/rust-fuzz/cargo-fuzz) targets.
+//! Contexts and documents are built from a small shared set of terms and
+//! IRIs, so that generated documents actually use the terms defined by
+//! generated contexts. They are not always valid: processing errors are
+//! expected and are part of what is being tested.
+//!
+//! Enabling the `proptest` feature provides the equivalent
+//! [`proptest`](https://crates.io/crates/proptest) strategies in the
+//! [`strategy`] module.
+use crate::{
+	object::{node::Included, Graph, List, Literal},
+	Direction, ExpandedDocument, Id, Indexed, IndexedNode, IndexedObject, LangString,
+	LenientLangTagBuf, Node, Object, Value,
+};
+use arbitrary::{Arbitrary, Result, Unstructured};
+use iref::IriBuf;
+use json_syntax::NumberBuf;
+use rdf_types::BlankIdBuf;
+
+mod context;
+mod document;
+#[cfg(feature = "proptest")]
+pub mod strategy;
+
+pub use context::Context;
+pub use document::Document;
+
+/// Maximum nesting depth of generated objects.
+const MAX_DEPTH: usize = 3;
+
+/// IRI prefixes.
+const PREFIXES: &[&str] = &[
+	"https://example.org/",
+	"https://schema.org/",
+	"http://www.w3.org/2001/XMLSchema#",
+];
+
+/// IRI suffixes.
+const NAMES: &[&str] = &["a", "b", "name", "knows", "Person", "date", "integer"];
+
+/// Language tags.
+const LANGUAGES: &[&str] = &["en", "fr", "de-CH", "zh-Hant"];
+
+/// Terms defined by generated contexts.
+const TERMS: &[&str] = &[
+	"name", "knows", "tags", "date", "label", "Person", "ex", "schema",
+];
+
+/// IRIs to which terms are mapped.
+const IRIS: &[&str] = &[
+	"https://schema.org/name",
+	"https://schema.org/knows",
+	"https://schema.org/keywords",
+	"http://purl.org/dc/terms/date",
+	"http://www.w3.org/2000/01/rdf-schema#label",
+	"https://schema.org/Person",
+	"https://example.org/",
+	"https://schema.org/",
+];
+
+/// Prefix of the IRIs of terms that must not share their IRI with other
+/// terms.
+const TERM_IRI_PREFIX: &str = "https://example.org/terms#";
+
+/// Node identifiers of generated documents.
+const IDS: &[&str] = &[
+	"https://example.org/alice",
+	"https://example.org/bob",
+	"ex:carol",
+	"#me",
+	"_:b0",
+	"_:b1",
+];
+
+/// Node properties, using defined terms, compact IRIs and absolute IRIs.
+const PROPERTIES: &[&str] = &[
+	"name",
+	"knows",
+	"tags",
+	"date",
+	"label",
+	"ex:age",
+	"schema:name",
+	"https://schema.org/description",
+];
+
+/// Node types of generated documents.
+const TYPES: &[&str] = &["Person", "schema:Thing", "https://example.org/Robot"];
+
+/// Value type mappings of expanded term definitions.
+const TYPE_MAPPINGS: &[&str] = &[
+	"@id",
+	"@vocab",
+	"@json",
+	"http://www.w3.org/2001/XMLSchema#date",
+	"http://www.w3.org/2001/XMLSchema#integer",
+];
+
+/// Container mappings of expanded term definitions.
+///
+/// `@graph` containers are not used: values put in such containers are not
+/// subject to the free-floating values rules of expansion, and empty graphs
+/// are dropped by compaction, so they do not round trip.
+const CONTAINERS: &[&str] = &["@set", "@list", "@language", "@index"];
+
+/// Vocabulary mappings.
+const VOCABS: &[&str] = &["https://schema.org/", "https://example.org/vocab#"];
+
+/// Strings of generated documents.
+const STRINGS: &[&str] = &[
+	"Alice",
+	"Bonjour",
+	"",
+	"42",
+	"2024-01-01",
+	"https://example.org/",
+];
+
+/// Numbers of generated documents.
+const NUMBERS: &[&str] = &["0", "42", "-7", "1.5", "1e3"];
+
+fn iri(u: &mut Unstructured) -> Result<IriBuf> {
+	let prefix = u.choose(PREFIXES)?;
+	let name = u.choose(NAMES)?;
+	Ok(IriBuf::new(format!("{prefix}{name}")).unwrap())
+}
+
+fn blank_id(u: &mut Unstructured) -> Result<BlankIdBuf> {
+	let n = u.int_in_range(0..=7u8)?;
+	Ok(BlankIdBuf::new(format!("_:b{n}")).unwrap())
+}
+
+fn index(u: &mut Unstructured) -> Result<Option<String>> {
+	if u.ratio(1, 8)? {
+		Ok(Some(u.arbitrary()?))
+	} else {
+		Ok(None)
+	}
+}
+
+fn string(u: &mut Unstructured) -> Result<json_syntax::String> {
+	let s: &str = u.arbitrary()?;
+	Ok(s.into())
+}
+
+fn json(u: &mut Unstructured, depth: usize) -> Result<json_syntax::Value> {
+	let max = if depth > 0 { 5 } else { 3 };
+
+	Ok(match u.int_in_range(0..=max)? {
+		0 => json_syntax::Value::Null,
+		1 => json_syntax::Value::Boolean(u.arbitrary()?),
+		2 => json_syntax::Value::Number(NumberBuf::from(u.arbitrary::<i32>()?)),
+		3 => json_syntax::Value::String(string(u)?),
+		4 => {
+			let mut array = json_syntax::Array::new();
+			for _ in 0..u.int_in_range(0..=3)? {
+				array.push(json(u, depth - 1)?)
+			}
+
+			json_syntax::Value::Array(array)
+		}
+		_ => {
+			let mut object = json_syntax::Object::new();
+			for _ in 0..u.int_in_range(0..=3)? {
+				object.insert(string(u)?, json(u, depth - 1)?);
+			}
+
+			json_syntax::Value::Object(object)
+		}
+	})
+}
+
+fn object(u: &mut Unstructured, depth: usize) -> Result<IndexedObject<IriBuf, BlankIdBuf>> {
+	let object = if depth == 0 {
+		// Only values and node references at the maximum depth.
+		if u.arbitrary()? {
+			Object::Value(u.arbitrary()?)
+		} else {
+			Object::node(Node::with_id(u.arbitrary()?))
+		}
+	} else {
+		match u.int_in_range(0..=2)? {
+			0 => Object::Value(u.arbitrary()?),
+			1 => Object::node(node(u, depth - 1)?),
+			_ => {
+				let mut list = List::new(Vec::new());
+				for _ in 0..u.int_in_range(0..=3)? {
+					list.push(object(u, depth - 1)?)
+				}
+
+				Object::List(list)
+			}
+		}
+	};
+
+	Ok(Indexed::new(object, index(u)?))
+}
+
+fn indexed_node(u: &mut Unstructured, depth: usize) -> Result<IndexedNode> {
+	Ok(Indexed::new(node(u, depth)?, index(u)?))
+}
+
+fn node(u: &mut Unstructured, depth: usize) -> Result<Node> {
+	let mut node = Node::new();
+
+	if u.ratio(3, 4)? {
+		node.id = Some(u.arbitrary()?)
+	}
+
+	if u.ratio(1, 3)? {
+		let types = node.types_mut_or_default();
+		for _ in 0..u.int_in_range(1..=2)? {
+			types.push(u.arbitrary()?)
+		}
+	}
+
+	for _ in 0..u.int_in_range(0..=3)? {
+		let property = Id::iri(iri(u)?);
+		for _ in 0..u.int_in_range(1..=2)? {
+			node.insert(property.clone(), object(u, depth)?)
+		}
+	}
+
+	if depth > 0 {
+		if u.ratio(1, 8)? {
+			let property = Id::iri(iri(u)?);
+			let reverse_properties = node.reverse_properties_or_default();
+			for _ in 0..u.int_in_range(1..=2)? {
+				reverse_properties.insert(property.clone(), indexed_node(u, depth - 1)?)
+			}
+		}
+
+		if u.ratio(1, 8)? {
+			let mut graph = Graph::new();
+			for _ in 0..u.int_in_range(0..=2)? {
+				graph.insert(indexed_node(u, depth - 1)?.map_inner(Object::node));
+			}
+
+			node.set_graph_entry(Some(graph))
+		}
+
+		if u.ratio(1, 10)? {
+			let mut included = Included::new();
+			for _ in 0..u.int_in_range(0..=2)? {
+				included.insert(indexed_node(u, depth - 1)?);
+			}
+
+			node.set_included(Some(included))
+		}
+	}
+
+	Ok(node)
+}
+
+impl<'a> Arbitrary<'a> for Id {
+	/// Generates a valid node reference: an IRI or a blank node identifier.
+	fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+		if u.ratio(3, 4)? {
+			Ok(Id::iri(iri(u)?))
+		} else {
+			Ok(Id::blank(blank_id(u)?))
+		}
+	}
+}
+
+impl<'a> Arbitrary<'a> for LangString {
+	fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+		let data = string(u)?;
+
+		let (language, direction) = match u.int_in_range(0..=2)? {
+			0 => (Some(u.choose(LANGUAGES)?), None),
+			1 => (None, Some(u.choose(&[Direction::Ltr, Direction::Rtl])?)),
+			_ => (
+				Some(u.choose(LANGUAGES)?),
+				Some(u.choose(&[Direction::Ltr, Direction::Rtl])?),
+			),
+		};
+
+		let language = language.map(|tag| LenientLangTagBuf::new(tag.to_string()).0);
+		Ok(LangString::new(data, language, direction.copied()).unwrap())
+	}
+}
+
+impl<'a> Arbitrary<'a> for Value {
+	fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+		let literal = match u.int_in_range(0..=4)? {
+			0 => Literal::Boolean(u.arbitrary()?),
+			1 => Literal::Number(NumberBuf::from(u.arbitrary::<i64>()?)),
+			2 => Literal::String(string(u)?),
+			3 => return Ok(Value::LangString(u.arbitrary()?)),
+			_ => return Ok(Value::Json(json(u, MAX_DEPTH)?)),
+		};
+
+		let ty = if u.ratio(1, 3)? { Some(iri(u)?) } else { None };
+
+		Ok(Value::Literal(literal, ty))
+	}
+}
+
+impl<'a> Arbitrary<'a> for Node {
+	fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+		node(u, MAX_DEPTH)
+	}
+}
+
+impl<'a> Arbitrary<'a> for Object {
+	fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+		Ok(object(u, MAX_DEPTH)?.into_inner())
+	}
+}
+
+impl<'a> Arbitrary<'a> for ExpandedDocument {
+	/// Generates an expanded document whose top-level objects are node
+	/// objects.
+	fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+		let mut document = ExpandedDocument::new();
+		for _ in 0..u.int_in_range(0..=3)? {
+			document.insert(indexed_node(u, MAX_DEPTH)?.map_inner(Object::node));
+		}
+
+		Ok(document)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::TryFromJson;
+	use json_ld_syntax::IntoJsonWithContext;
+
+	#[test]
+	fn expanded_json_round_trip() {
+		let mut state: u64 = 0x2545f4914f6cdd1d;
+		for _ in 0..256 {
+			let bytes: Vec<u8> = (0..1024)
+				.map(|_| {
+					state ^= state << 13;
+					state ^= state >> 7;
+					state ^= state << 17;
+					state as u8
+				})
+				.collect();
+
+			let document =
+				ExpandedDocument::arbitrary_take_rest(Unstructured::new(&bytes)).unwrap();
+			let json = document.clone().into_json_with(&());
+			let parsed = ExpandedDocument::try_from_json_in(&mut (), json).unwrap();
+			assert_eq!(parsed, document)
+		}
+	}
+}
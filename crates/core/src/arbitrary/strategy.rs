@@ -0,0 +1,43 @@
+//! Proptest strategies.
+//!
+//! Values are generated by feeding random bytes to their
+//! [`arbitrary::Arbitrary`] implementation. Shrinking operates on those
+//! bytes.
+use arbitrary::{Arbitrary, Unstructured};
+use proptest::{collection::vec, prelude::*};
+use std::fmt;
+
+use super::{Context, Document};
+
+/// Maximum number of random bytes used to generate a value.
+const MAX_BYTES: usize = 1024;
+
+fn arbitrary<T: 'static + for<'a> Arbitrary<'a> + fmt::Debug>() -> impl Strategy<Value = T> {
+	vec(any::<u8>(), 0..MAX_BYTES).prop_filter_map("not enough bytes", |bytes| {
+		T::arbitrary_take_rest(Unstructured::new(&bytes)).ok()
+	})
+}
+
+/// Strategy generating random contexts.
+pub fn context() -> impl Strategy<Value = Context> {
+	arbitrary()
+}
+
+/// Strategy generating random documents.
+pub fn document() -> impl Strategy<Value = Document> {
+	arbitrary()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	proptest! {
+		#![proptest_config(ProptestConfig::with_cases(128))]
+
+		#[test]
+		fn contexts_are_well_formed(context in context()) {
+			prop_assert!(context.to_syntax().is_ok())
+		}
+	}
+}
@@ -4,6 +4,7 @@ use json_ld_syntax::{context::definition::TypeContainer, Nullable};
 pub struct InvalidContainer;
 
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Container {
 	// Empty container
 	None,
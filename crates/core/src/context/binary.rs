@@ -0,0 +1,117 @@
+use super::Context;
+use std::hash::Hash;
+
+/// Magic number starting every binary context.
+const MAGIC: &[u8; 4] = b"JLDC";
+
+/// Version of the binary context format.
+///
+/// It is incremented every time the format changes in an incompatible way,
+/// meaning that pre-compiled contexts must be regenerated.
+pub const BINARY_CONTEXT_VERSION: u16 = 1;
+
+/// Binary context encoding/decoding error.
+#[derive(Debug, thiserror::Error)]
+pub enum BinaryContextError {
+	#[error("not a binary context")]
+	InvalidHeader,
+
+	#[error("unsupported binary context version {0} (expected {BINARY_CONTEXT_VERSION})")]
+	UnsupportedVersion(u16),
+
+	#[error("binary context encoding failed: {0}")]
+	Encoding(#[from] ciborium::ser::Error<std::io::Error>),
+
+	#[error("binary context decoding failed: {0}")]
+	Decoding(#[from] ciborium::de::Error<std::io::Error>),
+}
+
+impl<T, B> Context<T, B> {
+	/// Pre-compiles this context into a compact binary blob.
+	///
+	/// The blob contains the whole processed context, including its inverse
+	/// (computed if necessary), so that it can be loaded back with
+	/// [`Self::from_binary`] without having to parse and process the original
+	/// context again. The blob starts with a header containing the
+	/// [format version](BINARY_CONTEXT_VERSION).
+	///
+	/// Identifiers are stored as is: blobs are only portable when `T` and `B`
+	/// are not vocabulary indexes (e.g. `IriBuf` and `BlankIdBuf`).
+	pub fn to_binary(&self) -> Result<Vec<u8>, BinaryContextError>
+	where
+		T: Clone + Eq + Hash + serde::Serialize,
+		B: Clone + Eq + Hash + serde::Serialize,
+	{
+		self.inverse();
+
+		let mut bytes = MAGIC.to_vec();
+		bytes.extend(BINARY_CONTEXT_VERSION.to_be_bytes());
+		ciborium::into_writer(self, &mut bytes)?;
+		Ok(bytes)
+	}
+
+	/// Loads a context pre-compiled with [`Self::to_binary`].
+	///
+	/// Fails if the blob was produced with another version of the format.
+	pub fn from_binary(bytes: &[u8]) -> Result<Self, BinaryContextError>
+	where
+		T: Eq + Hash + serde::de::DeserializeOwned,
+		B: Eq + Hash + serde::de::DeserializeOwned,
+	{
+		let bytes = bytes
+			.strip_prefix(MAGIC)
+			.ok_or(BinaryContextError::InvalidHeader)?;
+
+		if bytes.len() < 2 {
+			return Err(BinaryContextError::InvalidHeader);
+		}
+
+		let (version, body) = bytes.split_at(2);
+		let version = u16::from_be_bytes([version[0], version[1]]);
+		if version != BINARY_CONTEXT_VERSION {
+			return Err(BinaryContextError::UnsupportedVersion(version));
+		}
+
+		Ok(ciborium::from_reader(body)?)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{context::NormalTermDefinition, Container, Id, Term};
+	use iref::IriBuf;
+
+	#[test]
+	fn round_trip() {
+		let iri = |s: &str| IriBuf::new(format!("https://schema.org/{s}")).unwrap();
+
+		let mut context: Context = Context::new(Some(iri("")));
+		context.set_vocabulary(Some(Term::Id(Id::iri(iri("")))));
+
+		let name = NormalTermDefinition {
+			value: Some(Term::Id(Id::iri(iri("name")))),
+			container: Container::Language,
+			context: Some(Box::new(json_ld_syntax::context::Context::one(
+				json_ld_syntax::ContextEntry::IriRef(
+					iref::IriRefBuf::new("inner".to_owned()).unwrap(),
+				),
+			))),
+			..Default::default()
+		};
+		context.set_normal("name".into(), Some(name));
+
+		let bytes = context.to_binary().unwrap();
+		let loaded: Context = Context::from_binary(&bytes).unwrap();
+		assert!(loaded.inverse.get().is_some());
+		assert!(loaded.get_normal("name") == context.get_normal("name"));
+		assert!(loaded.vocabulary() == context.vocabulary());
+
+		let mut outdated = bytes.clone();
+		outdated[5] += 1;
+		assert!(matches!(
+			Context::<IriBuf>::from_binary(&outdated),
+			Err(BinaryContextError::UnsupportedVersion(_))
+		))
+	}
+}
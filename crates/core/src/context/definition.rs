@@ -1,5 +1,7 @@
 use super::{IntoSyntax, Nest};
-use crate::{Container, Direction, LenientLangTagBuf, Nullable, Term, Type};
+use crate::{
+	utils::DefaultHashBuilder, Container, Direction, LenientLangTagBuf, Nullable, Term, Type,
+};
 use contextual::WithContext;
 use iref::IriBuf;
 use json_ld_syntax::{
@@ -72,15 +74,16 @@ impl<'a, T, B> BindingRef<'a, T, B> {
 
 /// Context term definitions.
 #[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Definitions<T, B> {
-	normal: HashMap<Key, NormalTermDefinition<T, B>>,
+	normal: HashMap<Key, NormalTermDefinition<T, B>, DefaultHashBuilder>,
 	type_: Option<TypeTermDefinition>,
 }
 
 impl<T, B> Default for Definitions<T, B> {
 	fn default() -> Self {
 		Self {
-			normal: HashMap::new(),
+			normal: HashMap::default(),
 			type_: None,
 		}
 	}
@@ -91,7 +94,7 @@ impl<T, B> Definitions<T, B> {
 	pub fn into_parts(
 		self,
 	) -> (
-		HashMap<Key, NormalTermDefinition<T, B>>,
+		HashMap<Key, NormalTermDefinition<T, B>, DefaultHashBuilder>,
 		Option<TypeTermDefinition>,
 	) {
 		(self.normal, self.type_)
@@ -278,6 +281,7 @@ impl<T, B> IntoIterator for Definitions<T, B> {
 /// Such definition compared to a [`NormalTermDefinition`] can only contain
 /// a `@container` and `@protected` value.
 #[derive(PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TypeTermDefinition {
 	/// Type container.
 	pub container: TypeContainer,
@@ -525,6 +529,7 @@ impl<'a, T, B> Copy for TermDefinitionRef<'a, T, B> {}
 
 // A term definition.
 #[derive(PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct NormalTermDefinition<T = IriBuf, B = BlankIdBuf> {
 	// IRI mapping.
 	pub value: Option<Term<T, B>>,
@@ -26,6 +26,11 @@ impl<T: fmt::Debug> fmt::Debug for TypeSelection<T> {
 	}
 }
 
+#[cfg_attr(
+	feature = "serde",
+	derive(serde::Serialize, serde::Deserialize),
+	serde(bound(deserialize = "T: serde::Deserialize<'de> + Eq + Hash"))
+)]
 struct InverseType<T> {
 	reverse: Option<Key>,
 	any: Option<Key>,
@@ -69,6 +74,7 @@ impl<T> InverseType<T> {
 
 type LangDir = Nullable<(Option<LenientLangTagBuf>, Option<Direction>)>;
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 struct InverseLang {
 	any: Option<Key>,
 	map: HashMap<LangDir, Key>,
@@ -111,12 +117,18 @@ impl InverseLang {
 	}
 }
 
+#[cfg_attr(
+	feature = "serde",
+	derive(serde::Serialize, serde::Deserialize),
+	serde(bound(deserialize = "T: serde::Deserialize<'de> + Eq + Hash"))
+)]
 struct InverseContainer<T> {
 	language: InverseLang,
 	typ: InverseType<T>,
 	any: Any,
 }
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 struct Any {
 	none: Key,
 }
@@ -138,6 +150,11 @@ impl<T> InverseContainer<T> {
 	}
 }
 
+#[cfg_attr(
+	feature = "serde",
+	derive(serde::Serialize, serde::Deserialize),
+	serde(bound(deserialize = "T: serde::Deserialize<'de> + Eq + Hash"))
+)]
 pub struct InverseDefinition<T> {
 	map: HashMap<Container, InverseContainer<T>>,
 }
@@ -199,6 +216,13 @@ impl<T> InverseDefinition<T> {
 }
 
 /// Inverse context.
+#[cfg_attr(
+	feature = "serde",
+	derive(serde::Serialize, serde::Deserialize),
+	serde(bound(
+		deserialize = "T: serde::Deserialize<'de> + Eq + Hash, B: serde::Deserialize<'de> + Eq + Hash"
+	))
+)]
 pub struct InverseContext<T, B> {
 	map: HashMap<Term<T, B>, InverseDefinition<T>>,
 }
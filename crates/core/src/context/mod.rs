@@ -1,23 +1,30 @@
 //! Context processing algorithm and related types.
+#[cfg(feature = "binary-context")]
+mod binary;
 mod definition;
 pub mod inverse;
+mod scoped;
 
 use crate::{Direction, LenientLangTag, LenientLangTagBuf, Term};
 use contextual::WithContext;
 use iref::IriBuf;
-use json_ld_syntax::{KeywordType, Nullable};
+use json_ld_syntax::{IntoJson, KeywordType, Nullable};
 use once_cell::sync::OnceCell;
 use rdf_types::{BlankIdBuf, Id, Vocabulary};
 use std::borrow::Borrow;
 use std::hash::Hash;
+use std::sync::Arc;
 
 pub use json_ld_syntax::context::{
 	definition::{Key, KeyOrType, Type},
 	term_definition::Nest,
 };
 
+#[cfg(feature = "binary-context")]
+pub use binary::{BinaryContextError, BINARY_CONTEXT_VERSION};
 pub use definition::*;
 pub use inverse::InverseContext;
+use scoped::ScopedContexts;
 
 /// Processed JSON-LD context.
 ///
@@ -26,6 +33,14 @@ pub use inverse::InverseContext;
 ///
 /// [1]: <https://www.w3.org/TR/json-ld11-api/#context-processing-algorithm>
 /// [`json-ld-context-processing`]: <https://crates.io/crates/json-ld-context-processing>
+#[cfg_attr(
+	feature = "serde",
+	derive(serde::Serialize, serde::Deserialize),
+	serde(bound(
+		serialize = "T: serde::Serialize, B: serde::Serialize",
+		deserialize = "T: serde::Deserialize<'de> + Eq + Hash, B: serde::Deserialize<'de> + Eq + Hash"
+	))
+)]
 pub struct Context<T = IriBuf, B = BlankIdBuf> {
 	original_base_url: Option<T>,
 	base_iri: Option<T>,
@@ -33,8 +48,13 @@ pub struct Context<T = IriBuf, B = BlankIdBuf> {
 	default_language: Option<LenientLangTagBuf>,
 	default_base_direction: Option<Direction>,
 	previous_context: Option<Box<Self>>,
+	#[cfg_attr(feature = "serde", serde(default))]
+	type_scoped: bool,
 	definitions: Definitions<T, B>,
-	inverse: OnceCell<InverseContext<T, B>>,
+	#[cfg_attr(feature = "serde", serde(with = "serde_inverse"))]
+	inverse: OnceCell<Arc<InverseContext<T, B>>>,
+	#[cfg_attr(feature = "serde", serde(skip))]
+	scoped_contexts: ScopedContexts<T, B>,
 }
 
 impl<T, B> Default for Context<T, B> {
@@ -46,8 +66,10 @@ impl<T, B> Default for Context<T, B> {
 			default_language: None,
 			default_base_direction: None,
 			previous_context: None,
+			type_scoped: false,
 			definitions: Definitions::default(),
 			inverse: OnceCell::default(),
+			scoped_contexts: ScopedContexts::default(),
 		}
 	}
 }
@@ -67,8 +89,10 @@ impl<T, B> Context<T, B> {
 			default_language: None,
 			default_base_direction: None,
 			previous_context: None,
+			type_scoped: false,
 			definitions: Definitions::default(),
 			inverse: OnceCell::default(),
+			scoped_contexts: ScopedContexts::default(),
 		}
 	}
 
@@ -144,6 +168,24 @@ impl<T, B> Context<T, B> {
 		}
 	}
 
+	/// Checks if this context is propagated to nested node objects.
+	///
+	/// A context is not propagated when it has a
+	/// [previous context](Self::previous_context), which happens when it
+	/// results from a type-scoped context or a context with `@propagate` set
+	/// to `false`.
+	pub fn is_propagated(&self) -> bool {
+		self.previous_context.is_none()
+	}
+
+	/// Checks if this context results from a type-scoped context.
+	///
+	/// Such context is not [propagated](Self::is_propagated): its previous
+	/// context is restored when processing nested node objects.
+	pub fn is_type_scoped(&self) -> bool {
+		self.type_scoped
+	}
+
 	/// Returns the number of terms defined.
 	pub fn len(&self) -> usize {
 		self.definitions.len()
@@ -176,7 +218,45 @@ impl<T, B> Context<T, B> {
 		T: Clone + Hash + Eq,
 		B: Clone + Hash + Eq,
 	{
-		self.inverse.get_or_init(|| self.into())
+		self.inverse.get_or_init(|| Arc::new(self.into()))
+	}
+
+	/// Returns the result of a previous processing of the scoped `context`
+	/// against this context, with the given `base_url` and processing
+	/// `options`, if any.
+	///
+	/// See [`Self::insert_scoped_context`].
+	pub fn scoped_context<K>(
+		&self,
+		context: &json_ld_syntax::context::Context,
+		base_url: Option<&T>,
+		options: &K,
+	) -> Option<Arc<Self>>
+	where
+		T: Hash + Eq,
+		K: 'static + Hash + Eq,
+	{
+		self.scoped_contexts.get(context, base_url, options)
+	}
+
+	/// Remembers the result of processing the scoped `context` against this
+	/// context, so that term definitions sharing identical scoped contexts
+	/// share the processed context.
+	///
+	/// The processed contexts are dropped with this context, or as soon as it
+	/// is modified. They are not shared with clones.
+	pub fn insert_scoped_context<K>(
+		&self,
+		context: json_ld_syntax::context::Context,
+		base_url: Option<T>,
+		options: K,
+		processed: Arc<Self>,
+	) where
+		T: Hash + Eq,
+		K: 'static + Send + Sync + Hash + Eq,
+	{
+		self.scoped_contexts
+			.insert(context, base_url, options, processed)
 	}
 
 	/// Sets the normal definition for the given term `key`.
@@ -186,44 +266,101 @@ impl<T, B> Context<T, B> {
 		definition: Option<NormalTermDefinition<T, B>>,
 	) -> Option<NormalTermDefinition<T, B>> {
 		self.inverse.take();
+		self.scoped_contexts.clear();
 		self.definitions.set_normal(key, definition)
 	}
 
 	/// Sets the `@type` definition.
 	pub fn set_type(&mut self, type_: Option<TypeTermDefinition>) -> Option<TypeTermDefinition> {
+		self.scoped_contexts.clear();
 		self.definitions.set_type(type_)
 	}
 
 	/// Sets the base IRI.
+	///
+	/// The inverse context does not depend on the base IRI, so it is kept if
+	/// it has already been computed.
 	pub fn set_base_iri(&mut self, iri: Option<T>) {
-		self.inverse.take();
+		self.scoped_contexts.clear();
 		self.base_iri = iri
 	}
 
 	/// Sets the `@vocab` value.
 	pub fn set_vocabulary(&mut self, vocab: Option<Term<T, B>>) {
 		self.inverse.take();
+		self.scoped_contexts.clear();
 		self.vocabulary = vocab;
 	}
 
 	/// Sets the default `@language` value.
 	pub fn set_default_language(&mut self, lang: Option<LenientLangTagBuf>) {
 		self.inverse.take();
+		self.scoped_contexts.clear();
 		self.default_language = lang;
 	}
 
 	/// Sets the default `@direction` value.
 	pub fn set_default_base_direction(&mut self, dir: Option<Direction>) {
 		self.inverse.take();
+		self.scoped_contexts.clear();
 		self.default_base_direction = dir;
 	}
 
 	/// Sets the previous context.
 	pub fn set_previous_context(&mut self, previous: Self) {
 		self.inverse.take();
+		self.scoped_contexts.clear();
 		self.previous_context = Some(Box::new(previous))
 	}
 
+	/// Removes and returns the previous context, making this context
+	/// propagated.
+	pub fn take_previous_context(&mut self) -> Option<Self> {
+		self.scoped_contexts.clear();
+		self.previous_context.take().map(|c| *c)
+	}
+
+	/// Replaces this context with its previous context, if any, as done by
+	/// the expansion algorithm when processing a nested node object with a
+	/// non-propagated context.
+	///
+	/// Returns `true` if the previous context has been restored.
+	pub fn restore_previous_context(&mut self) -> bool {
+		match self.previous_context.take() {
+			Some(previous) => {
+				*self = *previous;
+				true
+			}
+			None => false,
+		}
+	}
+
+	/// Sets whether or not this context results from a type-scoped context.
+	pub fn set_type_scoped(&mut self, type_scoped: bool) {
+		self.scoped_contexts.clear();
+		self.type_scoped = type_scoped
+	}
+
+	/// Resets this context to a newly-initialized context, as done by the
+	/// context processing algorithm when processing a `null` context.
+	///
+	/// The resulting context is empty, with its base IRI set to the original
+	/// base URL. If `propagate` is `false`, the current context becomes its
+	/// previous context.
+	///
+	/// Protected term definitions are not checked: the context processing
+	/// algorithm fails with an invalid context nullification error if the
+	/// context [has protected definitions](Self::has_protected_items).
+	pub fn reset(&mut self, propagate: bool)
+	where
+		T: Clone,
+	{
+		let previous = std::mem::replace(self, Self::new(self.original_base_url.clone()));
+		if !propagate {
+			self.set_previous_context(previous)
+		}
+	}
+
 	/// Converts this context into its syntactic definition.
 	pub fn into_syntax_definition(
 		self,
@@ -254,6 +391,30 @@ impl<T, B> Context<T, B> {
 		}
 	}
 
+	/// Writes this context back to the smallest equivalent JSON context
+	/// definition.
+	///
+	/// The base IRI is omitted when it is equal to the original base URL.
+	/// See [`json_ld_syntax::context::Context::minimize`] for the other
+	/// performed rewrites.
+	pub fn to_json_minimal(
+		&self,
+		vocabulary: &impl Vocabulary<Iri = T, BlankId = B>,
+	) -> json_syntax::Value
+	where
+		T: Clone + PartialEq,
+		B: Clone,
+	{
+		let mut context = self.clone();
+		if context.base_iri == context.original_base_url {
+			context.base_iri = None
+		}
+
+		let mut syntax = context.into_syntax(vocabulary);
+		syntax.minimize();
+		syntax.into_json()
+	}
+
 	pub fn map_ids<U, C>(
 		self,
 		mut map_iri: impl FnMut(T) -> U,
@@ -276,8 +437,10 @@ impl<T, B> Context<T, B> {
 			previous_context: self
 				.previous_context
 				.map(|c| Box::new((*c).map_ids_with(map_iri, map_id))),
+			type_scoped: self.type_scoped,
 			definitions: self.definitions.map_ids(map_iri, map_id),
 			inverse: OnceCell::new(),
+			scoped_contexts: ScopedContexts::default(),
 		}
 	}
 }
@@ -319,8 +482,12 @@ impl<T: Clone, B: Clone> Clone for Context<T, B> {
 			default_language: self.default_language.clone(),
 			default_base_direction: self.default_base_direction,
 			previous_context: self.previous_context.clone(),
+			type_scoped: self.type_scoped,
 			definitions: self.definitions.clone(),
-			inverse: OnceCell::default(),
+			// Every method modifying the context resets the inverse context,
+			// so it can be shared with the clone.
+			inverse: self.inverse.clone(),
+			scoped_contexts: ScopedContexts::default(),
 		}
 	}
 }
@@ -333,5 +500,116 @@ impl<T: PartialEq, B: PartialEq> PartialEq for Context<T, B> {
 			&& self.default_language == other.default_language
 			&& self.default_base_direction == other.default_base_direction
 			&& self.previous_context == other.previous_context
+			&& self.type_scoped == other.type_scoped
+	}
+}
+
+/// (De)serializes the inverse context only if it has already been computed.
+#[cfg(feature = "serde")]
+mod serde_inverse {
+	use super::InverseContext;
+	use once_cell::sync::OnceCell;
+	use std::hash::Hash;
+	use std::sync::Arc;
+
+	pub fn serialize<T, B, S>(
+		inverse: &OnceCell<Arc<InverseContext<T, B>>>,
+		serializer: S,
+	) -> Result<S::Ok, S::Error>
+	where
+		T: serde::Serialize,
+		B: serde::Serialize,
+		S: serde::Serializer,
+	{
+		serde::Serialize::serialize(&inverse.get(), serializer)
+	}
+
+	pub fn deserialize<'de, T, B, D>(
+		deserializer: D,
+	) -> Result<OnceCell<Arc<InverseContext<T, B>>>, D::Error>
+	where
+		T: serde::Deserialize<'de> + Eq + Hash,
+		B: serde::Deserialize<'de> + Eq + Hash,
+		D: serde::Deserializer<'de>,
+	{
+		let inverse: Option<Arc<InverseContext<T, B>>> =
+			serde::Deserialize::deserialize(deserializer)?;
+		Ok(inverse.map(OnceCell::with_value).unwrap_or_default())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{context::NormalTermDefinition, Id};
+
+	#[test]
+	fn reset() {
+		let base = IriBuf::new("https://example.com/".to_owned()).unwrap();
+		let mut context: Context = Context::new(Some(base.clone()));
+		context.set_vocabulary(Some(Term::Id(Id::iri(base.clone()))));
+		context.set_base_iri(None);
+
+		let mut reset = context.clone();
+		reset.reset(true);
+		assert!(reset.vocabulary().is_none());
+		assert_eq!(reset.base_iri(), Some(&base));
+		assert!(reset.is_propagated());
+
+		reset = context.clone();
+		reset.reset(false);
+		assert!(!reset.is_propagated());
+		assert!(reset.restore_previous_context());
+		assert!(reset == context);
+		assert!(!reset.restore_previous_context())
+	}
+
+	#[test]
+	fn to_json_minimal() {
+		let iri = |s: &str| IriBuf::new(format!("https://schema.org/{s}")).unwrap();
+
+		let mut context: Context = Context::new(Some(iri("")));
+		context.set_vocabulary(Some(Term::Id(Id::iri(iri("")))));
+
+		context.set_normal(
+			"name".into(),
+			Some(NormalTermDefinition {
+				value: Some(Term::Id(Id::iri(iri("name")))),
+				..Default::default()
+			}),
+		);
+
+		assert_eq!(
+			context.to_json_minimal(&()),
+			json_syntax::json!({
+				"@vocab": "https://schema.org/",
+				"name": "https://schema.org/name"
+			})
+		)
+	}
+
+	#[test]
+	fn shared_inverse() {
+		let iri = |s: &str| IriBuf::new(format!("https://schema.org/{s}")).unwrap();
+
+		let mut context: Context = Context::new(None);
+		context.set_normal(
+			"name".into(),
+			Some(NormalTermDefinition {
+				value: Some(Term::Id(Id::iri(iri("name")))),
+				..Default::default()
+			}),
+		);
+		context.inverse();
+
+		let mut rebased = context.clone();
+		rebased.set_base_iri(Some(iri("")));
+		assert!(Arc::ptr_eq(
+			context.inverse.get().unwrap(),
+			rebased.inverse.get().unwrap()
+		));
+
+		rebased.set_vocabulary(Some(Term::Id(Id::iri(iri("")))));
+		assert!(rebased.inverse.get().is_none())
 	}
 }
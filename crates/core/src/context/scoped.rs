@@ -0,0 +1,91 @@
+use super::Context;
+use std::any::Any;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex};
+
+/// Scoped contexts processed against a given active context.
+///
+/// Term definitions sharing the same scoped context are expanded using the
+/// same processed context, which is only computed once per active context.
+/// Entries are looked up by the hash of the syntactic context, base URL and
+/// processing options, and then compared for equality.
+///
+/// This memo is owned by the active context it depends on: it is not shared
+/// with clones and is cleared whenever the context is modified.
+pub(crate) struct ScopedContexts<T, B>(Mutex<HashMap<u64, Vec<Entry<T, B>>>>);
+
+struct Entry<T, B> {
+	context: json_ld_syntax::context::Context,
+	base_url: Option<T>,
+	options: Box<dyn Any + Send + Sync>,
+	processed: Arc<Context<T, B>>,
+}
+
+impl<T, B> Default for ScopedContexts<T, B> {
+	fn default() -> Self {
+		Self(Mutex::new(HashMap::new()))
+	}
+}
+
+impl<T, B> ScopedContexts<T, B> {
+	pub fn clear(&mut self) {
+		self.0.get_mut().unwrap_or_else(|e| e.into_inner()).clear()
+	}
+
+	pub fn get<K>(
+		&self,
+		context: &json_ld_syntax::context::Context,
+		base_url: Option<&T>,
+		options: &K,
+	) -> Option<Arc<Context<T, B>>>
+	where
+		T: Hash + Eq,
+		K: 'static + Hash + Eq,
+	{
+		let hash = hash(context, base_url, options);
+		let entries = self.0.lock().unwrap_or_else(|e| e.into_inner());
+		entries
+			.get(&hash)?
+			.iter()
+			.find(|e| {
+				e.context == *context
+					&& e.base_url.as_ref() == base_url
+					&& e.options.downcast_ref::<K>() == Some(options)
+			})
+			.map(|e| e.processed.clone())
+	}
+
+	pub fn insert<K>(
+		&self,
+		context: json_ld_syntax::context::Context,
+		base_url: Option<T>,
+		options: K,
+		processed: Arc<Context<T, B>>,
+	) where
+		T: Hash + Eq,
+		K: 'static + Send + Sync + Hash + Eq,
+	{
+		let hash = hash(&context, base_url.as_ref(), &options);
+		let mut entries = self.0.lock().unwrap_or_else(|e| e.into_inner());
+		entries.entry(hash).or_default().push(Entry {
+			context,
+			base_url,
+			options: Box::new(options),
+			processed,
+		})
+	}
+}
+
+fn hash<T: Hash, K: Hash>(
+	context: &json_ld_syntax::context::Context,
+	base_url: Option<&T>,
+	options: &K,
+) -> u64 {
+	let mut hasher = DefaultHasher::new();
+	context.hash(&mut hasher);
+	base_url.hash(&mut hasher);
+	options.hash(&mut hasher);
+	hasher.finish()
+}
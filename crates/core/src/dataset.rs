@@ -0,0 +1,800 @@
+//! RDF dataset.
+use crate::{
+	flattening::{ConflictingIndexes, NodeMap, NodeMapGraph},
+	object::node::properties::PropertyObjects,
+	rdf::{
+		is_rdf_type, literal_value, reference, Quad, Quads, RdfDirection, RDF_LANG_STRING, RDF_TYPE,
+	},
+	ExpandedDocument, Id, RdfQuads, ValidId,
+};
+use langtag::LangTagBuf;
+use rdf_types::{
+	vocabulary::{IriVocabulary, IriVocabularyMut, LiteralVocabularyMut},
+	Generator, LiteralTypeRef, Vocabulary,
+};
+use std::{
+	collections::{HashMap, HashSet},
+	hash::Hash,
+};
+
+mod inference;
+mod provenance;
+mod update;
+mod versioned;
+
+pub use inference::*;
+pub use provenance::*;
+pub use update::*;
+pub use versioned::*;
+
+/// RDF quad, as inserted in a [`Dataset`].
+pub type DatasetQuad<T, B> = Quad<T, B, rdf_types::Literal<T>>;
+
+/// RDF dataset.
+///
+/// A dataset owns a default graph and named graphs, stored as a [`NodeMap`]:
+/// each graph maps node identifiers to flattened nodes. It can be seen
+/// either as JSON-LD (through [`Self::node_map`]) or as RDF (through the
+/// [`RdfQuads`] trait), and quads can be inserted and removed.
+///
+/// Literals are inserted without using native types: every non-string
+/// literal becomes a typed value object. The object of an `rdf:type` quad is
+/// stored as a node type.
+///
+/// The source of each quad can optionally be tracked, see
+/// [`Self::insert_from_with`].
+#[derive(Default)]
+pub struct Dataset<T, B> {
+	node_map: NodeMap<T, B>,
+	provenance: Option<Provenance<T, B>>,
+}
+
+impl<T, B> Dataset<T, B> {
+	/// Creates a new empty dataset.
+	pub fn new() -> Self {
+		Self {
+			node_map: NodeMap::new(),
+			provenance: None,
+		}
+	}
+
+	/// Returns the JSON-LD view of this dataset.
+	pub fn node_map(&self) -> &NodeMap<T, B> {
+		&self.node_map
+	}
+
+	/// Returns the JSON-LD view of this dataset, for modification.
+	pub fn node_map_mut(&mut self) -> &mut NodeMap<T, B> {
+		&mut self.node_map
+	}
+
+	/// Turns this dataset into its node map.
+	pub fn into_node_map(self) -> NodeMap<T, B> {
+		self.node_map
+	}
+}
+
+impl<T: Clone + Eq + Hash, B: Clone + Eq + Hash> Dataset<T, B> {
+	/// Builds a dataset from an expanded document.
+	///
+	/// Nested anonymous nodes are assigned an identifier using the given
+	/// `generator`.
+	pub fn from_expanded_document_with<V, G: Generator<V>>(
+		vocabulary: &mut V,
+		generator: G,
+		document: ExpandedDocument<T, B>,
+	) -> Result<Self, ConflictingIndexes<T, B>>
+	where
+		V: Vocabulary<Iri = T, BlankId = B>,
+	{
+		document
+			.into_node_map_with(vocabulary, generator)
+			.map(Self::from)
+	}
+
+	/// Builds a dataset from an expanded document.
+	///
+	/// See [`Self::from_expanded_document_with`].
+	pub fn from_expanded_document<G: Generator>(
+		generator: G,
+		document: ExpandedDocument<T, B>,
+	) -> Result<Self, ConflictingIndexes<T, B>>
+	where
+		(): Vocabulary<Iri = T, BlankId = B>,
+	{
+		Self::from_expanded_document_with(
+			rdf_types::vocabulary::no_vocabulary_mut(),
+			generator,
+			document,
+		)
+	}
+
+	/// Returns the given graph (`None` for the default graph), if any.
+	pub fn graph(&self, id: Option<&ValidId<T, B>>) -> Option<&NodeMapGraph<T, B>> {
+		match id {
+			Some(id) => self.node_map.graph(Some(&Id::Valid(id.clone()))),
+			None => self.node_map.graph(None),
+		}
+	}
+
+	/// Checks if the dataset contains the given quad.
+	///
+	/// Values are compared in their JSON-LD form, as in
+	/// [`Self::remove_with`].
+	pub fn contains_with(
+		&self,
+		vocabulary: &impl IriVocabulary<Iri = T>,
+		rdf_types::Quad(subject, property, object, graph): &DatasetQuad<T, B>,
+	) -> bool {
+		let graph = graph.clone().map(Id::Valid);
+		let node = match self
+			.node_map
+			.graph(graph.as_ref())
+			.and_then(|nodes| nodes.get(&Id::Valid(subject.clone())))
+		{
+			Some(node) => node,
+			None => return false,
+		};
+
+		match object {
+			rdf_types::Object::Id(id) if is_rdf_type(vocabulary, property) => {
+				node.types().contains(&Id::Valid(id.clone()))
+			}
+			object => {
+				let value = match object.clone() {
+					rdf_types::Object::Id(id) => reference(id),
+					rdf_types::Object::Literal(l) => literal_value(vocabulary, l),
+				};
+
+				node.get(&Id::Valid(property.clone()))
+					.any(|v| v.equivalent(&value))
+			}
+		}
+	}
+
+	/// Checks if the dataset contains the given quad.
+	///
+	/// See [`Self::contains_with`].
+	pub fn contains(&self, quad: &DatasetQuad<T, B>) -> bool
+	where
+		(): IriVocabulary<Iri = T>,
+	{
+		self.contains_with(&(), quad)
+	}
+
+	/// Inserts the given quad.
+	///
+	/// If the provenance of the quads is tracked, a quad inserted from a
+	/// source and again with this function is kept when its sources are
+	/// removed.
+	///
+	/// Returns `true` if the quad was not already in the dataset.
+	pub fn insert_with(
+		&mut self,
+		vocabulary: &impl IriVocabulary<Iri = T>,
+		quad: DatasetQuad<T, B>,
+	) -> Result<bool, ConflictingIndexes<T, B>> {
+		let sourced = self
+			.provenance
+			.as_ref()
+			.is_some_and(|provenance| provenance.has_sources(&quad))
+			.then(|| quad.clone());
+
+		let inserted = self.insert_quad_with(vocabulary, quad)?;
+
+		if let (Some(provenance), Some(quad)) = (&mut self.provenance, sourced) {
+			provenance.insert_unsourced(quad);
+		}
+
+		Ok(inserted)
+	}
+
+	/// Inserts the given quad, without updating its provenance.
+	fn insert_quad_with(
+		&mut self,
+		vocabulary: &impl IriVocabulary<Iri = T>,
+		rdf_types::Quad(subject, property, object, graph): DatasetQuad<T, B>,
+	) -> Result<bool, ConflictingIndexes<T, B>> {
+		let graph = graph.map(Id::Valid);
+		if let Some(graph) = &graph {
+			self.node_map.declare_graph(graph.clone());
+		}
+
+		let node = self
+			.node_map
+			.graph_mut(graph.as_ref())
+			.unwrap()
+			.declare_node(Id::Valid(subject), None)?;
+
+		let inserted = match object {
+			rdf_types::Object::Id(id) if is_rdf_type(vocabulary, &property) => {
+				let ty = Id::Valid(id);
+				let types = node.types_mut_or_default();
+				if types.contains(&ty) {
+					false
+				} else {
+					types.push(ty);
+					true
+				}
+			}
+			object => {
+				let property = Id::Valid(property);
+				let value = match object {
+					rdf_types::Object::Id(id) => reference(id),
+					rdf_types::Object::Literal(l) => literal_value(vocabulary, l),
+				};
+
+				if node.get(&property).any(|v| v.equivalent(&value)) {
+					false
+				} else {
+					node.insert(property, value);
+					true
+				}
+			}
+		};
+
+		Ok(inserted)
+	}
+
+	/// Inserts the given quad.
+	///
+	/// See [`Self::insert_with`].
+	pub fn insert(&mut self, quad: DatasetQuad<T, B>) -> Result<bool, ConflictingIndexes<T, B>>
+	where
+		(): IriVocabulary<Iri = T>,
+	{
+		self.insert_with(&(), quad)
+	}
+
+	/// Removes the given quad.
+	///
+	/// Nodes left without any entry, and named graphs left without any
+	/// non-empty node, are removed as well. Values are compared in their JSON-LD form: a quad
+	/// whose literal is the RDF representation of a native JSON-LD value
+	/// (e.g. a number) does not match it. The provenance of the quad, if
+	/// tracked, is forgotten.
+	///
+	/// Returns `true` if the quad was in the dataset.
+	pub fn remove_with(
+		&mut self,
+		vocabulary: &impl IriVocabulary<Iri = T>,
+		quad: DatasetQuad<T, B>,
+	) -> bool {
+		if let Some(provenance) = &mut self.provenance {
+			provenance.remove(&quad)
+		}
+
+		let rdf_types::Quad(subject, property, object, graph) = quad;
+		let graph = graph.map(Id::Valid);
+		let nodes = match self.node_map.graph_mut(graph.as_ref()) {
+			Some(nodes) => nodes,
+			None => return false,
+		};
+
+		let subject = Id::Valid(subject);
+		let node = match nodes.get_mut(&subject) {
+			Some(node) => node,
+			None => return false,
+		};
+
+		let removed = match object {
+			rdf_types::Object::Id(id) if is_rdf_type(vocabulary, &property) => {
+				let ty = Id::Valid(id);
+				match node.types.as_mut() {
+					Some(types) => match types.iter().position(|t| *t == ty) {
+						Some(i) => {
+							types.remove(i);
+							if types.is_empty() {
+								node.types = None
+							}
+							true
+						}
+						None => false,
+					},
+					None => false,
+				}
+			}
+			object => {
+				let property = Id::Valid(property);
+				let value = match object {
+					rdf_types::Object::Id(id) => reference(id),
+					rdf_types::Object::Literal(l) => literal_value(vocabulary, l),
+				};
+
+				match node.properties_mut().remove(&property) {
+					Some(values) => {
+						let len = values.len();
+						let values: PropertyObjects<T, B> = values
+							.into_iter()
+							.filter(|v| !v.equivalent(&value))
+							.collect();
+						let removed = values.len() < len;
+						if !values.is_empty() {
+							node.properties_mut().set(property, values)
+						}
+						removed
+					}
+					None => false,
+				}
+			}
+		};
+
+		if removed {
+			if node.index().is_none() && node.is_empty() {
+				nodes.remove(&subject);
+			}
+
+			if let Some(graph) = &graph {
+				if nodes
+					.nodes()
+					.all(|node| node.index().is_none() && node.is_empty())
+				{
+					self.node_map.remove_graph(graph);
+				}
+			}
+		}
+
+		removed
+	}
+
+	/// Removes the given quad.
+	///
+	/// See [`Self::remove_with`].
+	pub fn remove(&mut self, quad: DatasetQuad<T, B>) -> bool
+	where
+		(): IriVocabulary<Iri = T>,
+	{
+		self.remove_with(&(), quad)
+	}
+}
+
+impl<T: Clone + Eq + Hash, B: Clone + Eq + Hash> Dataset<T, B> {
+	/// Collects the RDF quads of this dataset, with owned literals.
+	///
+	/// Quads whose literal is unknown to the vocabulary are skipped.
+	fn owned_quads_with<V, G: Generator<V>>(
+		&self,
+		vocabulary: &mut V,
+		generator: &mut G,
+	) -> Vec<DatasetQuad<T, B>>
+	where
+		V: Vocabulary<Iri = T, BlankId = B> + IriVocabularyMut + LiteralVocabularyMut,
+		V::Literal: Clone,
+	{
+		let quads: Vec<_> = self
+			.rdf_quads_full(vocabulary, generator, None, false)
+			.cloned()
+			.collect();
+
+		quads
+			.into_iter()
+			.filter_map(|rdf_types::Quad(s, p, o, g)| {
+				let o = match o {
+					rdf_types::Object::Id(id) => rdf_types::Object::Id(id),
+					rdf_types::Object::Literal(l) => {
+						rdf_types::Object::Literal(vocabulary.owned_literal(l).ok()?)
+					}
+				};
+
+				Some(rdf_types::Quad(s, p, o, g))
+			})
+			.collect()
+	}
+
+	/// Computes the summary of this dataset.
+	///
+	/// The dataset is summarized through its RDF quads, where anonymous
+	/// values (such as lists) are given a blank node identifier using the
+	/// given `generator`.
+	pub fn summary_with<V, G: Generator<V>>(
+		&self,
+		vocabulary: &mut V,
+		generator: &mut G,
+	) -> DatasetSummary<T, B>
+	where
+		V: Vocabulary<Iri = T, BlankId = B> + IriVocabularyMut + LiteralVocabularyMut,
+		V::Literal: Clone,
+	{
+		let quads: Vec<_> = self
+			.rdf_quads_full(vocabulary, generator, None, false)
+			.cloned()
+			.collect();
+
+		let rdf_type = ValidId::Iri(vocabulary.insert(RDF_TYPE));
+		let lang_string = vocabulary.insert(RDF_LANG_STRING);
+
+		let mut nodes = HashSet::new();
+		let mut graphs = HashSet::new();
+		let mut predicates = HashSet::new();
+		let mut summary = DatasetSummary::new();
+		summary.quads = quads.len();
+
+		for rdf_types::Quad(subject, predicate, object, graph) in &quads {
+			nodes.insert(subject);
+			predicates.insert(predicate);
+
+			if let Some(graph) = graph {
+				graphs.insert(graph);
+			}
+
+			match object {
+				rdf_types::Object::Id(id) => {
+					if *predicate == rdf_type {
+						*summary.types.entry(id.clone()).or_default() += 1
+					}
+
+					nodes.insert(id);
+				}
+				rdf_types::Object::Literal(literal) => {
+					if let Some(literal) = vocabulary.literal(literal) {
+						match literal.type_ {
+							LiteralTypeRef::Any(ty) => {
+								*summary.datatypes.entry(ty.clone()).or_default() += 1
+							}
+							LiteralTypeRef::LangString(tag) => {
+								*summary.datatypes.entry(lang_string.clone()).or_default() += 1;
+								*summary.languages.entry(tag.to_owned()).or_default() += 1
+							}
+						}
+					}
+				}
+			}
+		}
+
+		summary.nodes = nodes.len();
+		summary.graphs = graphs.len();
+		summary.predicates = predicates.len();
+		summary.blank_nodes = nodes
+			.iter()
+			.chain(graphs.iter().filter(|g| !nodes.contains(**g)))
+			.filter(|id| id.is_blank())
+			.count();
+
+		summary
+	}
+
+	/// Computes the summary of this dataset.
+	///
+	/// See [`Self::summary_with`].
+	pub fn summary<G: Generator>(&self, generator: &mut G) -> DatasetSummary<T, B>
+	where
+		(): Vocabulary<Iri = T, BlankId = B>,
+	{
+		self.summary_with(rdf_types::vocabulary::no_vocabulary_mut(), generator)
+	}
+}
+
+/// Dataset summary.
+///
+/// Gives an overview of the content of a [`Dataset`], typically to monitor a
+/// data pipeline. Counts are made on the RDF quads of the dataset.
+#[derive(Debug, Clone)]
+pub struct DatasetSummary<T, B> {
+	/// Number of distinct nodes appearing as subject or object.
+	pub nodes: usize,
+
+	/// Number of quads.
+	pub quads: usize,
+
+	/// Number of named graphs.
+	pub graphs: usize,
+
+	/// Number of distinct predicates.
+	pub predicates: usize,
+
+	/// Number of distinct blank nodes, including the ones generated for
+	/// anonymous values.
+	pub blank_nodes: usize,
+
+	/// Number of typed nodes per type.
+	pub types: HashMap<ValidId<T, B>, usize>,
+
+	/// Number of literals per datatype.
+	///
+	/// Language-tagged strings have the `rdf:langString` datatype.
+	pub datatypes: HashMap<T, usize>,
+
+	/// Number of language-tagged strings per language tag.
+	pub languages: HashMap<LangTagBuf, usize>,
+}
+
+impl<T, B> DatasetSummary<T, B> {
+	fn new() -> Self {
+		Self {
+			nodes: 0,
+			quads: 0,
+			graphs: 0,
+			predicates: 0,
+			blank_nodes: 0,
+			types: HashMap::new(),
+			datatypes: HashMap::new(),
+			languages: HashMap::new(),
+		}
+	}
+}
+
+impl<T, B> From<NodeMap<T, B>> for Dataset<T, B> {
+	fn from(node_map: NodeMap<T, B>) -> Self {
+		Self {
+			node_map,
+			provenance: None,
+		}
+	}
+}
+
+impl<T: Clone + Eq + Hash, B: Clone + Eq + Hash> From<Dataset<T, B>> for ExpandedDocument<T, B> {
+	/// Turns the dataset into a flattened expanded document.
+	fn from(dataset: Dataset<T, B>) -> Self {
+		dataset.node_map.into()
+	}
+}
+
+impl<T: Clone + Eq + Hash, B: Clone + Eq + Hash> FromIterator<DatasetQuad<T, B>> for Dataset<T, B>
+where
+	(): IriVocabulary<Iri = T>,
+{
+	fn from_iter<I: IntoIterator<Item = DatasetQuad<T, B>>>(iter: I) -> Self {
+		let mut result = Self::new();
+		result.extend(iter);
+		result
+	}
+}
+
+impl<T: Clone + Eq + Hash, B: Clone + Eq + Hash> Extend<DatasetQuad<T, B>> for Dataset<T, B>
+where
+	(): IriVocabulary<Iri = T>,
+{
+	fn extend<I: IntoIterator<Item = DatasetQuad<T, B>>>(&mut self, iter: I) {
+		for quad in iter {
+			// Quads are inserted without index, which never conflicts.
+			let _ = self.insert(quad);
+		}
+	}
+}
+
+impl<T: Eq + Hash, B: Eq + Hash> RdfQuads<T, B> for Dataset<T, B> {
+	fn rdf_quads_full<'a, V: Vocabulary<Iri = T, BlankId = B>, G: Generator<V>>(
+		&'a self,
+		vocabulary: &'a mut V,
+		generator: &'a mut G,
+		rdf_direction: Option<RdfDirection>,
+		produce_generalized_rdf: bool,
+	) -> Quads<'a, V, G> {
+		self.node_map.rdf_quads_full(
+			vocabulary,
+			generator,
+			rdf_direction,
+			produce_generalized_rdf,
+		)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use iref::IriBuf;
+	use rdf_types::{generator, BlankIdBuf};
+
+	fn iri(s: &str) -> ValidId<IriBuf, BlankIdBuf> {
+		ValidId::Iri(IriBuf::new(format!("https://example.com/{s}")).unwrap())
+	}
+
+	fn quad(s: &str, p: &str, o: &str, g: Option<&str>) -> DatasetQuad<IriBuf, BlankIdBuf> {
+		rdf_types::Quad(iri(s), iri(p), rdf_types::Object::Id(iri(o)), g.map(iri))
+	}
+
+	#[test]
+	fn insert_remove() {
+		let mut dataset = Dataset::new();
+		assert!(dataset.insert(quad("a", "knows", "b", None)).unwrap());
+		assert!(!dataset.insert(quad("a", "knows", "b", None)).unwrap());
+		assert!(dataset.insert(quad("a", "knows", "c", Some("g"))).unwrap());
+		assert!(dataset
+			.insert(rdf_types::Quad(
+				iri("a"),
+				iri("name"),
+				rdf_types::Object::Literal(rdf_types::Literal::new(
+					"Alice".to_owned(),
+					rdf_types::LiteralType::Any(
+						IriBuf::new("http://www.w3.org/2001/XMLSchema#string".to_owned()).unwrap()
+					)
+				)),
+				None
+			))
+			.unwrap());
+
+		let quads: Vec<_> = dataset
+			.rdf_quads(&mut generator::Blank::new(), None)
+			.cloned()
+			.collect();
+		assert_eq!(quads.len(), 3);
+
+		let document = ExpandedDocument::from(Dataset::from_iter(quads));
+		let mut round_trip =
+			Dataset::from_expanded_document(generator::Blank::new(), document).unwrap();
+		assert!(round_trip.graph(Some(&iri("g"))).is_some());
+
+		assert!(round_trip.remove(quad("a", "knows", "c", Some("g"))));
+		assert!(!round_trip.remove(quad("a", "knows", "c", Some("g"))));
+		assert!(round_trip.graph(Some(&iri("g"))).is_none());
+		assert_eq!(
+			round_trip
+				.rdf_quads(&mut generator::Blank::new(), None)
+				.count(),
+			2
+		)
+	}
+
+	#[test]
+	fn summary() {
+		let mut dataset: Dataset<IriBuf, BlankIdBuf> = Dataset::new();
+		dataset.insert(quad("a", "knows", "b", None)).unwrap();
+		dataset.insert(quad("b", "knows", "c", Some("g"))).unwrap();
+		dataset
+			.insert(rdf_types::Quad(
+				iri("a"),
+				ValidId::Iri(RDF_TYPE.to_owned()),
+				rdf_types::Object::Id(iri("Person")),
+				None,
+			))
+			.unwrap();
+		dataset
+			.insert(rdf_types::Quad(
+				ValidId::Blank(BlankIdBuf::from_suffix("x").unwrap()),
+				iri("name"),
+				rdf_types::Object::Literal(rdf_types::Literal::new(
+					"Alice".to_owned(),
+					rdf_types::LiteralType::LangString("en".parse().unwrap()),
+				)),
+				None,
+			))
+			.unwrap();
+
+		let summary = dataset.summary(&mut generator::Blank::new());
+		assert_eq!(summary.quads, 4);
+		assert_eq!(summary.nodes, 5);
+		assert_eq!(summary.graphs, 1);
+		assert_eq!(summary.predicates, 3);
+		assert_eq!(summary.blank_nodes, 1);
+		assert_eq!(summary.types[&iri("Person")], 1);
+		assert_eq!(summary.datatypes[RDF_LANG_STRING], 1);
+		assert_eq!(summary.languages.len(), 1)
+	}
+
+	#[test]
+	fn apply_update() {
+		let mut dataset = Dataset::new();
+		dataset.insert(quad("a", "knows", "b", None)).unwrap();
+
+		let update = Update::new()
+			.require(quad("a", "knows", "b", None))
+			.forbid(quad("a", "knows", "c", None))
+			.delete(quad("a", "knows", "b", None))
+			.insert(quad("a", "knows", "c", None));
+		assert!(dataset.apply_update(update.clone()).is_ok());
+		assert!(dataset.contains(&quad("a", "knows", "c", None)));
+		assert!(!dataset.contains(&quad("a", "knows", "b", None)));
+
+		// Conflicting updates leave the dataset untouched.
+		assert!(matches!(
+			dataset.apply_update(update),
+			Err(UpdateConflict::Missing(_))
+		));
+		assert!(matches!(
+			dataset.apply_update(
+				Update::new()
+					.insert(quad("a", "knows", "d", None))
+					.forbid(quad("a", "knows", "c", None))
+			),
+			Err(UpdateConflict::Present(_))
+		));
+		assert!(!dataset.contains(&quad("a", "knows", "d", None)))
+	}
+
+	#[test]
+	fn materialize_rdfs() {
+		use crate::rdf::{RDFS_DOMAIN, RDFS_RANGE, RDFS_SUB_CLASS_OF, RDFS_SUB_PROPERTY_OF};
+		let rdf = |iri: &iref::Iri| ValidId::Iri(iri.to_owned());
+		let schema = |s: &str, p: &iref::Iri, o: &str| {
+			rdf_types::Quad(iri(s), rdf(p), rdf_types::Object::Id(iri(o)), None)
+		};
+
+		let mut dataset = Dataset::new();
+		dataset
+			.insert(schema("author", RDFS_DOMAIN, "CreativeWork"))
+			.unwrap();
+		dataset
+			.insert(schema("author", RDFS_RANGE, "Person"))
+			.unwrap();
+		dataset
+			.insert(schema("Person", RDFS_SUB_CLASS_OF, "Thing"))
+			.unwrap();
+		dataset
+			.insert(schema("writer", RDFS_SUB_PROPERTY_OF, "author"))
+			.unwrap();
+		dataset.insert(quad("a", "writer", "b", Some("g"))).unwrap();
+
+		let inferred = dataset
+			.materialize_rdfs(&mut generator::Blank::new())
+			.unwrap();
+		let ty = |s: &str, o: &str| {
+			rdf_types::Quad(
+				iri(s),
+				rdf(RDF_TYPE),
+				rdf_types::Object::Id(iri(o)),
+				Some(iri("g")),
+			)
+		};
+		assert_eq!(inferred.len(), 4);
+		assert!(inferred.contains(&quad("a", "author", "b", Some("g"))));
+		assert!(inferred.contains(&ty("a", "CreativeWork")));
+		assert!(inferred.contains(&ty("b", "Person")));
+		assert!(inferred.contains(&ty("b", "Thing")));
+		assert!(dataset.contains(&ty("b", "Thing")));
+
+		dataset.retract(&inferred);
+		assert!(!dataset.contains(&ty("b", "Thing")));
+		assert_eq!(
+			dataset
+				.rdf_quads(&mut generator::Blank::new(), None)
+				.count(),
+			5
+		)
+	}
+
+	#[test]
+	fn provenance() {
+		use crate::TryFromJson;
+		let document = |json| ExpandedDocument::try_from_json_in(&mut (), json).unwrap();
+		let a = document(json_syntax::json!([{
+			"@id": "https://example.com/a",
+			"https://example.com/knows": [{ "@id": "https://example.com/b" }]
+		}]));
+		let b = document(json_syntax::json!([{
+			"@id": "https://example.com/a",
+			"https://example.com/knows": [
+				{ "@id": "https://example.com/b" },
+				{ "@id": "https://example.com/c" }
+			]
+		}]));
+
+		let mut generator = generator::Blank::new();
+		let mut dataset = Dataset::new();
+		dataset
+			.merge_document(&mut generator, iri("doc-a"), a)
+			.unwrap();
+		dataset
+			.merge_document(&mut generator, iri("doc-b"), b)
+			.unwrap();
+
+		let provenance = dataset.provenance().unwrap();
+		let mut sources: Vec<_> = provenance
+			.sources(&quad("a", "knows", "b", None))
+			.map(|s| s.as_str())
+			.collect();
+		sources.sort();
+		assert_eq!(
+			sources,
+			["https://example.com/doc-a", "https://example.com/doc-b"]
+		);
+		assert_eq!(provenance.quads_from(&iri("doc-b")).count(), 2);
+
+		assert_eq!(
+			dataset.remove_source(&iri("doc-b")),
+			[quad("a", "knows", "c", None)]
+		);
+		assert!(dataset.contains(&quad("a", "knows", "b", None)));
+		assert!(!dataset.contains(&quad("a", "knows", "c", None)));
+
+		// Quads also inserted without a source are kept.
+		dataset.insert(quad("a", "knows", "b", None)).unwrap();
+		dataset
+			.insert_from(quad("a", "knows", "d", None), iri("doc-c"))
+			.unwrap();
+		dataset.insert(quad("a", "knows", "d", None)).unwrap();
+		assert!(dataset.remove_source(&iri("doc-a")).is_empty());
+		assert!(dataset.remove_source(&iri("doc-c")).is_empty());
+		assert!(dataset.contains(&quad("a", "knows", "b", None)));
+		assert!(dataset.contains(&quad("a", "knows", "d", None)))
+	}
+}
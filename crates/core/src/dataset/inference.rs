@@ -0,0 +1,235 @@
+use super::{Dataset, DatasetQuad};
+use crate::{
+	flattening::ConflictingIndexes,
+	rdf::{RDFS_DOMAIN, RDFS_RANGE, RDFS_SUB_CLASS_OF, RDFS_SUB_PROPERTY_OF, RDF_TYPE},
+	Id, ValidId,
+};
+use rdf_types::{
+	vocabulary::{IriVocabulary, IriVocabularyMut, LiteralVocabularyMut},
+	Generator, Vocabulary,
+};
+use std::collections::{hash_set, HashMap, HashSet};
+use std::hash::Hash;
+
+/// Quads inferred by [`Dataset::materialize_rdfs_with`].
+///
+/// Inferred quads are stored in the dataset like asserted ones, so that
+/// they are visible in its JSON-LD view. This set tells them apart, and can
+/// be used to [retract](Dataset::retract_with) them.
+#[derive(Debug, Clone)]
+pub struct Inferred<T, B> {
+	quads: HashSet<DatasetQuad<T, B>>,
+}
+
+impl<T, B> Inferred<T, B> {
+	/// Returns the number of inferred quads.
+	pub fn len(&self) -> usize {
+		self.quads.len()
+	}
+
+	/// Checks if no quad was inferred.
+	pub fn is_empty(&self) -> bool {
+		self.quads.is_empty()
+	}
+
+	/// Returns an iterator over the inferred quads.
+	pub fn iter(&self) -> hash_set::Iter<'_, DatasetQuad<T, B>> {
+		self.quads.iter()
+	}
+}
+
+impl<T: Eq + Hash, B: Eq + Hash> Inferred<T, B> {
+	/// Checks if the given quad was inferred.
+	pub fn contains(&self, quad: &DatasetQuad<T, B>) -> bool {
+		self.quads.contains(quad)
+	}
+}
+
+impl<'a, T, B> IntoIterator for &'a Inferred<T, B> {
+	type Item = &'a DatasetQuad<T, B>;
+	type IntoIter = hash_set::Iter<'a, DatasetQuad<T, B>>;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.iter()
+	}
+}
+
+/// RDFS schema relations, indexed by subject.
+struct Schema<T, B> {
+	sub_class_of: HashMap<ValidId<T, B>, Vec<ValidId<T, B>>>,
+	sub_property_of: HashMap<ValidId<T, B>, Vec<ValidId<T, B>>>,
+	domain: HashMap<ValidId<T, B>, Vec<ValidId<T, B>>>,
+	range: HashMap<ValidId<T, B>, Vec<ValidId<T, B>>>,
+}
+
+impl<T: Clone + Eq + Hash, B: Clone + Eq + Hash> Dataset<T, B> {
+	/// Materializes the quads entailed by the core RDFS rules.
+	///
+	/// The `rdfs:subClassOf`, `rdfs:subPropertyOf`, `rdfs:domain` and
+	/// `rdfs:range` statements of every graph form the schema. The following
+	/// rules are then applied, until no new quad is produced, in the graph of
+	/// the quad they are applied to:
+	///   - `rdfs2`/`rdfs3`: the subject (resp. object) of a property with a
+	///     domain (resp. range) is given the domain (resp. range) as type;
+	///   - `rdfs7`: a statement is repeated for every superproperty of its
+	///     property;
+	///   - `rdfs9`: a node is given every superclass of its types as type.
+	///
+	/// Anonymous values (such as lists) are not inferred about. The schema
+	/// itself is not extended.
+	///
+	/// Returns the inferred quads, that were not already in the dataset.
+	pub fn materialize_rdfs_with<V, G: Generator<V>>(
+		&mut self,
+		vocabulary: &mut V,
+		generator: &mut G,
+	) -> Result<Inferred<T, B>, ConflictingIndexes<T, B>>
+	where
+		V: Vocabulary<Iri = T, BlankId = B> + IriVocabularyMut + LiteralVocabularyMut,
+		V::Literal: Clone,
+	{
+		let quads: Vec<DatasetQuad<T, B>> = self
+			.owned_quads_with(vocabulary, generator)
+			.into_iter()
+			.filter(|quad| !self.is_anonymous(quad))
+			.collect();
+
+		let rdf_type = ValidId::Iri(vocabulary.insert(RDF_TYPE));
+		let sub_class_of = ValidId::Iri(vocabulary.insert(RDFS_SUB_CLASS_OF));
+		let sub_property_of = ValidId::Iri(vocabulary.insert(RDFS_SUB_PROPERTY_OF));
+		let domain = ValidId::Iri(vocabulary.insert(RDFS_DOMAIN));
+		let range = ValidId::Iri(vocabulary.insert(RDFS_RANGE));
+
+		let mut schema = Schema {
+			sub_class_of: HashMap::new(),
+			sub_property_of: HashMap::new(),
+			domain: HashMap::new(),
+			range: HashMap::new(),
+		};
+
+		for rdf_types::Quad(s, p, o, _) in &quads {
+			if let rdf_types::Object::Id(o) = o {
+				let relation = if *p == sub_class_of {
+					&mut schema.sub_class_of
+				} else if *p == sub_property_of {
+					&mut schema.sub_property_of
+				} else if *p == domain {
+					&mut schema.domain
+				} else if *p == range {
+					&mut schema.range
+				} else {
+					continue;
+				};
+
+				relation.entry(s.clone()).or_default().push(o.clone())
+			}
+		}
+
+		let mut known: HashSet<_> = quads.iter().cloned().collect();
+		let mut inferred = HashSet::new();
+		let mut stack = quads;
+
+		while let Some(rdf_types::Quad(s, p, o, g)) = stack.pop() {
+			let mut entailed = Vec::new();
+
+			for q in schema.sub_property_of.get(&p).into_iter().flatten() {
+				entailed.push(rdf_types::Quad(s.clone(), q.clone(), o.clone(), g.clone()))
+			}
+
+			for c in schema.domain.get(&p).into_iter().flatten() {
+				entailed.push(rdf_types::Quad(
+					s.clone(),
+					rdf_type.clone(),
+					rdf_types::Object::Id(c.clone()),
+					g.clone(),
+				))
+			}
+
+			if let rdf_types::Object::Id(o) = &o {
+				for c in schema.range.get(&p).into_iter().flatten() {
+					entailed.push(rdf_types::Quad(
+						o.clone(),
+						rdf_type.clone(),
+						rdf_types::Object::Id(c.clone()),
+						g.clone(),
+					))
+				}
+
+				if p == rdf_type {
+					for c in schema.sub_class_of.get(o).into_iter().flatten() {
+						entailed.push(rdf_types::Quad(
+							s.clone(),
+							rdf_type.clone(),
+							rdf_types::Object::Id(c.clone()),
+							g.clone(),
+						))
+					}
+				}
+			}
+
+			for quad in entailed {
+				if known.insert(quad.clone()) {
+					inferred.insert(quad.clone());
+					stack.push(quad)
+				}
+			}
+		}
+
+		for quad in &inferred {
+			self.insert_with(vocabulary, quad.clone())?;
+		}
+
+		Ok(Inferred { quads: inferred })
+	}
+
+	/// Materializes the quads entailed by the core RDFS rules.
+	///
+	/// See [`Self::materialize_rdfs_with`].
+	pub fn materialize_rdfs<G: Generator>(
+		&mut self,
+		generator: &mut G,
+	) -> Result<Inferred<T, B>, ConflictingIndexes<T, B>>
+	where
+		(): Vocabulary<Iri = T, BlankId = B>,
+	{
+		self.materialize_rdfs_with(rdf_types::vocabulary::no_vocabulary_mut(), generator)
+	}
+
+	/// Removes the given inferred quads.
+	pub fn retract_with(
+		&mut self,
+		vocabulary: &impl IriVocabulary<Iri = T>,
+		inferred: &Inferred<T, B>,
+	) {
+		for quad in inferred {
+			self.remove_with(vocabulary, quad.clone());
+		}
+	}
+
+	/// Removes the given inferred quads.
+	///
+	/// See [`Self::retract_with`].
+	pub fn retract(&mut self, inferred: &Inferred<T, B>)
+	where
+		(): IriVocabulary<Iri = T>,
+	{
+		self.retract_with(&(), inferred)
+	}
+
+	/// Checks if the given quad is about an anonymous value, such as a list,
+	/// that has no node in the dataset.
+	fn is_anonymous(&self, rdf_types::Quad(s, _, o, g): &DatasetQuad<T, B>) -> bool {
+		let graph = match self.node_map.graph(g.clone().map(Id::Valid).as_ref()) {
+			Some(graph) => graph,
+			None => return true,
+		};
+
+		let is_node = |id: &ValidId<T, B>| graph.contains(&Id::Valid(id.clone()));
+
+		!is_node(s)
+			|| match o {
+				rdf_types::Object::Id(id @ ValidId::Blank(_)) => !is_node(id),
+				_ => false,
+			}
+	}
+}
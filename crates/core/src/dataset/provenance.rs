@@ -0,0 +1,234 @@
+use super::{Dataset, DatasetQuad};
+use crate::{flattening::ConflictingIndexes, ExpandedDocument, ValidId};
+use rdf_types::{
+	vocabulary::{IriVocabulary, IriVocabularyMut, LiteralVocabularyMut},
+	Generator, Vocabulary,
+};
+use std::collections::{hash_map, HashMap, HashSet};
+use std::hash::Hash;
+
+/// Quad provenance.
+///
+/// Records the sources (typically document IRIs or graph names) each quad of
+/// a [`Dataset`] was inserted from, answering "where did this value come
+/// from" questions when aggregating multiple documents.
+#[derive(Debug, Clone)]
+pub struct Provenance<T, B> {
+	sources: HashMap<DatasetQuad<T, B>, HashSet<ValidId<T, B>>>,
+
+	/// Quads with a known source that are also in the dataset without
+	/// source.
+	unsourced: HashSet<DatasetQuad<T, B>>,
+}
+
+impl<T, B> Provenance<T, B> {
+	fn new() -> Self {
+		Self {
+			sources: HashMap::new(),
+			unsourced: HashSet::new(),
+		}
+	}
+
+	/// Returns the number of quads with a known source.
+	pub fn len(&self) -> usize {
+		self.sources.len()
+	}
+
+	/// Checks if no quad has a known source.
+	pub fn is_empty(&self) -> bool {
+		self.sources.is_empty()
+	}
+
+	/// Returns an iterator over the quads with a known source, and their
+	/// sources.
+	pub fn iter(&self) -> hash_map::Iter<'_, DatasetQuad<T, B>, HashSet<ValidId<T, B>>> {
+		self.sources.iter()
+	}
+}
+
+impl<T: Eq + Hash, B: Eq + Hash> Provenance<T, B> {
+	/// Returns the sources of the given quad.
+	pub fn sources(&self, quad: &DatasetQuad<T, B>) -> impl Iterator<Item = &ValidId<T, B>> {
+		self.sources.get(quad).into_iter().flatten()
+	}
+
+	/// Returns the quads inserted from the given source.
+	pub fn quads_from<'a>(
+		&'a self,
+		source: &'a ValidId<T, B>,
+	) -> impl Iterator<Item = &'a DatasetQuad<T, B>> {
+		self.sources
+			.iter()
+			.filter(move |(_, sources)| sources.contains(source))
+			.map(|(quad, _)| quad)
+	}
+
+	pub(super) fn has_sources(&self, quad: &DatasetQuad<T, B>) -> bool {
+		self.sources.contains_key(quad)
+	}
+
+	/// Records that the given quad is also in the dataset without source.
+	pub(super) fn insert_unsourced(&mut self, quad: DatasetQuad<T, B>) {
+		self.unsourced.insert(quad);
+	}
+
+	fn insert(&mut self, quad: DatasetQuad<T, B>, source: ValidId<T, B>) {
+		self.sources.entry(quad).or_default().insert(source);
+	}
+
+	pub(super) fn remove(&mut self, quad: &DatasetQuad<T, B>) {
+		self.sources.remove(quad);
+		self.unsourced.remove(quad);
+	}
+}
+
+impl<'a, T, B> IntoIterator for &'a Provenance<T, B> {
+	type Item = (&'a DatasetQuad<T, B>, &'a HashSet<ValidId<T, B>>);
+	type IntoIter = hash_map::Iter<'a, DatasetQuad<T, B>, HashSet<ValidId<T, B>>>;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.iter()
+	}
+}
+
+impl<T, B> Dataset<T, B> {
+	/// Returns the provenance of the quads, if tracked.
+	pub fn provenance(&self) -> Option<&Provenance<T, B>> {
+		self.provenance.as_ref()
+	}
+
+	/// Stops tracking the provenance of the quads, and forgets it.
+	pub fn disable_provenance(&mut self) {
+		self.provenance = None
+	}
+}
+
+impl<T: Clone + Eq + Hash, B: Clone + Eq + Hash> Dataset<T, B> {
+	/// Inserts the given quad, recording that it comes from `source`.
+	///
+	/// This starts tracking the provenance of the quads if not already
+	/// enabled. Quads inserted without a source, or modified through
+	/// [`Self::node_map_mut`], have no known provenance.
+	///
+	/// Returns `true` if the quad was not already in the dataset.
+	pub fn insert_from_with(
+		&mut self,
+		vocabulary: &impl IriVocabulary<Iri = T>,
+		quad: DatasetQuad<T, B>,
+		source: ValidId<T, B>,
+	) -> Result<bool, ConflictingIndexes<T, B>> {
+		let inserted = self.insert_quad_with(vocabulary, quad.clone())?;
+		let provenance = self.provenance.get_or_insert_with(Provenance::new);
+
+		if !inserted && !provenance.has_sources(&quad) {
+			// The quad was already in the dataset, without source.
+			provenance.insert_unsourced(quad.clone());
+		}
+
+		provenance.insert(quad, source);
+		Ok(inserted)
+	}
+
+	/// Inserts the given quad, recording that it comes from `source`.
+	///
+	/// See [`Self::insert_from_with`].
+	pub fn insert_from(
+		&mut self,
+		quad: DatasetQuad<T, B>,
+		source: ValidId<T, B>,
+	) -> Result<bool, ConflictingIndexes<T, B>>
+	where
+		(): IriVocabulary<Iri = T>,
+	{
+		self.insert_from_with(&(), quad, source)
+	}
+
+	/// Merges the quads of the given expanded document into this dataset,
+	/// recording that they come from `source`.
+	///
+	/// Anonymous nodes are assigned an identifier using the given
+	/// `generator`, that should be shared by every merged document to avoid
+	/// blank node identifier collisions.
+	pub fn merge_document_with<V, G: Generator<V>>(
+		&mut self,
+		vocabulary: &mut V,
+		mut generator: G,
+		source: ValidId<T, B>,
+		document: ExpandedDocument<T, B>,
+	) -> Result<(), ConflictingIndexes<T, B>>
+	where
+		V: Vocabulary<Iri = T, BlankId = B> + IriVocabularyMut + LiteralVocabularyMut,
+		V::Literal: Clone,
+	{
+		let dataset = Self::from_expanded_document_with(vocabulary, &mut generator, document)?;
+		for quad in dataset.owned_quads_with(vocabulary, &mut generator) {
+			self.insert_from_with(vocabulary, quad, source.clone())?;
+		}
+
+		Ok(())
+	}
+
+	/// Merges the quads of the given expanded document into this dataset,
+	/// recording that they come from `source`.
+	///
+	/// See [`Self::merge_document_with`].
+	pub fn merge_document<G: Generator>(
+		&mut self,
+		generator: G,
+		source: ValidId<T, B>,
+		document: ExpandedDocument<T, B>,
+	) -> Result<(), ConflictingIndexes<T, B>>
+	where
+		(): Vocabulary<Iri = T, BlankId = B>,
+	{
+		self.merge_document_with(
+			rdf_types::vocabulary::no_vocabulary_mut(),
+			generator,
+			source,
+			document,
+		)
+	}
+
+	/// Removes the given source.
+	///
+	/// Quads that only come from `source` are removed from the dataset.
+	/// Quads also coming from other sources, or also inserted without a
+	/// source, are kept. Returns the removed quads.
+	pub fn remove_source_with(
+		&mut self,
+		vocabulary: &impl IriVocabulary<Iri = T>,
+		source: &ValidId<T, B>,
+	) -> Vec<DatasetQuad<T, B>> {
+		let mut removed = Vec::new();
+
+		if let Some(Provenance { sources, unsourced }) = &mut self.provenance {
+			sources.retain(|quad, quad_sources| {
+				if quad_sources.remove(source) && quad_sources.is_empty() {
+					if !unsourced.remove(quad) {
+						removed.push(quad.clone());
+					}
+
+					false
+				} else {
+					true
+				}
+			});
+		}
+
+		for quad in &removed {
+			self.remove_with(vocabulary, quad.clone());
+		}
+
+		removed
+	}
+
+	/// Removes the given source.
+	///
+	/// See [`Self::remove_source_with`].
+	pub fn remove_source(&mut self, source: &ValidId<T, B>) -> Vec<DatasetQuad<T, B>>
+	where
+		(): IriVocabulary<Iri = T>,
+	{
+		self.remove_source_with(&(), source)
+	}
+}
@@ -0,0 +1,166 @@
+use super::{Dataset, DatasetQuad};
+use crate::flattening::ConflictingIndexes;
+use rdf_types::vocabulary::IriVocabulary;
+use std::hash::Hash;
+
+/// Dataset update.
+///
+/// Programmatic equivalent of a SPARQL `DELETE DATA`/`INSERT DATA` request
+/// guarded by preconditions, built with
+/// `Update::new().require(q1).delete(q2).insert(q3)` and applied atomically
+/// with [`Dataset::apply_update_with`]. The update fails without modifying
+/// the dataset if a required or deleted quad is missing, or if a forbidden
+/// quad is present.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Update<T, B> {
+	required: Vec<DatasetQuad<T, B>>,
+	forbidden: Vec<DatasetQuad<T, B>>,
+	pub(super) deleted: Vec<DatasetQuad<T, B>>,
+	pub(super) inserted: Vec<DatasetQuad<T, B>>,
+}
+
+impl<T, B> Default for Update<T, B> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<T, B> Update<T, B> {
+	/// Creates a new empty update.
+	pub fn new() -> Self {
+		Self {
+			required: Vec::new(),
+			forbidden: Vec::new(),
+			deleted: Vec::new(),
+			inserted: Vec::new(),
+		}
+	}
+
+	/// Requires the given quad to be in the dataset.
+	pub fn require(mut self, quad: DatasetQuad<T, B>) -> Self {
+		self.required.push(quad);
+		self
+	}
+
+	/// Requires the given quad not to be in the dataset.
+	pub fn forbid(mut self, quad: DatasetQuad<T, B>) -> Self {
+		self.forbidden.push(quad);
+		self
+	}
+
+	/// Deletes the given quad, that must be in the dataset.
+	pub fn delete(mut self, quad: DatasetQuad<T, B>) -> Self {
+		self.deleted.push(quad);
+		self
+	}
+
+	/// Inserts the given quad.
+	///
+	/// Inserted quads are added after the deleted quads are removed.
+	pub fn insert(mut self, quad: DatasetQuad<T, B>) -> Self {
+		self.inserted.push(quad);
+		self
+	}
+
+	/// Returns the quads required to be in the dataset.
+	pub fn required(&self) -> &[DatasetQuad<T, B>] {
+		&self.required
+	}
+
+	/// Returns the quads required not to be in the dataset.
+	pub fn forbidden(&self) -> &[DatasetQuad<T, B>] {
+		&self.forbidden
+	}
+
+	/// Returns the deleted quads.
+	pub fn deleted(&self) -> &[DatasetQuad<T, B>] {
+		&self.deleted
+	}
+
+	/// Returns the inserted quads.
+	pub fn inserted(&self) -> &[DatasetQuad<T, B>] {
+		&self.inserted
+	}
+
+	/// Checks if this update is empty.
+	pub fn is_empty(&self) -> bool {
+		self.required.is_empty()
+			&& self.forbidden.is_empty()
+			&& self.deleted.is_empty()
+			&& self.inserted.is_empty()
+	}
+}
+
+/// Update conflict.
+///
+/// Raised when the preconditions of an [`Update`] do not hold, or when an
+/// inserted quad conflicts with the dataset.
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum UpdateConflict<T, B> {
+	/// A required or deleted quad is not in the dataset.
+	#[error("missing quad")]
+	Missing(DatasetQuad<T, B>),
+
+	/// A forbidden quad is in the dataset.
+	#[error("unexpected quad")]
+	Present(DatasetQuad<T, B>),
+
+	/// An inserted quad conflicts with the index of its subject node.
+	#[error(transparent)]
+	ConflictingIndexes(#[from] ConflictingIndexes<T, B>),
+}
+
+impl<T: Clone + Eq + Hash, B: Clone + Eq + Hash> Dataset<T, B> {
+	/// Applies the given update.
+	///
+	/// Every precondition is checked before the dataset is modified: if one
+	/// does not hold, the dataset is left untouched.
+	pub fn apply_update_with(
+		&mut self,
+		vocabulary: &impl IriVocabulary<Iri = T>,
+		update: Update<T, B>,
+	) -> Result<(), UpdateConflict<T, B>> {
+		self.check_update_with(vocabulary, &update)?;
+
+		for quad in update.deleted {
+			self.remove_with(vocabulary, quad);
+		}
+
+		for quad in update.inserted {
+			self.insert_with(vocabulary, quad)?;
+		}
+
+		Ok(())
+	}
+
+	/// Checks the preconditions of the given update.
+	pub(super) fn check_update_with(
+		&self,
+		vocabulary: &impl IriVocabulary<Iri = T>,
+		update: &Update<T, B>,
+	) -> Result<(), UpdateConflict<T, B>> {
+		for quad in update.required.iter().chain(&update.deleted) {
+			if !self.contains_with(vocabulary, quad) {
+				return Err(UpdateConflict::Missing(quad.clone()));
+			}
+		}
+
+		for quad in &update.forbidden {
+			if self.contains_with(vocabulary, quad) {
+				return Err(UpdateConflict::Present(quad.clone()));
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Applies the given update.
+	///
+	/// See [`Self::apply_update_with`].
+	pub fn apply_update(&mut self, update: Update<T, B>) -> Result<(), UpdateConflict<T, B>>
+	where
+		(): IriVocabulary<Iri = T>,
+	{
+		self.apply_update_with(&(), update)
+	}
+}
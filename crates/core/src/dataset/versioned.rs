@@ -0,0 +1,347 @@
+use super::{Dataset, DatasetQuad, Update, UpdateConflict};
+use crate::{flattening::ConflictingIndexes, ExpandedDocument};
+use json_ld_syntax::IntoJsonWithContext;
+use rdf_types::{vocabulary::IriVocabulary, Vocabulary};
+use std::hash::Hash;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Dataset change.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Change<T, B> {
+	/// The quad was inserted.
+	Insert(DatasetQuad<T, B>),
+
+	/// The quad was removed.
+	Remove(DatasetQuad<T, B>),
+}
+
+/// Set of changes made to a [`VersionedDataset`] by a single mutation.
+#[derive(Debug, Clone)]
+pub struct Changeset<T, B> {
+	/// Time of the mutation.
+	pub timestamp: SystemTime,
+
+	/// Changes, in the order they were made.
+	pub changes: Vec<Change<T, B>>,
+}
+
+impl<T, B> Changeset<T, B> {
+	/// Returns the inserted quads.
+	pub fn inserted(&self) -> impl Iterator<Item = &DatasetQuad<T, B>> {
+		self.changes.iter().filter_map(|change| match change {
+			Change::Insert(quad) => Some(quad),
+			Change::Remove(_) => None,
+		})
+	}
+
+	/// Returns the removed quads.
+	pub fn removed(&self) -> impl Iterator<Item = &DatasetQuad<T, B>> {
+		self.changes.iter().filter_map(|change| match change {
+			Change::Insert(_) => None,
+			Change::Remove(quad) => Some(quad),
+		})
+	}
+}
+
+impl<T: Clone + Eq + Hash, B: Clone + Eq + Hash> Changeset<T, B> {
+	/// Exports this changeset as a JSON-LD patch document.
+	///
+	/// The removed and inserted quads are given as flattened expanded
+	/// documents, to be applied in this order:
+	///
+	/// ```json
+	/// {
+	///   "timestamp": 1700000000000,
+	///   "delete": [ ... ],
+	///   "insert": [ ... ]
+	/// }
+	/// ```
+	///
+	/// The timestamp is given in milliseconds since the Unix epoch.
+	pub fn to_json_with(
+		&self,
+		vocabulary: &impl Vocabulary<Iri = T, BlankId = B>,
+	) -> Result<json_syntax::Value, ConflictingIndexes<T, B>> {
+		let timestamp = self
+			.timestamp
+			.duration_since(UNIX_EPOCH)
+			.map(|d| d.as_millis() as u64)
+			.unwrap_or_default();
+
+		let document = |quads: Vec<DatasetQuad<T, B>>| {
+			let mut dataset = Dataset::new();
+			for quad in quads {
+				dataset.insert_with(vocabulary, quad)?;
+			}
+
+			Ok(ExpandedDocument::from(dataset).into_json_with(vocabulary))
+		};
+
+		let mut result = json_syntax::Object::new();
+		result.insert("timestamp".into(), timestamp.into());
+		result.insert(
+			"delete".into(),
+			document(self.removed().cloned().collect())?,
+		);
+		result.insert(
+			"insert".into(),
+			document(self.inserted().cloned().collect())?,
+		);
+		Ok(json_syntax::Value::Object(result))
+	}
+
+	/// Exports this changeset as a JSON-LD patch document.
+	///
+	/// See [`Self::to_json_with`].
+	pub fn to_json(&self) -> Result<json_syntax::Value, ConflictingIndexes<T, B>>
+	where
+		(): Vocabulary<Iri = T, BlankId = B>,
+	{
+		self.to_json_with(&())
+	}
+}
+
+/// Versioned dataset.
+///
+/// Wraps a [`Dataset`] and records every mutation as a timestamped
+/// [`Changeset`], so that earlier versions can be checked out and changes
+/// audited. Version `n` is the state of the dataset after its first `n`
+/// changesets, starting from an empty dataset.
+///
+/// Mutations that have no effect (e.g. inserting a quad already in the
+/// dataset) are not recorded.
+#[derive(Default)]
+pub struct VersionedDataset<T, B> {
+	dataset: Dataset<T, B>,
+	changesets: Vec<Changeset<T, B>>,
+}
+
+impl<T, B> VersionedDataset<T, B> {
+	/// Creates a new empty versioned dataset.
+	pub fn new() -> Self {
+		Self {
+			dataset: Dataset::new(),
+			changesets: Vec::new(),
+		}
+	}
+
+	/// Returns the current version of the dataset.
+	pub fn dataset(&self) -> &Dataset<T, B> {
+		&self.dataset
+	}
+
+	/// Turns this versioned dataset into its current version, dropping the
+	/// change log.
+	pub fn into_dataset(self) -> Dataset<T, B> {
+		self.dataset
+	}
+
+	/// Returns the current version number, which is the number of recorded
+	/// changesets.
+	pub fn version(&self) -> usize {
+		self.changesets.len()
+	}
+
+	/// Returns the change log.
+	pub fn changesets(&self) -> &[Changeset<T, B>] {
+		&self.changesets
+	}
+
+	fn record(&mut self, changes: Vec<Change<T, B>>) {
+		if !changes.is_empty() {
+			self.changesets.push(Changeset {
+				timestamp: SystemTime::now(),
+				changes,
+			})
+		}
+	}
+}
+
+impl<T: Clone + Eq + Hash, B: Clone + Eq + Hash> VersionedDataset<T, B> {
+	/// Inserts the given quad.
+	///
+	/// See [`Dataset::insert_with`].
+	pub fn insert_with(
+		&mut self,
+		vocabulary: &impl IriVocabulary<Iri = T>,
+		quad: DatasetQuad<T, B>,
+	) -> Result<bool, ConflictingIndexes<T, B>> {
+		let inserted = self.dataset.insert_with(vocabulary, quad.clone())?;
+		if inserted {
+			self.record(vec![Change::Insert(quad)])
+		}
+
+		Ok(inserted)
+	}
+
+	/// Inserts the given quad.
+	///
+	/// See [`Self::insert_with`].
+	pub fn insert(&mut self, quad: DatasetQuad<T, B>) -> Result<bool, ConflictingIndexes<T, B>>
+	where
+		(): IriVocabulary<Iri = T>,
+	{
+		self.insert_with(&(), quad)
+	}
+
+	/// Removes the given quad.
+	///
+	/// See [`Dataset::remove_with`].
+	pub fn remove_with(
+		&mut self,
+		vocabulary: &impl IriVocabulary<Iri = T>,
+		quad: DatasetQuad<T, B>,
+	) -> bool {
+		let removed = self.dataset.remove_with(vocabulary, quad.clone());
+		if removed {
+			self.record(vec![Change::Remove(quad)])
+		}
+
+		removed
+	}
+
+	/// Removes the given quad.
+	///
+	/// See [`Self::remove_with`].
+	pub fn remove(&mut self, quad: DatasetQuad<T, B>) -> bool
+	where
+		(): IriVocabulary<Iri = T>,
+	{
+		self.remove_with(&(), quad)
+	}
+
+	/// Applies the given update, recorded as a single changeset.
+	///
+	/// See [`Dataset::apply_update_with`].
+	pub fn apply_update_with(
+		&mut self,
+		vocabulary: &impl IriVocabulary<Iri = T>,
+		update: Update<T, B>,
+	) -> Result<(), UpdateConflict<T, B>> {
+		self.dataset.check_update_with(vocabulary, &update)?;
+
+		let mut changes = Vec::new();
+		for quad in update.deleted {
+			if self.dataset.remove_with(vocabulary, quad.clone()) {
+				changes.push(Change::Remove(quad))
+			}
+		}
+
+		let mut result = Ok(());
+		for quad in update.inserted {
+			match self.dataset.insert_with(vocabulary, quad.clone()) {
+				Ok(true) => changes.push(Change::Insert(quad)),
+				Ok(false) => (),
+				Err(e) => {
+					result = Err(e.into());
+					break;
+				}
+			}
+		}
+
+		// Changes made before a failure are still recorded.
+		self.record(changes);
+		result
+	}
+
+	/// Applies the given update, recorded as a single changeset.
+	///
+	/// See [`Self::apply_update_with`].
+	pub fn apply_update(&mut self, update: Update<T, B>) -> Result<(), UpdateConflict<T, B>>
+	where
+		(): IriVocabulary<Iri = T>,
+	{
+		self.apply_update_with(&(), update)
+	}
+
+	/// Rebuilds the given version of the dataset.
+	///
+	/// Returns `None` if `version` is greater than the current version.
+	pub fn checkout_with(
+		&self,
+		vocabulary: &impl IriVocabulary<Iri = T>,
+		version: usize,
+	) -> Result<Option<Dataset<T, B>>, ConflictingIndexes<T, B>> {
+		let Some(changesets) = self.changesets.get(..version) else {
+			return Ok(None);
+		};
+
+		let mut dataset = Dataset::new();
+		for changeset in changesets {
+			for change in &changeset.changes {
+				match change {
+					Change::Insert(quad) => dataset.insert_with(vocabulary, quad.clone())?,
+					Change::Remove(quad) => dataset.remove_with(vocabulary, quad.clone()),
+				};
+			}
+		}
+
+		Ok(Some(dataset))
+	}
+
+	/// Rebuilds the given version of the dataset.
+	///
+	/// See [`Self::checkout_with`].
+	pub fn checkout(
+		&self,
+		version: usize,
+	) -> Result<Option<Dataset<T, B>>, ConflictingIndexes<T, B>>
+	where
+		(): IriVocabulary<Iri = T>,
+	{
+		self.checkout_with(&(), version)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::ValidId;
+	use iref::IriBuf;
+	use rdf_types::BlankIdBuf;
+
+	fn quad(s: &str, p: &str, o: &str) -> DatasetQuad<IriBuf, BlankIdBuf> {
+		let iri = |s: &str| ValidId::Iri(IriBuf::new(format!("https://example.com/{s}")).unwrap());
+		rdf_types::Quad(iri(s), iri(p), rdf_types::Object::Id(iri(o)), None)
+	}
+
+	#[test]
+	fn versioned_dataset() {
+		let mut dataset = VersionedDataset::new();
+		assert!(dataset.insert(quad("a", "knows", "b")).unwrap());
+		assert!(!dataset.insert(quad("a", "knows", "b")).unwrap());
+		dataset
+			.apply_update(
+				Update::new()
+					.delete(quad("a", "knows", "b"))
+					.insert(quad("a", "knows", "c")),
+			)
+			.unwrap();
+		assert_eq!(dataset.version(), 2);
+
+		let first = dataset.checkout(1).unwrap().unwrap();
+		assert!(first.contains(&quad("a", "knows", "b")));
+		assert!(!first.contains(&quad("a", "knows", "c")));
+		assert!(dataset.checkout(3).unwrap().is_none());
+
+		let json = dataset.changesets()[1].to_json().unwrap();
+		let object = json.as_object().unwrap();
+		assert_eq!(
+			object.get_unique("delete").unwrap().unwrap(),
+			&json_syntax::json!([{
+				"@id": "https://example.com/a",
+				"https://example.com/knows": [{ "@id": "https://example.com/b" }]
+			}])
+		);
+		assert_eq!(
+			object
+				.get_unique("insert")
+				.unwrap()
+				.unwrap()
+				.as_array()
+				.unwrap()
+				.len(),
+			1
+		)
+	}
+}
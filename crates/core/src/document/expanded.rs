@@ -1,10 +1,15 @@
-use crate::object::{FragmentRef, InvalidExpandedJson, Traverse};
-use crate::{Id, Indexed, IndexedObject, Node, Object, Relabel, TryFromJson};
+use crate::object::transform::{transform_objects, MapNodes, MapValues, RetainNodes};
+use crate::object::{DeduplicateOptions, FragmentRef, InvalidExpandedJson, Traverse};
+use crate::{
+	Id, Indexed, IndexedObject, IriValidation, Node, Object, Relabel, TryFromJson, ValidId, Value,
+};
 use hashbrown::HashMap;
-use indexmap::IndexSet;
+use indexmap::{IndexMap, IndexSet};
 use iref::IriBuf;
+use json_ld_syntax::IntoJsonWithContext;
 use rdf_types::vocabulary::VocabularyMut;
 use rdf_types::{BlankIdBuf, Generator, Vocabulary};
+use std::cell::RefCell;
 use std::collections::HashSet;
 use std::hash::Hash;
 
@@ -182,6 +187,76 @@ impl<T, B> ExpandedDocument<T, B> {
 		self.canonicalize_with(&mut buffer)
 	}
 
+	/// Removes duplicate objects under every (reverse) property of the
+	/// document, including in nested graphs.
+	///
+	/// Objects are compared through their stripped form, which ignores the
+	/// order of the entries of JSON literals. Duplicate list items are
+	/// preserved. Returns the number of removed objects.
+	pub fn deduplicate(&mut self) -> usize
+	where
+		T: Eq + Hash,
+		B: Eq + Hash,
+	{
+		self.deduplicate_with(DeduplicateOptions::default())
+	}
+
+	/// Removes duplicate objects under every (reverse) property of the
+	/// document, including in nested graphs, using the given `options`.
+	///
+	/// Objects are compared through their stripped form, which ignores the
+	/// order of the entries of JSON literals. Returns the number of removed
+	/// objects.
+	pub fn deduplicate_with(&mut self, options: DeduplicateOptions) -> usize
+	where
+		T: Eq + Hash,
+		B: Eq + Hash,
+	{
+		let mut count = 0;
+		let mut objects: Vec<_> = std::mem::take(&mut self.0).into_iter().collect();
+		for object in &mut objects {
+			count += object.deduplicate_with(options)
+		}
+
+		count += crate::utils::deduplicate(&mut objects);
+		self.0 = objects.into_iter().collect();
+		count
+	}
+
+	/// Removes every invalid identifier from this document.
+	///
+	/// Top-level nodes with an invalid identifier are removed. See
+	/// [`Node::remove_invalid_ids`]. Returns the number of removed
+	/// identifiers.
+	pub fn remove_invalid_ids(&mut self) -> usize
+	where
+		T: Clone + Eq + Hash,
+		B: Clone + Eq + Hash,
+	{
+		let mut count = 0;
+		let objects = std::mem::take(&mut self.0);
+		for mut object in objects {
+			if object.has_invalid_id() {
+				count += 1
+			} else {
+				count += object.remove_invalid_ids();
+				self.0.insert(object);
+			}
+		}
+
+		count
+	}
+
+	/// Returns the first invalid identifier found in this document, if any.
+	pub fn find_invalid_id(&self) -> Option<String> {
+		self.traverse()
+			.filter_map(FragmentRef::into_id)
+			.find_map(|id| match id {
+				Id::Invalid(s) => Some(s),
+				Id::Valid(_) => None,
+			})
+	}
+
 	/// Map the identifiers present in this expanded document (recursively).
 	pub fn map_ids<U, C>(
 		self,
@@ -200,6 +275,113 @@ impl<T, B> ExpandedDocument<T, B> {
 		)
 	}
 
+	/// Validates every node identifier, type and property of this document
+	/// using the given IRI `validation` level.
+	///
+	/// Returns the IRIs rejected by the validation level, now stored as
+	/// [`Id::Invalid`].
+	pub fn validate_iris_with<V>(
+		&mut self,
+		vocabulary: &mut V,
+		validation: IriValidation,
+	) -> Vec<String>
+	where
+		V: VocabularyMut<Iri = T, BlankId = B>,
+		T: Eq + Hash,
+		B: Eq + Hash,
+	{
+		let mut rejected = Vec::new();
+		*self = std::mem::take(self).map_ids(
+			|iri| iri,
+			|id| {
+				let was_valid = id.is_valid();
+				let id = validation.validate_in(vocabulary, id);
+				if let Id::Invalid(s) = &id {
+					if was_valid {
+						rejected.push(s.clone())
+					}
+				}
+				id
+			},
+		);
+
+		rejected
+	}
+
+	/// Rewrites every IRI of this document starting with one of the given
+	/// `(old, new)` prefixes, replacing `old` with `new`.
+	///
+	/// This covers node identifiers (including graph names), types,
+	/// properties, reverse properties, annotations and value datatypes. When
+	/// several prefixes match an IRI, the longest one is used. IRIs that would
+	/// not be valid once rewritten are left unchanged.
+	///
+	/// Returns every rewritten IRI along with its new value. If `dry_run` is
+	/// `true`, the document is left unchanged and the returned map only
+	/// reports what would change.
+	pub fn rewrite_iris_with<V>(
+		&mut self,
+		vocabulary: &mut V,
+		prefixes: &[(&str, &str)],
+		dry_run: bool,
+	) -> IndexMap<IriBuf, IriBuf>
+	where
+		V: VocabularyMut<Iri = T, BlankId = B>,
+		T: Eq + Hash,
+		B: Eq + Hash,
+	{
+		// Both mapping functions need the vocabulary.
+		let state = RefCell::new((vocabulary, IndexMap::new()));
+		let rewrite = |iri: T| {
+			let (vocabulary, rewritten) = &mut *state.borrow_mut();
+			let new_iri = vocabulary.iri(&iri).and_then(|old| {
+				let (from, to) = prefixes
+					.iter()
+					.filter(|(from, _)| old.as_str().starts_with(from))
+					.max_by_key(|(from, _)| from.len())?;
+				let new = IriBuf::new(format!("{to}{}", &old.as_str()[from.len()..])).ok()?;
+				(new != *old).then(|| (old.to_owned(), new))
+			});
+
+			match new_iri {
+				Some((old, new)) => {
+					let iri = if dry_run {
+						iri
+					} else {
+						vocabulary.insert(new.as_iri())
+					};
+					rewritten.insert(old, new);
+					iri
+				}
+				None => iri,
+			}
+		};
+
+		*self = std::mem::take(self).map_ids(&rewrite, |id| match id {
+			Id::Valid(ValidId::Iri(iri)) => Id::Valid(ValidId::Iri(rewrite(iri))),
+			id => id,
+		});
+
+		state.into_inner().1
+	}
+
+	/// Rewrites every IRI of this document starting with one of the given
+	/// `(old, new)` prefixes, replacing `old` with `new`.
+	///
+	/// See [`Self::rewrite_iris_with`].
+	pub fn rewrite_iris(
+		&mut self,
+		prefixes: &[(&str, &str)],
+		dry_run: bool,
+	) -> IndexMap<IriBuf, IriBuf>
+	where
+		T: Eq + Hash,
+		B: Eq + Hash,
+		(): VocabularyMut<Iri = T, BlankId = B>,
+	{
+		self.rewrite_iris_with(&mut (), prefixes, dry_run)
+	}
+
 	/// Returns the set of all blank identifiers in the given document.
 	pub fn blank_ids(&self) -> HashSet<&B>
 	where
@@ -256,6 +438,30 @@ impl<T: Hash + Eq, B: Hash + Eq> ExpandedDocument<T, B> {
 	pub fn insert(&mut self, object: IndexedObject<T, B>) -> bool {
 		self.0.insert(object)
 	}
+
+	/// Applies `f` to every value object of this document, including in
+	/// nested nodes, graphs and lists.
+	pub fn map_values(&mut self, f: impl FnMut(&mut Value<T>)) {
+		transform_objects(&mut self.0, &mut MapValues(f))
+	}
+
+	/// Applies `f` to every node object of this document, including in
+	/// nested nodes, graphs and lists.
+	///
+	/// A node is given to `f` before its sub-objects, so the sub-objects
+	/// added by `f` are visited as well.
+	pub fn map_nodes(&mut self, f: impl FnMut(&mut Node<T, B>)) {
+		transform_objects(&mut self.0, &mut MapNodes(f))
+	}
+
+	/// Removes every node object of this document for which `f` returns
+	/// `false`, including in nested nodes, graphs and lists.
+	///
+	/// The sub-objects of a removed node are not visited. Properties left
+	/// without values are removed.
+	pub fn retain_nodes(&mut self, f: impl FnMut(&Node<T, B>) -> bool) {
+		transform_objects(&mut self.0, &mut RetainNodes(f))
+	}
 }
 
 impl<T: Eq + Hash, B: Eq + Hash> From<Indexed<Node<T, B>>> for ExpandedDocument<T, B> {
@@ -300,6 +506,12 @@ impl<T: Eq + Hash, B: Eq + Hash> PartialEq for ExpandedDocument<T, B> {
 
 impl<T: Eq + Hash, B: Eq + Hash> Eq for ExpandedDocument<T, B> {}
 
+impl<T, B, N: Vocabulary<Iri = T, BlankId = B>> IntoJsonWithContext<N> for ExpandedDocument<T, B> {
+	fn into_json_with(self, vocabulary: &N) -> json_syntax::Value {
+		self.0.into_json_with(vocabulary)
+	}
+}
+
 impl<T, B> IntoIterator for ExpandedDocument<T, B> {
 	type IntoIter = IntoIter<T, B>;
 	type Item = IndexedObject<T, B>;
@@ -346,3 +558,52 @@ impl<T, B> From<IndexSet<IndexedObject<T, B>>> for ExpandedDocument<T, B> {
 		Self(set)
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn rewrite_iris() {
+		let json = json_syntax::json!([{
+			"@id": "http://example.com/alice",
+			"@type": ["http://schema.org/Person"],
+			"http://schema.org/age": [{
+				"@value": "42",
+				"@type": "http://www.w3.org/2001/XMLSchema#integer"
+			}],
+			"http://schema.org/knows": [{ "@id": "http://example.com/bob" }]
+		}]);
+		let mut document = ExpandedDocument::try_from_json_in(&mut (), json.clone()).unwrap();
+		let original = document.clone();
+
+		let prefixes = [
+			("http://", "https://"),
+			("http://www.w3.org/", "http://www.w3.org/"),
+		];
+		let report = document.rewrite_iris(&prefixes, true);
+		assert_eq!(document, original);
+		assert_eq!(report.len(), 5);
+		assert_eq!(
+			report[iref::Iri::new("http://schema.org/age").unwrap()],
+			"https://schema.org/age"
+		);
+
+		assert_eq!(document.rewrite_iris(&prefixes, false), report);
+		let expected = ExpandedDocument::try_from_json_in(
+			&mut (),
+			json_syntax::json!([{
+				"@id": "https://example.com/alice",
+				"@type": ["https://schema.org/Person"],
+				"https://schema.org/age": [{
+					"@value": "42",
+					"@type": "http://www.w3.org/2001/XMLSchema#integer"
+				}],
+				"https://schema.org/knows": [{ "@id": "https://example.com/bob" }]
+			}]),
+		)
+		.unwrap();
+		assert_eq!(document, expected);
+		assert!(document.rewrite_iris(&prefixes, false).is_empty())
+	}
+}
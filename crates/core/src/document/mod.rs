@@ -2,14 +2,22 @@ use std::ops::Deref;
 use std::{borrow::Borrow, hash::Hash};
 
 use iref::IriBuf;
+#[cfg(feature = "linked-data")]
 use linked_data::{LinkedData, LinkedDataGraph, LinkedDataResource, LinkedDataSubject};
-use rdf_types::{vocabulary::IriVocabularyMut, BlankIdBuf, Interpretation, Vocabulary};
+use rdf_types::BlankIdBuf;
+#[cfg(feature = "linked-data")]
+use rdf_types::{vocabulary::IriVocabularyMut, Interpretation, Vocabulary};
 
 pub mod expanded;
 pub mod flattened;
+mod partition;
+#[cfg(feature = "serde-json")]
+mod reader;
 
 pub use expanded::ExpandedDocument;
 pub use flattened::FlattenedDocument;
+#[cfg(feature = "serde-json")]
+pub use reader::ReadExpandedError;
 
 use crate::RemoteDocument;
 
@@ -99,6 +107,7 @@ impl<I, B> serde::Serialize for Document<I, B> {
 	}
 }
 
+#[cfg(feature = "linked-data")]
 impl<V: Vocabulary, I: Interpretation> LinkedData<I, V> for Document<V::Iri, V::BlankId>
 where
 	V: IriVocabularyMut,
@@ -113,6 +122,7 @@ where
 	}
 }
 
+#[cfg(feature = "linked-data")]
 impl<V: Vocabulary, I: Interpretation> LinkedDataGraph<I, V> for Document<V::Iri, V::BlankId>
 where
 	V: IriVocabularyMut,
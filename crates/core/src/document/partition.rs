@@ -0,0 +1,187 @@
+use super::ExpandedDocument;
+use crate::{Id, IndexedObject, Node, Object};
+use hashbrown::HashMap;
+use indexmap::IndexMap;
+use std::hash::Hash;
+
+/// Root node objects of a document, by blank node identifier.
+struct BlankNodes<'a, T, B>(HashMap<&'a B, &'a IndexedObject<T, B>>);
+
+impl<'a, T, B: Eq + Hash> BlankNodes<'a, T, B> {
+	fn new(document: &'a ExpandedDocument<T, B>) -> Self {
+		Self(
+			document
+				.iter()
+				.filter_map(|object| match object.inner() {
+					Object::Node(node) => Some((node.id.as_ref()?.as_blank()?, object)),
+					_ => None,
+				})
+				.collect(),
+		)
+	}
+
+	/// Inserts the given object in `document`, along with every root node
+	/// object it (transitively) references with a blank node identifier.
+	fn insert_closure(&self, object: &'a IndexedObject<T, B>, document: &mut ExpandedDocument<T, B>)
+	where
+		T: Clone + Eq + Hash,
+		B: Clone,
+	{
+		let mut stack = vec![object];
+		while let Some(object) = stack.pop() {
+			if document.insert(object.clone()) {
+				stack.extend(
+					object
+						.traverse()
+						.filter_map(|f| f.into_id().and_then(Id::into_blank))
+						.filter_map(|b| self.0.get(b).copied()),
+				)
+			}
+		}
+	}
+}
+
+impl<T: Clone + Eq + Hash, B: Clone + Eq + Hash> ExpandedDocument<T, B> {
+	/// Partitions the root node objects of this document using the keys
+	/// returned by `f` for each of them.
+	///
+	/// Each key is mapped to a sub-document containing the root nodes for
+	/// which `f` returned the key, along with the root nodes they reference
+	/// through blank node identifiers (transitively), so that no blank node
+	/// description is lost. A node may appear in multiple sub-documents.
+	/// Root value and list objects are not included.
+	pub fn partition_by<K: Eq + Hash, I: IntoIterator<Item = K>>(
+		&self,
+		mut f: impl FnMut(&Node<T, B>) -> I,
+	) -> IndexMap<K, Self> {
+		let blank_nodes = BlankNodes::new(self);
+		let mut result = IndexMap::new();
+
+		for object in self {
+			if let Object::Node(node) = object.inner() {
+				for key in f(node) {
+					blank_nodes.insert_closure(object, result.entry(key).or_insert_with(Self::new))
+				}
+			}
+		}
+
+		result
+	}
+
+	/// Partitions the root node objects of this document by type.
+	///
+	/// See [`Self::partition_by`]. Untyped nodes are not included.
+	pub fn partition_by_type(&self) -> IndexMap<Id<T, B>, Self> {
+		self.partition_by(|node| node.types().to_vec())
+	}
+
+	/// Partitions the root node objects of this document by property, each
+	/// property being mapped to the nodes using it.
+	///
+	/// This groups nodes by the domain of their properties.
+	/// See [`Self::partition_by`].
+	pub fn partition_by_property(&self) -> IndexMap<Id<T, B>, Self> {
+		self.partition_by(|node| {
+			node.properties()
+				.iter()
+				.map(|(prop, _)| prop.clone())
+				.collect::<Vec<_>>()
+		})
+	}
+
+	/// Partitions this document by graph.
+	///
+	/// Each named graph (root node object with a `@graph` entry) is mapped to
+	/// its content, and the default graph (`None`) to the other root objects.
+	/// Named graph nodes with entries besides `@id` and `@graph` are also
+	/// kept, without their `@graph` entry, in the default graph.
+	pub fn partition_by_graph(&self) -> IndexMap<Option<Id<T, B>>, Self> {
+		let mut result = IndexMap::new();
+		result.insert(None, Self::new());
+
+		for object in self {
+			match object.inner() {
+				Object::Node(node) if node.graph.is_some() => {
+					if let Some(graph) = node.graph() {
+						result
+							.entry(node.id.clone())
+							.or_insert_with(Self::new)
+							.extend(graph.iter().cloned());
+					}
+
+					if !node.is_graph() {
+						let mut object = object.clone();
+						if let Object::Node(node) = object.inner_mut() {
+							node.set_graph_entry(None)
+						}
+
+						result[&None].insert(object);
+					}
+				}
+				_ => {
+					result[&None].insert(object.clone());
+				}
+			}
+		}
+
+		result
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{ExpandedDocument, Id, TryFromJson};
+	use iref::IriBuf;
+
+	fn id(iri: &str) -> Id {
+		Id::iri(IriBuf::new(iri.to_owned()).unwrap())
+	}
+
+	#[test]
+	fn partition() {
+		let document = ExpandedDocument::try_from_json_in(
+			&mut (),
+			json_syntax::json!([
+				{
+					"@id": "https://example.com/alice",
+					"@type": ["https://schema.org/Person"],
+					"https://schema.org/address": [{ "@id": "_:address" }]
+				},
+				{
+					"@id": "_:address",
+					"https://schema.org/city": [{ "@value": "Paris" }]
+				},
+				{
+					"@id": "https://example.com/acme",
+					"@type": ["https://schema.org/Organization"],
+					"https://schema.org/name": [{ "@value": "ACME" }]
+				},
+				{
+					"@id": "https://example.com/graph",
+					"@graph": [{ "@id": "https://example.com/bob" }]
+				}
+			]),
+		)
+		.unwrap();
+
+		let by_type = document.partition_by_type();
+		assert_eq!(by_type.len(), 2);
+		assert_eq!(by_type[&id("https://schema.org/Person")].len(), 2);
+		assert_eq!(by_type[&id("https://schema.org/Organization")].len(), 1);
+
+		let by_property = document.partition_by_property();
+		assert_eq!(by_property[&id("https://schema.org/address")].len(), 2);
+		assert_eq!(by_property[&id("https://schema.org/city")].len(), 1);
+
+		let by_graph = document.partition_by_graph();
+		assert_eq!(by_graph[&None].len(), 3);
+		assert_eq!(
+			by_graph[&Some(id("https://example.com/graph"))],
+			ExpandedDocument::try_from_json_in(
+				&mut (),
+				json_syntax::json!([{ "@id": "https://example.com/bob" }])
+			)
+			.unwrap()
+		)
+	}
+}
@@ -0,0 +1,244 @@
+use super::ExpandedDocument;
+use crate::{object::InvalidExpandedJson, Indexed, TryFromJson};
+use json_syntax::Parse;
+use rdf_types::VocabularyMut;
+use serde::de::{DeserializeSeed, Deserializer, SeqAccess, Visitor};
+use serde_json::value::RawValue;
+use std::{cell::RefCell, fmt, hash::Hash, io::Read};
+
+/// Expanded document reading error.
+#[derive(Debug, thiserror::Error)]
+pub enum ReadExpandedError {
+	/// The input is not valid JSON.
+	///
+	/// The position of the error is given by [`serde_json::Error::line`] and
+	/// [`serde_json::Error::column`].
+	#[error("syntax error: {0}")]
+	Syntax(serde_json::Error),
+
+	/// The input is valid JSON but not in expanded form.
+	///
+	/// The `index` is the position of the offending object in the top-level
+	/// array. The `line` and `column` are the position reached by the reader
+	/// right after it.
+	#[error(
+		"invalid expanded JSON object {index} (before line {line} column {column}): {error:?}"
+	)]
+	Invalid {
+		index: usize,
+		line: usize,
+		column: usize,
+		error: InvalidExpandedJson,
+	},
+
+	#[error("IO: {0}")]
+	IO(std::io::Error),
+}
+
+impl<T: Eq + Hash, B: Eq + Hash> ExpandedDocument<T, B> {
+	/// Reads an expanded document from the given JSON bytes, interning its
+	/// identifiers into the given `vocabulary`.
+	///
+	/// This is equivalent to parsing the input and calling
+	/// [`TryFromJson::try_from_json_in`], except that only the top-level
+	/// array is streamed: the whole input is never materialized as a
+	/// [`json_syntax::Value`], but each top-level object is, one at a time,
+	/// before being validated and converted. Memory usage is hence bounded
+	/// by the size of the largest top-level object. Errors are reported with
+	/// their position.
+	///
+	/// Numbers are kept with their exact decimal representation, as with
+	/// [`json_syntax::Parse`].
+	pub fn from_slice_in(
+		vocabulary: &mut impl VocabularyMut<Iri = T, BlankId = B>,
+		bytes: &[u8],
+	) -> Result<Self, ReadExpandedError> {
+		read(vocabulary, &mut serde_json::Deserializer::from_slice(bytes))
+	}
+
+	/// Reads an expanded document from the given JSON bytes.
+	///
+	/// See [`Self::from_slice_in`].
+	pub fn from_slice(bytes: &[u8]) -> Result<Self, ReadExpandedError>
+	where
+		(): VocabularyMut<Iri = T, BlankId = B>,
+	{
+		Self::from_slice_in(&mut (), bytes)
+	}
+
+	/// Reads an expanded document from the given JSON reader, interning its
+	/// identifiers into the given `vocabulary`.
+	///
+	/// See [`Self::from_slice_in`]. The reader is not buffered.
+	pub fn from_reader_in(
+		vocabulary: &mut impl VocabularyMut<Iri = T, BlankId = B>,
+		reader: impl Read,
+	) -> Result<Self, ReadExpandedError> {
+		read(
+			vocabulary,
+			&mut serde_json::Deserializer::from_reader(reader),
+		)
+	}
+
+	/// Reads an expanded document from the given JSON reader.
+	///
+	/// See [`Self::from_reader_in`].
+	pub fn from_reader(reader: impl Read) -> Result<Self, ReadExpandedError>
+	where
+		(): VocabularyMut<Iri = T, BlankId = B>,
+	{
+		Self::from_reader_in(&mut (), reader)
+	}
+}
+
+fn read<'de, T: Eq + Hash, B: Eq + Hash, R: serde_json::de::Read<'de>>(
+	vocabulary: &mut impl VocabularyMut<Iri = T, BlankId = B>,
+	deserializer: &mut serde_json::Deserializer<R>,
+) -> Result<ExpandedDocument<T, B>, ReadExpandedError> {
+	// The conversion error is kept aside since serde errors only carry a
+	// message.
+	let invalid = RefCell::new(None);
+	let result = DocumentSeed {
+		vocabulary,
+		invalid: &invalid,
+	}
+	.deserialize(&mut *deserializer)
+	.and_then(|document| deserializer.end().map(|()| document));
+
+	result.map_err(|e| match invalid.into_inner() {
+		Some((index, error)) => ReadExpandedError::Invalid {
+			index,
+			line: e.line(),
+			column: e.column(),
+			error,
+		},
+		None if e.is_io() => ReadExpandedError::IO(e.into()),
+		None => ReadExpandedError::Syntax(e),
+	})
+}
+
+struct DocumentSeed<'a, V> {
+	vocabulary: &'a mut V,
+	invalid: &'a RefCell<Option<(usize, InvalidExpandedJson)>>,
+}
+
+impl<'de, 'a, T: Eq + Hash, B: Eq + Hash, V> DeserializeSeed<'de> for DocumentSeed<'a, V>
+where
+	V: VocabularyMut<Iri = T, BlankId = B>,
+{
+	type Value = ExpandedDocument<T, B>;
+
+	fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+	where
+		D: Deserializer<'de>,
+	{
+		deserializer.deserialize_seq(self)
+	}
+}
+
+impl<'de, 'a, T: Eq + Hash, B: Eq + Hash, V> Visitor<'de> for DocumentSeed<'a, V>
+where
+	V: VocabularyMut<Iri = T, BlankId = B>,
+{
+	type Value = ExpandedDocument<T, B>;
+
+	fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+		formatter.write_str("an expanded JSON-LD document")
+	}
+
+	fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+	where
+		A: SeqAccess<'de>,
+	{
+		let mut result = ExpandedDocument::new();
+		let mut index = 0;
+
+		// Items are read as raw JSON text, then parsed by `json_syntax` to
+		// keep arbitrary precision numbers.
+		while let Some(item) = seq.next_element::<Box<RawValue>>()? {
+			let (item, _) =
+				json_syntax::Value::parse_str(item.get()).map_err(serde::de::Error::custom)?;
+
+			match Indexed::try_from_json_in(self.vocabulary, item) {
+				Ok(object) => {
+					result.insert(object);
+					index += 1
+				}
+				Err(e) => {
+					*self.invalid.borrow_mut() = Some((index, e));
+					return Err(serde::de::Error::custom("invalid expanded JSON"));
+				}
+			}
+		}
+
+		Ok(result)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use iref::IriBuf;
+	use json_ld_syntax::IntoJsonWithContext;
+	use json_syntax::Print;
+	use rdf_types::BlankIdBuf;
+
+	#[test]
+	fn read_expanded() {
+		let json = json_syntax::json!([{
+			"@id": "https://example.com/a",
+			"https://example.com/name": [{ "@value": "Alice" }],
+			"https://example.com/knows": [{ "@id": "https://example.com/b" }]
+		}, {
+			"@id": "https://example.com/b",
+			"https://example.com/age": [{ "@value": 42 }]
+		}]);
+		let expected: ExpandedDocument<IriBuf, BlankIdBuf> =
+			ExpandedDocument::try_from_json_in(&mut (), json.clone()).unwrap();
+
+		let bytes = json.compact_print().to_string().into_bytes();
+		assert_eq!(ExpandedDocument::from_slice(&bytes).unwrap(), expected);
+		assert_eq!(
+			ExpandedDocument::from_reader(bytes.as_slice()).unwrap(),
+			expected
+		);
+
+		let invalid = b"[\n  { \"@id\": \"https://example.com/a\", \"https://example.com/p\": [] },\n  { \"@value\": \"x\", \"@language\": 42 },\n  {}\n]";
+		assert!(matches!(
+			ExpandedDocument::<IriBuf, BlankIdBuf>::from_slice(invalid),
+			Err(ReadExpandedError::Invalid {
+				index: 1,
+				line: 4,
+				..
+			})
+		));
+
+		assert!(matches!(
+			ExpandedDocument::<IriBuf, BlankIdBuf>::from_slice(b"[{ \"@id\" }]"),
+			Err(ReadExpandedError::Syntax(e)) if e.line() == 1 && e.column() == 10
+		));
+	}
+
+	#[test]
+	fn number_precision() {
+		let input = r#"[{
+			"https://example.com/big": [{ "@value": 123456789012345678901234567890 }],
+			"https://example.com/exact": [{ "@value": 0.10000000000000000000001 }]
+		}]"#;
+
+		let (json, _) = json_syntax::Value::parse_str(input).unwrap();
+		let expected: ExpandedDocument<IriBuf, BlankIdBuf> =
+			ExpandedDocument::try_from_json_in(&mut (), json).unwrap();
+
+		let document = ExpandedDocument::from_slice(input.as_bytes()).unwrap();
+		assert_eq!(document, expected);
+		assert_eq!(
+			ExpandedDocument::from_reader(input.as_bytes()).unwrap(),
+			expected
+		);
+
+		let printed = document.into_json_with(&()).compact_print().to_string();
+		assert!(printed.contains("123456789012345678901234567890"));
+		assert!(printed.contains("0.10000000000000000000001"));
+	}
+}
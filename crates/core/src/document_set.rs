@@ -0,0 +1,382 @@
+//! Sets of named expanded documents.
+#[cfg(feature = "rdf")]
+use crate::{
+	flattening::{ConflictingIndexes, NodeMap},
+	Dataset,
+};
+use crate::{ExpandedDocument, Id, IndexedObject, Node, Object, ValidId};
+use indexmap::IndexMap;
+#[cfg(feature = "rdf")]
+use rdf_types::{Generator, Vocabulary};
+use std::{collections::HashSet, hash::Hash};
+
+/// Set of expanded documents, by name.
+///
+/// Documents are named by IRI, typically the URL they were loaded from. Node
+/// references (node objects with only an `@id`) can be resolved across the
+/// documents of the set, and the whole set can be merged into a single
+/// flattened dataset.
+///
+/// Blank node identifiers are local to their document: a blank node
+/// reference is only resolved in the document it appears in, and blank nodes
+/// of different documents are kept apart when merging.
+#[derive(Clone)]
+pub struct DocumentSet<T, B> {
+	documents: IndexMap<T, ExpandedDocument<T, B>>,
+}
+
+impl<T, B> Default for DocumentSet<T, B> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<T, B> DocumentSet<T, B> {
+	/// Creates a new empty document set.
+	pub fn new() -> Self {
+		Self {
+			documents: IndexMap::new(),
+		}
+	}
+
+	/// Returns the number of documents in the set.
+	pub fn len(&self) -> usize {
+		self.documents.len()
+	}
+
+	/// Checks if the set is empty.
+	pub fn is_empty(&self) -> bool {
+		self.documents.is_empty()
+	}
+
+	/// Returns an iterator over the documents of the set, with their name, in
+	/// insertion order.
+	pub fn iter(&self) -> indexmap::map::Iter<'_, T, ExpandedDocument<T, B>> {
+		self.documents.iter()
+	}
+}
+
+impl<T: Eq + Hash, B> DocumentSet<T, B> {
+	/// Inserts a document with the given name.
+	///
+	/// Returns the document previously using this name, if any.
+	pub fn insert(
+		&mut self,
+		name: T,
+		document: ExpandedDocument<T, B>,
+	) -> Option<ExpandedDocument<T, B>> {
+		self.documents.insert(name, document)
+	}
+
+	/// Returns the document with the given name, if any.
+	pub fn get(&self, name: &T) -> Option<&ExpandedDocument<T, B>> {
+		self.documents.get(name)
+	}
+
+	/// Removes the document with the given name, and returns it.
+	pub fn remove(&mut self, name: &T) -> Option<ExpandedDocument<T, B>> {
+		self.documents.shift_remove(name)
+	}
+}
+
+impl<T: Eq + Hash, B: Eq + Hash> DocumentSet<T, B> {
+	/// Returns every node object describing the node identified by `iri`,
+	/// with the name of the document it appears in.
+	///
+	/// Node references, having no other entry than `@id`, are not included.
+	pub fn resolve(&self, iri: &T) -> Vec<(&T, &Node<T, B>)> {
+		let mut result = Vec::new();
+
+		for (name, document) in &self.documents {
+			for_each_node(document, |node, _| {
+				if !node.is_empty() && node.id.as_ref().and_then(Id::as_iri) == Some(iri) {
+					result.push((name, node))
+				}
+			})
+		}
+
+		result
+	}
+
+	/// Returns every node reference of the set that no document describes.
+	///
+	/// A node reference is a node object with no other entry than `@id`,
+	/// nested in another node object (as a property value, reverse property
+	/// value or list item). A reference to an IRI is dangling if no document
+	/// of the set describes the node. A reference to a blank node is
+	/// dangling if its own document does not describe the node.
+	pub fn dangling_references(&self) -> Vec<DanglingReference<'_, T, B>> {
+		let mut described_iris = HashSet::new();
+		for document in self.documents.values() {
+			for_each_node(document, |node, _| {
+				if !node.is_empty() {
+					if let Some(iri) = node.id.as_ref().and_then(Id::as_iri) {
+						described_iris.insert(iri);
+					}
+				}
+			})
+		}
+
+		let mut result = Vec::new();
+		for (name, document) in &self.documents {
+			let mut described_blank_ids = HashSet::new();
+			let mut references = Vec::new();
+
+			for_each_node(document, |node, nested| {
+				if let Some(Id::Valid(id)) = &node.id {
+					if !node.is_empty() {
+						if let ValidId::Blank(b) = id {
+							described_blank_ids.insert(b);
+						}
+					} else if nested {
+						references.push(id)
+					}
+				}
+			});
+
+			for id in references {
+				let described = match id {
+					ValidId::Iri(iri) => described_iris.contains(iri),
+					ValidId::Blank(b) => described_blank_ids.contains(b),
+				};
+
+				if !described {
+					result.push(DanglingReference { document: name, id })
+				}
+			}
+		}
+
+		result
+	}
+}
+
+#[cfg(feature = "rdf")]
+impl<T: Clone + Eq + Hash, B: Clone + Eq + Hash> DocumentSet<T, B> {
+	/// Merges every document of the set into a single flattened dataset.
+	///
+	/// Blank node identifiers are relabeled using the given `generator`, so
+	/// that blank nodes of different documents are never merged together.
+	/// Nodes with the same IRI are merged (see
+	/// [`NodeMapGraph::merge_node`](crate::flattening::NodeMapGraph::merge_node)).
+	pub fn merge_with<V, G: Generator<V>>(
+		&self,
+		vocabulary: &mut V,
+		mut generator: G,
+	) -> Result<Dataset<T, B>, ConflictingIndexes<T, B>>
+	where
+		V: Vocabulary<Iri = T, BlankId = B>,
+	{
+		let mut result = NodeMap::new();
+
+		for document in self.documents.values() {
+			let (default_graph, graphs) = document
+				.generate_node_map_with(vocabulary, &mut generator)?
+				.into_parts();
+
+			result.default_graph_mut().merge_with(default_graph)?;
+			for (id, graph) in graphs {
+				result.declare_graph(id.clone());
+				result.graph_mut(Some(&id)).unwrap().merge_with(graph)?
+			}
+		}
+
+		Ok(result.into())
+	}
+
+	/// Merges every document of the set into a single flattened dataset.
+	///
+	/// See [`Self::merge_with`].
+	pub fn merge<G: Generator>(
+		&self,
+		generator: G,
+	) -> Result<Dataset<T, B>, ConflictingIndexes<T, B>>
+	where
+		(): Vocabulary<Iri = T, BlankId = B>,
+	{
+		self.merge_with(rdf_types::vocabulary::no_vocabulary_mut(), generator)
+	}
+}
+
+impl<T: Eq + Hash, B> FromIterator<(T, ExpandedDocument<T, B>)> for DocumentSet<T, B> {
+	fn from_iter<I: IntoIterator<Item = (T, ExpandedDocument<T, B>)>>(iter: I) -> Self {
+		Self {
+			documents: iter.into_iter().collect(),
+		}
+	}
+}
+
+impl<T: Eq + Hash, B> Extend<(T, ExpandedDocument<T, B>)> for DocumentSet<T, B> {
+	fn extend<I: IntoIterator<Item = (T, ExpandedDocument<T, B>)>>(&mut self, iter: I) {
+		self.documents.extend(iter)
+	}
+}
+
+impl<'a, T, B> IntoIterator for &'a DocumentSet<T, B> {
+	type Item = (&'a T, &'a ExpandedDocument<T, B>);
+	type IntoIter = indexmap::map::Iter<'a, T, ExpandedDocument<T, B>>;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.iter()
+	}
+}
+
+/// Node reference that no document of a [`DocumentSet`] describes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DanglingReference<'a, T, B> {
+	/// Name of the document containing the reference.
+	pub document: &'a T,
+
+	/// Referenced node.
+	pub id: &'a ValidId<T, B>,
+}
+
+/// Calls `f` on every node object of `document`, telling if the node is
+/// nested in another node object.
+///
+/// Included nodes and the nodes of a graph are not considered nested.
+fn for_each_node<'a, T, B>(
+	document: &'a ExpandedDocument<T, B>,
+	mut f: impl FnMut(&'a Node<T, B>, bool),
+) {
+	for object in document {
+		visit_object(object, false, &mut f)
+	}
+}
+
+fn visit_object<'a, T, B>(
+	object: &'a IndexedObject<T, B>,
+	nested: bool,
+	f: &mut impl FnMut(&'a Node<T, B>, bool),
+) {
+	match object.inner() {
+		Object::Node(node) => visit_node(node, nested, f),
+		Object::List(list) => {
+			for item in list {
+				visit_object(item, true, f)
+			}
+		}
+		Object::Value(_) => (),
+	}
+}
+
+fn visit_node<'a, T, B>(
+	node: &'a Node<T, B>,
+	nested: bool,
+	f: &mut impl FnMut(&'a Node<T, B>, bool),
+) {
+	f(node, nested);
+
+	for (_, objects) in node.properties() {
+		for object in objects {
+			visit_object(object, true, f)
+		}
+	}
+
+	if let Some(reverse_properties) = node.reverse_properties() {
+		for (_, nodes) in reverse_properties {
+			for node in nodes {
+				visit_node(node, true, f)
+			}
+		}
+	}
+
+	if let Some(included) = node.included() {
+		for node in included {
+			visit_node(node, false, f)
+		}
+	}
+
+	if let Some(objects) = node.graph() {
+		for object in objects {
+			visit_object(object, false, f)
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::TryFromJson;
+	use iref::IriBuf;
+
+	fn iri(s: &str) -> IriBuf {
+		IriBuf::new(format!("https://example.com/{s}")).unwrap()
+	}
+
+	fn document(json: json_syntax::Value) -> ExpandedDocument {
+		ExpandedDocument::try_from_json_in(&mut (), json).unwrap()
+	}
+
+	fn documents() -> DocumentSet<IriBuf, rdf_types::BlankIdBuf> {
+		[
+			(
+				iri("alice.jsonld"),
+				document(json_syntax::json!([{
+					"@id": "https://example.com/alice",
+					"https://example.com/knows": [
+						{ "@id": "https://example.com/bob" },
+						{ "@id": "https://example.com/carol" },
+						{ "@id": "_:b0" }
+					]
+				}, {
+					"@id": "_:b0",
+					"https://example.com/name": [{ "@value": "Anonymous" }]
+				}])),
+			),
+			(
+				iri("bob.jsonld"),
+				document(json_syntax::json!([{
+					"@id": "https://example.com/bob",
+					"https://example.com/knows": [{ "@id": "_:b0" }]
+				}])),
+			),
+		]
+		.into_iter()
+		.collect()
+	}
+
+	#[test]
+	fn cross_document_references() {
+		let documents = documents();
+
+		let bob = documents.resolve(&iri("bob"));
+		assert_eq!(bob.len(), 1);
+		assert_eq!(bob[0].0, &iri("bob.jsonld"));
+
+		let dangling: Vec<_> = documents
+			.dangling_references()
+			.into_iter()
+			.map(|r| (r.document.as_str(), r.id.to_string()))
+			.collect();
+		assert_eq!(
+			dangling,
+			[
+				(
+					"https://example.com/alice.jsonld",
+					"https://example.com/carol".to_owned()
+				),
+				("https://example.com/bob.jsonld", "_:b0".to_owned())
+			]
+		)
+	}
+
+	#[cfg(feature = "rdf")]
+	#[test]
+	fn merge() {
+		let dataset = documents()
+			.merge(rdf_types::generator::Blank::new())
+			.unwrap();
+		let default_graph = dataset.graph(None).unwrap();
+		assert!(default_graph.contains(&Id::iri(iri("alice"))));
+		assert!(default_graph.contains(&Id::iri(iri("bob"))));
+
+		// The two `_:b0` blank nodes are kept apart.
+		assert_eq!(
+			default_graph
+				.nodes()
+				.filter(|node| node.id.as_ref().unwrap().is_blank())
+				.count(),
+			2
+		)
+	}
+}
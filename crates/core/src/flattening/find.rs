@@ -0,0 +1,216 @@
+use super::NodeMapGraph;
+use crate::{object::Literal, Id, IndexedNode, LenientLangTagBuf, Value};
+use educe::Educe;
+use std::hash::Hash;
+
+/// Value matching options of [`NodeMapGraph::find_by_value`].
+#[derive(Debug, Educe, Clone, PartialEq, Eq)]
+#[educe(Default)]
+pub struct FindOptions<T> {
+	/// Whether the lexical forms are compared case-insensitively.
+	pub case_insensitive: bool,
+
+	/// Only match language-tagged strings with the given language.
+	///
+	/// Language tags are always compared case-insensitively.
+	pub language: Option<LenientLangTagBuf>,
+
+	/// Only match typed literals with the given datatype.
+	pub datatype: Option<T>,
+}
+
+impl<T> FindOptions<T> {
+	/// Returns these options with case-insensitive matching enabled.
+	pub fn case_insensitive(self) -> Self {
+		Self {
+			case_insensitive: true,
+			..self
+		}
+	}
+
+	/// Returns these options with the given language filter.
+	pub fn with_language(self, language: LenientLangTagBuf) -> Self {
+		Self {
+			language: Some(language),
+			..self
+		}
+	}
+
+	/// Returns these options with the given datatype filter.
+	pub fn with_datatype(self, datatype: T) -> Self {
+		Self {
+			datatype: Some(datatype),
+			..self
+		}
+	}
+
+	/// Checks if the given value matches the `literal` lexical form
+	/// according to these options.
+	pub fn matches(&self, value: &Value<T>, literal: &str) -> bool
+	where
+		T: PartialEq,
+	{
+		match value {
+			Value::Literal(_, ty) => {
+				if self.language.is_some() {
+					return false;
+				}
+
+				if self.datatype.is_some() && self.datatype.as_ref() != ty.as_ref() {
+					return false;
+				}
+			}
+			Value::LangString(s) => {
+				if self.datatype.is_some() {
+					return false;
+				}
+
+				if let Some(language) = &self.language {
+					match s.language() {
+						Some(l) if l.as_str().eq_ignore_ascii_case(language.as_str()) => (),
+						_ => return false,
+					}
+				}
+			}
+			Value::Json(_) => (),
+		}
+
+		let lexical_form = match lexical_form(value) {
+			Some(lexical_form) => lexical_form,
+			None => return false,
+		};
+
+		if self.case_insensitive {
+			lexical_form.to_lowercase() == literal.to_lowercase()
+		} else {
+			lexical_form == literal
+		}
+	}
+}
+
+/// Returns the lexical form of the given value, unless it is `null` or a
+/// JSON literal.
+pub(crate) fn lexical_form<T>(value: &Value<T>) -> Option<&str> {
+	match value {
+		Value::Literal(Literal::Null, _) | Value::Json(_) => None,
+		Value::Literal(Literal::Boolean(true), _) => Some("true"),
+		Value::Literal(Literal::Boolean(false), _) => Some("false"),
+		Value::Literal(Literal::Number(n), _) => Some(n.as_str()),
+		Value::Literal(Literal::String(s), _) => Some(s.as_str()),
+		Value::LangString(s) => Some(s.as_str()),
+	}
+}
+
+impl<T: Eq + Hash, B: Eq + Hash> NodeMapGraph<T, B> {
+	/// Finds the nodes having a value of `property` matching the given
+	/// `literal` lexical form.
+	///
+	/// For instance, this finds the nodes whose `schema:identifier` is a
+	/// given string. Numbers and booleans are matched by their lexical form
+	/// (e.g. `"42"` or `"true"`), `null` and JSON literals never match. See
+	/// [`FindOptions`] for case-insensitive matching and language and
+	/// datatype filtering.
+	///
+	/// This uses the value index if [enabled](Self::enable_indexes), and
+	/// scans every node of the graph otherwise.
+	pub fn find_by_value<'a, Q>(
+		&'a self,
+		property: &'a Q,
+		literal: &'a str,
+		options: &'a FindOptions<T>,
+	) -> impl Iterator<Item = &'a IndexedNode<T, B>>
+	where
+		Q: ?Sized + Hash + indexmap::Equivalent<Id<T, B>>,
+	{
+		self.candidates(|indexes| {
+			indexes
+				.kinds
+				.values
+				.then(|| indexes.values.get(&literal.to_lowercase()))
+		})
+		.filter(move |node| {
+			node.properties()
+				.get(property)
+				.any(|object| match object.as_value() {
+					Some(value) => options.matches(value, literal),
+					None => false,
+				})
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{ExpandedDocument, TryFromJson};
+	use iref::{Iri, IriBuf};
+	use rdf_types::{generator, BlankIdBuf};
+
+	#[test]
+	fn find_by_value() {
+		let json = json_syntax::json!([{
+			"@id": "https://example.com/a",
+			"https://schema.org/identifier": [{ "@value": "ABC-1" }]
+		}, {
+			"@id": "https://example.com/b",
+			"https://schema.org/identifier": [{ "@value": "abc-1", "@language": "en-US" }]
+		}, {
+			"@id": "https://example.com/c",
+			"https://schema.org/identifier": [{
+				"@value": "ABC-1",
+				"@type": "https://example.com/Code"
+			}]
+		}, {
+			"@id": "https://example.com/d",
+			"https://schema.org/identifier": [{ "@value": 42 }]
+		}]);
+		let document: ExpandedDocument<IriBuf, BlankIdBuf> =
+			ExpandedDocument::try_from_json_in(&mut (), json).unwrap();
+		let node_map = document.into_node_map(generator::Blank::new()).unwrap();
+		let graph = node_map.graph(None).unwrap();
+
+		let identifier = Iri::new("https://schema.org/identifier").unwrap();
+		let find = |literal: &str, options: FindOptions<IriBuf>| {
+			let mut ids: Vec<_> = graph
+				.find_by_value(&identifier, literal, &options)
+				.map(|node| node.id.as_ref().unwrap().as_str().to_owned())
+				.collect();
+			ids.sort();
+			ids
+		};
+
+		assert_eq!(
+			find("ABC-1", FindOptions::default()),
+			["https://example.com/a", "https://example.com/c"]
+		);
+		assert_eq!(
+			find("abc-1", FindOptions::default().case_insensitive()),
+			[
+				"https://example.com/a",
+				"https://example.com/b",
+				"https://example.com/c"
+			]
+		);
+		assert_eq!(
+			find(
+				"ABC-1",
+				FindOptions::default()
+					.case_insensitive()
+					.with_language(LenientLangTagBuf::new("en-us".to_owned()).0)
+			),
+			["https://example.com/b"]
+		);
+		assert_eq!(
+			find(
+				"ABC-1",
+				FindOptions::default()
+					.with_datatype(IriBuf::new("https://example.com/Code".to_owned()).unwrap())
+			),
+			["https://example.com/c"]
+		);
+		assert_eq!(
+			find("42", FindOptions::default()),
+			["https://example.com/d"]
+		);
+	}
+}
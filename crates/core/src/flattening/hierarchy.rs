@@ -0,0 +1,258 @@
+use super::{NodeMap, NodeMapGraph};
+use crate::{
+	rdf::{RDFS_SUB_CLASS_OF, RDFS_SUB_PROPERTY_OF},
+	Id, IndexedNode,
+};
+use rdf_types::vocabulary::IriVocabulary;
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+/// Class and property hierarchy.
+///
+/// Stores `rdfs:subClassOf` and `rdfs:subPropertyOf` relations, registered
+/// one by one or loaded from an RDFS/OWL ontology, so that node queries such
+/// as [`NodeMapGraph::nodes_with_inferred_type`] also match subtypes. Both
+/// relations are transitive and reflexive.
+#[derive(Debug, Clone)]
+pub struct Hierarchy<T, B> {
+	/// Direct subclasses of each class.
+	sub_classes: HashMap<Id<T, B>, HashSet<Id<T, B>>>,
+
+	/// Direct subproperties of each property.
+	sub_properties: HashMap<Id<T, B>, HashSet<Id<T, B>>>,
+}
+
+impl<T, B> Default for Hierarchy<T, B> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<T, B> Hierarchy<T, B> {
+	/// Creates a new empty hierarchy.
+	pub fn new() -> Self {
+		Self {
+			sub_classes: HashMap::new(),
+			sub_properties: HashMap::new(),
+		}
+	}
+}
+
+impl<T: Clone + Eq + Hash, B: Clone + Eq + Hash> Hierarchy<T, B> {
+	/// Loads the hierarchy defined by the given ontology.
+	///
+	/// See [`Self::extend_from_node_map_with`].
+	pub fn from_node_map_with(
+		vocabulary: &impl IriVocabulary<Iri = T>,
+		ontology: &NodeMap<T, B>,
+	) -> Self {
+		let mut result = Self::new();
+		result.extend_from_node_map_with(vocabulary, ontology);
+		result
+	}
+
+	/// Loads the hierarchy defined by the given ontology.
+	///
+	/// See [`Self::extend_from_node_map_with`].
+	pub fn from_node_map(ontology: &NodeMap<T, B>) -> Self
+	where
+		(): IriVocabulary<Iri = T>,
+	{
+		Self::from_node_map_with(&(), ontology)
+	}
+
+	/// Registers the `rdfs:subClassOf` and `rdfs:subPropertyOf` relations
+	/// found in every graph of the given ontology.
+	///
+	/// The ontology is typically an RDFS or OWL JSON-LD document turned into
+	/// a node map. Other statements are ignored.
+	pub fn extend_from_node_map_with(
+		&mut self,
+		vocabulary: &impl IriVocabulary<Iri = T>,
+		ontology: &NodeMap<T, B>,
+	) {
+		let sub_class_of = vocabulary.get(RDFS_SUB_CLASS_OF).map(Id::iri);
+		let sub_property_of = vocabulary.get(RDFS_SUB_PROPERTY_OF).map(Id::iri);
+
+		for (_, graph) in ontology {
+			for node in graph.nodes() {
+				let id = match &node.id {
+					Some(id) => id,
+					None => continue,
+				};
+
+				if let Some(sub_class_of) = &sub_class_of {
+					for object in node.get(sub_class_of) {
+						if let Some(class) = object.id() {
+							self.insert_sub_class(id.clone(), class.clone());
+						}
+					}
+				}
+
+				if let Some(sub_property_of) = &sub_property_of {
+					for object in node.get(sub_property_of) {
+						if let Some(property) = object.id() {
+							self.insert_sub_property(id.clone(), property.clone());
+						}
+					}
+				}
+			}
+		}
+	}
+
+	/// Registers the `rdfs:subClassOf` and `rdfs:subPropertyOf` relations
+	/// found in every graph of the given ontology.
+	///
+	/// See [`Self::extend_from_node_map_with`].
+	pub fn extend_from_node_map(&mut self, ontology: &NodeMap<T, B>)
+	where
+		(): IriVocabulary<Iri = T>,
+	{
+		self.extend_from_node_map_with(&(), ontology)
+	}
+
+	/// Registers that `sub_class` is a subclass of `class`.
+	///
+	/// Returns `true` if the relation was not already registered.
+	pub fn insert_sub_class(&mut self, sub_class: Id<T, B>, class: Id<T, B>) -> bool {
+		self.sub_classes.entry(class).or_default().insert(sub_class)
+	}
+
+	/// Registers that `sub_property` is a subproperty of `property`.
+	///
+	/// Returns `true` if the relation was not already registered.
+	pub fn insert_sub_property(&mut self, sub_property: Id<T, B>, property: Id<T, B>) -> bool {
+		self.sub_properties
+			.entry(property)
+			.or_default()
+			.insert(sub_property)
+	}
+}
+
+impl<T: Eq + Hash, B: Eq + Hash> Hierarchy<T, B> {
+	/// Returns the given class and all its (direct or indirect) subclasses.
+	pub fn sub_classes<'a>(&'a self, class: &'a Id<T, B>) -> HashSet<&'a Id<T, B>> {
+		descendants(&self.sub_classes, class)
+	}
+
+	/// Returns the given property and all its (direct or indirect)
+	/// subproperties.
+	pub fn sub_properties<'a>(&'a self, property: &'a Id<T, B>) -> HashSet<&'a Id<T, B>> {
+		descendants(&self.sub_properties, property)
+	}
+
+	/// Checks if `sub_class` is `class` or one of its subclasses.
+	pub fn is_sub_class_of(&self, sub_class: &Id<T, B>, class: &Id<T, B>) -> bool {
+		self.sub_classes(class).contains(sub_class)
+	}
+
+	/// Checks if `sub_property` is `property` or one of its subproperties.
+	pub fn is_sub_property_of(&self, sub_property: &Id<T, B>, property: &Id<T, B>) -> bool {
+		self.sub_properties(property).contains(sub_property)
+	}
+}
+
+fn descendants<'a, T: Eq + Hash, B: Eq + Hash>(
+	map: &'a HashMap<Id<T, B>, HashSet<Id<T, B>>>,
+	root: &'a Id<T, B>,
+) -> HashSet<&'a Id<T, B>> {
+	let mut result = HashSet::new();
+	let mut stack = vec![root];
+
+	while let Some(id) = stack.pop() {
+		if result.insert(id) {
+			if let Some(children) = map.get(id) {
+				stack.extend(children)
+			}
+		}
+	}
+
+	result
+}
+
+impl<T: Eq + Hash, B: Eq + Hash> NodeMapGraph<T, B> {
+	/// Returns the nodes having the given type or one of its subclasses in
+	/// the given `hierarchy`.
+	///
+	/// Each node is returned once. See [`Self::nodes_with_type`].
+	pub fn nodes_with_inferred_type<'a>(
+		&'a self,
+		ty: &'a Id<T, B>,
+		hierarchy: &'a Hierarchy<T, B>,
+	) -> impl Iterator<Item = &'a IndexedNode<T, B>> {
+		let mut seen = HashSet::new();
+		hierarchy
+			.sub_classes(ty)
+			.into_iter()
+			.flat_map(move |ty| self.nodes_with_type(ty))
+			.filter(move |node| seen.insert(node.id.as_ref()))
+	}
+
+	/// Returns the nodes having at least one value for the given property or
+	/// one of its subproperties in the given `hierarchy`.
+	///
+	/// Each node is returned once. See [`Self::nodes_with_property`].
+	pub fn nodes_with_inferred_property<'a>(
+		&'a self,
+		property: &'a Id<T, B>,
+		hierarchy: &'a Hierarchy<T, B>,
+	) -> impl Iterator<Item = &'a IndexedNode<T, B>> {
+		let mut seen = HashSet::new();
+		hierarchy
+			.sub_properties(property)
+			.into_iter()
+			.flat_map(move |property| self.nodes_with_property(property))
+			.filter(move |node| seen.insert(node.id.as_ref()))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{ExpandedDocument, TryFromJson};
+	use iref::IriBuf;
+	use rdf_types::{generator, BlankIdBuf};
+
+	fn node_map(json: json_syntax::Value) -> NodeMap<IriBuf, BlankIdBuf> {
+		ExpandedDocument::try_from_json_in(&mut (), json)
+			.unwrap()
+			.into_node_map(generator::Blank::new())
+			.unwrap()
+	}
+
+	#[test]
+	fn inferred_type() {
+		let ontology = node_map(json_syntax::json!([{
+			"@id": "https://schema.org/Article",
+			"http://www.w3.org/2000/01/rdf-schema#subClassOf": [
+				{ "@id": "https://schema.org/CreativeWork" }
+			]
+		}, {
+			"@id": "https://schema.org/NewsArticle",
+			"http://www.w3.org/2000/01/rdf-schema#subClassOf": [
+				{ "@id": "https://schema.org/Article" }
+			]
+		}]));
+		let hierarchy = Hierarchy::from_node_map(&ontology);
+
+		let data = node_map(json_syntax::json!([{
+			"@id": "https://example.com/a",
+			"@type": ["https://schema.org/NewsArticle", "https://schema.org/Article"]
+		}, {
+			"@id": "https://example.com/b",
+			"@type": ["https://schema.org/Person"]
+		}]));
+		let graph = data.graph(None).unwrap();
+
+		let iri = |s: &str| Id::iri(IriBuf::new(s.to_owned()).unwrap());
+		let creative_work = iri("https://schema.org/CreativeWork");
+		assert!(hierarchy.is_sub_class_of(&iri("https://schema.org/NewsArticle"), &creative_work));
+		assert_eq!(graph.nodes_with_type(&creative_work).count(), 0);
+
+		let ids: Vec<_> = graph
+			.nodes_with_inferred_type(&creative_work, &hierarchy)
+			.map(|node| node.id.as_ref().unwrap().as_str())
+			.collect();
+		assert_eq!(ids, ["https://example.com/a"])
+	}
+}
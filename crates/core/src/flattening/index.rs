@@ -0,0 +1,360 @@
+use super::{find::lexical_form, NodeMapGraph, NodeMapGraphNodes};
+use crate::{Id, IndexedNode};
+use std::borrow::Borrow;
+use std::collections::{hash_set, HashMap, HashSet};
+use std::hash::Hash;
+
+/// Kinds of secondary indexes maintained by a [`NodeMapGraph`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct IndexKinds {
+	/// Index nodes by type, used by [`NodeMapGraph::nodes_with_type`].
+	pub types: bool,
+
+	/// Index nodes by property, used by
+	/// [`NodeMapGraph::nodes_with_property`].
+	pub properties: bool,
+
+	/// Index nodes by the (lowercase) lexical form of their values, used by
+	/// [`NodeMapGraph::find_by_value`].
+	pub values: bool,
+}
+
+impl IndexKinds {
+	/// Every kind of index.
+	pub const ALL: Self = Self {
+		types: true,
+		properties: true,
+		values: true,
+	};
+}
+
+/// Secondary indexes of a [`NodeMapGraph`].
+///
+/// Indexes are updated when nodes are merged into or removed from the graph.
+/// Nodes borrowed mutably (with [`NodeMapGraph::get_mut`] or
+/// [`NodeMapGraph::declare_node`]) may be modified in any way, so they are
+/// marked as stale: they are removed from the indexes and returned as
+/// candidates by every indexed lookup until the indexes are rebuilt with
+/// [`NodeMapGraph::rebuild_indexes`].
+///
+/// Use [`MemoryFootprint`](crate::MemoryFootprint) to estimate the memory
+/// used by the indexes.
+pub struct NodeMapIndexes<T, B> {
+	pub(crate) kinds: IndexKinds,
+	pub(crate) indexed: HashSet<Id<T, B>>,
+	pub(crate) stale: HashSet<Id<T, B>>,
+	pub(crate) types: HashMap<Id<T, B>, HashSet<Id<T, B>>>,
+	pub(crate) properties: HashMap<Id<T, B>, HashSet<Id<T, B>>>,
+	pub(crate) values: HashMap<String, HashSet<Id<T, B>>>,
+}
+
+impl<T, B> NodeMapIndexes<T, B> {
+	fn new(kinds: IndexKinds) -> Self {
+		Self {
+			kinds,
+			indexed: HashSet::new(),
+			stale: HashSet::new(),
+			types: HashMap::new(),
+			properties: HashMap::new(),
+			values: HashMap::new(),
+		}
+	}
+
+	/// Returns the kinds of maintained indexes.
+	pub fn kinds(&self) -> IndexKinds {
+		self.kinds
+	}
+
+	/// Returns the number of stale nodes, that will be indexed again by
+	/// [`NodeMapGraph::rebuild_indexes`].
+	pub fn stale_len(&self) -> usize {
+		self.stale.len()
+	}
+}
+
+impl<T: Eq + Hash, B: Eq + Hash> NodeMapIndexes<T, B> {
+	/// Indexes the given node, replacing its previous entries.
+	pub(crate) fn insert(&mut self, node: &IndexedNode<T, B>)
+	where
+		T: Clone,
+		B: Clone,
+	{
+		let id = match &node.id {
+			Some(id) => id,
+			None => return,
+		};
+
+		self.remove(node);
+		self.indexed.insert(id.clone());
+
+		if self.kinds.types {
+			for ty in node.types() {
+				self.types.entry(ty.clone()).or_default().insert(id.clone());
+			}
+		}
+
+		for (property, objects) in node.properties() {
+			if self.kinds.properties {
+				self.properties
+					.entry(property.clone())
+					.or_default()
+					.insert(id.clone());
+			}
+
+			if self.kinds.values {
+				for value in objects.iter().filter_map(|object| object.as_value()) {
+					if let Some(lexical_form) = lexical_form(value) {
+						self.values
+							.entry(lexical_form.to_lowercase())
+							.or_default()
+							.insert(id.clone());
+					}
+				}
+			}
+		}
+	}
+
+	/// Removes the given node from the indexes.
+	pub(crate) fn remove(&mut self, node: &IndexedNode<T, B>) {
+		if let Some(id) = &node.id {
+			if self.indexed.remove(id) {
+				self.remove_entries(id, node)
+			} else {
+				self.stale.remove(id);
+			}
+		}
+	}
+
+	/// Marks the given node as stale.
+	pub(crate) fn mark_stale(&mut self, node: &IndexedNode<T, B>) {
+		if let Some(id) = &node.id {
+			if let Some(id) = self.indexed.take(id) {
+				self.remove_entries(&id, node);
+				self.stale.insert(id);
+			}
+		}
+	}
+
+	fn remove_entries(&mut self, id: &Id<T, B>, node: &IndexedNode<T, B>) {
+		fn remove_from<K: Eq + Hash + Borrow<Q>, Q: ?Sized + Eq + Hash, I: Eq + Hash>(
+			map: &mut HashMap<K, HashSet<I>>,
+			key: &Q,
+			id: &I,
+		) {
+			if let Some(ids) = map.get_mut(key) {
+				ids.remove(id);
+				if ids.is_empty() {
+					map.remove(key);
+				}
+			}
+		}
+
+		for ty in node.types() {
+			remove_from(&mut self.types, ty, id)
+		}
+
+		for (property, objects) in node.properties() {
+			remove_from(&mut self.properties, property, id);
+
+			for value in objects.iter().filter_map(|object| object.as_value()) {
+				if let Some(lexical_form) = lexical_form(value) {
+					remove_from(&mut self.values, &lexical_form.to_lowercase(), id)
+				}
+			}
+		}
+	}
+}
+
+impl<T: Eq + Hash, B: Eq + Hash> NodeMapGraph<T, B> {
+	/// Enables the given kinds of secondary indexes, and builds them.
+	///
+	/// Indexes are disabled by default.
+	pub fn enable_indexes(&mut self, kinds: IndexKinds)
+	where
+		T: Clone,
+		B: Clone,
+	{
+		self.indexes = Some(NodeMapIndexes::new(kinds));
+		self.rebuild_indexes()
+	}
+
+	/// Disables and drops the secondary indexes.
+	pub fn disable_indexes(&mut self) {
+		self.indexes = None
+	}
+
+	/// Returns the secondary indexes, if enabled.
+	pub fn indexes(&self) -> Option<&NodeMapIndexes<T, B>> {
+		self.indexes.as_ref()
+	}
+
+	/// Rebuilds the secondary indexes from scratch, indexing again the stale
+	/// nodes.
+	pub fn rebuild_indexes(&mut self)
+	where
+		T: Clone,
+		B: Clone,
+	{
+		if let Some(indexes) = self.indexes.take() {
+			let mut indexes = NodeMapIndexes::new(indexes.kinds);
+			for node in self.nodes() {
+				indexes.insert(node)
+			}
+
+			self.indexes = Some(indexes)
+		}
+	}
+
+	/// Returns the nodes having the given type.
+	///
+	/// This uses the type index if enabled, and scans every node of the
+	/// graph otherwise.
+	pub fn nodes_with_type<'a>(
+		&'a self,
+		ty: &'a Id<T, B>,
+	) -> impl Iterator<Item = &'a IndexedNode<T, B>> {
+		self.candidates(|indexes| indexes.kinds.types.then(|| indexes.types.get(ty)))
+			.filter(move |node| node.has_type(ty))
+	}
+
+	/// Returns the nodes having at least one value for the given property.
+	///
+	/// This uses the property index if enabled, and scans every node of the
+	/// graph otherwise.
+	pub fn nodes_with_property<'a>(
+		&'a self,
+		property: &'a Id<T, B>,
+	) -> impl Iterator<Item = &'a IndexedNode<T, B>> {
+		self.candidates(|indexes| {
+			indexes
+				.kinds
+				.properties
+				.then(|| indexes.properties.get(property))
+		})
+		.filter(move |node| node.properties().contains(property))
+	}
+
+	/// Returns the nodes that may match an indexed lookup.
+	///
+	/// The `lookup` function returns `None` if the relevant index is not
+	/// enabled, in which case every node is a candidate.
+	pub(crate) fn candidates<'a>(
+		&'a self,
+		lookup: impl FnOnce(&'a NodeMapIndexes<T, B>) -> Option<Option<&'a HashSet<Id<T, B>>>>,
+	) -> Candidates<'a, T, B> {
+		match &self.indexes {
+			Some(indexes) => match lookup(indexes) {
+				Some(ids) => Candidates::Indexed {
+					graph: self,
+					ids: ids.map(HashSet::iter),
+					stale: indexes.stale.iter(),
+				},
+				None => Candidates::All(self.nodes()),
+			},
+			None => Candidates::All(self.nodes()),
+		}
+	}
+}
+
+/// Nodes that may match an indexed lookup.
+pub(crate) enum Candidates<'a, T, B> {
+	All(NodeMapGraphNodes<'a, T, B>),
+	Indexed {
+		graph: &'a NodeMapGraph<T, B>,
+		ids: Option<hash_set::Iter<'a, Id<T, B>>>,
+		stale: hash_set::Iter<'a, Id<T, B>>,
+	},
+}
+
+impl<'a, T: Eq + Hash, B: Eq + Hash> Iterator for Candidates<'a, T, B> {
+	type Item = &'a IndexedNode<T, B>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		match self {
+			Self::All(nodes) => nodes.next(),
+			Self::Indexed { graph, ids, stale } => {
+				let id = match ids.as_mut().and_then(Iterator::next) {
+					Some(id) => id,
+					None => stale.next()?,
+				};
+
+				graph.get(id)
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{flattening::FindOptions, ExpandedDocument, TryFromJson};
+	use iref::IriBuf;
+	use rdf_types::{generator, BlankIdBuf};
+
+	#[test]
+	fn maintained_indexes() {
+		let json = json_syntax::json!([{
+			"@id": "https://example.com/a",
+			"@type": ["https://schema.org/Person"],
+			"https://schema.org/name": [{ "@value": "Alice" }]
+		}, {
+			"@id": "https://example.com/b",
+			"https://schema.org/name": [{ "@value": "Bob" }]
+		}]);
+		let document: ExpandedDocument<IriBuf, BlankIdBuf> =
+			ExpandedDocument::try_from_json_in(&mut (), json).unwrap();
+		let mut graph = document
+			.into_node_map(generator::Blank::new())
+			.unwrap()
+			.merge()
+			.unwrap();
+		graph.enable_indexes(IndexKinds::ALL);
+
+		let iri = |s: &str| Id::iri(IriBuf::new(s.to_owned()).unwrap());
+		let person = iri("https://schema.org/Person");
+		let name = iri("https://schema.org/name");
+		let ids = |nodes: Vec<&IndexedNode<IriBuf, BlankIdBuf>>| {
+			let mut ids: Vec<_> = nodes
+				.into_iter()
+				.map(|node| node.id.as_ref().unwrap().as_str().to_owned())
+				.collect();
+			ids.sort();
+			ids
+		};
+
+		assert_eq!(
+			ids(graph.nodes_with_type(&person).collect()),
+			["https://example.com/a"]
+		);
+		assert_eq!(
+			ids(graph
+				.find_by_value(&name, "bob", &FindOptions::default().case_insensitive())
+				.collect()),
+			["https://example.com/b"]
+		);
+
+		// Nodes borrowed mutably are still found.
+		let b = iri("https://example.com/b");
+		graph
+			.get_mut(&b)
+			.unwrap()
+			.types_mut_or_default()
+			.push(person.clone());
+		assert_eq!(graph.indexes().unwrap().stale_len(), 1);
+		assert_eq!(
+			ids(graph.nodes_with_type(&person).collect()),
+			["https://example.com/a", "https://example.com/b"]
+		);
+
+		graph.rebuild_indexes();
+		assert_eq!(graph.indexes().unwrap().stale_len(), 0);
+		assert_eq!(graph.indexes().unwrap().types[&person].len(), 2);
+
+		graph.remove(&iri("https://example.com/a"));
+		assert_eq!(
+			ids(graph.nodes_with_property(&name).collect()),
+			["https://example.com/b"]
+		);
+		assert!(!graph.indexes().unwrap().values.contains_key("alice"))
+	}
+}
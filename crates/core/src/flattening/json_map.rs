@@ -0,0 +1,129 @@
+use super::{NodeMap, NodeMapGraph};
+use contextual::WithContext;
+use json_ld_syntax::IntoJsonWithContext;
+use rdf_types::Vocabulary;
+use std::hash::Hash;
+
+impl<T: Clone + Eq + Hash, B: Clone + Eq + Hash> NodeMap<T, B> {
+	/// Returns the subject-centric JSON representation of this node map.
+	///
+	/// Instead of the array of the flattened form, the result is a JSON
+	/// object mapping each graph name (`@default` for the default graph) to
+	/// an object mapping each node `@id` to the node:
+	///
+	/// ```json
+	/// {
+	///     "@default": {
+	///         "https://example.com/a": { "@id": "https://example.com/a", ... },
+	///         ...
+	///     },
+	///     "https://example.com/graph": { ... }
+	/// }
+	/// ```
+	///
+	/// As in the flattened form, nodes with no entry other than `@id` are
+	/// omitted. Since graphs are represented by the top-level entries, the
+	/// `@graph` entry of nodes is omitted. Graphs and nodes are sorted by
+	/// identifier.
+	pub fn to_json_map_with<N>(&self, vocabulary: &N) -> json_syntax::Value
+	where
+		N: Vocabulary<Iri = T, BlankId = B>,
+	{
+		let mut graphs: Vec<_> = self
+			.iter_named()
+			.map(|(id, graph)| (id.with(vocabulary).as_str().to_owned(), graph))
+			.collect();
+		graphs.sort_by(|a, b| a.0.cmp(&b.0));
+
+		let mut result = json_syntax::Object::new();
+		result.insert(
+			"@default".into(),
+			self.default_graph().to_json_map_with(vocabulary),
+		);
+		for (id, graph) in graphs {
+			result.insert(id.into(), graph.to_json_map_with(vocabulary));
+		}
+
+		json_syntax::Value::Object(result)
+	}
+
+	/// Returns the subject-centric JSON representation of this node map.
+	///
+	/// See [`Self::to_json_map_with`].
+	pub fn to_json_map(&self) -> json_syntax::Value
+	where
+		(): Vocabulary<Iri = T, BlankId = B>,
+	{
+		self.to_json_map_with(&())
+	}
+}
+
+impl<T: Clone + Eq + Hash, B: Clone + Eq + Hash> NodeMapGraph<T, B> {
+	/// Returns the JSON object mapping each node `@id` of this graph to the
+	/// node.
+	///
+	/// See [`NodeMap::to_json_map_with`].
+	pub fn to_json_map_with<N>(&self, vocabulary: &N) -> json_syntax::Value
+	where
+		N: Vocabulary<Iri = T, BlankId = B>,
+	{
+		let mut nodes = Vec::new();
+		for (id, node) in self {
+			let mut node = node.clone();
+			node.set_graph_entry(None);
+			if node.index().is_some() || !node.is_empty() {
+				nodes.push((id.with(vocabulary).as_str().to_owned(), node))
+			}
+		}
+		nodes.sort_by(|a, b| a.0.cmp(&b.0));
+
+		let mut result = json_syntax::Object::new();
+		for (id, node) in nodes {
+			result.insert(id.into(), node.into_json_with(vocabulary));
+		}
+
+		json_syntax::Value::Object(result)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{ExpandedDocument, TryFromJson};
+	use iref::IriBuf;
+	use rdf_types::{generator, BlankIdBuf};
+
+	#[test]
+	fn to_json_map() {
+		let json = json_syntax::json!([{
+			"@id": "https://example.com/g",
+			"@graph": [{
+				"@id": "https://example.com/b",
+				"https://example.com/name": [{ "@value": "Bob" }]
+			}]
+		}, {
+			"@id": "https://example.com/a",
+			"https://example.com/knows": [{ "@id": "https://example.com/b" }]
+		}]);
+		let document: ExpandedDocument<IriBuf, BlankIdBuf> =
+			ExpandedDocument::try_from_json_in(&mut (), json).unwrap();
+		let node_map = document.into_node_map(generator::Blank::new()).unwrap();
+
+		assert_eq!(
+			node_map.to_json_map(),
+			json_syntax::json!({
+				"@default": {
+					"https://example.com/a": {
+						"@id": "https://example.com/a",
+						"https://example.com/knows": [{ "@id": "https://example.com/b" }]
+					}
+				},
+				"https://example.com/g": {
+					"https://example.com/b": {
+						"@id": "https://example.com/b",
+						"https://example.com/name": [{ "@value": "Bob" }]
+					}
+				}
+			})
+		)
+	}
+}
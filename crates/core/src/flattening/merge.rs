@@ -0,0 +1,280 @@
+use super::ConflictingIndexes;
+use crate::{
+	object::node::{Graph, Included},
+	Id, IndexedNode,
+};
+use std::hash::Hash;
+
+/// Node merging policy.
+///
+/// Defines how the entries of a node are merged into a preexisting node with
+/// the same identifier when calling
+/// [`NodeMapGraph::merge_node_with`](super::NodeMapGraph::merge_node_with).
+/// Properties and reverse properties are always merged.
+///
+/// Every method has a default implementation following the
+/// [`merge_node`](super::NodeMapGraph::merge_node) semantics.
+pub trait MergePolicy<T, B> {
+	/// Merges the given `index` into the preexisting `node`.
+	///
+	/// By default, the new index (if any) overrides the preexisting one.
+	fn merge_index(
+		&mut self,
+		node: &mut IndexedNode<T, B>,
+		index: Option<String>,
+	) -> Result<(), ConflictingIndexes<T, B>>
+	where
+		T: Clone,
+		B: Clone,
+	{
+		if let Some(index) = index {
+			node.set_index(Some(index))
+		}
+
+		Ok(())
+	}
+
+	/// Merges the given `types` into the preexisting list of types.
+	///
+	/// By default, the given types are appended after the preexisting ones.
+	fn merge_types(&mut self, types: &mut Vec<Id<T, B>>, new_types: Vec<Id<T, B>>)
+	where
+		T: Eq + Hash,
+		B: Eq + Hash,
+	{
+		types.extend(new_types)
+	}
+
+	/// Merges the given `@graph` entry into the preexisting one.
+	///
+	/// By default, the preexisting graph is replaced.
+	fn merge_graph(&mut self, graph: &mut Option<Graph<T, B>>, new_graph: Option<Graph<T, B>>)
+	where
+		T: Eq + Hash,
+		B: Eq + Hash,
+	{
+		*graph = new_graph
+	}
+
+	/// Merges the given `@included` entry into the preexisting one.
+	///
+	/// By default, the preexisting included nodes are replaced.
+	fn merge_included(
+		&mut self,
+		included: &mut Option<Included<T, B>>,
+		new_included: Option<Included<T, B>>,
+	) where
+		T: Eq + Hash,
+		B: Eq + Hash,
+	{
+		*included = new_included
+	}
+}
+
+/// Types merging strategy.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TypesMerge {
+	/// New types are appended after the preexisting types.
+	#[default]
+	Concat,
+
+	/// New types are appended after the preexisting types, unless they are
+	/// already present.
+	Union,
+}
+
+/// Index merging strategy.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IndexMerge {
+	/// The new index overrides the preexisting index.
+	#[default]
+	Override,
+
+	/// The preexisting index is kept, unless there is none.
+	Keep,
+
+	/// A [`ConflictingIndexes`] error is raised if both indexes are defined
+	/// and different.
+	Error,
+}
+
+/// Graph (and included nodes) merging strategy.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GraphMerge {
+	/// The new graph overrides the preexisting graph.
+	#[default]
+	Override,
+
+	/// The new graph objects are added to the preexisting graph.
+	Union,
+}
+
+/// Configurable merge policy.
+///
+/// The default value of this type implements the same semantics as
+/// [`merge_node`](super::NodeMapGraph::merge_node).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MergeOptions {
+	/// How types are merged.
+	pub types: TypesMerge,
+
+	/// How indexes are merged.
+	pub index: IndexMerge,
+
+	/// How `@graph` and `@included` entries are merged.
+	pub graph: GraphMerge,
+}
+
+impl MergeOptions {
+	/// Returns these options with the given types merging strategy.
+	pub fn with_types(self, types: TypesMerge) -> Self {
+		Self { types, ..self }
+	}
+
+	/// Returns these options with the given index merging strategy.
+	pub fn with_index(self, index: IndexMerge) -> Self {
+		Self { index, ..self }
+	}
+
+	/// Returns these options with the given graph merging strategy.
+	pub fn with_graph(self, graph: GraphMerge) -> Self {
+		Self { graph, ..self }
+	}
+}
+
+impl<T, B> MergePolicy<T, B> for MergeOptions {
+	fn merge_index(
+		&mut self,
+		node: &mut IndexedNode<T, B>,
+		index: Option<String>,
+	) -> Result<(), ConflictingIndexes<T, B>>
+	where
+		T: Clone,
+		B: Clone,
+	{
+		if let Some(index) = index {
+			match (self.index, node.index()) {
+				(IndexMerge::Keep, Some(_)) => (),
+				(IndexMerge::Error, Some(current)) if current != index => {
+					return Err(ConflictingIndexes {
+						node_id: node.id.clone().unwrap(),
+						defined_index: current.to_owned(),
+						conflicting_index: index,
+					})
+				}
+				_ => node.set_index(Some(index)),
+			}
+		}
+
+		Ok(())
+	}
+
+	fn merge_types(&mut self, types: &mut Vec<Id<T, B>>, new_types: Vec<Id<T, B>>)
+	where
+		T: Eq + Hash,
+		B: Eq + Hash,
+	{
+		match self.types {
+			TypesMerge::Concat => types.extend(new_types),
+			TypesMerge::Union => {
+				for ty in new_types {
+					if !types.contains(&ty) {
+						types.push(ty)
+					}
+				}
+			}
+		}
+	}
+
+	fn merge_graph(&mut self, graph: &mut Option<Graph<T, B>>, new_graph: Option<Graph<T, B>>)
+	where
+		T: Eq + Hash,
+		B: Eq + Hash,
+	{
+		match (self.graph, new_graph) {
+			(GraphMerge::Union, Some(new_graph)) => {
+				graph.get_or_insert_with(Graph::default).extend(new_graph)
+			}
+			(GraphMerge::Union, None) => (),
+			(GraphMerge::Override, new_graph) => *graph = new_graph,
+		}
+	}
+
+	fn merge_included(
+		&mut self,
+		included: &mut Option<Included<T, B>>,
+		new_included: Option<Included<T, B>>,
+	) where
+		T: Eq + Hash,
+		B: Eq + Hash,
+	{
+		match (self.graph, new_included) {
+			(GraphMerge::Union, Some(new_included)) => included
+				.get_or_insert_with(Included::default)
+				.extend(new_included),
+			(GraphMerge::Union, None) => (),
+			(GraphMerge::Override, new_included) => *included = new_included,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{flattening::NodeMapGraph, Indexed, Node};
+	use iref::IriBuf;
+	use rdf_types::BlankIdBuf;
+
+	fn iri(s: &str) -> Id {
+		Id::iri(IriBuf::new(s.to_owned()).unwrap())
+	}
+
+	fn typed_node(types: &[&str], index: Option<&str>) -> IndexedNode<IriBuf, BlankIdBuf> {
+		let mut node = Node::with_id(iri("https://example.com/a"));
+		node.types = Some(types.iter().map(|t| iri(t)).collect());
+		Indexed::new(node, index.map(ToOwned::to_owned))
+	}
+
+	#[test]
+	fn union_types() {
+		let mut graph = NodeMapGraph::new();
+		let mut policy = MergeOptions::default().with_types(TypesMerge::Union);
+		graph
+			.merge_node_with(typed_node(&["https://example.com/T"], None), &mut policy)
+			.ok()
+			.unwrap();
+		graph
+			.merge_node_with(
+				typed_node(&["https://example.com/T", "https://example.com/U"], None),
+				&mut policy,
+			)
+			.ok()
+			.unwrap();
+
+		let node = graph.get(&iri("https://example.com/a")).unwrap();
+		assert_eq!(node.types().len(), 2)
+	}
+
+	#[test]
+	fn override_index() {
+		let mut graph = NodeMapGraph::new();
+		graph.merge_node(typed_node(&[], Some("a"))).ok().unwrap();
+		graph.merge_node(typed_node(&[], Some("b"))).ok().unwrap();
+
+		let node = graph.get(&iri("https://example.com/a")).unwrap();
+		assert_eq!(node.index(), Some("b"))
+	}
+
+	#[test]
+	fn conflicting_indexes() {
+		let mut graph = NodeMapGraph::new();
+		let mut policy = MergeOptions::default().with_index(IndexMerge::Error);
+		graph
+			.merge_node_with(typed_node(&[], Some("a")), &mut policy)
+			.ok()
+			.unwrap();
+		assert!(graph
+			.merge_node_with(typed_node(&[], Some("b")), &mut policy)
+			.is_err())
+	}
+}
@@ -1,16 +1,42 @@
 //! Flattening algorithm and related types.
 use crate::flattened::UnorderedFlattenedDocument;
-use crate::{ExpandedDocument, FlattenedDocument, IndexedNode, IndexedObject, Object};
+use crate::{
+	progress::{Progress, Yielder},
+	ExpandedDocument, FlattenedDocument, IndexedNode, IndexedObject, Object,
+};
 use contextual::WithContext;
 use rdf_types::{Generator, Vocabulary};
 use std::collections::HashSet;
 use std::hash::Hash;
 
 mod environment;
+mod find;
+mod hierarchy;
+mod index;
+mod json_map;
+mod merge;
 mod node_map;
+mod persist;
+mod prune;
+mod smush;
+mod walk;
+
+#[cfg(feature = "petgraph")]
+mod petgraph;
 
 pub use environment::Environment;
+pub use find::FindOptions;
+pub use hierarchy::Hierarchy;
+pub use index::{IndexKinds, NodeMapIndexes};
+pub use merge::*;
 pub use node_map::*;
+pub use persist::*;
+pub use prune::*;
+pub use smush::*;
+pub use walk::*;
+
+#[cfg(feature = "petgraph")]
+pub use self::petgraph::PetGraph;
 
 pub type FlattenResult<I, B> = Result<FlattenedDocument<I, B>, ConflictingIndexes<I, B>>;
 
@@ -67,7 +93,7 @@ impl<I: Clone + Eq + Hash, B: Clone + Eq + Hash> Flatten<I, B> for ExpandedDocum
 		V: Vocabulary<Iri = I, BlankId = B>,
 	{
 		Ok(self
-			.generate_node_map_with(vocabulary, generator)?
+			.into_node_map_with(vocabulary, generator)?
 			.flatten_with(vocabulary, ordered))
 	}
 
@@ -80,11 +106,53 @@ impl<I: Clone + Eq + Hash, B: Clone + Eq + Hash> Flatten<I, B> for ExpandedDocum
 		V: Vocabulary<Iri = I, BlankId = B>,
 	{
 		Ok(self
-			.generate_node_map_with(vocabulary, generator)?
+			.into_node_map_with(vocabulary, generator)?
 			.flatten_unordered())
 	}
 }
 
+impl<I: Clone + Eq + Hash, B: Clone + Eq + Hash> ExpandedDocument<I, B> {
+	/// Flattens this document, reporting the progress of the node map
+	/// generation to `progress`.
+	///
+	/// The progress is reported, and the cancellation checked, after each
+	/// top-level object of the document.
+	pub fn flatten_with_progress<V, G: Generator<V>>(
+		self,
+		vocabulary: &mut V,
+		generator: G,
+		ordered: bool,
+		progress: &Progress,
+	) -> Result<FlattenedDocument<I, B>, FlatteningError<I, B>>
+	where
+		V: Vocabulary<Iri = I, BlankId = B>,
+	{
+		Ok(self
+			.into_node_map_with_progress(vocabulary, generator, progress)?
+			.flatten_with(vocabulary, ordered))
+	}
+
+	/// Flattens this document, yielding to the async executor as directed by
+	/// `yielder` during the node map generation.
+	///
+	/// See [`Self::into_node_map_yielding`].
+	pub async fn flatten_yielding<V, G: Generator<V>>(
+		self,
+		vocabulary: &mut V,
+		generator: G,
+		ordered: bool,
+		yielder: &Yielder,
+	) -> FlattenResult<I, B>
+	where
+		V: Vocabulary<Iri = I, BlankId = B>,
+	{
+		Ok(self
+			.into_node_map_yielding(vocabulary, generator, yielder)
+			.await?
+			.flatten_with(vocabulary, ordered))
+	}
+}
+
 fn filter_graph<T, B>(node: IndexedNode<T, B>) -> Option<IndexedNode<T, B>> {
 	if node.index().is_none() && node.is_empty() {
 		None
@@ -178,3 +246,17 @@ impl<T: Clone + Eq + Hash, B: Clone + Eq + Hash> NodeMap<T, B> {
 			.collect()
 	}
 }
+
+impl<T: Clone + Eq + Hash, B: Clone + Eq + Hash> From<NodeMap<T, B>> for ExpandedDocument<T, B> {
+	/// Turns the node map into a flattened expanded document.
+	///
+	/// Named graphs are represented by nodes with a `@graph` entry, as in the
+	/// output of the flattening algorithm.
+	fn from(node_map: NodeMap<T, B>) -> Self {
+		node_map
+			.flatten_unordered()
+			.into_iter()
+			.map(|node| node.map_inner(Object::node))
+			.collect()
+	}
+}
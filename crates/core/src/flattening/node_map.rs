@@ -1,11 +1,16 @@
-use super::Environment;
-use crate::{object, ExpandedDocument, Id, Indexed, IndexedNode, IndexedObject, Node, Object};
+use super::{Environment, MergeOptions, MergePolicy, NodeMapIndexes};
+use crate::{
+	object,
+	progress::{Cancelled, Progress, Yielder},
+	utils::DefaultHashBuilder,
+	ExpandedDocument, Id, Indexed, IndexedNode, IndexedObject, Node, Object,
+};
 use educe::Educe;
-use indexmap::IndexSet;
 use rdf_types::{
 	vocabulary::{BlankIdVocabulary, IriVocabulary},
 	Generator, Vocabulary,
 };
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::hash::Hash;
 
@@ -20,20 +25,61 @@ pub struct ConflictingIndexes<T, B> {
 	pub conflicting_index: String,
 }
 
-pub type Parts<T, B> = (NodeMapGraph<T, B>, HashMap<Id<T, B>, NodeMapGraph<T, B>>);
+#[cfg(feature = "miette")]
+json_ld_syntax::impl_diagnostic!(
+	impl<T, B> ConflictingIndexes<T, B>,
+	json_ld_syntax::ErrorCode::ConflictingIndexes
+);
+
+/// Error raised by the flattening operations reporting their progress.
+#[derive(Clone, Debug, thiserror::Error)]
+pub enum FlatteningError<T, B> {
+	#[error(transparent)]
+	ConflictingIndexes(ConflictingIndexes<T, B>),
+
+	#[error(transparent)]
+	Cancelled(Cancelled),
+}
+
+impl<T, B> From<ConflictingIndexes<T, B>> for FlatteningError<T, B> {
+	fn from(e: ConflictingIndexes<T, B>) -> Self {
+		Self::ConflictingIndexes(e)
+	}
+}
+
+impl<T, B> From<Cancelled> for FlatteningError<T, B> {
+	fn from(e: Cancelled) -> Self {
+		Self::Cancelled(e)
+	}
+}
+
+pub type Parts<T, B> = (
+	NodeMapGraph<T, B>,
+	HashMap<Id<T, B>, NodeMapGraph<T, B>, DefaultHashBuilder>,
+);
 
 /// Node identifier to node definition map.
+///
+/// This is the node map of the
+/// [Node Map Generation algorithm](https://www.w3.org/TR/json-ld11-api/#node-map-generation),
+/// mapping each graph of a document (the default graph and the named graphs)
+/// to the nodes it contains. Every node is flattened: its property values are
+/// either values, lists, or references to other nodes by identifier.
+///
+/// This structure is part of the stable API and can be used as an in-memory
+/// graph store. It can be turned back into an expanded document using
+/// [`ExpandedDocument::from`].
 #[derive(Educe)]
 #[educe(Default)]
 pub struct NodeMap<T, B> {
-	graphs: HashMap<Id<T, B>, NodeMapGraph<T, B>>,
+	graphs: HashMap<Id<T, B>, NodeMapGraph<T, B>, DefaultHashBuilder>,
 	default_graph: NodeMapGraph<T, B>,
 }
 
 impl<T, B> NodeMap<T, B> {
 	pub fn new() -> Self {
 		Self {
-			graphs: HashMap::new(),
+			graphs: HashMap::default(),
 			default_graph: NodeMapGraph::new(),
 		}
 	}
@@ -52,6 +98,16 @@ impl<T, B> NodeMap<T, B> {
 	pub fn iter_named(&self) -> std::collections::hash_map::Iter<Id<T, B>, NodeMapGraph<T, B>> {
 		self.graphs.iter()
 	}
+
+	/// Returns the default graph.
+	pub fn default_graph(&self) -> &NodeMapGraph<T, B> {
+		&self.default_graph
+	}
+
+	/// Returns a mutable reference to the default graph.
+	pub fn default_graph_mut(&mut self) -> &mut NodeMapGraph<T, B> {
+		&mut self.default_graph
+	}
 }
 
 impl<T: Eq + Hash, B: Eq + Hash> NodeMap<T, B> {
@@ -75,10 +131,15 @@ impl<T: Eq + Hash, B: Eq + Hash> NodeMap<T, B> {
 		}
 	}
 
+	/// Removes the named graph with the given identifier, and returns it.
+	pub fn remove_graph(&mut self, id: &Id<T, B>) -> Option<NodeMapGraph<T, B>> {
+		self.graphs.remove(id)
+	}
+
 	/// Merge all the graphs into a single `NodeMapGraph`.
 	///
 	/// The order in which graphs are merged is not defined.
-	pub fn merge(self) -> NodeMapGraph<T, B>
+	pub fn merge(self) -> Result<NodeMapGraph<T, B>, ConflictingIndexes<T, B>>
 	where
 		T: Clone,
 		B: Clone,
@@ -86,10 +147,31 @@ impl<T: Eq + Hash, B: Eq + Hash> NodeMap<T, B> {
 		let mut result = self.default_graph;
 
 		for (_, graph) in self.graphs {
-			result.merge_with(graph)
+			result.merge_with(graph)?
 		}
 
-		result
+		Ok(result)
+	}
+
+	/// Merge all the graphs into a single `NodeMapGraph` using the given
+	/// merge `policy`.
+	///
+	/// The order in which graphs are merged is not defined.
+	pub fn merge_with_policy(
+		self,
+		policy: &mut impl MergePolicy<T, B>,
+	) -> Result<NodeMapGraph<T, B>, ConflictingIndexes<T, B>>
+	where
+		T: Clone,
+		B: Clone,
+	{
+		let mut result = self.default_graph;
+
+		for (_, graph) in self.graphs {
+			result.merge_with_policy(graph, policy)?
+		}
+
+		Ok(result)
 	}
 }
 
@@ -149,13 +231,15 @@ impl<T, B> IntoIterator for NodeMap<T, B> {
 #[derive(Educe)]
 #[educe(Default)]
 pub struct NodeMapGraph<T, B> {
-	nodes: HashMap<Id<T, B>, IndexedNode<T, B>>,
+	nodes: HashMap<Id<T, B>, IndexedNode<T, B>, DefaultHashBuilder>,
+	pub(super) indexes: Option<NodeMapIndexes<T, B>>,
 }
 
 impl<T, B> NodeMapGraph<T, B> {
 	pub fn new() -> Self {
 		Self {
-			nodes: HashMap::new(),
+			nodes: HashMap::default(),
+			indexes: None,
 		}
 	}
 }
@@ -172,8 +256,28 @@ impl<T: Eq + Hash, B: Eq + Hash> NodeMapGraph<T, B> {
 		self.nodes.get(id)
 	}
 
+	/// Returns a mutable reference to the node with the given identifier.
+	///
+	/// The node is marked as stale in the [secondary indexes](NodeMapIndexes),
+	/// if any.
 	pub fn get_mut(&mut self, id: &Id<T, B>) -> Option<&mut IndexedNode<T, B>> {
-		self.nodes.get_mut(id)
+		let node = self.nodes.get_mut(id)?;
+		if let Some(indexes) = &mut self.indexes {
+			indexes.mark_stale(node)
+		}
+
+		Some(node)
+	}
+
+	/// Removes the node with the given identifier from the graph, and returns
+	/// it.
+	pub fn remove(&mut self, id: &Id<T, B>) -> Option<IndexedNode<T, B>> {
+		let node = self.nodes.remove(id)?;
+		if let Some(indexes) = &mut self.indexes {
+			indexes.remove(&node)
+		}
+
+		Some(node)
 	}
 
 	pub fn declare_node(&mut self, id: Id<T, B>, index: Option<&str>) -> DeclareNodeResult<T, B>
@@ -202,20 +306,47 @@ impl<T: Eq + Hash, B: Eq + Hash> NodeMapGraph<T, B> {
 			);
 		}
 
-		Ok(self.nodes.get_mut(&id).unwrap())
+		let node = self.nodes.get_mut(&id).unwrap();
+		if let Some(indexes) = &mut self.indexes {
+			indexes.mark_stale(node)
+		}
+
+		Ok(node)
 	}
 
 	/// Merge this graph with `other`.
 	///
 	/// This calls [`merge_node`](Self::merge_node) with every node of `other`.
-	pub fn merge_with(&mut self, other: Self)
+	pub fn merge_with(&mut self, other: Self) -> Result<(), ConflictingIndexes<T, B>>
 	where
 		T: Clone,
 		B: Clone,
 	{
 		for (_, node) in other {
-			self.merge_node(node)
+			self.merge_node(node)?
 		}
+
+		Ok(())
+	}
+
+	/// Merge this graph with `other` using the given merge `policy`.
+	///
+	/// This calls [`merge_node_with`](Self::merge_node_with) with every node
+	/// of `other`.
+	pub fn merge_with_policy(
+		&mut self,
+		other: Self,
+		policy: &mut impl MergePolicy<T, B>,
+	) -> Result<(), ConflictingIndexes<T, B>>
+	where
+		T: Clone,
+		B: Clone,
+	{
+		for (_, node) in other {
+			self.merge_node_with(node, policy)?
+		}
+
+		Ok(())
 	}
 
 	/// Merge the given `node` into the graph.
@@ -226,7 +357,28 @@ impl<T: Eq + Hash, B: Eq + Hash> NodeMapGraph<T, B> {
 	/// - The list of `node` types is concatenated after the preexisting types.
 	/// - The graph and imported values are overridden.
 	/// - Properties and reverse properties are merged.
-	pub fn merge_node(&mut self, node: IndexedNode<T, B>)
+	///
+	/// Use [`merge_node_with`](Self::merge_node_with) to customize this
+	/// behavior.
+	pub fn merge_node(&mut self, node: IndexedNode<T, B>) -> Result<(), ConflictingIndexes<T, B>>
+	where
+		T: Clone,
+		B: Clone,
+	{
+		self.merge_node_with(node, &mut MergeOptions::default())
+	}
+
+	/// Merge the given `node` into the graph using the given merge `policy`.
+	///
+	/// The `node` must has an identifier, or this function will have no effect.
+	/// If there is already a node with the same identifier, its index, types,
+	/// graph and included values are merged according to `policy`.
+	/// Properties and reverse properties are always merged.
+	pub fn merge_node_with(
+		&mut self,
+		node: IndexedNode<T, B>,
+		policy: &mut impl MergePolicy<T, B>,
+	) -> Result<(), ConflictingIndexes<T, B>>
 	where
 		T: Clone,
 		B: Clone,
@@ -235,8 +387,9 @@ impl<T: Eq + Hash, B: Eq + Hash> NodeMapGraph<T, B> {
 
 		if let Some(id) = &node.id {
 			if let Some(entry) = self.nodes.get_mut(id) {
-				if let Some(index) = index {
-					entry.set_index(Some(index))
+				policy.merge_index(entry, index)?;
+				if let Some(indexes) = &mut self.indexes {
+					indexes.remove(entry)
 				}
 			} else {
 				self.nodes
@@ -246,11 +399,11 @@ impl<T: Eq + Hash, B: Eq + Hash> NodeMapGraph<T, B> {
 			let flat_node = self.nodes.get_mut(id).unwrap();
 
 			if let Some(types) = node.types {
-				flat_node.types_mut_or_default().extend(types);
+				policy.merge_types(flat_node.types_mut_or_default(), types);
 			}
 
-			flat_node.set_graph_entry(node.graph);
-			flat_node.set_included(node.included);
+			policy.merge_graph(&mut flat_node.graph, node.graph);
+			policy.merge_included(&mut flat_node.included, node.included);
 			flat_node.properties_mut().extend_unique(node.properties);
 
 			if let Some(props) = node.reverse_properties {
@@ -258,7 +411,13 @@ impl<T: Eq + Hash, B: Eq + Hash> NodeMapGraph<T, B> {
 					.reverse_properties_or_default()
 					.extend_unique(props);
 			}
+
+			if let Some(indexes) = &mut self.indexes {
+				indexes.insert(flat_node)
+			}
 		}
+
+		Ok(())
 	}
 
 	pub fn nodes(&self) -> NodeMapGraphNodes<T, B> {
@@ -302,10 +461,104 @@ impl<T: Clone + Eq + Hash, B: Clone + Eq + Hash> ExpandedDocument<T, B> {
 		let mut node_map: NodeMap<T, B> = NodeMap::new();
 		let mut env: Environment<V, G> = Environment::new(vocabulary, generator);
 		for object in self {
-			extend_node_map(&mut env, &mut node_map, object, None)?;
+			extend_node_map(&mut env, &mut node_map, Cow::Borrowed(object), None)?;
+		}
+		Ok(node_map)
+	}
+
+	/// Generates the node map of this document, consuming it.
+	///
+	/// Contrarily to [`Self::generate_node_map_with`], values, indexes and
+	/// property identifiers are moved into the node map instead of being
+	/// cloned.
+	pub fn into_node_map_with<V: Vocabulary<Iri = T, BlankId = B>, G: Generator<V>>(
+		self,
+		vocabulary: &mut V,
+		generator: G,
+	) -> Result<NodeMap<T, B>, ConflictingIndexes<T, B>> {
+		let mut node_map: NodeMap<T, B> = NodeMap::new();
+		let mut env: Environment<V, G> = Environment::new(vocabulary, generator);
+		for object in self.into_objects() {
+			extend_node_map(&mut env, &mut node_map, Cow::Owned(object), None)?;
 		}
 		Ok(node_map)
 	}
+
+	/// Generates the node map of this document, reporting the progress to
+	/// `progress`.
+	///
+	/// The progress is reported, and the cancellation checked, after each
+	/// top-level object of the document.
+	pub fn generate_node_map_with_progress<V: Vocabulary<Iri = T, BlankId = B>, G: Generator<V>>(
+		&self,
+		vocabulary: &mut V,
+		generator: G,
+		progress: &Progress,
+	) -> Result<NodeMap<T, B>, FlatteningError<T, B>> {
+		let mut node_map: NodeMap<T, B> = NodeMap::new();
+		let mut env: Environment<V, G> = Environment::new(vocabulary, generator);
+		let total = self.len();
+		for (i, object) in self.iter().enumerate() {
+			progress.check()?;
+			extend_node_map(&mut env, &mut node_map, Cow::Borrowed(object), None)?;
+			progress.report(i + 1, total)
+		}
+		Ok(node_map)
+	}
+
+	/// Generates the node map of this document, consuming it and reporting
+	/// the progress to `progress`.
+	///
+	/// See [`Self::generate_node_map_with_progress`].
+	pub fn into_node_map_with_progress<V: Vocabulary<Iri = T, BlankId = B>, G: Generator<V>>(
+		self,
+		vocabulary: &mut V,
+		generator: G,
+		progress: &Progress,
+	) -> Result<NodeMap<T, B>, FlatteningError<T, B>> {
+		let mut node_map: NodeMap<T, B> = NodeMap::new();
+		let mut env: Environment<V, G> = Environment::new(vocabulary, generator);
+		let total = self.len();
+		for (i, object) in self.into_objects().into_iter().enumerate() {
+			progress.check()?;
+			extend_node_map(&mut env, &mut node_map, Cow::Owned(object), None)?;
+			progress.report(i + 1, total)
+		}
+		Ok(node_map)
+	}
+
+	/// Generates the node map of this document, consuming it and yielding to
+	/// the async executor as directed by `yielder`.
+	///
+	/// Each top-level object of the document counts as one processed item.
+	/// See [`Self::into_node_map_with`].
+	pub async fn into_node_map_yielding<V: Vocabulary<Iri = T, BlankId = B>, G: Generator<V>>(
+		self,
+		vocabulary: &mut V,
+		generator: G,
+		yielder: &Yielder,
+	) -> Result<NodeMap<T, B>, ConflictingIndexes<T, B>> {
+		let mut node_map: NodeMap<T, B> = NodeMap::new();
+		let mut env: Environment<V, G> = Environment::new(vocabulary, generator);
+		for object in self.into_objects() {
+			extend_node_map(&mut env, &mut node_map, Cow::Owned(object), None)?;
+			yielder.tick().await
+		}
+		Ok(node_map)
+	}
+
+	/// Generates the node map of this document, consuming it.
+	///
+	/// See [`Self::into_node_map_with`].
+	pub fn into_node_map<G: Generator>(
+		self,
+		generator: G,
+	) -> Result<NodeMap<T, B>, ConflictingIndexes<T, B>>
+	where
+		(): Vocabulary<Iri = T, BlankId = B>,
+	{
+		self.into_node_map_with(rdf_types::vocabulary::no_vocabulary_mut(), generator)
+	}
 }
 
 pub type ExtendNodeMapResult<V> = Result<
@@ -314,52 +567,208 @@ pub type ExtendNodeMapResult<V> = Result<
 >;
 
 /// Extends the `NodeMap` with the given `element` of an expanded JSON-LD document.
-fn extend_node_map<N: Vocabulary, G: Generator<N>>(
+///
+/// The element is traversed iteratively, using an explicit stack of [`Task`]s
+/// instead of recursion, so that deeply nested documents cannot overflow the
+/// call stack. Tasks are executed in the same order as the recursive
+/// algorithm, so node identifiers are generated in the same order.
+///
+/// Borrowed elements are cloned into the node map, while owned elements are
+/// moved into it.
+fn extend_node_map<'a, N: Vocabulary, G: Generator<N>>(
 	env: &mut Environment<N, G>,
 	node_map: &mut NodeMap<N::Iri, N::BlankId>,
-	element: &IndexedObject<N::Iri, N::BlankId>,
+	element: Cow<'a, IndexedObject<N::Iri, N::BlankId>>,
 	active_graph: Option<&Id<N::Iri, N::BlankId>>,
 ) -> ExtendNodeMapResult<N>
 where
 	N::Iri: Clone + Eq + Hash,
 	N::BlankId: Clone + Eq + Hash,
 {
-	match element.inner() {
-		Object::Value(value) => {
-			let flat_value = value.clone();
-			Ok(Indexed::new(
-				Object::Value(flat_value),
-				element.index().map(ToOwned::to_owned),
-			))
-		}
-		Object::List(list) => {
-			let mut flat_list = Vec::new();
-
-			for item in list {
-				flat_list.push(extend_node_map(env, node_map, item, active_graph)?);
+	// Identifiers of the nodes and graphs referred to by the tasks.
+	let mut ids = Vec::new();
+	let active_graph = active_graph.map(|id| {
+		ids.push(id.clone());
+		0
+	});
+
+	let mut tasks = vec![Task::Object(element, active_graph)];
+
+	// Flattened objects, consumed by the tasks scheduled after them.
+	let mut results = Vec::new();
+
+	while let Some(task) = tasks.pop() {
+		match task {
+			Task::Object(Cow::Borrowed(element), graph) => {
+				let index = element.index().map(Cow::Borrowed);
+				match element.inner() {
+					Object::Value(value) => results.push(Indexed::new(
+						Object::Value(value.clone()),
+						index.map(Cow::into_owned),
+					)),
+					Object::List(list) => {
+						tasks.push(Task::List(list.len(), index));
+						tasks.extend(
+							list.iter()
+								.rev()
+								.map(|item| Task::Object(Cow::Borrowed(item), graph)),
+						)
+					}
+					Object::Node(node) => tasks.push(Task::Node(Cow::Borrowed(node), index, graph)),
+				}
 			}
+			Task::Object(Cow::Owned(element), graph) => {
+				let (object, index) = element.into_parts();
+				match object {
+					Object::Value(value) => results.push(Indexed::new(Object::Value(value), index)),
+					Object::List(list) => {
+						tasks.push(Task::List(list.len(), index.map(Cow::Owned)));
+						tasks.extend(
+							list.into_iter()
+								.rev()
+								.map(|item| Task::Object(Cow::Owned(item), graph)),
+						)
+					}
+					Object::Node(node) => {
+						tasks.push(Task::Node(Cow::Owned(*node), index.map(Cow::Owned), graph))
+					}
+				}
+			}
+			Task::Node(node, index, graph) => {
+				let id = extend_node_map_from_node(
+					env,
+					node_map,
+					&mut ids,
+					&mut tasks,
+					node,
+					index.as_deref(),
+					graph,
+				)?;
+				results.push(Indexed::none(Object::node(Node::with_id(id))))
+			}
+			Task::List(len, index) => {
+				let flat_list = results.split_off(results.len() - len);
+				results.push(Indexed::new(
+					Object::List(object::List::new(flat_list)),
+					index.map(Cow::into_owned),
+				))
+			}
+			Task::Graph { node, len, graph } => {
+				let flat_node = node_map
+					.graph_mut(graph.map(|g| &ids[g]))
+					.unwrap()
+					.get_mut(&ids[node])
+					.unwrap();
+				let flat_graph = results.drain(results.len() - len..);
+				match flat_node.graph_entry_mut() {
+					Some(graph) => graph.extend(flat_graph),
+					None => flat_node.set_graph_entry(Some(flat_graph.collect())),
+				}
+			}
+			Task::Property {
+				node,
+				property,
+				len,
+				graph,
+			} => {
+				let flat_objects = results.drain(results.len() - len..);
+				node_map
+					.graph_mut(graph.map(|g| &ids[g]))
+					.unwrap()
+					.get_mut(&ids[node])
+					.unwrap()
+					.properties_mut()
+					.insert_all_unique(property.into_owned(), flat_objects)
+			}
+			Task::Reverse {
+				node,
+				property,
+				graph,
+			} => {
+				let flat_subject = results.pop().unwrap();
+				let subject_id = flat_subject.as_node().unwrap().id.as_ref().unwrap();
 
-			Ok(Indexed::new(
-				Object::List(object::List::new(flat_list)),
-				element.index().map(ToOwned::to_owned),
-			))
-		}
-		Object::Node(node) => {
-			let flat_node =
-				extend_node_map_from_node(env, node_map, node, element.index(), active_graph)?;
-			Ok(flat_node.map_inner(Object::node))
+				let flat_subject = node_map
+					.graph_mut(graph.map(|g| &ids[g]))
+					.unwrap()
+					.get_mut(subject_id)
+					.unwrap();
+
+				flat_subject.properties_mut().insert_unique(
+					property.into_owned(),
+					Indexed::none(Object::node(Node::with_id(ids[node].clone()))),
+				)
+			}
+			Task::Discard => {
+				results.pop();
+			}
 		}
 	}
+
+	Ok(results.pop().unwrap())
+}
+
+/// Node map construction task.
+///
+/// Nodes and graphs are referred to by their index in the list of
+/// identifiers maintained by [`extend_node_map`]. Tasks producing a flattened
+/// object push it on the result stack, where it is consumed by a task
+/// scheduled after it.
+enum Task<'a, T: Clone, B: Clone> {
+	/// Flattens the given object in the given graph.
+	Object(Cow<'a, IndexedObject<T, B>>, Option<usize>),
+
+	/// Flattens the given node, with the given index, in the given graph.
+	Node(Cow<'a, Node<T, B>>, Option<Cow<'a, str>>, Option<usize>),
+
+	/// Collects the last `len` flattened objects into a list with the given
+	/// index.
+	List(usize, Option<Cow<'a, str>>),
+
+	/// Adds the last `len` flattened objects to the graph entry of `node`.
+	Graph {
+		node: usize,
+		len: usize,
+		graph: Option<usize>,
+	},
+
+	/// Adds the last `len` flattened objects as values of `property` for
+	/// `node`.
+	Property {
+		node: usize,
+		property: Cow<'a, Id<T, B>>,
+		len: usize,
+		graph: Option<usize>,
+	},
+
+	/// Adds `node` as value of `property` for the last flattened node.
+	Reverse {
+		node: usize,
+		property: Cow<'a, Id<T, B>>,
+		graph: Option<usize>,
+	},
+
+	/// Discards the last flattened object.
+	Discard,
 }
 
-type ExtendNodeMapFromNodeResult<T, B> = Result<Indexed<Node<T, B>>, ConflictingIndexes<T, B>>;
+/// Node to flatten, with its index.
+type NodeTask<'a, T, B> = (Cow<'a, Node<T, B>>, Option<Cow<'a, str>>);
+
+type ExtendNodeMapFromNodeResult<T, B> = Result<Id<T, B>, ConflictingIndexes<T, B>>;
 
-fn extend_node_map_from_node<N: Vocabulary, G: Generator<N>>(
+/// Declares the given node in the node map and schedules the tasks flattening
+/// its entries.
+///
+/// Returns the identifier assigned to the node.
+fn extend_node_map_from_node<'a, N: Vocabulary, G: Generator<N>>(
 	env: &mut Environment<N, G>,
 	node_map: &mut NodeMap<N::Iri, N::BlankId>,
-	node: &Node<N::Iri, N::BlankId>,
+	ids: &mut Vec<Id<N::Iri, N::BlankId>>,
+	tasks: &mut Vec<Task<'a, N::Iri, N::BlankId>>,
+	node: Cow<'a, Node<N::Iri, N::BlankId>>,
 	index: Option<&str>,
-	active_graph: Option<&Id<N::Iri, N::BlankId>>,
+	graph: Option<usize>,
 ) -> ExtendNodeMapFromNodeResult<N::Iri, N::BlankId>
 where
 	N::Iri: Clone + Eq + Hash,
@@ -369,7 +778,7 @@ where
 
 	{
 		let flat_node = node_map
-			.graph_mut(active_graph)
+			.graph_mut(graph.map(|g| &ids[g]))
 			.unwrap()
 			.declare_node(id.clone(), index)?;
 
@@ -383,93 +792,240 @@ where
 		}
 	}
 
-	if let Some(graph_entry) = node.graph_entry() {
+	let n = ids.len();
+	ids.push(id.clone());
+
+	if node.graph_entry().is_some() {
 		node_map.declare_graph(id.clone());
+	}
 
-		let mut flat_graph = IndexSet::new();
-		for object in graph_entry.iter() {
-			let flat_object = extend_node_map(env, node_map, object, Some(&id))?;
-			flat_graph.insert(flat_object);
-		}
+	// Tasks are scheduled in execution order, then reversed.
+	let start = tasks.len();
 
-		let flat_node = node_map
-			.graph_mut(active_graph)
-			.unwrap()
-			.get_mut(&id)
-			.unwrap();
-		match flat_node.graph_entry_mut() {
-			Some(graph) => graph.extend(flat_graph),
-			None => flat_node.set_graph_entry(Some(flat_graph)),
+	match node {
+		Cow::Borrowed(node) => {
+			let borrowed = |inode: &'a IndexedNode<N::Iri, N::BlankId>| {
+				(
+					Cow::Borrowed(inode.inner()),
+					inode.index().map(Cow::Borrowed),
+				)
+			};
+
+			schedule_node_entries(
+				tasks,
+				n,
+				graph,
+				node.graph_entry().map(|g| g.iter().map(Cow::Borrowed)),
+				node.included_entry().into_iter().flatten().map(borrowed),
+				node.properties().iter().map(|(property, objects)| {
+					(Cow::Borrowed(property), objects.iter().map(Cow::Borrowed))
+				}),
+				node.reverse_properties_entry()
+					.into_iter()
+					.flatten()
+					.map(|(property, nodes)| (Cow::Borrowed(property), nodes.iter().map(borrowed))),
+			)
+		}
+		Cow::Owned(node) => {
+			let owned = |inode: IndexedNode<N::Iri, N::BlankId>| {
+				let (inode, index) = inode.into_parts();
+				(Cow::Owned(inode), index.map(Cow::Owned))
+			};
+
+			schedule_node_entries(
+				tasks,
+				n,
+				graph,
+				node.graph.map(|g| g.into_iter().map(Cow::Owned)),
+				node.included.into_iter().flatten().map(owned),
+				node.properties.into_iter().map(|(property, objects)| {
+					(Cow::Owned(property), objects.into_iter().map(Cow::Owned))
+				}),
+				node.reverse_properties
+					.into_iter()
+					.flatten()
+					.map(|(property, nodes)| (Cow::Owned(property), nodes.into_iter().map(owned))),
+			)
 		}
 	}
 
-	if let Some(included_entry) = node.included_entry() {
-		for inode in included_entry {
-			extend_node_map_from_node(env, node_map, inode.inner(), inode.index(), active_graph)?;
-		}
+	tasks[start..].reverse();
+	Ok(id)
+}
+
+/// Schedules the tasks flattening the entries of the node `n`, in execution
+/// order.
+fn schedule_node_entries<'a, T: Clone, B: Clone>(
+	tasks: &mut Vec<Task<'a, T, B>>,
+	n: usize,
+	graph: Option<usize>,
+	graph_entry: Option<impl ExactSizeIterator<Item = Cow<'a, IndexedObject<T, B>>>>,
+	included: impl Iterator<Item = NodeTask<'a, T, B>>,
+	properties: impl Iterator<
+		Item = (
+			Cow<'a, Id<T, B>>,
+			impl ExactSizeIterator<Item = Cow<'a, IndexedObject<T, B>>>,
+		),
+	>,
+	reverse_properties: impl Iterator<
+		Item = (Cow<'a, Id<T, B>>, impl Iterator<Item = NodeTask<'a, T, B>>),
+	>,
+) {
+	if let Some(objects) = graph_entry {
+		let len = objects.len();
+		tasks.extend(objects.map(|object| Task::Object(object, Some(n))));
+		tasks.push(Task::Graph {
+			node: n,
+			len,
+			graph,
+		});
+	}
+
+	for (inode, index) in included {
+		tasks.push(Task::Node(inode, index, graph));
+		tasks.push(Task::Discard)
+	}
+
+	for (property, objects) in properties {
+		let len = objects.len();
+		tasks.extend(objects.map(|object| Task::Object(object, graph)));
+		tasks.push(Task::Property {
+			node: n,
+			property,
+			len,
+			graph,
+		})
 	}
 
-	for (property, objects) in node.properties() {
-		let mut flat_objects = Vec::new();
-		for object in objects {
-			let flat_object = extend_node_map(env, node_map, object, active_graph)?;
-			flat_objects.push(flat_object);
+	for (property, nodes) in reverse_properties {
+		for (subject, index) in nodes {
+			tasks.push(Task::Node(subject, index, graph));
+			tasks.push(Task::Reverse {
+				node: n,
+				property: property.clone(),
+				graph,
+			})
 		}
-		node_map
-			.graph_mut(active_graph)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::TryFromJson;
+	use iref::IriBuf;
+	use rdf_types::{generator, BlankIdBuf};
+
+	#[test]
+	fn into_node_map() {
+		let json = json_syntax::json!([{
+			"@id": "_:g",
+			"@type": [ "https://example.com/T" ],
+			"@graph": [{
+				"@id": "https://example.com/a",
+				"https://example.com/p": [{
+					"@list": [
+						{ "@value": 1 },
+						{ "https://example.com/q": [{ "@value": "x", "@index": "i" }] }
+					]
+				}]
+			}],
+			"@included": [{ "@id": "https://example.com/b", "@index": "k" }],
+			"@reverse": {
+				"https://example.com/r": [{ "@id": "https://example.com/c" }]
+			}
+		}]);
+		let document: ExpandedDocument<IriBuf, BlankIdBuf> =
+			ExpandedDocument::try_from_json_in(&mut (), json).unwrap();
+
+		let borrowed = document
+			.generate_node_map_with(&mut (), generator::Blank::new())
 			.unwrap()
-			.get_mut(&id)
+			.flatten(true);
+		let flattened = ExpandedDocument::from(
+			document
+				.generate_node_map_with(&mut (), generator::Blank::new())
+				.unwrap(),
+		);
+		let owned = document
+			.into_node_map(generator::Blank::new())
 			.unwrap()
-			.properties_mut()
-			.insert_all_unique(property.clone(), flat_objects)
+			.flatten(true);
+		assert!(!borrowed.is_empty());
+		assert_eq!(owned, borrowed);
+		assert_eq!(flattened.len(), owned.len());
+		assert!(owned
+			.into_iter()
+			.all(|node| flattened.objects().contains(&node.map_inner(Object::node))))
 	}
 
-	if let Some(reverse_properties) = node.reverse_properties_entry() {
-		for (property, nodes) in reverse_properties.iter() {
-			for subject in nodes {
-				let flat_subject = extend_node_map_from_node(
-					env,
-					node_map,
-					subject.inner(),
-					subject.index(),
-					active_graph,
-				)?;
+	#[test]
+	fn deep_nesting() {
+		const DEPTH: usize = 100_000;
+		let next = Id::iri(IriBuf::new("https://example.com/next".to_owned()).unwrap());
 
-				let subject_id = flat_subject.id.as_ref().unwrap();
+		// Nodes nested through properties, every other one inside a list.
+		let mut object = IndexedObject::none(Object::node(Node::new()));
+		for i in 0..DEPTH {
+			if i % 2 == 0 {
+				object = IndexedObject::none(Object::List(object::List::new(vec![object])))
+			}
 
-				let flat_subject = node_map
-					.graph_mut(active_graph)
-					.unwrap()
-					.get_mut(subject_id)
-					.unwrap();
+			let mut node = Node::new();
+			node.insert(next.clone(), object);
+			object = IndexedObject::none(Object::node(node))
+		}
 
-				flat_subject.properties_mut().insert_unique(
-					property.clone(),
-					Indexed::none(Object::node(Node::with_id(id.clone()))),
-				)
+		let mut vocabulary = ();
+		let mut node_map = NodeMap::new();
+		let mut env = Environment::new(&mut vocabulary, generator::Blank::new());
+		extend_node_map(&mut env, &mut node_map, Cow::Borrowed(&object), None).unwrap();
+		assert_eq!(node_map.default_graph.nodes.len(), DEPTH + 1);
+
+		// Dismantled iteratively, since dropping it at once would overflow the
+		// stack.
+		loop {
+			object = match object.into_inner() {
+				Object::Node(mut node) => match node.properties_mut().remove(&next) {
+					Some(values) => values.into_iter().next().unwrap(),
+					None => break,
+				},
+				Object::List(list) => list.into_iter().next().unwrap(),
+				Object::Value(_) => unreachable!(),
 			}
-
-			// let mut flat_nodes = Vec::new();
-			// for node in nodes {
-			// 	let flat_node = extend_node_map_from_node(
-			// 		env,
-			// 		node_map,
-			// 		node.inner(),
-			// 		node.index(),
-			// 		active_graph,
-			// 	)?;
-			// 	flat_nodes.push(flat_node);
-			// }
-
-			// node_map
-			// 	.graph_mut(active_graph)
-			// 	.unwrap()
-			// 	.get_mut(&id)
-			// 	.unwrap()
-			// 	.reverse_properties_mut()
-			// 	.insert_all_unique(property.clone(), flat_nodes)
 		}
 	}
 
-	Ok(Indexed::new(Node::with_id(id), None))
+	#[test]
+	fn into_node_map_yielding() {
+		use crate::progress::Yielder;
+		use futures::FutureExt;
+
+		let document = || {
+			ExpandedDocument::try_from_json_in(
+				&mut (),
+				json_syntax::json!([
+					{ "@id": "https://example.com/a", "https://example.com/p": [{ "@value": "A" }] },
+					{ "@id": "https://example.com/b", "https://example.com/p": [{ "@value": "B" }] }
+				]),
+			)
+			.unwrap()
+		};
+
+		let node_map = |yield_every| {
+			let yielder = Yielder::new(yield_every);
+			async move {
+				document()
+					.into_node_map_yielding(&mut (), generator::Blank::new(), &yielder)
+					.await
+			}
+		};
+
+		assert!(node_map(None).now_or_never().is_some());
+		assert!(node_map(Some(3)).now_or_never().is_some());
+		assert!(node_map(Some(2)).now_or_never().is_none());
+
+		let node_map = futures::executor::block_on(node_map(Some(1))).unwrap();
+		assert_eq!(node_map.default_graph.nodes.len(), 2)
+	}
 }
@@ -0,0 +1,370 @@
+use super::{NodeMap, NodeMapGraph};
+use crate::{object::InvalidExpandedJson, Id, IndexedNode, TryFromJson};
+use contextual::WithContext;
+use json_ld_syntax::IntoJsonWithContext;
+use json_syntax::Value;
+use rdf_types::{Vocabulary, VocabularyMut};
+use std::hash::Hash;
+
+/// Version of the persisted node map format.
+///
+/// It is incremented every time the format changes in an incompatible way.
+pub const NODE_MAP_FORMAT_VERSION: u16 = 1;
+
+/// Magic number starting every binary node map.
+#[cfg(feature = "binary-node-map")]
+const MAGIC: &[u8; 4] = b"JLDN";
+
+/// Invalid persisted node map error.
+#[derive(Debug, thiserror::Error)]
+pub enum InvalidNodeMap {
+	#[error("not a persisted node map")]
+	InvalidHeader,
+
+	#[error("unsupported node map format version {0} (expected {NODE_MAP_FORMAT_VERSION})")]
+	UnsupportedVersion(u64),
+
+	#[error("invalid graph `{0}`")]
+	InvalidGraph(String),
+
+	#[error("invalid node: {0:?}")]
+	InvalidNode(InvalidExpandedJson),
+
+	#[error("node without identifier")]
+	MissingId,
+
+	#[error("index `{0}` conflicts with index `{1}`")]
+	ConflictingIndexes(String, String),
+
+	#[cfg(feature = "binary-node-map")]
+	#[error("binary node map encoding failed: {0}")]
+	Encoding(#[from] ciborium::ser::Error<std::io::Error>),
+
+	#[cfg(feature = "binary-node-map")]
+	#[error("binary node map decoding failed: {0}")]
+	Decoding(#[from] ciborium::de::Error<std::io::Error>),
+}
+
+impl<T: Clone + Eq + Hash, B: Clone + Eq + Hash> NodeMap<T, B> {
+	/// Returns the persistable JSON representation of this node map.
+	///
+	/// Unlike the [flattened form](NodeMap::flatten) or the
+	/// [subject-centric form](NodeMap::to_json_map_with), this representation
+	/// is lossless: it can be loaded back with [`Self::from_json_in`] into
+	/// the same node map without flattening the original document again.
+	///
+	/// ```json
+	/// {
+	///     "version": 1,
+	///     "default": [ { "@id": "https://example.com/a", ... }, ... ],
+	///     "named": {
+	///         "https://example.com/graph": [ ... ]
+	///     }
+	/// }
+	/// ```
+	///
+	/// Graphs and nodes are sorted by identifier so that the output is
+	/// stable. Identifiers are written in their lexical form using the given
+	/// `vocabulary`, and interned again when loaded: the result is portable
+	/// even when `T` and `B` are vocabulary indexes.
+	pub fn to_json_with<N>(&self, vocabulary: &N) -> Value
+	where
+		N: Vocabulary<Iri = T, BlankId = B>,
+	{
+		let mut graphs: Vec<_> = self
+			.iter_named()
+			.map(|(id, graph)| (id.with(vocabulary).as_str().to_owned(), graph))
+			.collect();
+		graphs.sort_by(|a, b| a.0.cmp(&b.0));
+
+		let mut named = json_syntax::Object::new();
+		for (id, graph) in graphs {
+			named.insert(id.into(), graph.to_json_with(vocabulary));
+		}
+
+		let mut result = json_syntax::Object::new();
+		result.insert(
+			"version".into(),
+			Value::Number(NODE_MAP_FORMAT_VERSION.into()),
+		);
+		result.insert(
+			"default".into(),
+			self.default_graph().to_json_with(vocabulary),
+		);
+		result.insert("named".into(), Value::Object(named));
+		Value::Object(result)
+	}
+
+	/// Returns the persistable JSON representation of this node map.
+	///
+	/// See [`Self::to_json_with`].
+	pub fn to_json(&self) -> Value
+	where
+		(): Vocabulary<Iri = T, BlankId = B>,
+	{
+		self.to_json_with(&())
+	}
+
+	/// Loads a node map persisted with [`Self::to_json_with`], interning its
+	/// identifiers into the given `vocabulary`.
+	pub fn from_json_in(
+		vocabulary: &mut impl VocabularyMut<Iri = T, BlankId = B>,
+		json: Value,
+	) -> Result<Self, InvalidNodeMap> {
+		let mut entries = match json {
+			Value::Object(object) => object,
+			_ => return Err(InvalidNodeMap::InvalidHeader),
+		};
+
+		let version = match entries.remove_unique("version") {
+			Ok(Some(entry)) => entry
+				.value
+				.as_number()
+				.and_then(|n| n.as_u64())
+				.ok_or(InvalidNodeMap::InvalidHeader)?,
+			_ => return Err(InvalidNodeMap::InvalidHeader),
+		};
+
+		if version != u64::from(NODE_MAP_FORMAT_VERSION) {
+			return Err(InvalidNodeMap::UnsupportedVersion(version));
+		}
+
+		let mut result = Self::new();
+
+		if let Ok(Some(entry)) = entries.remove_unique("default") {
+			*result.default_graph_mut() = NodeMapGraph::from_json_in(vocabulary, entry.value)?
+		}
+
+		match entries.remove_unique("named") {
+			Ok(Some(entry)) => match entry.value {
+				Value::Object(named) => {
+					for entry in named {
+						let key = entry.key.to_string();
+						let id = Id::try_from_json_in(vocabulary, Value::String(entry.key))
+							.map_err(|_| InvalidNodeMap::InvalidGraph(key))?;
+						let graph = NodeMapGraph::from_json_in(vocabulary, entry.value)?;
+						result.declare_graph(id.clone());
+						*result.graph_mut(Some(&id)).unwrap() = graph
+					}
+				}
+				_ => return Err(InvalidNodeMap::InvalidHeader),
+			},
+			Ok(None) => (),
+			Err(_) => return Err(InvalidNodeMap::InvalidHeader),
+		}
+
+		Ok(result)
+	}
+
+	/// Loads a node map persisted with [`Self::to_json`].
+	pub fn from_json(json: Value) -> Result<Self, InvalidNodeMap>
+	where
+		(): VocabularyMut<Iri = T, BlankId = B>,
+	{
+		Self::from_json_in(&mut (), json)
+	}
+
+	/// Encodes this node map into a compact binary blob.
+	///
+	/// The blob is the [CBOR](https://www.rfc-editor.org/rfc/rfc8949)
+	/// encoding of the [persistable JSON representation](Self::to_json_with),
+	/// preceded by a header containing the
+	/// [format version](NODE_MAP_FORMAT_VERSION).
+	#[cfg(feature = "binary-node-map")]
+	pub fn to_binary_with<N>(&self, vocabulary: &N) -> Result<Vec<u8>, InvalidNodeMap>
+	where
+		N: Vocabulary<Iri = T, BlankId = B>,
+	{
+		let mut bytes = MAGIC.to_vec();
+		bytes.extend(NODE_MAP_FORMAT_VERSION.to_be_bytes());
+		ciborium::into_writer(&self.to_json_with(vocabulary), &mut bytes)?;
+		Ok(bytes)
+	}
+
+	/// Encodes this node map into a compact binary blob.
+	///
+	/// See [`Self::to_binary_with`].
+	#[cfg(feature = "binary-node-map")]
+	pub fn to_binary(&self) -> Result<Vec<u8>, InvalidNodeMap>
+	where
+		(): Vocabulary<Iri = T, BlankId = B>,
+	{
+		self.to_binary_with(&())
+	}
+
+	/// Loads a node map encoded with [`Self::to_binary_with`], interning its
+	/// identifiers into the given `vocabulary`.
+	#[cfg(feature = "binary-node-map")]
+	pub fn from_binary_in(
+		vocabulary: &mut impl VocabularyMut<Iri = T, BlankId = B>,
+		bytes: &[u8],
+	) -> Result<Self, InvalidNodeMap> {
+		let bytes = bytes
+			.strip_prefix(MAGIC)
+			.ok_or(InvalidNodeMap::InvalidHeader)?;
+
+		if bytes.len() < 2 {
+			return Err(InvalidNodeMap::InvalidHeader);
+		}
+
+		let (version, body) = bytes.split_at(2);
+		let version = u16::from_be_bytes([version[0], version[1]]);
+		if version != NODE_MAP_FORMAT_VERSION {
+			return Err(InvalidNodeMap::UnsupportedVersion(version.into()));
+		}
+
+		Self::from_json_in(vocabulary, ciborium::from_reader(body)?)
+	}
+
+	/// Loads a node map encoded with [`Self::to_binary`].
+	#[cfg(feature = "binary-node-map")]
+	pub fn from_binary(bytes: &[u8]) -> Result<Self, InvalidNodeMap>
+	where
+		(): VocabularyMut<Iri = T, BlankId = B>,
+	{
+		Self::from_binary_in(&mut (), bytes)
+	}
+}
+
+impl<T: Clone + Eq + Hash, B: Clone + Eq + Hash> NodeMapGraph<T, B> {
+	/// Returns the array of the nodes of this graph, sorted by identifier.
+	///
+	/// See [`NodeMap::to_json_with`].
+	pub fn to_json_with<N>(&self, vocabulary: &N) -> Value
+	where
+		N: Vocabulary<Iri = T, BlankId = B>,
+	{
+		let mut nodes: Vec<_> = self
+			.into_iter()
+			.map(|(id, node)| (id.with(vocabulary).as_str().to_owned(), node))
+			.collect();
+		nodes.sort_by(|a, b| a.0.cmp(&b.0));
+
+		Value::Array(
+			nodes
+				.into_iter()
+				.map(|(_, node)| node.clone().into_json_with(vocabulary))
+				.collect(),
+		)
+	}
+
+	/// Loads a graph persisted with [`Self::to_json_with`], interning its
+	/// identifiers into the given `vocabulary`.
+	pub fn from_json_in(
+		vocabulary: &mut impl VocabularyMut<Iri = T, BlankId = B>,
+		json: Value,
+	) -> Result<Self, InvalidNodeMap> {
+		let nodes: Vec<IndexedNode<T, B>> =
+			Vec::try_from_json_in(vocabulary, json).map_err(InvalidNodeMap::InvalidNode)?;
+
+		let mut result = Self::new();
+		for node in nodes {
+			if node.id.is_none() {
+				return Err(InvalidNodeMap::MissingId);
+			}
+
+			result.merge_node(node).map_err(|e| {
+				InvalidNodeMap::ConflictingIndexes(e.defined_index, e.conflicting_index)
+			})?
+		}
+
+		Ok(result)
+	}
+}
+
+#[cfg(feature = "serde")]
+impl<T: Clone + Eq + Hash, B: Clone + Eq + Hash> serde::Serialize for NodeMap<T, B>
+where
+	(): Vocabulary<Iri = T, BlankId = B>,
+{
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		S: serde::Serializer,
+	{
+		self.to_json().serialize(serializer)
+	}
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: Clone + Eq + Hash, B: Clone + Eq + Hash> serde::Deserialize<'de> for NodeMap<T, B>
+where
+	(): VocabularyMut<Iri = T, BlankId = B>,
+{
+	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+	where
+		D: serde::Deserializer<'de>,
+	{
+		let json = Value::deserialize(deserializer)?;
+		Self::from_json(json).map_err(serde::de::Error::custom)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::ExpandedDocument;
+	use iref::IriBuf;
+	use rdf_types::{generator, BlankIdBuf};
+
+	fn node_map() -> NodeMap<IriBuf, BlankIdBuf> {
+		let json = json_syntax::json!([{
+			"@id": "https://example.com/g",
+			"@graph": [{
+				"@id": "https://example.com/b",
+				"@index": "bob",
+				"https://example.com/name": [{ "@value": "Bob" }]
+			}]
+		}, {
+			"@id": "https://example.com/a",
+			"https://example.com/knows": [{
+				"https://example.com/name": [{ "@value": "Carol" }]
+			}]
+		}]);
+		let document: ExpandedDocument<IriBuf, BlankIdBuf> =
+			ExpandedDocument::try_from_json_in(&mut (), json).unwrap();
+		document.into_node_map(generator::Blank::new()).unwrap()
+	}
+
+	fn assert_same(a: &NodeMap<IriBuf, BlankIdBuf>, b: &NodeMap<IriBuf, BlankIdBuf>) {
+		assert_eq!(a.iter_named().count(), b.iter_named().count());
+		for (id, graph) in a {
+			let other = b.graph(id).unwrap();
+			assert_eq!(graph.nodes().count(), other.nodes().count());
+			for (node_id, node) in graph {
+				assert_eq!(Some(node), other.get(node_id))
+			}
+		}
+	}
+
+	#[test]
+	fn json_round_trip() {
+		let node_map = node_map();
+		let json = node_map.to_json();
+		assert_eq!(json, node_map.to_json());
+
+		let loaded = NodeMap::from_json(json.clone()).unwrap();
+		assert_same(&node_map, &loaded);
+		assert_eq!(loaded.to_json(), json);
+
+		let mut outdated = json;
+		*outdated
+			.as_object_mut()
+			.unwrap()
+			.get_unique_mut("version")
+			.unwrap()
+			.unwrap() = Value::Number(2u64.into());
+		assert!(matches!(
+			NodeMap::<IriBuf, BlankIdBuf>::from_json(outdated),
+			Err(InvalidNodeMap::UnsupportedVersion(2))
+		))
+	}
+
+	#[cfg(feature = "binary-node-map")]
+	#[test]
+	fn binary_round_trip() {
+		let node_map = node_map();
+		let bytes = node_map.to_binary().unwrap();
+		let loaded = NodeMap::from_binary(&bytes).unwrap();
+		assert_same(&node_map, &loaded);
+	}
+}
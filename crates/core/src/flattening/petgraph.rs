@@ -0,0 +1,142 @@
+use super::NodeMapGraph;
+use crate::{Id, IndexedObject, Object};
+use petgraph::graph::{Graph, NodeIndex};
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// [`petgraph`] representation of a [`NodeMapGraph`].
+///
+/// Graph nodes are weighted by subject identifiers, and edges by the property
+/// linking two subjects.
+pub type PetGraph<T, B> = Graph<Id<T, B>, Id<T, B>>;
+
+impl<T: Clone + Eq + Hash, B: Clone + Eq + Hash> NodeMapGraph<T, B> {
+	/// Converts this graph into a [`petgraph::Graph`].
+	///
+	/// Every node of the node map, and every node referenced by another,
+	/// becomes a graph node weighted by its identifier. Every node reference
+	/// (including in lists) becomes an edge weighted by the property IRI.
+	/// Values are ignored.
+	///
+	/// To convert every graph of a [`NodeMap`](super::NodeMap) at once, first
+	/// [`merge`](super::NodeMap::merge) them.
+	pub fn to_petgraph(&self) -> PetGraph<T, B> {
+		self.to_petgraph_indexed().0
+	}
+
+	/// Converts this graph into a [`petgraph::Graph`], also returning the
+	/// index of each node identifier in the graph.
+	///
+	/// See [`to_petgraph`](Self::to_petgraph).
+	pub fn to_petgraph_indexed(&self) -> (PetGraph<T, B>, HashMap<Id<T, B>, NodeIndex>) {
+		let mut graph = Graph::new();
+		let mut indexes = HashMap::new();
+
+		for node in self.nodes() {
+			if let Some(id) = &node.id {
+				node_index(&mut graph, &mut indexes, id);
+			}
+		}
+
+		for node in self.nodes() {
+			if let Some(id) = &node.id {
+				let a = indexes[id];
+				for (property, objects) in node.properties() {
+					add_edges(&mut graph, &mut indexes, a, property, objects)
+				}
+			}
+		}
+
+		(graph, indexes)
+	}
+}
+
+fn node_index<T: Clone + Eq + Hash, B: Clone + Eq + Hash>(
+	graph: &mut PetGraph<T, B>,
+	indexes: &mut HashMap<Id<T, B>, NodeIndex>,
+	id: &Id<T, B>,
+) -> NodeIndex {
+	*indexes
+		.entry(id.clone())
+		.or_insert_with(|| graph.add_node(id.clone()))
+}
+
+fn add_edges<'a, T: 'a + Clone + Eq + Hash, B: 'a + Clone + Eq + Hash>(
+	graph: &mut PetGraph<T, B>,
+	indexes: &mut HashMap<Id<T, B>, NodeIndex>,
+	a: NodeIndex,
+	property: &Id<T, B>,
+	objects: impl IntoIterator<Item = &'a IndexedObject<T, B>>,
+) {
+	for object in objects {
+		match object.inner() {
+			Object::Node(node) => {
+				if let Some(id) = &node.id {
+					let b = node_index(graph, indexes, id);
+					graph.add_edge(a, b, property.clone());
+				}
+			}
+			Object::List(list) => add_edges(graph, indexes, a, property, list),
+			Object::Value(_) => (),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{ExpandedDocument, Id, TryFromJson};
+	use iref::IriBuf;
+	use rdf_types::{generator, BlankIdBuf};
+
+	fn id(iri: &str) -> Id<IriBuf, BlankIdBuf> {
+		Id::iri(IriBuf::new(format!("https://example.com/{iri}")).unwrap())
+	}
+
+	#[test]
+	fn to_petgraph() {
+		let json = json_syntax::json!([
+			{
+				"@id": "https://example.com/a",
+				"https://example.com/knows": [
+					{ "@id": "https://example.com/b" },
+					{ "@value": "not an edge" }
+				],
+				"https://example.com/list": [{
+					"@list": [{ "@id": "https://example.com/c" }, { "@value": 1 }]
+				}]
+			},
+			{
+				"@id": "https://example.com/b",
+				"https://example.com/knows": [{ "@id": "https://example.com/a" }]
+			}
+		]);
+
+		let document: ExpandedDocument<IriBuf, BlankIdBuf> =
+			ExpandedDocument::try_from_json_in(&mut (), json).unwrap();
+		let node_map = document.into_node_map(generator::Blank::new()).unwrap();
+		let (graph, indexes) = node_map.graph(None).unwrap().to_petgraph_indexed();
+
+		// `c` is only referenced, and the values are ignored.
+		assert_eq!(graph.node_count(), 3);
+		assert_eq!(graph.edge_count(), 3);
+
+		let edge = |a: &str, b: &str| {
+			graph
+				.find_edge(indexes[&id(a)], indexes[&id(b)])
+				.map(|e| graph[e].as_str().to_owned())
+		};
+
+		assert_eq!(edge("a", "b").as_deref(), Some("https://example.com/knows"));
+		assert_eq!(edge("b", "a").as_deref(), Some("https://example.com/knows"));
+		assert_eq!(edge("a", "c").as_deref(), Some("https://example.com/list"));
+		assert_eq!(edge("c", "a"), None);
+
+		for (id, index) in &indexes {
+			assert_eq!(&graph[*index], id)
+		}
+
+		let unindexed = node_map.graph(None).unwrap().to_petgraph();
+		assert_eq!(unindexed.node_count(), graph.node_count());
+		assert_eq!(unindexed.edge_count(), graph.edge_count())
+	}
+}
@@ -0,0 +1,207 @@
+use super::{NodeMap, NodeMapGraph, TraversalOrder};
+use crate::{Id, IndexedNode};
+use std::collections::HashSet;
+use std::hash::Hash;
+
+impl<T: Clone + Eq + Hash, B: Clone + Eq + Hash> NodeMapGraph<T, B> {
+	/// Removes every node that is not reachable from the given `roots` by
+	/// following node references.
+	///
+	/// Returns the removed nodes.
+	pub fn prune_unreachable<'r>(
+		&mut self,
+		roots: impl IntoIterator<Item = &'r Id<T, B>>,
+	) -> Vec<IndexedNode<T, B>>
+	where
+		T: 'r,
+		B: 'r,
+	{
+		let roots: Vec<&Id<T, B>> = roots.into_iter().collect();
+		let reachable: HashSet<Id<T, B>> = self
+			.visit(roots.iter().copied(), TraversalOrder::BreadthFirst)
+			.filter_map(|node| node.id.clone())
+			.collect();
+
+		let unreachable: Vec<Id<T, B>> = self
+			.nodes()
+			.filter_map(|node| node.id.as_ref())
+			.filter(|id| !reachable.contains(*id))
+			.cloned()
+			.collect();
+
+		unreachable
+			.into_iter()
+			.filter_map(|id| self.remove(&id))
+			.collect()
+	}
+
+	/// Removes every node that is not reachable from a node with a non-blank
+	/// identifier.
+	///
+	/// This typically removes orphan blank nodes left after editing a
+	/// flattened document. Returns the removed nodes.
+	pub fn prune_orphans(&mut self) -> Vec<IndexedNode<T, B>> {
+		let roots: Vec<Id<T, B>> = self
+			.nodes()
+			.filter_map(|node| node.id.as_ref())
+			.filter(|id| !id.is_blank())
+			.cloned()
+			.collect();
+
+		self.prune_unreachable(&roots)
+	}
+}
+
+/// Nodes removed from a [`NodeMap`], with the graph they belonged to.
+pub type PrunedNodes<T, B> = Vec<(Option<Id<T, B>>, IndexedNode<T, B>)>;
+
+impl<T: Clone + Eq + Hash, B: Clone + Eq + Hash> NodeMap<T, B> {
+	/// Removes, in every graph, each node that is not reachable from the given
+	/// `roots` by following node references.
+	///
+	/// Returns the removed nodes, with the graph they belonged to.
+	pub fn prune_unreachable(&mut self, roots: &[Id<T, B>]) -> PrunedNodes<T, B> {
+		self.prune_graphs(|graph| graph.prune_unreachable(roots))
+	}
+
+	/// Removes, in every graph, each node that is not reachable from a node
+	/// with a non-blank identifier.
+	///
+	/// Returns the removed nodes, with the graph they belonged to.
+	pub fn prune_orphans(&mut self) -> PrunedNodes<T, B> {
+		self.prune_graphs(NodeMapGraph::prune_orphans)
+	}
+
+	fn prune_graphs(
+		&mut self,
+		mut f: impl FnMut(&mut NodeMapGraph<T, B>) -> Vec<IndexedNode<T, B>>,
+	) -> PrunedNodes<T, B> {
+		let mut removed = Vec::new();
+		let graph_ids: Vec<Option<Id<T, B>>> = self.iter().map(|(id, _)| id.cloned()).collect();
+		for id in graph_ids {
+			let graph = self.graph_mut(id.as_ref()).unwrap();
+			removed.extend(f(graph).into_iter().map(|node| (id.clone(), node)))
+		}
+
+		removed
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{ExpandedDocument, TryFromJson};
+	use iref::IriBuf;
+	use rdf_types::{generator, BlankIdBuf};
+
+	fn node_map() -> NodeMap<IriBuf, BlankIdBuf> {
+		let json = json_syntax::json!([
+			{
+				"@id": "https://example.com/a",
+				"https://example.com/p": [{ "@id": "_:x" }]
+			},
+			{
+				"@id": "_:x",
+				"https://example.com/p": [{ "@id": "_:y" }]
+			},
+			{
+				"@id": "_:y",
+				"https://example.com/q": [{ "@value": "y" }]
+			},
+			{
+				"@id": "_:orphan",
+				"https://example.com/p": [{ "@id": "_:z" }]
+			},
+			{
+				"@id": "_:z",
+				"https://example.com/q": [{ "@value": "z" }]
+			},
+			{
+				"@id": "https://example.com/b",
+				"https://example.com/q": [{ "@value": "b" }]
+			},
+			{
+				"@id": "https://example.com/g",
+				"@graph": [{
+					"@id": "_:w",
+					"https://example.com/q": [{ "@value": "w" }]
+				}]
+			}
+		]);
+
+		let document: ExpandedDocument<IriBuf, BlankIdBuf> =
+			ExpandedDocument::try_from_json_in(&mut (), json).unwrap();
+		document.into_node_map(generator::Blank::new()).unwrap()
+	}
+
+	fn id(iri: &str) -> Id<IriBuf, BlankIdBuf> {
+		Id::iri(IriBuf::new(iri.to_owned()).unwrap())
+	}
+
+	/// Returns the `q` values of the given nodes.
+	fn values<'a>(
+		nodes: impl IntoIterator<Item = &'a IndexedNode<IriBuf, BlankIdBuf>>,
+	) -> Vec<String> {
+		let q = id("https://example.com/q");
+		let mut values: Vec<_> = nodes
+			.into_iter()
+			.flat_map(|node| node.get(&q))
+			.filter_map(|object| object.as_str())
+			.map(ToOwned::to_owned)
+			.collect();
+		values.sort();
+		values
+	}
+
+	#[test]
+	fn prune_orphans() {
+		let mut node_map = node_map();
+		let graph = node_map.graph_mut(None).unwrap();
+
+		let removed = graph.prune_orphans();
+		assert_eq!(removed.len(), 2);
+		assert!(removed
+			.iter()
+			.all(|node| node.id.as_ref().unwrap().is_blank()));
+		assert_eq!(values(&removed), ["z"]);
+
+		assert_eq!(values(graph.nodes()), ["b", "y"]);
+		assert!(graph.prune_orphans().is_empty())
+	}
+
+	#[test]
+	fn prune_unreachable() {
+		let mut node_map = node_map();
+		let graph = node_map.graph_mut(None).unwrap();
+
+		let removed = graph.prune_unreachable(&[id("https://example.com/a")]);
+		assert_eq!(removed.len(), 4);
+		assert!(graph.get(&id("https://example.com/g")).is_none());
+		assert_eq!(values(&removed), ["b", "z"]);
+		assert_eq!(values(graph.nodes()), ["y"]);
+		assert!(graph.get(&id("https://example.com/a")).is_some());
+
+		// Unknown roots are ignored.
+		let removed = graph.prune_unreachable(&[id("https://example.com/unknown")]);
+		assert_eq!(removed.len(), 3)
+	}
+
+	#[test]
+	fn prune_every_graph() {
+		let mut node_map = node_map();
+		let removed = node_map.prune_orphans();
+		assert_eq!(removed.len(), 3);
+
+		// The blank node of the named graph is not reachable from an IRI of
+		// this graph.
+		let g = id("https://example.com/g");
+		let (graph, node) = removed.iter().find(|(graph, _)| graph.is_some()).unwrap();
+		assert_eq!(graph.as_ref(), Some(&g));
+		assert_eq!(values([node]), ["w"]);
+		assert!(node_map.graph(Some(&g)).unwrap().nodes().next().is_none());
+
+		let mut node_map = self::node_map();
+		let removed = node_map.prune_unreachable(&[id("https://example.com/b")]);
+		assert_eq!(removed.len(), 7)
+	}
+}
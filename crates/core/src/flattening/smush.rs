@@ -0,0 +1,209 @@
+use super::{ConflictingIndexes, GraphMerge, IndexMerge, MergeOptions, NodeMap, TypesMerge};
+use crate::{object::node::properties::PropertyObjects, rdf::OWL_SAME_AS, Id};
+use rdf_types::vocabulary::IriVocabulary;
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+/// Alias map produced by [`NodeMap::smush`], mapping each merged identifier
+/// to the identifier of its canonical node.
+pub type Aliases<T, B> = HashMap<Id<T, B>, Id<T, B>>;
+
+impl<T: Clone + Ord + Hash, B: Clone + Ord + Hash> NodeMap<T, B> {
+	/// Merges the nodes connected by `owl:sameAs`.
+	///
+	/// See [`Self::smush`].
+	pub fn smush_same_as_with(
+		&mut self,
+		vocabulary: &impl IriVocabulary<Iri = T>,
+	) -> Result<Aliases<T, B>, ConflictingIndexes<T, B>> {
+		match vocabulary.get(OWL_SAME_AS) {
+			Some(same_as) => self.smush(&Id::iri(same_as)),
+			None => Ok(Aliases::new()),
+		}
+	}
+
+	/// Merges the nodes connected by `owl:sameAs`.
+	///
+	/// See [`Self::smush`].
+	pub fn smush_same_as(&mut self) -> Result<Aliases<T, B>, ConflictingIndexes<T, B>>
+	where
+		(): IriVocabulary<Iri = T>,
+	{
+		self.smush_same_as_with(&())
+	}
+
+	/// Merges the nodes connected by the given equivalence `predicate`, in
+	/// any graph and in any direction, into canonical nodes.
+	///
+	/// The canonical identifier of a set of equivalent nodes is the smallest
+	/// IRI, or the smallest blank node identifier if there is no IRI. Every
+	/// occurrence of the other identifiers, including in references, types,
+	/// properties and graph names, is replaced by the canonical identifier,
+	/// and the merged nodes are combined with the union of their types and
+	/// properties (the first index found is kept). The `predicate`
+	/// statements made redundant by the merge are removed.
+	///
+	/// Returns the alias map.
+	pub fn smush(
+		&mut self,
+		predicate: &Id<T, B>,
+	) -> Result<Aliases<T, B>, ConflictingIndexes<T, B>> {
+		let mut edges: HashMap<Id<T, B>, Vec<Id<T, B>>> = HashMap::new();
+		for (_, graph) in &*self {
+			for node in graph.nodes() {
+				if let Some(id) = &node.id {
+					for other in node.get(predicate).filter_map(|object| object.id()) {
+						if other != id {
+							edges.entry(id.clone()).or_default().push(other.clone());
+							edges.entry(other.clone()).or_default().push(id.clone());
+						}
+					}
+				}
+			}
+		}
+
+		let mut aliases = Aliases::new();
+		let mut visited = HashSet::new();
+		for start in edges.keys() {
+			if !visited.insert(start) {
+				continue;
+			}
+
+			let mut class = vec![start];
+			let mut stack = vec![start];
+			while let Some(id) = stack.pop() {
+				for other in &edges[id] {
+					if visited.insert(other) {
+						class.push(other);
+						stack.push(other);
+					}
+				}
+			}
+
+			let canonical = class
+				.iter()
+				.min_by_key(|id| (id.is_blank(), **id))
+				.copied()
+				.unwrap();
+
+			for id in class {
+				if id != canonical {
+					aliases.insert(id.clone(), canonical.clone());
+				}
+			}
+		}
+
+		if !aliases.is_empty() {
+			self.rewrite_aliases(predicate, &aliases)?
+		}
+
+		Ok(aliases)
+	}
+
+	fn rewrite_aliases(
+		&mut self,
+		predicate: &Id<T, B>,
+		aliases: &Aliases<T, B>,
+	) -> Result<(), ConflictingIndexes<T, B>> {
+		let mut options = MergeOptions {
+			types: TypesMerge::Union,
+			index: IndexMerge::Keep,
+			graph: GraphMerge::Union,
+		};
+
+		let mut rewrite = |id: Id<T, B>| match aliases.get(&id) {
+			Some(canonical) => canonical.clone(),
+			None => id,
+		};
+
+		let graph_ids: Vec<Option<Id<T, B>>> = self.iter().map(|(id, _)| id.cloned()).collect();
+		for graph_id in &graph_ids {
+			let graph = self.graph_mut(graph_id.as_ref()).unwrap();
+			let ids: Vec<Id<T, B>> = graph.nodes().filter_map(|node| node.id.clone()).collect();
+			let nodes: Vec<_> = ids.iter().filter_map(|id| graph.remove(id)).collect();
+
+			for node in nodes {
+				let mut node = node.map_inner(|node| node.map_ids(|t| t, &mut rewrite));
+
+				if let Some(objects) = node.properties_mut().remove(predicate) {
+					let objects: PropertyObjects<T, B> = objects
+						.into_iter()
+						.filter(|object| object.id() != node.id.as_ref())
+						.collect();
+					if !objects.is_empty() {
+						node.properties_mut().set(predicate.clone(), objects)
+					}
+				}
+
+				graph.merge_node_with(node, &mut options)?
+			}
+		}
+
+		for graph_id in graph_ids.into_iter().flatten() {
+			if let Some(canonical) = aliases.get(&graph_id) {
+				let graph = self.remove_graph(&graph_id).unwrap();
+				self.declare_graph(canonical.clone());
+				self.graph_mut(Some(canonical))
+					.unwrap()
+					.merge_with_policy(graph, &mut options)?
+			}
+		}
+
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{flattening::NodeMap, ExpandedDocument, Id, TryFromJson};
+	use iref::IriBuf;
+	use rdf_types::{generator, BlankIdBuf};
+
+	#[test]
+	fn smush_same_as() {
+		let json = json_syntax::json!([{
+			"@id": "https://example.com/alice",
+			"@type": ["https://schema.org/Person"],
+			"http://www.w3.org/2002/07/owl#sameAs": [{ "@id": "https://other.org/a" }]
+		}, {
+			"@id": "https://other.org/a",
+			"@type": ["https://schema.org/Person"],
+			"https://schema.org/name": [{ "@value": "Alice" }],
+			"http://www.w3.org/2002/07/owl#sameAs": [{ "@id": "_:alice" }]
+		}, {
+			"@id": "https://example.com/bob",
+			"https://schema.org/knows": [{ "@id": "_:alice" }]
+		}]);
+		let mut node_map: NodeMap<IriBuf, BlankIdBuf> =
+			ExpandedDocument::try_from_json_in(&mut (), json)
+				.unwrap()
+				.into_node_map(generator::Blank::new())
+				.unwrap();
+
+		let iri = |s: &str| Id::iri(IriBuf::new(s.to_owned()).unwrap());
+		let aliases = node_map.smush_same_as().unwrap();
+		let alice = iri("https://example.com/alice");
+		assert_eq!(aliases.len(), 2);
+		assert_eq!(aliases[&iri("https://other.org/a")], alice);
+
+		let graph = node_map.graph(None).unwrap();
+		assert!(!graph.contains(&iri("https://other.org/a")));
+		let node = graph.get(&alice).unwrap();
+		assert_eq!(node.types().len(), 1);
+		assert_eq!(node.get(&iri("https://schema.org/name")).count(), 1);
+		assert_eq!(
+			node.get(&iri("http://www.w3.org/2002/07/owl#sameAs"))
+				.count(),
+			0
+		);
+
+		let bob = graph.get(&iri("https://example.com/bob")).unwrap();
+		assert_eq!(
+			bob.get(&iri("https://schema.org/knows"))
+				.next()
+				.unwrap()
+				.id(),
+			Some(&alice)
+		)
+	}
+}
@@ -0,0 +1,199 @@
+use super::NodeMapGraph;
+use crate::{Id, IndexedNode, IndexedObject, Object};
+use std::collections::{HashSet, VecDeque};
+use std::hash::Hash;
+
+/// Graph traversal order.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TraversalOrder {
+	/// Breadth-first traversal.
+	#[default]
+	BreadthFirst,
+
+	/// Depth-first traversal.
+	DepthFirst,
+}
+
+impl<T: Eq + Hash, B: Eq + Hash> NodeMapGraph<T, B> {
+	/// Follows the given property `path` starting from the `start` node.
+	///
+	/// At each step, every node referenced (by `@id`) through the current
+	/// property by the current set of nodes is selected, including nodes
+	/// referenced inside lists. References to nodes that are not in the graph
+	/// are ignored. Each node is selected at most once per step, so cycles are
+	/// harmless.
+	///
+	/// Returns the nodes selected by the last step, in discovery order.
+	pub fn walk<'a, 'p, Q>(
+		&'a self,
+		start: &Id<T, B>,
+		path: impl IntoIterator<Item = &'p Q>,
+	) -> Vec<&'a IndexedNode<T, B>>
+	where
+		Q: 'p + ?Sized + Hash + indexmap::Equivalent<Id<T, B>>,
+	{
+		let mut current: Vec<&'a IndexedNode<T, B>> = self.get(start).into_iter().collect();
+
+		for property in path {
+			let mut visited = HashSet::new();
+			let mut next = Vec::new();
+			let mut references = Vec::new();
+
+			for node in current {
+				references.clear();
+				node_references(node.get(property), &mut references);
+				for id in references.drain(..) {
+					if visited.insert(id) {
+						if let Some(node) = self.get(id) {
+							next.push(node)
+						}
+					}
+				}
+			}
+
+			current = next
+		}
+
+		current
+	}
+
+	/// Visits every node reachable from the given `roots` by following node
+	/// references (by `@id`), in the given `order`.
+	///
+	/// Roots are visited first. Each node is visited at most once, so cycles
+	/// are harmless. References to nodes that are not in the graph are
+	/// ignored.
+	pub fn visit<'a>(
+		&'a self,
+		roots: impl IntoIterator<Item = &'a Id<T, B>>,
+		order: TraversalOrder,
+	) -> Visit<'a, T, B> {
+		let mut pending: VecDeque<_> = roots.into_iter().collect();
+		if order == TraversalOrder::DepthFirst {
+			pending.make_contiguous().reverse()
+		}
+
+		Visit {
+			graph: self,
+			order,
+			pending,
+			visited: HashSet::new(),
+			buffer: Vec::new(),
+		}
+	}
+}
+
+/// Iterator over the nodes reachable from some roots in a [`NodeMapGraph`].
+///
+/// See [`NodeMapGraph::visit`].
+pub struct Visit<'a, T, B> {
+	graph: &'a NodeMapGraph<T, B>,
+	order: TraversalOrder,
+	pending: VecDeque<&'a Id<T, B>>,
+	visited: HashSet<&'a Id<T, B>>,
+	buffer: Vec<&'a Id<T, B>>,
+}
+
+impl<'a, T: Eq + Hash, B: Eq + Hash> Iterator for Visit<'a, T, B> {
+	type Item = &'a IndexedNode<T, B>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		loop {
+			let id = match self.order {
+				TraversalOrder::BreadthFirst => self.pending.pop_front()?,
+				TraversalOrder::DepthFirst => self.pending.pop_back()?,
+			};
+
+			if !self.visited.insert(id) {
+				continue;
+			}
+
+			if let Some(node) = self.graph.get(id) {
+				for (_, objects) in node.properties() {
+					node_references(objects, &mut self.buffer);
+				}
+
+				match self.order {
+					TraversalOrder::BreadthFirst => self.pending.extend(self.buffer.drain(..)),
+					TraversalOrder::DepthFirst => self.pending.extend(self.buffer.drain(..).rev()),
+				}
+
+				break Some(node);
+			}
+		}
+	}
+}
+
+/// Collects the identifiers of the nodes referenced by the given objects,
+/// including inside lists.
+fn node_references<'a, T, B>(
+	objects: impl IntoIterator<Item = &'a IndexedObject<T, B>>,
+	result: &mut Vec<&'a Id<T, B>>,
+) where
+	T: 'a,
+	B: 'a,
+{
+	for object in objects {
+		match object.inner() {
+			Object::Node(node) => {
+				if let Some(id) = &node.id {
+					result.push(id)
+				}
+			}
+			Object::List(list) => node_references(list, result),
+			Object::Value(_) => (),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Node;
+	use iref::IriBuf;
+
+	fn iri(s: &str) -> Id {
+		Id::iri(IriBuf::new(format!("https://example.com/{s}")).unwrap())
+	}
+
+	fn link(graph: &mut NodeMapGraph<IriBuf, rdf_types::BlankIdBuf>, from: &str, to: &str) {
+		graph.declare_node(iri(to), None).ok().unwrap();
+		graph.declare_node(iri(from), None).ok().unwrap().insert(
+			iri("knows"),
+			IndexedObject::none(Object::node(Node::with_id(iri(to)))),
+		)
+	}
+
+	#[test]
+	fn visit_cycle() {
+		let mut graph = NodeMapGraph::new();
+		link(&mut graph, "a", "b");
+		link(&mut graph, "b", "c");
+		link(&mut graph, "c", "a");
+		link(&mut graph, "a", "d");
+
+		let visited: Vec<_> = graph
+			.visit([&iri("a")], TraversalOrder::BreadthFirst)
+			.map(|n| n.id.clone().unwrap())
+			.collect();
+		assert_eq!(visited, [iri("a"), iri("b"), iri("d"), iri("c")]);
+
+		let visited: Vec<_> = graph
+			.visit([&iri("a")], TraversalOrder::DepthFirst)
+			.map(|n| n.id.clone().unwrap())
+			.collect();
+		assert_eq!(visited, [iri("a"), iri("b"), iri("c"), iri("d")]);
+	}
+
+	#[test]
+	fn walk_path() {
+		let mut graph = NodeMapGraph::new();
+		link(&mut graph, "a", "b");
+		link(&mut graph, "b", "a");
+
+		let knows = iri("knows");
+		let nodes = graph.walk(&iri("a"), [&knows, &knows, &knows]);
+		assert_eq!(nodes.len(), 1);
+		assert_eq!(nodes[0].id, Some(iri("b")))
+	}
+}
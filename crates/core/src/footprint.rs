@@ -0,0 +1,476 @@
+//! Memory footprint estimation.
+//!
+//! The [`MemoryFootprint`] trait estimates the memory used by documents,
+//! node maps and processed contexts, so that a cache can enforce a memory
+//! budget and evict entries by size.
+//!
+//! Sizes are estimates: allocator overhead is ignored and the size of hash
+//! tables is derived from their number of entries. The inverse context
+//! cached by a processed [`Context`] is not counted, and scoped contexts are
+//! estimated from the length of their compact JSON representation.
+use crate::{
+	context::{BindingRef, Definitions, NormalTermDefinition},
+	flattening::{NodeMap, NodeMapGraph, NodeMapIndexes},
+	object::{
+		node::{Annotation, Multiset, Properties, ReverseProperties},
+		value::Literal,
+		List,
+	},
+	Context, ExpandedDocument, Id, Indexed, LangString, LenientLangTagBuf, Node, Object, Term,
+	Type, Value,
+};
+use iref::IriBuf;
+use json_ld_syntax::context::{
+	definition::Key,
+	term_definition::{Index, Nest},
+};
+use json_syntax::Print;
+use rdf_types::{
+	vocabulary::{BlankIdIndex, IriIndex},
+	BlankIdBuf,
+};
+use std::{
+	collections::{HashMap, HashSet},
+	hash::Hash,
+	mem::size_of,
+};
+
+/// Type whose memory usage can be estimated.
+pub trait MemoryFootprint {
+	/// Returns the estimated number of bytes allocated on the heap by this
+	/// value.
+	fn heap_size(&self) -> usize;
+
+	/// Returns the estimated total number of bytes used by this value,
+	/// including its own inline size.
+	fn deep_size(&self) -> usize {
+		std::mem::size_of_val(self) + self.heap_size()
+	}
+}
+
+macro_rules! inline_only {
+	($($ty:ty),*) => {
+		$(
+			impl MemoryFootprint for $ty {
+				fn heap_size(&self) -> usize {
+					0
+				}
+			}
+		)*
+	};
+}
+
+inline_only!(bool, IriIndex, BlankIdIndex);
+
+/// Estimated size of a hash table with `len` entries of type `E`.
+fn table<E>(len: usize) -> usize {
+	len * (size_of::<E>() + 1)
+}
+
+/// Estimated size of an exactly allocated buffer holding the given items.
+fn items<T: MemoryFootprint>(items: &[T]) -> usize {
+	std::mem::size_of_val(items) + items.iter().map(T::heap_size).sum::<usize>()
+}
+
+impl MemoryFootprint for String {
+	fn heap_size(&self) -> usize {
+		self.capacity()
+	}
+}
+
+impl MemoryFootprint for IriBuf {
+	fn heap_size(&self) -> usize {
+		self.as_str().len()
+	}
+}
+
+impl MemoryFootprint for BlankIdBuf {
+	fn heap_size(&self) -> usize {
+		self.as_str().len()
+	}
+}
+
+impl MemoryFootprint for LenientLangTagBuf {
+	fn heap_size(&self) -> usize {
+		self.as_str().len()
+	}
+}
+
+impl MemoryFootprint for json_syntax::String {
+	fn heap_size(&self) -> usize {
+		if self.spilled() {
+			self.capacity()
+		} else {
+			0
+		}
+	}
+}
+
+impl MemoryFootprint for json_syntax::NumberBuf {
+	fn heap_size(&self) -> usize {
+		if self.as_str().len() > json_syntax::NUMBER_CAPACITY {
+			self.as_str().len()
+		} else {
+			0
+		}
+	}
+}
+
+impl MemoryFootprint for json_syntax::Value {
+	fn heap_size(&self) -> usize {
+		match self {
+			Self::Null | Self::Boolean(_) => 0,
+			Self::Number(n) => n.heap_size(),
+			Self::String(s) => s.heap_size(),
+			Self::Array(a) => a.heap_size(),
+			Self::Object(o) => {
+				o.capacity() * size_of::<json_syntax::object::Entry>()
+					+ table::<usize>(o.len())
+					+ o.iter()
+						.map(|e| e.key.heap_size() + e.value.heap_size())
+						.sum::<usize>()
+			}
+		}
+	}
+}
+
+impl MemoryFootprint for json_ld_syntax::context::Context {
+	fn heap_size(&self) -> usize {
+		self.compact_print().to_string().len()
+	}
+}
+
+impl<T: MemoryFootprint> MemoryFootprint for Option<T> {
+	fn heap_size(&self) -> usize {
+		self.as_ref().map_or(0, T::heap_size)
+	}
+}
+
+impl<T: MemoryFootprint> MemoryFootprint for Box<T> {
+	fn heap_size(&self) -> usize {
+		size_of::<T>() + T::heap_size(self)
+	}
+}
+
+/// Shared values are counted once per reference.
+impl<T: MemoryFootprint> MemoryFootprint for std::sync::Arc<T> {
+	fn heap_size(&self) -> usize {
+		2 * size_of::<usize>() + size_of::<T>() + T::heap_size(self)
+	}
+}
+
+impl<T: MemoryFootprint> MemoryFootprint for Vec<T> {
+	fn heap_size(&self) -> usize {
+		self.capacity() * size_of::<T>() + self.iter().map(T::heap_size).sum::<usize>()
+	}
+}
+
+impl<T: MemoryFootprint, S> MemoryFootprint for indexmap::IndexSet<T, S> {
+	fn heap_size(&self) -> usize {
+		self.capacity() * size_of::<T>()
+			+ table::<usize>(self.len())
+			+ self.iter().map(T::heap_size).sum::<usize>()
+	}
+}
+
+impl<T: MemoryFootprint, S> MemoryFootprint for Multiset<T, S> {
+	fn heap_size(&self) -> usize {
+		items(self.as_slice())
+	}
+}
+
+impl<T: MemoryFootprint, B: MemoryFootprint> MemoryFootprint for rdf_types::Id<T, B> {
+	fn heap_size(&self) -> usize {
+		match self {
+			Self::Iri(iri) => iri.heap_size(),
+			Self::Blank(b) => b.heap_size(),
+		}
+	}
+}
+
+impl<T: MemoryFootprint, B: MemoryFootprint> MemoryFootprint for Id<T, B> {
+	fn heap_size(&self) -> usize {
+		match self {
+			Self::Valid(id) => id.heap_size(),
+			Self::Invalid(s) => s.heap_size(),
+		}
+	}
+}
+
+impl<T: MemoryFootprint, B: MemoryFootprint> MemoryFootprint for Term<T, B> {
+	fn heap_size(&self) -> usize {
+		match self {
+			Self::Id(id) => id.heap_size(),
+			Self::Null | Self::Keyword(_) => 0,
+		}
+	}
+}
+
+impl<T: MemoryFootprint> MemoryFootprint for Type<T> {
+	fn heap_size(&self) -> usize {
+		match self {
+			Self::Iri(iri) => iri.heap_size(),
+			_ => 0,
+		}
+	}
+}
+
+impl<T: MemoryFootprint> MemoryFootprint for Indexed<T> {
+	fn heap_size(&self) -> usize {
+		self.index().map_or(0, str::len) + self.inner().heap_size()
+	}
+}
+
+impl MemoryFootprint for Literal {
+	fn heap_size(&self) -> usize {
+		match self {
+			Self::Null | Self::Boolean(_) => 0,
+			Self::Number(n) => n.heap_size(),
+			Self::String(s) => s.heap_size(),
+		}
+	}
+}
+
+impl MemoryFootprint for LangString {
+	fn heap_size(&self) -> usize {
+		let (data, language, _) = self.parts();
+		let data = if data.len() > json_syntax::SMALL_STRING_CAPACITY {
+			data.len()
+		} else {
+			0
+		};
+
+		data + language.map_or(0, LenientLangTagBuf::heap_size)
+	}
+}
+
+impl<T: MemoryFootprint> MemoryFootprint for Value<T> {
+	fn heap_size(&self) -> usize {
+		match self {
+			Self::Literal(lit, ty) => lit.heap_size() + ty.heap_size(),
+			Self::LangString(s) => s.heap_size(),
+			Self::Json(json) => json.heap_size(),
+		}
+	}
+}
+
+impl<T: MemoryFootprint, B: MemoryFootprint> MemoryFootprint for Object<T, B> {
+	fn heap_size(&self) -> usize {
+		match self {
+			Self::Value(value) => value.heap_size(),
+			Self::Node(node) => node.heap_size(),
+			Self::List(list) => list.heap_size(),
+		}
+	}
+}
+
+impl<T: MemoryFootprint, B: MemoryFootprint> MemoryFootprint for List<T, B> {
+	fn heap_size(&self) -> usize {
+		items(self.as_slice())
+	}
+}
+
+impl<T: MemoryFootprint, B: MemoryFootprint> MemoryFootprint for Properties<T, B> {
+	fn heap_size(&self) -> usize {
+		table::<(Id<T, B>, Multiset<Indexed<Object<T, B>>>)>(self.len())
+			+ self
+				.iter()
+				.map(|(property, objects)| property.heap_size() + items(objects))
+				.sum::<usize>()
+	}
+}
+
+impl<T: MemoryFootprint, B: MemoryFootprint> MemoryFootprint for ReverseProperties<T, B> {
+	fn heap_size(&self) -> usize {
+		table::<(Id<T, B>, Multiset<Indexed<Node<T, B>>>)>(self.len())
+			+ self
+				.iter()
+				.map(|(property, nodes)| property.heap_size() + items(nodes))
+				.sum::<usize>()
+	}
+}
+
+impl<T: MemoryFootprint, B: MemoryFootprint> MemoryFootprint for Annotation<T, B> {
+	fn heap_size(&self) -> usize {
+		self.property.heap_size() + self.object.heap_size() + self.nodes.heap_size()
+	}
+}
+
+impl<T: MemoryFootprint, B: MemoryFootprint> MemoryFootprint for Node<T, B> {
+	fn heap_size(&self) -> usize {
+		self.id.heap_size()
+			+ self.types.heap_size()
+			+ self.graph.heap_size()
+			+ self.included.heap_size()
+			+ self.properties.heap_size()
+			+ self.reverse_properties.heap_size()
+			+ self.annotations().map_or(0, MemoryFootprint::heap_size)
+	}
+}
+
+impl<T: MemoryFootprint, B: MemoryFootprint> MemoryFootprint for ExpandedDocument<T, B> {
+	fn heap_size(&self) -> usize {
+		self.len() * size_of::<Indexed<Object<T, B>>>()
+			+ table::<usize>(self.len())
+			+ self.iter().map(Indexed::heap_size).sum::<usize>()
+	}
+}
+
+impl<T: Eq + Hash + MemoryFootprint, B: Eq + Hash + MemoryFootprint> MemoryFootprint
+	for NodeMapGraph<T, B>
+{
+	fn heap_size(&self) -> usize {
+		// Each node is indexed by a copy of its identifier.
+		self.nodes()
+			.map(|node| {
+				table::<(Id<T, B>, Indexed<Node<T, B>>)>(1) + node.id.heap_size() + node.heap_size()
+			})
+			.sum::<usize>()
+			+ self.indexes().map_or(0, MemoryFootprint::heap_size)
+	}
+}
+
+impl<T: MemoryFootprint, B: MemoryFootprint> MemoryFootprint for NodeMapIndexes<T, B> {
+	fn heap_size(&self) -> usize {
+		fn ids<T: MemoryFootprint, B: MemoryFootprint>(ids: &HashSet<Id<T, B>>) -> usize {
+			table::<Id<T, B>>(ids.len()) + ids.iter().map(Id::heap_size).sum::<usize>()
+		}
+
+		fn index<K: MemoryFootprint, T: MemoryFootprint, B: MemoryFootprint>(
+			index: &HashMap<K, HashSet<Id<T, B>>>,
+		) -> usize {
+			index
+				.iter()
+				.map(|(key, set)| table::<(K, HashSet<Id<T, B>>)>(1) + key.heap_size() + ids(set))
+				.sum()
+		}
+
+		ids(&self.indexed)
+			+ ids(&self.stale)
+			+ index(&self.types)
+			+ index(&self.properties)
+			+ index(&self.values)
+	}
+}
+
+impl<T: Eq + Hash + MemoryFootprint, B: Eq + Hash + MemoryFootprint> MemoryFootprint
+	for NodeMap<T, B>
+{
+	fn heap_size(&self) -> usize {
+		self.iter()
+			.map(|(id, graph)| match id {
+				Some(id) => {
+					table::<(Id<T, B>, NodeMapGraph<T, B>)>(1) + id.heap_size() + graph.heap_size()
+				}
+				None => graph.heap_size(),
+			})
+			.sum()
+	}
+}
+
+#[cfg(feature = "rdf")]
+impl<T: Eq + Hash + MemoryFootprint, B: Eq + Hash + MemoryFootprint> MemoryFootprint
+	for crate::Dataset<T, B>
+{
+	fn heap_size(&self) -> usize {
+		self.node_map().heap_size()
+	}
+}
+
+impl MemoryFootprint for Key {
+	fn heap_size(&self) -> usize {
+		self.as_str().len()
+	}
+}
+
+impl MemoryFootprint for Index {
+	fn heap_size(&self) -> usize {
+		self.as_str().len()
+	}
+}
+
+impl MemoryFootprint for Nest {
+	fn heap_size(&self) -> usize {
+		match self {
+			Self::Nest => 0,
+			Self::Term(term) => term.heap_size(),
+		}
+	}
+}
+
+impl<T: MemoryFootprint, B: MemoryFootprint> MemoryFootprint for NormalTermDefinition<T, B> {
+	fn heap_size(&self) -> usize {
+		let language = match &self.language {
+			Some(json_ld_syntax::Nullable::Some(language)) => language.heap_size(),
+			_ => 0,
+		};
+
+		self.value.heap_size()
+			+ self.base_url.heap_size()
+			+ self.context.heap_size()
+			+ self.index.heap_size()
+			+ language
+			+ self.nest.heap_size()
+			+ self.typ.heap_size()
+	}
+}
+
+impl<T: MemoryFootprint, B: MemoryFootprint> MemoryFootprint for Definitions<T, B> {
+	fn heap_size(&self) -> usize {
+		table::<(Key, NormalTermDefinition<T, B>)>(self.len())
+			+ self
+				.iter()
+				.map(|binding| match binding {
+					BindingRef::Normal(key, definition) => key.heap_size() + definition.heap_size(),
+					BindingRef::Type(_) => 0,
+				})
+				.sum::<usize>()
+	}
+}
+
+impl<T: MemoryFootprint, B: MemoryFootprint> MemoryFootprint for Context<T, B> {
+	fn heap_size(&self) -> usize {
+		self.original_base_url().map_or(0, T::heap_size)
+			+ self.base_iri().map_or(0, T::heap_size)
+			+ self.vocabulary().map_or(0, Term::heap_size)
+			+ self.default_language().map_or(0, |l| l.as_str().len())
+			+ self
+				.previous_context()
+				.map_or(0, |c| size_of::<Self>() + c.heap_size())
+			+ self.definitions().heap_size()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn document() {
+		let iri = |s: &str| IriBuf::new(format!("https://example.com/{s}")).unwrap();
+
+		let mut node: Node = Node::with_id(Id::iri(iri("a")));
+		node.insert(
+			Id::iri(iri("name")),
+			Indexed::new(
+				Object::Value(Value::Literal(
+					Literal::String("a rather long string value".into()),
+					None,
+				)),
+				None,
+			),
+		);
+
+		let mut document = ExpandedDocument::new();
+		let empty = document.deep_size();
+		document.insert(Indexed::new(Object::node(node), None));
+		let one = document.deep_size();
+		assert!(one > empty + "a rather long string value".len());
+
+		let node_map = document
+			.clone()
+			.into_node_map(rdf_types::generator::Blank::new())
+			.unwrap();
+		assert!(node_map.heap_size() > "a rather long string value".len())
+	}
+}
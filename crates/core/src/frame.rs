@@ -0,0 +1,470 @@
+//! Frame matching.
+//!
+//! Implements the node matching part of the
+//! [JSON-LD Framing](https://www.w3.org/TR/json-ld11-framing/#frame-matching-algorithm)
+//! algorithm, so that expanded nodes can be filtered with frame-like patterns
+//! without producing framed output:
+//!
+//! ```
+//! use json_ld_core::{frame::Frame, object::Literal, IndexedObject, Node, Object, TryFromJson, Value};
+//! use static_iref::iri;
+//!
+//! let frame: Frame = Frame::try_from_json_in(
+//!   &mut (),
+//!   json_syntax::json!({
+//!     "@requireAll": true,
+//!     "@type": ["https://schema.org/Person"],
+//!     "https://schema.org/name": [{}]
+//!   }),
+//! )
+//! .unwrap();
+//!
+//! let mut node: Node = Node::new();
+//! node.types = Some(vec![iri!("https://schema.org/Person").to_owned().into()]);
+//! assert!(!frame.matches(&node));
+//!
+//! node.insert(
+//!   iri!("https://schema.org/name").to_owned().into(),
+//!   IndexedObject::none(Object::Value(Value::Literal(Literal::String("Alice".into()), None))),
+//! );
+//! assert!(frame.matches(&node));
+//! ```
+//!
+//! Frames are given in expanded form. Only embedded node objects are matched
+//! against node patterns: flatten the document first to match every node.
+use crate::{
+	object::{InvalidExpandedJson, Literal, TryFromJsonObject},
+	ExpandedDocument, Id, IndexedObject, Node, TryFromJson, Value,
+};
+use iref::{Iri, IriBuf};
+use json_ld_syntax::is_keyword;
+use rdf_types::{BlankIdBuf, VocabularyMut};
+use std::hash::Hash;
+
+/// Pattern matching the values of an entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Pattern<V> {
+	/// Matches any value, but the entry must be present (`[{}]`).
+	Wildcard,
+
+	/// Matches no value: the entry must be absent (`[]`).
+	None,
+
+	/// Matches entries with at least one of the given values.
+	OneOf(Vec<V>),
+}
+
+impl<V> Pattern<V> {
+	/// Checks that the given entry values match this pattern, comparing them
+	/// with `eq`.
+	fn matches_by<U>(&self, values: &[U], eq: impl Fn(&V, &U) -> bool) -> bool {
+		match self {
+			Self::Wildcard => !values.is_empty(),
+			Self::None => values.is_empty(),
+			Self::OneOf(patterns) => values.iter().any(|v| patterns.iter().any(|p| eq(p, v))),
+		}
+	}
+}
+
+/// Frame, matching node objects.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame<T = IriBuf, B = BlankIdBuf> {
+	/// Pattern of the node identifier, if any (`@id`).
+	pub id: Option<Pattern<Id<T, B>>>,
+
+	/// Pattern of the node types, if any (`@type`).
+	pub types: Option<Pattern<Id<T, B>>>,
+
+	/// Patterns of the node properties.
+	pub properties: Vec<(Id<T, B>, PropertyPattern<T, B>)>,
+
+	/// Whether all the patterns of the frame must match (`@requireAll`).
+	///
+	/// Otherwise, the node identifier, types, or any of the properties
+	/// matching is enough, in this order of precedence.
+	pub require_all: bool,
+}
+
+impl<T, B> Default for Frame<T, B> {
+	fn default() -> Self {
+		Self {
+			id: None,
+			types: None,
+			properties: Vec::new(),
+			require_all: false,
+		}
+	}
+}
+
+/// Pattern matching the values of a node property.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PropertyPattern<T = IriBuf, B = BlankIdBuf> {
+	/// The property must be present (`[{}]`).
+	Wildcard,
+
+	/// The property must be absent (`[]`).
+	None,
+
+	/// The property may be absent, which does not count as a match
+	/// (`[{"@default": ...}]`).
+	Default,
+
+	/// One of the values of the property must match the given value
+	/// pattern.
+	Value(ValuePattern<T>),
+
+	/// One of the node objects of the property must match the given frame.
+	Node(Box<Frame<T, B>>),
+}
+
+/// Pattern matching value objects.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValuePattern<T = IriBuf> {
+	/// Pattern of the value (`@value`).
+	pub value: Pattern<json_syntax::Value>,
+
+	/// Pattern of the datatype (`@type`).
+	pub types: Pattern<T>,
+
+	/// Pattern of the language, compared case-insensitively (`@language`).
+	pub languages: Pattern<String>,
+}
+
+impl<T: PartialEq> ValuePattern<T> {
+	/// Checks if the given value object matches this pattern.
+	pub fn matches(&self, value: &Value<T>) -> bool {
+		let ty = match value {
+			Value::Literal(_, ty) => ty.as_ref(),
+			_ => None,
+		};
+
+		let language = value.language().map(|l| l.as_str());
+
+		self.value.matches_by(&[value], |p, v| literal_eq(p, v))
+			&& self.types.matches_by(ty.as_slice(), |p, t| p == *t)
+			&& self
+				.languages
+				.matches_by(language.as_slice(), |p, l| p.eq_ignore_ascii_case(l))
+	}
+}
+
+/// Checks if the given JSON value is the value of the given value object.
+fn literal_eq<T>(pattern: &json_syntax::Value, value: &Value<T>) -> bool {
+	match (pattern, value) {
+		(json_syntax::Value::Null, Value::Literal(Literal::Null, _)) => true,
+		(json_syntax::Value::Boolean(a), Value::Literal(Literal::Boolean(b), _)) => a == b,
+		(json_syntax::Value::Number(a), Value::Literal(Literal::Number(b), _)) => a == b,
+		(json_syntax::Value::String(a), Value::Literal(Literal::String(b), _)) => a == b,
+		(json_syntax::Value::String(a), Value::LangString(s)) => a.as_str() == s.as_str(),
+		(pattern, Value::Json(json)) => pattern == json,
+		_ => false,
+	}
+}
+
+impl<T: Eq + Hash, B: Eq + Hash> Frame<T, B> {
+	/// Checks if the given node matches this frame.
+	pub fn matches(&self, node: &Node<T, B>) -> bool {
+		if let Some(id) = &self.id {
+			let matches = id.matches_by(node.id.as_slice(), PartialEq::eq);
+			if !matches || !self.require_all {
+				return matches;
+			}
+		}
+
+		if let Some(types) = &self.types {
+			let matches = types.matches_by(node.types(), PartialEq::eq);
+			if !matches || !self.require_all {
+				return matches;
+			}
+		}
+
+		let mut matches_some = false;
+		for (property, pattern) in &self.properties {
+			let mut objects = node.get(property).peekable();
+			let present = objects.peek().is_some();
+
+			let matches = match pattern {
+				PropertyPattern::Default if !present => continue,
+				PropertyPattern::Wildcard | PropertyPattern::Default => present,
+				PropertyPattern::None => {
+					if present {
+						return false;
+					}
+
+					true
+				}
+				PropertyPattern::Value(pattern) => objects
+					.filter_map(|o| o.as_value())
+					.any(|v| pattern.matches(v)),
+				PropertyPattern::Node(frame) => objects
+					.filter_map(|o| o.as_node())
+					.any(|n| frame.matches(n)),
+			};
+
+			if !matches && self.require_all {
+				return false;
+			}
+
+			matches_some |= matches
+		}
+
+		self.properties.is_empty() || matches_some
+	}
+
+	/// Returns the top-level nodes of the given document matching this frame.
+	pub fn filter<'a>(
+		&'a self,
+		document: &'a ExpandedDocument<T, B>,
+	) -> impl 'a + Iterator<Item = &'a IndexedObject<T, B>> {
+		document
+			.iter()
+			.filter(move |object| object.as_node().is_some_and(|node| self.matches(node)))
+	}
+}
+
+/// Returns the items of the given expanded entry value.
+fn items(value: json_syntax::Value) -> Vec<json_syntax::Value> {
+	match value {
+		json_syntax::Value::Array(items) => items,
+		value => vec![value],
+	}
+}
+
+/// Checks if the given value is the `{}` wildcard.
+fn is_wildcard(value: &json_syntax::Value) -> bool {
+	value.as_object().is_some_and(|o| o.is_empty())
+}
+
+/// Checks if the given value is a `@default` object.
+fn is_default(value: &json_syntax::Value) -> bool {
+	value
+		.as_object()
+		.is_some_and(|o| o.get_unique("@default").ok().flatten().is_some())
+}
+
+/// Parses the pattern of an expanded entry value.
+fn parse_pattern<V>(
+	value: json_syntax::Value,
+	mut f: impl FnMut(json_syntax::Value) -> Result<V, InvalidExpandedJson>,
+) -> Result<Pattern<V>, InvalidExpandedJson> {
+	let items = items(value);
+	match items.as_slice() {
+		[] => Ok(Pattern::None),
+		[item] if is_wildcard(item) => Ok(Pattern::Wildcard),
+		_ => Ok(Pattern::OneOf(
+			items.into_iter().map(&mut f).collect::<Result<_, _>>()?,
+		)),
+	}
+}
+
+/// Parses a `@requireAll` flag, possibly given as a value object.
+fn parse_flag(value: json_syntax::Value) -> Result<bool, InvalidExpandedJson> {
+	match items(value).into_iter().next() {
+		Some(json_syntax::Value::Boolean(b)) => Ok(b),
+		Some(json_syntax::Value::Object(o)) => match o.get_unique("@value") {
+			Ok(Some(json_syntax::Value::Boolean(b))) => Ok(*b),
+			_ => Err(InvalidExpandedJson::InvalidLiteral),
+		},
+		_ => Err(InvalidExpandedJson::InvalidLiteral),
+	}
+}
+
+impl<T: Eq + Hash, B: Eq + Hash> TryFromJson<T, B> for Frame<T, B> {
+	fn try_from_json_in(
+		vocabulary: &mut impl VocabularyMut<Iri = T, BlankId = B>,
+		value: json_syntax::Value,
+	) -> Result<Self, InvalidExpandedJson> {
+		let mut items = items(value).into_iter();
+		match (items.next(), items.next()) {
+			(Some(json_syntax::Value::Object(object)), None) => {
+				Self::try_from_json_object_in(vocabulary, object)
+			}
+			_ => Err(InvalidExpandedJson::InvalidObject),
+		}
+	}
+}
+
+impl<T: Eq + Hash, B: Eq + Hash> TryFromJsonObject<T, B> for Frame<T, B> {
+	fn try_from_json_object_in(
+		vocabulary: &mut impl VocabularyMut<Iri = T, BlankId = B>,
+		object: json_syntax::Object,
+	) -> Result<Self, InvalidExpandedJson> {
+		let mut frame = Self::default();
+
+		for entry in object {
+			match entry.key.as_str() {
+				"@id" => {
+					frame.id = Some(parse_pattern(entry.value, |v| {
+						Id::try_from_json_in(vocabulary, v)
+					})?)
+				}
+				"@type" => {
+					let value = entry.value;
+					if !items(value.clone()).iter().any(is_default) {
+						frame.types = Some(parse_pattern(value, |v| {
+							Id::try_from_json_in(vocabulary, v)
+						})?)
+					}
+				}
+				"@requireAll" => frame.require_all = parse_flag(entry.value)?,
+				key if is_keyword(key) => (),
+				key => {
+					let property = Id::try_from_json_in(vocabulary, key.into())?;
+					let pattern = match items(entry.value).into_iter().next() {
+						None => PropertyPattern::None,
+						Some(item) if is_wildcard(&item) => PropertyPattern::Wildcard,
+						Some(item) if is_default(&item) => PropertyPattern::Default,
+						Some(json_syntax::Value::Object(item)) => {
+							if item.get_unique("@list").ok().flatten().is_some() {
+								return Err(InvalidExpandedJson::UnexpectedEntry);
+							}
+
+							if item.get_unique("@value").ok().flatten().is_some() {
+								PropertyPattern::Value(ValuePattern::try_from_json_object_in(
+									vocabulary, item,
+								)?)
+							} else {
+								PropertyPattern::Node(Box::new(Self::try_from_json_object_in(
+									vocabulary, item,
+								)?))
+							}
+						}
+						Some(_) => return Err(InvalidExpandedJson::InvalidObject),
+					};
+
+					frame.properties.push((property, pattern))
+				}
+			}
+		}
+
+		Ok(frame)
+	}
+}
+
+impl<T> ValuePattern<T> {
+	fn try_from_json_object_in<B>(
+		vocabulary: &mut impl VocabularyMut<Iri = T, BlankId = B>,
+		object: json_syntax::Object,
+	) -> Result<Self, InvalidExpandedJson> {
+		let mut pattern = Self {
+			value: Pattern::Wildcard,
+			types: Pattern::None,
+			languages: Pattern::None,
+		};
+
+		for entry in object {
+			match entry.key.as_str() {
+				"@value" => pattern.value = parse_pattern(entry.value, Ok)?,
+				"@type" => {
+					pattern.types =
+						parse_pattern(entry.value, |v| match v.as_str().map(Iri::new) {
+							Some(Ok(iri)) => Ok(vocabulary.insert(iri)),
+							_ => Err(InvalidExpandedJson::InvalidValueType),
+						})?
+				}
+				"@language" => {
+					pattern.languages = parse_pattern(entry.value, |v| match v {
+						json_syntax::Value::String(s) => Ok(s.to_string()),
+						_ => Err(InvalidExpandedJson::InvalidLanguage),
+					})?
+				}
+				_ => (),
+			}
+		}
+
+		Ok(pattern)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn frame(json: json_syntax::Value) -> Frame {
+		Frame::try_from_json_in(&mut (), json).unwrap()
+	}
+
+	#[test]
+	fn matches() {
+		let document = ExpandedDocument::try_from_json_in(
+			&mut (),
+			json_syntax::json!([
+				{
+					"@id": "https://example.com/alice",
+					"@type": ["https://schema.org/Person"],
+					"https://schema.org/name": [{ "@value": "Alice", "@language": "en" }],
+					"https://schema.org/knows": [{
+						"@id": "https://example.com/bob",
+						"@type": ["https://schema.org/Person"]
+					}]
+				},
+				{
+					"@id": "https://example.com/acme",
+					"@type": ["https://schema.org/Organization"],
+					"https://schema.org/name": [{ "@value": "ACME" }]
+				},
+				{
+					"@id": "https://example.com/untyped"
+				}
+			]),
+		)
+		.unwrap();
+
+		let ids = |frame: Frame| -> Vec<String> {
+			frame
+				.filter(&document)
+				.map(|o| o.id().unwrap().as_str().to_owned())
+				.collect()
+		};
+
+		assert_eq!(
+			ids(frame(json_syntax::json!({
+				"@type": ["https://schema.org/Person"]
+			}))),
+			["https://example.com/alice"]
+		);
+		assert_eq!(
+			ids(frame(json_syntax::json!({ "@type": [] }))),
+			["https://example.com/untyped"]
+		);
+		assert_eq!(ids(frame(json_syntax::json!({ "@type": [{}] }))).len(), 2);
+		assert_eq!(ids(frame(json_syntax::json!({}))).len(), 3);
+		assert_eq!(
+			ids(frame(json_syntax::json!({
+				"https://schema.org/name": [{ "@value": {}, "@language": "EN" }]
+			}))),
+			["https://example.com/alice"]
+		);
+		assert_eq!(
+			ids(frame(json_syntax::json!({
+				"https://schema.org/name": [{ "@value": ["ACME", "Initech"] }]
+			}))),
+			["https://example.com/acme"]
+		);
+		assert_eq!(
+			ids(frame(json_syntax::json!({
+				"https://schema.org/knows": [{ "@id": "https://example.com/bob" }]
+			}))),
+			["https://example.com/alice"]
+		);
+		assert_eq!(
+			ids(frame(json_syntax::json!({
+				"@requireAll": true,
+				"https://schema.org/name": [{}],
+				"https://schema.org/knows": []
+			}))),
+			["https://example.com/acme"]
+		);
+
+		let node = Node::with_id(Id::iri(
+			IriBuf::new("https://example.com/x".to_owned()).unwrap(),
+		));
+		assert!(!frame(json_syntax::json!({ "https://schema.org/name": [{}] })).matches(&node));
+		assert!(frame(json_syntax::json!({
+			"@requireAll": true,
+			"https://schema.org/name": [{ "@default": "?" }],
+			"https://schema.org/knows": []
+		}))
+		.matches(&node));
+	}
+}
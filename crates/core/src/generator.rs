@@ -0,0 +1,424 @@
+//! Blank node identifier generators.
+use crate::{
+	object::value::Literal, ExpandedDocument, Id, IndexedNode, IndexedObject, Object, ValidId,
+	Value,
+};
+use iref::Iri;
+use json_syntax::Print;
+use rdf_types::{
+	generator::Blank, vocabulary::BlankIdVocabularyMut, BlankIdBuf, Generator, Vocabulary,
+};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Number of refinement rounds of the node hashes.
+///
+/// After `n` rounds, the hash of a blank node depends on the content of
+/// the blank nodes up to `n - 1` references away.
+const ROUNDS: usize = 3;
+
+/// SHA-256 digest.
+type NodeHash = [u8; 32];
+
+/// Generator of blank node identifiers derived from the content of the nodes
+/// they identify.
+///
+/// It is built from an expanded document and must then be used to flatten
+/// (or generate the node map of) this same document. Each blank node is
+/// labeled `_:h` followed by a hash of its types and properties, including
+/// the hashes of the blank nodes it references (up to a small depth). The
+/// same input document hence always yields the same labels, even across
+/// processes, and labels are mostly unaffected by unrelated changes in the
+/// document. This is much cheaper than a full canonical labeling
+/// ([RDFC-1.0](https://www.w3.org/TR/rdf-canon/)) but does not distinguish
+/// blank nodes with identical neighborhoods: those are labeled in order,
+/// with a `-n` suffix.
+///
+/// The hashed data is explicitly encoded and IRIs are hashed by content
+/// (through the vocabulary), so labels are stable across processes,
+/// platforms and vocabularies. Once the labels of the document are
+/// exhausted, the generator falls back to numbered `_:n` labels.
+pub struct ContentHash {
+	labels: std::vec::IntoIter<BlankIdBuf>,
+	fallback: Blank,
+}
+
+impl ContentHash {
+	/// Creates a generator for the given `document` with the given
+	/// `vocabulary`.
+	pub fn new_with<V>(vocabulary: &mut V, document: &ExpandedDocument<V::Iri, V::BlankId>) -> Self
+	where
+		V: Vocabulary + BlankIdVocabularyMut,
+		V::Iri: Clone + Eq + Hash,
+		V::BlankId: Clone + Eq + Hash,
+	{
+		// Labels are requested in node map generation order, so we generate
+		// the node map once to find out which blank node each call labels.
+		let mut recorder = Recorder {
+			inner: Blank::new(),
+			ids: Vec::new(),
+		};
+		let node_hashes = match document.generate_node_map_with(vocabulary, &mut recorder) {
+			Ok(node_map) => {
+				let mut nodes: HashMap<_, Vec<_>> = HashMap::new();
+				for (_, graph) in &node_map {
+					for node in graph.nodes() {
+						if let Some(b) = node.id.as_ref().and_then(Id::as_blank) {
+							nodes.entry(b).or_default().push(node)
+						}
+					}
+				}
+
+				let mut hashes = HashMap::new();
+				for _ in 0..ROUNDS {
+					hashes = nodes
+						.iter()
+						.map(|(b, nodes)| {
+							let mut node_hashes: Vec<_> = nodes
+								.iter()
+								.map(|n| hash_node(vocabulary, n, &hashes))
+								.collect();
+							node_hashes.sort_unstable();
+							(*b, digest(|d| d.hashes(&node_hashes)))
+						})
+						.collect();
+				}
+
+				recorder
+					.ids
+					.iter()
+					.map(|b| hashes.get(b).copied().unwrap_or_default())
+					.collect()
+			}
+			Err(_) => Vec::new(),
+		};
+
+		let mut counts: HashMap<NodeHash, usize> = HashMap::new();
+		let labels: Vec<_> = node_hashes
+			.into_iter()
+			.map(|hash| {
+				let mut label = "_:h".to_owned();
+				for byte in &hash[..8] {
+					label.push_str(&format!("{byte:02x}"))
+				}
+
+				let count = counts.entry(hash).or_default();
+				if *count > 0 {
+					label.push_str(&format!("-{count}"))
+				}
+				*count += 1;
+
+				BlankIdBuf::new(label).unwrap()
+			})
+			.collect();
+
+		Self {
+			labels: labels.into_iter(),
+			fallback: Blank::new_with_prefix("n".to_owned()),
+		}
+	}
+
+	/// Creates a generator for the given `document`.
+	pub fn new<T, B>(document: &ExpandedDocument<T, B>) -> Self
+	where
+		T: Clone + Eq + Hash,
+		B: Clone + Eq + Hash,
+		(): Vocabulary<Iri = T, BlankId = B> + BlankIdVocabularyMut,
+	{
+		Self::new_with(&mut (), document)
+	}
+}
+
+impl<V: Vocabulary + BlankIdVocabularyMut> Generator<V> for ContentHash {
+	fn next(&mut self, vocabulary: &mut V) -> rdf_types::Id<V::Iri, V::BlankId> {
+		match self.labels.next() {
+			Some(label) => rdf_types::Id::Blank(vocabulary.insert_blank_id(&label)),
+			None => self.fallback.next(vocabulary),
+		}
+	}
+}
+
+/// Generator recording the blank node identifiers it generates.
+struct Recorder<B> {
+	inner: Blank,
+	ids: Vec<B>,
+}
+
+impl<V: Vocabulary + BlankIdVocabularyMut> Generator<V> for Recorder<V::BlankId>
+where
+	V::BlankId: Clone,
+{
+	fn next(&mut self, vocabulary: &mut V) -> rdf_types::Id<V::Iri, V::BlankId> {
+		let id = self.inner.next(vocabulary);
+		if let rdf_types::Id::Blank(b) = &id {
+			self.ids.push(b.clone())
+		}
+		id
+	}
+}
+
+/// SHA-256 digest of explicitly encoded data, so that hashes do not depend
+/// on the process or platform.
+///
+/// Strings and byte sequences are prefixed by their length, so that
+/// consecutive fields cannot be confused.
+struct Digest256(Sha256);
+
+impl Digest256 {
+	fn tag(&mut self, tag: u8) {
+		self.0.update([tag])
+	}
+
+	fn bytes(&mut self, bytes: &[u8]) {
+		self.0.update((bytes.len() as u64).to_le_bytes());
+		self.0.update(bytes)
+	}
+
+	fn str(&mut self, s: &str) {
+		self.bytes(s.as_bytes())
+	}
+
+	fn opt_str(&mut self, s: Option<&str>) {
+		match s {
+			Some(s) => {
+				self.tag(1);
+				self.str(s)
+			}
+			None => self.tag(0),
+		}
+	}
+
+	fn hash(&mut self, hash: &NodeHash) {
+		self.0.update(hash)
+	}
+
+	fn hashes(&mut self, hashes: &[NodeHash]) {
+		self.0.update((hashes.len() as u64).to_le_bytes());
+		for hash in hashes {
+			self.hash(hash)
+		}
+	}
+}
+
+fn digest(f: impl FnOnce(&mut Digest256)) -> NodeHash {
+	let mut digest = Digest256(Sha256::new());
+	f(&mut digest);
+	digest.0.finalize().into()
+}
+
+/// Hashes the given identifier, using the current hash of blank nodes.
+fn hash_id<V: Vocabulary>(
+	vocabulary: &V,
+	id: &Id<V::Iri, V::BlankId>,
+	hashes: &HashMap<&V::BlankId, NodeHash>,
+) -> NodeHash
+where
+	V::BlankId: Eq + Hash,
+{
+	match id {
+		Id::Valid(ValidId::Blank(b)) => hashes.get(b).copied().unwrap_or_default(),
+		Id::Valid(ValidId::Iri(iri)) => digest(|d| {
+			d.tag(0);
+			d.opt_str(vocabulary.iri(iri).map(Iri::as_str))
+		}),
+		Id::Invalid(id) => digest(|d| {
+			d.tag(1);
+			d.str(id)
+		}),
+	}
+}
+
+/// Hashes the given value.
+fn hash_value<V: Vocabulary>(vocabulary: &V, value: &Value<V::Iri>, d: &mut Digest256) {
+	match value {
+		Value::Literal(literal, ty) => {
+			d.tag(0);
+			match literal {
+				Literal::Null => d.tag(0),
+				Literal::Boolean(b) => {
+					d.tag(1);
+					d.tag(*b as u8)
+				}
+				Literal::Number(n) => {
+					d.tag(2);
+					d.str(n.as_str())
+				}
+				Literal::String(s) => {
+					d.tag(3);
+					d.str(s)
+				}
+			}
+			d.opt_str(
+				ty.as_ref()
+					.map(|ty| vocabulary.iri(ty).map(Iri::as_str).unwrap_or_default()),
+			)
+		}
+		Value::LangString(s) => {
+			d.tag(1);
+			d.str(s.as_str());
+			d.opt_str(s.language().map(|l| l.as_str()));
+			d.opt_str(s.direction().map(|dir| dir.as_str()))
+		}
+		Value::Json(json) => {
+			d.tag(2);
+			let mut json = json.clone();
+			json.canonicalize();
+			d.str(&json.compact_print().to_string())
+		}
+	}
+}
+
+/// Hashes the given node map object.
+fn hash_object<V: Vocabulary>(
+	vocabulary: &V,
+	object: &IndexedObject<V::Iri, V::BlankId>,
+	hashes: &HashMap<&V::BlankId, NodeHash>,
+) -> NodeHash
+where
+	V::BlankId: Eq + Hash,
+{
+	digest(|d| {
+		d.opt_str(object.index());
+		match object.inner() {
+			Object::Value(value) => {
+				d.tag(0);
+				hash_value(vocabulary, value, d)
+			}
+			Object::Node(node) => {
+				d.tag(1);
+				match &node.id {
+					Some(id) => {
+						d.tag(1);
+						d.hash(&hash_id(vocabulary, id, hashes))
+					}
+					None => d.tag(0),
+				}
+			}
+			Object::List(list) => {
+				d.tag(2);
+				let items: Vec<_> = list
+					.iter()
+					.map(|item| hash_object(vocabulary, item, hashes))
+					.collect();
+				d.hashes(&items)
+			}
+		}
+	})
+}
+
+/// Hashes the given node map node, independently of the order of its
+/// entries.
+fn hash_node<V: Vocabulary>(
+	vocabulary: &V,
+	node: &IndexedNode<V::Iri, V::BlankId>,
+	hashes: &HashMap<&V::BlankId, NodeHash>,
+) -> NodeHash
+where
+	V::BlankId: Eq + Hash,
+{
+	let mut entries: Vec<NodeHash> = node
+		.types()
+		.iter()
+		.map(|ty| digest(|d| d.hash(&hash_id(vocabulary, ty, hashes))))
+		.collect();
+
+	for (prop, objects) in node.properties() {
+		let prop = hash_id(vocabulary, prop, hashes);
+		for object in objects {
+			entries.push(digest(|d| {
+				d.hash(&prop);
+				d.hash(&hash_object(vocabulary, object, hashes))
+			}))
+		}
+	}
+
+	entries.sort_unstable();
+	digest(|d| {
+		d.opt_str(node.index());
+		d.hashes(&entries)
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{flattening::Flatten, TryFromJson};
+	use iref::IriBuf;
+	use rdf_types::vocabulary::{BlankIdVocabulary, IndexVocabulary};
+
+	type FlattenedDocument = crate::FlattenedDocument<IriBuf, BlankIdBuf>;
+
+	fn flatten(json: json_syntax::Value) -> FlattenedDocument {
+		let document = ExpandedDocument::try_from_json_in(&mut (), json).unwrap();
+		let generator = ContentHash::new(&document);
+		document.flatten(generator, true).unwrap()
+	}
+
+	fn ids(flattened: &FlattenedDocument) -> Vec<String> {
+		let mut ids: Vec<_> = flattened
+			.iter()
+			.map(|node| node.id.as_ref().unwrap().as_str().to_owned())
+			.collect();
+		ids.sort();
+		ids
+	}
+
+	#[test]
+	fn content_hash() {
+		let alice = json_syntax::json!({
+			"https://schema.org/name": [{ "@value": "Alice" }],
+			"https://schema.org/address": [{
+				"https://schema.org/city": [{ "@value": "Paris" }]
+			}]
+		});
+		let bob = json_syntax::json!({
+			"https://schema.org/name": [{ "@value": "Bob" }]
+		});
+
+		let flattened = flatten(json_syntax::json!([alice.clone(), bob.clone()]));
+		assert_eq!(
+			flattened,
+			flatten(json_syntax::json!([alice.clone(), bob.clone()]))
+		);
+
+		// Labels do not depend on the order of the nodes.
+		let labels = ids(&flattened);
+		assert_eq!(labels.len(), 3);
+		assert!(labels.iter().all(|id| id.starts_with("_:h")));
+		assert_eq!(
+			labels,
+			ids(&flatten(json_syntax::json!([bob, alice.clone()])))
+		);
+
+		// Nodes with identical neighborhoods are labeled in order.
+		let labels = ids(&flatten(json_syntax::json!([{
+			"https://schema.org/knows": [alice.clone(), alice]
+		}])));
+		assert_eq!(labels.len(), 5);
+		assert_eq!(labels.iter().filter(|id| id.ends_with("-1")).count(), 2);
+	}
+
+	#[test]
+	fn known_label() {
+		let json = json_syntax::json!([{
+			"@type": ["https://schema.org/Person"],
+			"https://schema.org/name": [{ "@value": "Alice" }]
+		}]);
+		assert_eq!(ids(&flatten(json.clone())), ["_:h04c315a8b6db45f1"]);
+
+		// Labels do not depend on the vocabulary.
+		let mut vocabulary: IndexVocabulary = IndexVocabulary::new();
+		let document = ExpandedDocument::try_from_json_in(&mut vocabulary, json).unwrap();
+		let mut generator = ContentHash::new_with(&mut vocabulary, &document);
+		match generator.next(&mut vocabulary) {
+			rdf_types::Id::Blank(b) => {
+				assert_eq!(
+					vocabulary.blank_id(&b).unwrap().as_str(),
+					"_:h04c315a8b6db45f1"
+				)
+			}
+			rdf_types::Id::Iri(_) => panic!("expected a blank node identifier"),
+		}
+	}
+}
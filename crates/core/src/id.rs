@@ -34,6 +34,7 @@ pub type VocabularyId<V> = Id<<V as IriVocabulary>::Iri, <V as BlankIdVocabulary
 /// This may be useful to define custom [`indexmap::Equivalent<Id<I, B>>`]
 /// implementation.
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Id<I = IriBuf, B = BlankIdBuf> {
 	/// Valid node identifier.
 	Valid(ValidId<I, B>),
@@ -380,6 +381,119 @@ impl<'a, T, B> TryFrom<&'a mut Id<T, B>> for &'a mut ValidId<T, B> {
 	}
 }
 
+/// IRI validation level.
+///
+/// Determines which strings are accepted as valid IRIs once a document is
+/// expanded. Rejected strings are preserved in [`Id::Invalid`].
+#[derive(Debug, Default, Clone, Copy)]
+pub enum IriValidation {
+	/// Only IRIs conforming to RFC 3987 are valid.
+	#[default]
+	Strict,
+
+	/// Any string starting with a scheme is valid.
+	///
+	/// Characters that are not allowed in IRIs (such as spaces) are
+	/// percent-encoded.
+	Lenient,
+
+	/// Only IRIs conforming to RFC 3987 and accepted by the given function are
+	/// valid.
+	Custom(fn(&Iri) -> bool),
+}
+
+impl IriValidation {
+	/// Validates the given identifier.
+	///
+	/// Returns the validated identifier, which may be [`Id::Invalid`] if
+	/// the input was a valid IRI rejected by this validation level, or
+	/// [`Id::Valid`] if the input was an invalid string repaired by this
+	/// validation level.
+	pub fn validate_in<T, B>(
+		&self,
+		vocabulary: &mut impl VocabularyMut<Iri = T, BlankId = B>,
+		id: Id<T, B>,
+	) -> Id<T, B> {
+		match (self, id) {
+			(Self::Custom(f), Id::Valid(ValidId::Iri(iri))) => {
+				let lexical = vocabulary.iri(&iri).unwrap();
+				if f(lexical) {
+					Id::iri(iri)
+				} else {
+					Id::Invalid(lexical.as_str().to_owned())
+				}
+			}
+			(Self::Lenient, Id::Invalid(s)) if has_scheme(&s) => {
+				match IriBuf::new(percent_encode_invalid(&s)) {
+					Ok(iri) => Id::iri(vocabulary.insert(iri.as_iri())),
+					Err(_) => Id::Invalid(s),
+				}
+			}
+			(_, id) => id,
+		}
+	}
+}
+
+/// Policy applied to invalid identifiers ([`Id::Invalid`]) by the algorithms
+/// producing an output from an expanded document, such as compaction or RDF
+/// serialization.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InvalidIriPolicy {
+	/// Invalid identifiers are removed from the output, along with the
+	/// entries they identify.
+	Skip,
+
+	/// Invalid identifiers cause an error.
+	Error,
+
+	/// Invalid identifiers are output as is.
+	///
+	/// Since invalid identifiers cannot be represented in RDF, RDF
+	/// serialization treats this policy as [`Self::Skip`].
+	#[default]
+	PassThrough,
+}
+
+/// Checks if the given string starts with an IRI scheme followed by `:`.
+fn has_scheme(s: &str) -> bool {
+	match s.split_once(':') {
+		Some((scheme, _)) => {
+			let mut chars = scheme.chars();
+			chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+				&& chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+		}
+		None => false,
+	}
+}
+
+/// Percent-encodes every character of `s` that cannot appear in an IRI.
+fn percent_encode_invalid(s: &str) -> String {
+	let bytes = s.as_bytes();
+	let mut result = String::with_capacity(s.len());
+	for (i, c) in s.char_indices() {
+		let escape = match c {
+			'%' => {
+				!(bytes.len() > i + 2
+					&& bytes[i + 1].is_ascii_hexdigit()
+					&& bytes[i + 2].is_ascii_hexdigit())
+			}
+			' ' | '"' | '<' | '>' | '\\' | '^' | '`' | '{' | '|' | '}' => true,
+			c => c.is_control(),
+		};
+
+		if escape {
+			let mut buffer = [0; 4];
+			for b in c.encode_utf8(&mut buffer).bytes() {
+				result.push_str(&format!("%{b:02X}"))
+			}
+		} else {
+			result.push(c)
+		}
+	}
+
+	result
+}
+
 /// Id to a reference.
 #[derive(Clone, PartialEq, Eq, Hash)]
 #[repr(u8)]
@@ -439,3 +553,29 @@ pub trait Relabel<T, B> {
 		)
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn lenient_iri_validation() {
+		let id: Id = Id::Invalid("https://example.com/a b".to_owned());
+		let id = IriValidation::Lenient.validate_in(&mut (), id);
+		assert_eq!(id.as_str(), "https://example.com/a%20b");
+		assert!(id.is_valid());
+
+		let id: Id = Id::Invalid("not an iri".to_owned());
+		assert!(!IriValidation::Lenient.validate_in(&mut (), id).is_valid())
+	}
+
+	#[test]
+	fn custom_iri_validation() {
+		let validation = IriValidation::Custom(|iri| iri.scheme().as_str() == "https");
+		let id: Id = Id::iri(IriBuf::new("http://example.com/".to_owned()).unwrap());
+		assert_eq!(
+			validation.validate_in(&mut (), id),
+			Id::Invalid("http://example.com/".to_owned())
+		)
+	}
+}
@@ -1,20 +1,64 @@
 //! JSON-LD core types.
+//!
+//! # Features
+//!
+//! The minimal core (without default features) provides the JSON-LD data
+//! model, flattening and the basic document loaders ([`NoLoader`],
+//! [`FsLoader`], [`ChainLoader`] and maps). The following features are
+//! enabled by default:
+//!   - `linked-data`: implementation of the
+//!     [`linked-data`](https://crates.io/crates/linked-data) traits by
+//!     expanded documents, and conversion of RDF datasets into expanded
+//!     documents.
+//!   - `frame-matching`: frame matching of expanded nodes (the `frame`
+//!     module).
+//!   - `rdf`: conversion of expanded documents to RDF (`RdfQuads`), the
+//!     Turtle/TriG writers and the `dataset` module.
+//!   - `loaders`: the other document loaders (static, snapshot, fixture,
+//!     fragment and integrity loaders).
+//!   - `content-hash`: the content-derived blank node identifier generator
+//!     (the `generator` module) and property redaction (the `redact`
+//!     module), both based on SHA-256.
+//!
+//! Other optional features include `serde`, `reqwest` (HTTP loader),
+//! `petgraph`, `rdf-star`, `rdf-parse` (Turtle, N-Triples and N-Quads
+//! parsers), `miette`, `arbitrary` (random data for property testing and
+//! fuzzing, with `proptest` strategies behind the `proptest` feature),
+//! `schema-org` and `ahash`.
 pub use json_ld_syntax::{Direction, LenientLangTag, LenientLangTagBuf, Nullable};
 
+#[cfg(feature = "arbitrary")]
+pub mod arbitrary;
 mod container;
 pub mod context;
+#[cfg(feature = "rdf")]
+pub mod dataset;
+#[cfg(feature = "linked-data")]
 mod deserialization;
 mod document;
+pub mod document_set;
 pub mod flattening;
+pub mod footprint;
+#[cfg(feature = "frame-matching")]
+pub mod frame;
+#[cfg(feature = "content-hash")]
+pub mod generator;
 pub mod id;
 mod indexed;
 mod lang_string;
 pub mod loader;
 mod mode;
 pub mod object;
+pub mod patch;
 pub mod print;
+pub mod progress;
 pub mod quad;
 pub mod rdf;
+#[cfg(feature = "content-hash")]
+pub mod redact;
+#[cfg(feature = "schema-org")]
+pub mod schema;
+#[cfg(feature = "linked-data")]
 mod serialization;
 mod term;
 mod ty;
@@ -23,8 +67,12 @@ pub mod warning;
 
 pub use container::{Container, ContainerKind};
 pub use context::Context;
+#[cfg(feature = "rdf")]
+pub use dataset::Dataset;
 pub use document::*;
+pub use document_set::DocumentSet;
 pub use flattening::Flatten;
+pub use footprint::MemoryFootprint;
 pub use id::*;
 pub use indexed::*;
 pub use lang_string::*;
@@ -33,6 +81,7 @@ pub use mode::*;
 pub use object::{IndexedNode, IndexedObject, Node, Nodes, Object, Objects, TryFromJson, Value};
 pub use print::Print;
 pub use quad::LdQuads;
+#[cfg(feature = "rdf")]
 pub use rdf::RdfQuads;
 pub use term::*;
 pub use ty::*;
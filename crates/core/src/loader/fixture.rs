@@ -0,0 +1,150 @@
+use super::{snapshot::InvalidSnapshot, Loader, Snapshot, SnapshotLoader};
+use crate::{LoadError, LoadingResult};
+use iref::{Iri, IriBuf};
+use json_syntax::{Parse, Print};
+use std::path::{Path, PathBuf};
+
+/// * [`RecordingLoader`]: records every document loaded by the inner loader
+///   into a fixture file.
+///
+/// This is meant to capture the real-world documents (typically context
+/// chains) required by a test once, so that the test can then run with a
+/// [`ReplayLoader`] serving them from the fixture, without network access.
+///
+/// The fixture is only written when [`RecordingLoader::save`] is called. It
+/// contains a [`Snapshot`] bundle.
+pub struct RecordingLoader<L> {
+	loader: SnapshotLoader<L>,
+	path: PathBuf,
+}
+
+impl<L> RecordingLoader<L> {
+	/// Wraps the given loader, recording into the fixture file at `path`.
+	pub fn new(loader: L, path: impl Into<PathBuf>) -> Self {
+		Self {
+			loader: SnapshotLoader::new(loader),
+			path: path.into(),
+		}
+	}
+
+	/// Returns the path of the fixture file.
+	pub fn path(&self) -> &Path {
+		&self.path
+	}
+
+	/// Returns a copy of the documents recorded so far.
+	pub fn snapshot(&self) -> Snapshot {
+		self.loader.snapshot()
+	}
+
+	/// Writes the documents recorded so far to the fixture file, replacing
+	/// it.
+	pub fn save(&self) -> std::io::Result<()> {
+		let json = self.loader.snapshot().to_json();
+		std::fs::write(&self.path, json.pretty_print().to_string())
+	}
+}
+
+impl<L: Loader> Loader for RecordingLoader<L> {
+	async fn load(&self, url: &Iri) -> LoadingResult<IriBuf> {
+		self.loader.load(url).await
+	}
+}
+
+/// * [`ReplayLoader`]: serves the documents of a fixture file written by a
+///   [`RecordingLoader`].
+///
+/// Loading a document that is not in the fixture fails with
+/// [`Error::NotRecorded`].
+pub struct ReplayLoader {
+	snapshot: Snapshot,
+}
+
+impl ReplayLoader {
+	/// Reads the fixture file at `path`.
+	pub fn open(path: impl AsRef<Path>) -> Result<Self, FixtureError> {
+		let contents = std::fs::read_to_string(path).map_err(FixtureError::IO)?;
+		let (json, _) = json_syntax::Value::parse_str(&contents).map_err(FixtureError::Parse)?;
+		Ok(Self {
+			snapshot: Snapshot::from_json(&json).map_err(FixtureError::Invalid)?,
+		})
+	}
+
+	/// Returns the recorded documents.
+	pub fn snapshot(&self) -> &Snapshot {
+		&self.snapshot
+	}
+}
+
+impl From<Snapshot> for ReplayLoader {
+	fn from(snapshot: Snapshot) -> Self {
+		Self { snapshot }
+	}
+}
+
+impl Loader for ReplayLoader {
+	async fn load(&self, url: &Iri) -> LoadingResult<IriBuf> {
+		match self.snapshot.get(url) {
+			Some(document) => Ok(document.clone()),
+			None => Err(LoadError::new(url.to_owned(), Error::NotRecorded)),
+		}
+	}
+}
+
+/// Replay error.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+	#[error("document not recorded in the fixture")]
+	NotRecorded,
+}
+
+/// Fixture file reading error.
+#[derive(Debug, thiserror::Error)]
+pub enum FixtureError {
+	#[error("IO: {0}")]
+	IO(std::io::Error),
+
+	#[error("parse error: {0}")]
+	Parse(json_syntax::parse::Error),
+
+	#[error(transparent)]
+	Invalid(InvalidSnapshot),
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::RemoteDocument;
+	use std::collections::HashMap;
+
+	#[test]
+	fn record_and_replay() {
+		let url = IriBuf::new("https://example.com/context.jsonld".to_owned()).unwrap();
+		let mut documents = HashMap::new();
+		documents.insert(
+			url.clone(),
+			RemoteDocument::new(
+				Some(url.clone()),
+				None,
+				json_syntax::json!({ "@context": { "name": "https://schema.org/name" } }),
+			),
+		);
+
+		let path =
+			std::env::temp_dir().join(format!("json-ld-fixture-{}.json", std::process::id()));
+
+		let recording = RecordingLoader::new(documents, &path);
+		let recorded = futures::executor::block_on(recording.load(&url)).unwrap();
+		recording.save().unwrap();
+
+		let replay = ReplayLoader::open(&path);
+		std::fs::remove_file(&path).unwrap();
+		let replay = replay.unwrap();
+
+		let replayed = futures::executor::block_on(replay.load(&url)).unwrap();
+		assert_eq!(replayed.document(), recorded.document());
+
+		let missing = Iri::new("https://example.com/missing.jsonld").unwrap();
+		assert!(futures::executor::block_on(replay.load(missing)).is_err())
+	}
+}
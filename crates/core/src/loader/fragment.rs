@@ -0,0 +1,313 @@
+use super::Loader;
+use crate::{LoadError, LoadingResult};
+use iref::{Iri, IriBuf, IriRef};
+use json_syntax::Value;
+
+/// * [`FragmentLoader`]: selects the object targeted by the fragment
+///   identifier of the loaded IRI.
+///
+/// When the IRI has a fragment (e.g. `https://example.com/doc#key-1`), the
+/// inner loader is used to load the document without the fragment, then the
+/// targeted object is selected: the first JSON object (in depth-first order)
+/// whose identifier, resolved against the document URL, is the full IRI.
+/// The identifier is the value of the `@id` entry, or of any of its aliases
+/// (such as `id`) defined by the contexts of the document. Remote contexts
+/// are loaded with the inner loader to find those aliases, ignoring loading
+/// failures. If the selected object has no `@context` entry, the top-level
+/// `@context` of the document (if any) is copied into it.
+///
+/// Loading fails if no object is targeted by the fragment. IRIs without
+/// fragment are passed to the inner loader unchanged.
+///
+/// Aliases are collected from all the contexts of the document, regardless
+/// of their scope, and from the term definitions of the remote contexts
+/// (but not from the contexts those import). HTML element identifiers are
+/// not supported, since loaders only return JSON documents.
+pub struct FragmentLoader<L>(L);
+
+impl<L> FragmentLoader<L> {
+	/// Wraps the given loader.
+	pub fn new(loader: L) -> Self {
+		Self(loader)
+	}
+
+	/// Returns the inner loader.
+	pub fn into_inner(self) -> L {
+		self.0
+	}
+}
+
+impl<L: Loader> FragmentLoader<L> {
+	/// Collects the aliases of `@id` defined by the contexts of `document`,
+	/// loading its remote contexts.
+	async fn id_aliases(&self, document: &Value, base_url: &Iri) -> Vec<String> {
+		let mut aliases = Vec::new();
+		let mut contexts = Vec::new();
+		collect_contexts(document, &mut contexts);
+
+		for context in contexts {
+			match context {
+				Value::String(iri) => {
+					let Ok(iri) = IriRef::new(iri.as_str()) else {
+						continue;
+					};
+
+					if let Ok(remote) = self.0.load(&iri.resolved(base_url)).await {
+						if let Some(context) = remote
+							.document()
+							.as_object()
+							.and_then(|object| object.get_unique("@context").ok().flatten())
+						{
+							for definition in items(context) {
+								collect_id_aliases(definition, &mut aliases)
+							}
+						}
+					}
+				}
+				definition => collect_id_aliases(definition, &mut aliases),
+			}
+		}
+
+		aliases
+	}
+}
+
+impl<L: Loader> Loader for FragmentLoader<L> {
+	async fn load(&self, url: &Iri) -> LoadingResult<IriBuf> {
+		match url.fragment() {
+			Some(fragment) => {
+				let mut document_url = url.to_owned();
+				document_url.set_fragment(None);
+
+				let document = self.0.load(&document_url).await?;
+				let aliases = self.id_aliases(document.document(), &document_url).await;
+				document
+					.try_map(|value| select(url, fragment.as_str(), &aliases, value))
+					.map_err(|e| LoadError::new(url.to_owned(), e))
+			}
+			None => self.0.load(url).await,
+		}
+	}
+}
+
+/// Selects the object targeted by the given `fragment` of `url` in
+/// `document`.
+///
+/// Objects are identified by their `@id` entry, or any of the given
+/// `aliases`. Relative identifiers are resolved against `url`.
+pub fn select(
+	url: &Iri,
+	fragment: &str,
+	aliases: &[String],
+	document: Value,
+) -> Result<Value, Error> {
+	let targeted =
+		|id: &str| IriRef::new(id).is_ok_and(|id| id.resolved(url).as_str() == url.as_str());
+
+	let mut target = find(&document, aliases, targeted)
+		.ok_or_else(|| Error::MissingTarget(fragment.to_owned()))?
+		.clone();
+
+	if let (Some(target), Some(document)) = (target.as_object_mut(), document.as_object()) {
+		if target.get_unique("@context").ok().flatten().is_none() {
+			if let Some(context) = document.get_unique("@context").ok().flatten() {
+				target.insert("@context".into(), context.clone());
+			}
+		}
+	}
+
+	Ok(target)
+}
+
+fn find<'a>(
+	value: &'a Value,
+	aliases: &[String],
+	f: impl Copy + Fn(&str) -> bool,
+) -> Option<&'a Value> {
+	match value {
+		Value::Array(items) => items.iter().find_map(|item| find(item, aliases, f)),
+		Value::Object(object) => {
+			let targeted = object.iter().any(|entry| {
+				(entry.key.as_str() == "@id" || aliases.iter().any(|a| *a == entry.key.as_str()))
+					&& entry.value.as_str().is_some_and(f)
+			});
+
+			if targeted {
+				Some(value)
+			} else {
+				object
+					.iter()
+					.filter(|entry| entry.key.as_str() != "@context")
+					.find_map(|entry| find(&entry.value, aliases, f))
+			}
+		}
+		_ => None,
+	}
+}
+
+/// Collects the `@context` entries of `value`, flattening arrays of
+/// contexts.
+fn collect_contexts<'a>(value: &'a Value, contexts: &mut Vec<&'a Value>) {
+	match value {
+		Value::Array(items) => items
+			.iter()
+			.for_each(|item| collect_contexts(item, contexts)),
+		Value::Object(object) => {
+			for entry in object {
+				if entry.key.as_str() == "@context" {
+					contexts.extend(items(&entry.value))
+				} else {
+					collect_contexts(&entry.value, contexts)
+				}
+			}
+		}
+		_ => (),
+	}
+}
+
+/// Collects the terms defined as aliases of `@id` by the given context
+/// definition.
+fn collect_id_aliases(definition: &Value, aliases: &mut Vec<String>) {
+	if let Some(definition) = definition.as_object() {
+		for entry in definition {
+			let id = match &entry.value {
+				Value::Object(term) => term.get_unique("@id").ok().flatten(),
+				value => Some(value),
+			};
+
+			if id.and_then(Value::as_str) == Some("@id") {
+				aliases.push(entry.key.to_string())
+			}
+		}
+	}
+}
+
+fn items(value: &Value) -> &[Value] {
+	match value {
+		Value::Array(items) => items,
+		value => std::slice::from_ref(value),
+	}
+}
+
+/// Fragment selection error.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+	#[error("no object is targeted by the fragment `{0}`")]
+	MissingTarget(String),
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::RemoteDocument;
+	use std::collections::HashMap;
+
+	fn loader(documents: Vec<(&str, Value)>) -> FragmentLoader<HashMap<IriBuf, RemoteDocument>> {
+		FragmentLoader::new(
+			documents
+				.into_iter()
+				.map(|(url, document)| {
+					let url = IriBuf::new(url.to_owned()).unwrap();
+					(url.clone(), RemoteDocument::new(Some(url), None, document))
+				})
+				.collect(),
+		)
+	}
+
+	fn load(loader: &impl Loader, url: &str) -> Result<Value, LoadError> {
+		futures::executor::block_on(loader.load(Iri::new(url).unwrap()))
+			.map(RemoteDocument::into_document)
+	}
+
+	#[test]
+	fn fragment_target() {
+		let loader = loader(vec![(
+			"https://example.com/did.json",
+			json_syntax::json!({
+				"@context": "https://www.w3.org/ns/did/v1",
+				"id": "https://example.com/did.json",
+				"verificationMethod": [{ "@id": "#key-1", "type": "JsonWebKey2020" }]
+			}),
+		)]);
+
+		assert_eq!(
+			load(&loader, "https://example.com/did.json#key-1").unwrap(),
+			json_syntax::json!({
+				"@id": "#key-1",
+				"type": "JsonWebKey2020",
+				"@context": "https://www.w3.org/ns/did/v1"
+			})
+		);
+
+		assert!(load(&loader, "https://example.com/did.json#key-2").is_err())
+	}
+
+	#[test]
+	fn aliased_id() {
+		let loader = loader(vec![
+			(
+				"https://example.com/context.json",
+				json_syntax::json!({
+					"@context": { "id": "@id", "name": "https://schema.org/name" }
+				}),
+			),
+			(
+				"https://example.com/did.json",
+				json_syntax::json!({
+					"@context": "context.json",
+					"id": "https://example.com/did.json",
+					"verificationMethod": [{ "id": "#key-1", "name": "Key 1" }]
+				}),
+			),
+			(
+				"https://example.com/inline.json",
+				json_syntax::json!({
+					"@context": { "identifier": { "@id": "@id" } },
+					"items": [{ "identifier": "#a", "https://schema.org/name": "A" }]
+				}),
+			),
+		]);
+
+		// Alias defined by a remote context.
+		assert_eq!(
+			load(&loader, "https://example.com/did.json#key-1").unwrap(),
+			json_syntax::json!({
+				"id": "#key-1",
+				"name": "Key 1",
+				"@context": "context.json"
+			})
+		);
+
+		// Alias defined by an inline context.
+		assert_eq!(
+			load(&loader, "https://example.com/inline.json#a").unwrap(),
+			json_syntax::json!({
+				"identifier": "#a",
+				"https://schema.org/name": "A",
+				"@context": { "identifier": { "@id": "@id" } }
+			})
+		)
+	}
+
+	#[test]
+	fn relative_id() {
+		let loader = loader(vec![(
+			"https://example.com/keys/did.json",
+			json_syntax::json!([
+				{ "@id": "other.json#key-1", "https://schema.org/name": "Other" },
+				{ "@id": "did.json#key-1", "https://schema.org/name": "Key 1" },
+				{ "@id": "../keys/did.json#key-2", "https://schema.org/name": "Key 2" }
+			]),
+		)]);
+
+		assert_eq!(
+			load(&loader, "https://example.com/keys/did.json#key-1").unwrap(),
+			json_syntax::json!({ "@id": "did.json#key-1", "https://schema.org/name": "Key 1" })
+		);
+
+		assert_eq!(
+			load(&loader, "https://example.com/keys/did.json#key-2").unwrap(),
+			json_syntax::json!({ "@id": "../keys/did.json#key-2", "https://schema.org/name": "Key 2" })
+		)
+	}
+}
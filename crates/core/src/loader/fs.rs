@@ -83,7 +83,8 @@ impl Loader for FsLoader {
 					Some(url.to_owned()),
 					Some("application/ld+json".parse().unwrap()),
 					doc,
-				))
+				)
+				.with_raw(contents.into_bytes()))
 			}
 			None => Err(LoadError::new(url.to_owned(), Error::NoMountPoint)),
 		}
@@ -0,0 +1,371 @@
+use super::Loader;
+use crate::{LoadError, LoadingResult, RemoteDocument};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use iref::{Iri, IriBuf};
+use json_syntax::{Print, Value};
+use sha2::{Digest, Sha256, Sha384, Sha512};
+use std::collections::HashMap;
+use std::fmt::Write;
+
+/// * [`IntegrityLoader`]: verifies the documents loaded by the inner loader
+///   against pinned digests.
+///
+/// Just like [Subresource Integrity](https://www.w3.org/TR/SRI/) (SRI), this
+/// protects against the substitution of remote documents (typically
+/// contexts used to verify signatures): loading a pinned IRI fails if the
+/// digest of the loaded document differs from the expected one.
+///
+/// Digests are pinned with [`IntegrityLoader::pin`] using SRI integrity
+/// metadata such as `sha256-<base64 digest>`, and verified against the
+/// [raw content](RemoteDocument::raw) of the loaded documents. Loading a
+/// pinned IRI fails if the inner loader does not keep the raw content of
+/// the documents. Use [`IntegrityLoader::integrity`] to compute the
+/// integrity metadata of a trusted copy.
+///
+/// Alternatively, [`IntegrityLoader::pin_canonical`] pins the digest of the
+/// canonical serialization ([JCS](https://www.rfc-editor.org/rfc/rfc8785))
+/// of the parsed document, which does not depend on its formatting and
+/// works with any loader.
+///
+/// IRIs without pinned digest are passed to the inner loader unchanged,
+/// unless [`IntegrityLoader::deny_unpinned`] is set.
+pub struct IntegrityLoader<L> {
+	loader: L,
+	pins: HashMap<IriBuf, Pin>,
+	deny_unpinned: bool,
+}
+
+/// Pinned digest.
+enum Pin {
+	/// SRI integrity metadata of the raw content.
+	Raw(String, Vec<(Algorithm, Vec<u8>)>),
+
+	/// Hexadecimal SHA-256 digest of the canonical serialization.
+	Canonical(String),
+}
+
+/// Hash algorithm supported by SRI, from the weakest to the strongest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Algorithm {
+	Sha256,
+	Sha384,
+	Sha512,
+}
+
+impl Algorithm {
+	fn from_name(name: &str) -> Option<Self> {
+		match name {
+			"sha256" => Some(Self::Sha256),
+			"sha384" => Some(Self::Sha384),
+			"sha512" => Some(Self::Sha512),
+			_ => None,
+		}
+	}
+
+	fn name(&self) -> &'static str {
+		match self {
+			Self::Sha256 => "sha256",
+			Self::Sha384 => "sha384",
+			Self::Sha512 => "sha512",
+		}
+	}
+
+	fn digest(&self, bytes: &[u8]) -> Vec<u8> {
+		match self {
+			Self::Sha256 => Sha256::digest(bytes).to_vec(),
+			Self::Sha384 => Sha384::digest(bytes).to_vec(),
+			Self::Sha512 => Sha512::digest(bytes).to_vec(),
+		}
+	}
+}
+
+/// Parses SRI integrity metadata.
+///
+/// Following the SRI specification, tokens using an unknown algorithm or
+/// an invalid encoding are ignored, as well as options (`?...`).
+fn parse_integrity(integrity: &str) -> Result<Vec<(Algorithm, Vec<u8>)>, InvalidIntegrity> {
+	let digests: Vec<_> = integrity
+		.split_ascii_whitespace()
+		.filter_map(|token| {
+			let (algorithm, digest) = token.split_once('-')?;
+			let digest = digest.split_once('?').map_or(digest, |(digest, _)| digest);
+			Some((
+				Algorithm::from_name(algorithm)?,
+				BASE64.decode(digest).ok()?,
+			))
+		})
+		.collect();
+
+	if digests.is_empty() {
+		Err(InvalidIntegrity(integrity.to_owned()))
+	} else {
+		Ok(digests)
+	}
+}
+
+impl<L> IntegrityLoader<L> {
+	/// Wraps the given loader, without any pinned digest.
+	pub fn new(loader: L) -> Self {
+		Self {
+			loader,
+			pins: HashMap::new(),
+			deny_unpinned: false,
+		}
+	}
+
+	/// Pins the raw content of the document behind `url` to the given SRI
+	/// integrity metadata (e.g. `sha256-<base64 digest>`).
+	///
+	/// The `sha256`, `sha384` and `sha512` algorithms are supported. If
+	/// several digests are given, only the ones using the strongest
+	/// algorithm are considered, and the document must match one of them.
+	pub fn pin(&mut self, url: IriBuf, integrity: &str) -> Result<(), InvalidIntegrity> {
+		let digests = parse_integrity(integrity)?;
+		self.pins
+			.insert(url, Pin::Raw(integrity.to_owned(), digests));
+		Ok(())
+	}
+
+	/// Returns this loader with the given pinned integrity metadata.
+	///
+	/// See [`Self::pin`].
+	pub fn with_pin(mut self, url: IriBuf, integrity: &str) -> Result<Self, InvalidIntegrity> {
+		self.pin(url, integrity)?;
+		Ok(self)
+	}
+
+	/// Pins the document behind `url` to the given hexadecimal SHA-256
+	/// digest of its canonical serialization.
+	///
+	/// See [`Self::canonical_digest`].
+	pub fn pin_canonical(&mut self, url: IriBuf, digest: &str) {
+		self.pins
+			.insert(url, Pin::Canonical(digest.to_ascii_lowercase()));
+	}
+
+	/// Returns this loader with the given pinned canonical digest.
+	///
+	/// See [`Self::pin_canonical`].
+	pub fn with_canonical_pin(mut self, url: IriBuf, digest: &str) -> Self {
+		self.pin_canonical(url, digest);
+		self
+	}
+
+	/// Returns this loader refusing to load IRIs without pinned digest.
+	pub fn deny_unpinned(self) -> Self {
+		Self {
+			deny_unpinned: true,
+			..self
+		}
+	}
+
+	/// Returns the inner loader.
+	pub fn into_inner(self) -> L {
+		self.loader
+	}
+
+	/// Computes the SRI integrity metadata (`sha256-<base64 digest>`) of the
+	/// given raw content.
+	pub fn integrity(raw: &[u8]) -> String {
+		integrity(Algorithm::Sha256, raw)
+	}
+
+	/// Computes the hexadecimal SHA-256 digest of the canonical serialization
+	/// of the given document.
+	pub fn canonical_digest(document: &Value) -> String {
+		let mut document = document.clone();
+		document.canonicalize();
+
+		let mut digest = String::with_capacity(64);
+		for byte in Sha256::digest(document.compact_print().to_string().as_bytes()) {
+			write!(digest, "{byte:02x}").unwrap()
+		}
+
+		digest
+	}
+}
+
+fn integrity(algorithm: Algorithm, raw: &[u8]) -> String {
+	format!(
+		"{}-{}",
+		algorithm.name(),
+		BASE64.encode(algorithm.digest(raw))
+	)
+}
+
+impl Pin {
+	/// Verifies the given document against this pin.
+	fn verify(&self, document: &RemoteDocument) -> Result<(), Error> {
+		match self {
+			Self::Raw(expected, digests) => {
+				let raw = document.raw().ok_or(Error::MissingRawContent)?;
+
+				// Only the strongest algorithm is considered.
+				let algorithm = digests.iter().map(|(a, _)| *a).max().unwrap();
+				let found = algorithm.digest(raw);
+				if digests
+					.iter()
+					.any(|(a, digest)| *a == algorithm && *digest == found)
+				{
+					Ok(())
+				} else {
+					Err(Error::DigestMismatch {
+						expected: expected.clone(),
+						found: integrity(algorithm, raw),
+					})
+				}
+			}
+			Self::Canonical(expected) => {
+				let found = IntegrityLoader::<()>::canonical_digest(document.document());
+				if *expected == found {
+					Ok(())
+				} else {
+					Err(Error::DigestMismatch {
+						expected: expected.clone(),
+						found,
+					})
+				}
+			}
+		}
+	}
+}
+
+impl<L: Loader> Loader for IntegrityLoader<L> {
+	async fn load(&self, url: &Iri) -> LoadingResult<IriBuf> {
+		match self.pins.get(url) {
+			Some(pin) => {
+				let document = self.loader.load(url).await?;
+				pin.verify(&document)
+					.map_err(|e| LoadError::new(url.to_owned(), e))?;
+				Ok(document)
+			}
+			None if self.deny_unpinned => Err(LoadError::new(url.to_owned(), Error::Unpinned)),
+			None => self.loader.load(url).await,
+		}
+	}
+}
+
+/// Integrity verification error.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+	#[error("digest mismatch: expected `{expected}`, found `{found}`")]
+	DigestMismatch { expected: String, found: String },
+
+	#[error("the raw content of the document is not available")]
+	MissingRawContent,
+
+	#[error("no digest is pinned for this document")]
+	Unpinned,
+}
+
+/// Invalid SRI integrity metadata.
+#[derive(Debug, thiserror::Error)]
+#[error("invalid integrity metadata `{0}`")]
+pub struct InvalidIntegrity(pub String);
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::StaticLoader;
+
+	const CONTEXT: &str = r#"{ "@context": { "name": "https://schema.org/name" } }"#;
+
+	fn url() -> IriBuf {
+		IriBuf::new("https://example.com/context.jsonld".to_owned()).unwrap()
+	}
+
+	fn other() -> IriBuf {
+		IriBuf::new("https://example.com/other.jsonld".to_owned()).unwrap()
+	}
+
+	fn static_loader(context: &'static str) -> StaticLoader {
+		let mut loader = StaticLoader::new();
+		loader.insert(url(), context);
+		loader.insert(other(), context);
+		loader
+	}
+
+	#[test]
+	fn pinned_integrity() {
+		let integrity = IntegrityLoader::<()>::integrity(CONTEXT.as_bytes());
+		assert!(integrity.starts_with("sha256-"));
+
+		let loader = IntegrityLoader::new(static_loader(CONTEXT))
+			.with_pin(url(), &integrity)
+			.unwrap();
+		assert!(futures::executor::block_on(loader.load(&url())).is_ok());
+		assert!(futures::executor::block_on(loader.load(&other())).is_ok());
+
+		let loader = loader.deny_unpinned();
+		assert!(futures::executor::block_on(loader.load(&other())).is_err());
+
+		// The raw content is verified, not the parsed document.
+		let reformatted = r#"{"@context":{"name":"https://schema.org/name"}}"#;
+		let loader = IntegrityLoader::new(static_loader(reformatted))
+			.with_pin(url(), &integrity)
+			.unwrap();
+		assert!(futures::executor::block_on(loader.load(&url())).is_err())
+	}
+
+	#[test]
+	fn strongest_algorithm() {
+		let sha256 = integrity(Algorithm::Sha256, CONTEXT.as_bytes());
+		let sha384 = integrity(Algorithm::Sha384, CONTEXT.as_bytes());
+		let wrong = integrity(Algorithm::Sha512, b"{}");
+
+		// Unknown algorithms and options are ignored.
+		let metadata = format!("md5-AAAA {sha256} {sha384}?foo");
+		let loader = IntegrityLoader::new(static_loader(CONTEXT))
+			.with_pin(url(), &metadata)
+			.unwrap();
+		assert!(futures::executor::block_on(loader.load(&url())).is_ok());
+
+		// Only the strongest algorithm counts.
+		let metadata = format!("{sha256} {wrong}");
+		let loader = IntegrityLoader::new(static_loader(CONTEXT))
+			.with_pin(url(), &metadata)
+			.unwrap();
+		assert!(futures::executor::block_on(loader.load(&url())).is_err());
+
+		assert!(IntegrityLoader::new(static_loader(CONTEXT))
+			.with_pin(url(), "md5-AAAA")
+			.is_err())
+	}
+
+	#[test]
+	fn missing_raw_content() {
+		let context = json_syntax::json!({ "@context": { "name": "https://schema.org/name" } });
+		let mut documents = HashMap::new();
+		documents.insert(url(), RemoteDocument::new(Some(url()), None, context));
+
+		let integrity = IntegrityLoader::<()>::integrity(CONTEXT.as_bytes());
+		let loader = IntegrityLoader::new(documents)
+			.with_pin(url(), &integrity)
+			.unwrap();
+		assert!(futures::executor::block_on(loader.load(&url())).is_err())
+	}
+
+	#[test]
+	fn pinned_canonical_digest() {
+		let context = json_syntax::json!({ "@context": { "name": "https://schema.org/name" } });
+		let digest = IntegrityLoader::<()>::canonical_digest(&context);
+
+		// The canonical digest does not depend on the formatting.
+		let reformatted = r#"{"@context":{"name":"https://schema.org/name"}}"#;
+		let loader =
+			IntegrityLoader::new(static_loader(reformatted)).with_canonical_pin(url(), &digest);
+		assert!(futures::executor::block_on(loader.load(&url())).is_ok());
+
+		let mut documents = HashMap::new();
+		documents.insert(
+			url(),
+			RemoteDocument::new(
+				Some(url()),
+				None,
+				json_syntax::json!({ "@context": { "name": "https://example.com/name" } }),
+			),
+		);
+		let loader = IntegrityLoader::new(documents).with_canonical_pin(url(), &digest);
+		assert!(futures::executor::block_on(loader.load(&url())).is_err())
+	}
+}
@@ -3,16 +3,36 @@ use iref::{Iri, IriBuf};
 use mime::Mime;
 use rdf_types::vocabulary::{IriVocabulary, IriVocabularyMut};
 use static_iref::iri;
-use std::{borrow::Cow, hash::Hash};
+use std::{borrow::Cow, hash::Hash, sync::Arc};
 
 pub mod chain;
+#[cfg(feature = "loaders")]
+pub mod fixture;
+#[cfg(feature = "loaders")]
+pub mod fragment;
 pub mod fs;
+#[cfg(feature = "loaders")]
+pub mod integrity;
 pub mod map;
 pub mod none;
+#[cfg(feature = "loaders")]
+pub mod snapshot;
+#[cfg(feature = "loaders")]
+pub mod static_;
 
 pub use chain::ChainLoader;
+#[cfg(feature = "loaders")]
+pub use fixture::{RecordingLoader, ReplayLoader};
+#[cfg(feature = "loaders")]
+pub use fragment::FragmentLoader;
 pub use fs::FsLoader;
+#[cfg(feature = "loaders")]
+pub use integrity::IntegrityLoader;
 pub use none::NoLoader;
+#[cfg(feature = "loaders")]
+pub use snapshot::{Snapshot, SnapshotLoader};
+#[cfg(feature = "loaders")]
+pub use static_::StaticLoader;
 
 #[cfg(feature = "reqwest")]
 pub mod reqwest;
@@ -175,6 +195,10 @@ pub struct RemoteDocument<I = IriBuf, T = json_syntax::Value> {
 
 	/// The retrieved document.
 	pub document: T,
+
+	/// Raw content of the retrieved document, as received (for instance the
+	/// HTTP response body), if the loader kept it.
+	raw: Option<Arc<[u8]>>,
 }
 
 pub type RemoteContext<I = IriBuf> = RemoteDocument<I, json_ld_syntax::context::Context>;
@@ -216,6 +240,15 @@ impl<I, T> RemoteDocument<I, T> {
 			context_url,
 			profile,
 			document,
+			raw: None,
+		}
+	}
+
+	/// Returns this document with the given raw content.
+	pub fn with_raw(self, raw: impl Into<Arc<[u8]>>) -> Self {
+		Self {
+			raw: Some(raw.into()),
+			..self
 		}
 	}
 
@@ -227,6 +260,7 @@ impl<I, T> RemoteDocument<I, T> {
 			context_url: self.context_url,
 			profile: self.profile,
 			document: f(self.document),
+			raw: self.raw,
 		}
 	}
 
@@ -238,6 +272,7 @@ impl<I, T> RemoteDocument<I, T> {
 			context_url: self.context_url,
 			profile: self.profile,
 			document: f(self.document)?,
+			raw: self.raw,
 		})
 	}
 
@@ -256,6 +291,7 @@ impl<I, T> RemoteDocument<I, T> {
 				.map(|p| p.map_iri(&mut f))
 				.collect(),
 			document: self.document,
+			raw: self.raw,
 		}
 	}
 
@@ -289,6 +325,12 @@ impl<I, T> RemoteDocument<I, T> {
 		&self.document
 	}
 
+	/// Returns the raw content of the document, as received, if the loader
+	/// kept it.
+	pub fn raw(&self) -> Option<&[u8]> {
+		self.raw.as_deref()
+	}
+
 	/// Returns a mutable reference to the content of the document.
 	pub fn document_mut(&mut self) -> &mut T {
 		&mut self.document
@@ -447,9 +489,18 @@ impl LoadError {
 ///   - [`FsLoader`] that redirecting registered IRI prefixes to a local
 ///     directory on the file system. This also avoids network calls. The loaded
 ///     content can be trusted as long as the file system is trusted.
+///   - [`StaticLoader`] serving documents embedded in the binary, typically
+///     generated at compile time by the `json-ld-build` crate.
 ///   - `ReqwestLoader` actually downloading the remote documents using the
 ///     [`reqwest`](https://crates.io/crates/reqwest) library.
 ///     This requires the `reqwest` feature to be enabled.
+///
+/// Loaders can be combined with [`ChainLoader`], wrapped in a
+/// [`FragmentLoader`] to select the objects targeted by fragment identifiers,
+/// in an [`IntegrityLoader`] to verify the loaded documents against pinned
+/// digests, or in a [`SnapshotLoader`] to record them for later replay.
+/// For tests, a [`RecordingLoader`] captures the loaded documents into a
+/// fixture file that a [`ReplayLoader`] serves back without network access.
 pub trait Loader {
 	/// Loads the document behind the given IRI, using the given vocabulary.
 	#[allow(async_fn_in_trait)]
@@ -12,7 +12,7 @@ use reqwest::{
 	StatusCode,
 };
 use reqwest_middleware::ClientWithMiddleware;
-use std::string::FromUtf8Error;
+use std::{string::FromUtf8Error, time::Duration};
 
 mod content_type;
 mod link;
@@ -39,7 +39,26 @@ pub struct Options {
 	pub max_redirections: usize,
 
 	/// HTTP client.
+	///
+	/// See [`Options::with_client`].
 	pub client: ClientWithMiddleware,
+
+	/// Timeout of each HTTP request, from the moment the request starts
+	/// connecting until the response body has finished.
+	///
+	/// Defaults to `None` (no timeout other than the one configured on the
+	/// [`client`](Self::client)).
+	pub timeout: Option<Duration>,
+
+	/// Retry policy applied on network failures.
+	///
+	/// Defaults to [`RetryPolicy::none`].
+	pub retry: RetryPolicy,
+
+	/// Maximum size of a response body, in bytes.
+	///
+	/// Defaults to `None` (no limit).
+	pub max_body_size: Option<usize>,
 }
 
 impl Default for Options {
@@ -48,10 +67,89 @@ impl Default for Options {
 			request_profile: Vec::new(),
 			max_redirections: 8,
 			client: reqwest_middleware::ClientBuilder::new(reqwest::Client::default()).build(),
+			timeout: None,
+			retry: RetryPolicy::none(),
+			max_body_size: None,
+		}
+	}
+}
+
+impl Options {
+	/// Sets the HTTP client used to fetch documents.
+	///
+	/// The client can be a preconfigured [`reqwest::Client`], for instance to
+	/// route requests through a proxy, use client certificates (mTLS) or a
+	/// custom DNS resolver:
+	///
+	/// ```no_run
+	/// # use json_ld_core::loader::reqwest::{Options, ReqwestLoader};
+	/// let client = reqwest::Client::builder()
+	///     .proxy(reqwest::Proxy::all("http://proxy.example.com:8080").unwrap())
+	///     .build()
+	///     .unwrap();
+	///
+	/// let loader = ReqwestLoader::new_using(Options::default().with_client(client));
+	/// ```
+	///
+	/// It can also be a [`ClientWithMiddleware`] wrapping a client into custom
+	/// [`reqwest_middleware::Middleware`] services (authentication, caching,
+	/// tracing, etc.).
+	pub fn with_client(self, client: impl Into<ClientWithMiddleware>) -> Self {
+		Self {
+			client: client.into(),
+			..self
 		}
 	}
 }
 
+/// Retry policy with exponential backoff.
+///
+/// A request is retried when it fails because of a network failure (see
+/// [`Error::is_network_failure`]), waiting `initial_backoff` before the first
+/// retry, and twice as long before each subsequent retry, up to
+/// `max_backoff`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+	/// Maximum number of retries.
+	pub max_retries: u32,
+
+	/// Delay before the first retry.
+	pub initial_backoff: Duration,
+
+	/// Maximum delay between two attempts.
+	pub max_backoff: Duration,
+}
+
+impl RetryPolicy {
+	/// Policy that never retries.
+	pub fn none() -> Self {
+		Self::new(0)
+	}
+
+	/// Retries at most `max_retries` times, starting with a 500ms delay and
+	/// up to 10s.
+	pub fn new(max_retries: u32) -> Self {
+		Self {
+			max_retries,
+			initial_backoff: Duration::from_millis(500),
+			max_backoff: Duration::from_secs(10),
+		}
+	}
+
+	/// Returns the delay to wait before the given retry (starting from 0).
+	pub fn backoff(&self, retry: u32) -> Duration {
+		self.initial_backoff
+			.saturating_mul(2u32.saturating_pow(retry))
+			.min(self.max_backoff)
+	}
+}
+
+impl Default for RetryPolicy {
+	fn default() -> Self {
+		Self::none()
+	}
+}
+
 /// Loading error.
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
@@ -72,6 +170,35 @@ pub enum Error {
 
 	#[error("JSON parse error: {0}")]
 	Parse(json_syntax::parse::Error<std::io::Error>),
+
+	#[error("response body exceeds the maximum size of {0} bytes")]
+	BodyTooLarge(usize),
+}
+
+impl Error {
+	/// Checks if this error is caused by a network failure (connection
+	/// failure, timeout, or server error), in which case loading the document
+	/// again later may succeed.
+	///
+	/// Other errors mean that the document could not be loaded as a JSON-LD
+	/// document (the `loading document failed` error of the specification).
+	pub fn is_network_failure(&self) -> bool {
+		match self {
+			Self::Reqwest(reqwest_middleware::Error::Reqwest(e)) => {
+				e.is_timeout() || e.is_connect() || e.is_request() || e.is_body()
+			}
+			Self::QueryFailed(code) => {
+				code.is_server_error() || *code == StatusCode::TOO_MANY_REQUESTS
+			}
+			_ => false,
+		}
+	}
+
+	/// Returns the `reqwest` loader error that caused the given loading
+	/// error, if any.
+	pub fn from_load_error(error: &LoadError) -> Option<&Self> {
+		error.cause.downcast_ref()
+	}
 }
 
 /// `reqwest`-based loader.
@@ -99,6 +226,14 @@ impl ReqwestLoader {
 		Self::default()
 	}
 
+	/// Creates a new loader using the given HTTP client, and the default
+	/// options otherwise.
+	///
+	/// See [`Options::with_client`].
+	pub fn with_client(client: impl Into<ClientWithMiddleware>) -> Self {
+		Self::new_using(Options::default().with_client(client))
+	}
+
 	/// Creates a new leader with the given options.
 	pub fn new_using(options: Options) -> Self {
 		let mut json_ld_params = String::new();
@@ -130,6 +265,68 @@ impl ReqwestLoader {
 	}
 }
 
+impl ReqwestLoader {
+	/// Sends a `GET` request to the given URL, retrying on network failures
+	/// according to the retry policy.
+	async fn send(&self, url: &Iri) -> Result<reqwest::Response, Error> {
+		let mut retry = 0;
+		loop {
+			let mut request = self
+				.options
+				.client
+				.get(url.as_str())
+				.header(ACCEPT, &self.accept_header);
+
+			if let Some(timeout) = self.options.timeout {
+				request = request.timeout(timeout)
+			}
+
+			let error = match request.send().await {
+				Ok(response) if response.status().is_server_error() => {
+					Error::QueryFailed(response.status())
+				}
+				Ok(response) => break Ok(response),
+				Err(e) => Error::Reqwest(e),
+			};
+
+			if retry >= self.options.retry.max_retries || !error.is_network_failure() {
+				break Err(error);
+			}
+
+			let backoff = self.options.retry.backoff(retry);
+			log::debug!("{error}, retrying {url} in {backoff:?}");
+			tokio::time::sleep(backoff).await;
+			retry += 1
+		}
+	}
+
+	/// Reads the response body, enforcing the maximum body size.
+	async fn read_body(&self, mut response: reqwest::Response) -> Result<Vec<u8>, Error> {
+		let max = self.options.max_body_size.unwrap_or(usize::MAX);
+		if response
+			.content_length()
+			.is_some_and(|len| len > max as u64)
+		{
+			return Err(Error::BodyTooLarge(max));
+		}
+
+		let mut bytes = Vec::new();
+		while let Some(chunk) = response
+			.chunk()
+			.await
+			.map_err(|e| Error::Reqwest(e.into()))?
+		{
+			if bytes.len() + chunk.len() > max {
+				return Err(Error::BodyTooLarge(max));
+			}
+
+			bytes.extend_from_slice(&chunk)
+		}
+
+		Ok(bytes)
+	}
+}
+
 /// HTTP body parse error.
 #[derive(Debug, thiserror::Error)]
 pub enum ParseError {
@@ -152,14 +349,8 @@ impl Loader for ReqwestLoader {
 			}
 
 			log::debug!("downloading: {}", url);
-			let request = self
-				.options
-				.client
-				.get(url.as_str())
-				.header(ACCEPT, &self.accept_header);
-
-			let response = request
-				.send()
+			let response = self
+				.send(&url)
 				.await
 				.map_err(|e| LoadError::new(url.clone(), e))?;
 
@@ -206,9 +397,10 @@ impl Loader for ReqwestLoader {
 								}
 							}
 
-							let bytes = response.bytes().await.map_err(|e| {
-								LoadError::new(url.clone(), Error::Reqwest(e.into()))
-							})?;
+							let bytes = self
+								.read_body(response)
+								.await
+								.map_err(|e| LoadError::new(url.clone(), e))?;
 
 							let decoder = utf8_decode::Decoder::new(bytes.iter().copied());
 							let (document, _) = json_syntax::Value::parse_utf8(decoder)
@@ -220,7 +412,8 @@ impl Loader for ReqwestLoader {
 								context_url,
 								profile,
 								document,
-							));
+							)
+							.with_raw(bytes));
 						}
 						None => {
 							log::debug!("no valid media type found");
@@ -246,3 +439,18 @@ impl Loader for ReqwestLoader {
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn exponential_backoff() {
+		let policy = RetryPolicy::new(8);
+		assert_eq!(policy.backoff(0), Duration::from_millis(500));
+		assert_eq!(policy.backoff(2), Duration::from_secs(2));
+		assert_eq!(policy.backoff(7), Duration::from_secs(10));
+		assert!(Error::QueryFailed(StatusCode::BAD_GATEWAY).is_network_failure());
+		assert!(!Error::QueryFailed(StatusCode::NOT_FOUND).is_network_failure())
+	}
+}
@@ -0,0 +1,254 @@
+use super::{Loader, RemoteDocument};
+use crate::LoadingResult;
+use iref::{Iri, IriBuf};
+use json_syntax::Value;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+/// * [`SnapshotLoader`]: records every document loaded by the inner loader
+///   into a [`Snapshot`].
+///
+/// The snapshot can later be used as a loader to replay the processing
+/// without accessing the original documents, for instance to reproduce an
+/// expansion for an audit or a bug report. Failed loads are not recorded.
+pub struct SnapshotLoader<L> {
+	loader: L,
+	snapshot: Mutex<Snapshot>,
+}
+
+impl<L> SnapshotLoader<L> {
+	/// Wraps the given loader, with an empty snapshot.
+	pub fn new(loader: L) -> Self {
+		Self {
+			loader,
+			snapshot: Mutex::new(Snapshot::new()),
+		}
+	}
+
+	/// Returns a copy of the documents recorded so far.
+	pub fn snapshot(&self) -> Snapshot {
+		self.lock().clone()
+	}
+
+	/// Returns the inner loader and the recorded documents.
+	pub fn into_parts(self) -> (L, Snapshot) {
+		let snapshot = self
+			.snapshot
+			.into_inner()
+			.unwrap_or_else(|e| e.into_inner());
+		(self.loader, snapshot)
+	}
+
+	fn lock(&self) -> std::sync::MutexGuard<'_, Snapshot> {
+		// A panic while holding the lock cannot leave the snapshot in an
+		// inconsistent state, so poisoning is ignored.
+		self.snapshot.lock().unwrap_or_else(|e| e.into_inner())
+	}
+}
+
+impl<L: Loader> Loader for SnapshotLoader<L> {
+	async fn load(&self, url: &Iri) -> LoadingResult<IriBuf> {
+		let document = self.loader.load(url).await?;
+		self.lock().insert(url.to_owned(), document.clone());
+		Ok(document)
+	}
+}
+
+/// Snapshot of loaded remote documents, indexed by the IRI used to load
+/// them.
+///
+/// A snapshot is itself a [`Loader`] serving the recorded documents, and can
+/// be bundled into a single JSON document with [`Snapshot::to_json`].
+#[derive(Debug, Default, Clone)]
+pub struct Snapshot {
+	documents: BTreeMap<IriBuf, RemoteDocument>,
+}
+
+impl Snapshot {
+	/// Creates an empty snapshot.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Returns the number of recorded documents.
+	pub fn len(&self) -> usize {
+		self.documents.len()
+	}
+
+	/// Checks if the snapshot is empty.
+	pub fn is_empty(&self) -> bool {
+		self.documents.is_empty()
+	}
+
+	/// Returns the document recorded for the given IRI, if any.
+	pub fn get(&self, url: &Iri) -> Option<&RemoteDocument> {
+		self.documents.get(url)
+	}
+
+	/// Records the document loaded from the given IRI.
+	pub fn insert(&mut self, url: IriBuf, document: RemoteDocument) -> Option<RemoteDocument> {
+		self.documents.insert(url, document)
+	}
+
+	/// Returns an iterator over the recorded documents.
+	pub fn iter(&self) -> std::collections::btree_map::Iter<'_, IriBuf, RemoteDocument> {
+		self.documents.iter()
+	}
+
+	/// Bundles the snapshot into a JSON object.
+	///
+	/// Each entry maps a loaded IRI to an object with the `document` and,
+	/// if any, the final `url`, `contentType` and `contextUrl` of the
+	/// remote document.
+	pub fn to_json(&self) -> Value {
+		let mut bundle = json_syntax::Object::new();
+
+		for (url, document) in &self.documents {
+			let mut entry = json_syntax::Object::new();
+
+			if let Some(final_url) = document.url() {
+				entry.insert("url".into(), Value::String(final_url.as_str().into()));
+			}
+
+			if let Some(content_type) = document.content_type() {
+				entry.insert(
+					"contentType".into(),
+					Value::String(content_type.as_ref().into()),
+				);
+			}
+
+			if let Some(context_url) = document.context_url() {
+				entry.insert(
+					"contextUrl".into(),
+					Value::String(context_url.as_str().into()),
+				);
+			}
+
+			entry.insert("document".into(), document.document().clone());
+			bundle.insert(url.as_str().into(), Value::Object(entry));
+		}
+
+		Value::Object(bundle)
+	}
+
+	/// Reads a snapshot bundled with [`Snapshot::to_json`].
+	pub fn from_json(json: &Value) -> Result<Self, InvalidSnapshot> {
+		let bundle = json.as_object().ok_or(InvalidSnapshot::NotAnObject)?;
+		let mut result = Self::new();
+
+		for bundle_entry in bundle {
+			let key = bundle_entry.key.as_str();
+			let url = parse_iri(key)?;
+			let entry = bundle_entry
+				.value
+				.as_object()
+				.ok_or_else(|| InvalidSnapshot::InvalidEntry(key.to_owned()))?;
+
+			let string = |name: &str| -> Result<Option<&str>, InvalidSnapshot> {
+				match entry.get_unique(name) {
+					Ok(None) => Ok(None),
+					Ok(Some(value)) => value
+						.as_str()
+						.map(Some)
+						.ok_or_else(|| InvalidSnapshot::InvalidEntry(key.to_owned())),
+					Err(_) => Err(InvalidSnapshot::InvalidEntry(key.to_owned())),
+				}
+			};
+
+			let final_url = string("url")?.map(parse_iri).transpose()?;
+			let context_url = string("contextUrl")?.map(parse_iri).transpose()?;
+			let content_type = string("contentType")?
+				.map(|s| {
+					s.parse()
+						.map_err(|_| InvalidSnapshot::InvalidEntry(key.to_owned()))
+				})
+				.transpose()?;
+			let document = match entry.get_unique("document") {
+				Ok(Some(document)) => document.clone(),
+				_ => return Err(InvalidSnapshot::InvalidEntry(key.to_owned())),
+			};
+
+			result.insert(
+				url,
+				RemoteDocument::new_full(
+					final_url,
+					content_type,
+					context_url,
+					Default::default(),
+					document,
+				),
+			);
+		}
+
+		Ok(result)
+	}
+}
+
+fn parse_iri(s: &str) -> Result<IriBuf, InvalidSnapshot> {
+	IriBuf::new(s.to_owned()).map_err(|e| InvalidSnapshot::InvalidIri(e.0))
+}
+
+impl Loader for Snapshot {
+	async fn load(&self, url: &Iri) -> LoadingResult<IriBuf> {
+		self.documents.load(url).await
+	}
+}
+
+impl<'a> IntoIterator for &'a Snapshot {
+	type Item = (&'a IriBuf, &'a RemoteDocument);
+	type IntoIter = std::collections::btree_map::Iter<'a, IriBuf, RemoteDocument>;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.iter()
+	}
+}
+
+/// Invalid snapshot bundle error.
+#[derive(Debug, thiserror::Error)]
+pub enum InvalidSnapshot {
+	#[error("snapshot bundle is not a JSON object")]
+	NotAnObject,
+
+	#[error("invalid IRI `{0}` in snapshot bundle")]
+	InvalidIri(String),
+
+	#[error("invalid snapshot entry for `{0}`")]
+	InvalidEntry(String),
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::collections::HashMap;
+
+	#[test]
+	fn record_and_replay() {
+		let url = IriBuf::new("https://example.com/context.jsonld".to_owned()).unwrap();
+		let missing = Iri::new("https://example.com/missing.jsonld").unwrap();
+		let mut documents = HashMap::new();
+		documents.insert(
+			url.clone(),
+			RemoteDocument::new(
+				Some(url.clone()),
+				Some("application/ld+json".parse().unwrap()),
+				json_syntax::json!({ "@context": { "name": "https://schema.org/name" } }),
+			),
+		);
+
+		let loader = SnapshotLoader::new(documents);
+		assert!(futures::executor::block_on(loader.load(&url)).is_ok());
+		assert!(futures::executor::block_on(loader.load(missing)).is_err());
+
+		let (documents, snapshot) = loader.into_parts();
+		assert_eq!(snapshot.len(), 1);
+
+		let replayed = Snapshot::from_json(&snapshot.to_json()).unwrap();
+		let document = futures::executor::block_on(replayed.load(&url)).unwrap();
+		assert_eq!(document.document(), documents[&url].document());
+		assert_eq!(
+			document.content_type().unwrap().as_ref(),
+			"application/ld+json"
+		);
+		assert!(futures::executor::block_on(replayed.load(missing)).is_err())
+	}
+}
@@ -0,0 +1,71 @@
+use super::{Loader, RemoteDocument};
+use crate::{LoadError, LoadingResult};
+use iref::{Iri, IriBuf};
+use json_syntax::Parse;
+use std::collections::HashMap;
+
+/// Loading error.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+	/// No document is registered for the given IRI.
+	#[error("document not found")]
+	NotFound,
+
+	/// Parse error.
+	#[error("parse error: {0}")]
+	Parse(json_syntax::parse::Error),
+}
+
+/// Static loader.
+///
+/// Serves documents embedded in the binary (e.g. with [`include_str!`]).
+/// This is the loader generated by the `json-ld-build` crate from a build
+/// script.
+///
+/// Documents are parsed each time they are loaded.
+#[derive(Debug, Default, Clone)]
+pub struct StaticLoader {
+	documents: HashMap<IriBuf, &'static str>,
+}
+
+impl StaticLoader {
+	/// Creates a new empty static loader.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Registers the given JSON `content` for the given `url`.
+	///
+	/// Returns the content previously registered for `url`, if any.
+	pub fn insert(&mut self, url: IriBuf, content: &'static str) -> Option<&'static str> {
+		self.documents.insert(url, content)
+	}
+
+	/// Checks if a document is registered for the given `url`.
+	pub fn contains(&self, url: &Iri) -> bool {
+		self.documents.contains_key(url)
+	}
+
+	/// Returns an iterator over the registered IRIs.
+	pub fn iris(&self) -> impl Iterator<Item = &Iri> {
+		self.documents.keys().map(IriBuf::as_iri)
+	}
+}
+
+impl Loader for StaticLoader {
+	async fn load(&self, url: &Iri) -> LoadingResult<IriBuf> {
+		match self.documents.get(url) {
+			Some(content) => {
+				let (doc, _) = json_syntax::Value::parse_str(content)
+					.map_err(|e| LoadError::new(url.to_owned(), Error::Parse(e)))?;
+				Ok(RemoteDocument::new(
+					Some(url.to_owned()),
+					Some("application/ld+json".parse().unwrap()),
+					doc,
+				)
+				.with_raw(content.as_bytes()))
+			}
+			None => Err(LoadError::new(url.to_owned(), Error::NotFound)),
+		}
+	}
+}
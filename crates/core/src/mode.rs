@@ -1,3 +1,4 @@
+use json_ld_syntax::context::definition::Version;
 use std::convert::TryFrom;
 use std::fmt;
 
@@ -24,6 +25,14 @@ impl ProcessingMode {
 			ProcessingMode::JsonLd1_1 => "json-ld-1.1",
 		}
 	}
+
+	/// Returns the JSON-LD version targeted by the processing mode.
+	pub fn version(&self) -> Version {
+		match self {
+			ProcessingMode::JsonLd1_0 => Version::V1_0,
+			ProcessingMode::JsonLd1_1 => Version::V1_1,
+		}
+	}
 }
 
 impl<'a> TryFrom<&'a str> for ProcessingMode {
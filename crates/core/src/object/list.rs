@@ -1,4 +1,4 @@
-use super::{Any, InvalidExpandedJson, MappedEq};
+use super::{Any, DeduplicateOptions, InvalidExpandedJson, MappedEq};
 use crate::{Id, IndexedObject, Relabel, TryFromJson};
 use contextual::WithContext;
 use educe::Educe;
@@ -136,6 +136,49 @@ impl<T, B> Relabel<T, B> for List<T, B> {
 }
 
 impl<T: Eq + Hash, B: Eq + Hash> List<T, B> {
+	/// Removes duplicate objects under every (reverse) property of the list
+	/// items.
+	///
+	/// If `options.lists` is set, duplicate items are also removed from the
+	/// list, keeping the first occurrence. Returns the number of removed
+	/// objects.
+	pub fn deduplicate_with(&mut self, options: DeduplicateOptions) -> usize {
+		let mut count = 0;
+		for object in &mut self.entry {
+			count += object.deduplicate_with(options)
+		}
+
+		if options.lists {
+			count += crate::utils::deduplicate(&mut self.entry)
+		}
+
+		count
+	}
+
+	/// Removes every invalid identifier from the list items.
+	///
+	/// Items that are nodes with an invalid identifier are removed. See
+	/// [`Node::remove_invalid_ids`](crate::Node::remove_invalid_ids).
+	/// Returns the number of removed identifiers.
+	pub fn remove_invalid_ids(&mut self) -> usize
+	where
+		T: Clone,
+		B: Clone,
+	{
+		let mut count = 0;
+		self.entry.retain_mut(|object| {
+			if object.has_invalid_id() {
+				count += 1;
+				false
+			} else {
+				count += object.remove_invalid_ids();
+				true
+			}
+		});
+
+		count
+	}
+
 	pub(crate) fn try_from_json_object_in(
 		vocabulary: &mut impl VocabularyMut<Iri = T, BlankId = B>,
 		object: json_syntax::Object,
@@ -13,6 +13,8 @@ use std::hash::Hash;
 pub mod list;
 mod mapped_eq;
 pub mod node;
+mod stripped;
+pub(crate) mod transform;
 mod typ;
 pub mod value;
 
@@ -82,6 +84,18 @@ pub enum Ref<'a, T, B> {
 	List(&'a List<T, B>),
 }
 
+/// Deduplication options.
+///
+/// See [`Object::deduplicate_with`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DeduplicateOptions {
+	/// Also removes duplicate list items.
+	///
+	/// Lists are ordered, meaning that duplicate items are significant. By
+	/// default they are preserved.
+	pub lists: bool,
+}
+
 /// Indexed object.
 pub type IndexedObject<T, B = ()> = Indexed<Object<T, B>>;
 
@@ -268,6 +282,15 @@ impl<T, B> Object<T, B> {
 		}
 	}
 
+	/// Tests if the object is a node with an invalid identifier.
+	#[inline(always)]
+	pub fn has_invalid_id(&self) -> bool {
+		match self {
+			Object::Node(n) => n.has_invalid_id(),
+			_ => false,
+		}
+	}
+
 	/// Tests if the object is a graph object (a node with a `@graph` field).
 	#[inline(always)]
 	pub fn is_graph(&self) -> bool {
@@ -392,6 +415,55 @@ impl<T, B> Object<T, B> {
 		}
 	}
 
+	/// Removes duplicate objects under every (reverse) property of this object
+	/// and its sub-objects.
+	///
+	/// Objects are compared through their stripped form, which ignores the
+	/// order of the entries of JSON literals. Duplicate list items are
+	/// preserved. Returns the number of removed objects.
+	pub fn deduplicate(&mut self) -> usize
+	where
+		T: Eq + Hash,
+		B: Eq + Hash,
+	{
+		self.deduplicate_with(DeduplicateOptions::default())
+	}
+
+	/// Removes duplicate objects under every (reverse) property of this object
+	/// and its sub-objects, using the given `options`.
+	///
+	/// Objects are compared through their stripped form, which ignores the
+	/// order of the entries of JSON literals. Returns the number of removed
+	/// objects.
+	pub fn deduplicate_with(&mut self, options: DeduplicateOptions) -> usize
+	where
+		T: Eq + Hash,
+		B: Eq + Hash,
+	{
+		match self {
+			Self::Value(_) => 0,
+			Self::Node(n) => n.deduplicate_properties_with(options),
+			Self::List(l) => l.deduplicate_with(options),
+		}
+	}
+
+	/// Removes every invalid identifier from the sub-objects of this object.
+	///
+	/// See [`Node::remove_invalid_ids`]. If this object is a node, its
+	/// identifier is left untouched (see [`Self::has_invalid_id`]). Returns
+	/// the number of removed identifiers.
+	pub fn remove_invalid_ids(&mut self) -> usize
+	where
+		T: Clone + Eq + Hash,
+		B: Clone + Eq + Hash,
+	{
+		match self {
+			Self::Value(_) => 0,
+			Self::Node(n) => n.remove_invalid_ids(),
+			Self::List(l) => l.remove_invalid_ids(),
+		}
+	}
+
 	/// Returns an iterator over the entries of JSON representation of the
 	/// object.
 	pub fn entries(&self) -> Entries<T, B> {
@@ -0,0 +1,70 @@
+use super::IndexedNode;
+use crate::{Id, IndexedObject};
+use educe::Educe;
+use iref::IriBuf;
+use rdf_types::BlankIdBuf;
+use std::hash::Hash;
+
+/// Annotations of the property values of a node object.
+pub type Annotations<T = IriBuf, B = BlankIdBuf> = Vec<Annotation<T, B>>;
+
+/// Annotation of a property value (experimental JSON-LD-star support).
+///
+/// This is the content of an `@annotation` entry found on a value object or
+/// node object appearing as the value of `property`. An annotation describes
+/// the statement asserted by this property value, and is stored on the
+/// subject node, next to its properties.
+#[allow(clippy::derived_hash_with_manual_eq)]
+#[derive(Educe, Debug, Clone, Hash)]
+#[educe(
+	PartialEq(bound = "T: Eq + Hash, B: Eq + Hash"),
+	Eq(bound = "T: Eq + Hash, B: Eq + Hash")
+)]
+pub struct Annotation<T = IriBuf, B = BlankIdBuf> {
+	/// Annotated property.
+	pub property: Id<T, B>,
+
+	/// Annotated property value.
+	pub object: IndexedObject<T, B>,
+
+	/// Annotation nodes.
+	///
+	/// These nodes have no `@id`: they are about the statement formed by
+	/// the subject node, `property` and `object`.
+	pub nodes: Vec<IndexedNode<T, B>>,
+}
+
+impl<T, B> Annotation<T, B> {
+	/// Creates a new annotation.
+	pub fn new(
+		property: Id<T, B>,
+		object: IndexedObject<T, B>,
+		nodes: Vec<IndexedNode<T, B>>,
+	) -> Self {
+		Self {
+			property,
+			object,
+			nodes,
+		}
+	}
+
+	pub(crate) fn map_ids_with<U, C>(
+		self,
+		map_iri: &mut impl FnMut(T) -> U,
+		map_id: &mut impl FnMut(Id<T, B>) -> Id<U, C>,
+	) -> Annotation<U, C>
+	where
+		U: Eq + Hash,
+		C: Eq + Hash,
+	{
+		Annotation {
+			property: map_id(self.property),
+			object: self.object.map_inner(|o| o.map_ids_with(map_iri, map_id)),
+			nodes: self
+				.nodes
+				.into_iter()
+				.map(|n| n.map_inner(|n| n.map_ids_with(map_iri, map_id)))
+				.collect(),
+		}
+	}
+}
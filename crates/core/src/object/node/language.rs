@@ -0,0 +1,143 @@
+use super::Node;
+use crate::{object::Literal, Id, IndexedObject, Object, Value};
+use std::hash::Hash;
+
+impl<T: Eq + Hash, B: Eq + Hash> Node<T, B> {
+	/// Get the object associated to the node with the given property that
+	/// best matches the given language preferences.
+	///
+	/// The `languages` preference list is given in decreasing order of
+	/// preference, like the `Accept-Language` HTTP header
+	/// (e.g. `&["fr-CA", "fr", "en"]`). Language tags are compared
+	/// case-insensitively, and `*` matches any language.
+	///
+	/// See [`Self::get_by_preference`] for how the objects are ranked.
+	pub fn get_preferred<'a, Q: ?Sized + Hash + indexmap::Equivalent<Id<T, B>>>(
+		&self,
+		prop: &Q,
+		languages: &[&str],
+	) -> Option<&IndexedObject<T, B>>
+	where
+		T: 'a,
+	{
+		self.get_by_preference(prop, languages).into_iter().next()
+	}
+
+	/// Get all the objects associated to the node with the given property,
+	/// sorted by language preference.
+	///
+	/// For each language range of `languages`, in order, objects are ranked
+	/// using the lookup scheme of [RFC 4647]: first the values tagged with
+	/// the range itself, then with the range truncated one subtag at a time
+	/// (`fr-CA`, then `fr`), and finally the values tagged with a more
+	/// specific language (`fr-BE`). Untagged strings come next, then every
+	/// other object. Objects of the same rank keep their order.
+	///
+	/// [RFC 4647]: https://www.rfc-editor.org/rfc/rfc4647#section-3.4
+	pub fn get_by_preference<'a, Q: ?Sized + Hash + indexmap::Equivalent<Id<T, B>>>(
+		&self,
+		prop: &Q,
+		languages: &[&str],
+	) -> Vec<&IndexedObject<T, B>>
+	where
+		T: 'a,
+	{
+		let mut objects: Vec<_> = self.get(prop).collect();
+		objects.sort_by_cached_key(|object| language_rank(object, languages));
+		objects
+	}
+}
+
+/// Computes the rank of the given object in the language preference order.
+fn language_rank<T, B>(object: &IndexedObject<T, B>, languages: &[&str]) -> (usize, usize) {
+	match object.inner() {
+		Object::Value(Value::LangString(s)) => match s.language() {
+			Some(tag) => languages
+				.iter()
+				.enumerate()
+				.find_map(|(i, range)| range_rank(tag.as_str(), range).map(|r| (i, r)))
+				.unwrap_or((languages.len() + 1, 0)),
+			None => (languages.len(), 0),
+		},
+		Object::Value(Value::Literal(Literal::String(_), None)) => (languages.len(), 0),
+		_ => (languages.len() + 1, 0),
+	}
+}
+
+/// Matches the given language tag against the given language range,
+/// returning the number of subtags truncated from the range to match it.
+///
+/// Tags more specific than the range rank after every truncation.
+fn range_rank(tag: &str, range: &str) -> Option<usize> {
+	if range == "*" {
+		return Some(0);
+	}
+
+	let mut truncated = range;
+	let mut rank = 0;
+	loop {
+		if tag.eq_ignore_ascii_case(truncated) {
+			return Some(rank);
+		}
+
+		match truncated.rfind('-') {
+			Some(i) => {
+				truncated = &truncated[..i];
+				// A single-character subtag (extension or private use
+				// singleton) is removed along with the subtag after it.
+				if truncated.len() > 1 && truncated.as_bytes()[truncated.len() - 2] == b'-' {
+					truncated = &truncated[..truncated.len() - 2]
+				}
+
+				rank += 1
+			}
+			None => break,
+		}
+	}
+
+	let is_more_specific = tag.len() > range.len()
+		&& tag.as_bytes()[range.len()] == b'-'
+		&& tag[..range.len()].eq_ignore_ascii_case(range);
+	if is_more_specific {
+		Some(usize::MAX)
+	} else {
+		None
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{ExpandedDocument, Id, TryFromJson};
+	use iref::IriBuf;
+	use rdf_types::BlankIdBuf;
+
+	#[test]
+	fn get_preferred() {
+		let json = json_syntax::json!([{
+			"https://schema.org/name": [
+				{ "@value": "Name" },
+				{ "@value": "Name", "@language": "en-US" },
+				{ "@value": "Nom", "@language": "fr" },
+				{ "@value": "Nom", "@language": "fr-BE" },
+				{ "@value": "Nom", "@language": "FR-CA" }
+			]
+		}]);
+		let document: ExpandedDocument<IriBuf, BlankIdBuf> =
+			ExpandedDocument::try_from_json_in(&mut (), json).unwrap();
+		let node = document.objects().iter().next().unwrap().as_node().unwrap();
+		let name = Id::iri(IriBuf::new("https://schema.org/name".to_owned()).unwrap());
+
+		let language = |languages: &[&str]| {
+			node.get_preferred(&name, languages)
+				.unwrap()
+				.language()
+				.map(|l| l.as_str().to_owned())
+		};
+
+		assert_eq!(language(&["fr-ca", "en"]).as_deref(), Some("FR-CA"));
+		assert_eq!(language(&["fr-CH", "en"]).as_deref(), Some("fr"));
+		assert_eq!(language(&["en", "fr"]).as_deref(), Some("en-US"));
+		assert_eq!(language(&["de"]), None);
+		assert_eq!(node.get_by_preference(&name, &["de"]).len(), 5);
+	}
+}
@@ -1,4 +1,4 @@
-use super::{InvalidExpandedJson, Traverse, TryFromJson, TryFromJsonObject};
+use super::{DeduplicateOptions, InvalidExpandedJson, Traverse, TryFromJson, TryFromJsonObject};
 use crate::{object, utils, Id, Indexed, IndexedObject, Object, Objects, Relabel, Term};
 use contextual::{IntoRefWithContext, WithContext};
 use educe::Educe;
@@ -9,10 +9,13 @@ use rdf_types::{BlankIdBuf, Generator, Subject, Vocabulary, VocabularyMut};
 use std::convert::TryFrom;
 use std::hash::{Hash, Hasher};
 
+pub mod annotation;
+mod language;
 pub mod multiset;
 pub mod properties;
 pub mod reverse_properties;
 
+pub use annotation::{Annotation, Annotations};
 pub use multiset::Multiset;
 pub use properties::Properties;
 pub use reverse_properties::ReverseProperties;
@@ -63,6 +66,12 @@ pub struct Node<T = IriBuf, B = BlankIdBuf> {
 	///
 	/// This is the `@reverse` field.
 	pub reverse_properties: Option<ReverseProperties<T, B>>,
+
+	/// Annotations of the property values (experimental JSON-LD-star
+	/// support).
+	///
+	/// These are the `@annotation` fields of the property values.
+	annotations: Option<Annotations<T, B>>,
 }
 
 impl<T, B> Default for Node<T, B> {
@@ -83,6 +92,7 @@ impl<T, B> Node<T, B> {
 			included: None,
 			properties: Properties::new(),
 			reverse_properties: None,
+			annotations: None,
 		}
 	}
 
@@ -96,6 +106,7 @@ impl<T, B> Node<T, B> {
 			included: None,
 			properties: Properties::new(),
 			reverse_properties: None,
+			annotations: None,
 		}
 	}
 
@@ -108,6 +119,7 @@ impl<T, B> Node<T, B> {
 			included: None,
 			properties: Properties::new(),
 			reverse_properties: None,
+			annotations: None,
 		}
 	}
 
@@ -205,6 +217,13 @@ impl<T, B> Node<T, B> {
 				}
 			}
 		}
+
+		for annotation in self.annotations.iter_mut().flatten() {
+			annotation.object.canonicalize_with(buffer);
+			for node in &mut annotation.nodes {
+				node.canonicalize_with(buffer)
+			}
+		}
 	}
 
 	/// Puts this node object literals into canonical form.
@@ -299,6 +318,12 @@ impl<T, B> Node<T, B> {
 			&& self.reverse_properties.is_none()
 	}
 
+	/// Tests if the node identifier is invalid.
+	#[inline(always)]
+	pub fn has_invalid_id(&self) -> bool {
+		matches!(self.id, Some(Id::Invalid(_)))
+	}
+
 	/// Tests if the node is a graph object (has a `@graph` field, and optionally an `@id` field).
 	/// Note that node objects may have a @graph entry,
 	/// but are not considered graph objects if they include any other entries other than `@id`.
@@ -413,6 +438,38 @@ impl<T, B> Node<T, B> {
 		self.reverse_properties = reverse_properties
 	}
 
+	/// Returns a reference to the annotations of the property values of the
+	/// node (experimental JSON-LD-star support).
+	///
+	/// This correspond to the `@annotation` fields of the property values in
+	/// the JSON representation.
+	#[inline(always)]
+	pub fn annotations(&self) -> Option<&Annotations<T, B>> {
+		self.annotations.as_ref()
+	}
+
+	/// Returns a mutable reference to the annotations of the property values
+	/// of the node.
+	#[inline(always)]
+	pub fn annotations_mut(&mut self) -> Option<&mut Annotations<T, B>> {
+		self.annotations.as_mut()
+	}
+
+	/// Returns a mutable reference to the annotations of the property values
+	/// of the node.
+	///
+	/// If the node has no annotations, an empty list is created.
+	#[inline(always)]
+	pub fn annotations_mut_or_default(&mut self) -> &mut Annotations<T, B> {
+		self.annotations.get_or_insert_with(Vec::new)
+	}
+
+	/// Sets the annotations of the property values of the node.
+	#[inline(always)]
+	pub fn set_annotations(&mut self, annotations: Option<Annotations<T, B>>) {
+		self.annotations = annotations
+	}
+
 	/// Tests if the node is an unnamed graph object.
 	///
 	/// Returns `true` is the only field of the object is a `@graph` field.
@@ -524,6 +581,11 @@ impl<T, B> Node<T, B> {
 					})
 					.collect()
 			}),
+			annotations: self.annotations.map(|a| {
+				a.into_iter()
+					.map(|a| a.map_ids_with(map_iri, map_id))
+					.collect()
+			}),
 		}
 	}
 }
@@ -631,6 +693,194 @@ impl<T: Eq + Hash, B: Eq + Hash> Node<T, B> {
 			false
 		}
 	}
+
+	/// Removes duplicate objects under every (reverse) property of this node
+	/// and its sub-objects (including nested graphs and included nodes).
+	///
+	/// Objects are compared through their stripped form, which ignores the
+	/// order of the entries of JSON literals. Duplicate list items are
+	/// preserved. Returns the number of removed objects.
+	pub fn deduplicate_properties(&mut self) -> usize {
+		self.deduplicate_properties_with(DeduplicateOptions::default())
+	}
+
+	/// Removes duplicate objects under every (reverse) property of this node
+	/// and its sub-objects (including nested graphs and included nodes), using
+	/// the given `options`.
+	///
+	/// Objects are compared through their stripped form, which ignores the
+	/// order of the entries of JSON literals. Returns the number of removed
+	/// objects.
+	pub fn deduplicate_properties_with(&mut self, options: DeduplicateOptions) -> usize {
+		let mut count = 0;
+
+		if let Some(graph) = self.graph_mut() {
+			let mut objects: Vec<_> = std::mem::take(graph).into_iter().collect();
+			for object in &mut objects {
+				count += object.deduplicate_with(options)
+			}
+
+			count += utils::deduplicate(&mut objects);
+			*graph = objects.into_iter().collect()
+		}
+
+		if let Some(included) = self.included_mut() {
+			let mut nodes: Vec<_> = std::mem::take(included).into_iter().collect();
+			for node in &mut nodes {
+				count += node.deduplicate_properties_with(options)
+			}
+
+			count += utils::deduplicate(&mut nodes);
+			*included = nodes.into_iter().collect()
+		}
+
+		for (_, objects) in self.properties_mut() {
+			for object in objects.iter_mut() {
+				count += object.deduplicate_with(options)
+			}
+
+			count += objects.deduplicate()
+		}
+
+		if let Some(reverse_properties) = self.reverse_properties_mut() {
+			for (_, nodes) in reverse_properties.iter_mut() {
+				for node in nodes.iter_mut() {
+					count += node.deduplicate_properties_with(options)
+				}
+
+				count += nodes.deduplicate()
+			}
+		}
+
+		for annotation in self.annotations.iter_mut().flatten() {
+			// The annotated object is a copy of a property value: it is
+			// deduplicated the same way, but not counted twice.
+			annotation.object.deduplicate_with(options);
+			for node in &mut annotation.nodes {
+				count += node.deduplicate_properties_with(options)
+			}
+		}
+
+		count
+	}
+
+	/// Removes every invalid identifier from the sub-objects of this node.
+	///
+	/// Nested nodes with an invalid identifier are removed, with their
+	/// content, as well as invalid types and every (reverse) property whose
+	/// identifier is invalid, with its values. The identifier of this node is
+	/// left untouched: removing the node itself is up to the caller (see
+	/// [`Self::has_invalid_id`]). Returns the number of removed identifiers.
+	pub fn remove_invalid_ids(&mut self) -> usize
+	where
+		T: Clone,
+		B: Clone,
+	{
+		let mut count = 0;
+
+		if let Some(types) = &mut self.types {
+			let len = types.len();
+			types.retain(Id::is_valid);
+			count += len - types.len()
+		}
+
+		if let Some(graph) = self.graph_mut() {
+			*graph = std::mem::take(graph)
+				.into_iter()
+				.filter_map(|mut o| {
+					if o.has_invalid_id() {
+						count += 1;
+						None
+					} else {
+						count += o.remove_invalid_ids();
+						Some(o)
+					}
+				})
+				.collect();
+		}
+
+		if let Some(included) = self.included_mut() {
+			*included = std::mem::take(included)
+				.into_iter()
+				.filter_map(|mut n| {
+					if n.has_invalid_id() {
+						count += 1;
+						None
+					} else {
+						count += n.remove_invalid_ids();
+						Some(n)
+					}
+				})
+				.collect();
+		}
+
+		let invalid: Vec<_> = self
+			.properties()
+			.iter()
+			.filter(|(prop, _)| !prop.is_valid())
+			.map(|(prop, _)| prop.clone())
+			.collect();
+		for prop in invalid {
+			self.properties_mut().remove(&prop);
+			count += 1
+		}
+
+		for (_, objects) in self.properties_mut() {
+			objects.retain_mut(|o| {
+				if o.has_invalid_id() {
+					count += 1;
+					false
+				} else {
+					count += o.remove_invalid_ids();
+					true
+				}
+			})
+		}
+
+		if let Some(reverse_properties) = self.reverse_properties_mut() {
+			let invalid: Vec<_> = reverse_properties
+				.iter()
+				.filter(|(prop, _)| !prop.is_valid())
+				.map(|(prop, _)| prop.clone())
+				.collect();
+			for prop in invalid {
+				reverse_properties.remove(&prop);
+				count += 1
+			}
+
+			for (_, nodes) in reverse_properties.iter_mut() {
+				nodes.retain_mut(|n| {
+					if n.has_invalid_id() {
+						count += 1;
+						false
+					} else {
+						count += n.remove_invalid_ids();
+						true
+					}
+				})
+			}
+		}
+
+		if let Some(annotations) = &mut self.annotations {
+			for annotation in annotations.iter_mut() {
+				// The annotated object is a copy of a property value: it is
+				// cleaned up the same way, but not counted twice.
+				annotation.object.remove_invalid_ids();
+				for node in &mut annotation.nodes {
+					count += node.remove_invalid_ids()
+				}
+			}
+
+			// Annotations whose property or object was removed are dropped.
+			let properties = &self.properties;
+			annotations.retain(|a| properties.get(&a.property).any(|o| *o == a.object));
+			if annotations.is_empty() {
+				self.annotations = None
+			}
+		}
+
+		count
+	}
 }
 
 impl<T, B> Relabel<T, B> for Node<T, B> {
@@ -658,6 +908,26 @@ impl<T, B> Relabel<T, B> for Node<T, B> {
 			id => id,
 		};
 
+		self.relabel_contents_with(vocabulary, generator, relabeling)
+	}
+}
+
+impl<T, B> Node<T, B> {
+	/// Relabels the blank node identifiers of this node, except its own
+	/// identifier.
+	fn relabel_contents_with<N: Vocabulary<Iri = T, BlankId = B>, G: Generator<N>>(
+		&mut self,
+		vocabulary: &mut N,
+		generator: &mut G,
+		relabeling: &mut hashbrown::HashMap<B, Subject<T, B>>,
+	) where
+		T: Clone + Eq + Hash,
+		B: Clone + Eq + Hash,
+	{
+		// Anonymous annotated objects are given fresh labels, so the
+		// annotations are synchronized with the relabeled property values.
+		let annotated = self.annotated_positions();
+
 		for ty in self.types_mut() {
 			if let Some(b) = ty.as_blank().cloned() {
 				*ty = relabeling
@@ -701,6 +971,46 @@ impl<T, B> Relabel<T, B> for Node<T, B> {
 				}
 			}
 		}
+
+		self.set_annotated_objects(annotated);
+		for annotation in self.annotations.iter_mut().flatten() {
+			// Annotation nodes have no identifier.
+			for node in &mut annotation.nodes {
+				node.relabel_contents_with(vocabulary, generator, relabeling)
+			}
+		}
+	}
+
+	/// Returns the position of each annotated object among the values of its
+	/// property.
+	fn annotated_positions(&self) -> Vec<Option<usize>>
+	where
+		T: Eq + Hash,
+		B: Eq + Hash,
+	{
+		self.annotations
+			.iter()
+			.flatten()
+			.map(|a| {
+				self.properties
+					.get(&a.property)
+					.position(|object| *object == a.object)
+			})
+			.collect()
+	}
+
+	/// Replaces each annotated object with the property value found at the
+	/// given position.
+	fn set_annotated_objects(&mut self, positions: Vec<Option<usize>>)
+	where
+		T: Clone + Eq + Hash,
+		B: Clone + Eq + Hash,
+	{
+		for (annotation, i) in self.annotations.iter_mut().flatten().zip(positions) {
+			if let Some(object) = i.and_then(|i| self.properties.get(&annotation.property).nth(i)) {
+				annotation.object = object.clone()
+			}
+		}
 	}
 }
 
@@ -712,6 +1022,7 @@ impl<T: Eq + Hash, B: Eq + Hash> PartialEq for Node<T, B> {
 			&& self.included.as_ref() == other.included.as_ref()
 			&& self.properties.eq(&other.properties)
 			&& self.reverse_properties.eq(&other.reverse_properties)
+			&& self.annotations.eq(&other.annotations)
 	}
 }
 
@@ -1205,7 +1516,8 @@ impl<T: Hash, B: Hash> Hash for Node<T, B> {
 		utils::hash_set_opt(self.graph.as_ref(), h);
 		utils::hash_set_opt(self.included.as_ref(), h);
 		self.properties.hash(h);
-		self.reverse_properties.hash(h)
+		self.reverse_properties.hash(h);
+		self.annotations.hash(h)
 	}
 }
 
@@ -1279,8 +1591,50 @@ impl<T: Eq + Hash, B: Eq + Hash> TryFromJsonObject<T, B> for Node<T, B> {
 			None => None,
 		};
 
+		// Annotated property values are stored without their `@annotation`
+		// entry, which is moved to the node annotations.
+		#[cfg(feature = "rdf-star")]
+		let annotated = {
+			let mut annotated = Vec::new();
+			for (key, value) in object.iter_mut() {
+				if let json_syntax::Value::Array(items) = value {
+					for item in items {
+						let annotation = match item.as_object_mut() {
+							Some(item) => item
+								.remove_unique("@annotation")
+								.map_err(InvalidExpandedJson::duplicate_key)?,
+							None => None,
+						};
+
+						if let Some(annotation) = annotation {
+							annotated.push((key.to_string(), item.clone(), annotation.value))
+						}
+					}
+				}
+			}
+			annotated
+		};
+
 		let properties = Properties::try_from_json_object_in(vocabulary, object)?;
 
+		#[cfg(feature = "rdf-star")]
+		let annotations = if annotated.is_empty() {
+			None
+		} else {
+			let mut annotations = Vec::with_capacity(annotated.len());
+			for (property, object, nodes) in annotated {
+				annotations.push(Annotation::new(
+					Id::from_string_in(vocabulary, property),
+					IndexedObject::try_from_json_in(vocabulary, object)?,
+					Vec::try_from_json_in(vocabulary, nodes)?,
+				))
+			}
+			Some(annotations)
+		};
+
+		#[cfg(not(feature = "rdf-star"))]
+		let annotations = None;
+
 		Ok(Self {
 			id,
 			types,
@@ -1288,6 +1642,7 @@ impl<T: Eq + Hash, B: Eq + Hash> TryFromJsonObject<T, B> for Node<T, B> {
 			included,
 			reverse_properties,
 			properties,
+			annotations,
 		})
 	}
 }
@@ -1319,7 +1674,7 @@ impl<T, B, N: Vocabulary<Iri = T, BlankId = B>> IntoJsonWithContext<N> for Node<
 
 		if let Some(included) = self.included {
 			obj.insert(
-				"@include".into(),
+				"@included".into(),
 				included.into_with(vocabulary).into_json(),
 			);
 		}
@@ -1338,6 +1693,263 @@ impl<T, B, N: Vocabulary<Iri = T, BlankId = B>> IntoJsonWithContext<N> for Node<
 			);
 		}
 
+		for annotation in self.annotations.into_iter().flatten() {
+			let key = annotation.property.with(vocabulary).to_string();
+			let object = annotation.object.into_json_with(vocabulary);
+			let annotated = obj
+				.get_unique_mut(key.as_str())
+				.ok()
+				.flatten()
+				.and_then(json_syntax::Value::as_array_mut)
+				.and_then(|values| values.iter_mut().find(|v| **v == object))
+				.and_then(json_syntax::Value::as_object_mut);
+
+			if let Some(annotated) = annotated {
+				annotated.insert(
+					"@annotation".into(),
+					annotation.nodes.into_json_with(vocabulary),
+				);
+			}
+		}
+
 		obj.into()
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use crate::{ExpandedDocument, TryFromJson};
+	use iref::IriBuf;
+	use json_ld_syntax::IntoJsonWithContext;
+	use rdf_types::BlankIdBuf;
+
+	/// Parses the given expanded document and serializes it back to JSON.
+	fn round_trip(json: json_syntax::Value) -> json_syntax::Value {
+		let document: ExpandedDocument<IriBuf, BlankIdBuf> =
+			ExpandedDocument::try_from_json_in(&mut (), json).unwrap();
+		document.into_json_with(&())
+	}
+
+	#[test]
+	fn included_round_trip() {
+		let json = json_syntax::json!([{
+			"@id": "https://example.com/a",
+			"@included": [{ "@id": "https://example.com/b" }]
+		}]);
+		assert_eq!(round_trip(json.clone()), json)
+	}
+
+	#[cfg(feature = "rdf-star")]
+	fn annotated() -> json_syntax::Value {
+		json_syntax::json!([{
+			"@id": "https://example.com/bob",
+			"https://example.com/knows": [
+				{
+					"https://example.com/name": [{ "@value": "Alice" }],
+					"@annotation": [{
+						"https://example.com/since": [{ "@value": "2020" }, { "@value": "2020" }]
+					}]
+				}
+			]
+		}])
+	}
+
+	#[cfg(feature = "rdf-star")]
+	/// Returns the first value of `property` in the first node of `json`.
+	fn first_value<'a>(json: &'a json_syntax::Value, property: &str) -> &'a json_syntax::Object {
+		let node = json.as_array().unwrap()[0].as_object().unwrap();
+		let values = node.get_unique(property).unwrap().unwrap();
+		values.as_array().unwrap()[0].as_object().unwrap()
+	}
+
+	fn parse(json: json_syntax::Value) -> ExpandedDocument<IriBuf, BlankIdBuf> {
+		ExpandedDocument::try_from_json_in(&mut (), json).unwrap()
+	}
+
+	#[cfg(feature = "rdf-star")]
+	#[test]
+	fn annotation_round_trip() {
+		let document = parse(annotated());
+		let node = document.main_node().unwrap();
+		let annotations = node.annotations().unwrap();
+		assert_eq!(annotations.len(), 1);
+		assert_eq!(annotations[0].nodes.len(), 1);
+
+		// The `@annotation` entry is not parsed as a property of the value.
+		let mut values = node.properties().iter().flat_map(|(_, objects)| objects);
+		let value = values.next().unwrap().as_node().unwrap();
+		assert_eq!(value.properties().len(), 1);
+
+		assert_eq!(round_trip(annotated()), annotated())
+	}
+
+	#[cfg(feature = "rdf-star")]
+	#[test]
+	fn relabel_annotations() {
+		let mut document = parse(annotated());
+		document.relabel(&mut rdf_types::generator::Blank::new());
+
+		// The annotated anonymous node and its copy get the same label, and
+		// the annotation node stays anonymous.
+		let json = document.into_json_with(&());
+		let value = first_value(&json, "https://example.com/knows");
+		assert!(value.get_unique("@id").unwrap().is_some());
+		let annotation = value.get_unique("@annotation").unwrap().unwrap();
+		assert!(annotation.as_array().unwrap()[0]
+			.as_object()
+			.unwrap()
+			.get_unique("@id")
+			.unwrap()
+			.is_none())
+	}
+
+	#[cfg(feature = "rdf-star")]
+	#[test]
+	fn deduplicate_annotations() {
+		let mut document = parse(annotated());
+		assert_eq!(document.deduplicate(), 1);
+
+		let node = document.main_node().unwrap();
+		let annotation = &node.annotations().unwrap()[0];
+		assert_eq!(annotation.nodes[0].properties().len(), 1);
+		assert_eq!(
+			annotation.nodes[0]
+				.properties()
+				.iter()
+				.flat_map(|(_, objects)| objects)
+				.count(),
+			1
+		)
+	}
+
+	#[test]
+	fn deduplicate_properties() {
+		let json = json_syntax::json!([{
+			"@id": "https://example.com/a",
+			"https://example.com/p": [
+				{ "@value": "x" },
+				{ "@id": "https://example.com/b" },
+				{ "@value": "x" },
+				{ "@value": "y" },
+				{ "@id": "https://example.com/b" },
+				{ "@list": [{ "@value": "x" }, { "@value": "x" }] }
+			],
+			"@reverse": {
+				"https://example.com/q": [
+					{ "@id": "https://example.com/c" },
+					{ "@id": "https://example.com/c" }
+				]
+			}
+		}]);
+
+		let expected = |list: json_syntax::Value| {
+			json_syntax::json!([{
+				"@id": "https://example.com/a",
+				"@reverse": {
+					"https://example.com/q": [{ "@id": "https://example.com/c" }]
+				},
+				"https://example.com/p": [
+					{ "@value": "x" },
+					{ "@id": "https://example.com/b" },
+					{ "@value": "y" },
+					{ "@list": list }
+				]
+			}])
+		};
+
+		let mut document = parse(json.clone());
+		assert_eq!(document.deduplicate(), 3);
+		assert_eq!(
+			document.into_json_with(&()),
+			expected(json_syntax::json!([{ "@value": "x" }, { "@value": "x" }]))
+		);
+
+		let mut document = parse(json);
+		assert_eq!(
+			document.deduplicate_with(crate::object::DeduplicateOptions { lists: true }),
+			4
+		);
+		assert_eq!(
+			document.into_json_with(&()),
+			expected(json_syntax::json!([{ "@value": "x" }]))
+		)
+	}
+
+	#[test]
+	fn deduplicate_json_literals() {
+		let mut document = parse(json_syntax::json!([{
+			"@id": "https://example.com/a",
+			"https://example.com/p": [
+				{ "@value": { "a": 1, "b": 2 }, "@type": "@json" },
+				{ "@value": { "b": 2, "a": 1 }, "@type": "@json" },
+				{ "@value": { "a": 2, "b": 1 }, "@type": "@json" }
+			]
+		}]));
+
+		assert_eq!(document.deduplicate(), 1);
+		assert_eq!(
+			document.into_json_with(&()),
+			json_syntax::json!([{
+				"@id": "https://example.com/a",
+				"https://example.com/p": [
+					{ "@type": "@json", "@value": { "a": 1, "b": 2 } },
+					{ "@type": "@json", "@value": { "a": 2, "b": 1 } }
+				]
+			}])
+		)
+	}
+
+	#[test]
+	fn remove_invalid_ids() {
+		let mut document = parse(json_syntax::json!([
+			{ "@id": "a b" },
+			{
+				"@id": "https://example.com/a",
+				"@type": ["https://example.com/T", "c d"],
+				"@included": [{ "@id": "e f" }, { "@id": "https://example.com/b" }],
+				"https://example.com/p": [
+					{ "@id": "g h" },
+					{ "@list": [{ "@id": "i j" }, { "@value": 1 }] }
+				],
+				"k l": [{ "@value": 2 }],
+				"@reverse": {
+					"https://example.com/q": [{ "@id": "m n" }]
+				}
+			}
+		]));
+
+		assert_eq!(document.remove_invalid_ids(), 7);
+		assert_eq!(
+			document.into_json_with(&()),
+			json_syntax::json!([{
+				"@id": "https://example.com/a",
+				"@type": ["https://example.com/T"],
+				"@included": [{ "@id": "https://example.com/b" }],
+				"@reverse": {
+					"https://example.com/q": []
+				},
+				"https://example.com/p": [{ "@list": [{ "@value": 1 }] }]
+			}])
+		)
+	}
+
+	#[cfg(feature = "rdf-star")]
+	#[test]
+	fn rewrite_annotations() {
+		let mut document = parse(annotated());
+		document.rewrite_iris(&[("https://example.com/", "https://example.org/")], false);
+
+		let node = document.main_node().unwrap();
+		let annotation = &node.annotations().unwrap()[0];
+		assert_eq!(annotation.property.as_str(), "https://example.org/knows");
+		assert!(annotation.nodes[0]
+			.properties()
+			.iter()
+			.all(|(property, _)| property.as_str() == "https://example.org/since"));
+
+		// The annotation is still attached to the rewritten property value.
+		let json = document.into_json_with(&());
+		let value = first_value(&json, "https://example.org/knows");
+		assert!(value.get_unique("@annotation").unwrap().is_some())
+	}
+}
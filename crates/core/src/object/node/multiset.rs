@@ -74,6 +74,28 @@ impl<T, S> Multiset<T, S> {
 		&self.data
 	}
 
+	/// Retains only the values for which `f` returns `true`.
+	pub fn retain(&mut self, f: impl FnMut(&T) -> bool) {
+		self.data.retain(f)
+	}
+
+	/// Retains only the values for which `f` returns `true`, passing a
+	/// mutable reference to each value.
+	pub fn retain_mut(&mut self, f: impl FnMut(&mut T) -> bool) {
+		self.data.retain_mut(f)
+	}
+
+	/// Removes duplicate values, keeping the first occurrence of each value.
+	///
+	/// Values are compared through their stripped form. Returns the number of
+	/// removed values.
+	pub fn deduplicate(&mut self) -> usize
+	where
+		T: locspan::StrippedEq + locspan::StrippedHash,
+	{
+		crate::utils::deduplicate(&mut self.data)
+	}
+
 	// pub fn into_stripped(self) -> Multiset<locspan::Stripped<T>, S> {
 	// 	Multiset { data: unsafe { core::mem::transmute(self.data) }, hasher: self.hasher }
 	// }
@@ -1,6 +1,7 @@
 use super::{Multiset, Objects};
 use crate::{
 	object::{InvalidExpandedJson, TryFromJson, TryFromJsonObject},
+	utils::DefaultHashBuilder,
 	Id, IndexedObject,
 };
 use educe::Educe;
@@ -16,7 +17,7 @@ pub type PropertyObjects<T, B> = Multiset<IndexedObject<T, B>>;
 	PartialEq(bound = "T: Eq + Hash, B: Eq + Hash"),
 	Eq(bound = "T: Eq + Hash, B: Eq + Hash")
 )]
-pub struct Properties<T, B>(IndexMap<Id<T, B>, PropertyObjects<T, B>>);
+pub struct Properties<T, B>(IndexMap<Id<T, B>, PropertyObjects<T, B>, DefaultHashBuilder>);
 
 impl<T, B> Default for Properties<T, B> {
 	fn default() -> Self {
@@ -27,7 +28,7 @@ impl<T, B> Default for Properties<T, B> {
 impl<T, B> Properties<T, B> {
 	/// Creates an empty map.
 	pub fn new() -> Self {
-		Self(IndexMap::new())
+		Self(IndexMap::default())
 	}
 
 	/// Returns the number of properties.
@@ -61,6 +62,12 @@ impl<T, B> Properties<T, B> {
 	pub fn clear(&mut self) {
 		self.0.clear()
 	}
+
+	/// Retains only the properties for which `f` returns `true`, in order.
+	#[inline(always)]
+	pub fn retain(&mut self, f: impl FnMut(&Id<T, B>, &mut PropertyObjects<T, B>) -> bool) {
+		self.0.retain(f)
+	}
 }
 
 impl<T: Eq + Hash, B: Eq + Hash> Properties<T, B> {
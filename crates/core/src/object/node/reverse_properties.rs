@@ -1,6 +1,7 @@
 use super::{Multiset, Nodes};
 use crate::{
 	object::{InvalidExpandedJson, TryFromJson, TryFromJsonObject},
+	utils::DefaultHashBuilder,
 	Id, IndexedNode,
 };
 use contextual::WithContext;
@@ -20,7 +21,7 @@ pub type ReversePropertyNodes<T = IriBuf, B = BlankIdBuf> = Multiset<IndexedNode
 	Eq(bound = "T: Eq + Hash, B: Eq + Hash")
 )]
 pub struct ReverseProperties<T = IriBuf, B = BlankIdBuf>(
-	IndexMap<Id<T, B>, ReversePropertyNodes<T, B>>,
+	IndexMap<Id<T, B>, ReversePropertyNodes<T, B>, DefaultHashBuilder>,
 );
 
 impl<T, B> Default for ReverseProperties<T, B> {
@@ -32,7 +33,7 @@ impl<T, B> Default for ReverseProperties<T, B> {
 impl<T, B> ReverseProperties<T, B> {
 	/// Creates an empty map.
 	pub fn new() -> Self {
-		Self(IndexMap::new())
+		Self(IndexMap::default())
 	}
 
 	/// Returns the number of reverse properties.
@@ -66,6 +67,13 @@ impl<T, B> ReverseProperties<T, B> {
 	pub fn clear(&mut self) {
 		self.0.clear()
 	}
+
+	/// Retains only the reverse properties for which `f` returns `true`, in
+	/// order.
+	#[inline(always)]
+	pub fn retain(&mut self, f: impl FnMut(&Id<T, B>, &mut ReversePropertyNodes<T, B>) -> bool) {
+		self.0.retain(f)
+	}
 }
 
 impl<T: Eq + Hash, B: Eq + Hash> ReverseProperties<T, B> {
@@ -0,0 +1,207 @@
+//! Stripped comparison of objects.
+//!
+//! Two objects are stripped-equal if they only differ in parts that carry no
+//! meaning. The entries of JSON literals (`@json` values) are compared
+//! regardless of their order, the rest of the object is compared
+//! structurally.
+use super::{
+	node::{multiset, Properties, ReverseProperties},
+	List, Node, Object, Value,
+};
+use crate::{utils, Indexed};
+use json_syntax::UnorderedPartialEq;
+use locspan::{BorrowStripped, StrippedEq, StrippedHash, StrippedPartialEq};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+/// Compares two collections as multisets of stripped values.
+fn unordered_stripped_eq<'a, T: 'a + StrippedPartialEq>(
+	a: impl IntoIterator<Item = &'a T>,
+	b: impl IntoIterator<Item = &'a T>,
+) -> bool {
+	let a: Vec<_> = a.into_iter().map(BorrowStripped::stripped).collect();
+	let b: Vec<_> = b.into_iter().map(BorrowStripped::stripped).collect();
+	multiset::compare_unordered(&a, &b)
+}
+
+/// Compares two optional collections as multisets of stripped values.
+fn opt_unordered_stripped_eq<'a, C, T: 'a + StrippedPartialEq>(
+	a: Option<&'a C>,
+	b: Option<&'a C>,
+) -> bool
+where
+	&'a C: IntoIterator<Item = &'a T>,
+{
+	match (a, b) {
+		(Some(a), Some(b)) => unordered_stripped_eq(a, b),
+		(None, None) => true,
+		_ => false,
+	}
+}
+
+/// Hashes a collection as a multiset of stripped values.
+fn unordered_stripped_hash<'a, T: 'a + StrippedHash, H: Hasher>(
+	items: impl IntoIterator<Item = &'a T>,
+	state: &mut H,
+) {
+	utils::hash_set(items.into_iter().map(BorrowStripped::stripped), state)
+}
+
+/// Hashes the bindings of a property map, regardless of their order.
+fn unordered_bindings_stripped_hash<'a, K: 'a + Hash, T: 'a + StrippedHash, H: Hasher>(
+	bindings: impl IntoIterator<Item = (&'a K, &'a [T])>,
+	state: &mut H,
+) {
+	let mut hash = 0u64;
+	for (key, items) in bindings {
+		let mut h = DefaultHasher::new();
+		key.hash(&mut h);
+		unordered_stripped_hash(items, &mut h);
+		hash = hash.wrapping_add(h.finish());
+	}
+
+	state.write_u64(hash)
+}
+
+impl<T: StrippedPartialEq> StrippedPartialEq for Indexed<T> {
+	fn stripped_eq(&self, other: &Self) -> bool {
+		self.index() == other.index() && self.inner().stripped_eq(other.inner())
+	}
+}
+
+impl<T: StrippedEq> StrippedEq for Indexed<T> {}
+
+impl<T: StrippedHash> StrippedHash for Indexed<T> {
+	fn stripped_hash<H: Hasher>(&self, state: &mut H) {
+		self.index().hash(state);
+		self.inner().stripped_hash(state)
+	}
+}
+
+impl<T: Eq + Hash, B: Eq + Hash> StrippedPartialEq for Object<T, B> {
+	fn stripped_eq(&self, other: &Self) -> bool {
+		match (self, other) {
+			(Self::Value(a), Self::Value(b)) => a.stripped_eq(b),
+			(Self::Node(a), Self::Node(b)) => a.stripped_eq(b),
+			(Self::List(a), Self::List(b)) => a.stripped_eq(b),
+			_ => false,
+		}
+	}
+}
+
+impl<T: Eq + Hash, B: Eq + Hash> StrippedEq for Object<T, B> {}
+
+impl<T: Eq + Hash, B: Eq + Hash> StrippedHash for Object<T, B> {
+	fn stripped_hash<H: Hasher>(&self, state: &mut H) {
+		std::mem::discriminant(self).hash(state);
+		match self {
+			Self::Value(v) => v.stripped_hash(state),
+			Self::Node(n) => n.stripped_hash(state),
+			Self::List(l) => l.stripped_hash(state),
+		}
+	}
+}
+
+impl<T: PartialEq> StrippedPartialEq for Value<T> {
+	fn stripped_eq(&self, other: &Self) -> bool {
+		match (self, other) {
+			(Self::Json(a), Self::Json(b)) => a.unordered_eq(b),
+			(a, b) => a == b,
+		}
+	}
+}
+
+impl<T: Eq> StrippedEq for Value<T> {}
+
+impl<T: Hash> StrippedHash for Value<T> {
+	fn stripped_hash<H: Hasher>(&self, state: &mut H) {
+		match self {
+			// The hash must not depend on the order of the entries.
+			Self::Json(json) => json.kind().hash(state),
+			value => value.hash(state),
+		}
+	}
+}
+
+impl<T: Eq + Hash, B: Eq + Hash> StrippedPartialEq for List<T, B> {
+	fn stripped_eq(&self, other: &Self) -> bool {
+		self.len() == other.len() && self.iter().zip(other).all(|(a, b)| a.stripped_eq(b))
+	}
+}
+
+impl<T: Eq + Hash, B: Eq + Hash> StrippedEq for List<T, B> {}
+
+impl<T: Eq + Hash, B: Eq + Hash> StrippedHash for List<T, B> {
+	fn stripped_hash<H: Hasher>(&self, state: &mut H) {
+		self.len().hash(state);
+		for item in self {
+			item.stripped_hash(state)
+		}
+	}
+}
+
+impl<T: Eq + Hash, B: Eq + Hash> StrippedPartialEq for Properties<T, B> {
+	fn stripped_eq(&self, other: &Self) -> bool {
+		self.len() == other.len()
+			&& self
+				.iter()
+				.all(|(property, objects)| unordered_stripped_eq(objects, other.get(property)))
+	}
+}
+
+impl<T: Eq + Hash, B: Eq + Hash> StrippedEq for Properties<T, B> {}
+
+impl<T: Eq + Hash, B: Eq + Hash> StrippedHash for Properties<T, B> {
+	fn stripped_hash<H: Hasher>(&self, state: &mut H) {
+		unordered_bindings_stripped_hash(self, state)
+	}
+}
+
+impl<T: Eq + Hash, B: Eq + Hash> StrippedPartialEq for ReverseProperties<T, B> {
+	fn stripped_eq(&self, other: &Self) -> bool {
+		self.len() == other.len()
+			&& self
+				.iter()
+				.all(|(property, nodes)| unordered_stripped_eq(nodes, other.get(property)))
+	}
+}
+
+impl<T: Eq + Hash, B: Eq + Hash> StrippedEq for ReverseProperties<T, B> {}
+
+impl<T: Eq + Hash, B: Eq + Hash> StrippedHash for ReverseProperties<T, B> {
+	fn stripped_hash<H: Hasher>(&self, state: &mut H) {
+		unordered_bindings_stripped_hash(self, state)
+	}
+}
+
+impl<T: Eq + Hash, B: Eq + Hash> StrippedPartialEq for Node<T, B> {
+	fn stripped_eq(&self, other: &Self) -> bool {
+		self.id == other.id
+			&& multiset::compare_unordered_opt(self.types.as_deref(), other.types.as_deref())
+			&& opt_unordered_stripped_eq(self.graph.as_ref(), other.graph.as_ref())
+			&& opt_unordered_stripped_eq(self.included.as_ref(), other.included.as_ref())
+			&& self.properties.stripped_eq(&other.properties)
+			&& self
+				.reverse_properties
+				.stripped_eq(&other.reverse_properties)
+			&& self.annotations() == other.annotations()
+	}
+}
+
+impl<T: Eq + Hash, B: Eq + Hash> StrippedEq for Node<T, B> {}
+
+impl<T: Eq + Hash, B: Eq + Hash> StrippedHash for Node<T, B> {
+	fn stripped_hash<H: Hasher>(&self, state: &mut H) {
+		self.id.hash(state);
+		utils::hash_set_opt(self.types.as_ref(), state);
+		if let Some(graph) = &self.graph {
+			unordered_stripped_hash(graph, state)
+		}
+		if let Some(included) = &self.included {
+			unordered_stripped_hash(included, state)
+		}
+		self.properties.stripped_hash(state);
+		self.reverse_properties.stripped_hash(state);
+		self.annotations().hash(state)
+	}
+}
@@ -0,0 +1,200 @@
+use super::{List, Node, Object, Value};
+use crate::{Indexed, IndexedObject};
+use indexmap::IndexSet;
+use std::hash::Hash;
+
+/// In-place transformation of the objects of a document.
+pub(crate) trait Transform<T, B> {
+	/// Transforms the given node object, before its sub-objects.
+	///
+	/// Returns `false` if the node must be removed.
+	fn node(&mut self, node: &mut Node<T, B>) -> bool;
+
+	/// Transforms the given value object.
+	fn value(&mut self, value: &mut Value<T>);
+}
+
+/// Transformation applying a function to every value object.
+pub(crate) struct MapValues<F>(pub F);
+
+impl<T, B, F: FnMut(&mut Value<T>)> Transform<T, B> for MapValues<F> {
+	fn node(&mut self, _node: &mut Node<T, B>) -> bool {
+		true
+	}
+
+	fn value(&mut self, value: &mut Value<T>) {
+		(self.0)(value)
+	}
+}
+
+/// Transformation applying a function to every node object.
+pub(crate) struct MapNodes<F>(pub F);
+
+impl<T, B, F: FnMut(&mut Node<T, B>)> Transform<T, B> for MapNodes<F> {
+	fn node(&mut self, node: &mut Node<T, B>) -> bool {
+		(self.0)(node);
+		true
+	}
+
+	fn value(&mut self, _value: &mut Value<T>) {}
+}
+
+/// Transformation removing the node objects not satisfying a predicate.
+pub(crate) struct RetainNodes<F>(pub F);
+
+impl<T, B, F: FnMut(&Node<T, B>) -> bool> Transform<T, B> for RetainNodes<F> {
+	fn node(&mut self, node: &mut Node<T, B>) -> bool {
+		(self.0)(node)
+	}
+
+	fn value(&mut self, _value: &mut Value<T>) {}
+}
+
+/// Transforms the given object, returning `false` if it must be removed.
+fn transform_object<T: Eq + Hash, B: Eq + Hash>(
+	object: &mut Object<T, B>,
+	t: &mut impl Transform<T, B>,
+) -> bool {
+	match object {
+		Object::Value(value) => {
+			t.value(value);
+			true
+		}
+		Object::Node(node) => transform_node(node, t),
+		Object::List(list) => {
+			transform_list(list, t);
+			true
+		}
+	}
+}
+
+/// Transforms the given node and its sub-objects, returning `false` if it
+/// must be removed.
+fn transform_node<T: Eq + Hash, B: Eq + Hash>(
+	node: &mut Node<T, B>,
+	t: &mut impl Transform<T, B>,
+) -> bool {
+	if !t.node(node) {
+		return false;
+	}
+
+	if let Some(graph) = node.graph_mut() {
+		transform_set(graph, |o| transform_object(o, t))
+	}
+
+	if let Some(included) = node.included_mut() {
+		transform_set(included, |n| transform_node(n, t))
+	}
+
+	// Properties left without values are removed.
+	node.properties_mut().retain(|_, objects| {
+		let was_empty = objects.is_empty();
+		objects.retain_mut(|o| transform_object(o, t));
+		was_empty || !objects.is_empty()
+	});
+
+	if let Some(reverse_properties) = node.reverse_properties_mut() {
+		reverse_properties.retain(|_, nodes| {
+			let was_empty = nodes.is_empty();
+			nodes.retain_mut(|n| transform_node(n, t));
+			was_empty || !nodes.is_empty()
+		});
+	}
+
+	true
+}
+
+/// Transforms the items of the given list, removing the ones that must be.
+fn transform_list<T: Eq + Hash, B: Eq + Hash>(list: &mut List<T, B>, t: &mut impl Transform<T, B>) {
+	list.entry_mut().retain_mut(|o| transform_object(o, t))
+}
+
+/// Transforms the items of the given set, removing the ones that must be.
+///
+/// Items are reinserted since their hash may change.
+fn transform_set<O: Eq + Hash>(set: &mut IndexSet<Indexed<O>>, mut f: impl FnMut(&mut O) -> bool)
+where
+	Indexed<O>: Eq + Hash,
+{
+	*set = std::mem::take(set)
+		.into_iter()
+		.filter_map(|mut item| f(&mut item).then_some(item))
+		.collect()
+}
+
+/// Transforms the given objects, removing the ones that must be.
+pub(crate) fn transform_objects<T: Eq + Hash, B: Eq + Hash>(
+	objects: &mut IndexSet<IndexedObject<T, B>>,
+	t: &mut impl Transform<T, B>,
+) {
+	transform_set(objects, |o| transform_object(o, t))
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{object::Literal, ExpandedDocument, Id, TryFromJson, Value};
+
+	#[test]
+	fn transform() {
+		let mut document = ExpandedDocument::try_from_json_in(
+			&mut (),
+			json_syntax::json!([{
+				"@id": "https://example.com/alice",
+				"https://schema.org/email": [{ "@value": "alice@example.com" }],
+				"https://schema.org/knows": [{
+					"@id": "https://example.com/bob",
+					"https://schema.org/email": [{ "@value": "bob@example.com" }]
+				}],
+				"https://schema.org/children": [{
+					"@list": [
+						{ "@id": "https://example.com/carol" },
+						{ "@value": "dave@example.com" }
+					]
+				}],
+				"@graph": [{ "@id": "https://example.com/eve" }]
+			}]),
+		)
+		.unwrap();
+
+		document.map_values(|value| {
+			if let Value::Literal(Literal::String(s), _) = value {
+				if s.contains('@') {
+					*s = "redacted".into()
+				}
+			}
+		});
+
+		let mut ids = Vec::new();
+		document.map_nodes(|node| ids.push(node.id.as_ref().unwrap().as_str().to_owned()));
+		assert_eq!(
+			ids,
+			[
+				"https://example.com/alice",
+				"https://example.com/eve",
+				"https://example.com/bob",
+				"https://example.com/carol"
+			]
+		);
+
+		document.retain_nodes(|node| {
+			node.id.as_ref().map(Id::as_str) != Some("https://example.com/bob")
+		});
+
+		let expected = ExpandedDocument::try_from_json_in(
+			&mut (),
+			json_syntax::json!([{
+				"@id": "https://example.com/alice",
+				"https://schema.org/email": [{ "@value": "redacted" }],
+				"https://schema.org/children": [{
+					"@list": [
+						{ "@id": "https://example.com/carol" },
+						{ "@value": "redacted" }
+					]
+				}],
+				"@graph": [{ "@id": "https://example.com/eve" }]
+			}]),
+		)
+		.unwrap();
+		assert_eq!(document, expected)
+	}
+}
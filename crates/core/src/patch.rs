@@ -0,0 +1,663 @@
+//! [JSON Patch](https://www.rfc-editor.org/rfc/rfc6902) support.
+//!
+//! A [`Patch`] can be applied to any JSON value, such as a compacted
+//! document, with [`Patch::apply`]. It can also be applied to an
+//! [`ExpandedDocument`] with [`ExpandedDocument::apply_patch_in`]. In this
+//! case the first segment of a path can also address a top-level node by its
+//! `@id`, and only the top-level objects affected by the patch are converted
+//! to JSON and validated again.
+//!
+//! Patches are applied atomically: if an operation fails, the patched value
+//! is left unchanged.
+use crate::{object::InvalidExpandedJson, ExpandedDocument, Indexed, IndexedObject, TryFromJson};
+use contextual::WithContext;
+use json_ld_syntax::IntoJsonWithContext;
+use json_syntax::Value;
+use rdf_types::{Vocabulary, VocabularyMut};
+use std::{fmt, hash::Hash};
+
+/// [JSON Pointer](https://www.rfc-editor.org/rfc/rfc6901).
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct Pointer(Vec<String>);
+
+impl Pointer {
+	/// Returns the pointer to the whole value.
+	pub fn root() -> Self {
+		Self::default()
+	}
+
+	/// Parses a JSON Pointer.
+	pub fn parse(s: &str) -> Result<Self, InvalidPointer> {
+		if s.is_empty() {
+			return Ok(Self::root());
+		}
+
+		let invalid = || InvalidPointer(s.to_owned());
+		let mut segments = Vec::new();
+		for escaped in s.strip_prefix('/').ok_or_else(invalid)?.split('/') {
+			let mut segment = String::with_capacity(escaped.len());
+			let mut chars = escaped.chars();
+			while let Some(c) = chars.next() {
+				match c {
+					'~' => match chars.next() {
+						Some('0') => segment.push('~'),
+						Some('1') => segment.push('/'),
+						_ => return Err(invalid()),
+					},
+					c => segment.push(c),
+				}
+			}
+
+			segments.push(segment)
+		}
+
+		Ok(Self(segments))
+	}
+
+	/// Returns the unescaped segments of the pointer.
+	pub fn segments(&self) -> &[String] {
+		&self.0
+	}
+
+	/// Checks if this pointer is a proper prefix of `other`.
+	fn is_proper_prefix_of(&self, other: &Self) -> bool {
+		self.0.len() < other.0.len() && other.0.starts_with(&self.0)
+	}
+}
+
+impl fmt::Display for Pointer {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		for segment in &self.0 {
+			write!(f, "/{}", segment.replace('~', "~0").replace('/', "~1"))?
+		}
+
+		Ok(())
+	}
+}
+
+/// Invalid JSON Pointer error.
+#[derive(Debug, thiserror::Error)]
+#[error("invalid JSON pointer `{0}`")]
+pub struct InvalidPointer(pub String);
+
+/// JSON Patch operation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Operation {
+	Add { path: Pointer, value: Value },
+	Remove { path: Pointer },
+	Replace { path: Pointer, value: Value },
+	Move { from: Pointer, path: Pointer },
+	Copy { from: Pointer, path: Pointer },
+	Test { path: Pointer, value: Value },
+}
+
+impl Operation {
+	fn from_json(index: usize, json: &Value) -> Result<Self, InvalidPatch> {
+		let invalid = || InvalidPatch::InvalidOperation(index);
+		let object = json.as_object().ok_or_else(invalid)?;
+
+		let value = |key: &str| object.get_unique(key).ok().flatten().ok_or_else(invalid);
+		let pointer = |key: &str| match value(key)?.as_str() {
+			Some(s) => Ok(Pointer::parse(s)?),
+			None => Err(invalid()),
+		};
+
+		Ok(match value("op")?.as_str().ok_or_else(invalid)? {
+			"add" => Self::Add {
+				path: pointer("path")?,
+				value: value("value")?.clone(),
+			},
+			"remove" => Self::Remove {
+				path: pointer("path")?,
+			},
+			"replace" => Self::Replace {
+				path: pointer("path")?,
+				value: value("value")?.clone(),
+			},
+			"move" => Self::Move {
+				from: pointer("from")?,
+				path: pointer("path")?,
+			},
+			"copy" => Self::Copy {
+				from: pointer("from")?,
+				path: pointer("path")?,
+			},
+			"test" => Self::Test {
+				path: pointer("path")?,
+				value: value("value")?.clone(),
+			},
+			_ => return Err(invalid()),
+		})
+	}
+}
+
+/// JSON Patch, sequence of operations.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Patch(Vec<Operation>);
+
+impl Patch {
+	/// Creates an empty patch.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Adds an operation at the end of the patch.
+	pub fn push(&mut self, operation: Operation) {
+		self.0.push(operation)
+	}
+
+	/// Returns the operations of the patch.
+	pub fn operations(&self) -> &[Operation] {
+		&self.0
+	}
+
+	/// Reads a patch from its JSON representation.
+	pub fn from_json(json: &Value) -> Result<Self, InvalidPatch> {
+		let operations = json.as_array().ok_or(InvalidPatch::NotAnArray)?;
+		let mut result = Vec::with_capacity(operations.len());
+
+		for (i, operation) in operations.iter().enumerate() {
+			result.push(Operation::from_json(i, operation)?)
+		}
+
+		Ok(Self(result))
+	}
+
+	/// Applies this patch to the given JSON value.
+	pub fn apply(&self, value: &mut Value) -> Result<(), PatchError> {
+		let mut patched = value.clone();
+		for operation in &self.0 {
+			apply_operation(&mut patched, operation)?
+		}
+
+		*value = patched;
+		Ok(())
+	}
+}
+
+impl From<Vec<Operation>> for Patch {
+	fn from(operations: Vec<Operation>) -> Self {
+		Self(operations)
+	}
+}
+
+/// Invalid JSON Patch error.
+#[derive(Debug, thiserror::Error)]
+pub enum InvalidPatch {
+	#[error("patch is not a JSON array")]
+	NotAnArray,
+
+	#[error("invalid patch operation {0}")]
+	InvalidOperation(usize),
+
+	#[error(transparent)]
+	InvalidPointer(#[from] InvalidPointer),
+}
+
+/// Patch application error.
+#[derive(Debug, thiserror::Error)]
+pub enum PatchError {
+	#[error("no value at `{0}`")]
+	NotFound(Pointer),
+
+	#[error("cannot add a value at `{0}`")]
+	InvalidTarget(Pointer),
+
+	#[error("test failed at `{0}`")]
+	TestFailed(Pointer),
+
+	#[error("invalid expanded object {index}: {error:?}")]
+	InvalidObject {
+		index: usize,
+		error: InvalidExpandedJson,
+	},
+}
+
+/// Value on which patch operations can be applied.
+trait Target {
+	/// Returns a copy of the value at the given path.
+	fn get(&mut self, path: &[String]) -> Option<Value>;
+
+	/// Adds the value at the given path, returning `false` if the parent of
+	/// the path does not exist or cannot hold the value.
+	fn add(&mut self, path: &[String], value: Value) -> bool;
+
+	/// Replaces the value at the given path, in place.
+	fn replace(&mut self, path: &[String], value: Value) -> Option<Value>;
+
+	/// Removes the value at the given path.
+	fn remove(&mut self, path: &[String]) -> Option<Value>;
+}
+
+fn apply_operation(target: &mut impl Target, operation: &Operation) -> Result<(), PatchError> {
+	fn add(target: &mut impl Target, path: &Pointer, value: Value) -> Result<(), PatchError> {
+		if target.add(path.segments(), value) {
+			Ok(())
+		} else {
+			Err(PatchError::InvalidTarget(path.clone()))
+		}
+	}
+
+	fn remove(target: &mut impl Target, path: &Pointer) -> Result<Value, PatchError> {
+		target
+			.remove(path.segments())
+			.ok_or_else(|| PatchError::NotFound(path.clone()))
+	}
+
+	fn get(target: &mut impl Target, path: &Pointer) -> Result<Value, PatchError> {
+		target
+			.get(path.segments())
+			.ok_or_else(|| PatchError::NotFound(path.clone()))
+	}
+
+	match operation {
+		Operation::Add { path, value } => add(target, path, value.clone()),
+		Operation::Remove { path } => remove(target, path).map(|_| ()),
+		Operation::Replace { path, value } => target
+			.replace(path.segments(), value.clone())
+			.map(|_| ())
+			.ok_or_else(|| PatchError::NotFound(path.clone())),
+		Operation::Move { from, path } => {
+			if from.is_proper_prefix_of(path) {
+				return Err(PatchError::InvalidTarget(path.clone()));
+			}
+
+			let value = remove(target, from)?;
+			add(target, path, value)
+		}
+		Operation::Copy { from, path } => {
+			let value = get(target, from)?;
+			add(target, path, value)
+		}
+		Operation::Test { path, value } => {
+			if get(target, path)? == *value {
+				Ok(())
+			} else {
+				Err(PatchError::TestFailed(path.clone()))
+			}
+		}
+	}
+}
+
+/// Parses an array index segment, without leading zeros.
+fn array_index(segment: &str) -> Option<usize> {
+	if segment == "0" || (!segment.starts_with('0') && segment.bytes().all(|b| b.is_ascii_digit()))
+	{
+		segment.parse().ok()
+	} else {
+		None
+	}
+}
+
+fn get<'a>(value: &'a Value, path: &[String]) -> Option<&'a Value> {
+	path.iter().try_fold(value, |value, segment| match value {
+		Value::Object(object) => object.get_unique(segment.as_str()).ok().flatten(),
+		Value::Array(items) => items.get(array_index(segment)?),
+		_ => None,
+	})
+}
+
+fn get_mut<'a>(value: &'a mut Value, path: &[String]) -> Option<&'a mut Value> {
+	path.iter().try_fold(value, |value, segment| match value {
+		Value::Object(object) => object.get_unique_mut(segment.as_str()).ok().flatten(),
+		Value::Array(items) => items.get_mut(array_index(segment)?),
+		_ => None,
+	})
+}
+
+impl Target for Value {
+	fn get(&mut self, path: &[String]) -> Option<Value> {
+		get(self, path).cloned()
+	}
+
+	fn add(&mut self, path: &[String], value: Value) -> bool {
+		let (last, parent) = match path.split_last() {
+			Some(split) => split,
+			None => {
+				*self = value;
+				return true;
+			}
+		};
+
+		match get_mut(self, parent) {
+			Some(Value::Object(object)) => {
+				object.insert(last.as_str().into(), value);
+				true
+			}
+			Some(Value::Array(items)) => {
+				let i = match last.as_str() {
+					"-" => items.len(),
+					last => match array_index(last) {
+						Some(i) if i <= items.len() => i,
+						_ => return false,
+					},
+				};
+
+				items.insert(i, value);
+				true
+			}
+			_ => false,
+		}
+	}
+
+	fn replace(&mut self, path: &[String], value: Value) -> Option<Value> {
+		get_mut(self, path).map(|target| std::mem::replace(target, value))
+	}
+
+	fn remove(&mut self, path: &[String]) -> Option<Value> {
+		let (last, parent) = match path.split_last() {
+			Some(split) => split,
+			None => return Some(std::mem::replace(self, Value::Null)),
+		};
+
+		match get_mut(self, parent)? {
+			Value::Object(object) => object
+				.remove_unique(last.as_str())
+				.ok()
+				.flatten()
+				.map(|entry| entry.value),
+			Value::Array(items) => {
+				let i = array_index(last).filter(|i| *i < items.len())?;
+				Some(items.remove(i))
+			}
+			_ => None,
+		}
+	}
+}
+
+/// Top-level item of an expanded document being patched.
+enum Item {
+	/// Original object, at the given position, not affected by the patch.
+	Original(usize),
+
+	/// Affected object, in JSON form.
+	Json(Value),
+}
+
+/// Expanded document being patched.
+struct DocumentTarget<'a, T, B, N> {
+	objects: Vec<&'a IndexedObject<T, B>>,
+	vocabulary: &'a N,
+	items: Vec<Item>,
+}
+
+impl<'a, T, B, N: Vocabulary<Iri = T, BlankId = B>> DocumentTarget<'a, T, B, N>
+where
+	T: Clone + Eq + Hash,
+	B: Clone + Eq + Hash,
+{
+	/// Finds the top-level item addressed by the given segment, either an
+	/// index or a node `@id`.
+	fn resolve(&self, segment: &str) -> Option<usize> {
+		match array_index(segment) {
+			Some(i) => (i < self.items.len()).then_some(i),
+			None => self.items.iter().position(|item| match item {
+				Item::Original(i) => self.objects[*i]
+					.as_node()
+					.and_then(|node| node.id.as_ref())
+					.is_some_and(|id| id.with(self.vocabulary).as_str() == segment),
+				Item::Json(value) => {
+					get(value, &["@id".to_owned()]).and_then(Value::as_str) == Some(segment)
+				}
+			}),
+		}
+	}
+
+	fn to_json(&self, i: usize) -> Value {
+		match &self.items[i] {
+			Item::Original(j) => self.objects[*j].clone().into_json_with(self.vocabulary),
+			Item::Json(value) => value.clone(),
+		}
+	}
+
+	/// Returns the JSON form of the given item, marking it as affected.
+	fn touch(&mut self, i: usize) -> &mut Value {
+		if let Item::Original(j) = self.items[i] {
+			self.items[i] = Item::Json(self.objects[j].clone().into_json_with(self.vocabulary))
+		}
+
+		match &mut self.items[i] {
+			Item::Json(value) => value,
+			Item::Original(_) => unreachable!(),
+		}
+	}
+}
+
+impl<'a, T, B, N: Vocabulary<Iri = T, BlankId = B>> Target for DocumentTarget<'a, T, B, N>
+where
+	T: Clone + Eq + Hash,
+	B: Clone + Eq + Hash,
+{
+	fn get(&mut self, path: &[String]) -> Option<Value> {
+		match path.split_first() {
+			Some((first, rest)) => {
+				let json = self.to_json(self.resolve(first)?);
+				get(&json, rest).cloned()
+			}
+			None => Some(Value::Array(
+				(0..self.items.len()).map(|i| self.to_json(i)).collect(),
+			)),
+		}
+	}
+
+	fn add(&mut self, path: &[String], value: Value) -> bool {
+		match path {
+			[] => match value {
+				Value::Array(items) => {
+					self.items = items.into_iter().map(Item::Json).collect();
+					true
+				}
+				_ => false,
+			},
+			[first] => {
+				match first.as_str() {
+					"-" => self.items.push(Item::Json(value)),
+					first => match array_index(first) {
+						Some(i) if i <= self.items.len() => self.items.insert(i, Item::Json(value)),
+						Some(_) => return false,
+						// Adding a node with an existing `@id` replaces it.
+						None => match self.resolve(first) {
+							Some(i) => self.items[i] = Item::Json(value),
+							None => self.items.push(Item::Json(value)),
+						},
+					},
+				}
+
+				true
+			}
+			[first, rest @ ..] => match self.resolve(first) {
+				Some(i) => self.touch(i).add(rest, value),
+				None => false,
+			},
+		}
+	}
+
+	fn replace(&mut self, path: &[String], value: Value) -> Option<Value> {
+		match path {
+			[] => {
+				let previous = self.get(path);
+				self.add(path, value).then_some(previous?)
+			}
+			[first] => {
+				let i = self.resolve(first)?;
+				let previous = self.to_json(i);
+				self.items[i] = Item::Json(value);
+				Some(previous)
+			}
+			[first, rest @ ..] => {
+				let i = self.resolve(first)?;
+				self.touch(i).replace(rest, value)
+			}
+		}
+	}
+
+	fn remove(&mut self, path: &[String]) -> Option<Value> {
+		match path {
+			[] => {
+				let value = self.get(path);
+				self.items.clear();
+				value
+			}
+			[first] => {
+				let i = self.resolve(first)?;
+				let value = self.to_json(i);
+				self.items.remove(i);
+				Some(value)
+			}
+			[first, rest @ ..] => {
+				let i = self.resolve(first)?;
+				self.touch(i).remove(rest)
+			}
+		}
+	}
+}
+
+impl<T: Clone + Eq + Hash, B: Clone + Eq + Hash> ExpandedDocument<T, B> {
+	/// Applies the given patch to this document, interning new identifiers
+	/// into the given `vocabulary`.
+	///
+	/// The document is patched as if it were in its JSON form, an array of
+	/// top-level objects, except that the first segment of a path can also be
+	/// the `@id` of a top-level node. Adding a node at the `@id` of an
+	/// existing node replaces it, otherwise the node is appended.
+	///
+	/// Only the top-level objects affected by the patch are converted to
+	/// JSON and validated again. The document is left unchanged if the patch
+	/// fails or results in an invalid expanded object.
+	pub fn apply_patch_in(
+		&mut self,
+		vocabulary: &mut impl VocabularyMut<Iri = T, BlankId = B>,
+		patch: &Patch,
+	) -> Result<(), PatchError> {
+		let items = {
+			let mut target = DocumentTarget {
+				objects: self.iter().collect(),
+				vocabulary: &*vocabulary,
+				items: (0..self.len()).map(Item::Original).collect(),
+			};
+
+			for operation in patch.operations() {
+				apply_operation(&mut target, operation)?
+			}
+
+			target.items
+		};
+
+		let mut converted = Vec::with_capacity(items.len());
+		for (index, item) in items.into_iter().enumerate() {
+			converted.push(match item {
+				Item::Original(i) => Err(i),
+				Item::Json(json) => Ok(Indexed::try_from_json_in(vocabulary, json)
+					.map_err(|error| PatchError::InvalidObject { index, error })?),
+			})
+		}
+
+		let mut originals: Vec<_> = std::mem::take(self).into_iter().map(Some).collect();
+		for object in converted {
+			self.insert(match object {
+				Ok(object) => object,
+				Err(i) => originals[i].take().unwrap(),
+			});
+		}
+
+		Ok(())
+	}
+
+	/// Applies the given patch to this document.
+	///
+	/// See [`Self::apply_patch_in`].
+	pub fn apply_patch(&mut self, patch: &Patch) -> Result<(), PatchError>
+	where
+		(): VocabularyMut<Iri = T, BlankId = B>,
+	{
+		self.apply_patch_in(&mut (), patch)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use iref::IriBuf;
+	use rdf_types::BlankIdBuf;
+
+	#[test]
+	fn patch_json() {
+		let patch = Patch::from_json(&json_syntax::json!([
+			{ "op": "replace", "path": "/name", "value": "Bob" },
+			{ "op": "add", "path": "/tags/-", "value": "b" },
+			{ "op": "move", "from": "/a~1b", "path": "/c" },
+			{ "op": "test", "path": "/tags/0", "value": "a" }
+		]))
+		.unwrap();
+
+		let mut value = json_syntax::json!({ "name": "Alice", "tags": ["a"], "a/b": 1 });
+		patch.apply(&mut value).unwrap();
+		assert_eq!(
+			value,
+			json_syntax::json!({ "name": "Bob", "tags": ["a", "b"], "c": 1 })
+		);
+
+		let failing = Patch::from(vec![
+			Operation::Remove {
+				path: Pointer::parse("/name").unwrap(),
+			},
+			Operation::Remove {
+				path: Pointer::parse("/missing").unwrap(),
+			},
+		]);
+		assert!(matches!(
+			failing.apply(&mut value),
+			Err(PatchError::NotFound(_))
+		));
+		assert!(value.as_object().unwrap().contains_key("name"))
+	}
+
+	#[test]
+	fn patch_expanded() {
+		let mut document: ExpandedDocument<IriBuf, BlankIdBuf> =
+			ExpandedDocument::try_from_json_in(
+				&mut (),
+				json_syntax::json!([{
+					"@id": "https://example.com/a",
+					"https://example.com/name": [{ "@value": "Alice" }]
+				}, {
+					"@id": "https://example.com/b",
+					"https://example.com/name": [{ "@value": "Bob" }]
+				}]),
+			)
+			.unwrap();
+
+		let patch = Patch::from_json(&json_syntax::json!([{
+			"op": "replace",
+			"path": "/https:~1~1example.com~1b/https:~1~1example.com~1name/0/@value",
+			"value": "Robert"
+		}]))
+		.unwrap();
+		document.apply_patch(&patch).unwrap();
+
+		let expected = ExpandedDocument::try_from_json_in(
+			&mut (),
+			json_syntax::json!([{
+				"@id": "https://example.com/a",
+				"https://example.com/name": [{ "@value": "Alice" }]
+			}, {
+				"@id": "https://example.com/b",
+				"https://example.com/name": [{ "@value": "Robert" }]
+			}]),
+		)
+		.unwrap();
+		assert_eq!(document, expected);
+
+		let invalid = Patch::from_json(&json_syntax::json!([{
+			"op": "add",
+			"path": "/0/https:~1~1example.com~1name/0/@language",
+			"value": 42
+		}]))
+		.unwrap();
+		assert!(matches!(
+			document.apply_patch(&invalid),
+			Err(PatchError::InvalidObject { index: 0, .. })
+		));
+		assert_eq!(document, expected)
+	}
+}
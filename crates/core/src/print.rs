@@ -3,11 +3,205 @@ use json_syntax::print::{
 	printed_string_size, string_literal, PrecomputeSize, PrecomputeSizeWithContext,
 	PrintWithContext, PrintWithSize, PrintWithSizeAndContext, Size,
 };
-pub use json_syntax::print::{Options, Print, Printed};
+pub use json_syntax::print::{Indent, Limit, Options, Print, Printed};
 
-use crate::{object, ExpandedDocument, Id, Indexed, Object};
+use crate::{flattening::NodeMap, object, ExpandedDocument, Id, Indexed, Object};
 use contextual::WithContext;
+use json_ld_syntax::IntoJsonWithContext;
 use rdf_types::vocabulary::{IriVocabulary, Vocabulary};
+use std::fmt;
+use std::hash::Hash;
+
+/// JSON output format.
+///
+/// Combines the printing [`Options`] (indentation, spacing, and the limits
+/// after which arrays and objects are wrapped over multiple lines) with the
+/// ordering of object entries. Use [`IntoJsonWithContext`] to turn expanded
+/// or flattened documents into JSON values first.
+///
+/// [`IntoJsonWithContext`]: json_ld_syntax::IntoJsonWithContext
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Format {
+	/// Printing options.
+	pub options: Options,
+
+	/// If set to `true`, object entries are sorted by key.
+	/// Otherwise entries are printed in insertion order.
+	pub sort_keys: bool,
+}
+
+impl Format {
+	/// Pretty format, indented with two spaces.
+	pub fn pretty() -> Self {
+		Self::new(Options::pretty())
+	}
+
+	/// Compact format, without any whitespace.
+	pub fn compact() -> Self {
+		Self::new(Options::compact())
+	}
+
+	/// Inline format, on a single line with spaces.
+	pub fn inline() -> Self {
+		Self::new(Options::inline())
+	}
+
+	/// Creates a format with the given printing options, preserving the
+	/// order of object entries.
+	pub fn new(options: Options) -> Self {
+		Self {
+			options,
+			sort_keys: false,
+		}
+	}
+
+	/// Returns this format with the given indentation.
+	pub fn with_indent(mut self, indent: Indent) -> Self {
+		self.options.indent = indent;
+		self
+	}
+
+	/// Returns this format with the given array and object wrapping limit.
+	///
+	/// `None` means that arrays and objects are never wrapped.
+	pub fn with_limit(mut self, limit: Option<Limit>) -> Self {
+		self.options.array_limit = limit;
+		self.options.object_limit = limit;
+		self
+	}
+
+	/// Returns this format with object entries sorted by key, or not.
+	pub fn with_sorted_keys(mut self, sort_keys: bool) -> Self {
+		self.sort_keys = sort_keys;
+		self
+	}
+
+	/// Prints the given JSON value with this format.
+	pub fn print(&self, value: &json_syntax::Value) -> String {
+		if self.sort_keys {
+			let mut value = value.clone();
+			sort_keys(&mut value);
+			value.print_with(self.options.clone()).to_string()
+		} else {
+			value.print_with(self.options.clone()).to_string()
+		}
+	}
+}
+
+impl Default for Format {
+	fn default() -> Self {
+		Self::pretty()
+	}
+}
+
+/// Types with a deterministic JSON representation, used by their `Display`
+/// implementation and by [`DebugCompact`].
+pub trait ToDisplayJson {
+	/// Returns the JSON representation of this value.
+	///
+	/// Object entries are sorted by key when printed, so the output only
+	/// depends on the content of the value.
+	fn to_display_json(&self) -> json_syntax::Value;
+
+	/// Wraps this value so that it is debug-formatted as compact JSON.
+	fn debug_compact(&self) -> DebugCompact<'_, Self> {
+		DebugCompact(self)
+	}
+}
+
+impl<T: Clone, B: Clone> ToDisplayJson for ExpandedDocument<T, B>
+where
+	(): Vocabulary<Iri = T, BlankId = B>,
+{
+	fn to_display_json(&self) -> json_syntax::Value {
+		self.clone().into_json_with(&())
+	}
+}
+
+impl<T: Clone, B: Clone> ToDisplayJson for object::Node<T, B>
+where
+	(): Vocabulary<Iri = T, BlankId = B>,
+{
+	fn to_display_json(&self) -> json_syntax::Value {
+		self.clone().into_json_with(&())
+	}
+}
+
+impl<T: Clone + Eq + Hash, B: Clone + Eq + Hash> ToDisplayJson for NodeMap<T, B>
+where
+	(): Vocabulary<Iri = T, BlankId = B>,
+{
+	/// Returns the subject-centric JSON representation of the node map (see
+	/// [`NodeMap::to_json_map`]).
+	fn to_display_json(&self) -> json_syntax::Value {
+		self.to_json_map()
+	}
+}
+
+/// Compact debug formatting.
+///
+/// The `Debug` implementation of this wrapper prints the same JSON as the
+/// `Display` implementation of the wrapped value, on a single line instead of
+/// the derived nested structure dump. Use [`ToDisplayJson::debug_compact`] to
+/// create it.
+pub struct DebugCompact<'a, T: ?Sized>(&'a T);
+
+impl<'a, T: ?Sized + ToDisplayJson> fmt::Debug for DebugCompact<'a, T> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let format = Format::compact().with_sorted_keys(true);
+		f.write_str(&format.print(&self.0.to_display_json()))
+	}
+}
+
+fn fmt_display_json(value: &impl ToDisplayJson, f: &mut fmt::Formatter) -> fmt::Result {
+	let format = Format::pretty().with_sorted_keys(true);
+	f.write_str(&format.print(&value.to_display_json()))
+}
+
+impl<T: Clone, B: Clone> fmt::Display for ExpandedDocument<T, B>
+where
+	(): Vocabulary<Iri = T, BlankId = B>,
+{
+	/// Prints the expanded document as pretty JSON, with sorted keys.
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		fmt_display_json(self, f)
+	}
+}
+
+impl<T: Clone, B: Clone> fmt::Display for object::Node<T, B>
+where
+	(): Vocabulary<Iri = T, BlankId = B>,
+{
+	/// Prints the node object as pretty JSON, with sorted keys.
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		fmt_display_json(self, f)
+	}
+}
+
+impl<T: Clone + Eq + Hash, B: Clone + Eq + Hash> fmt::Display for NodeMap<T, B>
+where
+	(): Vocabulary<Iri = T, BlankId = B>,
+{
+	/// Prints the subject-centric JSON representation of the node map as
+	/// pretty JSON, with sorted keys.
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		fmt_display_json(self, f)
+	}
+}
+
+fn sort_keys(value: &mut json_syntax::Value) {
+	match value {
+		json_syntax::Value::Array(items) => items.iter_mut().for_each(sort_keys),
+		json_syntax::Value::Object(object) => {
+			for (_, item) in object.iter_mut() {
+				sort_keys(item)
+			}
+
+			object.sort()
+		}
+		_ => (),
+	}
+}
 
 pub trait PrintWithSizeAndVocabulary<V> {
 	fn fmt_with_size_and(
@@ -662,3 +856,63 @@ impl<T, B, N: Vocabulary<Iri = T, BlankId = B>> PrintWithSizeAndContext<N>
 		)
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn format() {
+		let value = json_syntax::json!({ "b": [1, 2], "a": { "d": true, "c": null } });
+
+		assert_eq!(
+			Format::compact().print(&value),
+			r#"{"b":[1,2],"a":{"d":true,"c":null}}"#
+		);
+		assert_eq!(
+			Format::compact().with_sorted_keys(true).print(&value),
+			r#"{"a":{"c":null,"d":true},"b":[1,2]}"#
+		);
+		assert_eq!(
+			Format::pretty()
+				.with_indent(Indent::Tabs(1))
+				.with_limit(Some(Limit::Always))
+				.print(&json_syntax::json!({ "a": [1] })),
+			"{\n\t\"a\": [\n\t\t1\n\t]\n}"
+		)
+	}
+
+	#[test]
+	fn display() {
+		use crate::{Indexed, Node};
+		use static_iref::iri;
+
+		let mut node: Node = Node::with_id(Id::iri(iri!("https://example.org/a").to_owned()));
+		node.insert(
+			Id::iri(iri!("https://example.org/name").to_owned()),
+			Indexed::none(Object::Value(object::Value::Literal(
+				object::Literal::String("A".into()),
+				None,
+			))),
+		);
+		node.insert(
+			Id::iri(iri!("https://example.org/knows").to_owned()),
+			Indexed::none(Object::node(Node::with_id(Id::iri(
+				iri!("https://example.org/b").to_owned(),
+			)))),
+		);
+
+		assert_eq!(
+			format!("{:?}", node.debug_compact()),
+			r#"{"@id":"https://example.org/a","https://example.org/knows":[{"@id":"https://example.org/b"}],"https://example.org/name":[{"@value":"A"}]}"#
+		);
+
+		let document = ExpandedDocument::from(Indexed::none(node));
+		assert_eq!(
+			document.to_string(),
+			Format::pretty()
+				.with_sorted_keys(true)
+				.print(&document.to_display_json())
+		)
+	}
+}
@@ -0,0 +1,134 @@
+//! Progress reporting, cancellation and cooperative yielding of
+//! long-running operations.
+use std::{
+	future::Future,
+	pin::Pin,
+	sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+	task::{Context, Poll},
+};
+
+/// Progress of a long-running operation (expansion, compaction or
+/// flattening of a large document).
+///
+/// The operation reports its progress to the optional callback, and checks
+/// for cancellation, after each top-level item of the document. Cancellation
+/// is cooperative: once [`Progress::cancel`] is called (for instance from
+/// another thread, sharing the `Progress` with an `Arc`), the operation
+/// stops before the next top-level item and fails with [`Cancelled`].
+#[derive(Default)]
+pub struct Progress {
+	cancelled: AtomicBool,
+	callback: Option<Box<dyn Fn(usize, usize) + Send + Sync>>,
+}
+
+impl Progress {
+	/// Creates a new progress handle, without callback.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Creates a new progress handle calling `f` with the number of processed
+	/// top-level items and the total number of top-level items.
+	pub fn with_callback(f: impl Fn(usize, usize) + Send + Sync + 'static) -> Self {
+		Self {
+			cancelled: AtomicBool::new(false),
+			callback: Some(Box::new(f)),
+		}
+	}
+
+	/// Requests the cancellation of the operation.
+	pub fn cancel(&self) {
+		self.cancelled.store(true, Ordering::Relaxed)
+	}
+
+	/// Checks if the cancellation of the operation was requested.
+	pub fn is_cancelled(&self) -> bool {
+		self.cancelled.load(Ordering::Relaxed)
+	}
+
+	/// Fails with [`Cancelled`] if the cancellation of the operation was
+	/// requested.
+	pub fn check(&self) -> Result<(), Cancelled> {
+		if self.is_cancelled() {
+			Err(Cancelled)
+		} else {
+			Ok(())
+		}
+	}
+
+	/// Reports that `processed` out of `total` top-level items are processed.
+	pub fn report(&self, processed: usize, total: usize) {
+		if let Some(f) = &self.callback {
+			f(processed, total)
+		}
+	}
+}
+
+impl std::fmt::Debug for Progress {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		f.debug_struct("Progress")
+			.field("cancelled", &self.is_cancelled())
+			.finish_non_exhaustive()
+	}
+}
+
+/// Error raised by operations cancelled with [`Progress::cancel`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error("operation cancelled")]
+pub struct Cancelled;
+
+/// Cooperative yielding of a long-running operation.
+///
+/// Counts the items processed by the whole operation (expansion, compaction
+/// or flattening of a document), and yields to the async executor every time
+/// the given number of items is processed, so that CPU-bound operations do
+/// not starve the executor they run on. The count is shared by all the
+/// arrays of the document: many small arrays yield as often as a big one.
+#[derive(Debug, Default)]
+pub struct Yielder {
+	every: Option<usize>,
+	processed: AtomicUsize,
+}
+
+impl Yielder {
+	/// Creates a new yielder, yielding every `every` processed items.
+	///
+	/// The yielder never yields if `every` is `None` or zero.
+	pub fn new(every: Option<usize>) -> Self {
+		Self {
+			every,
+			processed: AtomicUsize::new(0),
+		}
+	}
+
+	/// Counts a processed item, yielding to the async executor if the number
+	/// of processed items is a multiple of the configured period.
+	pub async fn tick(&self) {
+		if let Some(every) = self.every {
+			let processed = self.processed.fetch_add(1, Ordering::Relaxed) + 1;
+			if processed.checked_rem(every) == Some(0) {
+				YieldNow(false).await
+			}
+		}
+	}
+}
+
+/// Future yielding once to the executor before completing.
+///
+/// Executor agnostic: the task is immediately woken up again, but other
+/// tasks get a chance to be polled in the meantime.
+struct YieldNow(bool);
+
+impl Future for YieldNow {
+	type Output = ();
+
+	fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<()> {
+		if self.0 {
+			Poll::Ready(())
+		} else {
+			self.0 = true;
+			cx.waker().wake_by_ref();
+			Poll::Pending
+		}
+	}
+}
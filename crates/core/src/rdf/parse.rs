@@ -0,0 +1,1003 @@
+//! Turtle, N-Triples and N-Quads parsing.
+#[cfg(feature = "linked-data")]
+use super::FromRdfOptions;
+use super::{RDF_FIRST, RDF_NIL, RDF_REST, RDF_TYPE};
+use super::{XSD_BOOLEAN, XSD_DOUBLE, XSD_INTEGER, XSD_STRING};
+#[cfg(feature = "linked-data")]
+use crate::{serialization::SerializationError, ExpandedDocument};
+use iref::{Iri, IriBuf, IriRef};
+use langtag::LangTagBuf;
+use rdf_types::{BlankIdBuf, Literal, LiteralType, Quad, Term};
+use static_iref::iri;
+use std::collections::{HashMap, HashSet};
+
+const XSD_DECIMAL: &Iri = iri!("http://www.w3.org/2001/XMLSchema#decimal");
+
+/// Maximum nesting depth of Turtle blank node property lists and
+/// collections.
+const MAX_NESTING: usize = 256;
+
+/// RDF syntax.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RdfSyntax {
+	/// [N-Triples](https://www.w3.org/TR/n-triples/).
+	NTriples,
+
+	/// [N-Quads](https://www.w3.org/TR/n-quads/).
+	NQuads,
+
+	/// [Turtle](https://www.w3.org/TR/turtle/).
+	Turtle,
+}
+
+impl RdfSyntax {
+	/// Returns the syntax with the given media type, if any.
+	pub fn from_media_type(media_type: &str) -> Option<Self> {
+		match media_type {
+			"application/n-triples" => Some(Self::NTriples),
+			"application/n-quads" => Some(Self::NQuads),
+			"text/turtle" => Some(Self::Turtle),
+			_ => None,
+		}
+	}
+
+	/// Returns the syntax with the given file extension, if any.
+	pub fn from_extension(extension: &str) -> Option<Self> {
+		match extension {
+			"nt" => Some(Self::NTriples),
+			"nq" => Some(Self::NQuads),
+			"ttl" => Some(Self::Turtle),
+			_ => None,
+		}
+	}
+
+	/// Returns the media type of the syntax.
+	pub fn media_type(&self) -> &'static str {
+		match self {
+			Self::NTriples => "application/n-triples",
+			Self::NQuads => "application/n-quads",
+			Self::Turtle => "text/turtle",
+		}
+	}
+}
+
+/// RDF parse error.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("{kind} at line {line}, column {column}")]
+pub struct RdfParseError {
+	/// Line of the error, starting from 1.
+	pub line: usize,
+
+	/// Column of the error, in characters, starting from 1.
+	pub column: usize,
+
+	/// Error kind.
+	pub kind: RdfParseErrorKind,
+}
+
+/// RDF parse error kind.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum RdfParseErrorKind {
+	#[error("unexpected character `{0}`")]
+	UnexpectedChar(char),
+
+	#[error("unexpected end of input")]
+	UnexpectedEnd,
+
+	#[error("unexpected token")]
+	UnexpectedToken,
+
+	#[error("invalid escape sequence")]
+	InvalidEscape,
+
+	#[error("invalid IRI `{0}`")]
+	InvalidIri(String),
+
+	#[error("undefined prefix `{0}`")]
+	UndefinedPrefix(String),
+
+	#[error("invalid blank node identifier `{0}`")]
+	InvalidBlankId(String),
+
+	#[error("invalid language tag `{0}`")]
+	InvalidLangTag(String),
+
+	#[error("too many nested blank node property lists or collections")]
+	TooDeep,
+}
+
+/// Error returned by [`ExpandedDocument::from_rdf_str`].
+#[cfg(feature = "linked-data")]
+#[derive(Debug, thiserror::Error)]
+pub enum FromRdfStrError {
+	#[error(transparent)]
+	Parse(RdfParseError),
+
+	#[error(transparent)]
+	Serialization(SerializationError),
+}
+
+/// Parses the given RDF document into a list of quads.
+///
+/// Relative IRIs are resolved against the `base` IRI, which can be
+/// redefined in Turtle by `@base` directives. Anonymous Turtle blank nodes
+/// (`[]` and collections) are given fresh identifiers that do not conflict
+/// with the labeled ones.
+pub fn parse_rdf(
+	syntax: RdfSyntax,
+	input: &str,
+	base: Option<&Iri>,
+) -> Result<Vec<Quad<Term>>, RdfParseError> {
+	let error = |offset: usize, kind| {
+		let before = &input[..offset];
+		let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+		RdfParseError {
+			line: before.matches('\n').count() + 1,
+			column: before[line_start..].chars().count() + 1,
+			kind,
+		}
+	};
+
+	let tokens = Lexer::new(input)
+		.tokenize()
+		.map_err(|(offset, kind)| error(offset, kind))?;
+
+	let mut parser = Parser::new(&tokens, input.len(), base);
+	let result = match syntax {
+		RdfSyntax::NTriples => parser.n_quads(false),
+		RdfSyntax::NQuads => parser.n_quads(true),
+		RdfSyntax::Turtle => parser.turtle(),
+	};
+
+	result.map_err(|(offset, kind)| error(offset, kind))?;
+	Ok(parser.quads)
+}
+
+#[cfg(feature = "linked-data")]
+impl ExpandedDocument {
+	/// Parses the given RDF document and converts it into an expanded
+	/// JSON-LD document, using the given conversion options.
+	///
+	/// See [`parse_rdf`] for the meaning of `base`.
+	pub fn from_rdf_str(
+		syntax: RdfSyntax,
+		input: &str,
+		base: Option<&Iri>,
+		options: FromRdfOptions,
+	) -> Result<Self, FromRdfStrError> {
+		let quads = parse_rdf(syntax, input, base).map_err(FromRdfStrError::Parse)?;
+		Self::from_interpreted_quads_full(
+			&(),
+			&(),
+			quads
+				.iter()
+				.map(|Quad(s, p, o, g)| Quad(s, p, o, g.as_ref())),
+			linked_data::Context::default(),
+			options,
+		)
+		.map_err(FromRdfStrError::Serialization)
+	}
+}
+
+type Error = (usize, RdfParseErrorKind);
+
+#[derive(Debug, PartialEq)]
+enum Token {
+	IriRef(String),
+	PrefixedName(String, String),
+	BlankLabel(String),
+	String(String),
+	LangTag(String),
+	Integer(String),
+	Decimal(String),
+	Double(String),
+	/// `@prefix` or `@base`.
+	Directive(String),
+	/// `a`, `true`, `false`, `PREFIX` or `BASE`.
+	Keyword(String),
+	Punct(char),
+	/// `^^`.
+	DatatypeMarker,
+}
+
+struct Lexer<'a> {
+	input: &'a str,
+	offset: usize,
+}
+
+impl<'a> Lexer<'a> {
+	fn new(input: &'a str) -> Self {
+		Self { input, offset: 0 }
+	}
+
+	fn peek(&self) -> Option<char> {
+		self.input[self.offset..].chars().next()
+	}
+
+	fn peek_nth(&self, n: usize) -> Option<char> {
+		self.input[self.offset..].chars().nth(n)
+	}
+
+	fn next_char(&mut self) -> Result<char, Error> {
+		match self.peek() {
+			Some(c) => {
+				self.offset += c.len_utf8();
+				Ok(c)
+			}
+			None => Err((self.offset, RdfParseErrorKind::UnexpectedEnd)),
+		}
+	}
+
+	fn tokenize(mut self) -> Result<Vec<(usize, Token)>, Error> {
+		let mut tokens: Vec<(usize, Token)> = Vec::new();
+
+		loop {
+			self.skip_whitespace();
+
+			let start = self.offset;
+			let c = match self.peek() {
+				Some(c) => c,
+				None => break Ok(tokens),
+			};
+
+			let token = match c {
+				'<' => {
+					self.offset += 1;
+					Token::IriRef(self.iri_ref()?)
+				}
+				'"' | '\'' => Token::String(self.string(c)?),
+				'@' => {
+					self.offset += 1;
+					let name = self.take_while(|c| c.is_ascii_alphanumeric() || c == '-');
+					let after_string = matches!(tokens.last(), Some((_, Token::String(_))));
+					if !after_string && (name == "prefix" || name == "base") {
+						Token::Directive(name.to_owned())
+					} else if name.is_empty() {
+						return Err((start, RdfParseErrorKind::UnexpectedChar('@')));
+					} else {
+						Token::LangTag(name.to_owned())
+					}
+				}
+				'^' => {
+					self.offset += 1;
+					match self.next_char()? {
+						'^' => Token::DatatypeMarker,
+						c => {
+							return Err((
+								self.offset - c.len_utf8(),
+								RdfParseErrorKind::UnexpectedChar(c),
+							))
+						}
+					}
+				}
+				'_' if self.peek_nth(1) == Some(':') => {
+					self.offset += 2;
+					Token::BlankLabel(self.name()?)
+				}
+				'0'..='9' | '+' | '-' => self.number()?,
+				'.' if self.peek_nth(1).is_some_and(|c| c.is_ascii_digit()) => self.number()?,
+				'.' | ';' | ',' | '[' | ']' | '(' | ')' | '{' | '}' => {
+					self.offset += 1;
+					Token::Punct(c)
+				}
+				c if c == ':' || c.is_alphabetic() || c == '_' => {
+					let name = self.name()?;
+					match name.split_once(':') {
+						Some((prefix, local)) => {
+							Token::PrefixedName(prefix.to_owned(), local.to_owned())
+						}
+						None => Token::Keyword(name),
+					}
+				}
+				c => return Err((start, RdfParseErrorKind::UnexpectedChar(c))),
+			};
+
+			tokens.push((start, token))
+		}
+	}
+
+	fn skip_whitespace(&mut self) {
+		loop {
+			match self.peek() {
+				Some(c) if c.is_whitespace() => self.offset += c.len_utf8(),
+				Some('#') => {
+					self.take_while(|c| c != '\n');
+				}
+				_ => break,
+			}
+		}
+	}
+
+	fn take_while(&mut self, f: impl Fn(char) -> bool) -> &'a str {
+		let rest = &self.input[self.offset..];
+		let len = rest.find(|c| !f(c)).unwrap_or(rest.len());
+		self.offset += len;
+		&rest[..len]
+	}
+
+	/// Reads an IRI reference, after its `<`.
+	fn iri_ref(&mut self) -> Result<String, Error> {
+		let mut result = String::new();
+		loop {
+			match self.next_char()? {
+				'>' => break Ok(result),
+				'\\' => result.push(self.unicode_escape()?),
+				c if c.is_whitespace() || matches!(c, '<' | '"' | '{' | '}' | '|' | '^' | '`') => {
+					break Err((
+						self.offset - c.len_utf8(),
+						RdfParseErrorKind::UnexpectedChar(c),
+					))
+				}
+				c => result.push(c),
+			}
+		}
+	}
+
+	/// Reads a `\u` or `\U` escape sequence, after its `\`.
+	fn unicode_escape(&mut self) -> Result<char, Error> {
+		let start = self.offset - 1;
+		let len = match self.next_char()? {
+			'u' => 4,
+			'U' => 8,
+			_ => return Err((start, RdfParseErrorKind::InvalidEscape)),
+		};
+
+		let hex = self.input.get(self.offset..self.offset + len);
+		let c = hex
+			.filter(|hex| hex.chars().all(|c| c.is_ascii_hexdigit()))
+			.and_then(|hex| u32::from_str_radix(hex, 16).ok())
+			.and_then(char::from_u32)
+			.ok_or((start, RdfParseErrorKind::InvalidEscape))?;
+
+		self.offset += len;
+		Ok(c)
+	}
+
+	/// Reads a string literal, short or long.
+	fn string(&mut self, quote: char) -> Result<String, Error> {
+		let long = self.peek_nth(1) == Some(quote) && self.peek_nth(2) == Some(quote);
+		self.offset += if long { 3 } else { 1 };
+
+		let mut result = String::new();
+		loop {
+			let c = self.next_char()?;
+			match c {
+				c if c == quote => {
+					if !long {
+						break Ok(result);
+					}
+
+					if self.peek() == Some(quote) && self.peek_nth(1) == Some(quote) {
+						// Up to two quotes can precede the closing ones.
+						while self.peek_nth(2) == Some(quote) {
+							result.push(quote);
+							self.offset += 1
+						}
+
+						self.offset += 2;
+						break Ok(result);
+					}
+
+					result.push(c)
+				}
+				'\\' => {
+					let escaped = match self.peek() {
+						Some('t') => '\t',
+						Some('b') => '\u{8}',
+						Some('n') => '\n',
+						Some('r') => '\r',
+						Some('f') => '\u{c}',
+						Some('"') => '"',
+						Some('\'') => '\'',
+						Some('\\') => '\\',
+						Some('u' | 'U') => {
+							result.push(self.unicode_escape()?);
+							continue;
+						}
+						_ => return Err((self.offset - 1, RdfParseErrorKind::InvalidEscape)),
+					};
+
+					self.offset += 1;
+					result.push(escaped)
+				}
+				'\n' | '\r' if !long => {
+					break Err((self.offset - 1, RdfParseErrorKind::UnexpectedChar(c)))
+				}
+				c => result.push(c),
+			}
+		}
+	}
+
+	/// Reads a name: a blank node label, a prefixed name or a keyword.
+	///
+	/// Prefixed names local part escapes are decoded.
+	fn name(&mut self) -> Result<String, Error> {
+		let mut result = String::new();
+
+		while let Some(c) = self.peek() {
+			match c {
+				'\\' => {
+					self.offset += 1;
+					match self.next_char()? {
+						c @ ('_' | '~' | '.' | '-' | '!' | '$' | '&' | '\'' | '(' | ')' | '*'
+						| '+' | ',' | ';' | '=' | '/' | '?' | '#' | '@' | '%') => result.push(c),
+						_ => return Err((self.offset - 2, RdfParseErrorKind::InvalidEscape)),
+					}
+				}
+				'.' => {
+					// A name cannot end with a `.`.
+					match self.peek_nth(1) {
+						Some(next) if is_name_char(next) || next == '.' || next == '\\' => {
+							self.offset += 1;
+							result.push('.')
+						}
+						_ => break,
+					}
+				}
+				c if is_name_char(c) => {
+					self.offset += c.len_utf8();
+					result.push(c)
+				}
+				_ => break,
+			}
+		}
+
+		Ok(result)
+	}
+
+	fn number(&mut self) -> Result<Token, Error> {
+		let start = self.offset;
+
+		if matches!(self.peek(), Some('+' | '-')) {
+			self.offset += 1
+		}
+
+		self.take_while(|c| c.is_ascii_digit());
+
+		let mut decimal = false;
+		if self.peek() == Some('.') && self.peek_nth(1).is_some_and(|c| c.is_ascii_digit()) {
+			self.offset += 1;
+			self.take_while(|c| c.is_ascii_digit());
+			decimal = true
+		}
+
+		let mut double = false;
+		if matches!(self.peek(), Some('e' | 'E')) {
+			self.offset += 1;
+			if matches!(self.peek(), Some('+' | '-')) {
+				self.offset += 1
+			}
+
+			if self.take_while(|c| c.is_ascii_digit()).is_empty() {
+				return Err((self.offset, RdfParseErrorKind::UnexpectedToken));
+			}
+
+			double = true
+		}
+
+		let value = self.input[start..self.offset].to_owned();
+		if !value.contains(|c: char| c.is_ascii_digit()) {
+			return Err((start, RdfParseErrorKind::UnexpectedToken));
+		}
+
+		Ok(if double {
+			Token::Double(value)
+		} else if decimal {
+			Token::Decimal(value)
+		} else {
+			Token::Integer(value)
+		})
+	}
+}
+
+fn is_name_char(c: char) -> bool {
+	c.is_alphanumeric()
+		|| matches!(c, '_' | '-' | ':' | '%' | '\u{b7}')
+		|| (!c.is_ascii() && !c.is_whitespace())
+}
+
+struct Parser<'t> {
+	tokens: &'t [(usize, Token)],
+	position: usize,
+	end: usize,
+	base: Option<IriBuf>,
+	prefixes: HashMap<String, IriBuf>,
+
+	/// Labels used in the document, that fresh blank nodes must avoid.
+	labels: HashSet<&'t str>,
+	fresh_count: usize,
+
+	/// Current nesting depth of blank node property lists and collections.
+	depth: usize,
+
+	quads: Vec<Quad<Term>>,
+}
+
+impl<'t> Parser<'t> {
+	fn new(tokens: &'t [(usize, Token)], end: usize, base: Option<&Iri>) -> Self {
+		let labels = tokens
+			.iter()
+			.filter_map(|(_, token)| match token {
+				Token::BlankLabel(label) => Some(label.as_str()),
+				_ => None,
+			})
+			.collect();
+
+		Self {
+			tokens,
+			position: 0,
+			end,
+			base: base.map(ToOwned::to_owned),
+			prefixes: HashMap::new(),
+			labels,
+			fresh_count: 0,
+			depth: 0,
+			quads: Vec::new(),
+		}
+	}
+
+	fn peek(&self) -> Option<&'t Token> {
+		self.tokens.get(self.position).map(|(_, token)| token)
+	}
+
+	fn offset(&self) -> usize {
+		self.tokens
+			.get(self.position)
+			.map_or(self.end, |(offset, _)| *offset)
+	}
+
+	fn unexpected(&self) -> Error {
+		match self.peek() {
+			Some(_) => (self.offset(), RdfParseErrorKind::UnexpectedToken),
+			None => (self.end, RdfParseErrorKind::UnexpectedEnd),
+		}
+	}
+
+	fn next(&mut self) -> Result<&'t Token, Error> {
+		match self.peek() {
+			Some(token) => {
+				self.position += 1;
+				Ok(token)
+			}
+			None => Err(self.unexpected()),
+		}
+	}
+
+	fn expect_punct(&mut self, c: char) -> Result<(), Error> {
+		match self.peek() {
+			Some(Token::Punct(p)) if *p == c => {
+				self.position += 1;
+				Ok(())
+			}
+			_ => Err(self.unexpected()),
+		}
+	}
+
+	fn is_punct(&self, c: char) -> bool {
+		matches!(self.peek(), Some(Token::Punct(p)) if *p == c)
+	}
+
+	fn emit(&mut self, s: Term, p: Term, o: Term, g: Option<Term>) {
+		self.quads.push(Quad(s, p, o, g))
+	}
+
+	fn fresh(&mut self) -> Term {
+		loop {
+			let label = format!("b{}", self.fresh_count);
+			self.fresh_count += 1;
+			if !self.labels.contains(label.as_str()) {
+				break Term::blank(BlankIdBuf::from_suffix(&label).unwrap());
+			}
+		}
+	}
+
+	fn iri(&self, offset: usize, value: &str) -> Result<IriBuf, Error> {
+		let invalid = || (offset, RdfParseErrorKind::InvalidIri(value.to_owned()));
+		let iri_ref = IriRef::new(value).map_err(|_| invalid())?;
+		let iri = match &self.base {
+			Some(base) => iri_ref.resolved(base),
+			None => iri_ref.as_iri().ok_or_else(invalid)?.to_owned(),
+		};
+
+		Ok(iri)
+	}
+
+	fn blank(&self, offset: usize, label: &str) -> Result<Term, Error> {
+		BlankIdBuf::from_suffix(label)
+			.map(Term::blank)
+			.map_err(|_| (offset, RdfParseErrorKind::InvalidBlankId(label.to_owned())))
+	}
+
+	fn prefixed_name(&self, offset: usize, prefix: &str, local: &str) -> Result<IriBuf, Error> {
+		let namespace = self.prefixes.get(prefix).ok_or_else(|| {
+			(
+				offset,
+				RdfParseErrorKind::UndefinedPrefix(prefix.to_owned()),
+			)
+		})?;
+
+		let iri = format!("{namespace}{local}");
+		IriBuf::new(iri).map_err(|e| (offset, RdfParseErrorKind::InvalidIri(e.0)))
+	}
+
+	/// Parses the optional language tag or datatype following a string.
+	fn literal(&mut self, value: String) -> Result<Term, Error> {
+		let type_ = match self.peek() {
+			Some(Token::LangTag(tag)) => {
+				let offset = self.offset();
+				self.position += 1;
+				let tag = LangTagBuf::new(tag.clone())
+					.map_err(|e| (offset, RdfParseErrorKind::InvalidLangTag(e.0)))?;
+				LiteralType::LangString(tag)
+			}
+			Some(Token::DatatypeMarker) => {
+				self.position += 1;
+				let offset = self.offset();
+				let datatype = match self.next()? {
+					Token::IriRef(iri) => self.iri(offset, iri)?,
+					Token::PrefixedName(prefix, local) => {
+						self.prefixed_name(offset, prefix, local)?
+					}
+					_ => return Err((offset, RdfParseErrorKind::UnexpectedToken)),
+				};
+
+				LiteralType::Any(datatype)
+			}
+			_ => LiteralType::Any(XSD_STRING.to_owned()),
+		};
+
+		Ok(Term::Literal(Literal::new(value, type_)))
+	}
+
+	/// Parses an N-Triples or N-Quads document.
+	fn n_quads(&mut self, quads: bool) -> Result<(), Error> {
+		while self.peek().is_some() {
+			let subject = self.n_quads_resource()?;
+
+			let offset = self.offset();
+			let predicate = match self.next()? {
+				Token::IriRef(iri) => Term::iri(self.iri(offset, iri)?),
+				_ => return Err((offset, RdfParseErrorKind::UnexpectedToken)),
+			};
+
+			let object = match self.peek() {
+				Some(Token::String(value)) => {
+					self.position += 1;
+					self.literal(value.clone())?
+				}
+				_ => self.n_quads_resource()?,
+			};
+
+			let graph = if quads && !self.is_punct('.') {
+				Some(self.n_quads_resource()?)
+			} else {
+				None
+			};
+
+			self.expect_punct('.')?;
+			self.emit(subject, predicate, object, graph)
+		}
+
+		Ok(())
+	}
+
+	fn n_quads_resource(&mut self) -> Result<Term, Error> {
+		let offset = self.offset();
+		match self.next()? {
+			Token::IriRef(iri) => self.iri(offset, iri).map(Term::iri),
+			Token::BlankLabel(label) => self.blank(offset, label),
+			_ => Err((offset, RdfParseErrorKind::UnexpectedToken)),
+		}
+	}
+
+	/// Parses a Turtle document.
+	fn turtle(&mut self) -> Result<(), Error> {
+		while let Some(token) = self.peek() {
+			match token {
+				Token::Directive(name) => {
+					self.position += 1;
+					self.directive(name == "prefix")?;
+					self.expect_punct('.')?
+				}
+				Token::Keyword(name) if name.eq_ignore_ascii_case("prefix") => {
+					self.position += 1;
+					self.directive(true)?
+				}
+				Token::Keyword(name) if name.eq_ignore_ascii_case("base") => {
+					self.position += 1;
+					self.directive(false)?
+				}
+				_ => {
+					self.triples()?;
+					self.expect_punct('.')?
+				}
+			}
+		}
+
+		Ok(())
+	}
+
+	fn directive(&mut self, prefix: bool) -> Result<(), Error> {
+		let name = if prefix {
+			match self.next()? {
+				Token::PrefixedName(name, local) if local.is_empty() => Some(name),
+				_ => {
+					self.position -= 1;
+					return Err(self.unexpected());
+				}
+			}
+		} else {
+			None
+		};
+
+		let offset = self.offset();
+		let iri = match self.next()? {
+			Token::IriRef(iri) => self.iri(offset, iri)?,
+			_ => return Err((offset, RdfParseErrorKind::UnexpectedToken)),
+		};
+
+		match name {
+			Some(name) => {
+				self.prefixes.insert(name.clone(), iri);
+			}
+			None => self.base = Some(iri),
+		}
+
+		Ok(())
+	}
+
+	fn triples(&mut self) -> Result<(), Error> {
+		if self.is_punct('[') {
+			let subject = self.blank_node_property_list()?;
+			if !self.is_punct('.') {
+				self.predicate_object_list(&subject)?
+			}
+
+			return Ok(());
+		}
+
+		let subject = self.subject()?;
+		self.predicate_object_list(&subject)
+	}
+
+	fn subject(&mut self) -> Result<Term, Error> {
+		let offset = self.offset();
+		match self.peek() {
+			Some(Token::Punct('(')) => self.collection(),
+			_ => match self.next()? {
+				Token::IriRef(iri) => self.iri(offset, iri).map(Term::iri),
+				Token::PrefixedName(prefix, local) => {
+					self.prefixed_name(offset, prefix, local).map(Term::iri)
+				}
+				Token::BlankLabel(label) => self.blank(offset, label),
+				_ => Err((offset, RdfParseErrorKind::UnexpectedToken)),
+			},
+		}
+	}
+
+	fn predicate_object_list(&mut self, subject: &Term) -> Result<(), Error> {
+		loop {
+			let offset = self.offset();
+			let predicate = match self.next()? {
+				Token::IriRef(iri) => Term::iri(self.iri(offset, iri)?),
+				Token::PrefixedName(prefix, local) => {
+					Term::iri(self.prefixed_name(offset, prefix, local)?)
+				}
+				Token::Keyword(a) if a == "a" => Term::iri(RDF_TYPE.to_owned()),
+				_ => return Err((offset, RdfParseErrorKind::UnexpectedToken)),
+			};
+
+			loop {
+				let object = self.object()?;
+				self.emit(subject.clone(), predicate.clone(), object, None);
+
+				if self.is_punct(',') {
+					self.position += 1
+				} else {
+					break;
+				}
+			}
+
+			if !self.is_punct(';') {
+				break Ok(());
+			}
+
+			while self.is_punct(';') {
+				self.position += 1
+			}
+
+			if self.is_punct('.') || self.is_punct(']') {
+				break Ok(());
+			}
+		}
+	}
+
+	fn object(&mut self) -> Result<Term, Error> {
+		let offset = self.offset();
+		match self.peek() {
+			Some(Token::Punct('[')) => self.blank_node_property_list(),
+			Some(Token::Punct('(')) => self.collection(),
+			_ => match self.next()? {
+				Token::IriRef(iri) => self.iri(offset, iri).map(Term::iri),
+				Token::PrefixedName(prefix, local) => {
+					self.prefixed_name(offset, prefix, local).map(Term::iri)
+				}
+				Token::BlankLabel(label) => self.blank(offset, label),
+				Token::String(value) => self.literal(value.clone()),
+				Token::Integer(value) => Ok(typed(value, XSD_INTEGER)),
+				Token::Decimal(value) => Ok(typed(value, XSD_DECIMAL)),
+				Token::Double(value) => Ok(typed(value, XSD_DOUBLE)),
+				Token::Keyword(value) if value == "true" || value == "false" => {
+					Ok(typed(value, XSD_BOOLEAN))
+				}
+				_ => Err((offset, RdfParseErrorKind::UnexpectedToken)),
+			},
+		}
+	}
+
+	/// Enters a nested blank node property list or collection.
+	fn enter(&mut self) -> Result<(), Error> {
+		if self.depth == MAX_NESTING {
+			return Err((self.offset(), RdfParseErrorKind::TooDeep));
+		}
+
+		self.depth += 1;
+		Ok(())
+	}
+
+	/// Parses a `[ ... ]` blank node, returning it.
+	fn blank_node_property_list(&mut self) -> Result<Term, Error> {
+		self.enter()?;
+		self.expect_punct('[')?;
+		let node = self.fresh();
+
+		if !self.is_punct(']') {
+			self.predicate_object_list(&node)?
+		}
+
+		self.expect_punct(']')?;
+		self.depth -= 1;
+		Ok(node)
+	}
+
+	/// Parses a `( ... )` collection, returning its head.
+	fn collection(&mut self) -> Result<Term, Error> {
+		self.enter()?;
+		self.expect_punct('(')?;
+
+		let mut items = Vec::new();
+		while !self.is_punct(')') {
+			items.push(self.object()?)
+		}
+
+		self.position += 1;
+		self.depth -= 1;
+
+		let nodes: Vec<Term> = items.iter().map(|_| self.fresh()).collect();
+		let mut rest = nodes.iter().skip(1);
+		for (node, item) in nodes.iter().zip(items) {
+			let next = match rest.next() {
+				Some(next) => next.clone(),
+				None => Term::iri(RDF_NIL.to_owned()),
+			};
+
+			self.emit(node.clone(), Term::iri(RDF_FIRST.to_owned()), item, None);
+			self.emit(node.clone(), Term::iri(RDF_REST.to_owned()), next, None)
+		}
+
+		Ok(nodes
+			.into_iter()
+			.next()
+			.unwrap_or_else(|| Term::iri(RDF_NIL.to_owned())))
+	}
+}
+
+fn typed(value: &str, datatype: &Iri) -> Term {
+	Term::Literal(Literal::new(
+		value.to_owned(),
+		LiteralType::Any(datatype.to_owned()),
+	))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn turtle() {
+		let input = r#"
+			@prefix ex: <http://example.org/> .
+			PREFIX schema: <https://schema.org/>
+			@base <http://example.org/base/> .
+
+			<alice> a schema:Person ;
+				schema:name "Alice"@en, """Al "the" one""" ;
+				schema:age 42 ;
+				schema:knows [ schema:name 'Bob' ] ;
+				ex:list ( 1 2.5 true ) ;
+				ex:weight 1.2e3 . # comment
+			_:b0 ex:p "x"^^ex:type .
+		"#;
+
+		let quads = parse_rdf(RdfSyntax::Turtle, input, None).unwrap();
+		assert_eq!(quads.len(), 15);
+
+		let alice: Term =
+			Term::iri(IriBuf::new("http://example.org/base/alice".to_owned()).unwrap());
+		assert!(quads.iter().all(|Quad(_, _, _, g)| g.is_none()));
+		assert_eq!(quads[0].0, alice);
+		assert_eq!(quads[0].1, Term::<rdf_types::Id>::iri(RDF_TYPE.to_owned()));
+
+		let strings: Vec<_> = quads
+			.iter()
+			.filter_map(|Quad(_, _, o, _)| o.as_literal())
+			.map(|l| l.value.as_str())
+			.collect();
+		assert_eq!(
+			strings,
+			[
+				"Alice",
+				"Al \"the\" one",
+				"42",
+				"Bob",
+				"1",
+				"2.5",
+				"true",
+				"1.2e3",
+				"x"
+			]
+		);
+
+		// Fresh blank nodes avoid `_:b0`.
+		let b0: Term = Term::blank(BlankIdBuf::from_suffix("b0").unwrap());
+		assert_eq!(quads.iter().filter(|Quad(s, _, _, _)| *s == b0).count(), 1);
+	}
+
+	#[test]
+	fn n_quads() {
+		let input = "<http://a> <http://p> \"v\\n\"@fr <http://g> .\n_:x <http://p> <http://b> .\n";
+		let quads = parse_rdf(RdfSyntax::NQuads, input, None).unwrap();
+		assert_eq!(quads.len(), 2);
+		assert!(quads[0].3.is_some());
+		assert!(quads[1].3.is_none());
+
+		let error = parse_rdf(RdfSyntax::NTriples, input, None).unwrap_err();
+		assert_eq!((error.line, error.column), (1, 32));
+	}
+
+	#[test]
+	fn deep_nesting() {
+		let nested = |open: &str, close: &str, depth: usize| {
+			format!(
+				"<http://a> <http://p> {}<http://b>{} .",
+				open.repeat(depth),
+				close.repeat(depth)
+			)
+		};
+
+		for (open, close) in [("[ <http://p> ", " ]"), ("( ", " )")] {
+			assert!(parse_rdf(RdfSyntax::Turtle, &nested(open, close, MAX_NESTING), None).is_ok());
+
+			let error =
+				parse_rdf(RdfSyntax::Turtle, &nested(open, close, 100_000), None).unwrap_err();
+			assert_eq!(error.kind, RdfParseErrorKind::TooDeep)
+		}
+	}
+
+	#[cfg(feature = "linked-data")]
+	#[test]
+	fn from_rdf_str() {
+		let input = "<http://a> <http://p> ( \"x\" ) .";
+		let document = ExpandedDocument::from_rdf_str(
+			RdfSyntax::Turtle,
+			input,
+			None,
+			FromRdfOptions::default(),
+		)
+		.unwrap();
+		assert_eq!(document.len(), 1);
+		let node = document.iter().next().unwrap().as_node().unwrap();
+		assert_eq!(node.id.as_ref().unwrap().as_str(), "http://a");
+		assert_eq!(node.properties().len(), 1)
+	}
+}
@@ -0,0 +1,289 @@
+use super::{RdfDirection, RdfQuads, ValidId, Value};
+use crate::{ExpandedDocument, Id, Indexed, IndexedObject, Node, Object};
+use rdf_types::{
+	vocabulary::{IriVocabularyMut, LiteralVocabularyMut},
+	Generator, Vocabulary,
+};
+use std::hash::Hash;
+
+/// RDF-star term.
+///
+/// Either a node identifier, a literal or a quoted triple.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum StarTerm<T, B, L> {
+	/// Node identifier.
+	Id(ValidId<T, B>),
+
+	/// Literal value.
+	Literal(L),
+
+	/// Quoted triple.
+	Triple(Box<StarTriple<T, B, L>>),
+}
+
+impl<T, B, L> StarTerm<T, B, L> {
+	/// Checks if this term is a quoted triple.
+	pub fn is_triple(&self) -> bool {
+		matches!(self, Self::Triple(_))
+	}
+
+	/// Replaces every occurrence of `id` in this term (including inside
+	/// quoted triples) with `term`.
+	fn substitute(&mut self, id: &ValidId<T, B>, term: &Self)
+	where
+		T: Clone + PartialEq,
+		B: Clone + PartialEq,
+		L: Clone,
+	{
+		match self {
+			Self::Id(i) if i == id => *self = term.clone(),
+			Self::Triple(triple) => {
+				triple.0.substitute(id, term);
+				triple.2.substitute(id, term)
+			}
+			_ => (),
+		}
+	}
+}
+
+impl<T, B, L> From<Value<T, B, L>> for StarTerm<T, B, L> {
+	fn from(value: Value<T, B, L>) -> Self {
+		match value {
+			Value::Id(id) => Self::Id(id),
+			Value::Literal(l) => Self::Literal(l),
+		}
+	}
+}
+
+/// RDF-star triple, where the subject and object may be quoted triples.
+pub type StarTriple<T, B, L> =
+	rdf_types::Triple<StarTerm<T, B, L>, ValidId<T, B>, StarTerm<T, B, L>>;
+
+/// RDF-star quad, where the subject and object may be quoted triples.
+pub type StarQuad<T, B, L> =
+	rdf_types::Quad<StarTerm<T, B, L>, ValidId<T, B>, StarTerm<T, B, L>, ValidId<T, B>>;
+
+impl<T: Clone + Eq + Hash, B: Clone + Eq + Hash> ExpandedDocument<T, B> {
+	/// Returns the RDF-star quads describing the annotations (`@annotation`)
+	/// of this document (experimental JSON-LD-star support).
+	///
+	/// Each annotation node is turned into quads whose subject is the quoted
+	/// triple formed by the annotated subject, property and value. The
+	/// annotated statements themselves are not included, since they are
+	/// already part of the quads returned by [`RdfQuads`].
+	///
+	/// Annotations on anonymous nodes, or on list objects, are ignored. Use
+	/// [`Self::identify_all_with`] first to give an identifier to every node.
+	pub fn rdf_star_annotation_quads<V, G: Generator<V>>(
+		&self,
+		vocabulary: &mut V,
+		generator: &mut G,
+		rdf_direction: Option<RdfDirection>,
+		produce_generalized_rdf: bool,
+	) -> Vec<StarQuad<T, B, V::Literal>>
+	where
+		V: Vocabulary<Iri = T, BlankId = B> + IriVocabularyMut + LiteralVocabularyMut,
+		V::Literal: Clone,
+	{
+		let mut nodes = Vec::new();
+		for object in self {
+			collect_annotated_nodes(object, None, &mut nodes)
+		}
+
+		let mut result = Vec::new();
+		for (graph, node) in nodes {
+			annotation_quads(
+				vocabulary,
+				generator,
+				rdf_direction,
+				produce_generalized_rdf,
+				graph,
+				node,
+				&mut result,
+			)
+		}
+
+		result
+	}
+}
+
+/// Annotated nodes, with the graph they belong to.
+type AnnotatedNodes<'a, T, B> = Vec<(Option<&'a ValidId<T, B>>, &'a Node<T, B>)>;
+
+/// Collects every node of `object` having annotations, with the graph it
+/// belongs to.
+fn collect_annotated_nodes<'a, T, B>(
+	object: &'a IndexedObject<T, B>,
+	graph: Option<&'a ValidId<T, B>>,
+	result: &mut AnnotatedNodes<'a, T, B>,
+) {
+	match object.inner() {
+		Object::Node(node) => collect_annotated_nodes_in(node, graph, result),
+		Object::List(list) => {
+			for item in list {
+				collect_annotated_nodes(item, graph, result)
+			}
+		}
+		Object::Value(_) => (),
+	}
+}
+
+fn collect_annotated_nodes_in<'a, T, B>(
+	node: &'a Node<T, B>,
+	graph: Option<&'a ValidId<T, B>>,
+	result: &mut AnnotatedNodes<'a, T, B>,
+) {
+	if node.annotations().is_some() {
+		result.push((graph, node))
+	}
+
+	for (_, objects) in node.properties() {
+		for object in objects {
+			collect_annotated_nodes(object, graph, result)
+		}
+	}
+
+	if let Some(reverse_properties) = node.reverse_properties() {
+		for (_, nodes) in reverse_properties {
+			for node in nodes {
+				collect_annotated_nodes_in(node, graph, result)
+			}
+		}
+	}
+
+	if let Some(included) = node.included() {
+		for node in included {
+			collect_annotated_nodes_in(node, graph, result)
+		}
+	}
+
+	if let Some(objects) = node.graph() {
+		let graph = node.id.as_ref().and_then(valid_id);
+		for object in objects {
+			collect_annotated_nodes(object, graph, result)
+		}
+	}
+}
+
+fn valid_id<T, B>(id: &Id<T, B>) -> Option<&ValidId<T, B>> {
+	match id {
+		Id::Valid(id) => Some(id),
+		Id::Invalid(_) => None,
+	}
+}
+
+/// Produces the quads describing the annotations of `node`.
+fn annotation_quads<T, B, V, G: Generator<V>>(
+	vocabulary: &mut V,
+	generator: &mut G,
+	rdf_direction: Option<RdfDirection>,
+	produce_generalized_rdf: bool,
+	graph: Option<&ValidId<T, B>>,
+	node: &Node<T, B>,
+	result: &mut Vec<StarQuad<T, B, V::Literal>>,
+) where
+	T: Clone + Eq + Hash,
+	B: Clone + Eq + Hash,
+	V: Vocabulary<Iri = T, BlankId = B> + IriVocabularyMut + LiteralVocabularyMut,
+	V::Literal: Clone,
+{
+	let subject = match node.id.as_ref().and_then(valid_id) {
+		Some(id) => id,
+		None => return,
+	};
+
+	for annotation in node.annotations().into_iter().flatten() {
+		let property = match valid_id(&annotation.property) {
+			Some(id) => id,
+			None => continue,
+		};
+
+		if !produce_generalized_rdf && property.is_blank() {
+			// The quoted triple would be a gRDF triple.
+			continue;
+		}
+
+		if annotation.object.is_list() {
+			continue;
+		}
+
+		let object = match annotation
+			.object
+			.rdf_value_with(vocabulary, generator, rdf_direction)
+		{
+			Some(compound) => compound.value,
+			None => continue,
+		};
+
+		let quoted = StarTerm::Triple(Box::new(rdf_types::Triple(
+			StarTerm::Id(subject.clone()),
+			property.clone(),
+			object.into(),
+		)));
+
+		for annotation_node in &annotation.nodes {
+			// The annotation node is labeled with a fresh identifier, which is
+			// then substituted with the quoted triple.
+			let label: ValidId<T, B> = generator.next(vocabulary);
+			let mut annotation_node = annotation_node.inner().clone();
+			annotation_node.id = Some(label.clone().into());
+
+			let mut document = ExpandedDocument::new();
+			document.insert(Indexed::none(Object::node(annotation_node)));
+			document.identify_all_with(vocabulary, generator);
+
+			let quads: Vec<_> = document
+				.rdf_quads_full(
+					vocabulary,
+					generator,
+					rdf_direction,
+					produce_generalized_rdf,
+				)
+				.cloned()
+				.collect();
+			let nested = document.rdf_star_annotation_quads(
+				vocabulary,
+				generator,
+				rdf_direction,
+				produce_generalized_rdf,
+			);
+
+			let quads = quads.into_iter().map(|rdf_types::Quad(s, p, o, g)| {
+				rdf_types::Quad(StarTerm::Id(s), p, o.into(), g)
+			});
+
+			for rdf_types::Quad(mut s, p, mut o, g) in quads.chain(nested) {
+				s.substitute(&label, &quoted);
+				o.substitute(&label, &quoted);
+				result.push(rdf_types::Quad(s, p, o, g.or_else(|| graph.cloned())))
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::StarTerm;
+	use crate::{ExpandedDocument, TryFromJson};
+	use iref::IriBuf;
+	use rdf_types::{generator, BlankIdBuf};
+
+	#[test]
+	fn annotation_quads() {
+		let json = json_syntax::json!([{
+			"@id": "https://example.com/bob",
+			"https://example.com/age": [{
+				"@value": 23,
+				"@annotation": [{ "https://example.com/certainty": [{ "@value": 0.8 }] }]
+			}]
+		}]);
+		let document: ExpandedDocument<IriBuf, BlankIdBuf> =
+			ExpandedDocument::try_from_json_in(&mut (), json).unwrap();
+
+		let quads =
+			document.rdf_star_annotation_quads(&mut (), &mut generator::Blank::new(), None, false);
+		assert_eq!(quads.len(), 1);
+		assert!(quads[0].0.is_triple());
+		assert!(matches!(&quads[0].2, StarTerm::Literal(_)))
+	}
+}
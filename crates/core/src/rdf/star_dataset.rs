@@ -0,0 +1,568 @@
+use super::{
+	i18n_parts, is_rdf_type, literal_value, reference, RdfDirection, RdfQuads, StarQuad, StarTerm,
+	StarTriple, ValidId, RDF_FIRST, RDF_JSON, RDF_NIL, RDF_REST,
+};
+use crate::{
+	object::{node::Annotation, List},
+	ExpandedDocument, Id, Indexed, IndexedObject, LangString, Node, Object,
+};
+use indexmap::IndexMap;
+use iref::Iri;
+use json_syntax::Parse;
+use rdf_types::{
+	vocabulary::{IriVocabulary, IriVocabularyMut, LiteralVocabularyMut},
+	Generator, LiteralType, Vocabulary,
+};
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Nodes of each graph of an RDF-star dataset, by identifier.
+type Graphs<T, B> = IndexMap<Option<ValidId<T, B>>, IndexMap<ValidId<T, B>, Node<T, B>>>;
+
+impl<T: Clone + Eq + Hash, B: Clone + Eq + Hash> ExpandedDocument<T, B> {
+	/// Returns the RDF-star dataset described by this document.
+	///
+	/// This includes every quad returned by [`RdfQuads`], followed by the
+	/// quads describing the annotations of the document (see
+	/// [`Self::rdf_star_annotation_quads`]).
+	pub fn rdf_star_quads_full<V, G: Generator<V>>(
+		&self,
+		vocabulary: &mut V,
+		generator: &mut G,
+		rdf_direction: Option<RdfDirection>,
+		produce_generalized_rdf: bool,
+	) -> Vec<StarQuad<T, B, V::Literal>>
+	where
+		V: Vocabulary<Iri = T, BlankId = B> + IriVocabularyMut + LiteralVocabularyMut,
+		V::Literal: Clone,
+	{
+		let mut result: Vec<_> = self
+			.rdf_quads_full(
+				vocabulary,
+				generator,
+				rdf_direction,
+				produce_generalized_rdf,
+			)
+			.cloned()
+			.map(|rdf_types::Quad(s, p, o, g)| rdf_types::Quad(StarTerm::Id(s), p, o.into(), g))
+			.collect();
+
+		result.extend(self.rdf_star_annotation_quads(
+			vocabulary,
+			generator,
+			rdf_direction,
+			produce_generalized_rdf,
+		));
+
+		result
+	}
+
+	/// Builds an expanded document from an RDF-star dataset, using the given
+	/// direction representation method.
+	///
+	/// Quads whose subject is a quoted triple are turned into annotations
+	/// (`@annotation`) of the quoted statement. Quads with a quoted triple
+	/// as object, or nested quoted triples, cannot be represented and are
+	/// ignored.
+	///
+	/// Literals are converted without using native types: every non-string
+	/// literal becomes a typed value object, except `rdf:JSON` literals,
+	/// which become JSON literals (unless their lexical form is not valid
+	/// JSON), and `https://www.w3.org/ns/i18n#` literals, which become
+	/// language-tagged strings with a direction if `rdf_direction` is
+	/// [`RdfDirection::I18nDatatype`]. Compound literal nodes are kept as is.
+	///
+	/// Well-formed RDF lists (chains of blank nodes with a single `rdf:first`
+	/// and `rdf:rest` value, each referenced once, ending with `rdf:nil`)
+	/// become list objects, and references to `rdf:nil` become empty lists,
+	/// as in the RDF to JSON-LD conversion algorithm. Lists whose head is
+	/// quoted by an annotated triple are kept as nodes.
+	///
+	/// Quads with a blank node predicate, as found in generalized RDF
+	/// datasets, are accepted: the predicate becomes a blank node property
+	/// identifier.
+	pub fn from_rdf_star_quads_full<V>(
+		vocabulary: &V,
+		quads: impl IntoIterator<Item = StarQuad<T, B, rdf_types::Literal<T>>>,
+		rdf_direction: Option<RdfDirection>,
+	) -> Self
+	where
+		V: IriVocabulary<Iri = T>,
+	{
+		let mut graphs: Graphs<T, B> = IndexMap::new();
+		graphs.insert(None, IndexMap::new());
+
+		for rdf_types::Quad(subject, property, object, graph) in quads {
+			let nodes = graphs.entry(graph).or_default();
+
+			let node = match subject {
+				StarTerm::Id(id) => node_mut(nodes, id),
+				StarTerm::Triple(triple) => {
+					match annotation_node_mut(vocabulary, nodes, *triple, rdf_direction) {
+						Some(node) => node,
+						None => continue,
+					}
+				}
+				StarTerm::Literal(_) => continue,
+			};
+
+			match object {
+				StarTerm::Id(id) if is_rdf_type(vocabulary, &property) => {
+					node.types_mut_or_default().push(Id::Valid(id))
+				}
+				StarTerm::Id(id) => node.insert(Id::Valid(property), reference(id)),
+				StarTerm::Literal(l) => node.insert(
+					Id::Valid(property),
+					star_literal_value(vocabulary, l, rdf_direction),
+				),
+				StarTerm::Triple(_) => (),
+			}
+		}
+
+		for nodes in graphs.values_mut() {
+			convert_lists(vocabulary, nodes)
+		}
+
+		let mut default_graph = graphs.shift_remove(&None).unwrap_or_default();
+		for (id, nodes) in graphs {
+			let id = id.unwrap();
+			let graph = nodes
+				.into_values()
+				.map(|node| Indexed::none(Object::node(node)))
+				.collect();
+			node_mut(&mut default_graph, id).set_graph_entry(Some(graph))
+		}
+
+		default_graph
+			.into_values()
+			.map(|node| Indexed::none(Object::node(node)))
+			.collect()
+	}
+
+	/// Builds an expanded document from an RDF-star dataset.
+	///
+	/// `https://www.w3.org/ns/i18n#` literals are kept as is. See
+	/// [`Self::from_rdf_star_quads_full`].
+	pub fn from_rdf_star_quads_with<V>(
+		vocabulary: &V,
+		quads: impl IntoIterator<Item = StarQuad<T, B, rdf_types::Literal<T>>>,
+	) -> Self
+	where
+		V: IriVocabulary<Iri = T>,
+	{
+		Self::from_rdf_star_quads_full(vocabulary, quads, None)
+	}
+
+	/// Builds an expanded document from an RDF-star dataset.
+	///
+	/// See [`Self::from_rdf_star_quads_with`].
+	pub fn from_rdf_star_quads(
+		quads: impl IntoIterator<Item = StarQuad<T, B, rdf_types::Literal<T>>>,
+	) -> Self
+	where
+		(): IriVocabulary<Iri = T>,
+	{
+		Self::from_rdf_star_quads_with(&(), quads)
+	}
+}
+
+fn node_mut<T: Clone + Eq + Hash, B: Clone + Eq + Hash>(
+	nodes: &mut IndexMap<ValidId<T, B>, Node<T, B>>,
+	id: ValidId<T, B>,
+) -> &mut Node<T, B> {
+	nodes
+		.entry(id.clone())
+		.or_insert_with(|| Node::with_id(Id::Valid(id)))
+}
+
+/// Returns the annotation node describing the given quoted triple, creating
+/// the annotation if necessary.
+fn annotation_node_mut<'n, T, B, V>(
+	vocabulary: &V,
+	nodes: &'n mut IndexMap<ValidId<T, B>, Node<T, B>>,
+	rdf_types::Triple(subject, property, object): StarTriple<T, B, rdf_types::Literal<T>>,
+	rdf_direction: Option<RdfDirection>,
+) -> Option<&'n mut Node<T, B>>
+where
+	T: Clone + Eq + Hash,
+	B: Clone + Eq + Hash,
+	V: IriVocabulary<Iri = T>,
+{
+	// Types cannot be annotated in JSON-LD-star.
+	if is_rdf_type(vocabulary, &property) {
+		return None;
+	}
+
+	let subject = match subject {
+		StarTerm::Id(id) => id,
+		_ => return None,
+	};
+
+	let object = match object {
+		StarTerm::Id(id) => reference(id),
+		StarTerm::Literal(l) => star_literal_value(vocabulary, l, rdf_direction),
+		StarTerm::Triple(_) => return None,
+	};
+
+	let property = Id::Valid(property);
+	let annotations = node_mut(nodes, subject).annotations_mut_or_default();
+
+	let i = match annotations
+		.iter()
+		.position(|a| a.property == property && a.object == object)
+	{
+		Some(i) => i,
+		None => {
+			annotations.push(Annotation::new(property, object, Vec::new()));
+			annotations.len() - 1
+		}
+	};
+
+	let annotation_nodes = &mut annotations[i].nodes;
+	if annotation_nodes.is_empty() {
+		annotation_nodes.push(Indexed::none(Node::new()))
+	}
+
+	Some(annotation_nodes[0].inner_mut())
+}
+
+/// Converts an RDF literal into a JSON-LD value, decoding `rdf:JSON` and
+/// `https://www.w3.org/ns/i18n#` literals.
+fn star_literal_value<T, B>(
+	vocabulary: &impl IriVocabulary<Iri = T>,
+	literal: rdf_types::Literal<T>,
+	rdf_direction: Option<RdfDirection>,
+) -> IndexedObject<T, B> {
+	use crate::Value;
+
+	if let LiteralType::Any(ty) = &literal.type_ {
+		let ty = vocabulary.iri(ty);
+
+		let i18n = match rdf_direction {
+			Some(RdfDirection::I18nDatatype) => ty.and_then(i18n_parts),
+			_ => None,
+		};
+
+		if let Some((language, direction)) = i18n {
+			if let Ok(s) = LangString::new(literal.value.as_str().into(), language, Some(direction))
+			{
+				return Indexed::none(Object::Value(Value::LangString(s)));
+			}
+		}
+
+		if ty == Some(RDF_JSON) {
+			if let Ok((json, _)) = json_syntax::Value::parse_str(&literal.value) {
+				return Indexed::none(Object::Value(Value::Json(json)));
+			}
+		}
+	}
+
+	literal_value(vocabulary, literal)
+}
+
+/// Checks if the given identifier is the given IRI.
+fn is_iri<T, B>(vocabulary: &impl IriVocabulary<Iri = T>, id: &Id<T, B>, iri: &Iri) -> bool {
+	match id {
+		Id::Valid(ValidId::Iri(i)) => vocabulary.iri(i) == Some(iri),
+		_ => false,
+	}
+}
+
+/// Returns the identifier of the given object, if it is a node reference.
+fn reference_id<T, B>(object: &IndexedObject<T, B>) -> Option<&ValidId<T, B>> {
+	match object.inner() {
+		Object::Node(node) if node.is_empty() && node.annotations().is_none() => match &node.id {
+			Some(Id::Valid(id)) => Some(id),
+			_ => None,
+		},
+		_ => None,
+	}
+}
+
+/// `rdf:first` value and `rdf:rest` identifier of a list node.
+type ListNode<'a, T, B> = (&'a IndexedObject<T, B>, &'a ValidId<T, B>);
+
+/// Returns the `rdf:first` value and `rdf:rest` identifier of the given
+/// node, if it is a list node.
+fn list_node<'a, T, B>(
+	vocabulary: &impl IriVocabulary<Iri = T>,
+	node: &'a Node<T, B>,
+) -> Option<ListNode<'a, T, B>> {
+	if node.types.is_some()
+		|| node.graph.is_some()
+		|| node.included.is_some()
+		|| node.reverse_properties.is_some()
+		|| node.annotations().is_some()
+		|| node.properties().len() != 2
+	{
+		return None;
+	}
+
+	let mut first = None;
+	let mut rest = None;
+	for (property, objects) in node.properties() {
+		let mut objects = objects.iter();
+		let (Some(object), None) = (objects.next(), objects.next()) else {
+			return None;
+		};
+
+		if is_iri(vocabulary, property, RDF_FIRST) {
+			first = Some(object)
+		} else if is_iri(vocabulary, property, RDF_REST) {
+			rest = Some(reference_id(object)?)
+		}
+	}
+
+	Some((first?, rest?))
+}
+
+/// Converts the well-formed RDF lists of a graph into list objects.
+fn convert_lists<T, B>(
+	vocabulary: &impl IriVocabulary<Iri = T>,
+	nodes: &mut IndexMap<ValidId<T, B>, Node<T, B>>,
+) where
+	T: Clone + Eq + Hash,
+	B: Clone + Eq + Hash,
+{
+	// Number of references to each blank node, and whether the last one is
+	// an `rdf:first` or `rdf:rest` value.
+	let mut references: HashMap<B, (usize, bool)> = HashMap::new();
+	let mut count = |object: &IndexedObject<T, B>, in_list: bool| {
+		if let Some(ValidId::Blank(b)) = reference_id(object) {
+			let r = references.entry(b.clone()).or_default();
+			*r = (r.0 + 1, in_list)
+		}
+	};
+
+	for node in nodes.values() {
+		for (property, objects) in node.properties() {
+			let in_list =
+				is_iri(vocabulary, property, RDF_FIRST) || is_iri(vocabulary, property, RDF_REST);
+			for object in objects {
+				count(object, in_list)
+			}
+		}
+
+		// Annotated objects are copies of property values: lists quoted by
+		// an annotation are not converted.
+		for annotation in node.annotations().into_iter().flatten() {
+			count(&annotation.object, false)
+		}
+	}
+
+	let referenced_once = |id: &ValidId<T, B>, in_list: bool| match id {
+		ValidId::Blank(b) => references.get(b) == Some(&(1, in_list)),
+		ValidId::Iri(_) => false,
+	};
+
+	let mut lists = HashMap::new();
+	for id in nodes.keys() {
+		if !referenced_once(id, false) {
+			continue;
+		}
+
+		let mut items = Vec::new();
+		let mut current = id;
+		let well_formed = loop {
+			let Some((first, rest)) = nodes.get(current).and_then(|n| list_node(vocabulary, n))
+			else {
+				break false;
+			};
+
+			items.push(first.clone());
+			match rest {
+				ValidId::Iri(i) if vocabulary.iri(i) == Some(RDF_NIL) => break true,
+				// A node is only referenced once, so there can be no cycle
+				// longer than the graph.
+				rest if referenced_once(rest, true) && items.len() <= nodes.len() => current = rest,
+				_ => break false,
+			}
+		};
+
+		if well_formed {
+			lists.insert(id.clone(), items);
+		}
+	}
+
+	// Removes the list nodes, following each list from its head.
+	for head in lists.keys() {
+		let mut current = Some(head.clone());
+		while let Some(id) = current {
+			current = nodes
+				.shift_remove(&id)
+				.and_then(|node| list_node(vocabulary, &node).map(|(_, rest)| rest.clone()))
+				.filter(
+					|rest| !matches!(rest, ValidId::Iri(i) if vocabulary.iri(i) == Some(RDF_NIL)),
+				);
+		}
+	}
+
+	for node in nodes.values_mut() {
+		for (property, objects) in node.properties_mut() {
+			if is_iri(vocabulary, property, RDF_FIRST) || is_iri(vocabulary, property, RDF_REST) {
+				continue;
+			}
+
+			for object in objects.iter_mut() {
+				let items = match reference_id(object) {
+					Some(ValidId::Iri(i)) if vocabulary.iri(i) == Some(RDF_NIL) => Vec::new(),
+					Some(id) => match lists.remove(id) {
+						Some(items) => items,
+						None => continue,
+					},
+					None => continue,
+				};
+
+				*object = Indexed::none(Object::List(List::new(items)))
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{object::value::Literal, IndexedObject, Value};
+	use iref::IriBuf;
+	use rdf_types::generator;
+
+	fn iri(s: &str) -> Id {
+		Id::iri(IriBuf::new(format!("https://example.com/{s}")).unwrap())
+	}
+
+	fn string(s: &str) -> IndexedObject<IriBuf, rdf_types::BlankIdBuf> {
+		Indexed::none(Object::Value(Value::Literal(
+			Literal::String(s.into()),
+			None,
+		)))
+	}
+
+	#[test]
+	fn round_trip() {
+		let mut certainty = Node::new();
+		certainty.insert(iri("certainty"), string("high"));
+
+		let mut bob = Node::with_id(iri("bob"));
+		bob.insert(iri("age"), string("23"));
+		bob.set_annotations(Some(vec![Annotation::new(
+			iri("age"),
+			string("23"),
+			vec![Indexed::none(certainty)],
+		)]));
+
+		let mut document = ExpandedDocument::new();
+		document.insert(Indexed::none(Object::node(bob)));
+
+		let quads =
+			document.rdf_star_quads_full(&mut (), &mut generator::Blank::new(), None, false);
+		assert_eq!(quads.len(), 2);
+		assert!(quads[1].0.is_triple());
+
+		let parsed = ExpandedDocument::from_rdf_star_quads(quads);
+		assert_eq!(parsed, document)
+	}
+
+	#[test]
+	fn generalized_rdf() {
+		let property = Id::blank(rdf_types::BlankIdBuf::new("_:p".to_string()).unwrap());
+
+		let mut alice = Node::with_id(iri("alice"));
+		alice.insert(property, string("secret"));
+
+		let mut document = ExpandedDocument::new();
+		document.insert(Indexed::none(Object::node(alice)));
+
+		let quads =
+			document.rdf_star_quads_full(&mut (), &mut generator::Blank::new(), None, false);
+		assert!(quads.is_empty());
+
+		let quads = document.rdf_star_quads_full(&mut (), &mut generator::Blank::new(), None, true);
+		assert_eq!(quads.len(), 1);
+		assert!(quads[0].1.is_blank());
+
+		let parsed = ExpandedDocument::from_rdf_star_quads(quads);
+		assert_eq!(parsed, document)
+	}
+
+	#[test]
+	fn lists_json_and_i18n() {
+		use crate::{object::List, Direction, LangString};
+
+		let ltr = LangString::new("hello".into(), None, Some(Direction::Ltr)).unwrap();
+
+		let mut alice = Node::with_id(iri("alice"));
+		alice.insert(
+			iri("list"),
+			Indexed::none(Object::List(List::new(vec![string("a"), string("b")]))),
+		);
+		alice.insert(
+			iri("empty"),
+			Indexed::none(Object::List(List::new(Vec::new()))),
+		);
+		alice.insert(
+			iri("json"),
+			Indexed::none(Object::Value(Value::Json(
+				json_syntax::json!({ "a": [1, true] }),
+			))),
+		);
+		alice.insert(
+			iri("greeting"),
+			Indexed::none(Object::Value(Value::LangString(ltr))),
+		);
+
+		let mut document = ExpandedDocument::new();
+		document.insert(Indexed::none(Object::node(alice)));
+
+		let direction = Some(RdfDirection::I18nDatatype);
+		let quads =
+			document.rdf_star_quads_full(&mut (), &mut generator::Blank::new(), direction, false);
+		assert_eq!(quads.len(), 8);
+
+		let parsed = ExpandedDocument::from_rdf_star_quads_full(&(), quads.clone(), direction);
+		assert_eq!(parsed, document);
+
+		// Without `rdf_direction`, i18n literals are kept as typed values.
+		let parsed = ExpandedDocument::from_rdf_star_quads(quads);
+		let greeting = parsed
+			.main_node()
+			.unwrap()
+			.get_any(&iri("greeting"))
+			.unwrap();
+		assert!(matches!(
+			greeting.as_value().unwrap().typ(),
+			Some(crate::object::value::TypeRef::Id(ty)) if ty.as_str() == "https://www.w3.org/ns/i18n#ltr"
+		))
+	}
+
+	#[test]
+	fn ill_formed_lists() {
+		let rdf = |s: &str| {
+			IriBuf::new(format!("http://www.w3.org/1999/02/22-rdf-syntax-ns#{s}")).unwrap()
+		};
+		let ex = |s: &str| IriBuf::new(format!("https://example.com/{s}")).unwrap();
+		let blank = |s: &str| ValidId::Blank(rdf_types::BlankIdBuf::new(format!("_:{s}")).unwrap());
+		let quad = |s: ValidId<IriBuf, rdf_types::BlankIdBuf>,
+		            p: IriBuf,
+		            o: ValidId<IriBuf, rdf_types::BlankIdBuf>| {
+			rdf_types::Quad(StarTerm::Id(s), ValidId::Iri(p), StarTerm::Id(o), None)
+		};
+
+		// `_:l` is referenced twice, so it is not converted.
+		let quads = vec![
+			quad(ValidId::Iri(ex("a")), ex("p"), blank("l")),
+			quad(ValidId::Iri(ex("b")), ex("p"), blank("l")),
+			quad(blank("l"), rdf("first"), ValidId::Iri(ex("x"))),
+			quad(blank("l"), rdf("rest"), ValidId::Iri(rdf("nil"))),
+		];
+
+		let parsed = ExpandedDocument::from_rdf_star_quads(quads);
+		assert_eq!(parsed.len(), 3);
+		assert!(parsed.objects().iter().all(|object| !object
+			.as_node()
+			.unwrap()
+			.properties()
+			.iter()
+			.flat_map(|(_, objects)| objects)
+			.any(|object| object.is_list())));
+	}
+}
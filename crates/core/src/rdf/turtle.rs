@@ -0,0 +1,753 @@
+//! Turtle and TriG serialization.
+use super::{Quad, ValidId, Value, RDF_FIRST, RDF_NIL, RDF_REST, RDF_TYPE};
+use super::{XsdStringPolicy, XSD_BOOLEAN, XSD_INTEGER, XSD_STRING};
+use crate::{Context, Term};
+use iref::{Iri, IriBuf};
+use rdf_types::{
+	vocabulary::{BlankIdVocabulary, IriVocabulary, LiteralVocabulary},
+	BlankIdBuf, Literal, LiteralType, LiteralTypeRef,
+};
+use std::{
+	collections::{HashMap, HashSet},
+	fmt::{self, Write},
+};
+
+/// Prefix declarations, used to abbreviate IRIs in Turtle and TriG.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Prefixes(Vec<(String, IriBuf)>);
+
+impl Prefixes {
+	/// Creates an empty set of prefix declarations.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Derives prefix declarations from the given (compaction) context.
+	///
+	/// Every term that can be used as a compact IRI prefix, or whose IRI
+	/// ends with `/` or `#`, is declared. The vocabulary mapping, if any, is
+	/// declared as the empty prefix.
+	pub fn from_context<V: IriVocabulary, B>(vocabulary: &V, context: &Context<V::Iri, B>) -> Self {
+		let mut result = Self::new();
+
+		if let Some(vocab) = context
+			.vocabulary()
+			.and_then(Term::as_iri)
+			.and_then(|iri| vocabulary.iri(iri))
+		{
+			result.insert("", vocab.to_owned())
+		}
+
+		let mut terms: Vec<_> = context
+			.definitions()
+			.iter()
+			.filter_map(|binding| {
+				let term = binding.term().as_str();
+				let definition = binding.definition();
+				let iri = vocabulary.iri(definition.value()?.as_iri()?)?;
+				let is_namespace = iri.as_str().ends_with(['/', '#']);
+				if is_prefix_name(term) && (definition.prefix() || is_namespace) {
+					Some((term, iri))
+				} else {
+					None
+				}
+			})
+			.collect();
+
+		terms.sort_unstable_by_key(|(term, _)| *term);
+		for (term, iri) in terms {
+			result.insert(term, iri.to_owned())
+		}
+
+		result
+	}
+
+	/// Declares a prefix, replacing any previous declaration of the same
+	/// prefix.
+	pub fn insert(&mut self, prefix: impl Into<String>, namespace: IriBuf) {
+		let prefix = prefix.into();
+		match self.0.iter_mut().find(|(p, _)| *p == prefix) {
+			Some((_, ns)) => *ns = namespace,
+			None => self.0.push((prefix, namespace)),
+		}
+	}
+
+	/// Returns the namespace of the given prefix.
+	pub fn get(&self, prefix: &str) -> Option<&Iri> {
+		self.0
+			.iter()
+			.find(|(p, _)| p == prefix)
+			.map(|(_, ns)| ns.as_iri())
+	}
+
+	/// Returns the number of declared prefixes.
+	pub fn len(&self) -> usize {
+		self.0.len()
+	}
+
+	/// Checks if no prefix is declared.
+	pub fn is_empty(&self) -> bool {
+		self.0.is_empty()
+	}
+
+	/// Iterates over the declared prefixes and their namespace.
+	pub fn iter(&self) -> impl Iterator<Item = (&str, &Iri)> {
+		self.0.iter().map(|(p, ns)| (p.as_str(), ns.as_iri()))
+	}
+
+	/// Abbreviates the given IRI using the longest matching namespace.
+	fn abbreviate<'p, 'i>(&'p self, iri: &'i Iri) -> Option<(&'p str, &'i str)> {
+		self.0
+			.iter()
+			.filter_map(|(prefix, ns)| {
+				let local = iri.as_str().strip_prefix(ns.as_str())?;
+				is_local_name(local).then_some((prefix.as_str(), local, ns.len()))
+			})
+			.max_by_key(|(_, _, len)| *len)
+			.map(|(prefix, local, _)| (prefix, local))
+	}
+
+	fn write_declarations(&self, out: &mut impl fmt::Write, used: &HashSet<&str>) -> fmt::Result {
+		let mut any = false;
+		for (prefix, ns) in &self.0 {
+			if used.contains(prefix.as_str()) {
+				writeln!(out, "@prefix {prefix}: <{ns}> .")?;
+				any = true
+			}
+		}
+
+		if any {
+			writeln!(out)?
+		}
+
+		Ok(())
+	}
+}
+
+/// Checks if the given term is a valid Turtle prefix name.
+fn is_prefix_name(term: &str) -> bool {
+	let mut chars = term.chars();
+	chars.next().is_some_and(char::is_alphabetic)
+		&& chars.all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.'))
+		&& !term.ends_with('.')
+}
+
+/// Checks if the given string can be used as is as the local part of a
+/// Turtle prefixed name.
+fn is_local_name(local: &str) -> bool {
+	local
+		.chars()
+		.all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.' | ':'))
+		&& !local.starts_with(['-', '.'])
+		&& !local.ends_with('.')
+}
+
+/// Writes the default graph of the given dataset in Turtle.
+///
+/// Quads belonging to a named graph are ignored. Use [`write_trig`] to
+/// serialize every graph.
+///
+/// Only the prefixes actually used are declared. Blank nodes referenced
+/// exactly once are nested using the `[ ... ]` syntax, and well-formed lists
+/// are written as collections. `xsd:string` literals are written as simple
+/// literals, unless the `xsd_string` policy is
+/// [`XsdStringPolicy::Explicit`], in which case their datatype is written
+/// out (`"value"^^xsd:string`).
+///
+/// # Panics
+///
+/// Panics if an identifier or literal is unknown to the vocabulary.
+pub fn write_turtle<V, W>(
+	vocabulary: &V,
+	prefixes: &Prefixes,
+	xsd_string: XsdStringPolicy,
+	quads: impl IntoIterator<Item = Quad<V::Iri, V::BlankId, V::Literal>>,
+	out: &mut W,
+) -> fmt::Result
+where
+	V: IriVocabulary + BlankIdVocabulary + LiteralVocabulary,
+	W: fmt::Write,
+{
+	let dataset = Dataset::new(
+		vocabulary,
+		quads
+			.into_iter()
+			.filter(|rdf_types::Quad(_, _, _, g)| g.is_none()),
+	);
+	Writer::new(prefixes, xsd_string, &dataset).write(out)
+}
+
+/// Writes the given dataset in TriG.
+///
+/// Named graphs are written as `<graph> { ... }` blocks. See
+/// [`write_turtle`] for details on the formatting of triples.
+///
+/// # Panics
+///
+/// Panics if an identifier or literal is unknown to the vocabulary.
+pub fn write_trig<V, W>(
+	vocabulary: &V,
+	prefixes: &Prefixes,
+	xsd_string: XsdStringPolicy,
+	quads: impl IntoIterator<Item = Quad<V::Iri, V::BlankId, V::Literal>>,
+	out: &mut W,
+) -> fmt::Result
+where
+	V: IriVocabulary + BlankIdVocabulary + LiteralVocabulary,
+	W: fmt::Write,
+{
+	let dataset = Dataset::new(vocabulary, quads);
+	Writer::new(prefixes, xsd_string, &dataset).write(out)
+}
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+enum Resource {
+	Iri(IriBuf),
+	Blank(BlankIdBuf),
+}
+
+impl Resource {
+	fn new<V: IriVocabulary + BlankIdVocabulary>(
+		vocabulary: &V,
+		id: ValidId<V::Iri, V::BlankId>,
+	) -> Self {
+		match id {
+			ValidId::Iri(iri) => Self::Iri(vocabulary.iri(&iri).unwrap().to_owned()),
+			ValidId::Blank(b) => Self::Blank(vocabulary.blank_id(&b).unwrap().to_owned()),
+		}
+	}
+
+	fn is_iri(&self, iri: &Iri) -> bool {
+		matches!(self, Self::Iri(i) if i == iri)
+	}
+}
+
+enum Object {
+	Resource(Resource),
+	Literal(Literal),
+}
+
+/// Triples of a graph, grouped by subject then predicate, in order of
+/// appearance.
+#[derive(Default)]
+struct Graph {
+	subjects: Vec<Resource>,
+	triples: HashMap<Resource, Vec<(Resource, Vec<Object>)>>,
+
+	/// Number of times each blank node is used as object.
+	references: HashMap<BlankIdBuf, usize>,
+}
+
+impl Graph {
+	fn insert(&mut self, subject: Resource, predicate: Resource, object: Object) {
+		if let Object::Resource(Resource::Blank(b)) = &object {
+			*self.references.entry(b.clone()).or_default() += 1
+		}
+
+		let predicates = match self.triples.get_mut(&subject) {
+			Some(predicates) => predicates,
+			None => {
+				self.subjects.push(subject.clone());
+				self.triples.entry(subject).or_default()
+			}
+		};
+
+		match predicates.iter_mut().find(|(p, _)| *p == predicate) {
+			Some((_, objects)) => objects.push(object),
+			None => predicates.push((predicate, vec![object])),
+		}
+	}
+}
+
+struct Dataset {
+	default_graph: Graph,
+	named_graphs: Vec<(Resource, Graph)>,
+}
+
+impl Dataset {
+	fn new<V: IriVocabulary + BlankIdVocabulary + LiteralVocabulary>(
+		vocabulary: &V,
+		quads: impl IntoIterator<Item = Quad<V::Iri, V::BlankId, V::Literal>>,
+	) -> Self {
+		let mut result = Self {
+			default_graph: Graph::default(),
+			named_graphs: Vec::new(),
+		};
+
+		for rdf_types::Quad(s, p, o, g) in quads {
+			let object = match o {
+				Value::Id(id) => Object::Resource(Resource::new(vocabulary, id)),
+				Value::Literal(l) => {
+					let l = vocabulary.literal(&l).unwrap();
+					Object::Literal(Literal::new(
+						l.value.to_owned(),
+						match l.type_ {
+							LiteralTypeRef::Any(ty) => {
+								LiteralType::Any(vocabulary.iri(ty).unwrap().to_owned())
+							}
+							LiteralTypeRef::LangString(tag) => {
+								LiteralType::LangString(tag.to_owned())
+							}
+						},
+					))
+				}
+			};
+
+			let graph = match g {
+				Some(g) => {
+					let g = Resource::new(vocabulary, g);
+					match result.named_graphs.iter().position(|(name, _)| *name == g) {
+						Some(i) => &mut result.named_graphs[i].1,
+						None => {
+							result.named_graphs.push((g, Graph::default()));
+							&mut result.named_graphs.last_mut().unwrap().1
+						}
+					}
+				}
+				None => &mut result.default_graph,
+			};
+
+			graph.insert(
+				Resource::new(vocabulary, s),
+				Resource::new(vocabulary, p),
+				object,
+			)
+		}
+
+		result
+	}
+}
+
+struct Writer<'a> {
+	prefixes: &'a Prefixes,
+	xsd_string: XsdStringPolicy,
+	dataset: &'a Dataset,
+
+	/// Blank nodes used as graph names, that cannot be nested.
+	graph_names: HashSet<&'a BlankIdBuf>,
+}
+
+impl<'a> Writer<'a> {
+	fn new(prefixes: &'a Prefixes, xsd_string: XsdStringPolicy, dataset: &'a Dataset) -> Self {
+		let graph_names = dataset
+			.named_graphs
+			.iter()
+			.filter_map(|(name, _)| match name {
+				Resource::Blank(b) => Some(b),
+				Resource::Iri(_) => None,
+			})
+			.collect();
+
+		Self {
+			prefixes,
+			xsd_string,
+			dataset,
+			graph_names,
+		}
+	}
+
+	fn write(&self, out: &mut impl fmt::Write) -> fmt::Result {
+		let mut body = String::new();
+		let mut used = HashSet::new();
+
+		let mut output = Output {
+			out: &mut body,
+			used: &mut used,
+		};
+
+		self.write_graph(&mut output, &self.dataset.default_graph, 0)?;
+
+		for (name, graph) in &self.dataset.named_graphs {
+			if !output.out.is_empty() {
+				writeln!(output.out)?
+			}
+
+			self.write_resource(&mut output, name)?;
+			writeln!(output.out, " {{")?;
+			self.write_graph(&mut output, graph, 1)?;
+			writeln!(output.out, "}}")?;
+		}
+
+		self.prefixes.write_declarations(out, &used)?;
+		out.write_str(&body)
+	}
+
+	/// Checks if the given blank node can be nested in its only referencing
+	/// triple.
+	fn is_nestable(&self, graph: &Graph, b: &BlankIdBuf) -> bool {
+		graph.references.get(b) == Some(&1) && !self.graph_names.contains(b)
+	}
+
+	fn write_graph(
+		&self,
+		output: &mut Output<'_, 'a>,
+		graph: &'a Graph,
+		depth: usize,
+	) -> fmt::Result {
+		let mut written = HashSet::new();
+
+		let top_level = graph.subjects.iter().filter(|s| match s {
+			Resource::Blank(b) => !self.is_nestable(graph, b),
+			Resource::Iri(_) => true,
+		});
+
+		for subject in top_level {
+			self.write_subject(output, graph, subject, &mut written, depth)?
+		}
+
+		// Nestable blank nodes part of a reference cycle.
+		for subject in &graph.subjects {
+			if !written.contains(subject) {
+				self.write_subject(output, graph, subject, &mut written, depth)?
+			}
+		}
+
+		Ok(())
+	}
+
+	fn write_subject(
+		&self,
+		output: &mut Output<'_, 'a>,
+		graph: &'a Graph,
+		subject: &'a Resource,
+		written: &mut HashSet<&'a Resource>,
+		depth: usize,
+	) -> fmt::Result {
+		written.insert(subject);
+		indent(output.out, depth)?;
+		self.write_resource(output, subject)?;
+		output.out.write_char(' ')?;
+		self.write_predicates(output, graph, subject, written, depth, false)?;
+		writeln!(output.out, " .")
+	}
+
+	/// Writes the predicate-object list of the given subject.
+	///
+	/// If `nested` is true, every predicate is written on its own line.
+	/// Otherwise the first predicate is written on the current line.
+	fn write_predicates(
+		&self,
+		output: &mut Output<'_, 'a>,
+		graph: &'a Graph,
+		subject: &'a Resource,
+		written: &mut HashSet<&'a Resource>,
+		depth: usize,
+		nested: bool,
+	) -> fmt::Result {
+		let predicates = match graph.triples.get(subject) {
+			Some(predicates) => predicates,
+			None => return Ok(()),
+		};
+
+		for (i, (predicate, objects)) in predicates.iter().enumerate() {
+			if i > 0 {
+				output.out.write_str(" ;")?
+			}
+
+			if i > 0 || nested {
+				writeln!(output.out)?;
+				indent(output.out, depth + 1)?
+			}
+
+			if predicate.is_iri(RDF_TYPE) {
+				output.out.write_char('a')?
+			} else {
+				self.write_resource(output, predicate)?
+			}
+
+			for (j, object) in objects.iter().enumerate() {
+				output.out.write_str(if j == 0 { " " } else { ", " })?;
+				self.write_object(output, graph, object, written, depth + 1)?
+			}
+		}
+
+		Ok(())
+	}
+
+	fn write_object(
+		&self,
+		output: &mut Output<'_, 'a>,
+		graph: &'a Graph,
+		object: &'a Object,
+		written: &mut HashSet<&'a Resource>,
+		depth: usize,
+	) -> fmt::Result {
+		match object {
+			Object::Literal(literal) => self.write_literal(output, literal),
+			Object::Resource(r) if r.is_iri(RDF_NIL) => output.out.write_str("()"),
+			Object::Resource(r @ Resource::Blank(b))
+				if self.is_nestable(graph, b) && !written.contains(r) =>
+			{
+				if let Some(items) = self.list(graph, r, written) {
+					output.out.write_char('(')?;
+					for item in items {
+						output.out.write_char(' ')?;
+						self.write_object(output, graph, item, written, depth)?
+					}
+
+					return output.out.write_str(" )");
+				}
+
+				written.insert(r);
+				if graph.triples.contains_key(r) {
+					output.out.write_char('[')?;
+					self.write_predicates(output, graph, r, written, depth, true)?;
+					writeln!(output.out)?;
+					indent(output.out, depth)?;
+					output.out.write_char(']')
+				} else {
+					output.out.write_str("[]")
+				}
+			}
+			Object::Resource(r) => self.write_resource(output, r),
+		}
+	}
+
+	/// Returns the items of the list starting with the given node, if it is
+	/// a well-formed list of nestable blank nodes.
+	///
+	/// Marks the list nodes as written.
+	fn list(
+		&self,
+		graph: &'a Graph,
+		head: &'a Resource,
+		written: &mut HashSet<&'a Resource>,
+	) -> Option<Vec<&'a Object>> {
+		let mut items = Vec::new();
+		let mut nodes = Vec::new();
+		let mut node = head;
+
+		loop {
+			match node {
+				Resource::Blank(b) if self.is_nestable(graph, b) => (),
+				_ => return None,
+			}
+
+			if nodes.contains(&node) || written.contains(node) {
+				return None;
+			}
+
+			let (first, rest) = match graph.triples.get(node)?.as_slice() {
+				[(p1, o1), (p2, o2)] if p1.is_iri(RDF_FIRST) && p2.is_iri(RDF_REST) => (o1, o2),
+				[(p1, o1), (p2, o2)] if p1.is_iri(RDF_REST) && p2.is_iri(RDF_FIRST) => (o2, o1),
+				_ => return None,
+			};
+
+			let (first, rest) = match (first.as_slice(), rest.as_slice()) {
+				([first], [Object::Resource(rest)]) => (first, rest),
+				_ => return None,
+			};
+
+			items.push(first);
+			nodes.push(node);
+
+			if rest.is_iri(RDF_NIL) {
+				break;
+			}
+
+			node = rest
+		}
+
+		written.extend(nodes);
+		Some(items)
+	}
+
+	fn write_resource(&self, output: &mut Output<'_, 'a>, resource: &Resource) -> fmt::Result {
+		match resource {
+			Resource::Iri(iri) => self.write_iri(output, iri),
+			Resource::Blank(b) => output.out.write_str(b.as_str()),
+		}
+	}
+
+	fn write_iri(&self, output: &mut Output<'_, 'a>, iri: &Iri) -> fmt::Result {
+		match self.prefixes.abbreviate(iri) {
+			Some((prefix, local)) => {
+				output.used.insert(prefix);
+				write!(output.out, "{prefix}:{local}")
+			}
+			None => write!(output.out, "<{iri}>"),
+		}
+	}
+
+	fn write_literal(&self, output: &mut Output<'_, 'a>, literal: &Literal) -> fmt::Result {
+		let value = literal.value.as_str();
+		match &literal.type_ {
+			LiteralType::Any(ty)
+				if ty == XSD_STRING && self.xsd_string == XsdStringPolicy::Implicit =>
+			{
+				write_string(output.out, value)
+			}
+			LiteralType::Any(ty) if ty == XSD_BOOLEAN && matches!(value, "true" | "false") => {
+				output.out.write_str(value)
+			}
+			LiteralType::Any(ty) if ty == XSD_INTEGER && is_integer(value) => {
+				output.out.write_str(value)
+			}
+			LiteralType::Any(ty) => {
+				write_string(output.out, value)?;
+				output.out.write_str("^^")?;
+				self.write_iri(output, ty)
+			}
+			LiteralType::LangString(tag) => {
+				write_string(output.out, value)?;
+				write!(output.out, "@{tag}")
+			}
+		}
+	}
+}
+
+/// Output buffer, recording the prefixes used.
+struct Output<'o, 'a> {
+	out: &'o mut String,
+	used: &'o mut HashSet<&'a str>,
+}
+
+fn indent(out: &mut impl fmt::Write, depth: usize) -> fmt::Result {
+	for _ in 0..depth {
+		out.write_char('\t')?
+	}
+
+	Ok(())
+}
+
+fn is_integer(value: &str) -> bool {
+	let digits = value.strip_prefix(['-', '+']).unwrap_or(value);
+	!digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit())
+}
+
+fn write_string(out: &mut impl fmt::Write, value: &str) -> fmt::Result {
+	out.write_char('"')?;
+	for c in value.chars() {
+		match c {
+			'"' => out.write_str("\\\"")?,
+			'\\' => out.write_str("\\\\")?,
+			'\n' => out.write_str("\\n")?,
+			'\r' => out.write_str("\\r")?,
+			'\t' => out.write_str("\\t")?,
+			c if c.is_control() => write!(out, "\\u{:04X}", c as u32)?,
+			c => out.write_char(c)?,
+		}
+	}
+	out.write_char('"')
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::rdf::RdfQuads;
+	use crate::{ExpandedDocument, Indexed, Node, Object as LdObject};
+	use rdf_types::generator;
+	use static_iref::iri;
+
+	#[test]
+	fn nested_turtle() {
+		let mut alice = Node::with_id(crate::Id::iri(iri!("http://example.org/alice").to_owned()));
+		alice
+			.types_mut_or_default()
+			.push(crate::Id::iri(iri!("https://schema.org/Person").to_owned()));
+
+		let mut address = Node::new();
+		address.insert(
+			crate::Id::iri(iri!("https://schema.org/postalCode").to_owned()),
+			Indexed::none(LdObject::Value(crate::Value::Literal(
+				crate::object::Literal::String("12345".into()),
+				None,
+			))),
+		);
+		alice.insert(
+			crate::Id::iri(iri!("https://schema.org/address").to_owned()),
+			Indexed::none(LdObject::node(address)),
+		);
+		alice.insert(
+			crate::Id::iri(iri!("https://schema.org/knows").to_owned()),
+			Indexed::none(LdObject::List(crate::object::List::new(vec![
+				Indexed::none(LdObject::node(Node::with_id(crate::Id::iri(
+					iri!("http://example.org/bob").to_owned(),
+				)))),
+				Indexed::none(LdObject::node(Node::with_id(crate::Id::iri(
+					iri!("http://example.org/carol").to_owned(),
+				)))),
+			]))),
+		);
+
+		let mut document = ExpandedDocument::new();
+		document.insert(Indexed::none(LdObject::node(alice)));
+
+		let mut generator = generator::Blank::new();
+		document.identify_all(&mut generator);
+		let quads: Vec<_> = document.rdf_quads(&mut generator, None).cloned().collect();
+
+		let mut prefixes = Prefixes::new();
+		prefixes.insert("schema", iri!("https://schema.org/").to_owned());
+		prefixes.insert("ex", iri!("http://example.org/").to_owned());
+		prefixes.insert("foaf", iri!("http://xmlns.com/foaf/0.1/").to_owned());
+
+		let mut turtle = String::new();
+		write_turtle(
+			&(),
+			&prefixes,
+			XsdStringPolicy::Implicit,
+			quads,
+			&mut turtle,
+		)
+		.unwrap();
+
+		assert_eq!(
+			turtle,
+			"@prefix schema: <https://schema.org/> .\n\
+			@prefix ex: <http://example.org/> .\n\
+			\n\
+			ex:alice a schema:Person ;\n\
+			\tschema:address [\n\
+			\t\tschema:postalCode \"12345\"\n\
+			\t] ;\n\
+			\tschema:knows ( ex:bob ex:carol ) .\n"
+		)
+	}
+
+	#[test]
+	fn xsd_string_policy() {
+		let mut alice = Node::with_id(crate::Id::iri(iri!("http://example.org/alice").to_owned()));
+		alice.insert(
+			crate::Id::iri(iri!("https://schema.org/name").to_owned()),
+			Indexed::none(LdObject::Value(crate::Value::Literal(
+				crate::object::Literal::String("Alice".into()),
+				None,
+			))),
+		);
+
+		let mut document = ExpandedDocument::new();
+		document.insert(Indexed::none(LdObject::node(alice)));
+
+		let mut generator = generator::Blank::new();
+		let quads: Vec<_> = document.rdf_quads(&mut generator, None).cloned().collect();
+
+		let mut prefixes = Prefixes::new();
+		prefixes.insert("schema", iri!("https://schema.org/").to_owned());
+		prefixes.insert("ex", iri!("http://example.org/").to_owned());
+		prefixes.insert("xsd", iri!("http://www.w3.org/2001/XMLSchema#").to_owned());
+
+		let write = |xsd_string| {
+			let mut turtle = String::new();
+			write_turtle(&(), &prefixes, xsd_string, quads.clone(), &mut turtle).unwrap();
+			turtle
+		};
+
+		assert_eq!(
+			write(XsdStringPolicy::Implicit),
+			"@prefix schema: <https://schema.org/> .\n\
+			@prefix ex: <http://example.org/> .\n\
+			\n\
+			ex:alice schema:name \"Alice\" .\n"
+		);
+
+		assert_eq!(
+			write(XsdStringPolicy::Explicit),
+			"@prefix schema: <https://schema.org/> .\n\
+			@prefix ex: <http://example.org/> .\n\
+			@prefix xsd: <http://www.w3.org/2001/XMLSchema#> .\n\
+			\n\
+			ex:alice schema:name \"Alice\"^^xsd:string .\n"
+		)
+	}
+}
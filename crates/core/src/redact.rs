@@ -0,0 +1,195 @@
+//! Redaction of property values.
+//!
+//! Removes or masks the values of sensitive properties (such as personal
+//! information) of expanded documents, for instance before logging or
+//! sharing them.
+use crate::{object::Literal, ExpandedDocument, Id, Node, Object, Value};
+use json_syntax::Print;
+use sha2::{Digest, Sha256};
+use std::fmt::Write;
+use std::hash::Hash;
+
+/// Redaction policy, defining what happens to redacted properties.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RedactionPolicy {
+	/// Removes the property.
+	Drop,
+
+	/// Replaces every value of the property with the given string literal.
+	Replace(String),
+
+	/// Replaces every literal value of the property with the hexadecimal
+	/// SHA-256 digest of its lexical form, as an untyped string literal.
+	///
+	/// Equal values keep equal digests, so redacted documents can still be
+	/// correlated. Node objects are left unchanged, but list items are
+	/// hashed.
+	Hash,
+}
+
+impl RedactionPolicy {
+	/// Redacts the given property values.
+	fn redact_object<T, B>(&self, object: &mut Object<T, B>) {
+		match self {
+			Self::Drop => (),
+			Self::Replace(placeholder) => {
+				*object = Object::Value(Value::Literal(
+					Literal::String(placeholder.as_str().into()),
+					None,
+				))
+			}
+			Self::Hash => match object {
+				Object::Value(value) => {
+					if let Some(digest) = hash_value(value) {
+						*value = Value::Literal(Literal::String(digest.into()), None)
+					}
+				}
+				Object::Node(_) => (),
+				Object::List(list) => {
+					for item in list.iter_mut() {
+						self.redact_object(item)
+					}
+				}
+			},
+		}
+	}
+}
+
+/// Returns the hexadecimal SHA-256 digest of the lexical form of the given
+/// value, unless it is `null`.
+fn hash_value<T>(value: &Value<T>) -> Option<String> {
+	let lexical_form = match value {
+		Value::Literal(Literal::Null, _) => return None,
+		Value::Literal(Literal::Boolean(b), _) => b.to_string(),
+		Value::Literal(Literal::Number(n), _) => n.as_str().to_owned(),
+		Value::Literal(Literal::String(s), _) => s.as_str().to_owned(),
+		Value::LangString(s) => s.as_str().to_owned(),
+		Value::Json(json) => {
+			let mut json = json.clone();
+			json.canonicalize();
+			json.compact_print().to_string()
+		}
+	};
+
+	let mut digest = String::with_capacity(64);
+	for byte in Sha256::digest(lexical_form.as_bytes()) {
+		write!(digest, "{byte:02x}").unwrap()
+	}
+
+	Some(digest)
+}
+
+impl<T: Eq + Hash, B: Eq + Hash> ExpandedDocument<T, B> {
+	/// Redacts the given properties in every node object of this document,
+	/// including nested nodes and graphs, using the given `policy`.
+	///
+	/// Returns the number of redacted property entries.
+	pub fn redact(&mut self, properties: &[T], policy: &RedactionPolicy) -> usize {
+		let mut count = 0;
+		self.map_nodes(|node| count += redact_node(node, properties, policy));
+		count
+	}
+}
+
+/// Redacts the given properties of `node`, without recursion.
+fn redact_node<T, B>(node: &mut Node<T, B>, properties: &[T], policy: &RedactionPolicy) -> usize
+where
+	T: Eq + Hash,
+	B: Eq + Hash,
+{
+	let is_redacted = |prop: &Id<T, B>| prop.as_iri().is_some_and(|iri| properties.contains(iri));
+	let mut count = 0;
+
+	match policy {
+		RedactionPolicy::Drop => node.properties_mut().retain(|prop, _| {
+			let redacted = is_redacted(prop);
+			count += redacted as usize;
+			!redacted
+		}),
+		_ => {
+			for (prop, objects) in node.properties_mut().iter_mut() {
+				if is_redacted(prop) {
+					for object in objects.iter_mut() {
+						policy.redact_object(object.inner_mut())
+					}
+
+					count += 1
+				}
+			}
+		}
+	}
+
+	count
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::TryFromJson;
+	use iref::IriBuf;
+
+	fn document(email: json_syntax::Value, list_email: json_syntax::Value) -> ExpandedDocument {
+		ExpandedDocument::try_from_json_in(
+			&mut (),
+			json_syntax::json!([{
+				"https://schema.org/name": [{ "@value": "Alice" }],
+				"https://schema.org/email": email,
+				"@graph": [{
+					"https://schema.org/email": list_email
+				}]
+			}]),
+		)
+		.unwrap()
+	}
+
+	#[test]
+	fn redact() {
+		let original = document(
+			json_syntax::json!([{ "@value": "alice@example.com" }]),
+			json_syntax::json!([{ "@list": [{ "@value": "bob@example.com" }] }]),
+		);
+		let properties = [IriBuf::new("https://schema.org/email".to_owned()).unwrap()];
+
+		let mut dropped = original.clone();
+		assert_eq!(dropped.redact(&properties, &RedactionPolicy::Drop), 2);
+		assert_eq!(
+			dropped,
+			ExpandedDocument::try_from_json_in(
+				&mut (),
+				json_syntax::json!([{
+					"https://schema.org/name": [{ "@value": "Alice" }],
+					"@graph": [{}]
+				}])
+			)
+			.unwrap()
+		);
+
+		let mut replaced = original.clone();
+		let policy = RedactionPolicy::Replace("[redacted]".to_owned());
+		assert_eq!(replaced.redact(&properties, &policy), 2);
+		assert_eq!(
+			replaced,
+			document(
+				json_syntax::json!([{ "@value": "[redacted]" }]),
+				json_syntax::json!([{ "@value": "[redacted]" }]),
+			)
+		);
+
+		let mut hashed = original.clone();
+		assert_eq!(hashed.redact(&properties, &RedactionPolicy::Hash), 2);
+		let digest = |s: &str| {
+			hash_value::<IriBuf>(&Value::Literal(Literal::String(s.into()), None)).unwrap()
+		};
+		assert_eq!(
+			hashed,
+			document(
+				json_syntax::json!([{ "@value": digest("alice@example.com") }]),
+				json_syntax::json!([{ "@list": [{ "@value": digest("bob@example.com") }] }]),
+			)
+		);
+		assert_eq!(
+			digest("alice@example.com"),
+			"ff8d9819fc0e12bf0d24892e45987e249a28dce836a85cad60e28eaaa8c6d976"
+		)
+	}
+}
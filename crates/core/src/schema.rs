@@ -0,0 +1,108 @@
+//! schema.org vocabulary.
+//!
+//! Provides the IRIs of common [schema.org](https://schema.org/) properties
+//! (as constants of this module) and types (in the [`types`] module), the
+//! [`Property`] and [`Type`] enums, and node accessors using them:
+//!
+//! ```
+//! use json_ld_core::{schema, Node};
+//! # let node: Node = Node::new();
+//! for name in node.get_schema(schema::NAME) {
+//!   // ...
+//! }
+//! ```
+//!
+//! This module is generated by the build script from the term lists found
+//! in the `schema.org` directory of this crate.
+use crate::{IndexedObject, Node};
+use iref::{Iri, IriBuf};
+
+include!(concat!(env!("OUT_DIR"), "/schema.rs"));
+
+/// schema.org namespace.
+pub const NAMESPACE: &str = "https://schema.org/";
+
+/// Legacy `http` schema.org namespace, still used by many documents.
+pub const HTTP_NAMESPACE: &str = "http://schema.org/";
+
+/// Returns the schema.org term of the given IRI, in either namespace.
+fn term_of(iri: &Iri) -> Option<&str> {
+	let iri = iri.as_str();
+	iri.strip_prefix(NAMESPACE)
+		.or_else(|| iri.strip_prefix(HTTP_NAMESPACE))
+}
+
+/// Returns the `http` variant of the given schema.org IRI.
+fn http_variant(iri: &Iri) -> Option<IriBuf> {
+	iri.as_str()
+		.strip_prefix(NAMESPACE)
+		.map(|term| IriBuf::new(format!("{HTTP_NAMESPACE}{term}")).unwrap())
+}
+
+impl Type {
+	/// Returns the type with the given IRI, in either the `https` or `http`
+	/// schema.org namespace.
+	pub fn from_iri(iri: &Iri) -> Option<Self> {
+		term_of(iri).and_then(Self::from_name)
+	}
+}
+
+impl Property {
+	/// Returns the property with the given IRI, in either the `https` or
+	/// `http` schema.org namespace.
+	pub fn from_iri(iri: &Iri) -> Option<Self> {
+		term_of(iri).and_then(Self::from_name)
+	}
+}
+
+impl<B: Eq + std::hash::Hash> Node<IriBuf, B> {
+	/// Returns the objects associated to the node with the given schema.org
+	/// property.
+	///
+	/// The property is looked up in both the `https` and legacy `http`
+	/// schema.org namespaces.
+	pub fn get_schema(&self, property: &Iri) -> impl Iterator<Item = &IndexedObject<IriBuf, B>> {
+		let http = http_variant(property).map(|http| self.get(&http));
+		self.get(&property).chain(http.into_iter().flatten())
+	}
+
+	/// Checks if the node has the given schema.org type, in either the
+	/// `https` or legacy `http` schema.org namespace.
+	pub fn has_schema_type(&self, ty: &Iri) -> bool {
+		let http = http_variant(ty);
+		self.types()
+			.iter()
+			.filter_map(|t| t.as_iri())
+			.any(|t| t.as_iri() == ty || http.as_ref() == Some(t))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{object::Literal, Id, Indexed, Object, Value};
+
+	#[test]
+	fn schema_accessors() {
+		let mut node: Node = Node::new();
+		node.types_mut_or_default().push(Id::iri(
+			IriBuf::new("http://schema.org/Person".to_owned()).unwrap(),
+		));
+		node.insert(
+			Id::iri(IriBuf::new("http://schema.org/name".to_owned()).unwrap()),
+			Indexed::new(
+				Object::Value(Value::Literal(Literal::String("Alice".into()), None)),
+				None,
+			),
+		);
+
+		assert!(node.has_schema_type(types::PERSON));
+		assert_eq!(node.get_schema(NAME).count(), 1);
+		assert_eq!(Property::from_iri(NAME), Some(Property::Name));
+		assert_eq!(Type::Person.iri(), types::PERSON);
+		assert_eq!(
+			Type::from_iri(Iri::new("http://schema.org/FAQPage").unwrap()),
+			Some(Type::FAQPage)
+		)
+	}
+}
@@ -20,10 +20,12 @@ use std::{
 use crate::{
 	object::{List, Literal},
 	rdf::{
-		RDF_FIRST, RDF_JSON, RDF_NIL, RDF_REST, RDF_TYPE, XSD_BOOLEAN, XSD_DOUBLE, XSD_INTEGER,
-		XSD_STRING,
+		i18n_parts, FromRdfOptions, RdfDirection, XsdStringPolicy, RDF_DIRECTION, RDF_FIRST,
+		RDF_JSON, RDF_LANGUAGE, RDF_NIL, RDF_REST, RDF_TYPE, RDF_VALUE, XSD_BOOLEAN, XSD_DOUBLE,
+		XSD_INTEGER, XSD_STRING,
 	},
-	ExpandedDocument, Id, Indexed, IndexedObject, LangString, Node, Object, ValidId, Value,
+	ExpandedDocument, Id, Indexed, IndexedObject, LangString, LenientLangTagBuf, Node, Object,
+	ValidId, Value,
 };
 
 struct SerDataset<R> {
@@ -235,13 +237,16 @@ pub struct RdfTerms<R> {
 }
 
 impl<I, B> ExpandedDocument<I, B> {
-	pub fn from_interpreted_quads_in<'a, V, T>(
+	/// Builds an expanded document from interpreted RDF quads, using the
+	/// given conversion options.
+	pub fn from_interpreted_quads_full<'a, V, T>(
 		vocabulary: &V,
 		interpretation: &T,
 		quads: impl IntoIterator<
 			Item = Quad<&'a T::Resource, &'a T::Resource, &'a T::Resource, &'a T::Resource>,
 		>,
 		context: linked_data::Context<T>,
+		options: FromRdfOptions,
 	) -> Result<Self, SerializationError>
 	where
 		V: Vocabulary<Iri = I, BlankId = B>,
@@ -264,8 +269,7 @@ impl<I, B> ExpandedDocument<I, B> {
 			let subject = graph.resource_mut(quad.0);
 
 			match rdf_property(vocabulary, interpretation, quad.1) {
-				Some(RdfProperty::Type) => {
-					rdf_terms.first = Some(quad.1);
+				Some(RdfProperty::Type) if !options.use_rdf_type => {
 					let ty = rdf_type(vocabulary, interpretation, quad.2);
 
 					if ty.is_list() {
@@ -292,7 +296,7 @@ impl<I, B> ExpandedDocument<I, B> {
 					subject.list.rest.insert(quad.2);
 					graph.resource_mut(quad.2).list.reverse_rest.insert(quad.1);
 				}
-				None => {
+				_ => {
 					subject.insert(quad.1, quad.2);
 				}
 			}
@@ -357,6 +361,7 @@ impl<I, B> ExpandedDocument<I, B> {
 					vocabulary,
 					interpretation,
 					rdf_terms,
+					options,
 					&graph,
 					id,
 					resource,
@@ -368,6 +373,34 @@ impl<I, B> ExpandedDocument<I, B> {
 		Ok(result)
 	}
 
+	/// Builds an expanded document from interpreted RDF quads, using the
+	/// default conversion options.
+	///
+	/// See [`Self::from_interpreted_quads_full`].
+	pub fn from_interpreted_quads_in<'a, V, T>(
+		vocabulary: &V,
+		interpretation: &T,
+		quads: impl IntoIterator<
+			Item = Quad<&'a T::Resource, &'a T::Resource, &'a T::Resource, &'a T::Resource>,
+		>,
+		context: linked_data::Context<T>,
+	) -> Result<Self, SerializationError>
+	where
+		V: Vocabulary<Iri = I, BlankId = B>,
+		T: ReverseTermInterpretation<Iri = I, BlankId = B, Literal = V::Literal>,
+		T::Resource: 'a + Ord + Hash,
+		I: Clone + Eq + Hash,
+		B: Clone + Eq + Hash,
+	{
+		Self::from_interpreted_quads_full(
+			vocabulary,
+			interpretation,
+			quads,
+			context,
+			FromRdfOptions::default(),
+		)
+	}
+
 	pub fn from_interpreted_quads<'a, V, T>(
 		vocabulary: &V,
 		interpretation: &T,
@@ -391,10 +424,12 @@ impl<I, B> ExpandedDocument<I, B> {
 	}
 }
 
+#[allow(clippy::too_many_arguments)]
 fn render_object<V, I>(
 	vocabulary: &V,
 	interpretation: &I,
 	rdf_terms: RdfTerms<&I::Resource>,
+	options: FromRdfOptions,
 	graph: &SerGraph<&I::Resource>,
 	id: &I::Resource,
 	resource: &SerResource<&I::Resource>,
@@ -409,7 +444,7 @@ where
 {
 	let context = context.with_subject(id);
 	if resource.is_empty() {
-		render_reference(vocabulary, interpretation, id, context)
+		render_reference(vocabulary, interpretation, id, options, context)
 	} else {
 		match &resource.list.values {
 			Some(values) => {
@@ -420,6 +455,7 @@ where
 						vocabulary,
 						interpretation,
 						rdf_terms,
+						options,
 						graph,
 						value,
 						context,
@@ -460,6 +496,7 @@ where
 								vocabulary,
 								interpretation,
 								rdf_terms,
+								options,
 								graph,
 								id,
 								resource,
@@ -476,6 +513,7 @@ where
 						vocabulary,
 						interpretation,
 						rdf_terms,
+						options,
 						graph,
 						&mut node,
 						prop,
@@ -490,6 +528,7 @@ where
 						vocabulary,
 						interpretation,
 						rdf_terms,
+						options,
 						graph,
 						&mut node,
 						rdf_first_id,
@@ -504,6 +543,7 @@ where
 						vocabulary,
 						interpretation,
 						rdf_terms,
+						options,
 						graph,
 						&mut node,
 						rdf_rest_id,
@@ -523,6 +563,7 @@ fn insert_property<'a, V, I, O>(
 	vocabulary: &V,
 	interpretation: &I,
 	rdf_terms: RdfTerms<&'a I::Resource>,
+	options: FromRdfOptions,
 	graph: &SerGraph<&'a I::Resource>,
 	node: &mut Node<V::Iri, V::BlankId>,
 	prop: &I::Resource,
@@ -549,6 +590,7 @@ where
 					vocabulary,
 					interpretation,
 					rdf_terms,
+					options,
 					graph,
 					value,
 					context,
@@ -561,6 +603,7 @@ where
 					vocabulary,
 					interpretation,
 					rdf_terms,
+					options,
 					graph,
 					value,
 					context,
@@ -578,6 +621,7 @@ fn render_object_or_reference<V, I>(
 	vocabulary: &V,
 	interpretation: &I,
 	rdf_terms: RdfTerms<&I::Resource>,
+	options: FromRdfOptions,
 	graph: &SerGraph<&I::Resource>,
 	id: &I::Resource,
 	context: linked_data::Context<I>,
@@ -592,27 +636,90 @@ where
 	match graph.get(&id) {
 		Some(resource) => {
 			if resource.references == 1 && !resource.is_empty() {
+				if options.rdf_direction == Some(RdfDirection::CompoundLiteral)
+					&& is_anonymous(interpretation, id)
+				{
+					if let Some(value) = compound_literal(vocabulary, interpretation, resource) {
+						return Ok(Indexed::none(Object::Value(value)));
+					}
+				}
+
 				render_object(
 					vocabulary,
 					interpretation,
 					rdf_terms,
+					options,
 					graph,
 					id,
 					resource,
 					context,
 				)
 			} else {
-				render_reference(vocabulary, interpretation, id, context)
+				render_reference(vocabulary, interpretation, id, options, context)
 			}
 		}
-		None => render_reference(vocabulary, interpretation, id, context),
+		None => render_reference(vocabulary, interpretation, id, options, context),
 	}
 }
 
+/// Decodes the language-tagged string with direction described by the given
+/// compound literal node, if any.
+///
+/// The node must only have an `rdf:value` and an `rdf:direction` property,
+/// and optionally an `rdf:language` property, each with a single literal
+/// value. The language is taken from `rdf:language`, or from the language tag
+/// of the `rdf:value` literal.
+fn compound_literal<V, I>(
+	vocabulary: &V,
+	interpretation: &I,
+	resource: &SerResource<&I::Resource>,
+) -> Option<Value<V::Iri>>
+where
+	V: Vocabulary,
+	I: ReverseTermInterpretation<Iri = V::Iri, BlankId = V::BlankId, Literal = V::Literal>,
+{
+	if !resource.types.is_empty() || resource.graph.is_some() || !resource.list.is_empty() {
+		return None;
+	}
+
+	let mut value = None;
+	let mut language = None;
+	let mut direction = None;
+
+	for (prop, objects) in &resource.properties {
+		if objects.len() != 1 {
+			return None;
+		}
+
+		let object = objects.iter().next().unwrap();
+		let literal = vocabulary.literal(interpretation.literals_of(object).next()?)?;
+		let prop = vocabulary.iri(interpretation.iris_of(prop).next()?)?;
+
+		if prop == RDF_VALUE {
+			if let LiteralTypeRef::LangString(tag) = literal.type_ {
+				language.get_or_insert_with(|| tag.to_owned().into());
+			}
+
+			value = Some(literal.value)
+		} else if prop == RDF_DIRECTION {
+			direction = Some(literal.value.parse().ok()?)
+		} else if prop == RDF_LANGUAGE {
+			language = Some(LenientLangTagBuf::new(literal.value.to_owned()).0)
+		} else {
+			return None;
+		}
+	}
+
+	LangString::new(value?.into(), language, Some(direction?))
+		.ok()
+		.map(Value::LangString)
+}
+
 fn render_reference<V, I>(
 	vocabulary: &V,
 	interpretation: &I,
 	id: &I::Resource,
+	options: FromRdfOptions,
 	context: linked_data::Context<I>,
 ) -> Result<IndexedObject<V::Iri, V::BlankId>, SerializationError>
 where
@@ -622,7 +729,7 @@ where
 	V::BlankId: Clone,
 	I::Resource: Ord,
 {
-	match term_of(vocabulary, interpretation, id, context)? {
+	match term_of(vocabulary, interpretation, id, options, context)? {
 		Some(Term::Id(id)) => Ok(Indexed::none(Object::node(Node::with_id(id)))),
 		Some(Term::Literal(value)) => Ok(Indexed::none(Object::Value(value))),
 		None => Ok(Indexed::none(Object::node(Node::new()))),
@@ -656,6 +763,7 @@ fn term_of<V, T>(
 	vocabulary: &V,
 	interpretation: &T,
 	resource: &T::Resource,
+	options: FromRdfOptions,
 	context: linked_data::Context<T>,
 ) -> Result<Option<ResourceTerm<V>>, SerializationError>
 where
@@ -672,7 +780,16 @@ where
 				let value = match l.type_ {
 					LiteralTypeRef::Any(i) => {
 						let ty = vocabulary.iri(i).unwrap();
-						if ty == RDF_JSON {
+						let i18n = match options.rdf_direction {
+							Some(RdfDirection::I18nDatatype) => i18n_parts(ty),
+							_ => None,
+						};
+
+						if let Some((language, direction)) = i18n {
+							Value::LangString(
+								LangString::new(l.value.into(), language, Some(direction)).unwrap(),
+							)
+						} else if ty == RDF_JSON {
 							let (json, _) =
 								json_syntax::Value::parse_str(l.value).map_err(|e| {
 									SerializationError::InvalidJson(
@@ -702,7 +819,9 @@ where
 								)
 							})?;
 							Value::Literal(Literal::Number(n), Some(i.clone()))
-						} else if ty == XSD_STRING {
+						} else if ty == XSD_STRING
+							&& options.xsd_string == XsdStringPolicy::Implicit
+						{
 							Value::Literal(Literal::String(l.as_ref().into()), None)
 						} else {
 							Value::Literal(Literal::String(l.as_ref().into()), Some(i.clone()))
@@ -739,3 +858,170 @@ where
 		})
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Direction;
+	use iref::IriBuf;
+
+	fn iri(s: &str) -> IriBuf {
+		IriBuf::new(format!("http://example.com/{s}")).unwrap()
+	}
+
+	fn from_rdf(quads: &[Quad<rdf_types::Term>], options: FromRdfOptions) -> ExpandedDocument {
+		ExpandedDocument::from_interpreted_quads_full(
+			&(),
+			&(),
+			quads
+				.iter()
+				.map(|Quad(s, p, o, g)| Quad(s, p, o, g.as_ref())),
+			linked_data::Context::default(),
+			options,
+		)
+		.unwrap()
+	}
+
+	/// `rdf:type` quads, as in the fromRdf tests of the `useRdfType` option.
+	#[test]
+	fn use_rdf_type() {
+		let quads = [Quad(
+			Term::iri(iri("Subj1")),
+			Term::iri(RDF_TYPE.to_owned()),
+			Term::iri(iri("Type")),
+			None,
+		)];
+
+		let mut node = Node::with_id(Id::iri(iri("Subj1")));
+		node.types = Some(vec![Id::iri(iri("Type"))]);
+		let expected: ExpandedDocument = [Indexed::none(Object::node(node))].into_iter().collect();
+		assert_eq!(from_rdf(&quads, FromRdfOptions::default()), expected);
+
+		let mut node = Node::with_id(Id::iri(iri("Subj1")));
+		node.insert(
+			Id::iri(RDF_TYPE.to_owned()),
+			Indexed::none(Object::node(Node::with_id(Id::iri(iri("Type"))))),
+		);
+		let expected: ExpandedDocument = [Indexed::none(Object::node(node))].into_iter().collect();
+		assert_eq!(
+			from_rdf(
+				&quads,
+				FromRdfOptions {
+					use_rdf_type: true,
+					..Default::default()
+				}
+			),
+			expected
+		)
+	}
+
+	fn literal(value: &str, type_: rdf_types::LiteralType) -> rdf_types::Term {
+		Term::Literal(rdf_types::Literal::new(value.to_owned(), type_))
+	}
+
+	fn rtl_string(language: Option<&str>) -> ExpandedDocument {
+		let language = language.map(|tag| LenientLangTagBuf::new(tag.to_owned()).0);
+		let value = LangString::new("مرحبا".into(), language, Some(Direction::Rtl)).unwrap();
+
+		let mut node = Node::with_id(Id::iri(iri("s")));
+		node.insert(
+			Id::iri(iri("p")),
+			Indexed::none(Object::Value(Value::LangString(value))),
+		);
+		[Indexed::none(Object::node(node))].into_iter().collect()
+	}
+
+	#[test]
+	fn i18n_datatype() {
+		for (ty, language) in [("ar-EG_rtl", Some("ar-EG")), ("_rtl", None), ("rtl", None)] {
+			let ty = IriBuf::new(format!("https://www.w3.org/ns/i18n#{ty}")).unwrap();
+			let quads = [Quad(
+				Term::iri(iri("s")),
+				Term::iri(iri("p")),
+				literal("مرحبا", rdf_types::LiteralType::Any(ty)),
+				None,
+			)];
+
+			let document = from_rdf(
+				&quads,
+				FromRdfOptions {
+					rdf_direction: Some(RdfDirection::I18nDatatype),
+					..Default::default()
+				},
+			);
+			assert_eq!(document, rtl_string(language));
+
+			let document = from_rdf(&quads, FromRdfOptions::default());
+			assert_ne!(document, rtl_string(language))
+		}
+	}
+
+	#[test]
+	fn compound_literal() {
+		let b = Term::blank(rdf_types::BlankIdBuf::new("_:b0".to_owned()).unwrap());
+		let quads = [
+			Quad(Term::iri(iri("s")), Term::iri(iri("p")), b.clone(), None),
+			Quad(
+				b.clone(),
+				Term::iri(RDF_VALUE.to_owned()),
+				literal(
+					"مرحبا",
+					rdf_types::LiteralType::LangString("ar-EG".parse().unwrap()),
+				),
+				None,
+			),
+			Quad(
+				b,
+				Term::iri(RDF_DIRECTION.to_owned()),
+				literal("rtl", rdf_types::LiteralType::Any(XSD_STRING.to_owned())),
+				None,
+			),
+		];
+
+		let document = from_rdf(
+			&quads,
+			FromRdfOptions {
+				rdf_direction: Some(RdfDirection::CompoundLiteral),
+				..Default::default()
+			},
+		);
+		assert_eq!(document, rtl_string(Some("ar-EG")));
+
+		let document = from_rdf(&quads, FromRdfOptions::default());
+		assert_ne!(document, rtl_string(Some("ar-EG")))
+	}
+
+	#[test]
+	fn xsd_string() {
+		let quads = [Quad(
+			Term::iri(iri("s")),
+			Term::iri(iri("p")),
+			literal("Alice", rdf_types::LiteralType::Any(XSD_STRING.to_owned())),
+			None,
+		)];
+
+		let expected = |ty: Option<IriBuf>| -> ExpandedDocument {
+			let mut node = Node::with_id(Id::iri(iri("s")));
+			node.insert(
+				Id::iri(iri("p")),
+				Indexed::none(Object::Value(Value::Literal(
+					Literal::String("Alice".into()),
+					ty,
+				))),
+			);
+			[Indexed::none(Object::node(node))].into_iter().collect()
+		};
+
+		let document = from_rdf(&quads, FromRdfOptions::default());
+		assert_eq!(document, expected(None));
+
+		let document = from_rdf(
+			&quads,
+			FromRdfOptions {
+				xsd_string: XsdStringPolicy::Explicit,
+				..Default::default()
+			},
+		);
+		assert_eq!(document, expected(Some(XSD_STRING.to_owned())))
+	}
+}
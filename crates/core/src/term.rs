@@ -7,6 +7,7 @@ use std::fmt;
 
 /// Identifier, keyword or `@null`.
 #[derive(Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Term<T = IriBuf, B = BlankIdBuf> {
 	/// `@null` value.
 	Null,
@@ -9,6 +9,7 @@ use std::fmt;
 ///
 /// This is the value of a `@type` entry.
 #[derive(Clone, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Type<I = IriBuf> {
 	/// `@id`.
 	///
@@ -39,6 +40,21 @@ impl<I> Type<I> {
 		}
 	}
 
+	/// Checks if string values are coerced into node references by this
+	/// type mapping, and how.
+	///
+	/// Returns `Some(false)` for `@id`, meaning the string is an IRI
+	/// expanded (or compacted) relative to the document base only, and
+	/// `Some(true)` for `@vocab`, meaning the vocabulary mapping and terms
+	/// are also considered. Returns `None` for any other type mapping.
+	pub fn node_reference_vocab(&self) -> Option<bool> {
+		match self {
+			Type::Id => Some(false),
+			Type::Vocab => Some(true),
+			_ => None,
+		}
+	}
+
 	/// Maps the IRI of this type.
 	pub fn map<U>(self, f: impl FnOnce(I) -> U) -> Type<U> {
 		match self {
@@ -0,0 +1,133 @@
+use json_syntax::Value;
+use std::cmp::Ordering;
+use std::fmt::Write;
+
+/// Compares two strings by their UTF-16 code units.
+///
+/// This is the locale-independent order required by
+/// [RFC 8785](https://www.rfc-editor.org/rfc/rfc8785#name-sorting-of-object-properties)
+/// to sort object properties. It differs from the byte-wise order of `str`
+/// for characters outside the Basic Multilingual Plane.
+pub fn compare_utf16(a: &str, b: &str) -> Ordering {
+	a.encode_utf16().cmp(b.encode_utf16())
+}
+
+/// Canonical JSON serialization.
+///
+/// Writes JSON values according to the JSON Canonicalization Scheme
+/// ([RFC 8785](https://www.rfc-editor.org/rfc/rfc8785)): no whitespace,
+/// object properties sorted with [`compare_utf16`], numbers serialized as
+/// ECMAScript does and minimal string escaping. The output is byte-stable and
+/// suitable for hashing or signing, contrarily to the default printers.
+///
+/// This can be used on compacted documents as well as on the value of JSON
+/// literals (`@json`).
+pub trait CanonicalJson {
+	/// Writes the canonical form of this value into `output`.
+	fn write_canonical_json(&self, buffer: &mut ryu_js::Buffer, output: &mut String);
+
+	/// Returns the canonical form of this value.
+	fn to_canonical_json(&self) -> String {
+		let mut output = String::new();
+		self.write_canonical_json(&mut ryu_js::Buffer::new(), &mut output);
+		output
+	}
+}
+
+impl CanonicalJson for Value {
+	fn write_canonical_json(&self, buffer: &mut ryu_js::Buffer, output: &mut String) {
+		match self {
+			Self::Null => output.push_str("null"),
+			Self::Boolean(b) => output.push_str(if *b { "true" } else { "false" }),
+			Self::Number(n) => output.push_str(n.canonical_with(buffer).as_str()),
+			Self::String(s) => write_string(s, output),
+			Self::Array(items) => {
+				output.push('[');
+				for (i, item) in items.iter().enumerate() {
+					if i > 0 {
+						output.push(',')
+					}
+
+					item.write_canonical_json(buffer, output)
+				}
+				output.push(']')
+			}
+			Self::Object(object) => {
+				let mut entries: Vec<_> = object.iter().collect();
+				entries.sort_by(|a, b| compare_utf16(&a.key, &b.key));
+
+				output.push('{');
+				for (i, entry) in entries.into_iter().enumerate() {
+					if i > 0 {
+						output.push(',')
+					}
+
+					write_string(&entry.key, output);
+					output.push(':');
+					entry.value.write_canonical_json(buffer, output)
+				}
+				output.push('}')
+			}
+		}
+	}
+}
+
+fn write_string(s: &str, output: &mut String) {
+	output.push('"');
+
+	for c in s.chars() {
+		match c {
+			'\\' => output.push_str("\\\\"),
+			'"' => output.push_str("\\\""),
+			'\u{0008}' => output.push_str("\\b"),
+			'\u{0009}' => output.push_str("\\t"),
+			'\u{000a}' => output.push_str("\\n"),
+			'\u{000c}' => output.push_str("\\f"),
+			'\u{000d}' => output.push_str("\\r"),
+			'\u{0000}'..='\u{001f}' => write!(output, "\\u{:04x}", c as u32).unwrap(),
+			c => output.push(c),
+		}
+	}
+
+	output.push('"')
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use json_syntax::Parse;
+
+	#[test]
+	fn rfc8785() {
+		// Parsed from text, so the numbers keep the precision of the RFC 8785
+		// example.
+		let (value, _) = json_syntax::Value::parse_str(
+			r#"{
+				"numbers": [333333333.33333329, 1E30, 4.50, 2e-3, 0.000000000000000000000000001],
+				"string": "€$\u000f\nA'B\"\\\\\"/",
+				"literals": [null, true, false]
+			}"#,
+		)
+		.unwrap();
+
+		assert_eq!(
+			value.to_canonical_json(),
+			r#"{"literals":[null,true,false],"numbers":[333333333.3333333,1e+30,4.5,0.002,1e-27],"string":"€$\u000f\nA'B\"\\\\\"/"}"#
+		);
+
+		let value = json_syntax::json!({
+			"\u{20ac}": "Euro Sign",
+			"\r": "Carriage Return",
+			"\u{fb33}": "Hebrew Letter Dalet With Dagesh",
+			"1": "One",
+			"\u{1f600}": "Emoji: Grinning Face",
+			"\u{0080}": "Control",
+			"\u{00f6}": "Latin Small Letter O With Diaeresis"
+		});
+
+		assert_eq!(
+			value.to_canonical_json(),
+			"{\"\\r\":\"Carriage Return\",\"1\":\"One\",\"\u{0080}\":\"Control\",\"\u{00f6}\":\"Latin Small Letter O With Diaeresis\",\"\u{20ac}\":\"Euro Sign\",\"\u{1f600}\":\"Emoji: Grinning Face\",\"\u{fb33}\":\"Hebrew Letter Dalet With Dagesh\"}"
+		)
+	}
+}
@@ -1,5 +1,67 @@
+use locspan::{BorrowStripped, StrippedEq, StrippedHash};
 use std::collections::hash_map::DefaultHasher;
-use std::hash::{Hash, Hasher};
+use std::collections::HashSet;
+use std::hash::{BuildHasher, Hash, Hasher};
+
+#[cfg(not(feature = "ahash"))]
+type RandomState = std::collections::hash_map::RandomState;
+
+#[cfg(feature = "ahash")]
+type RandomState = ahash::RandomState;
+
+/// Hasher builder used by the node maps, node properties and context term
+/// definitions.
+///
+/// This wraps the standard library's SipHash-based `RandomState` by default,
+/// or `ahash::RandomState` with the `ahash` feature. Both are randomly
+/// seeded, which protects against hash flooding attacks, but `ahash` is
+/// significantly faster on large graphs. The feature only changes the inner
+/// hasher: public types are the same with or without it.
+#[derive(Clone, Default)]
+pub struct DefaultHashBuilder(RandomState);
+
+impl BuildHasher for DefaultHashBuilder {
+	type Hasher = DefaultMapHasher;
+
+	fn build_hasher(&self) -> DefaultMapHasher {
+		DefaultMapHasher(self.0.build_hasher())
+	}
+}
+
+impl std::fmt::Debug for DefaultHashBuilder {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		f.debug_struct("DefaultHashBuilder").finish_non_exhaustive()
+	}
+}
+
+/// Hasher built by [`DefaultHashBuilder`].
+pub struct DefaultMapHasher(<RandomState as BuildHasher>::Hasher);
+
+impl Hasher for DefaultMapHasher {
+	fn finish(&self) -> u64 {
+		self.0.finish()
+	}
+
+	fn write(&mut self, bytes: &[u8]) {
+		self.0.write(bytes)
+	}
+
+	fn write_u8(&mut self, i: u8) {
+		self.0.write_u8(i)
+	}
+
+	fn write_u32(&mut self, i: u32) {
+		self.0.write_u32(i)
+	}
+
+	fn write_u64(&mut self, i: u64) {
+		self.0.write_u64(i)
+	}
+
+	fn write_usize(&mut self, i: usize) {
+		self.0.write_usize(i)
+	}
+}
 
 /// Hash a set of items.
 ///
@@ -60,3 +122,87 @@ pub fn hash_map<'a, K: 'a + Hash, V: 'a + Hash, H: Hasher>(
 
 	hasher.write_u64(hash);
 }
+
+/// Removes duplicate items, keeping the first occurrence of each item.
+///
+/// Items are compared through their [`locspan::Stripped`] view, using a hash
+/// set, so this runs in linear time. Returns the number of removed items.
+pub(crate) fn deduplicate<T: StrippedEq + StrippedHash>(items: &mut Vec<T>) -> usize {
+	let len = items.len();
+	let mut seen = HashSet::with_capacity_and_hasher(len, DefaultHashBuilder::default());
+	let keep: Vec<bool> = items
+		.iter()
+		.map(|item| seen.insert(item.stripped()))
+		.collect();
+
+	let mut keep = keep.into_iter();
+	items.retain(|_| keep.next().unwrap());
+	len - items.len()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::collections::HashMap;
+
+	#[test]
+	fn default_hash_builder() {
+		let builder = DefaultHashBuilder::default();
+		let hash = |value: &str| builder.hash_one(value);
+
+		assert_eq!(hash("https://example.com/a"), hash("https://example.com/a"));
+		assert_ne!(hash("https://example.com/a"), hash("https://example.com/b"));
+
+		let mut map: HashMap<&str, usize, DefaultHashBuilder> = HashMap::default();
+		map.insert("a", 1);
+		map.insert("b", 2);
+		assert_eq!(map.get("a"), Some(&1));
+		assert_eq!(map.get("b"), Some(&2));
+		assert_eq!(map.get("c"), None)
+	}
+
+	#[derive(Debug, PartialEq, Eq, Hash)]
+	struct Item(u32);
+
+	impl locspan::StrippedPartialEq for Item {
+		fn stripped_eq(&self, other: &Self) -> bool {
+			self == other
+		}
+	}
+
+	impl StrippedEq for Item {}
+
+	impl StrippedHash for Item {
+		fn stripped_hash<H: Hasher>(&self, state: &mut H) {
+			self.hash(state)
+		}
+	}
+
+	#[test]
+	fn deduplicate_keeps_first_occurrences() {
+		let mut items: Vec<_> = [3, 1, 3, 2, 1, 3].into_iter().map(Item).collect();
+		assert_eq!(deduplicate(&mut items), 3);
+		assert_eq!(items, [Item(3), Item(1), Item(2)]);
+
+		let mut items: Vec<Item> = Vec::new();
+		assert_eq!(deduplicate(&mut items), 0);
+		assert!(items.is_empty())
+	}
+
+	#[test]
+	fn deduplicate_many() {
+		let mut items: Vec<_> = (0..100_000).map(|i| Item(i % 1000)).collect();
+		assert_eq!(deduplicate(&mut items), 99_000);
+		assert!(items.into_iter().map(|Item(i)| i).eq(0..1000))
+	}
+
+	#[test]
+	fn randomly_seeded() {
+		let hash = |builder: &DefaultHashBuilder| builder.hash_one("https://example.com/a");
+
+		assert_ne!(
+			hash(&DefaultHashBuilder::default()),
+			hash(&DefaultHashBuilder::default())
+		)
+	}
+}
@@ -1,7 +1,9 @@
 //! Utility functions.
 
+mod canonical;
 mod comparison;
 mod hash;
 
+pub use canonical::*;
 pub use comparison::*;
 pub use hash::*;
@@ -1,4 +1,7 @@
-use crate::{expand_element, ActiveProperty, Error, Expanded, Loader, Options, WarningHandler};
+use crate::{
+	expand_element, recover, ActiveProperty, Error, Expanded, Loader, Options, State,
+	WarningHandler,
+};
 use json_ld_core::{context::TermDefinitionRef, object, Context, Environment, Object};
 use json_ld_syntax::ContainerKind;
 use json_syntax::Array;
@@ -7,7 +10,8 @@ use std::hash::Hash;
 
 #[allow(clippy::too_many_arguments)]
 pub(crate) async fn expand_array<N, L, W>(
-	env: Environment<'_, N, L, W>,
+	mut env: Environment<'_, N, L, W>,
+	state: &State<'_, N::Iri, N::BlankId>,
 	active_context: &Context<N::Iri, N::BlankId>,
 	active_property: ActiveProperty<'_>,
 	active_property_definition: Option<TermDefinitionRef<'_, N::Iri, N::BlankId>>,
@@ -35,7 +39,9 @@ where
 	}
 
 	// For each item in element:
-	for item in element.iter() {
+	for (i, item) in element.iter().enumerate() {
+		state.check_progress(element)?;
+
 		// Initialize `expanded_item` to the result of using this algorithm
 		// recursively, passing `active_context`, `active_property`, `item` as element,
 		// `base_url`, the `frame_expansion`, `ordered`, and `from_map` flags.
@@ -45,6 +51,7 @@ where
 				loader: env.loader,
 				warnings: env.warnings,
 			},
+			state,
 			active_context,
 			active_property,
 			item,
@@ -52,9 +59,15 @@ where
 			options,
 			from_map,
 		))
-		.await?;
+		.await;
+
+		// In lossy mode, skip the item if its expansion failed.
+		if let Some(e) = recover(&mut env, options, e)? {
+			result.extend(e);
+		}
 
-		result.extend(e);
+		state.report_progress(element, i + 1);
+		state.tick().await
 	}
 
 	if is_list {
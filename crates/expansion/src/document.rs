@@ -1,6 +1,12 @@
 use super::expand_element;
-use crate::{ActiveProperty, Error, Loader, Options, WarningHandler};
-use json_ld_core::{Context, Environment, ExpandedDocument, IndexedObject, Object};
+use crate::{
+	check_input, recover, ActiveProperty, Error, LimitedLoader, Loader, Options, State,
+	WarningHandler,
+};
+use json_ld_context_processing::SharedContextCache;
+use json_ld_core::{
+	progress::Progress, Context, Environment, ExpandedDocument, IndexedObject, Object,
+};
 use json_syntax::Value;
 use rdf_types::VocabularyMut;
 use std::hash::Hash;
@@ -16,6 +22,8 @@ pub(crate) async fn expand<'a, N, L, W>(
 	active_context: Context<N::Iri, N::BlankId>,
 	base_url: Option<&'a N::Iri>,
 	options: Options,
+	progress: &Progress,
+	cache: Option<&SharedContextCache<N::Iri, N::BlankId>>,
 ) -> Result<ExpandedDocument<N::Iri, N::BlankId>, Error>
 where
 	N: VocabularyMut,
@@ -24,8 +32,38 @@ where
 	L: Loader,
 	W: WarningHandler<N>,
 {
+	check_input(document, options.max_depth, &options.limits)?;
+
+	// The same limits apply to the remote contexts.
+	let loader = LimitedLoader::new(env.loader, options.max_depth, options.limits);
+	let mut env = Environment {
+		vocabulary: env.vocabulary,
+		loader: &loader,
+		warnings: env.warnings,
+	};
+
+	let state = State::new(
+		options.limits,
+		cache,
+		options.yield_every,
+		progress,
+		document,
+	);
+
+	// The progress over the items of a top-level array is reported by
+	// `expand_array`.
+	let is_array = document.is_array();
+	if !is_array {
+		progress.check()?;
+	}
+
 	let expanded = expand_element(
-		env,
+		Environment {
+			vocabulary: env.vocabulary,
+			loader: env.loader,
+			warnings: env.warnings,
+		},
+		&state,
 		&active_context,
 		ActiveProperty::None,
 		document,
@@ -33,25 +71,499 @@ where
 		options,
 		false,
 	)
-	.await?;
-	if expanded.len() == 1 {
+	.await;
+
+	if !is_array {
+		progress.report(1, 1)
+	}
+
+	// In lossy mode, the whole document may be skipped.
+	let expanded = match recover(&mut env, options, expanded)? {
+		Some(expanded) => expanded,
+		None => return Ok(ExpandedDocument::new()),
+	};
+	let result = if expanded.len() == 1 {
 		let obj = expanded.into_iter().next().unwrap();
 		match obj.into_unnamed_graph() {
-			Ok(graph) => Ok(ExpandedDocument::from(graph)),
+			Ok(graph) => ExpandedDocument::from(graph),
 			Err(obj) => {
 				let mut result = ExpandedDocument::new();
 				if filter_top_level_item(&obj) {
 					result.insert(obj);
 				}
-				Ok(result)
+				result
 			}
 		}
 	} else {
-		Ok(expanded.into_iter().filter(filter_top_level_item).collect())
-	}
+		expanded.into_iter().filter(filter_top_level_item).collect()
+	};
+
+	Ok(result)
 }
 
 pub(crate) fn filter_top_level_item<T, B>(item: &IndexedObject<T, B>) -> bool {
 	// Remove dangling values.
 	!matches!(item.inner(), Object::Value(_))
 }
+
+#[cfg(test)]
+mod tests {
+	use crate::{Error, Expand, LanguageCase, Limits, Options, ResourceLimit, DEFAULT_MAX_DEPTH};
+	use json_ld_core::{Context, NoLoader};
+	use json_syntax::Value;
+
+	fn nested(depth: usize) -> Value {
+		let mut value = json_syntax::json!({ "@id": "https://example.com/leaf" });
+		for _ in 0..depth {
+			value = json_syntax::json!({ "https://example.com/p": value });
+		}
+		value
+	}
+
+	fn expand(input: &Value, max_depth: Option<usize>) -> Result<(), Error> {
+		futures::executor::block_on(input.expand_full(
+			&mut (),
+			Context::default(),
+			None,
+			&NoLoader,
+			Options {
+				max_depth,
+				..Options::default()
+			},
+			(),
+		))
+		.map(|_| ())
+	}
+
+	#[test]
+	fn maximum_depth() {
+		let input = nested(10);
+		assert!(expand(&input, Some(11)).is_ok());
+		assert!(matches!(
+			expand(&input, Some(10)),
+			Err(Error::MaximumDepthExceeded(10))
+		));
+
+		// Valid documents are not rejected by default.
+		assert_eq!(Options::default().max_depth, None);
+
+		let input = nested(1_000);
+		assert!(matches!(
+			expand(&input, Some(DEFAULT_MAX_DEPTH)),
+			Err(Error::MaximumDepthExceeded(DEFAULT_MAX_DEPTH))
+		))
+	}
+
+	#[test]
+	fn lossy() {
+		let input = json_syntax::json!({
+			"@id": "https://example.com/a",
+			"@index": 1,
+			"https://example.com/name": "A",
+			"https://example.com/p": [
+				"B",
+				{ "@value": "C", "@language": "en", "@type": "https://example.com/T" }
+			],
+			"https://example.com/q": { "@value": "D", "@index": 2 }
+		});
+
+		assert!(matches!(
+			futures::executor::block_on(input.expand(&NoLoader)),
+			Err(Error::InvalidIndexValue)
+		));
+
+		let (expanded, errors) =
+			futures::executor::block_on(input.expand_lossy(&NoLoader)).unwrap();
+		assert_eq!(errors.len(), 3);
+
+		let expected = json_syntax::json!({
+			"@id": "https://example.com/a",
+			"https://example.com/name": "A",
+			"https://example.com/p": "B"
+		});
+		let expected = futures::executor::block_on(expected.expand(&NoLoader)).unwrap();
+		assert_eq!(expanded, expected)
+	}
+
+	#[test]
+	fn language_case() {
+		let input = json_syntax::json!({
+			"@context": {
+				"@language": "FR-ca",
+				"names": { "@id": "https://example.com/names", "@container": "@language" }
+			},
+			"https://example.com/name": [{ "@value": "a", "@language": "EN-us" }, "b"],
+			"names": { "zh-hant-TW": "c" }
+		});
+
+		let languages = |language_case| {
+			let expanded = futures::executor::block_on(input.expand_full(
+				&mut (),
+				Context::default(),
+				None,
+				&NoLoader,
+				Options {
+					language_case,
+					..Options::default()
+				},
+				(),
+			))
+			.unwrap();
+
+			let mut languages: Vec<_> = expanded
+				.main_node()
+				.unwrap()
+				.properties()
+				.iter()
+				.flat_map(|(_, objects)| objects)
+				.map(|object| object.language().unwrap().as_str().to_owned())
+				.collect();
+			languages.sort_unstable();
+			languages
+		};
+
+		assert_eq!(
+			languages(LanguageCase::Preserve),
+			["EN-us", "FR-ca", "zh-hant-TW"]
+		);
+		assert_eq!(
+			languages(LanguageCase::Lowercase),
+			["en-us", "fr-ca", "zh-hant-tw"]
+		);
+		assert_eq!(
+			languages(LanguageCase::Canonical),
+			["en-US", "fr-CA", "zh-Hant-TW"]
+		)
+	}
+
+	#[test]
+	fn resource_limits() {
+		let input = json_syntax::json!({
+			"@id": "https://example.com/a",
+			"https://example.com/name": "Alice",
+			"https://example.com/knows": { "@id": "https://example.com/b" },
+			"https://example.com/graph": {
+				"@graph": { "@id": "https://example.com/c", "https://example.com/name": "C" }
+			}
+		});
+
+		let expand = |limits| {
+			futures::executor::block_on(input.expand_full(
+				&mut (),
+				Context::default(),
+				None,
+				&NoLoader,
+				Options {
+					limits,
+					..Options::default()
+				},
+				(),
+			))
+			.map(|_| ())
+		};
+
+		let limits = Limits {
+			max_literal_length: Some(25),
+			max_properties: Some(4),
+			max_nodes: Some(4),
+			max_graphs: Some(1),
+		};
+		assert!(expand(limits).is_ok());
+
+		for (limits, expected) in [
+			(
+				Limits {
+					max_literal_length: Some(24),
+					..limits
+				},
+				ResourceLimit::LiteralLength(24),
+			),
+			(
+				Limits {
+					max_properties: Some(3),
+					..limits
+				},
+				ResourceLimit::Properties(3),
+			),
+			(
+				Limits {
+					max_nodes: Some(3),
+					..limits
+				},
+				ResourceLimit::Nodes(3),
+			),
+			(
+				Limits {
+					max_graphs: Some(0),
+					..limits
+				},
+				ResourceLimit::Graphs(0),
+			),
+		] {
+			match expand(limits) {
+				Err(Error::ResourceLimit(limit)) => assert_eq!(limit, expected),
+				other => panic!("unexpected result: {other:?}"),
+			}
+		}
+	}
+
+	#[test]
+	fn resource_limits_fail_early() {
+		// The node limit is exceeded before the invalid index is reached.
+		let mut items: Vec<_> = (0..10)
+			.map(|i| json_syntax::json!({ "@id": format!("https://example.com/{i}") }))
+			.collect();
+		items.push(json_syntax::json!({ "@id": "https://example.com/x", "@index": 1 }));
+		let input = Value::Array(items);
+
+		let result = futures::executor::block_on(input.expand_full(
+			&mut (),
+			Context::default(),
+			None,
+			&NoLoader,
+			Options {
+				limits: Limits {
+					max_nodes: Some(5),
+					..Limits::default()
+				},
+				..Options::default()
+			},
+			(),
+		));
+
+		assert!(matches!(
+			result,
+			Err(Error::ResourceLimit(ResourceLimit::Nodes(5)))
+		))
+	}
+
+	#[test]
+	fn remote_context_limits() {
+		use iref::IriBuf;
+		use json_ld_core::RemoteDocument;
+		use std::collections::HashMap;
+
+		let url = IriBuf::new("https://example.com/context.jsonld".to_owned()).unwrap();
+		let mut loader = HashMap::new();
+		loader.insert(
+			url.clone(),
+			RemoteDocument::new(
+				Some(url.clone()),
+				None,
+				json_syntax::json!({
+					"@context": { "name": "https://example.com/a-rather-long-property-name" }
+				}),
+			),
+		);
+
+		let input = json_syntax::json!({
+			"@context": "https://example.com/context.jsonld",
+			"name": "Alice"
+		});
+
+		let expand = |limits| {
+			futures::executor::block_on(input.expand_full(
+				&mut (),
+				Context::default(),
+				None,
+				&loader,
+				Options {
+					limits,
+					..Options::default()
+				},
+				(),
+			))
+			.map(|_| ())
+		};
+
+		assert!(expand(Limits::default()).is_ok());
+		assert!(matches!(
+			expand(Limits {
+				max_literal_length: Some(40),
+				..Limits::default()
+			}),
+			Err(Error::ResourceLimit(ResourceLimit::LiteralLength(40)))
+		))
+	}
+
+	#[test]
+	fn shared_context_cache() {
+		use iref::{Iri, IriBuf};
+		use json_ld_context_processing::SharedContextCache;
+		use json_ld_core::{LoadError, Loader, RemoteDocument};
+		use std::sync::atomic::{AtomicUsize, Ordering};
+
+		struct CountingLoader(AtomicUsize);
+
+		impl Loader for CountingLoader {
+			async fn load(&self, url: &Iri) -> Result<RemoteDocument<IriBuf>, LoadError> {
+				self.0.fetch_add(1, Ordering::Relaxed);
+				Ok(RemoteDocument::new(
+					Some(url.to_owned()),
+					None,
+					json_syntax::json!({ "@context": { "name": "https://schema.org/name" } }),
+				))
+			}
+		}
+
+		let input = json_syntax::json!({
+			"@context": "https://example.com/context.jsonld",
+			"name": "Alice"
+		});
+
+		let loader = CountingLoader(AtomicUsize::new(0));
+		let cache = SharedContextCache::new();
+		for _ in 0..2 {
+			let expanded = futures::executor::block_on(input.expand_full_with_cache(
+				&mut (),
+				Context::default(),
+				None,
+				&loader,
+				Options::default(),
+				(),
+				&cache,
+			))
+			.unwrap();
+			assert_eq!(expanded.len(), 1)
+		}
+
+		// The remote context is only loaded and processed once.
+		assert_eq!(loader.0.load(Ordering::Relaxed), 1);
+		let stats = cache.stats();
+		assert_eq!(stats.hits, 1);
+		assert_eq!(stats.misses, 1)
+	}
+
+	#[test]
+	fn progress() {
+		use json_ld_core::progress::Progress;
+		use std::sync::{Arc, Mutex};
+
+		// Only the items of the top-level array are reported.
+		let input = json_syntax::json!([
+			{ "@id": "https://example.com/a", "https://example.com/p": ["A", "B"] },
+			{ "@id": "https://example.com/b", "https://example.com/p": "B" },
+			{ "@id": "https://example.com/c", "https://example.com/p": [["C"]] }
+		]);
+
+		let expand = |progress: &Progress| {
+			futures::executor::block_on(input.expand_full_with_progress(
+				&mut (),
+				Context::default(),
+				None,
+				&NoLoader,
+				Options::default(),
+				(),
+				progress,
+			))
+		};
+
+		let reports = Arc::new(Mutex::new(Vec::new()));
+		let progress = Progress::with_callback({
+			let reports = reports.clone();
+			move |processed, total| reports.lock().unwrap().push((processed, total))
+		});
+		assert_eq!(expand(&progress).unwrap().len(), 3);
+		assert_eq!(*reports.lock().unwrap(), [(1, 3), (2, 3), (3, 3)]);
+
+		// A single top-level object counts as one item.
+		reports.lock().unwrap().clear();
+		let object =
+			json_syntax::json!({ "@id": "https://example.com/a", "https://example.com/p": ["A"] });
+		futures::executor::block_on(object.expand_full_with_progress(
+			&mut (),
+			Context::default(),
+			None,
+			&NoLoader,
+			Options::default(),
+			(),
+			&progress,
+		))
+		.unwrap();
+		assert_eq!(*reports.lock().unwrap(), [(1, 1)]);
+
+		progress.cancel();
+		assert!(matches!(expand(&progress), Err(Error::Cancelled)));
+
+		// The cancellation is not recovered in lossy mode.
+		assert!(matches!(
+			futures::executor::block_on(input.expand_full_with_progress(
+				&mut (),
+				Context::default(),
+				None,
+				&NoLoader,
+				Options {
+					lossy: true,
+					..Options::default()
+				},
+				(),
+				&progress,
+			)),
+			Err(Error::Cancelled)
+		))
+	}
+
+	#[test]
+	fn yield_every() {
+		use futures::FutureExt;
+
+		let input = json_syntax::json!([
+			{ "@id": "https://example.com/a", "https://example.com/p": "A" },
+			{ "@id": "https://example.com/b", "https://example.com/p": "B" }
+		]);
+
+		let expand = |yield_every| {
+			input.expand_full(
+				rdf_types::vocabulary::no_vocabulary_mut(),
+				Context::default(),
+				None,
+				&NoLoader,
+				Options {
+					yield_every,
+					..Options::default()
+				},
+				(),
+			)
+		};
+
+		assert!(expand(None).now_or_never().is_some());
+		assert!(expand(Some(1)).now_or_never().is_none());
+		assert_eq!(
+			futures::executor::block_on(expand(Some(1))).unwrap().len(),
+			2
+		)
+	}
+
+	#[test]
+	fn yield_every_small_arrays() {
+		use futures::FutureExt;
+
+		// Each array holds a single item, but items are counted over the
+		// whole document.
+		let input = json_syntax::json!({
+			"https://example.com/p": ["A"],
+			"https://example.com/q": ["B"],
+			"https://example.com/r": [{ "https://example.com/s": ["C"] }]
+		});
+
+		let expand = |yield_every| {
+			input.expand_full(
+				rdf_types::vocabulary::no_vocabulary_mut(),
+				Context::default(),
+				None,
+				&NoLoader,
+				Options {
+					yield_every,
+					..Options::default()
+				},
+				(),
+			)
+		};
+
+		assert!(expand(Some(2)).now_or_never().is_none());
+		assert_eq!(
+			futures::executor::block_on(expand(Some(2))).unwrap(),
+			futures::executor::block_on(expand(None)).unwrap()
+		)
+	}
+}
@@ -1,10 +1,12 @@
 use crate::{
-	expand_array, expand_iri, expand_literal, expand_node, expand_value, Error, Expanded,
-	GivenLiteralValue, LiteralValue, Loader, Options, Warning, WarningHandler,
+	expand_array, expand_iri, expand_literal, expand_node, expand_value, recover, Action, Error,
+	Expanded, GivenLiteralValue, LiteralValue, Loader, Options, State, Warning, WarningHandler,
+};
+use json_ld_context_processing::{
+	algorithm::process_scoped_context, Options as ProcessingOptions, Process,
 };
-use json_ld_context_processing::{Options as ProcessingOptions, Process};
 use json_ld_core::{object, Context, Environment, Id, Indexed, Object, Term, ValidId};
-use json_ld_syntax::{Keyword, Nullable};
+use json_ld_syntax::{is_keyword, is_keyword_like, Keyword, Nullable};
 use json_syntax::{object::Entry, Value};
 use mown::Mown;
 use rdf_types::VocabularyMut;
@@ -71,6 +73,7 @@ pub(crate) type ElementExpansionResult<T, B> = Result<Expanded<T, B>, Error>;
 #[allow(clippy::too_many_arguments)]
 pub(crate) async fn expand_element<'a, N, L, W>(
 	mut env: Environment<'a, N, L, W>,
+	state: &'a State<'_, N::Iri, N::BlankId>,
 	active_context: &'a Context<N::Iri, N::BlankId>,
 	active_property: ActiveProperty<'a>,
 	element: &'a Value,
@@ -110,6 +113,7 @@ where
 		Value::Array(element) => {
 			expand_array(
 				env,
+				state,
 				active_context,
 				active_property,
 				active_property_definition,
@@ -150,6 +154,7 @@ where
 			// Otherwise element is a map.
 			// If `active_context` has a `previous_context`, the active context is not
 			// propagated.
+			let property_scoped;
 			let mut active_context = Mown::Borrowed(active_context);
 			if let Some(previous_context) = active_context.previous_context() {
 				// If `from_map` is undefined or false, and `element` does not contain an entry
@@ -172,19 +177,16 @@ where
 			// `override_protected`.
 			if let Some(property_scoped_context) = property_scoped_context {
 				let options: ProcessingOptions = options.into();
-				active_context = Mown::Owned(
-					property_scoped_context
-						.process_with(
-							env.vocabulary,
-							active_context.as_ref(),
-							env.loader,
-							property_scoped_base_url,
-							options.with_override(),
-						)
-						.await?
-						.into_processed(), // .err_at(|| active_property.as_ref().map(Meta::metadata).cloned().unwrap_or_default())?
-					                   // .into_inner(),
-				);
+				property_scoped = process_scoped_context(
+					env.vocabulary,
+					active_context.as_ref(),
+					property_scoped_context,
+					env.loader,
+					property_scoped_base_url,
+					options.with_override(),
+				)
+				.await?;
+				active_context = Mown::Borrowed(property_scoped.as_ref());
 			}
 
 			// If `element` contains the entry `@context`, set `active_context` to the result
@@ -198,18 +200,34 @@ where
 				let local_context =
 					json_ld_syntax::context::Context::try_from_json(local_context.clone())?;
 
-				active_context = Mown::Owned(
-					local_context
-						.process_with(
-							env.vocabulary,
-							active_context.as_ref(),
-							env.loader,
-							base_url.cloned(),
-							options.into(),
-						)
-						.await?
-						.into_processed(),
-				);
+				let processed = match state.cache() {
+					Some(cache) => {
+						cache
+							.process_full(
+								&local_context,
+								env.vocabulary,
+								active_context.as_ref(),
+								env.loader,
+								base_url.cloned(),
+								options.into(),
+								json_ld_core::warning::Print,
+							)
+							.await?
+					}
+					None => {
+						local_context
+							.process_with(
+								env.vocabulary,
+								active_context.as_ref(),
+								env.loader,
+								base_url.cloned(),
+								options.into(),
+							)
+							.await?
+					}
+				};
+
+				active_context = Mown::Owned(processed.into_processed());
 			}
 
 			let entries: Cow<[Entry]> = if options.ordered {
@@ -239,7 +257,7 @@ where
 			// This is used for expanding values that may be relevant to any previous
 			// type-scoped context.
 			let type_scoped_context = active_context.as_ref();
-			let mut active_context = Mown::Borrowed(active_context.as_ref());
+			let mut type_scoped = None;
 
 			// For each `key` and `value` in `element` ordered lexicographically by key where
 			// key IRI expands to @type:
@@ -268,23 +286,25 @@ where
 							// definition for value in `active_context`, and `false` for `propagate`.
 							let base_url = term_definition.base_url().cloned();
 							let options: ProcessingOptions = options.into();
-							active_context = Mown::Owned(
-								local_context
-									.process_with(
-										env.vocabulary,
-										active_context.as_ref(),
-										env.loader,
-										base_url,
-										options.without_propagation(),
-									)
-									.await?
-									.into_processed(),
+							type_scoped = Some(
+								process_scoped_context(
+									env.vocabulary,
+									type_scoped.as_deref().unwrap_or(type_scoped_context),
+									local_context,
+									env.loader,
+									base_url,
+									options.without_propagation(),
+								)
+								.await?,
 							);
 						}
 					}
 				}
 			}
 
+			let active_context =
+				Mown::Borrowed(type_scoped.as_deref().unwrap_or(type_scoped_context));
+
 			// Initialize `input_type` to expansion of the last value of the first entry in
 			// `element` expanding to `@type` (if any), ordering entries lexicographically by
 			// key.
@@ -322,13 +342,25 @@ where
 					env.warnings.handle(env.vocabulary, Warning::EmptyTerm);
 				}
 
-				let expanded_key = expand_iri(
-					&mut env,
-					active_context.as_ref(),
-					Nullable::Some(key.as_str().into()),
-					false,
-					Some(options.policy.vocab),
-				)?;
+				let expanded_key = if is_keyword_like(key) && !is_keyword(key) {
+					// Keyword-like keys are reserved for future use.
+					env.warnings
+						.handle(env.vocabulary, Warning::KeywordLikeTerm(key.to_string()));
+
+					match options.policy.keyword_like {
+						Action::Keep => Some(Term::Id(Id::Invalid(key.to_string()))),
+						Action::Drop => Some(Term::Null),
+						Action::Reject => return Err(Error::KeyExpansionFailed(key.to_string())),
+					}
+				} else {
+					expand_iri(
+						&mut env,
+						active_context.as_ref(),
+						Nullable::Some(key.as_str().into()),
+						false,
+						Some(options.policy.vocab),
+					)?
+				};
 
 				if let Some(expanded_key) = expanded_key {
 					match &expanded_key {
@@ -377,6 +409,7 @@ where
 							loader: env.loader,
 							warnings: env.warnings,
 						},
+						state,
 						active_context.as_ref(),
 						active_property,
 						item,
@@ -384,8 +417,12 @@ where
 						options,
 						false,
 					))
-					.await?;
-					result.extend(e)
+					.await;
+
+					// In lossy mode, skip the item if its expansion failed.
+					if let Some(e) = recover(&mut env, options, e)? {
+						result.extend(e)
+					}
 				}
 
 				Ok(Expanded::Object(Indexed::new(
@@ -410,6 +447,7 @@ where
 				// and ordered flags.
 				Box::pin(expand_element(
 					env,
+					state,
 					active_context.as_ref(),
 					active_property,
 					&set_entry,
@@ -423,6 +461,7 @@ where
 				let expanded_value = expand_value(
 					&mut env,
 					options.policy.vocab,
+					options.language_case,
 					input_type,
 					type_scoped_context,
 					expanded_entries,
@@ -436,8 +475,10 @@ where
 				}
 			} else {
 				// Node objects.
+				state.node()?;
 				let e = expand_node(
 					env,
+					state,
 					active_context.as_ref(),
 					type_scoped_context,
 					active_property,
@@ -470,36 +511,43 @@ where
 			// Context Processing algorithm, passing `active_context`, `property_scoped_context` as
 			// local context, and `base_url` from the term definition for `active_property` in
 			// `active context`.
+			let property_scoped;
 			let active_context = if let Some(property_scoped_context) = property_scoped_context {
 				// FIXME it is unclear what we should use as `base_url` if there is no term definition for `active_context`.
 				let base_url = active_property
 					.get_from(active_context)
 					.and_then(|definition| definition.base_url().cloned());
 
-				let result = property_scoped_context
-					.process_with(
-						env.vocabulary,
-						active_context,
-						env.loader,
-						base_url,
-						options.into(),
-					)
-					.await?
-					.into_processed();
-				Mown::Owned(result)
+				property_scoped = process_scoped_context(
+					env.vocabulary,
+					active_context,
+					property_scoped_context,
+					env.loader,
+					base_url,
+					options.into(),
+				)
+				.await?;
+				property_scoped.as_ref()
 			} else {
-				Mown::Borrowed(active_context)
+				active_context
 			};
 
 			// Return the result of the Value Expansion algorithm, passing the `active_context`,
 			// `active_property`, and `element` as value.
-			Ok(Expanded::Object(expand_literal(
+			let expanded = expand_literal(
 				env,
 				options.policy.vocab,
-				active_context.as_ref(),
+				options.language_case,
+				active_context,
 				active_property,
 				LiteralValue::Given(GivenLiteralValue::new(element)),
-			)?))
+			)?;
+
+			if expanded.is_node() {
+				state.node()?
+			}
+
+			Ok(Expanded::Object(expanded))
 		}
 	}
 }
@@ -1,5 +1,8 @@
+use crate::{Options, Warning, WarningHandler};
 use json_ld_context_processing::algorithm::RejectVocab;
+use json_ld_core::{progress::Cancelled, Environment};
 use json_ld_syntax::ErrorCode;
+use rdf_types::vocabulary::BlankIdVocabulary;
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
@@ -56,6 +59,34 @@ pub enum Error {
 
 	#[error("Forbidden use of `@vocab`")]
 	ForbiddenVocab,
+
+	#[error("Invalid `@annotation` value")]
+	InvalidAnnotation,
+
+	#[error("Maximum depth exceeded (limit is {0})")]
+	MaximumDepthExceeded(usize),
+
+	#[error("Resource limit exceeded: {0}")]
+	ResourceLimit(ResourceLimit),
+
+	#[error("Expansion cancelled")]
+	Cancelled,
+}
+
+/// Exceeded [resource limit](crate::Limits).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum ResourceLimit {
+	#[error("literal longer than {0} bytes")]
+	LiteralLength(usize),
+
+	#[error("object with more than {0} entries")]
+	Properties(usize),
+
+	#[error("more than {0} nodes")]
+	Nodes(usize),
+
+	#[error("more than {0} graphs")]
+	Graphs(usize),
 }
 
 impl From<RejectVocab> for Error {
@@ -85,10 +116,81 @@ impl Error {
 			Self::Literal(e) => e.code(),
 			Self::Value(e) => e.code(),
 			Self::ForbiddenVocab => ErrorCode::InvalidVocabMapping,
+			Self::InvalidAnnotation => ErrorCode::InvalidAnnotation,
+			Self::MaximumDepthExceeded(_) => ErrorCode::MaximumDepthExceeded,
+			Self::ResourceLimit(_) => ErrorCode::ResourceLimitExceeded,
+			Self::Cancelled => ErrorCode::OperationCancelled,
 		}
 	}
+
+	/// Checks if the expansion can recover from this error by skipping the
+	/// offending entry, in [lossy mode](Options::lossy).
+	///
+	/// Errors caused by the context, the maximum depth, the resource
+	/// [limits](crate::Limits), the cancellation of the expansion, or the
+	/// rejection of a term by the expansion [policy](crate::Policy) are not
+	/// recoverable.
+	pub fn is_recoverable(&self) -> bool {
+		!matches!(
+			self,
+			Self::ContextSyntax(_)
+				| Self::ContextProcessing(_)
+				| Self::KeyExpansionFailed(_)
+				| Self::ForbiddenVocab
+				| Self::Literal(crate::LiteralExpansionError::ForbiddenVocab)
+				| Self::Value(crate::InvalidValue::ForbiddenVocab)
+				| Self::MaximumDepthExceeded(_)
+				| Self::ResourceLimit(_)
+				| Self::Cancelled
+		)
+	}
+}
+
+/// Recovers from the given `error` in lossy mode.
+///
+/// If [`Options::lossy`] is set and the error is recoverable, the error is
+/// reported to the warning handler and the caller must skip the offending
+/// entry. Otherwise the error is returned.
+pub(crate) fn recover_error<N, L, W>(
+	env: &mut Environment<N, L, W>,
+	options: Options,
+	error: Error,
+) -> Result<(), Error>
+where
+	N: BlankIdVocabulary,
+	W: WarningHandler<N>,
+{
+	if options.lossy && error.is_recoverable() {
+		env.warnings
+			.handle(env.vocabulary, Warning::RecoveredError(error));
+		Ok(())
+	} else {
+		Err(error)
+	}
+}
+
+/// Recovers from the given `result` in lossy mode.
+///
+/// Returns `None` if the result was a recoverable error, in which case the
+/// caller must skip the offending entry.
+pub(crate) fn recover<N, L, W, T>(
+	env: &mut Environment<N, L, W>,
+	options: Options,
+	result: Result<T, Error>,
+) -> Result<Option<T>, Error>
+where
+	N: BlankIdVocabulary,
+	W: WarningHandler<N>,
+{
+	match result {
+		Ok(t) => Ok(Some(t)),
+		Err(e) => recover_error(env, options, e).map(|()| None),
+	}
 }
 
+#[cfg(feature = "miette")]
+json_ld_syntax::impl_diagnostic!(Error);
+
 impl Error {
 	pub fn duplicate_key_ref(
 		json_syntax::object::Duplicate(a, _b): json_syntax::object::Duplicate<
@@ -101,6 +203,13 @@ impl Error {
 
 impl From<json_ld_context_processing::Error> for Error {
 	fn from(e: json_ld_context_processing::Error) -> Self {
+		// Limits exceeded by a remote context are reported as such.
+		if let json_ld_context_processing::Error::ContextLoadingFailed(load) = e.inner() {
+			if let Some(limit) = load.cause.downcast_ref::<crate::ExceededLimit>() {
+				return (*limit).into();
+			}
+		}
+
 		Self::ContextProcessing(e)
 	}
 }
@@ -111,6 +220,18 @@ impl From<crate::LiteralExpansionError> for Error {
 	}
 }
 
+impl From<Cancelled> for Error {
+	fn from(_: Cancelled) -> Self {
+		Self::Cancelled
+	}
+}
+
+impl From<ResourceLimit> for Error {
+	fn from(e: ResourceLimit) -> Self {
+		Self::ResourceLimit(e)
+	}
+}
+
 impl From<crate::InvalidValue> for Error {
 	fn from(e: crate::InvalidValue) -> Self {
 		Self::Value(e)
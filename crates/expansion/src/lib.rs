@@ -6,8 +6,8 @@
 //! The expansion algorithm is provided by the [`Expand`] trait.
 use std::hash::Hash;
 
-use json_ld_context_processing::Context;
-use json_ld_core::{Environment, ExpandedDocument, Loader, RemoteDocument};
+use json_ld_context_processing::{Context, SharedContextCache};
+use json_ld_core::{progress::Progress, Environment, ExpandedDocument, Loader, RemoteDocument};
 use json_syntax::Value;
 use rdf_types::{vocabulary, vocabulary::BlankIdVocabulary, BlankIdBuf, VocabularyMut};
 
@@ -16,28 +16,40 @@ mod document;
 mod element;
 mod error;
 mod expanded;
+mod limits;
 mod literal;
 mod node;
 mod options;
+mod state;
+mod term;
 mod value;
 mod warning;
 
 pub use error::*;
 pub use expanded::*;
 pub use options::*;
+pub use term::ExpandTerm;
 pub use warning::*;
 
 pub(crate) use array::*;
 pub(crate) use document::filter_top_level_item;
 pub(crate) use element::*;
 pub(crate) use json_ld_context_processing::algorithm::expand_iri_simple as expand_iri;
+pub(crate) use limits::*;
 pub(crate) use literal::*;
 pub(crate) use node::*;
+pub(crate) use state::*;
 pub(crate) use value::*;
 
 /// Result of the document expansion.
 pub type ExpansionResult<T, B> = Result<ExpandedDocument<T, B>, Error>;
 
+/// Result of the [lossy](Options::lossy) document expansion.
+///
+/// On success, the partially expanded document is returned along with the
+/// errors recovered from.
+pub type LossyExpansionResult<T, B> = Result<(ExpandedDocument<T, B>, Vec<Error>), Error>;
+
 /// Handler for the possible warnings emitted during the expansion
 /// of a JSON-LD document.
 pub trait WarningHandler<N: BlankIdVocabulary>:
@@ -131,6 +143,83 @@ pub trait Expand<Iri> {
 		L: Loader,
 		W: WarningHandler<N>;
 
+	/// Expand the document with full options, reporting the progress of the
+	/// expansion to `progress`.
+	///
+	/// The progress is reported, and the cancellation checked, after each
+	/// top-level item of the document. If the expansion is
+	/// [cancelled](Progress::cancel), [`Error::Cancelled`] is returned. The
+	/// default implementation ignores the progress.
+	///
+	/// See [`Expand::expand_full`] for the other parameters.
+	#[allow(clippy::too_many_arguments, async_fn_in_trait)]
+	async fn expand_full_with_progress<N, L, W>(
+		&self,
+		vocabulary: &mut N,
+		context: Context<Iri, N::BlankId>,
+		base_url: Option<&N::Iri>,
+		loader: &L,
+		options: Options,
+		warnings_handler: W,
+		progress: &Progress,
+	) -> ExpansionResult<N::Iri, N::BlankId>
+	where
+		N: VocabularyMut<Iri = Iri>,
+		Iri: Clone + Eq + Hash,
+		N::BlankId: Clone + Eq + Hash,
+		L: Loader,
+		W: WarningHandler<N>,
+	{
+		let _ = progress;
+		self.expand_full(
+			vocabulary,
+			context,
+			base_url,
+			loader,
+			options,
+			warnings_handler,
+		)
+		.await
+	}
+
+	/// Expand the document with full options, looking up the remote contexts
+	/// in (and adding them to) the given `cache`.
+	///
+	/// Expanding many documents referencing the same remote contexts with a
+	/// shared cache avoids processing those contexts again for each
+	/// document. The default implementation ignores the cache.
+	///
+	/// See [`Expand::expand_full`] for the other parameters.
+	#[allow(clippy::too_many_arguments, async_fn_in_trait)]
+	async fn expand_full_with_cache<N, L, W>(
+		&self,
+		vocabulary: &mut N,
+		context: Context<Iri, N::BlankId>,
+		base_url: Option<&N::Iri>,
+		loader: &L,
+		options: Options,
+		warnings_handler: W,
+		cache: &SharedContextCache<Iri, N::BlankId>,
+	) -> ExpansionResult<N::Iri, N::BlankId>
+	where
+		N: VocabularyMut<Iri = Iri>,
+		Iri: Clone + Eq + Hash,
+		N::BlankId: Clone + Eq + Hash,
+		L: Loader,
+		W: WarningHandler<N>,
+	{
+		let _ = cache;
+		self.expand_full(
+			vocabulary,
+			context,
+			base_url,
+			loader,
+			options,
+			warnings_handler,
+		)
+		.await
+	}
+
 	/// Expand the input JSON-LD document with the given `vocabulary`
 	/// to interpret identifiers.
 	///
@@ -177,6 +266,56 @@ pub trait Expand<Iri> {
 		self.expand_with(vocabulary::no_vocabulary_mut(), loader)
 			.await
 	}
+
+	/// Expand the input JSON-LD document in [lossy mode](Options::lossy)
+	/// with the given `vocabulary` to interpret identifiers.
+	///
+	/// Instead of aborting on the first recoverable error, the offending
+	/// entries are skipped and the errors are returned alongside the
+	/// partially expanded document. Warnings are ignored.
+	#[allow(async_fn_in_trait)]
+	async fn expand_lossy_with<'a, N, L>(
+		&'a self,
+		vocabulary: &'a mut N,
+		loader: &'a L,
+	) -> LossyExpansionResult<Iri, N::BlankId>
+	where
+		N: VocabularyMut<Iri = Iri>,
+		Iri: 'a + Clone + Eq + Hash,
+		N::BlankId: 'a + Clone + Eq + Hash,
+		L: Loader,
+	{
+		let mut errors = RecoveredErrors::new();
+		let expanded = self
+			.expand_full(
+				vocabulary,
+				Context::<N::Iri, N::BlankId>::new(self.default_base_url().cloned()),
+				self.default_base_url(),
+				loader,
+				Options {
+					lossy: true,
+					..Options::default()
+				},
+				&mut errors,
+			)
+			.await?;
+
+		Ok((expanded, errors.into_errors()))
+	}
+
+	/// Expand the input JSON-LD document in [lossy mode](Options::lossy).
+	///
+	/// See [`Expand::expand_lossy_with`].
+	#[allow(async_fn_in_trait)]
+	async fn expand_lossy<'a, L>(&'a self, loader: &'a L) -> LossyExpansionResult<Iri, BlankIdBuf>
+	where
+		(): VocabularyMut<Iri = Iri>,
+		Iri: 'a + Clone + Eq + Hash,
+		L: Loader,
+	{
+		self.expand_lossy_with(vocabulary::no_vocabulary_mut(), loader)
+			.await
+	}
 }
 
 /// Value expansion without base URL.
@@ -211,6 +350,74 @@ impl<Iri> Expand<Iri> for Value {
 			context,
 			base_url,
 			options,
+			&Progress::new(),
+			None,
+		)
+		.await
+	}
+
+	async fn expand_full_with_progress<N, L, W>(
+		&self,
+		vocabulary: &mut N,
+		context: Context<Iri, N::BlankId>,
+		base_url: Option<&Iri>,
+		loader: &L,
+		options: Options,
+		mut warnings_handler: W,
+		progress: &Progress,
+	) -> ExpansionResult<Iri, N::BlankId>
+	where
+		N: VocabularyMut<Iri = Iri>,
+		Iri: Clone + Eq + Hash,
+		N::BlankId: Clone + Eq + Hash,
+		L: Loader,
+		W: WarningHandler<N>,
+	{
+		document::expand(
+			Environment {
+				vocabulary,
+				loader,
+				warnings: &mut warnings_handler,
+			},
+			self,
+			context,
+			base_url,
+			options,
+			progress,
+			None,
+		)
+		.await
+	}
+
+	async fn expand_full_with_cache<N, L, W>(
+		&self,
+		vocabulary: &mut N,
+		context: Context<Iri, N::BlankId>,
+		base_url: Option<&Iri>,
+		loader: &L,
+		options: Options,
+		mut warnings_handler: W,
+		cache: &SharedContextCache<Iri, N::BlankId>,
+	) -> ExpansionResult<Iri, N::BlankId>
+	where
+		N: VocabularyMut<Iri = Iri>,
+		Iri: Clone + Eq + Hash,
+		N::BlankId: Clone + Eq + Hash,
+		L: Loader,
+		W: WarningHandler<N>,
+	{
+		document::expand(
+			Environment {
+				vocabulary,
+				loader,
+				warnings: &mut warnings_handler,
+			},
+			self,
+			context,
+			base_url,
+			options,
+			&Progress::new(),
+			Some(cache),
 		)
 		.await
 	}
@@ -252,4 +459,64 @@ impl<Iri> Expand<Iri> for RemoteDocument<Iri> {
 			)
 			.await
 	}
+
+	async fn expand_full_with_progress<N, L, W>(
+		&self,
+		vocabulary: &mut N,
+		context: Context<Iri, N::BlankId>,
+		base_url: Option<&Iri>,
+		loader: &L,
+		options: Options,
+		warnings_handler: W,
+		progress: &Progress,
+	) -> ExpansionResult<Iri, N::BlankId>
+	where
+		N: VocabularyMut<Iri = Iri>,
+		Iri: Clone + Eq + Hash,
+		N::BlankId: Clone + Eq + Hash,
+		L: Loader,
+		W: WarningHandler<N>,
+	{
+		self.document()
+			.expand_full_with_progress(
+				vocabulary,
+				context,
+				base_url,
+				loader,
+				options,
+				warnings_handler,
+				progress,
+			)
+			.await
+	}
+
+	async fn expand_full_with_cache<N, L, W>(
+		&self,
+		vocabulary: &mut N,
+		context: Context<Iri, N::BlankId>,
+		base_url: Option<&Iri>,
+		loader: &L,
+		options: Options,
+		warnings_handler: W,
+		cache: &SharedContextCache<Iri, N::BlankId>,
+	) -> ExpansionResult<Iri, N::BlankId>
+	where
+		N: VocabularyMut<Iri = Iri>,
+		Iri: Clone + Eq + Hash,
+		N::BlankId: Clone + Eq + Hash,
+		L: Loader,
+		W: WarningHandler<N>,
+	{
+		self.document()
+			.expand_full_with_cache(
+				vocabulary,
+				context,
+				base_url,
+				loader,
+				options,
+				warnings_handler,
+				cache,
+			)
+			.await
+	}
 }
@@ -0,0 +1,102 @@
+use crate::{Error, Limits, ResourceLimit};
+use iref::{Iri, IriBuf};
+use json_ld_core::{LoadError, Loader, RemoteDocument};
+use json_syntax::Value;
+
+/// Limit exceeded by an input document or one of its remote contexts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub(crate) enum ExceededLimit {
+	#[error("maximum depth exceeded (limit is {0})")]
+	Depth(usize),
+
+	#[error(transparent)]
+	Resource(ResourceLimit),
+}
+
+impl From<ExceededLimit> for Error {
+	fn from(e: ExceededLimit) -> Self {
+		match e {
+			ExceededLimit::Depth(max) => Self::MaximumDepthExceeded(max),
+			ExceededLimit::Resource(e) => Self::ResourceLimit(e),
+		}
+	}
+}
+
+/// Checks the depth, literal length and entry count limits on an input
+/// document, in a single iterative traversal.
+pub(crate) fn check_input(
+	value: &Value,
+	max_depth: Option<usize>,
+	limits: &Limits,
+) -> Result<(), ExceededLimit> {
+	if max_depth.is_none() && limits.max_literal_length.is_none() && limits.max_properties.is_none()
+	{
+		return Ok(());
+	}
+
+	let check_literal = |len: usize| match limits.max_literal_length {
+		Some(max) if len > max => Err(ExceededLimit::Resource(ResourceLimit::LiteralLength(max))),
+		_ => Ok(()),
+	};
+
+	let check_depth = |depth: usize| match max_depth {
+		Some(max) if depth == max => Err(ExceededLimit::Depth(max)),
+		_ => Ok(()),
+	};
+
+	let mut stack = vec![(value, 0)];
+	while let Some((value, depth)) = stack.pop() {
+		match value {
+			Value::String(s) => check_literal(s.len())?,
+			Value::Number(n) => check_literal(n.as_str().len())?,
+			Value::Array(items) if !items.is_empty() => {
+				check_depth(depth)?;
+				stack.extend(items.iter().map(|item| (item, depth + 1)))
+			}
+			Value::Object(object) => {
+				if let Some(max) = limits.max_properties {
+					if object.len() > max {
+						return Err(ExceededLimit::Resource(ResourceLimit::Properties(max)));
+					}
+				}
+
+				if !object.is_empty() {
+					check_depth(depth)?;
+					for entry in object {
+						check_literal(entry.key.len())?;
+						stack.push((&entry.value, depth + 1))
+					}
+				}
+			}
+			_ => (),
+		}
+	}
+
+	Ok(())
+}
+
+/// Loader checking the input limits on the loaded remote contexts.
+pub(crate) struct LimitedLoader<'l, L> {
+	inner: &'l L,
+	max_depth: Option<usize>,
+	limits: Limits,
+}
+
+impl<'l, L> LimitedLoader<'l, L> {
+	pub fn new(inner: &'l L, max_depth: Option<usize>, limits: Limits) -> Self {
+		Self {
+			inner,
+			max_depth,
+			limits,
+		}
+	}
+}
+
+impl<'l, L: Loader> Loader for LimitedLoader<'l, L> {
+	async fn load(&self, url: &Iri) -> Result<RemoteDocument<IriBuf>, LoadError> {
+		let document = self.inner.load(url).await?;
+		check_input(document.document(), self.max_depth, &self.limits)
+			.map_err(|e| LoadError::new(url.to_owned(), e))?;
+		Ok(document)
+	}
+}
@@ -1,4 +1,4 @@
-use crate::{expand_iri, node_id_of_term, ActiveProperty, WarningHandler};
+use crate::{expand_iri, node_id_of_term, ActiveProperty, LanguageCase, WarningHandler};
 use json_ld_context_processing::algorithm::{Action, RejectVocab};
 use json_ld_core::{
 	object::value::Literal, Context, Environment, IndexedObject, LangString, Node, Object, Type,
@@ -66,6 +66,9 @@ pub enum LiteralExpansionError {
 
 	#[error("Forbidden use of `@vocab`")]
 	ForbiddenVocab,
+
+	#[error("Not a literal value")]
+	NotALiteral,
 }
 
 impl LiteralExpansionError {
@@ -73,10 +76,14 @@ impl LiteralExpansionError {
 		match self {
 			Self::InvalidTypeValue => ErrorCode::InvalidTypeValue,
 			Self::ForbiddenVocab => ErrorCode::InvalidTypeValue,
+			Self::NotALiteral => ErrorCode::InvalidValueObjectValue,
 		}
 	}
 }
 
+#[cfg(feature = "miette")]
+json_ld_syntax::impl_diagnostic!(LiteralExpansionError);
+
 impl From<RejectVocab> for LiteralExpansionError {
 	fn from(_value: RejectVocab) -> Self {
 		Self::ForbiddenVocab
@@ -90,6 +97,7 @@ pub(crate) type LiteralExpansionResult<T, B> = Result<ExpandedLiteral<T, B>, Lit
 pub(crate) fn expand_literal<N, L, W>(
 	mut env: Environment<N, L, W>,
 	vocab_policy: Action,
+	language_case: LanguageCase,
 	active_context: &Context<N::Iri, N::BlankId>,
 	active_property: ActiveProperty<'_>,
 	value: LiteralValue,
@@ -108,47 +116,27 @@ where
 		None
 	};
 
-	match active_property_type {
-		// If the `active_property` has a type mapping in `active_context` that is `@id`, and the
-		// `value` is a string, return a new map containing a single entry where the key is `@id` and
-		// the value is the result of IRI expanding `value` using `true` for `document_relative` and
-		// `false` for vocab.
-		Some(Type::Id) if value.is_string() => {
-			let mut node = Node::new();
-			let id = node_id_of_term(
-				expand_iri(
-					&mut env,
-					active_context,
-					Nullable::Some(value.as_str().unwrap().into()),
-					true,
-					None,
-				)
-				.unwrap()
-				.unwrap(),
-			);
-
-			node.id = id;
-			Ok(Object::node(node).into())
-		}
-
-		// If `active_property` has a type mapping in active context that is `@vocab`, and the
-		// value is a string, return a new map containing a single entry where the key is
-		// `@id` and the value is the result of IRI expanding `value` using `true` for
-		// document relative.
-		Some(Type::Vocab) if value.is_string() => {
+	match active_property_type
+		.as_ref()
+		.and_then(Type::node_reference_vocab)
+	{
+		// If the `active_property` has a type mapping in `active_context` that is `@id` or
+		// `@vocab`, and the `value` is a string, return a new map containing a single entry where
+		// the key is `@id` and the value is the result of IRI expanding `value` using `true` for
+		// `document_relative`, and `true` for `vocab` only if the type mapping is `@vocab`.
+		Some(vocab) if value.is_string() => {
 			let mut node = Node::new();
 
-			let ty = expand_iri(
+			let id = expand_iri(
 				&mut env,
 				active_context,
 				Nullable::Some(value.as_str().unwrap().into()),
 				true,
-				Some(vocab_policy),
+				vocab.then_some(vocab_policy),
 			)?;
 
-			if let Some(ty) = ty {
-				let id = node_id_of_term(ty);
-				node.id = id;
+			if let Some(id) = id {
+				node.id = node_id_of_term(id);
 			}
 
 			Ok(Object::node(node).into())
@@ -212,6 +200,7 @@ where
 						// `language`.
 						// If `direction` is not null, add `@direction` to result with the
 						// value `direction`.
+						let language = language.map(|language| language_case.apply(language));
 						return match LangString::new(s, language, direction) {
 							Ok(lang_str) => Ok(Object::Value(Value::LangString(lang_str)).into()),
 							Err(s) => {
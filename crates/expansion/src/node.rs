@@ -1,15 +1,16 @@
 use crate::{
-	expand_element, expand_iri, expand_literal, filter_top_level_item, Action, ActiveProperty,
-	Error, Expanded, ExpandedEntry, LiteralValue, Options, Warning, WarningHandler,
+	expand_element, expand_iri, expand_literal, filter_top_level_item, recover, recover_error,
+	Action, ActiveProperty, Error, Expanded, ExpandedEntry, LiteralValue, Options, ResourceLimit,
+	State, Warning, WarningHandler,
 };
 use contextual::WithContext;
 use indexmap::IndexSet;
-use json_ld_context_processing::{Options as ProcessingOptions, Process};
+use json_ld_context_processing::{algorithm::process_scoped_context, Options as ProcessingOptions};
 use json_ld_core::{
-	object, object::value::Literal, Container, Context, Environment, Id, Indexed, IndexedObject,
-	LangString, Loader, Node, Object, ProcessingMode, Term, Type, Value,
+	object, object::node::Annotation, object::value::Literal, Container, Context, Environment, Id,
+	Indexed, IndexedObject, LangString, Loader, Node, Object, ProcessingMode, Term, Type, Value,
 };
-use json_ld_syntax::{ContainerKind, Keyword, LenientLangTagBuf, Nullable};
+use json_ld_syntax::{is_keyword_like, ContainerKind, Keyword, LenientLangTagBuf, Nullable};
 use json_syntax::object::Entry;
 use mown::Mown;
 use rdf_types::VocabularyMut;
@@ -29,6 +30,7 @@ pub(crate) fn node_id_of_term<T, B>(term: Term<T, B>) -> Option<Id<T, B>> {
 #[allow(clippy::too_many_arguments)]
 pub(crate) async fn expand_node<'a, N, L, W>(
 	env: Environment<'a, N, L, W>,
+	state: &'a State<'_, N::Iri, N::BlankId>,
 	active_context: &'a Context<N::Iri, N::BlankId>,
 	type_scoped_context: &'a Context<N::Iri, N::BlankId>,
 	active_property: ActiveProperty<'a>,
@@ -49,6 +51,7 @@ where
 
 	let (result, has_value_object_entries) = expand_node_entries(
 		env,
+		state,
 		Indexed::new(Node::new(), None),
 		false,
 		active_context,
@@ -105,6 +108,7 @@ type NodeEntriesExpensionResult<T, B> = Result<ExpandedNode<T, B>, Error>;
 #[allow(clippy::too_many_arguments)]
 async fn expand_node_entries<'a, N, L, W>(
 	mut env: Environment<'a, N, L, W>,
+	state: &'a State<'_, N::Iri, N::BlankId>,
 	mut result: Indexed<Node<N::Iri, N::BlankId>>,
 	mut has_value_object_entries: bool,
 	active_context: &'a Context<N::Iri, N::BlankId>,
@@ -207,11 +211,11 @@ where
 
 										result.types_mut_or_default().push(ty)
 									} else {
-										return Err(Error::InvalidTypeValue);
+										recover_error(&mut env, options, Error::InvalidTypeValue)?
 									}
 								}
 							} else {
-								return Err(Error::InvalidTypeValue);
+								recover_error(&mut env, options, Error::InvalidTypeValue)?
 							}
 						}
 					}
@@ -228,6 +232,7 @@ where
 								loader: env.loader,
 								warnings: env.warnings,
 							},
+							state,
 							active_context,
 							ActiveProperty::Some("@graph"),
 							value,
@@ -237,6 +242,7 @@ where
 						))
 						.await?;
 
+						state.graph()?;
 						result.set_graph_entry(Some(
 							expanded_value
 								.into_iter()
@@ -262,6 +268,7 @@ where
 								loader: env.loader,
 								warnings: env.warnings,
 							},
+							state,
 							active_context,
 							ActiveProperty::Some("@included"),
 							value,
@@ -297,7 +304,7 @@ where
 						} else {
 							// If value is not a string, an invalid @index value
 							// error has been detected and processing is aborted.
-							return Err(Error::InvalidIndexValue);
+							recover_error(&mut env, options, Error::InvalidIndexValue)?
 						}
 					}
 					// If expanded property is @reverse:
@@ -350,6 +357,7 @@ where
 												loader: env.loader,
 												warnings: env.warnings,
 											},
+											state,
 											active_context,
 											ActiveProperty::Some(reverse_key.as_ref()),
 											reverse_value,
@@ -435,23 +443,22 @@ where
 							};
 
 							// Step 8 again.
+							let scoped_context;
 							let active_context = match property_scoped_context {
 								Some(property_scoped_context) => {
 									let options: ProcessingOptions = options.into();
-									Mown::Owned(
-										property_scoped_context
-											.process_with(
-												env.vocabulary,
-												active_context,
-												env.loader,
-												property_scoped_base_url,
-												options.with_override(),
-											)
-											.await?
-											.into_processed(),
+									scoped_context = process_scoped_context(
+										env.vocabulary,
+										active_context,
+										property_scoped_context,
+										env.loader,
+										property_scoped_base_url,
+										options.with_override(),
 									)
+									.await?;
+									scoped_context.as_ref()
 								}
-								None => Mown::Borrowed(active_context),
+								None => active_context,
 							};
 
 							// Steps 13 and 14 again.
@@ -471,7 +478,7 @@ where
 									.filter_map(|Entry { key, value }| {
 										expand_iri(
 											&mut env,
-											active_context.as_ref(),
+											active_context,
 											Nullable::Some(key.as_str().into()),
 											false,
 											Some(options.policy.vocab),
@@ -492,9 +499,10 @@ where
 											loader: env.loader,
 											warnings: env.warnings,
 										},
+										state,
 										result,
 										has_value_object_entries,
-										active_context.as_ref(),
+										active_context,
 										type_scoped_context,
 										active_property,
 										nested_expanded_entries,
@@ -515,9 +523,14 @@ where
 				}
 			}
 
-			Term::Id(prop) if prop.with(&*env.vocabulary).as_str().contains(':') => {
+			Term::Id(prop)
+				if prop.with(&*env.vocabulary).as_str().contains(':')
+					|| is_kept_keyword_like(&prop) =>
+			{
+				// Kept keyword-like keys are not subject to the `invalid` policy.
 				if let Id::Invalid(name) = &prop {
 					match options.policy.invalid {
+						_ if is_keyword_like(name) => (),
 						Action::Keep => (),
 						Action::Drop => continue,
 						Action::Reject => return Err(Error::KeyExpansionFailed(name.to_owned())),
@@ -544,6 +557,7 @@ where
 					}
 				}
 
+				let mut annotations = Vec::new();
 				let mut expanded_value = if is_json {
 					Expanded::Object(Object::Value(Value::Json(value.clone())).into())
 				} else {
@@ -619,7 +633,7 @@ where
 													)
 												}
 
-												Some(language)
+												Some(options.language_case.apply(language))
 											};
 
 											// initialize a new map v consisting of two
@@ -651,7 +665,11 @@ where
 											// item must be a string, otherwise an
 											// invalid language map value error has
 											// been detected and processing is aborted.
-											return Err(Error::InvalidLanguageMapValue);
+											recover_error(
+												&mut env,
+												options,
+												Error::InvalidLanguageMapValue,
+											)?
 										}
 									}
 								}
@@ -703,14 +721,15 @@ where
 								// initialize `map_context` to the `previous_context`
 								// from `active_context` if it exists, otherwise, set
 								// `map_context` to `active_context`.
-								let mut map_context = Mown::Borrowed(active_context);
+								let index_context;
+								let mut map_context = active_context;
 								if container_mapping.contains(ContainerKind::Type)
 									|| container_mapping.contains(ContainerKind::Id)
 								{
 									if let Some(previous_context) =
 										active_context.previous_context()
 									{
-										map_context = Mown::Borrowed(previous_context)
+										map_context = previous_context
 									}
 								}
 
@@ -726,18 +745,16 @@ where
 									{
 										if let Some(local_context) = index_definition.context() {
 											let base_url = index_definition.base_url().cloned();
-											map_context = Mown::Owned(
-												local_context
-													.process_with(
-														env.vocabulary,
-														map_context.as_ref(),
-														env.loader,
-														base_url,
-														options.into(),
-													)
-													.await?
-													.into_processed(),
+											index_context = process_scoped_context(
+												env.vocabulary,
+												map_context,
+												local_context,
+												env.loader,
+												base_url,
+												options.into(),
 											)
+											.await?;
+											map_context = index_context.as_ref()
 										}
 									}
 								}
@@ -774,14 +791,23 @@ where
 										loader: env.loader,
 										warnings: env.warnings,
 									},
-									map_context.as_ref(),
+									state,
+									map_context,
 									ActiveProperty::Some(key),
 									index_value,
 									base_url,
 									options,
 									true,
 								))
-								.await?;
+								.await;
+
+								// In lossy mode, skip the index entry if its
+								// expansion failed.
+								let expanded_index_value =
+									match recover(&mut env, options, expanded_index_value)? {
+										Some(expanded_index_value) => expanded_index_value,
+										None => continue,
+									};
 								// For each item in index value:
 								for mut item in expanded_index_value {
 									// If container mapping includes @graph,
@@ -792,6 +818,8 @@ where
 									if container_mapping.contains(ContainerKind::Graph)
 										&& !item.is_graph()
 									{
+										state.node()?;
+										state.graph()?;
 										let mut node = Node::new();
 										let mut graph = IndexSet::new();
 										graph.insert(item);
@@ -799,11 +827,13 @@ where
 										item = Object::node(node).into();
 									}
 
+									// Items of the @none entry (or of any entry
+									// whose key is an alias of @none) are added
+									// without index, identifier or type.
 									if expanded_index.is_some() {
 										// If `container_mapping` includes @index,
 										// index key is not @index, and expanded index is
 										// not @none:
-										// TODO the @none part.
 										if container_mapping.contains(ContainerKind::Index)
 											&& index_key != "@index"
 										{
@@ -818,6 +848,7 @@ where
 													warnings: env.warnings,
 												},
 												options.policy.vocab,
+												options.language_case,
 												active_context,
 												ActiveProperty::Some(index_key),
 												LiteralValue::Inferred(index.as_str().into()),
@@ -836,10 +867,24 @@ where
 												_ => continue,
 											};
 
+											// Initialize index property values to an
+											// array consisting of re-expanded index
+											// followed by the existing values of expanded
+											// index key in item, if any.
 											// Add the key-value pair (expanded index
 											// key-index property values) to item.
 											if let Object::Node(node) = item.inner_mut() {
-												node.insert(expanded_index_key, re_expanded_index);
+												let existing_values = node
+													.properties_mut()
+													.remove(&expanded_index_key);
+												node.properties_mut().set(
+													expanded_index_key,
+													std::iter::once(re_expanded_index)
+														.chain(
+															existing_values.into_iter().flatten(),
+														)
+														.collect(),
+												)
 											} else {
 												// If item is a value object, it MUST NOT
 												// contain any extra properties; an invalid
@@ -903,16 +948,69 @@ where
 
 							Expanded::Array(expanded_value)
 						}
+						_ if options.rdf_star && has_annotation(value) => {
+							// JSON-LD-star: each item is expanded separately so that
+							// its annotation can be attached to the resulting values.
+							let mut expanded_value = Vec::new();
+							let items = match value {
+								json_syntax::Value::Array(items) => items.as_slice(),
+								item => std::slice::from_ref(item),
+							};
+
+							for item in items {
+								let (item, annotation) = split_annotation(item);
+								let expanded_item = Box::pin(expand_element(
+									Environment {
+										vocabulary: env.vocabulary,
+										loader: env.loader,
+										warnings: env.warnings,
+									},
+									state,
+									active_context,
+									ActiveProperty::Some(key),
+									&item,
+									base_url,
+									options,
+									false,
+								))
+								.await?;
+
+								if let Some(annotation) = annotation {
+									let nodes = Box::pin(expand_annotation(
+										Environment {
+											vocabulary: env.vocabulary,
+											loader: env.loader,
+											warnings: env.warnings,
+										},
+										state,
+										active_context,
+										&annotation,
+										base_url,
+										options,
+									))
+									.await?;
+
+									for object in &expanded_item {
+										annotations.push((object.clone(), nodes.clone()))
+									}
+								}
+
+								expanded_value.extend(expanded_item)
+							}
+
+							Expanded::Array(expanded_value)
+						}
 						_ => {
 							// Otherwise, initialize expanded value to the result of using this
 							// algorithm recursively, passing active context, key for active property,
 							// value for element, base URL, and the frameExpansion and ordered flags.
-							Box::pin(expand_element(
+							let expanded_value = Box::pin(expand_element(
 								Environment {
 									vocabulary: env.vocabulary,
 									loader: env.loader,
 									warnings: env.warnings,
 								},
+								state,
 								active_context,
 								ActiveProperty::Some(key),
 								value,
@@ -920,7 +1018,13 @@ where
 								options,
 								false,
 							))
-							.await?
+							.await;
+
+							// In lossy mode, skip the entry if its expansion failed.
+							match recover(&mut env, options, expanded_value)? {
+								Some(expanded_value) => expanded_value,
+								None => continue,
+							}
 						}
 					}
 				};
@@ -950,16 +1054,35 @@ where
 						expanded_value
 							.into_iter()
 							.map(|ev| {
+								state.node()?;
+								state.graph()?;
 								let mut node = Node::new();
 								let mut graph = IndexSet::new();
 								graph.insert(ev);
 								node.set_graph_entry(Some(graph));
-								Object::node(node).into()
+								Ok(Object::node(node).into())
 							})
-							.collect(),
+							.collect::<Result<_, ResourceLimit>>()?,
 					);
 				}
 
+				if !annotations.is_empty() {
+					// Annotated values cannot be reverse properties, list items or
+					// graph objects.
+					if is_reverse_property
+						|| container_mapping.contains(ContainerKind::List)
+						|| container_mapping.contains(ContainerKind::Graph)
+					{
+						return Err(Error::InvalidAnnotation);
+					}
+
+					result.annotations_mut_or_default().extend(
+						annotations
+							.into_iter()
+							.map(|(object, nodes)| Annotation::new(prop.clone(), object, nodes)),
+					)
+				}
+
 				if !expanded_value.is_null() {
 					// If the term definition associated to key indicates that it
 					// is a reverse property:
@@ -1005,3 +1128,150 @@ where
 
 	Ok((result, has_value_object_entries))
 }
+
+/// Checks if the given property is a keyword-like key kept by the
+/// [`Policy::keyword_like`](crate::Policy::keyword_like) expansion policy.
+fn is_kept_keyword_like<T, B>(prop: &Id<T, B>) -> bool {
+	matches!(prop, Id::Invalid(name) if is_keyword_like(name))
+}
+
+/// Checks if the given property value, or one of its items, has an
+/// `@annotation` entry.
+fn has_annotation(value: &json_syntax::Value) -> bool {
+	match value {
+		json_syntax::Value::Array(items) => items.iter().any(has_annotation),
+		json_syntax::Value::Object(object) => {
+			object.get_unique("@annotation").ok().flatten().is_some()
+		}
+		_ => false,
+	}
+}
+
+/// Removes the `@annotation` entry from the given property value item.
+fn split_annotation(
+	item: &json_syntax::Value,
+) -> (Mown<'_, json_syntax::Value>, Option<json_syntax::Value>) {
+	match item.as_object() {
+		Some(object) if object.get_unique("@annotation").ok().flatten().is_some() => {
+			let mut object = object.clone();
+			let annotation = object.remove("@annotation").next().map(|entry| entry.value);
+			(Mown::Owned(object.into()), annotation)
+		}
+		_ => (Mown::Borrowed(item), None),
+	}
+}
+
+/// Expands the value of an `@annotation` entry into annotation nodes.
+async fn expand_annotation<'a, N, L, W>(
+	env: Environment<'a, N, L, W>,
+	state: &'a State<'_, N::Iri, N::BlankId>,
+	active_context: &'a Context<N::Iri, N::BlankId>,
+	annotation: &'a json_syntax::Value,
+	base_url: Option<&'a N::Iri>,
+	options: Options,
+) -> Result<Vec<Indexed<Node<N::Iri, N::BlankId>>>, Error>
+where
+	N: VocabularyMut,
+	N::Iri: Clone + Eq + Hash,
+	N::BlankId: Clone + Eq + Hash,
+	L: Loader,
+	W: WarningHandler<N>,
+{
+	let expanded = expand_element(
+		env,
+		state,
+		active_context,
+		ActiveProperty::None,
+		annotation,
+		base_url,
+		options,
+		false,
+	)
+	.await?;
+
+	let mut nodes = Vec::new();
+	for object in expanded {
+		// Annotations must be node objects without identifier.
+		match object.try_cast::<Node<N::Iri, N::BlankId>>() {
+			Ok(node) if node.id.is_none() => nodes.push(node),
+			_ => return Err(Error::InvalidAnnotation),
+		}
+	}
+
+	Ok(nodes)
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{Action, Error, Expand, Options, Policy};
+	use json_ld_core::{Context, Id, NoLoader};
+
+	#[test]
+	fn annotation() {
+		let input = json_syntax::json!({
+			"@id": "https://example.com/bob",
+			"https://example.com/age": {
+				"@value": 23,
+				"@annotation": { "https://example.com/certainty": 0.8 }
+			}
+		});
+
+		let expand = |rdf_star| {
+			futures::executor::block_on(input.expand_full(
+				&mut (),
+				Context::default(),
+				None,
+				&NoLoader,
+				Options {
+					rdf_star,
+					..Options::default()
+				},
+				(),
+			))
+		};
+
+		assert!(expand(false).is_err());
+
+		let document = expand(true).unwrap();
+		let node = document.main_node().unwrap();
+		assert_eq!(node.annotations().unwrap().len(), 1)
+	}
+
+	#[test]
+	fn keyword_like() {
+		let input = json_syntax::json!({
+			"@id": "https://example.com/bob",
+			"https://example.com/name": "Bob",
+			"@foo": "bar"
+		});
+
+		let expand = |keyword_like| {
+			futures::executor::block_on(input.expand_full(
+				&mut (),
+				Context::default(),
+				None,
+				&NoLoader,
+				Options {
+					policy: Policy {
+						keyword_like,
+						..Policy::default()
+					},
+					..Options::default()
+				},
+				(),
+			))
+		};
+
+		let document = expand(Action::Drop).unwrap();
+		assert_eq!(document.main_node().unwrap().properties().len(), 1);
+
+		let document = expand(Action::Keep).unwrap();
+		let foo = Id::Invalid("@foo".to_owned());
+		assert!(document.main_node().unwrap().properties().contains(&foo));
+
+		assert!(matches!(
+			expand(Action::Reject),
+			Err(Error::KeyExpansionFailed(_))
+		))
+	}
+}
@@ -1,7 +1,15 @@
 use json_ld_core::ProcessingMode;
+use json_ld_syntax::LenientLangTagBuf;
 
 pub use json_ld_context_processing::algorithm::Action;
 
+/// Recommended value of [`Options::max_depth`] for untrusted documents.
+///
+/// Each nesting level costs roughly 15KiB of stack in release builds (much
+/// more in debug builds), so this limit stays well within the 2MiB stack
+/// of most runtime worker threads.
+pub const DEFAULT_MAX_DEPTH: usize = 64;
+
 /// Expansion options.
 #[derive(Clone, Copy, Default)]
 pub struct Options {
@@ -16,6 +24,79 @@ pub struct Options {
 	/// If set to true, input document entries are processed lexicographically.
 	/// If false, order is not considered in processing.
 	pub ordered: bool,
+
+	/// Enables the experimental JSON-LD-star support.
+	///
+	/// When set, `@annotation` entries of property values are expanded and
+	/// stored in the [`annotations`](json_ld_core::Node::annotations) of the
+	/// subject node. Otherwise they are processed like any other
+	/// keyword-like entry.
+	pub rdf_star: bool,
+
+	/// Maximum nesting depth of the input document.
+	///
+	/// The expansion algorithm is recursive, so deeply nested documents may
+	/// overflow the stack. If the nesting depth of the document (number of
+	/// nested arrays and objects) exceeds this limit, expansion fails with
+	/// [`Error::MaximumDepthExceeded`](crate::Error::MaximumDepthExceeded)
+	/// before anything is expanded. The limit also applies to the remote
+	/// contexts. `None` means no limit.
+	///
+	/// Defaults to `None`. [`DEFAULT_MAX_DEPTH`] is a sensible limit for
+	/// untrusted documents.
+	pub max_depth: Option<usize>,
+
+	/// Resource limits of the input document.
+	///
+	/// Defaults to no limit. [`DEFAULT_LIMITS`] is recommended for untrusted
+	/// documents.
+	pub limits: Limits,
+
+	/// Enables the lossy expansion mode.
+	///
+	/// When set, recoverable errors (see
+	/// [`Error::is_recoverable`](crate::Error::is_recoverable)) do not abort
+	/// the expansion. The offending entry or array item is skipped instead,
+	/// and the error is reported to the warning handler as a
+	/// [`Warning::RecoveredError`](crate::Warning::RecoveredError).
+	///
+	/// Defaults to `false`.
+	pub lossy: bool,
+
+	/// If set to `true`, remote contexts including themselves raise a
+	/// recursive context inclusion error instead of being ignored.
+	///
+	/// See [`json_ld_context_processing::Options::reject_recursive_contexts`].
+	///
+	/// Defaults to `false`.
+	pub reject_recursive_contexts: bool,
+
+	/// If set to `true`, the scoped contexts of term definitions are only
+	/// processed on first use.
+	///
+	/// See [`json_ld_context_processing::Options::lazy_scoped_contexts`].
+	///
+	/// Defaults to `false`.
+	pub lazy_scoped_contexts: bool,
+
+	/// If set, the expansion yields to the async executor every time this
+	/// number of array items is expanded.
+	///
+	/// Expansion is CPU-bound: on very large documents, yielding prevents it
+	/// from starving the other tasks of the executor. Items are counted over
+	/// the whole document, not per array.
+	///
+	/// Defaults to `None` (never yield).
+	pub yield_every: Option<usize>,
+
+	/// Normalization of the case of the language tags of the expanded
+	/// value objects.
+	///
+	/// Language tags are case-insensitive, and compared as such, but their
+	/// original case is kept in the expanded document unless normalized.
+	///
+	/// Defaults to [`LanguageCase::Preserve`].
+	pub language_case: LanguageCase,
 }
 
 impl Options {
@@ -29,13 +110,92 @@ impl Options {
 
 impl From<Options> for json_ld_context_processing::Options {
 	fn from(options: Options) -> json_ld_context_processing::Options {
-		json_ld_context_processing::Options {
-			processing_mode: options.processing_mode,
-			..Default::default()
+		let mut result = json_ld_context_processing::Options::default()
+			.with_reject_recursive_contexts(options.reject_recursive_contexts)
+			.with_lazy_scoped_contexts(options.lazy_scoped_contexts);
+		result.processing_mode = options.processing_mode;
+		result
+	}
+}
+
+/// Language tag case normalization.
+///
+/// See [`Options::language_case`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum LanguageCase {
+	/// Language tags are kept as is.
+	#[default]
+	Preserve,
+
+	/// Language tags are lowercased, as JSON-LD processors are allowed to
+	/// do.
+	Lowercase,
+
+	/// Language tags are given the canonical case of BCP47 (`en-US`,
+	/// `zh-Hant`).
+	///
+	/// See [`LenientLangTag::to_canonical_case`](json_ld_syntax::LenientLangTag::to_canonical_case).
+	Canonical,
+}
+
+impl LanguageCase {
+	/// Normalizes the case of the given language tag.
+	pub fn apply(self, language: LenientLangTagBuf) -> LenientLangTagBuf {
+		match self {
+			Self::Preserve => language,
+			Self::Lowercase => language.to_lowercase(),
+			Self::Canonical => language.to_canonical_case(),
 		}
 	}
 }
 
+/// Recommended value of [`Options::limits`] for untrusted documents.
+///
+/// These limits are well above what legitimate documents need (1MiB literals,
+/// 1024 entries per object, 100000 nodes and 1000 graphs), while bounding the
+/// memory and time spent expanding a malicious document.
+pub const DEFAULT_LIMITS: Limits = Limits {
+	max_literal_length: Some(1024 * 1024),
+	max_properties: Some(1024),
+	max_nodes: Some(100_000),
+	max_graphs: Some(1_000),
+};
+
+/// Resource limits, protecting services expanding untrusted documents.
+///
+/// If a limit is exceeded, expansion fails with
+/// [`Error::ResourceLimit`](crate::Error::ResourceLimit). `None` means no
+/// limit.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Limits {
+	/// Maximum length, in bytes, of the strings (including IRIs and keys) and
+	/// numbers of the input document and of its remote contexts.
+	///
+	/// Checked before anything is expanded, and when a remote context is
+	/// loaded.
+	pub max_literal_length: Option<usize>,
+
+	/// Maximum number of entries (properties and keywords) of each object of
+	/// the input document and of its remote contexts.
+	///
+	/// Checked before anything is expanded, and when a remote context is
+	/// loaded.
+	pub max_properties: Option<usize>,
+
+	/// Maximum number of node objects in the expanded document, at any depth.
+	///
+	/// Node objects are counted as they are expanded, so the expansion stops
+	/// as soon as the limit is exceeded.
+	pub max_nodes: Option<usize>,
+
+	/// Maximum number of graphs (node objects with a `@graph` entry) in the
+	/// expanded document, at any depth.
+	///
+	/// Graphs are counted as they are expanded, so the expansion stops as soon
+	/// as the limit is exceeded.
+	pub max_graphs: Option<usize>,
+}
+
 /// Key expansion policy.
 ///
 /// The default behavior of the expansion algorithm
@@ -60,6 +220,18 @@ pub struct Policy {
 
 	/// How to expand valid terms when there is no vocabulary mapping.
 	pub allow_undefined: bool,
+
+	/// How to expand keyword-like keys.
+	///
+	/// Keys of the form `@foo` that are not keywords are reserved by the
+	/// specification, and must be ignored with a warning
+	/// ([`Warning::KeywordLikeTerm`](crate::Warning::KeywordLikeTerm)).
+	/// Some APIs use them as extension keys nonetheless: with
+	/// `Action::Keep` they are kept as is (as invalid identifiers), and with
+	/// `Action::Reject` they raise a key expansion error.
+	///
+	/// Default is `Action::Drop`.
+	pub keyword_like: Action,
 }
 
 impl Default for Policy {
@@ -68,6 +240,7 @@ impl Default for Policy {
 			invalid: Action::Keep,
 			vocab: Action::Keep,
 			allow_undefined: true,
+			keyword_like: Action::Drop,
 		}
 	}
 }
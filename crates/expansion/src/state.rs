@@ -0,0 +1,93 @@
+use crate::{Limits, ResourceLimit};
+use json_ld_context_processing::SharedContextCache;
+use json_ld_core::progress::{Cancelled, Progress, Yielder};
+use json_syntax::{Array, Value};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// State of the expansion of a whole document, shared by all the recursive
+/// calls of the expansion algorithm.
+pub(crate) struct State<'a, T, B> {
+	limits: Limits,
+	cache: Option<&'a SharedContextCache<T, B>>,
+	nodes: AtomicUsize,
+	graphs: AtomicUsize,
+	yielder: Yielder,
+	progress: &'a Progress,
+	root: Option<&'a Array>,
+}
+
+impl<'a, T, B> State<'a, T, B> {
+	pub fn new(
+		limits: Limits,
+		cache: Option<&'a SharedContextCache<T, B>>,
+		yield_every: Option<usize>,
+		progress: &'a Progress,
+		document: &'a Value,
+	) -> Self {
+		Self {
+			limits,
+			cache,
+			nodes: AtomicUsize::new(0),
+			graphs: AtomicUsize::new(0),
+			yielder: Yielder::new(yield_every),
+			progress,
+			root: match document {
+				Value::Array(array) => Some(array),
+				_ => None,
+			},
+		}
+	}
+
+	/// Checks the cancellation of the expansion before expanding an item of
+	/// the given `array`, if it is the document root.
+	pub fn check_progress(&self, array: &Array) -> Result<(), Cancelled> {
+		if self.is_root(array) {
+			self.progress.check()
+		} else {
+			Ok(())
+		}
+	}
+
+	/// Reports that the `processed` first items of the given `array` are
+	/// expanded, if it is the document root.
+	pub fn report_progress(&self, array: &Array, processed: usize) {
+		if self.is_root(array) {
+			self.progress.report(processed, array.len())
+		}
+	}
+
+	fn is_root(&self, array: &Array) -> bool {
+		self.root.is_some_and(|root| std::ptr::eq(root, array))
+	}
+
+	/// Cache of processed remote contexts, if any.
+	pub fn cache(&self) -> Option<&'a SharedContextCache<T, B>> {
+		self.cache
+	}
+
+	/// Counts an expanded array item, yielding to the async executor every
+	/// [`Options::yield_every`](crate::Options::yield_every) items of the
+	/// whole document.
+	pub async fn tick(&self) {
+		self.yielder.tick().await
+	}
+
+	/// Counts a new node object, failing as soon as [`Limits::max_nodes`] is
+	/// exceeded.
+	pub fn node(&self) -> Result<(), ResourceLimit> {
+		count(&self.nodes, self.limits.max_nodes).map_err(ResourceLimit::Nodes)
+	}
+
+	/// Counts a new graph, failing as soon as [`Limits::max_graphs`] is
+	/// exceeded.
+	pub fn graph(&self) -> Result<(), ResourceLimit> {
+		count(&self.graphs, self.limits.max_graphs).map_err(ResourceLimit::Graphs)
+	}
+}
+
+fn count(counter: &AtomicUsize, max: Option<usize>) -> Result<(), usize> {
+	match max {
+		Some(max) if counter.fetch_add(1, Ordering::Relaxed) >= max => Err(max),
+		_ => Ok(()),
+	}
+}
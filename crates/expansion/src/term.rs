@@ -0,0 +1,231 @@
+use crate::{
+	expand_iri, expand_literal, ActiveProperty, GivenLiteralValue, LanguageCase,
+	LiteralExpansionError, LiteralValue, Warning,
+};
+use json_ld_context_processing::algorithm::Action;
+use json_ld_core::{Context, Environment, IndexedObject, Term};
+use json_ld_syntax::Nullable;
+use rdf_types::{vocabulary, VocabularyMut};
+
+/// Expansion of single terms and values against a processed context.
+///
+/// This gives access to the IRI and value expansion algorithms used by the
+/// document expansion, without expanding a whole document.
+pub trait ExpandTerm<T, B> {
+	/// Expands the given term into an IRI, blank node identifier or keyword
+	/// with the given `vocabulary`.
+	///
+	/// If `vocab` is `true`, the term is expanded as a property or type
+	/// (using the vocabulary mapping). If `document_relative` is `true`, it is
+	/// resolved against the base IRI of the context as a node identifier.
+	/// See <https://www.w3.org/TR/json-ld11-api/#iri-expansion>.
+	fn expand_iri_with<N>(
+		&self,
+		vocabulary: &mut N,
+		term: &str,
+		vocab: bool,
+		document_relative: bool,
+	) -> Term<T, B>
+	where
+		N: VocabularyMut<Iri = T, BlankId = B>,
+		T: Clone,
+		B: Clone;
+
+	/// Expands the given term into an IRI, blank node identifier or keyword.
+	///
+	/// See [`ExpandTerm::expand_iri_with`].
+	fn expand_iri(&self, term: &str, vocab: bool, document_relative: bool) -> Term<T, B>
+	where
+		(): VocabularyMut<Iri = T, BlankId = B>,
+		T: Clone,
+		B: Clone,
+	{
+		self.expand_iri_with(
+			vocabulary::no_vocabulary_mut(),
+			term,
+			vocab,
+			document_relative,
+		)
+	}
+
+	/// Expands the given literal `value` of the property `term` with the
+	/// given `vocabulary`.
+	///
+	/// The type, language and direction mappings of the term are applied.
+	/// Fails with [`LiteralExpansionError::NotALiteral`] if `value` is not a
+	/// boolean, number or string.
+	/// See <https://www.w3.org/TR/json-ld11-api/#value-expansion>.
+	fn expand_value_with<N>(
+		&self,
+		vocabulary: &mut N,
+		term: &str,
+		value: &json_syntax::Value,
+	) -> Result<IndexedObject<T, B>, LiteralExpansionError>
+	where
+		N: VocabularyMut<Iri = T, BlankId = B>,
+		T: Clone,
+		B: Clone;
+
+	/// Expands the given literal `value` of the property `term`.
+	///
+	/// See [`ExpandTerm::expand_value_with`].
+	fn expand_value(
+		&self,
+		term: &str,
+		value: &json_syntax::Value,
+	) -> Result<IndexedObject<T, B>, LiteralExpansionError>
+	where
+		(): VocabularyMut<Iri = T, BlankId = B>,
+		T: Clone,
+		B: Clone,
+	{
+		self.expand_value_with(vocabulary::no_vocabulary_mut(), term, value)
+	}
+}
+
+impl<T, B> ExpandTerm<T, B> for Context<T, B> {
+	fn expand_iri_with<N>(
+		&self,
+		vocabulary: &mut N,
+		term: &str,
+		vocab: bool,
+		document_relative: bool,
+	) -> Term<T, B>
+	where
+		N: VocabularyMut<Iri = T, BlankId = B>,
+		T: Clone,
+		B: Clone,
+	{
+		let mut env = Environment {
+			vocabulary,
+			loader: &(),
+			warnings: &mut (),
+		};
+
+		// Terms are never dropped nor rejected when the vocabulary mapping is
+		// kept.
+		expand_iri::<Warning<B>, _, _, _>(
+			&mut env,
+			self,
+			Nullable::Some(term.into()),
+			document_relative,
+			vocab.then_some(Action::Keep),
+		)
+		.ok()
+		.flatten()
+		.unwrap_or(Term::Null)
+	}
+
+	fn expand_value_with<N>(
+		&self,
+		vocabulary: &mut N,
+		term: &str,
+		value: &json_syntax::Value,
+	) -> Result<IndexedObject<T, B>, LiteralExpansionError>
+	where
+		N: VocabularyMut<Iri = T, BlankId = B>,
+		T: Clone,
+		B: Clone,
+	{
+		match value {
+			json_syntax::Value::Boolean(_)
+			| json_syntax::Value::Number(_)
+			| json_syntax::Value::String(_) => expand_literal(
+				Environment {
+					vocabulary,
+					loader: &(),
+					warnings: &mut (),
+				},
+				Action::Keep,
+				LanguageCase::Preserve,
+				self,
+				ActiveProperty::Some(term),
+				LiteralValue::Given(GivenLiteralValue::new(value)),
+			),
+			_ => Err(LiteralExpansionError::NotALiteral),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use futures::executor::block_on;
+	use iref::IriBuf;
+	use json_ld_context_processing::Process;
+	use json_ld_core::{object::value::TypeRef, NoLoader, Object};
+	use json_ld_syntax::TryFromJson;
+	use rdf_types::BlankIdBuf;
+
+	#[test]
+	fn expand_term() {
+		let context = json_ld_syntax::context::Context::try_from_json(json_syntax::json!({
+			"@vocab": "https://schema.org/",
+			"@base": "https://example.com/",
+			"@language": "en",
+			"ex": "https://example.org/ns#",
+			"url": { "@type": "@id" },
+			"age": { "@type": "ex:years" }
+		}))
+		.unwrap();
+		let context: Context<IriBuf, BlankIdBuf> =
+			block_on(context.process(&mut (), &NoLoader, None))
+				.ok()
+				.unwrap()
+				.into_processed();
+
+		let iri = |term, vocab, document_relative| {
+			context
+				.expand_iri(term, vocab, document_relative)
+				.into_iri()
+				.ok()
+				.unwrap()
+				.as_str()
+				.to_owned()
+		};
+
+		assert_eq!(iri("name", true, false), "https://schema.org/name");
+		assert_eq!(iri("ex:foo", false, false), "https://example.org/ns#foo");
+		assert_eq!(iri("alice", false, true), "https://example.com/alice");
+		assert!(context.expand_iri("@type", true, false).is_keyword());
+
+		let url = context
+			.expand_value("url", &json_syntax::json!("bob"))
+			.unwrap();
+		assert_eq!(
+			url.as_node()
+				.unwrap()
+				.id
+				.as_ref()
+				.unwrap()
+				.as_iri()
+				.unwrap()
+				.as_str(),
+			"https://example.com/bob"
+		);
+
+		let age = context
+			.expand_value("age", &json_syntax::json!("42"))
+			.unwrap();
+		match age.inner() {
+			Object::Value(value) => assert!(matches!(
+				value.typ(),
+				Some(TypeRef::Id(ty)) if ty.as_str() == "https://example.org/ns#years"
+			)),
+			_ => panic!("expected a value object"),
+		}
+
+		let name = context
+			.expand_value("name", &json_syntax::json!("Bob"))
+			.unwrap();
+		match name.inner() {
+			Object::Value(value) => assert_eq!(value.language().unwrap().as_str(), "en"),
+			_ => panic!("expected a value object"),
+		}
+
+		assert!(matches!(
+			context.expand_value("name", &json_syntax::json!(null)),
+			Err(LiteralExpansionError::NotALiteral)
+		))
+	}
+}
@@ -1,4 +1,4 @@
-use crate::{expand_iri, Action, ExpandedEntry, Warning, WarningHandler};
+use crate::{expand_iri, Action, ExpandedEntry, LanguageCase, Warning, WarningHandler};
 use json_ld_context_processing::algorithm::RejectVocab;
 use json_ld_core::{
 	object::value::Literal, Context, Environment, Id, Indexed, IndexedObject, LangString, Object,
@@ -49,6 +49,9 @@ impl InvalidValue {
 	}
 }
 
+#[cfg(feature = "miette")]
+json_ld_syntax::impl_diagnostic!(InvalidValue);
+
 impl From<RejectVocab> for InvalidValue {
 	fn from(_value: RejectVocab) -> Self {
 		Self::ForbiddenVocab
@@ -61,6 +64,7 @@ pub type ValueExpansionResult<T, B> = Result<Option<IndexedObject<T, B>>, Invali
 pub(crate) fn expand_value<N, L, W>(
 	env: &mut Environment<N, L, W>,
 	vocab_policy: Action,
+	language_case: LanguageCase,
 	input_type: Option<Term<N::Iri, N::BlankId>>,
 	type_scoped_context: &Context<N::Iri, N::BlankId>,
 	expanded_entries: Vec<ExpandedEntry<N::Iri, N::BlankId>>,
@@ -216,7 +220,7 @@ where
 						)
 					}
 
-					Some(language)
+					Some(language_case.apply(language))
 				}
 				None => None,
 			};
@@ -1,3 +1,4 @@
+use crate::Error;
 use contextual::DisplayWithContext;
 use json_ld_context_processing::algorithm::MalformedIri;
 use langtag::InvalidLangTag;
@@ -10,6 +11,11 @@ pub enum Warning<B> {
 	EmptyTerm,
 	BlankNodeIdProperty(B),
 	MalformedLanguageTag(String, InvalidLangTag<String>),
+	KeywordLikeTerm(String),
+
+	/// An error has been recovered from in
+	/// [lossy mode](crate::Options::lossy), skipping the offending entry.
+	RecoveredError(Error),
 }
 
 impl<B> From<MalformedIri> for Warning<B> {
@@ -27,6 +33,8 @@ impl<B: fmt::Display> fmt::Display for Warning<B> {
 				write!(f, "blank node identifier `{b}` used as property")
 			}
 			Self::MalformedLanguageTag(t, e) => write!(f, "invalid language tag `{t}`: {e}"),
+			Self::KeywordLikeTerm(s) => write!(f, "keyword-like term `{s}`"),
+			Self::RecoveredError(e) => write!(f, "recovered error: {e}"),
 		}
 	}
 }
@@ -44,6 +52,39 @@ impl<B, N: BlankIdVocabulary<BlankId = B>> DisplayWithContext<N> for Warning<B>
 				)
 			}
 			Self::MalformedLanguageTag(t, e) => write!(f, "invalid language tag `{t}`: {e}"),
+			Self::KeywordLikeTerm(s) => write!(f, "keyword-like term `{s}`"),
+			Self::RecoveredError(e) => write!(f, "recovered error: {e}"),
 		}
 	}
 }
+
+/// Warning handler collecting the errors recovered from in
+/// [lossy mode](crate::Options::lossy).
+///
+/// Other warnings, including context processing warnings, are ignored.
+#[derive(Debug, Default)]
+pub struct RecoveredErrors(pub Vec<Error>);
+
+impl RecoveredErrors {
+	/// Creates a new empty collection of recovered errors.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Returns the collected errors.
+	pub fn into_errors(self) -> Vec<Error> {
+		self.0
+	}
+}
+
+impl<N, B> json_ld_core::warning::Handler<N, Warning<B>> for RecoveredErrors {
+	fn handle(&mut self, _vocabulary: &N, warning: Warning<B>) {
+		if let Warning::RecoveredError(e) = warning {
+			self.0.push(e)
+		}
+	}
+}
+
+impl<N> json_ld_core::warning::Handler<N, json_ld_context_processing::Warning> for RecoveredErrors {
+	fn handle(&mut self, _vocabulary: &N, _warning: json_ld_context_processing::Warning) {}
+}
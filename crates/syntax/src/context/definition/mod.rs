@@ -3,6 +3,8 @@ use crate::{Direction, Keyword, LenientLangTagBuf, Nullable};
 use educe::Educe;
 use indexmap::IndexMap;
 use iref::IriRefBuf;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
 
 mod import;
 mod key;
@@ -19,7 +21,7 @@ pub use version::*;
 pub use vocab::*;
 
 /// Context definition.
-#[derive(PartialEq, Eq, Clone, Educe, Debug)]
+#[derive(PartialEq, Eq, Hash, Clone, Educe, Debug)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[educe(Default)]
 pub struct Definition {
@@ -114,6 +116,88 @@ impl Definition {
 		Self::default()
 	}
 
+	/// Sets the `@base` entry.
+	pub fn with_base(mut self, base: Nullable<IriRefBuf>) -> Self {
+		self.base = Some(base);
+		self
+	}
+
+	/// Sets the `@import` entry.
+	pub fn with_import(mut self, import: IriRefBuf) -> Self {
+		self.import = Some(import);
+		self
+	}
+
+	/// Sets the `@language` entry.
+	pub fn with_language(mut self, language: Nullable<LenientLangTagBuf>) -> Self {
+		self.language = Some(language);
+		self
+	}
+
+	/// Sets the `@direction` entry.
+	pub fn with_direction(mut self, direction: Nullable<Direction>) -> Self {
+		self.direction = Some(direction);
+		self
+	}
+
+	/// Sets the `@propagate` entry.
+	pub fn with_propagate(mut self, propagate: bool) -> Self {
+		self.propagate = Some(propagate);
+		self
+	}
+
+	/// Sets the `@protected` entry.
+	pub fn with_protected(mut self, protected: bool) -> Self {
+		self.protected = Some(protected);
+		self
+	}
+
+	/// Sets the `@type` entry.
+	pub fn with_type(mut self, type_: Type) -> Self {
+		self.type_ = Some(type_);
+		self
+	}
+
+	/// Sets the `@version` entry.
+	pub fn with_version(mut self, version: Version) -> Self {
+		self.version = Some(version);
+		self
+	}
+
+	/// Sets the `@vocab` entry.
+	pub fn with_vocab(mut self, vocab: Nullable<Vocab>) -> Self {
+		self.vocab = Some(vocab);
+		self
+	}
+
+	/// Binds the given term to the given definition.
+	///
+	/// The definition is [simplified](term_definition::Expanded::simplify):
+	/// a definition with only an `@id` is bound as a simple term definition,
+	/// and an empty definition binds the term to `null`.
+	pub fn with_term(
+		mut self,
+		term: impl Into<Key>,
+		definition: term_definition::Expanded,
+	) -> Self {
+		self.bindings.insert(term.into(), definition.simplify());
+		self
+	}
+
+	/// Checks if this definition uses a feature introduced by JSON-LD 1.1.
+	pub(crate) fn uses_json_ld_1_1(&self) -> bool {
+		self.version.is_some()
+			|| self.import.is_some()
+			|| self.direction.is_some()
+			|| self.propagate.is_some()
+			|| self.protected.is_some()
+			|| self.type_.is_some()
+			|| self.bindings.iter().any(|(_, binding)| match binding {
+				Nullable::Some(TermDefinition::Expanded(e)) => e.uses_json_ld_1_1(),
+				_ => false,
+			})
+	}
+
 	pub fn get(&self, key: &KeyOrKeyword) -> Option<EntryValueRef> {
 		match key {
 			KeyOrKeyword::Keyword(k) => match k {
@@ -132,7 +216,7 @@ impl Definition {
 				Keyword::Propagate => self.propagate.map(EntryValueRef::Propagate),
 				Keyword::Protected => self.protected.map(EntryValueRef::Protected),
 				Keyword::Type => self.type_.map(EntryValueRef::Type),
-				Keyword::Version => self.version.map(EntryValueRef::Version),
+				Keyword::Version => self.version.as_ref().map(EntryValueRef::Version),
 				Keyword::Vocab => self
 					.vocab
 					.as_ref()
@@ -161,6 +245,10 @@ pub struct BindingsIter<'a>(indexmap::map::Iter<'a, Key, Nullable<TermDefinition
 impl<'a> Iterator for BindingsIter<'a> {
 	type Item = (&'a Key, Nullable<&'a TermDefinition>);
 
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		self.0.size_hint()
+	}
+
 	fn next(&mut self) -> Option<Self::Item> {
 		self.0.next().map(|(k, d)| (k, d.as_ref()))
 	}
@@ -220,6 +308,23 @@ impl Bindings {
 	}
 }
 
+/// Bindings compare equal regardless of their order, so each binding is hashed
+/// separately and the results are combined with a commutative operation.
+impl Hash for Bindings {
+	fn hash<H: Hasher>(&self, state: &mut H) {
+		self.0.len().hash(state);
+		self.0
+			.iter()
+			.map(|binding| {
+				let mut hasher = DefaultHasher::new();
+				binding.hash(&mut hasher);
+				hasher.finish()
+			})
+			.fold(0u64, u64::wrapping_add)
+			.hash(state)
+	}
+}
+
 impl IntoIterator for Bindings {
 	type Item = (Key, Nullable<TermDefinition>);
 	type IntoIter = indexmap::map::IntoIter<Key, Nullable<TermDefinition>>;
@@ -352,9 +457,78 @@ mod tests {
 		assert_eq!(definition.vocab, Some(crate::Nullable::Null))
 	}
 
+	#[test]
+	fn builder() {
+		use crate::{
+			context::{definition::Vocab, term_definition::Expanded},
+			ContainerKind, Nullable,
+		};
+
+		let definition = Definition::new()
+			.with_vocab(Nullable::Some(Vocab::from(
+				"https://schema.org/".to_owned(),
+			)))
+			.with_protected(true)
+			.with_term("name", Expanded::new().with_id("https://schema.org/name"))
+			.with_term(
+				"tags",
+				Expanded::new()
+					.with_id("https://schema.org/keywords")
+					.with_container(ContainerKind::Set)
+					.with_type("@id"),
+			)
+			.with_term("ignored", Expanded::new());
+
+		let expected: Definition = json_syntax::from_value(json_syntax::json!({
+			"@vocab": "https://schema.org/",
+			"@protected": true,
+			"name": "https://schema.org/name",
+			"tags": {
+				"@id": "https://schema.org/keywords",
+				"@container": "@set",
+				"@type": "@id"
+			},
+			"ignored": null
+		}))
+		.unwrap();
+		assert_eq!(definition, expected)
+	}
+
 	#[test]
 	fn deserialize_no_vocab() {
 		let definition: Definition = json_syntax::from_value(json_syntax::json!({})).unwrap();
 		assert_eq!(definition.vocab, None)
 	}
+
+	#[test]
+	fn hash_ignores_binding_order() {
+		use std::collections::hash_map::DefaultHasher;
+		use std::hash::{Hash, Hasher};
+
+		let hash = |definition: &Definition| {
+			let mut hasher = DefaultHasher::new();
+			definition.hash(&mut hasher);
+			hasher.finish()
+		};
+
+		let a: Definition = json_syntax::from_value(json_syntax::json!({
+			"name": "https://schema.org/name",
+			"knows": { "@id": "https://schema.org/knows", "@type": "@id" }
+		}))
+		.unwrap();
+		let b: Definition = json_syntax::from_value(json_syntax::json!({
+			"knows": { "@id": "https://schema.org/knows", "@type": "@id" },
+			"name": "https://schema.org/name"
+		}))
+		.unwrap();
+		let c: Definition = json_syntax::from_value(json_syntax::json!({
+			"name": "https://schema.org/name",
+			"knows": "https://schema.org/knows"
+		}))
+		.unwrap();
+
+		assert_eq!(a, b);
+		assert_eq!(hash(&a), hash(&b));
+		assert_ne!(hash(&a), hash(&c))
+	}
 }
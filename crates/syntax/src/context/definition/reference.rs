@@ -13,7 +13,7 @@ impl Definition {
 			propagate: self.propagate,
 			protected: self.protected,
 			type_: self.type_,
-			version: self.version,
+			version: self.version.as_ref(),
 			vocab: self.vocab.as_ref().map(Nullable::as_ref),
 			bindings: self.bindings.iter(),
 		}
@@ -28,7 +28,7 @@ pub struct Entries<'a> {
 	propagate: Option<bool>,
 	protected: Option<bool>,
 	type_: Option<Type>,
-	version: Option<Version>,
+	version: Option<&'a Version>,
 	vocab: Option<Nullable<&'a Vocab>>,
 	bindings: BindingsIter<'a>,
 }
@@ -123,7 +123,7 @@ pub enum EntryValueRef<'a> {
 	Propagate(bool),
 	Protected(bool),
 	Type(Type),
-	Version(Version),
+	Version(&'a Version),
 	Vocab(Nullable<&'a Vocab>),
 	Definition(Nullable<&'a TermDefinition>),
 }
@@ -155,7 +155,7 @@ pub enum EntryRef<'a> {
 	Propagate(bool),
 	Protected(bool),
 	Type(Type),
-	Version(Version),
+	Version(&'a Version),
 	Vocab(Nullable<&'a Vocab>),
 	Definition(&'a Key, Nullable<&'a TermDefinition>),
 }
@@ -246,7 +246,7 @@ impl<'a> EntryRef<'a> {
 			Self::Propagate(v) => EntryValueRef::Propagate(*v),
 			Self::Protected(v) => EntryValueRef::Protected(*v),
 			Self::Type(v) => EntryValueRef::Type(*v),
-			Self::Version(v) => EntryValueRef::Version(*v),
+			Self::Version(v) => EntryValueRef::Version(v),
 			Self::Vocab(v) => EntryValueRef::Vocab(*v),
 			Self::Definition(_, b) => EntryValueRef::Definition(*b),
 		}
@@ -265,7 +265,7 @@ impl<'a> EntryRef<'a> {
 			Self::Propagate(v) => (EntryKeyRef::Propagate, EntryValueRef::Propagate(*v)),
 			Self::Protected(v) => (EntryKeyRef::Protected, EntryValueRef::Protected(*v)),
 			Self::Type(v) => (EntryKeyRef::Type, EntryValueRef::Type(*v)),
-			Self::Version(v) => (EntryKeyRef::Version, EntryValueRef::Version(*v)),
+			Self::Version(v) => (EntryKeyRef::Version, EntryValueRef::Version(v)),
 			Self::Vocab(v) => (EntryKeyRef::Vocab, EntryValueRef::Vocab(*v)),
 			Self::Definition(key, b) => {
 				(EntryKeyRef::Definition(key), EntryValueRef::Definition(*b))
@@ -1,4 +1,4 @@
-use std::{hash::Hash, str::FromStr};
+use std::{fmt, str::FromStr};
 
 #[derive(Debug, thiserror::Error)]
 #[error("unknown JSON-LD version `{0}`")]
@@ -6,51 +6,79 @@ pub struct UnknownVersion(pub String);
 
 /// Version number.
 ///
-/// The only allowed value is a number with the value `1.1`.
-#[derive(Clone, Copy, PartialOrd, Ord, Debug)]
+/// The only value allowed by the JSON-LD 1.1 specification is a number with
+/// the value `1.1`. Other numbers are preserved so that contexts targeting
+/// other versions can be represented, but are rejected by the context
+/// processing algorithm.
+///
+/// Versions are compared by value: `1.10` and `1.1` are the same version.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
 pub enum Version {
+	/// JSON-LD 1.0.
+	V1_0,
+
+	/// JSON-LD 1.1.
 	V1_1,
+
+	/// Any other version number.
+	Unknown(json_syntax::NumberBuf),
 }
 
 impl Version {
-	pub fn into_bytes(self) -> &'static [u8] {
-		match self {
-			Self::V1_1 => b"1.1",
+	/// Creates a version from its number.
+	pub fn new(number: &json_syntax::Number) -> Self {
+		let number = number.trimmed();
+		match number.as_str() {
+			"1" => Self::V1_0,
+			"1.1" => Self::V1_1,
+			_ => Self::Unknown(json_syntax::NumberBuf::from_number(number)),
 		}
 	}
 
-	pub fn into_str(self) -> &'static str {
+	/// Checks if this is a version defined by a JSON-LD specification.
+	pub fn is_known(&self) -> bool {
+		!matches!(self, Self::Unknown(_))
+	}
+
+	pub fn as_bytes(&self) -> &[u8] {
+		self.as_str().as_bytes()
+	}
+
+	pub fn as_str(&self) -> &str {
 		match self {
+			Self::V1_0 => "1.0",
 			Self::V1_1 => "1.1",
+			Self::Unknown(n) => n.as_str(),
 		}
 	}
 
-	pub fn into_json_number(self) -> &'static json_syntax::Number {
-		unsafe { json_syntax::Number::new_unchecked(self.into_bytes()) }
+	pub fn as_json_number(&self) -> &json_syntax::Number {
+		unsafe { json_syntax::Number::new_unchecked(self.as_bytes()) }
 	}
 
 	pub fn into_json_number_buf(self) -> json_syntax::NumberBuf {
-		unsafe { json_syntax::NumberBuf::new_unchecked(self.into_bytes().into()) }
+		match self {
+			Self::Unknown(n) => n,
+			known => json_syntax::NumberBuf::from_number(known.as_json_number()),
+		}
 	}
 }
 
-impl PartialEq for Version {
-	fn eq(&self, _other: &Self) -> bool {
-		true
+impl fmt::Display for Version {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		self.as_str().fmt(f)
 	}
 }
 
-impl Eq for Version {}
-
-impl Hash for Version {
-	fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-		self.into_str().hash(state)
+impl<'a> From<&'a json_syntax::Number> for Version {
+	fn from(n: &'a json_syntax::Number) -> Self {
+		Self::new(n)
 	}
 }
 
-impl<'a> From<Version> for &'a json_syntax::Number {
-	fn from(v: Version) -> Self {
-		v.into_json_number()
+impl From<json_syntax::NumberBuf> for Version {
+	fn from(n: json_syntax::NumberBuf) -> Self {
+		Self::new(&n)
 	}
 }
 
@@ -60,26 +88,13 @@ impl From<Version> for json_syntax::NumberBuf {
 	}
 }
 
-impl TryFrom<json_syntax::NumberBuf> for Version {
-	type Error = UnknownVersion;
-
-	fn try_from(value: json_syntax::NumberBuf) -> Result<Self, Self::Error> {
-		if value.trimmed().as_str() == "1.1" {
-			Ok(Self::V1_1)
-		} else {
-			Err(UnknownVersion(value.to_string()))
-		}
-	}
-}
-
 impl FromStr for Version {
 	type Err = UnknownVersion;
 
 	fn from_str(s: &str) -> Result<Self, Self::Err> {
-		if s == "1.1" {
-			Ok(Version::V1_1)
-		} else {
-			Err(UnknownVersion(s.to_owned()))
+		match json_syntax::Number::new(s) {
+			Ok(n) => Ok(Self::new(n)),
+			Err(_) => Err(UnknownVersion(s.to_owned())),
 		}
 	}
 }
@@ -88,11 +103,7 @@ impl TryFrom<f32> for Version {
 	type Error = UnknownVersion;
 
 	fn try_from(value: f32) -> Result<Self, Self::Error> {
-		if value == 1.1 {
-			Ok(Version::V1_1)
-		} else {
-			Err(UnknownVersion(value.to_string()))
-		}
+		value.to_string().parse()
 	}
 }
 
@@ -100,11 +111,7 @@ impl TryFrom<f64> for Version {
 	type Error = UnknownVersion;
 
 	fn try_from(value: f64) -> Result<Self, Self::Error> {
-		if value == 1.1 {
-			Ok(Version::V1_1)
-		} else {
-			Err(UnknownVersion(value.to_string()))
-		}
+		value.to_string().parse()
 	}
 }
 
@@ -114,7 +121,7 @@ impl serde::Serialize for Version {
 	where
 		S: serde::Serializer,
 	{
-		self.into_json_number().serialize(serializer)
+		self.as_json_number().serialize(serializer)
 	}
 }
 
@@ -124,8 +131,24 @@ impl<'de> serde::Deserialize<'de> for Version {
 	where
 		D: serde::Deserializer<'de>,
 	{
-		json_syntax::NumberBuf::deserialize(deserializer)?
-			.try_into()
-			.map_err(serde::de::Error::custom)
+		json_syntax::NumberBuf::deserialize(deserializer).map(Self::from)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::Version;
+
+	#[test]
+	fn equality() {
+		assert_eq!("1.10".parse::<Version>().unwrap(), Version::V1_1);
+		assert_eq!("1.0".parse::<Version>().unwrap(), Version::V1_0);
+		assert_ne!(Version::V1_0, Version::V1_1);
+
+		let v1_2: Version = "1.2".parse().unwrap();
+		assert!(!v1_2.is_known());
+		assert_eq!(v1_2.as_str(), "1.2");
+		assert_ne!(v1_2, Version::V1_1);
+		assert!("one".parse::<Version>().is_err())
 	}
 }
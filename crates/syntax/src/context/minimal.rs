@@ -0,0 +1,159 @@
+use super::{
+	term_definition::{Expanded, Id, Simple},
+	Context, ContextEntry, Definition, TermDefinition,
+};
+use crate::{Container, IntoJson, Nullable};
+
+impl Context {
+	/// Rewrites this context into the smallest equivalent form.
+	///
+	/// Empty context definitions are removed from context arrays, and
+	/// single-entry arrays are unwrapped. Entries set to their default value
+	/// (`"@protected": false`, `"@prefix": false`, etc.) are dropped, and
+	/// expanded term definitions with only an `@id` entry are turned into
+	/// simple term definitions, unless the IRI ends with a gen-delim
+	/// character (which would make the term a prefix) or is a blank node
+	/// identifier. Scoped contexts are minimized recursively.
+	pub fn minimize(&mut self) {
+		match self {
+			Self::One(entry) => entry.minimize(),
+			Self::Many(entries) => {
+				entries.retain_mut(|entry| {
+					entry.minimize();
+					!matches!(entry, ContextEntry::Definition(d) if *d == Definition::default())
+				});
+
+				if entries.len() == 1 {
+					*self = Self::One(entries.pop().unwrap())
+				}
+			}
+		}
+	}
+
+	/// Writes this context to the smallest equivalent JSON form.
+	///
+	/// See [`Self::minimize`] for the performed rewrites.
+	pub fn to_json_minimal(&self) -> json_syntax::Value {
+		let mut context = self.clone();
+		context.minimize();
+		context.into_json()
+	}
+}
+
+impl ContextEntry {
+	fn minimize(&mut self) {
+		if let Self::Definition(definition) = self {
+			definition.minimize()
+		}
+	}
+}
+
+impl Definition {
+	fn minimize(&mut self) {
+		let protected = self.protected == Some(true);
+		if self.protected == Some(false) {
+			self.protected = None
+		}
+
+		self.bindings = std::mem::take(&mut self.bindings)
+			.into_iter()
+			.map(|(key, binding)| {
+				let binding = match binding {
+					Nullable::Some(TermDefinition::Expanded(expanded)) => {
+						expanded.minimize(protected)
+					}
+					binding => binding,
+				};
+
+				(key, binding)
+			})
+			.collect();
+	}
+}
+
+impl Expanded {
+	fn minimize(mut self: Box<Self>, protected_context: bool) -> Nullable<TermDefinition> {
+		if self.protected == Some(protected_context) {
+			self.protected = None
+		}
+
+		if self.prefix == Some(false) {
+			self.prefix = None
+		}
+
+		if let Some(Nullable::Some(Container::Many(kinds))) = &mut self.container {
+			if kinds.len() == 1 {
+				self.container = Some(Nullable::Some(Container::One(kinds.pop().unwrap())))
+			}
+		}
+
+		if let Some(context) = &mut self.context {
+			context.minimize()
+		}
+
+		let id_only = Self {
+			id: self.id.clone(),
+			..Default::default()
+		};
+
+		if *self == id_only {
+			match self.id {
+				Some(Nullable::Null) => return Nullable::Null,
+				Some(Nullable::Some(Id::Term(iri))) if !is_prefix_like(&iri) => {
+					return Nullable::Some(TermDefinition::Simple(Simple(iri)))
+				}
+				_ => (),
+			}
+		}
+
+		Nullable::Some(TermDefinition::Expanded(self))
+	}
+}
+
+/// Checks if a simple term definition mapping to `iri` could define a
+/// prefix.
+fn is_prefix_like(iri: &str) -> bool {
+	iri.starts_with("_:")
+		|| iri
+			.chars()
+			.last()
+			.is_some_and(|c| matches!(c, ':' | '/' | '?' | '#' | '[' | ']' | '@'))
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{context::Context, TryFromJson};
+
+	#[test]
+	fn to_json_minimal() {
+		let context = Context::try_from_json(json_syntax::json!([
+			{},
+			{
+				"@protected": false,
+				"name": { "@id": "https://schema.org/name", "@prefix": false },
+				"schema": { "@id": "https://schema.org/" },
+				"none": { "@id": null },
+				"tags": {
+					"@id": "https://schema.org/keywords",
+					"@container": [ "@set" ],
+					"@context": [ { "@protected": false }, "https://example.org/tags.jsonld" ]
+				}
+			}
+		]))
+		.unwrap();
+
+		assert_eq!(
+			context.to_json_minimal(),
+			json_syntax::json!({
+				"name": "https://schema.org/name",
+				"schema": { "@id": "https://schema.org/" },
+				"none": null,
+				"tags": {
+					"@id": "https://schema.org/keywords",
+					"@context": "https://example.org/tags.jsonld",
+					"@container": "@set"
+				}
+			})
+		)
+	}
+}
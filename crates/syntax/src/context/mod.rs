@@ -2,6 +2,7 @@ use iref::{Iri, IriRef, IriRefBuf};
 use smallvec::SmallVec;
 
 pub mod definition;
+mod minimal;
 mod print;
 pub mod term_definition;
 mod try_from_json;
@@ -13,7 +14,7 @@ pub use try_from_json::InvalidContext;
 /// JSON-LD Context.
 ///
 /// Can represent a single context entry, or a list of context entries.
-#[derive(PartialEq, Eq, Clone, Debug)]
+#[derive(PartialEq, Eq, Hash, Clone, Debug)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(feature = "serde", serde(untagged))]
 pub enum Context {
@@ -47,6 +48,26 @@ impl Context {
 	pub fn definition(def: Definition) -> Self {
 		Self::one(ContextEntry::Definition(def))
 	}
+
+	/// Returns the minimum JSON-LD version required to process this context.
+	///
+	/// This is [`Version::V1_1`] if one of the context definitions (including
+	/// scoped contexts) uses a feature introduced by JSON-LD 1.1, such as
+	/// `@version`, `@import`, `@protected` or `@id` containers, and
+	/// [`Version::V1_0`] otherwise. Remote contexts are not inspected.
+	///
+	/// [`Version::V1_0`]: definition::Version::V1_0
+	/// [`Version::V1_1`]: definition::Version::V1_1
+	pub fn required_version(&self) -> definition::Version {
+		if self.iter().any(|entry| match entry {
+			ContextEntry::Definition(def) => def.uses_json_ld_1_1(),
+			_ => false,
+		}) {
+			definition::Version::V1_1
+		} else {
+			definition::Version::V1_0
+		}
+	}
 }
 
 impl Context {
@@ -168,7 +189,7 @@ impl From<Definition> for Context {
 }
 
 /// Context.
-#[derive(PartialEq, Eq, Clone, Debug)]
+#[derive(PartialEq, Eq, Hash, Clone, Debug)]
 #[cfg_attr(
 	feature = "serde",
 	derive(serde::Serialize, serde::Deserialize),
@@ -211,17 +211,13 @@ impl Print for definition::TypeContainer {
 
 impl PrecomputeSize for definition::Version {
 	fn pre_compute_size(&self, _options: &Options, _sizes: &mut Vec<Size>) -> Size {
-		match self {
-			Self::V1_1 => Size::Width(3),
-		}
+		Size::Width(self.as_str().len())
 	}
 }
 
 impl Print for definition::Version {
 	fn fmt_with(&self, f: &mut fmt::Formatter, _options: &Options, _indent: usize) -> fmt::Result {
-		match self {
-			Self::V1_1 => write!(f, "1.1"),
-		}
+		write!(f, "{self}")
 	}
 }
 
@@ -17,7 +17,7 @@ pub use nest::*;
 pub use type_::*;
 
 /// Term definition.
-#[derive(PartialEq, Eq, Clone, Debug)]
+#[derive(PartialEq, Eq, Hash, Clone, Debug)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(feature = "serde", serde(untagged))]
 pub enum TermDefinition {
@@ -45,7 +45,7 @@ impl TermDefinition {
 	}
 }
 
-#[derive(PartialEq, Eq, Clone, Debug)]
+#[derive(PartialEq, Eq, Hash, Clone, Debug)]
 #[cfg_attr(
 	feature = "serde",
 	derive(serde::Serialize, serde::Deserialize),
@@ -94,7 +94,7 @@ impl From<BlankIdBuf> for Simple {
 }
 
 /// Expanded term definition.
-#[derive(PartialEq, Eq, Clone, Educe, Debug)]
+#[derive(PartialEq, Eq, Hash, Clone, Educe, Debug)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[educe(Default)]
 pub struct Expanded {
@@ -209,6 +209,111 @@ impl Expanded {
 		Self::default()
 	}
 
+	/// Sets the `@id` entry.
+	///
+	/// The identifier may be an IRI, a compact IRI, a blank node identifier,
+	/// a term or a keyword.
+	pub fn with_id(mut self, id: impl Into<String>) -> Self {
+		self.id = Some(Nullable::Some(Id::from(id.into())));
+		self
+	}
+
+	/// Sets the `@type` entry, the type coercion of the term.
+	pub fn with_type(mut self, type_: impl Into<String>) -> Self {
+		self.type_ = Some(Nullable::Some(Type::from(type_.into())));
+		self
+	}
+
+	/// Sets the `@context` entry, the scoped context of the term.
+	pub fn with_context(mut self, context: impl Into<context::Context>) -> Self {
+		self.context = Some(Box::new(context.into()));
+		self
+	}
+
+	/// Sets the `@reverse` entry.
+	pub fn with_reverse(mut self, reverse: impl Into<context::definition::Key>) -> Self {
+		self.reverse = Some(reverse.into());
+		self
+	}
+
+	/// Sets the `@index` entry.
+	pub fn with_index(mut self, index: impl Into<String>) -> Self {
+		self.index = Some(Index::from(index.into()));
+		self
+	}
+
+	/// Sets the `@language` entry.
+	pub fn with_language(mut self, language: Nullable<LenientLangTagBuf>) -> Self {
+		self.language = Some(language);
+		self
+	}
+
+	/// Sets the `@direction` entry.
+	pub fn with_direction(mut self, direction: Nullable<Direction>) -> Self {
+		self.direction = Some(direction);
+		self
+	}
+
+	/// Sets the `@container` entry.
+	pub fn with_container(mut self, container: impl Into<Container>) -> Self {
+		self.container = Some(Nullable::Some(container.into()));
+		self
+	}
+
+	/// Sets the `@nest` entry.
+	pub fn with_nest(mut self, nest: Nest) -> Self {
+		self.nest = Some(nest);
+		self
+	}
+
+	/// Sets the `@prefix` entry.
+	pub fn with_prefix(mut self, prefix: bool) -> Self {
+		self.prefix = Some(prefix);
+		self
+	}
+
+	/// Sets the `@propagate` entry.
+	pub fn with_propagate(mut self, propagate: bool) -> Self {
+		self.propagate = Some(propagate);
+		self
+	}
+
+	/// Sets the `@protected` entry.
+	pub fn with_protected(mut self, protected: bool) -> Self {
+		self.protected = Some(protected);
+		self
+	}
+
+	/// Checks if this definition uses a feature introduced by JSON-LD 1.1.
+	pub(crate) fn uses_json_ld_1_1(&self) -> bool {
+		let json_ld_1_0_container = match &self.container {
+			None | Some(Nullable::Null) => true,
+			Some(Nullable::Some(Container::One(c))) => matches!(
+				c,
+				ContainerKind::List
+					| ContainerKind::Set
+					| ContainerKind::Index
+					| ContainerKind::Language
+			),
+			Some(Nullable::Some(Container::Many(_))) => false,
+		};
+
+		!json_ld_1_0_container
+			|| self.context.is_some()
+			|| self.direction.is_some()
+			|| self.index.is_some()
+			|| self.nest.is_some()
+			|| self.prefix.is_some()
+			|| self.propagate.is_some()
+			|| self.protected.is_some()
+			|| matches!(
+				self.type_,
+				Some(Nullable::Some(Type::Keyword(
+					TypeKeyword::Json | TypeKeyword::None
+				)))
+			)
+	}
+
 	pub fn is_null(&self) -> bool {
 		matches!(&self.id, None | Some(Nullable::Null))
 			&& self.type_.is_none()
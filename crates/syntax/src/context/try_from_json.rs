@@ -40,6 +40,9 @@ impl InvalidContext {
 	}
 }
 
+#[cfg(feature = "miette")]
+crate::impl_diagnostic!(InvalidContext);
+
 impl From<crate::Unexpected> for InvalidContext {
 	fn from(crate::Unexpected(u, e): crate::Unexpected) -> Self {
 		Self::Unexpected(u, e)
@@ -187,10 +190,7 @@ impl TryFromJson for definition::Version {
 
 	fn try_from_json(value: json_syntax::Value) -> Result<Self, InvalidContext> {
 		match value {
-			json_syntax::Value::Number(n) => match n.as_str() {
-				"1.1" => Ok(Self::V1_1),
-				_ => Err(InvalidContext::InvalidTermDefinition),
-			},
+			json_syntax::Value::Number(n) => Ok(Self::new(&n)),
 			unexpected => Err(InvalidContext::Unexpected(
 				unexpected.kind(),
 				&[json_syntax::Kind::Number],
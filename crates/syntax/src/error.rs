@@ -47,6 +47,9 @@ pub enum ErrorCode {
 	/// The `@version` entry was used in a context with an out of range value.
 	InvalidVersionValue,
 
+	/// An invalid `@annotation` entry has been detected (JSON-LD-star).
+	InvalidAnnotation,
+
 	/// The value of `@direction` is not "ltr", "rtl", or null and thus invalid.
 	InvalidBaseDirection,
 
@@ -171,6 +174,23 @@ pub enum ErrorCode {
 
 	/// Duplicate key in JSON object.
 	DuplicateKey,
+
+	/// The nesting depth of the document exceeds the configured limit.
+	MaximumDepthExceeded,
+
+	/// The document exceeds a configured resource limit (literal length,
+	/// number of entries, nodes or graphs).
+	ResourceLimitExceeded,
+
+	/// The operation was cancelled by the user.
+	OperationCancelled,
+
+	/// A cycle in remote context inclusions has been detected.
+	///
+	/// This error code is defined by the JSON-LD 1.0 API specification. The
+	/// JSON-LD 1.1 API specification only bounds the number of remote
+	/// contexts with [`Self::ContextOverflow`].
+	RecursiveContextInclusion,
 }
 
 impl ErrorCode {
@@ -193,6 +213,7 @@ impl ErrorCode {
 			InvalidProtectedValue => "invalid @protected value",
 			InvalidReverseValue => "invalid @reverse value",
 			InvalidVersionValue => "invalid @version value",
+			InvalidAnnotation => "invalid annotation",
 			InvalidBaseDirection => "invalid base direction",
 			InvalidBaseIri => "invalid base IRI",
 			InvalidContainerMapping => "invalid container mapping",
@@ -230,8 +251,113 @@ impl ErrorCode {
 			ProcessingModeConflict => "processing mode conflict",
 			ProtectedTermRedefinition => "protected term redefinition",
 			DuplicateKey => "duplicate key",
+			MaximumDepthExceeded => "maximum depth exceeded",
+			ResourceLimitExceeded => "resource limit exceeded",
+			OperationCancelled => "operation cancelled",
+			RecursiveContextInclusion => "recursive context inclusion",
+		}
+	}
+
+	/// Checks if this error code is defined by the JSON-LD 1.1 API
+	/// specification.
+	pub fn is_standard(&self) -> bool {
+		use ErrorCode::*;
+		!matches!(
+			self,
+			InvalidAnnotation
+				| KeyExpansionFailed
+				| DuplicateKey
+				| MaximumDepthExceeded
+				| ResourceLimitExceeded
+				| OperationCancelled
+				| RecursiveContextInclusion
+		)
+	}
+
+	/// Returns the URL of the definition of this error code in the
+	/// [JSON-LD 1.1 API specification](https://www.w3.org/TR/json-ld11-api/),
+	/// if it is a [standard](Self::is_standard) error code.
+	pub fn url(&self) -> Option<String> {
+		if self.is_standard() {
+			let fragment: String = self
+				.as_str()
+				.chars()
+				.map(|c| match c {
+					' ' => "-".to_owned(),
+					'@' => "%40".to_owned(),
+					c => c.to_lowercase().to_string(),
+				})
+				.collect();
+
+			Some(format!(
+				"https://www.w3.org/TR/json-ld11-api/#dom-jsonlderrorcode-{fragment}"
+			))
+		} else {
+			None
 		}
 	}
+
+	/// Returns a help message pointing at the definition of this error code
+	/// in the specification, if any.
+	pub fn help(&self) -> Option<String> {
+		self.url()
+			.map(|url| format!("see the definition of the `{self}` error code at <{url}>"))
+	}
+}
+
+/// Implements [`miette::Diagnostic`] for an error type, using its
+/// [`ErrorCode`] as diagnostic code, and the [help](ErrorCode::help) and
+/// [URL](ErrorCode::url) of this code.
+///
+/// The error code is given by the `code` method of the error type, unless an
+/// expression is provided.
+///
+/// ```ignore
+/// json_ld_syntax::impl_diagnostic!(Error);
+/// json_ld_syntax::impl_diagnostic!(impl<T, B> ConflictingIndexes<T, B>, ErrorCode::ConflictingIndexes);
+/// ```
+#[cfg(feature = "miette")]
+#[macro_export]
+macro_rules! impl_diagnostic {
+	(impl<$($param:ident),*> $ty:ty, |$e:ident| $code:expr) => {
+		impl<$($param),*> $crate::__miette::Diagnostic for $ty
+		where
+			Self: ::std::error::Error,
+		{
+			fn code<'a>(&'a self) -> Option<Box<dyn ::std::fmt::Display + 'a>> {
+				let $e = self;
+				let code: $crate::ErrorCode = $code;
+				Some(Box::new(code))
+			}
+
+			fn help<'a>(&'a self) -> Option<Box<dyn ::std::fmt::Display + 'a>> {
+				let $e = self;
+				let code: $crate::ErrorCode = $code;
+				code.help().map(|h| Box::new(h) as _)
+			}
+
+			fn url<'a>(&'a self) -> Option<Box<dyn ::std::fmt::Display + 'a>> {
+				let $e = self;
+				let code: $crate::ErrorCode = $code;
+				code.url().map(|u| Box::new(u) as _)
+			}
+		}
+	};
+	(impl<$($param:ident),*> $ty:ty, $code:expr) => {
+		$crate::impl_diagnostic!(impl<$($param),*> $ty, |_e| $code);
+	};
+	(impl<$($param:ident),*> $ty:ty) => {
+		$crate::impl_diagnostic!(impl<$($param),*> $ty, |e| e.code());
+	};
+	($ty:ty, |$e:ident| $code:expr) => {
+		$crate::impl_diagnostic!(impl<> $ty, |$e| $code);
+	};
+	($ty:ty, $code:expr) => {
+		$crate::impl_diagnostic!(impl<> $ty, |_e| $code);
+	};
+	($ty:ty) => {
+		$crate::impl_diagnostic!(impl<> $ty, |e| e.code());
+	};
 }
 
 impl<'a> TryFrom<&'a str> for ErrorCode {
@@ -254,6 +380,7 @@ impl<'a> TryFrom<&'a str> for ErrorCode {
 			"invalid @protected value" => Ok(InvalidProtectedValue),
 			"invalid @reverse value" => Ok(InvalidReverseValue),
 			"invalid @version value" => Ok(InvalidVersionValue),
+			"invalid annotation" => Ok(InvalidAnnotation),
 			"invalid base direction" => Ok(InvalidBaseDirection),
 			"invalid base IRI" => Ok(InvalidBaseIri),
 			"invalid container mapping" => Ok(InvalidContainerMapping),
@@ -290,6 +417,7 @@ impl<'a> TryFrom<&'a str> for ErrorCode {
 			"multiple context link headers" => Ok(MultipleContextLinkHeaders),
 			"processing mode conflict" => Ok(ProcessingModeConflict),
 			"protected term redefinition" => Ok(ProtectedTermRedefinition),
+			"recursive context inclusion" => Ok(RecursiveContextInclusion),
 			_ => Err(()),
 		}
 	}
@@ -301,3 +429,64 @@ impl fmt::Display for ErrorCode {
 		write!(f, "{}", self.as_str())
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn url() {
+		assert_eq!(
+			ErrorCode::InvalidIdValue.url().as_deref(),
+			Some("https://www.w3.org/TR/json-ld11-api/#dom-jsonlderrorcode-invalid-%40id-value")
+		);
+		assert_eq!(
+			ErrorCode::CyclicIriMapping.url().as_deref(),
+			Some("https://www.w3.org/TR/json-ld11-api/#dom-jsonlderrorcode-cyclic-iri-mapping")
+		);
+		assert_eq!(ErrorCode::MaximumDepthExceeded.url(), None)
+	}
+	#[cfg(feature = "miette")]
+	#[test]
+	fn impl_diagnostic() {
+		use miette::Diagnostic;
+
+		#[derive(Debug, thiserror::Error)]
+		#[error("invalid @id value")]
+		struct InvalidId;
+
+		impl InvalidId {
+			fn code(&self) -> ErrorCode {
+				ErrorCode::InvalidIdValue
+			}
+		}
+
+		crate::impl_diagnostic!(InvalidId);
+
+		#[derive(Debug, thiserror::Error)]
+		#[error("too deep")]
+		struct TooDeep<T>(T);
+
+		crate::impl_diagnostic!(impl<T> TooDeep<T>, ErrorCode::MaximumDepthExceeded);
+
+		assert_eq!(
+			Diagnostic::code(&InvalidId)
+				.map(|c| c.to_string())
+				.as_deref(),
+			Some("invalid @id value")
+		);
+		assert_eq!(
+			Diagnostic::url(&InvalidId).map(|u| u.to_string()),
+			ErrorCode::InvalidIdValue.url()
+		);
+		assert!(Diagnostic::help(&InvalidId).is_some());
+
+		assert_eq!(
+			Diagnostic::code(&TooDeep(()))
+				.map(|c| c.to_string())
+				.as_deref(),
+			Some("maximum depth exceeded")
+		);
+		assert!(Diagnostic::url(&TooDeep(())).is_none())
+	}
+}
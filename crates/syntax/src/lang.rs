@@ -30,6 +30,45 @@ impl LenientLangTag {
 	pub fn as_well_formed(&self) -> Option<&LangTag> {
 		LangTag::new(self.as_str()).ok()
 	}
+
+	/// Returns this language tag in lowercase.
+	pub fn to_lowercase(&self) -> LenientLangTagBuf {
+		LenientLangTagBuf(self.0.to_ascii_lowercase())
+	}
+
+	/// Returns this language tag with the canonical case recommended by
+	/// [RFC 5646 section 2.1.1](https://www.rfc-editor.org/rfc/rfc5646#section-2.1.1).
+	///
+	/// Two-letter region subtags are uppercased (`en-US`), four-letter
+	/// script subtags are titlecased (`zh-Hant`), and every other subtag is
+	/// lowercased, including the subtags following a singleton (extensions
+	/// and private use).
+	pub fn to_canonical_case(&self) -> LenientLangTagBuf {
+		let mut result = String::with_capacity(self.0.len());
+		let mut after_singleton = false;
+
+		for (i, subtag) in self.0.split('-').enumerate() {
+			if i > 0 {
+				result.push('-')
+			}
+
+			let is_alphabetic = subtag.bytes().all(|b| b.is_ascii_alphabetic());
+			if i > 0 && !after_singleton && is_alphabetic && subtag.len() == 2 {
+				result.push_str(&subtag.to_ascii_uppercase())
+			} else if i > 0 && !after_singleton && is_alphabetic && subtag.len() == 4 {
+				result.push_str(&subtag[..1].to_ascii_uppercase());
+				result.push_str(&subtag[1..].to_ascii_lowercase())
+			} else {
+				result.push_str(&subtag.to_ascii_lowercase())
+			}
+
+			if subtag.len() == 1 {
+				after_singleton = true
+			}
+		}
+
+		LenientLangTagBuf(result)
+	}
 }
 
 impl PartialEq for LenientLangTag {
@@ -194,3 +233,18 @@ impl<'de> serde::Deserialize<'de> for LenientLangTagBuf {
 		Ok(Self(String::deserialize(deserializer)?))
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::LenientLangTag;
+
+	#[test]
+	fn canonical_case() {
+		let canonical = |s| LenientLangTag::new(s).0.to_canonical_case().into_string();
+		assert_eq!(canonical("EN-us"), "en-US");
+		assert_eq!(canonical("zh-hant-tw"), "zh-Hant-TW");
+		assert_eq!(canonical("es-419"), "es-419");
+		assert_eq!(canonical("en-a-BBB-X-ab-CDEF"), "en-a-bbb-x-ab-cdef");
+		assert_eq!(canonical("X-Private"), "x-private");
+	}
+}
@@ -35,5 +35,9 @@ pub use try_from_json::*;
 #[cfg(feature = "serde")]
 pub use json_syntax::{from_value, to_value};
 
+#[cfg(feature = "miette")]
+#[doc(hidden)]
+pub use miette as __miette;
+
 #[derive(Clone, Copy, Debug)]
 pub struct Unexpected(json_syntax::Kind, &'static [json_syntax::Kind]);
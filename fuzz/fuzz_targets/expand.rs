@@ -0,0 +1,7 @@
+#![no_main]
+use json_ld::{arbitrary::Document, invariant};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|document: Document| {
+	invariant::expand(&document);
+});
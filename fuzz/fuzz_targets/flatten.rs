@@ -0,0 +1,9 @@
+#![no_main]
+use json_ld::{arbitrary::Document, invariant};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|document: Document| {
+	if let Err(e) = invariant::check_flattening(&document) {
+		panic!("{e}\ndocument: {document:?}")
+	}
+});
@@ -0,0 +1,796 @@
+//! ActivityStreams 2.0 convenience layer.
+//!
+//! This module bundles the [ActivityStreams 2.0](https://www.w3.org/TR/activitystreams-core/)
+//! context and provides typed wrappers around the expanded node objects of
+//! common AS2 types:
+//!   - [`Object`], any AS2 object;
+//!   - [`Actor`], for actors (`Person`, `Service`, etc.);
+//!   - [`Note`], for notes;
+//!   - [`Activity`], for activities (`Create`, `Follow`, etc.).
+//!
+//! Federated (ActivityPub) payloads are read with [`parse`], which expands
+//! the JSON document, and written with [`emit`], which compacts an object
+//! using the AS2 context. The AS2 context is always served from the bundled
+//! copy (see [`As2Loader`]), so it is never fetched from the network.
+//!
+//! ## Example
+//!
+//! ```
+//! # #[async_std::main]
+//! # async fn main() {
+//! use json_ld::{as2, IriBuf, NoLoader};
+//!
+//! let actor = IriBuf::new("https://example.com/alice".to_owned()).unwrap();
+//! let note = as2::Note::new(IriBuf::new("https://example.com/notes/1".to_owned()).unwrap())
+//!   .with_attributed_to(actor.clone())
+//!   .with_content("Hello, Fediverse!")
+//!   .with_to(IriBuf::new(as2::PUBLIC.to_owned()).unwrap());
+//!
+//! let create = as2::Activity::create(
+//!   IriBuf::new("https://example.com/notes/1/create".to_owned()).unwrap(),
+//!   actor,
+//!   note.into(),
+//! );
+//!
+//! let json = as2::emit(&create, &NoLoader).await.unwrap();
+//! let parsed: as2::Activity = as2::parse(json, &NoLoader).await.unwrap();
+//! assert_eq!(parsed.kind(), Some(as2::ActivityType::Create));
+//! # }
+//! ```
+use crate::{
+	object::Literal, syntax, ExpandError, ExpandedDocument, Id, Indexed, IndexedObject,
+	JsonLdProcessor, LoadError, Loader, LoadingResult, NoLoader, Node, Options,
+	RemoteContextReference, RemoteDocument, Value,
+};
+use iref::{Iri, IriBuf, IriRefBuf};
+use json_syntax::Parse;
+use rdf_types::BlankIdBuf;
+use std::ops::Deref;
+
+/// URL of the ActivityStreams 2.0 context.
+pub const CONTEXT_IRI: &str = "https://www.w3.org/ns/activitystreams";
+
+/// Bundled ActivityStreams 2.0 context document.
+pub const CONTEXT: &str = include_str!("activitystreams.jsonld");
+
+/// ActivityStreams 2.0 namespace.
+pub const NAMESPACE: &str = "https://www.w3.org/ns/activitystreams#";
+
+/// Special collection addressing every user.
+pub const PUBLIC: &str = "https://www.w3.org/ns/activitystreams#Public";
+
+/// Loader serving the bundled ActivityStreams 2.0 context.
+///
+/// The AS2 context IRI is served from the bundled [`CONTEXT`] (whatever its
+/// fragment). Other IRIs are loaded with the inner loader.
+#[derive(Debug, Default, Clone)]
+pub struct As2Loader<L = NoLoader>(L);
+
+impl<L> As2Loader<L> {
+	/// Wraps the given loader.
+	pub fn new(loader: L) -> Self {
+		Self(loader)
+	}
+
+	/// Returns the inner loader.
+	pub fn into_inner(self) -> L {
+		self.0
+	}
+}
+
+impl<L: Loader> Loader for As2Loader<L> {
+	async fn load(&self, url: &Iri) -> LoadingResult<IriBuf> {
+		let mut document_url = url.to_owned();
+		document_url.set_fragment(None);
+
+		if document_url == CONTEXT_IRI {
+			let (doc, _) = json_syntax::Value::parse_str(CONTEXT)
+				.map_err(|e| LoadError::new(url.to_owned(), e))?;
+			Ok(RemoteDocument::new(
+				Some(url.to_owned()),
+				Some("application/ld+json".parse().unwrap()),
+				doc,
+			))
+		} else {
+			self.0.load(url).await
+		}
+	}
+}
+
+macro_rules! types {
+	($(#[$meta:meta])* $name:ident { $($variant:ident),* }) => {
+		$(#[$meta])*
+		#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+		pub enum $name {
+			$($variant),*
+		}
+
+		impl $name {
+			/// Every type.
+			pub const ALL: &'static [Self] = &[$(Self::$variant),*];
+
+			/// Returns the term defined for this type by the AS2 context.
+			pub fn name(&self) -> &'static str {
+				match self {
+					$(Self::$variant => stringify!($variant)),*
+				}
+			}
+
+			/// Returns the IRI of this type.
+			pub fn iri(&self) -> &'static Iri {
+				match self {
+					$(Self::$variant => Iri::new(concat!(
+						"https://www.w3.org/ns/activitystreams#",
+						stringify!($variant)
+					))
+					.unwrap()),*
+				}
+			}
+
+			/// Returns the type with the given IRI, if any.
+			pub fn from_iri(iri: &Iri) -> Option<Self> {
+				Self::ALL.iter().copied().find(|ty| ty.iri() == iri)
+			}
+		}
+	};
+}
+
+types! {
+	/// Actor type.
+	ActorType {
+		Application,
+		Group,
+		Organization,
+		Person,
+		Service
+	}
+}
+
+types! {
+	/// Activity type.
+	ActivityType {
+		Accept,
+		Add,
+		Announce,
+		Arrive,
+		Block,
+		Create,
+		Delete,
+		Dislike,
+		Flag,
+		Follow,
+		Ignore,
+		Invite,
+		Join,
+		Leave,
+		Like,
+		Listen,
+		Move,
+		Offer,
+		Question,
+		Read,
+		Reject,
+		Remove,
+		TentativeAccept,
+		TentativeReject,
+		Travel,
+		Undo,
+		Update,
+		View
+	}
+}
+
+/// Returns the IRI of the given AS2 property.
+fn property(name: &str) -> IriBuf {
+	IriBuf::new(format!("{NAMESPACE}{name}")).unwrap()
+}
+
+/// Any ActivityStreams 2.0 object.
+///
+/// Wraps an expanded node object, providing accessors and `with_*` builder
+/// methods for the common AS2 properties.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Object(Node);
+
+impl Object {
+	/// Creates a new object with the given identifier and type.
+	pub fn new(id: IriBuf, ty: &Iri) -> Self {
+		let mut node = Node::with_id(Id::iri(id));
+		node.types_mut_or_default().push(Id::iri(ty.to_owned()));
+		Self(node)
+	}
+
+	/// Returns the underlying node object.
+	pub fn as_node(&self) -> &Node {
+		&self.0
+	}
+
+	/// Returns the underlying node object.
+	pub fn into_node(self) -> Node {
+		self.0
+	}
+
+	/// Returns the identifier of the object, if it is an IRI.
+	pub fn id(&self) -> Option<&Iri> {
+		self.0.id.as_ref().and_then(Id::as_iri).map(IriBuf::as_iri)
+	}
+
+	/// Returns the IRIs of the object types.
+	pub fn types(&self) -> impl Iterator<Item = &Iri> {
+		self.0
+			.types()
+			.iter()
+			.filter_map(Id::as_iri)
+			.map(IriBuf::as_iri)
+	}
+
+	/// Checks if the object has the given type.
+	pub fn has_type(&self, ty: &Iri) -> bool {
+		self.types().any(|t| t == ty)
+	}
+
+	/// Returns the objects of the given AS2 property.
+	pub fn get(&self, name: &str) -> impl Iterator<Item = &IndexedObject<IriBuf, BlankIdBuf>> {
+		self.0.get(&property(name))
+	}
+
+	/// Returns the first string value of the given AS2 property.
+	pub fn get_str(&self, name: &str) -> Option<&str> {
+		self.get(name)
+			.filter_map(|object| object.as_value())
+			.find_map(Value::as_str)
+	}
+
+	/// Returns the IRIs of the nodes referenced (or embedded) by the given
+	/// AS2 property.
+	pub fn get_iris(&self, name: &str) -> impl Iterator<Item = &Iri> {
+		self.get(name)
+			.filter_map(|object| object.as_node())
+			.filter_map(|node| node.id.as_ref().and_then(Id::as_iri))
+			.map(IriBuf::as_iri)
+	}
+
+	/// Returns the `name` of the object.
+	pub fn name(&self) -> Option<&str> {
+		self.get_str("name")
+	}
+
+	/// Returns the `summary` of the object.
+	pub fn summary(&self) -> Option<&str> {
+		self.get_str("summary")
+	}
+
+	/// Returns the `content` of the object.
+	pub fn content(&self) -> Option<&str> {
+		self.get_str("content")
+	}
+
+	/// Returns the `published` date of the object.
+	pub fn published(&self) -> Option<&str> {
+		self.get_str("published")
+	}
+
+	/// Returns the `attributedTo` IRIs of the object.
+	pub fn attributed_to(&self) -> impl Iterator<Item = &Iri> {
+		self.get_iris("attributedTo")
+	}
+
+	/// Returns the `inReplyTo` IRIs of the object.
+	pub fn in_reply_to(&self) -> impl Iterator<Item = &Iri> {
+		self.get_iris("inReplyTo")
+	}
+
+	/// Returns the `to` recipients of the object.
+	pub fn to(&self) -> impl Iterator<Item = &Iri> {
+		self.get_iris("to")
+	}
+
+	/// Returns the `cc` recipients of the object.
+	pub fn cc(&self) -> impl Iterator<Item = &Iri> {
+		self.get_iris("cc")
+	}
+
+	/// Checks if the object is addressed to the [`PUBLIC`] collection.
+	///
+	/// As required by the AS2 specification, the `Public` and `as:Public`
+	/// forms are accepted too, even when they are not expanded into
+	/// [`PUBLIC`] (`Public` is a relative IRI reference, and `as:Public` is
+	/// kept as is if the `as` prefix is not defined).
+	pub fn is_public(&self) -> bool {
+		["to", "cc"]
+			.into_iter()
+			.flat_map(|name| self.get(name))
+			.filter_map(|object| object.as_node()?.id.as_ref())
+			.any(|id| matches!(id.as_str(), PUBLIC | "as:Public" | "Public"))
+	}
+}
+
+impl From<Node> for Object {
+	fn from(node: Node) -> Self {
+		Self(node)
+	}
+}
+
+impl From<Object> for Node {
+	fn from(object: Object) -> Self {
+		object.0
+	}
+}
+
+macro_rules! builders {
+	($ty:ident) => {
+		impl $ty {
+			/// Adds a value to the given AS2 property.
+			pub fn with(mut self, name: &str, value: crate::Object) -> Self {
+				self.node_mut()
+					.insert(Id::iri(property(name)), Indexed::new(value, None));
+				self
+			}
+
+			/// Adds a string value to the given AS2 property.
+			pub fn with_str(self, name: &str, value: &str) -> Self {
+				self.with(
+					name,
+					crate::Object::Value(Value::Literal(Literal::String(value.into()), None)),
+				)
+			}
+
+			/// Adds a node reference to the given AS2 property.
+			pub fn with_iri(self, name: &str, iri: IriBuf) -> Self {
+				self.with(name, crate::Object::node(Node::with_id(Id::iri(iri))))
+			}
+
+			/// Sets the `name` of the object.
+			pub fn with_name(self, name: &str) -> Self {
+				self.with_str("name", name)
+			}
+
+			/// Sets the `summary` of the object.
+			pub fn with_summary(self, summary: &str) -> Self {
+				self.with_str("summary", summary)
+			}
+
+			/// Sets the `content` of the object.
+			pub fn with_content(self, content: &str) -> Self {
+				self.with_str("content", content)
+			}
+
+			/// Sets the `published` date of the object.
+			pub fn with_published(self, published: &str) -> Self {
+				self.with(
+					"published",
+					crate::Object::Value(Value::Literal(
+						Literal::String(published.into()),
+						Some(IriBuf::new(XSD_DATE_TIME.to_owned()).unwrap()),
+					)),
+				)
+			}
+
+			/// Adds an `attributedTo` IRI.
+			pub fn with_attributed_to(self, iri: IriBuf) -> Self {
+				self.with_iri("attributedTo", iri)
+			}
+
+			/// Adds an `inReplyTo` IRI.
+			pub fn with_in_reply_to(self, iri: IriBuf) -> Self {
+				self.with_iri("inReplyTo", iri)
+			}
+
+			/// Adds a `to` recipient.
+			pub fn with_to(self, iri: IriBuf) -> Self {
+				self.with_iri("to", iri)
+			}
+
+			/// Adds a `cc` recipient.
+			pub fn with_cc(self, iri: IriBuf) -> Self {
+				self.with_iri("cc", iri)
+			}
+		}
+	};
+}
+
+/// `xsd:dateTime` datatype, used by the AS2 context for dates.
+const XSD_DATE_TIME: &str = "http://www.w3.org/2001/XMLSchema#dateTime";
+
+impl Object {
+	fn node_mut(&mut self) -> &mut Node {
+		&mut self.0
+	}
+}
+
+builders!(Object);
+
+macro_rules! wrapper {
+	($(#[$meta:meta])* $ty:ident) => {
+		$(#[$meta])*
+		#[derive(Debug, Clone, PartialEq, Eq)]
+		pub struct $ty(Object);
+
+		impl $ty {
+			fn node_mut(&mut self) -> &mut Node {
+				&mut (self.0).0
+			}
+
+			/// Returns the underlying object.
+			pub fn into_object(self) -> Object {
+				self.0
+			}
+		}
+
+		impl Deref for $ty {
+			type Target = Object;
+
+			fn deref(&self) -> &Object {
+				&self.0
+			}
+		}
+
+		impl From<$ty> for Object {
+			fn from(value: $ty) -> Self {
+				value.0
+			}
+		}
+
+		builders!($ty);
+	};
+}
+
+wrapper! {
+	/// Actor, such as a `Person` or a `Service`.
+	Actor
+}
+
+impl Actor {
+	/// Creates a new actor.
+	pub fn new(kind: ActorType, id: IriBuf) -> Self {
+		Self(Object::new(id, kind.iri()))
+	}
+
+	/// Returns the actor type.
+	pub fn kind(&self) -> Option<ActorType> {
+		self.types().find_map(ActorType::from_iri)
+	}
+
+	/// Returns the `preferredUsername` of the actor.
+	pub fn preferred_username(&self) -> Option<&str> {
+		self.get_str("preferredUsername")
+	}
+
+	/// Returns the `inbox` of the actor.
+	///
+	/// The `inbox` property is defined in the LDP namespace.
+	pub fn inbox(&self) -> Option<&Iri> {
+		self.0
+			.as_node()
+			.get(&IriBuf::new(LDP_INBOX.to_owned()).unwrap())
+			.filter_map(|object| object.as_node())
+			.find_map(|node| node.id.as_ref().and_then(Id::as_iri))
+			.map(IriBuf::as_iri)
+	}
+
+	/// Returns the `outbox` of the actor.
+	pub fn outbox(&self) -> Option<&Iri> {
+		self.get_iris("outbox").next()
+	}
+
+	/// Returns the `followers` collection of the actor.
+	pub fn followers(&self) -> Option<&Iri> {
+		self.get_iris("followers").next()
+	}
+
+	/// Returns the `following` collection of the actor.
+	pub fn following(&self) -> Option<&Iri> {
+		self.get_iris("following").next()
+	}
+
+	/// Sets the `preferredUsername` of the actor.
+	pub fn with_preferred_username(self, username: &str) -> Self {
+		self.with_str("preferredUsername", username)
+	}
+
+	/// Sets the `inbox` of the actor.
+	pub fn with_inbox(mut self, iri: IriBuf) -> Self {
+		self.node_mut().insert(
+			Id::iri(IriBuf::new(LDP_INBOX.to_owned()).unwrap()),
+			Indexed::new(crate::Object::node(Node::with_id(Id::iri(iri))), None),
+		);
+		self
+	}
+
+	/// Sets the `outbox` of the actor.
+	pub fn with_outbox(self, iri: IriBuf) -> Self {
+		self.with_iri("outbox", iri)
+	}
+
+	/// Sets the `followers` collection of the actor.
+	pub fn with_followers(self, iri: IriBuf) -> Self {
+		self.with_iri("followers", iri)
+	}
+
+	/// Sets the `following` collection of the actor.
+	pub fn with_following(self, iri: IriBuf) -> Self {
+		self.with_iri("following", iri)
+	}
+}
+
+/// `inbox` property.
+const LDP_INBOX: &str = "http://www.w3.org/ns/ldp#inbox";
+
+impl TryFrom<Object> for Actor {
+	type Error = Object;
+
+	fn try_from(object: Object) -> Result<Self, Object> {
+		if object.types().any(|ty| ActorType::from_iri(ty).is_some()) {
+			Ok(Self(object))
+		} else {
+			Err(object)
+		}
+	}
+}
+
+wrapper! {
+	/// Note, a short written work.
+	Note
+}
+
+/// `Note` type.
+const NOTE: &str = "https://www.w3.org/ns/activitystreams#Note";
+
+impl Note {
+	/// Creates a new note.
+	pub fn new(id: IriBuf) -> Self {
+		Self(Object::new(id, Iri::new(NOTE).unwrap()))
+	}
+}
+
+impl TryFrom<Object> for Note {
+	type Error = Object;
+
+	fn try_from(object: Object) -> Result<Self, Object> {
+		if object.has_type(Iri::new(NOTE).unwrap()) {
+			Ok(Self(object))
+		} else {
+			Err(object)
+		}
+	}
+}
+
+wrapper! {
+	/// Activity, such as `Create` or `Follow`.
+	Activity
+}
+
+impl Activity {
+	/// Creates a new activity performed by the given `actor`.
+	pub fn new(kind: ActivityType, id: IriBuf, actor: IriBuf) -> Self {
+		Self(Object::new(id, kind.iri())).with_iri("actor", actor)
+	}
+
+	/// Creates a `Create` activity embedding the given `object`.
+	///
+	/// The activity is addressed to the `to` and `cc` recipients of the
+	/// object.
+	pub fn create(id: IriBuf, actor: IriBuf, object: Object) -> Self {
+		let mut activity = Self::new(ActivityType::Create, id, actor);
+
+		for iri in object.to() {
+			activity = activity.with_to(iri.to_owned())
+		}
+
+		for iri in object.cc() {
+			activity = activity.with_cc(iri.to_owned())
+		}
+
+		activity.with_object(object)
+	}
+
+	/// Returns the activity type.
+	pub fn kind(&self) -> Option<ActivityType> {
+		self.types().find_map(ActivityType::from_iri)
+	}
+
+	/// Returns the `actor` IRIs of the activity.
+	pub fn actor(&self) -> impl Iterator<Item = &Iri> {
+		self.get_iris("actor")
+	}
+
+	/// Returns the `object` of the activity, if it is embedded.
+	pub fn object(&self) -> Option<Object> {
+		self.get("object")
+			.find_map(|object| object.as_node())
+			.filter(|node| !node.is_empty())
+			.map(|node| Object(node.clone()))
+	}
+
+	/// Returns the IRI of the `object` of the activity.
+	pub fn object_iri(&self) -> Option<&Iri> {
+		self.get_iris("object").next()
+	}
+
+	/// Returns the `target` IRI of the activity.
+	pub fn target(&self) -> Option<&Iri> {
+		self.get_iris("target").next()
+	}
+
+	/// Embeds the given `object` in the activity.
+	pub fn with_object(self, object: Object) -> Self {
+		self.with("object", crate::Object::node(object.0))
+	}
+
+	/// Sets the IRI of the `object` of the activity.
+	pub fn with_object_iri(self, iri: IriBuf) -> Self {
+		self.with_iri("object", iri)
+	}
+
+	/// Sets the `target` of the activity.
+	pub fn with_target(self, iri: IriBuf) -> Self {
+		self.with_iri("target", iri)
+	}
+}
+
+impl TryFrom<Object> for Activity {
+	type Error = Object;
+
+	fn try_from(object: Object) -> Result<Self, Object> {
+		if object
+			.types()
+			.any(|ty| ActivityType::from_iri(ty).is_some())
+		{
+			Ok(Self(object))
+		} else {
+			Err(object)
+		}
+	}
+}
+
+/// Error raised by [`parse`].
+#[derive(Debug, thiserror::Error)]
+pub enum ParseError {
+	/// Document expansion failed.
+	#[error("Expansion failed: {0}")]
+	Expand(ExpandError),
+
+	/// The document does not contain exactly one top-level node object.
+	#[error("expected one top-level object, found {0}")]
+	NotOneObject(usize),
+
+	/// The object does not have the expected type.
+	#[error("unexpected object type")]
+	UnexpectedType(Box<Object>),
+}
+
+/// Parses an ActivityStreams 2.0 payload.
+///
+/// The JSON document is expanded using the given loader, wrapped in an
+/// [`As2Loader`], and must contain exactly one top-level node object, which
+/// is then converted into `T` ([`Object`], [`Actor`], [`Note`] or
+/// [`Activity`]).
+pub async fn parse<T: TryFrom<Object, Error = Object>>(
+	json: json_syntax::Value,
+	loader: &impl Loader,
+) -> Result<T, ParseError> {
+	let document = RemoteDocument::new(None, None, json)
+		.expand(&As2Loader::new(loader))
+		.await
+		.map_err(ParseError::Expand)?;
+
+	let len = document.len();
+	let mut nodes = document
+		.into_iter()
+		.filter_map(|object| object.into_inner().into_node());
+	match (nodes.next(), len) {
+		(Some(node), 1) => {
+			T::try_from(Object(node)).map_err(|object| ParseError::UnexpectedType(Box::new(object)))
+		}
+		_ => Err(ParseError::NotOneObject(len)),
+	}
+}
+
+/// Emits an ActivityStreams 2.0 payload.
+///
+/// The object is compacted using the AS2 context, served by an
+/// [`As2Loader`] wrapping the given loader. The `@context` entry of the
+/// result is the AS2 context IRI.
+pub async fn emit(object: &Object, loader: &impl Loader) -> crate::CompactResult {
+	let mut document = ExpandedDocument::new();
+	document.insert(Indexed::new(crate::Object::node(object.0.clone()), None));
+
+	crate::processor::compact_expanded_full(
+		&document,
+		None,
+		rdf_types::vocabulary::no_vocabulary_mut(),
+		RemoteContextReference::Loaded(RemoteDocument::new(
+			None,
+			None,
+			syntax::context::Context::iri_ref(IriRefBuf::new(CONTEXT_IRI.to_owned()).unwrap()),
+		)),
+		&As2Loader::new(loader),
+		Options::default(),
+		(),
+	)
+	.await
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn iri(s: &str) -> IriBuf {
+		IriBuf::new(s.to_owned()).unwrap()
+	}
+
+	#[async_std::test]
+	async fn round_trip() {
+		let note = Note::new(iri("https://example.com/notes/1"))
+			.with_attributed_to(iri("https://example.com/alice"))
+			.with_content("Hello")
+			.with_published("2024-01-01T00:00:00Z")
+			.with_to(iri(PUBLIC));
+
+		let create = Activity::create(
+			iri("https://example.com/notes/1/create"),
+			iri("https://example.com/alice"),
+			note.clone().into(),
+		);
+
+		let json = emit(&create, &NoLoader).await.unwrap();
+		assert_eq!(
+			json,
+			json_syntax::json!({
+				"@context": "https://www.w3.org/ns/activitystreams",
+				"id": "https://example.com/notes/1/create",
+				"type": "Create",
+				"actor": "https://example.com/alice",
+				"to": "as:Public",
+				"object": {
+					"id": "https://example.com/notes/1",
+					"type": "Note",
+					"attributedTo": "https://example.com/alice",
+					"content": "Hello",
+					"published": "2024-01-01T00:00:00Z",
+					"to": "as:Public"
+				}
+			})
+		);
+
+		let parsed: Activity = parse(json, &NoLoader).await.unwrap();
+		assert_eq!(parsed.kind(), Some(ActivityType::Create));
+		assert_eq!(parsed.actor().next().unwrap(), "https://example.com/alice");
+
+		let object: Note = parsed.object().unwrap().try_into().unwrap();
+		assert_eq!(object.content(), Some("Hello"));
+		assert!(object.is_public());
+		assert_eq!(object, note)
+	}
+	#[async_std::test]
+	async fn non_canonical_audience() {
+		let contexts = [
+			json_syntax::json!("https://www.w3.org/ns/activitystreams"),
+			json_syntax::json!(["https://www.w3.org/ns/activitystreams", { "as": null }]),
+		];
+
+		for context in contexts {
+			for audience in ["Public", "as:Public", PUBLIC] {
+				let json = json_syntax::json!({
+					"@context": context.clone(),
+					"id": "https://example.com/notes/1",
+					"type": "Note",
+					"cc": audience
+				});
+
+				let note: Note = parse(json, &NoLoader).await.unwrap();
+				assert!(note.is_public(), "{audience} is not public")
+			}
+		}
+
+		let json = json_syntax::json!({
+			"@context": "https://www.w3.org/ns/activitystreams",
+			"id": "https://example.com/notes/1",
+			"type": "Note",
+			"to": "https://example.com/Public"
+		});
+
+		let note: Note = parse(json, &NoLoader).await.unwrap();
+		assert!(!note.is_public())
+	}
+}
@@ -0,0 +1,333 @@
+//! Follow-your-nose dereferencing.
+use crate::{
+	DocumentSet, ExpandError, ExpandedDocument, JsonLdProcessor, Loader, Options,
+	RemoteDocumentReference, ValidId,
+};
+use iref::Iri;
+use rdf_types::VocabularyMut;
+use std::{collections::HashSet, hash::Hash};
+
+/// Dereferences node references to build a [`DocumentSet`].
+///
+/// Starting from an expanded document, every dangling node reference (see
+/// [`DocumentSet::dangling_references`]) whose IRI is accepted by the filter
+/// is dereferenced: the IRI, without its fragment, is loaded with the
+/// loader and the resulting remote document is expanded. Expanded documents
+/// are attached to the document set, named by the URL they were loaded from,
+/// and their own node references are followed in turn, up to the maximum
+/// depth.
+///
+/// Each URL is loaded at most once. Failing to load or expand a document does
+/// not stop the process: the error is reported in the
+/// [`Dereferenced::failures`] list.
+///
+/// # Example
+///
+/// ```
+/// # #[async_std::main]
+/// # async fn main() {
+/// use json_ld::{Dereferencer, ExpandedDocument, IriBuf, NoLoader};
+///
+/// let url = IriBuf::new("https://example.com/".to_owned()).unwrap();
+/// let document = ExpandedDocument::new();
+///
+/// let result = Dereferencer::new()
+///   .with_max_depth(2)
+///   .dereference(&NoLoader, url, document)
+///   .await;
+///
+/// assert_eq!(result.documents.len(), 1);
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct Dereferencer<I = iref::IriBuf> {
+	options: Options<I>,
+	max_depth: usize,
+	filter: fn(&Iri) -> bool,
+}
+
+impl<I> Default for Dereferencer<I> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<I> Dereferencer<I> {
+	/// Creates a new dereferencer following references to `http` and `https`
+	/// IRIs, up to a depth of 1.
+	pub fn new() -> Self {
+		Self {
+			options: Options::default(),
+			max_depth: 1,
+			filter: is_http,
+		}
+	}
+
+	/// Sets the options used to expand the dereferenced documents.
+	///
+	/// The `base` option is ignored: the base IRI of a dereferenced document
+	/// is always the URL it was loaded from.
+	pub fn with_options(self, options: Options<I>) -> Self {
+		Self { options, ..self }
+	}
+
+	/// Sets the maximum depth of the dereferenced documents.
+	///
+	/// Documents dereferenced from the initial document have depth 1. A depth
+	/// of 0 disables dereferencing.
+	pub fn with_max_depth(self, max_depth: usize) -> Self {
+		Self { max_depth, ..self }
+	}
+
+	/// Sets the function deciding which IRIs are dereferenced.
+	pub fn with_filter(self, filter: fn(&Iri) -> bool) -> Self {
+		Self { filter, ..self }
+	}
+}
+
+impl<I: Clone + Eq + Hash> Dereferencer<I> {
+	/// Dereferences the node references of `document`, named `url`, using
+	/// the given vocabulary.
+	pub async fn dereference_with<N>(
+		&self,
+		vocabulary: &mut N,
+		loader: &impl Loader,
+		url: I,
+		document: ExpandedDocument<I, N::BlankId>,
+	) -> Dereferenced<I, N::BlankId>
+	where
+		N: VocabularyMut<Iri = I>,
+		N::BlankId: Clone + Eq + Hash,
+	{
+		let options = Options {
+			base: None,
+			..self.options.clone()
+		};
+
+		let mut documents = DocumentSet::new();
+		let mut failures = Vec::new();
+		let mut visited = HashSet::new();
+
+		visited.insert(url.clone());
+		documents.insert(url.clone(), document);
+		let mut frontier = vec![url];
+
+		for _ in 0..self.max_depth {
+			let mut urls = Vec::new();
+			for reference in documents.dangling_references() {
+				if frontier.contains(reference.document) {
+					if let ValidId::Iri(iri) = reference.id {
+						if let Some(url) = self.document_url(vocabulary, iri) {
+							if visited.insert(url.clone()) {
+								urls.push(url)
+							}
+						}
+					}
+				}
+			}
+
+			frontier.clear();
+			for url in urls {
+				match RemoteDocumentReference::Iri(url.clone())
+					.expand_full(vocabulary, loader, options.clone(), ())
+					.await
+				{
+					Ok(document) => {
+						documents.insert(url.clone(), document);
+						frontier.push(url)
+					}
+					Err(e) => failures.push((url, e)),
+				}
+			}
+
+			if frontier.is_empty() {
+				break;
+			}
+		}
+
+		Dereferenced {
+			documents,
+			failures,
+		}
+	}
+
+	/// Dereferences the node references of `document`, named `url`.
+	///
+	/// See [`Self::dereference_with`].
+	pub async fn dereference(
+		&self,
+		loader: &impl Loader,
+		url: I,
+		document: ExpandedDocument<I>,
+	) -> Dereferenced<I>
+	where
+		(): VocabularyMut<Iri = I, BlankId = rdf_types::BlankIdBuf>,
+	{
+		self.dereference_with(
+			rdf_types::vocabulary::no_vocabulary_mut(),
+			loader,
+			url,
+			document,
+		)
+		.await
+	}
+
+	/// Returns the URL of the document to load to dereference `iri`, if it
+	/// passes the filter.
+	fn document_url<N: VocabularyMut<Iri = I>>(&self, vocabulary: &mut N, iri: &I) -> Option<I> {
+		let iri = vocabulary.iri(iri)?;
+		if !(self.filter)(iri) {
+			return None;
+		}
+
+		let mut url = iri.to_owned();
+		url.set_fragment(None);
+		Some(vocabulary.insert(url.as_iri()))
+	}
+}
+
+/// Result of [`Dereferencer::dereference_with`].
+pub struct Dereferenced<I = iref::IriBuf, B = rdf_types::BlankIdBuf> {
+	/// Initial document and every successfully dereferenced document.
+	pub documents: DocumentSet<I, B>,
+
+	/// URLs that could not be dereferenced, with the error.
+	pub failures: Vec<(I, ExpandError)>,
+}
+
+/// Checks that the given IRI uses the `http` or `https` scheme.
+fn is_http(iri: &Iri) -> bool {
+	matches!(iri.scheme().as_str(), "http" | "https")
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{IriBuf, RemoteDocument};
+	use std::collections::HashMap;
+
+	fn iri(iri: &str) -> IriBuf {
+		IriBuf::new(iri.to_owned()).unwrap()
+	}
+
+	fn loader() -> HashMap<IriBuf, RemoteDocument> {
+		[
+			(
+				"https://example.com/a",
+				json_syntax::json!({
+					"@id": "https://example.com/a",
+					"https://example.com/p": [
+						{ "@id": "https://example.com/b#x" },
+						{ "@id": "https://example.com/b#y" },
+						{ "@id": "urn:example:c" },
+						{ "@id": "https://example.com/missing" }
+					]
+				}),
+			),
+			(
+				"https://example.com/b",
+				json_syntax::json!([
+					{ "@id": "https://example.com/b#x", "https://example.com/p": { "@id": "https://example.com/d" } },
+					{ "@id": "https://example.com/b#y" }
+				]),
+			),
+			(
+				"https://example.com/d",
+				json_syntax::json!({
+					"@id": "https://example.com/d",
+					"https://example.com/p": { "@id": "https://example.com/e" }
+				}),
+			),
+			(
+				"https://example.com/e",
+				json_syntax::json!({ "@id": "https://example.com/e" }),
+			),
+		]
+		.into_iter()
+		.map(|(url, json)| (iri(url), RemoteDocument::new(Some(iri(url)), None, json)))
+		.collect()
+	}
+
+	fn dereference(dereferencer: Dereferencer) -> (Vec<IriBuf>, Vec<IriBuf>) {
+		let loader = loader();
+		let url = iri("https://example.com/a");
+		let document =
+			futures::executor::block_on(RemoteDocumentReference::Iri(url.clone()).expand(&loader))
+				.unwrap();
+
+		let result = futures::executor::block_on(dereferencer.dereference(&loader, url, document));
+		let documents = result
+			.documents
+			.iter()
+			.map(|(url, _)| url.clone())
+			.collect();
+		let failures = result.failures.into_iter().map(|(url, _)| url).collect();
+		(documents, failures)
+	}
+
+	#[test]
+	fn max_depth() {
+		let (documents, failures) = dereference(Dereferencer::new().with_max_depth(0));
+		assert_eq!(documents, [iri("https://example.com/a")]);
+		assert!(failures.is_empty());
+
+		let (documents, _) = dereference(Dereferencer::new());
+		assert_eq!(
+			documents,
+			[iri("https://example.com/a"), iri("https://example.com/b")]
+		);
+
+		let (documents, _) = dereference(Dereferencer::new().with_max_depth(2));
+		assert_eq!(documents.len(), 3);
+
+		let (documents, _) = dereference(Dereferencer::new().with_max_depth(10));
+		assert_eq!(
+			documents,
+			[
+				iri("https://example.com/a"),
+				iri("https://example.com/b"),
+				iri("https://example.com/d"),
+				iri("https://example.com/e")
+			]
+		)
+	}
+
+	#[test]
+	fn fragments_are_stripped() {
+		// `b#x` and `b#y` both dereference to `b`, loaded once. The loader
+		// does not know the IRIs with a fragment.
+		let (documents, failures) = dereference(Dereferencer::new());
+		assert_eq!(
+			documents,
+			[iri("https://example.com/a"), iri("https://example.com/b")]
+		);
+		assert_eq!(failures, [iri("https://example.com/missing")])
+	}
+
+	#[test]
+	fn filter() {
+		// `urn:` IRIs are not dereferenced by default.
+		let (_, failures) = dereference(Dereferencer::new());
+		assert_eq!(failures, [iri("https://example.com/missing")]);
+
+		let (_, failures) = dereference(Dereferencer::new().with_filter(|_| true));
+		assert_eq!(
+			failures,
+			[iri("urn:example:c"), iri("https://example.com/missing")]
+		);
+
+		let (documents, failures) = dereference(
+			Dereferencer::new().with_filter(|iri| is_http(iri) && iri.path().as_str() != "/b"),
+		);
+		assert_eq!(documents, [iri("https://example.com/a")]);
+		assert_eq!(failures, [iri("https://example.com/missing")])
+	}
+
+	#[test]
+	fn load_failures() {
+		// Failures do not stop the process.
+		let (documents, failures) = dereference(Dereferencer::new().with_max_depth(10));
+		assert_eq!(documents.len(), 4);
+		assert_eq!(failures, [iri("https://example.com/missing")])
+	}
+}
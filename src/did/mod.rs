@@ -0,0 +1,711 @@
+//! DID document processing helpers.
+//!
+//! This module bundles the [DID Core](https://www.w3.org/TR/did-core/)
+//! context and provides helpers to expand and validate DID documents.
+//!
+//! DID documents found in the wild do not always have a well-formed
+//! `@context`: it may be missing (plain JSON representation), or list the
+//! DID context after the cryptographic suite contexts. [`normalize_context`]
+//! fixes these before expansion, and the DID Core context is always served
+//! from the bundled copy (see [`DidLoader`]). Other contexts, such as the
+//! suite contexts listed by `did:key` and `did:web` documents, or the
+//! [legacy DID contexts](LEGACY_CONTEXT_IRIS), are loaded with the given
+//! loader.
+//!
+//! ## Example
+//!
+//! ```
+//! # #[async_std::main]
+//! # async fn main() {
+//! use json_ld::{did, NoLoader};
+//!
+//! let json = json_syntax::json!({
+//!   "id": "did:web:example.com",
+//!   "verificationMethod": [{
+//!     "id": "did:web:example.com#key-1",
+//!     "type": "https://w3id.org/security#JsonWebKey2020",
+//!     "controller": "did:web:example.com"
+//!   }],
+//!   "authentication": ["did:web:example.com#key-1"]
+//! });
+//!
+//! let document = did::expand(json, &NoLoader).await.unwrap();
+//! assert_eq!(document.verification_methods().len(), 1);
+//! document.validate().unwrap();
+//! # }
+//! ```
+use crate::{
+	ExpandError, Id, IndexedObject, JsonLdProcessor, LoadError, Loader, LoadingResult, NoLoader,
+	Node, RemoteDocument, Value,
+};
+use iref::{Iri, IriBuf};
+use json_syntax::Parse;
+use rdf_types::BlankIdBuf;
+
+/// URL of the DID Core context.
+pub const CONTEXT_IRI: &str = "https://www.w3.org/ns/did/v1";
+
+/// Bundled DID Core context document.
+pub const CONTEXT: &str = include_str!("did-v1.jsonld");
+
+/// Legacy IRIs of the DID context, still used by some implementations.
+///
+/// Legacy contexts define terms that DID Core does not (such as `publicKey`
+/// and `publicKeyBase58`), so they are not replaced by the DID Core context:
+/// [`normalize_context`] keeps them, and [`DidLoader`] loads them with the
+/// inner loader.
+pub const LEGACY_CONTEXT_IRIS: &[&str] = &[
+	"https://w3id.org/did/v1",
+	"https://w3id.org/did/v0.11",
+	"https://www.w3.org/2019/did/v1",
+];
+
+const CONTROLLER: &str = "https://w3id.org/security#controller";
+const PUBLIC_KEY: &str = "https://w3id.org/security#publicKey";
+const VERIFICATION_METHOD: &str = "https://w3id.org/security#verificationMethod";
+const PUBLIC_KEY_MULTIBASE: &str = "https://w3id.org/security#publicKeyMultibase";
+const PUBLIC_KEY_JWK: &str = "https://w3id.org/security#publicKeyJwk";
+const ALSO_KNOWN_AS: &str = "https://www.w3.org/ns/activitystreams#alsoKnownAs";
+const SERVICE: &str = "https://www.w3.org/ns/did#service";
+const SERVICE_ENDPOINT: &str = "https://www.w3.org/ns/did#serviceEndpoint";
+
+/// Checks if the given IRI is the DID context IRI or one of its legacy IRIs.
+fn is_context_iri(iri: &str) -> bool {
+	iri == CONTEXT_IRI || LEGACY_CONTEXT_IRIS.contains(&iri)
+}
+
+/// Loader serving the bundled DID Core context.
+///
+/// The DID Core context IRI is served from the bundled [`CONTEXT`]. Other
+/// IRIs, including the [legacy DID context IRIs](LEGACY_CONTEXT_IRIS), are
+/// loaded with the inner loader.
+#[derive(Debug, Default, Clone)]
+pub struct DidLoader<L = NoLoader>(L);
+
+impl<L> DidLoader<L> {
+	/// Wraps the given loader.
+	pub fn new(loader: L) -> Self {
+		Self(loader)
+	}
+
+	/// Returns the inner loader.
+	pub fn into_inner(self) -> L {
+		self.0
+	}
+}
+
+impl<L: Loader> Loader for DidLoader<L> {
+	async fn load(&self, url: &Iri) -> LoadingResult<IriBuf> {
+		if url.as_str() == CONTEXT_IRI {
+			let (doc, _) = json_syntax::Value::parse_str(CONTEXT)
+				.map_err(|e| LoadError::new(url.to_owned(), e))?;
+			Ok(RemoteDocument::new(
+				Some(url.to_owned()),
+				Some("application/ld+json".parse().unwrap()),
+				doc,
+			))
+		} else {
+			self.0.load(url).await
+		}
+	}
+}
+
+/// Normalizes the `@context` entry of a DID document.
+///
+/// After normalization, the DID contexts come first in the `@context` of
+/// the document:
+///   - a missing `@context` is set to the DID context (the document is
+///     treated as a plain JSON DID document), and so is a `@context` without
+///     DID context;
+///   - the DID context IRIs, including the [legacy
+///     ones](LEGACY_CONTEXT_IRIS), are moved in first position (keeping
+///     their relative order), and duplicates are removed.
+///
+/// Values that are not JSON objects are left untouched.
+pub fn normalize_context(document: &mut json_syntax::Value) {
+	if let json_syntax::Value::Object(object) = document {
+		let context = object.remove("@context").next().map(|entry| entry.value);
+
+		let (mut contexts, mut others) = (Vec::new(), Vec::new());
+		let items = match context {
+			None | Some(json_syntax::Value::Null) => Vec::new(),
+			Some(json_syntax::Value::Array(array)) => array.into_iter().collect(),
+			Some(other) => vec![other],
+		};
+
+		for context in items {
+			match context.as_str() {
+				Some(iri) if is_context_iri(iri) => {
+					if !contexts.contains(&context) {
+						contexts.push(context)
+					}
+				}
+				_ => others.push(context),
+			}
+		}
+
+		if contexts.is_empty() {
+			contexts.push(json_syntax::Value::String(CONTEXT_IRI.into()))
+		}
+
+		contexts.extend(others);
+
+		let context = if contexts.len() == 1 {
+			contexts.pop().unwrap()
+		} else {
+			json_syntax::Value::Array(contexts.into_iter().collect())
+		};
+
+		object.insert_front("@context".into(), context);
+	}
+}
+
+/// Error raised by [`expand`].
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+	/// Document expansion failed.
+	#[error("Expansion failed: {0}")]
+	Expand(ExpandError),
+
+	/// The document does not contain exactly one top-level node object.
+	#[error("expected one top-level object, found {0}")]
+	NotOneObject(usize),
+}
+
+/// Expands a DID document.
+///
+/// The `@context` of the document is first normalized (see
+/// [`normalize_context`]), then the document is expanded using the given
+/// loader wrapped in a [`DidLoader`]. The expanded document must contain
+/// exactly one top-level node object.
+pub async fn expand(
+	mut json: json_syntax::Value,
+	loader: &impl Loader,
+) -> Result<DidDocument, Error> {
+	normalize_context(&mut json);
+
+	let document = RemoteDocument::new(None, None, json)
+		.expand(&DidLoader::new(loader))
+		.await
+		.map_err(Error::Expand)?;
+
+	let len = document.len();
+	let mut nodes = document
+		.into_iter()
+		.filter_map(|object| object.into_inner().into_node());
+	match (nodes.next(), len) {
+		(Some(node), 1) => Ok(DidDocument(node)),
+		_ => Err(Error::NotOneObject(len)),
+	}
+}
+
+/// Verification relationship.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VerificationRelationship {
+	/// `authentication`.
+	Authentication,
+
+	/// `assertionMethod`.
+	AssertionMethod,
+
+	/// `keyAgreement`.
+	KeyAgreement,
+
+	/// `capabilityInvocation`.
+	CapabilityInvocation,
+
+	/// `capabilityDelegation`.
+	CapabilityDelegation,
+}
+
+impl VerificationRelationship {
+	/// Every verification relationship.
+	pub const ALL: &'static [Self] = &[
+		Self::Authentication,
+		Self::AssertionMethod,
+		Self::KeyAgreement,
+		Self::CapabilityInvocation,
+		Self::CapabilityDelegation,
+	];
+
+	/// Returns the term defined for this relationship by the DID context.
+	pub fn name(&self) -> &'static str {
+		match self {
+			Self::Authentication => "authentication",
+			Self::AssertionMethod => "assertionMethod",
+			Self::KeyAgreement => "keyAgreement",
+			Self::CapabilityInvocation => "capabilityInvocation",
+			Self::CapabilityDelegation => "capabilityDelegation",
+		}
+	}
+
+	/// Returns the IRI of this relationship.
+	pub fn iri(&self) -> &'static Iri {
+		Iri::new(match self {
+			Self::Authentication => "https://w3id.org/security#authenticationMethod",
+			Self::AssertionMethod => "https://w3id.org/security#assertionMethod",
+			Self::KeyAgreement => "https://w3id.org/security#keyAgreementMethod",
+			Self::CapabilityInvocation => "https://w3id.org/security#capabilityInvocationMethod",
+			Self::CapabilityDelegation => "https://w3id.org/security#capabilityDelegationMethod",
+		})
+		.unwrap()
+	}
+}
+
+/// Returns the objects of `node` for the given property.
+fn get<'a>(
+	node: &'a Node,
+	property: &str,
+) -> impl Iterator<Item = &'a IndexedObject<IriBuf, BlankIdBuf>> {
+	node.get(&Iri::new(property).unwrap())
+}
+
+/// Returns the IRIs of the nodes referenced (or embedded) by `node` with the
+/// given property.
+fn get_iris<'a>(node: &'a Node, property: &str) -> impl Iterator<Item = &'a Iri> {
+	get(node, property)
+		.filter_map(|object| object.as_node())
+		.filter_map(|node| node.id.as_ref().and_then(Id::as_iri))
+		.map(IriBuf::as_iri)
+}
+
+/// Returns the nodes embedded by `node` with the given property.
+///
+/// Node references are not included.
+fn get_nodes<'a>(node: &'a Node, property: &str) -> impl Iterator<Item = &'a Node> {
+	get(node, property)
+		.filter_map(|object| object.as_node())
+		.filter(|node| !node.is_empty())
+}
+
+/// Returns the identifier of `node`, if it is an IRI.
+fn node_iri(node: &Node) -> Option<&Iri> {
+	node.id.as_ref().and_then(Id::as_iri).map(IriBuf::as_iri)
+}
+
+/// Returns the DID of the given DID URL (without path, query and fragment).
+fn did_of(url: &Iri) -> Option<&str> {
+	let s = url.as_str();
+	if !s.starts_with("did:") {
+		return None;
+	}
+
+	let end = s.find(['/', '?', '#']).unwrap_or(s.len());
+	Some(&s[..end])
+}
+
+/// Expanded DID document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DidDocument(Node);
+
+impl DidDocument {
+	/// Returns the underlying node object.
+	pub fn as_node(&self) -> &Node {
+		&self.0
+	}
+
+	/// Returns the underlying node object.
+	pub fn into_node(self) -> Node {
+		self.0
+	}
+
+	/// Returns the DID subject.
+	pub fn id(&self) -> Option<&Iri> {
+		node_iri(&self.0)
+	}
+
+	/// Returns the controllers of the DID document.
+	pub fn controllers(&self) -> impl Iterator<Item = &Iri> {
+		get_iris(&self.0, CONTROLLER)
+	}
+
+	/// Returns the `alsoKnownAs` IRIs of the DID subject.
+	pub fn also_known_as(&self) -> impl Iterator<Item = &Iri> {
+		get_iris(&self.0, ALSO_KNOWN_AS)
+	}
+
+	/// Returns every verification method defined by the document.
+	///
+	/// This includes the methods listed by `verificationMethod` (or the
+	/// legacy `publicKey`) and the methods embedded in verification
+	/// relationships.
+	pub fn verification_methods(&self) -> Vec<VerificationMethod<'_>> {
+		let mut result: Vec<_> = get_nodes(&self.0, VERIFICATION_METHOD)
+			.chain(get_nodes(&self.0, PUBLIC_KEY))
+			.map(VerificationMethod)
+			.collect();
+
+		for relationship in VerificationRelationship::ALL {
+			result.extend(get_nodes(&self.0, relationship.iri().as_str()).map(VerificationMethod))
+		}
+
+		result
+	}
+
+	/// Returns the verification method with the given identifier, if the
+	/// document defines it.
+	pub fn verification_method(&self, id: &Iri) -> Option<VerificationMethod<'_>> {
+		self.verification_methods()
+			.into_iter()
+			.find(|method| method.id() == Some(id))
+	}
+
+	/// Returns the identifiers of the verification methods of the given
+	/// verification relationship, whether they are embedded or referenced.
+	pub fn relationship(
+		&self,
+		relationship: VerificationRelationship,
+	) -> impl Iterator<Item = &Iri> {
+		get_iris(&self.0, relationship.iri().as_str())
+	}
+
+	/// Returns the services of the DID subject.
+	pub fn services(&self) -> Vec<Service<'_>> {
+		get_nodes(&self.0, SERVICE).map(Service).collect()
+	}
+
+	/// Validates the DID document.
+	///
+	/// Checks that:
+	///   - the DID subject is a DID;
+	///   - every verification method has a DID URL identifier, a type and a
+	///     controller;
+	///   - every verification method referenced by a verification
+	///     relationship is defined, if it belongs to the DID subject;
+	///   - every service has an identifier, a type and an endpoint.
+	///
+	/// Returns every problem found.
+	pub fn validate(&self) -> Result<(), Vec<Invalid>> {
+		let mut errors = Vec::new();
+
+		let did = match self.id() {
+			Some(id) if did_of(id) == Some(id.as_str()) => Some(id.as_str()),
+			_ => {
+				errors.push(Invalid::Subject);
+				None
+			}
+		};
+
+		let methods = self.verification_methods();
+		for method in &methods {
+			match method.id() {
+				Some(id) if did_of(id).is_some() => {
+					if method.as_node().types().is_empty() {
+						errors.push(Invalid::MissingType(id.to_owned()))
+					}
+
+					if method.controller().is_none() {
+						errors.push(Invalid::MissingController(id.to_owned()))
+					}
+				}
+				_ => errors.push(Invalid::VerificationMethodId),
+			}
+		}
+
+		for relationship in VerificationRelationship::ALL {
+			for id in self.relationship(*relationship) {
+				let defined = methods.iter().any(|method| method.id() == Some(id));
+				if !defined && did.is_some() && did_of(id) == did {
+					errors.push(Invalid::UndefinedVerificationMethod(
+						*relationship,
+						id.to_owned(),
+					))
+				}
+			}
+		}
+
+		for service in self.services() {
+			match service.id() {
+				Some(id) => {
+					if service.as_node().types().is_empty() {
+						errors.push(Invalid::MissingType(id.to_owned()))
+					}
+
+					if get(service.as_node(), SERVICE_ENDPOINT).next().is_none() {
+						errors.push(Invalid::MissingServiceEndpoint(id.to_owned()))
+					}
+				}
+				None => errors.push(Invalid::ServiceId),
+			}
+		}
+
+		if errors.is_empty() {
+			Ok(())
+		} else {
+			Err(errors)
+		}
+	}
+}
+
+/// DID document validation error.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum Invalid {
+	/// The DID subject is missing or is not a DID.
+	#[error("DID subject is missing or is not a DID")]
+	Subject,
+
+	/// A verification method is not identified by a DID URL.
+	#[error("verification method is not identified by a DID URL")]
+	VerificationMethodId,
+
+	/// A verification method or service has no type.
+	#[error("`{0}` has no type")]
+	MissingType(IriBuf),
+
+	/// A verification method has no controller.
+	#[error("verification method `{0}` has no controller")]
+	MissingController(IriBuf),
+
+	/// A verification relationship references a verification method of the
+	/// DID subject that the document does not define.
+	#[error("`{}` references undefined verification method `{1}`", .0.name())]
+	UndefinedVerificationMethod(VerificationRelationship, IriBuf),
+
+	/// A service has no identifier.
+	#[error("service has no identifier")]
+	ServiceId,
+
+	/// A service has no endpoint.
+	#[error("service `{0}` has no endpoint")]
+	MissingServiceEndpoint(IriBuf),
+}
+
+/// Verification method of a DID document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VerificationMethod<'a>(&'a Node);
+
+impl<'a> VerificationMethod<'a> {
+	/// Returns the underlying node object.
+	pub fn as_node(&self) -> &'a Node {
+		self.0
+	}
+
+	/// Returns the identifier of the verification method.
+	pub fn id(&self) -> Option<&'a Iri> {
+		node_iri(self.0)
+	}
+
+	/// Returns the IRIs of the verification method types.
+	///
+	/// Verification method types are defined by the cryptographic suite
+	/// contexts.
+	pub fn types(&self) -> impl Iterator<Item = &'a Iri> {
+		self.0
+			.types()
+			.iter()
+			.filter_map(Id::as_iri)
+			.map(IriBuf::as_iri)
+	}
+
+	/// Returns the controller of the verification method.
+	pub fn controller(&self) -> Option<&'a Iri> {
+		get_iris(self.0, CONTROLLER).next()
+	}
+
+	/// Returns the `publicKeyMultibase` of the verification method.
+	pub fn public_key_multibase(&self) -> Option<&'a str> {
+		get(self.0, PUBLIC_KEY_MULTIBASE)
+			.filter_map(|object| object.as_value())
+			.find_map(Value::as_str)
+	}
+
+	/// Returns the `publicKeyJwk` of the verification method.
+	pub fn public_key_jwk(&self) -> Option<&'a json_syntax::Value> {
+		get(self.0, PUBLIC_KEY_JWK).find_map(|object| match object.as_value() {
+			Some(Value::Json(json)) => Some(json),
+			_ => None,
+		})
+	}
+}
+
+/// Service of a DID document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Service<'a>(&'a Node);
+
+impl<'a> Service<'a> {
+	/// Returns the underlying node object.
+	pub fn as_node(&self) -> &'a Node {
+		self.0
+	}
+
+	/// Returns the identifier of the service.
+	pub fn id(&self) -> Option<&'a Iri> {
+		node_iri(self.0)
+	}
+
+	/// Returns the IRIs of the service types.
+	pub fn types(&self) -> impl Iterator<Item = &'a Iri> {
+		self.0
+			.types()
+			.iter()
+			.filter_map(Id::as_iri)
+			.map(IriBuf::as_iri)
+	}
+
+	/// Returns the service endpoint URLs.
+	///
+	/// Endpoints given as maps are available through the underlying node.
+	pub fn endpoints(&self) -> impl Iterator<Item = &'a Iri> {
+		get_iris(self.0, SERVICE_ENDPOINT)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn normalize_quirky_contexts() {
+		let mut missing = json_syntax::json!({ "id": "did:example:123" });
+		normalize_context(&mut missing);
+		assert_eq!(
+			missing,
+			json_syntax::json!({
+				"@context": "https://www.w3.org/ns/did/v1",
+				"id": "did:example:123"
+			})
+		);
+
+		let mut unordered = json_syntax::json!({
+			"@context": [
+				"https://w3id.org/security/suites/ed25519-2020/v1",
+				"https://w3id.org/did/v1",
+				"https://www.w3.org/ns/did/v1"
+			],
+			"id": "did:example:123"
+		});
+		normalize_context(&mut unordered);
+		assert_eq!(
+			unordered,
+			json_syntax::json!({
+				"@context": [
+					"https://w3id.org/did/v1",
+					"https://www.w3.org/ns/did/v1",
+					"https://w3id.org/security/suites/ed25519-2020/v1"
+				],
+				"id": "did:example:123"
+			})
+		);
+
+		let mut suite_only = json_syntax::json!({
+			"@context": "https://w3id.org/security/suites/ed25519-2020/v1",
+			"id": "did:example:123"
+		});
+		normalize_context(&mut suite_only);
+		assert_eq!(
+			suite_only,
+			json_syntax::json!({
+				"@context": [
+					"https://www.w3.org/ns/did/v1",
+					"https://w3id.org/security/suites/ed25519-2020/v1"
+				],
+				"id": "did:example:123"
+			})
+		);
+	}
+
+	#[async_std::test]
+	async fn validate() {
+		let json = json_syntax::json!({
+			"@context": "https://www.w3.org/ns/did/v1",
+			"id": "did:web:example.com",
+			"verificationMethod": [{
+				"id": "did:web:example.com#key-1",
+				"type": "https://w3id.org/security#JsonWebKey2020",
+				"controller": "did:web:example.com"
+			}],
+			"authentication": [
+				"did:web:example.com#key-1",
+				"did:web:example.com#key-2",
+				"did:web:other.com#key-1"
+			],
+			"service": [{
+				"id": "did:web:example.com#linked-domain",
+				"type": "https://example.com/LinkedDomains",
+				"serviceEndpoint": "https://example.com/"
+			}]
+		});
+
+		let document = expand(json, &NoLoader).await.unwrap();
+		assert_eq!(document.id().unwrap(), "did:web:example.com");
+
+		let key = document
+			.verification_method(Iri::new("did:web:example.com#key-1").unwrap())
+			.unwrap();
+		assert_eq!(key.controller().unwrap(), "did:web:example.com");
+
+		let services = document.services();
+		assert_eq!(
+			services[0].endpoints().collect::<Vec<_>>(),
+			["https://example.com/"]
+		);
+
+		assert_eq!(
+			document.validate(),
+			Err(vec![Invalid::UndefinedVerificationMethod(
+				VerificationRelationship::Authentication,
+				IriBuf::new("did:web:example.com#key-2".to_owned()).unwrap()
+			)])
+		)
+	}
+	#[async_std::test]
+	async fn legacy_context() {
+		use std::collections::HashMap;
+
+		const DID: &str = "did:key:z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK";
+		const KEY: &str = "did:key:z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK#z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK";
+
+		// Excerpt of the legacy DID context.
+		let legacy = IriBuf::new("https://w3id.org/did/v0.11".to_owned()).unwrap();
+		let mut loader = HashMap::new();
+		loader.insert(
+			legacy.clone(),
+			RemoteDocument::new(
+				Some(legacy),
+				None,
+				json_syntax::json!({
+					"@context": {
+						"id": "@id",
+						"type": "@type",
+						"sec": "https://w3id.org/security#",
+						"Ed25519VerificationKey2018": "sec:Ed25519VerificationKey2018",
+						"authentication": {
+							"@id": "sec:authenticationMethod",
+							"@type": "@id",
+							"@container": "@set"
+						},
+						"controller": { "@id": "sec:controller", "@type": "@id" },
+						"publicKey": { "@id": "sec:publicKey", "@type": "@id", "@container": "@set" },
+						"publicKeyBase58": "sec:publicKeyBase58"
+					}
+				}),
+			),
+		);
+
+		let json = json_syntax::json!({
+			"@context": "https://w3id.org/did/v0.11",
+			"id": DID,
+			"publicKey": [{
+				"id": KEY,
+				"type": "Ed25519VerificationKey2018",
+				"controller": DID,
+				"publicKeyBase58": "B12NYF8RrR3h41TDCTJojY59usg3mbtbjnFs7Eud1Y6u"
+			}],
+			"authentication": [KEY]
+		});
+
+		let document = expand(json, &loader).await.unwrap();
+		document.validate().unwrap();
+
+		let key = document
+			.verification_method(Iri::new(KEY).unwrap())
+			.unwrap();
+		assert_eq!(key.controller().unwrap(), DID);
+		assert_eq!(
+			get(key.as_node(), "https://w3id.org/security#publicKeyBase58")
+				.filter_map(|object| object.as_str())
+				.collect::<Vec<_>>(),
+			["B12NYF8RrR3h41TDCTJojY59usg3mbtbjnFs7Eud1Y6u"]
+		)
+	}
+}
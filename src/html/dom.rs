@@ -0,0 +1,352 @@
+//! Tolerant HTML tree builder.
+//!
+//! This is not a conforming HTML parser: it only recovers the element tree,
+//! attributes and text content needed by the structured data extractors,
+//! closing unterminated elements as best it can.
+
+/// Elements that never have content.
+const VOID_ELEMENTS: &[&str] = &[
+	"area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source",
+	"track", "wbr",
+];
+
+/// Elements whose content is raw text.
+const RAW_TEXT_ELEMENTS: &[&str] = &["script", "style", "textarea", "title"];
+
+/// Elements implicitly closed by the opening of an element with the same
+/// name.
+const SELF_CLOSING_SIBLINGS: &[&str] = &["li", "p", "dt", "dd", "option", "tr", "td", "th"];
+
+/// Maximum nesting depth of the element tree.
+///
+/// Elements opened deeper than that are closed immediately, and their content
+/// is added to their parent. This bounds the recursion of the extractors (and
+/// of the tree destructor) on untrusted input.
+const MAX_DEPTH: usize = 256;
+
+/// HTML element.
+pub(crate) struct Element {
+	/// Lowercase tag name.
+	pub name: String,
+
+	/// Attributes, with lowercase names.
+	pub attributes: Vec<(String, String)>,
+
+	/// Content.
+	pub children: Vec<Content>,
+}
+
+/// Element content.
+pub(crate) enum Content {
+	Element(Element),
+	Text(String),
+}
+
+impl Element {
+	fn new(name: String, attributes: Vec<(String, String)>) -> Self {
+		Self {
+			name,
+			attributes,
+			children: Vec::new(),
+		}
+	}
+
+	/// Returns the value of the given attribute, if any.
+	pub fn attribute(&self, name: &str) -> Option<&str> {
+		self.attributes
+			.iter()
+			.find(|(n, _)| n == name)
+			.map(|(_, value)| value.as_str())
+	}
+
+	/// Checks if the element has the given attribute.
+	pub fn has_attribute(&self, name: &str) -> bool {
+		self.attribute(name).is_some()
+	}
+
+	/// Returns the child elements.
+	pub fn elements(&self) -> impl Iterator<Item = &Element> {
+		self.children.iter().filter_map(|c| match c {
+			Content::Element(e) => Some(e),
+			Content::Text(_) => None,
+		})
+	}
+
+	/// Returns an iterator over the content of the element and of its
+	/// descendants, in tree order.
+	fn contents(&self) -> Contents<'_> {
+		Contents {
+			stack: vec![self.children.iter()],
+		}
+	}
+
+	/// Visits every descendant element, in tree order.
+	pub fn visit<'a>(&'a self, f: &mut impl FnMut(&'a Element)) {
+		for c in self.contents() {
+			if let Content::Element(e) = c {
+				f(e)
+			}
+		}
+	}
+
+	/// Finds the descendant element with the given `id` attribute.
+	pub fn find_by_id(&self, id: &str) -> Option<&Element> {
+		self.contents().find_map(|c| match c {
+			Content::Element(e) if e.attribute("id") == Some(id) => Some(e),
+			_ => None,
+		})
+	}
+
+	/// Returns the text content of the element, with whitespace collapsed.
+	pub fn text(&self) -> String {
+		let mut text = String::new();
+		for c in self.contents() {
+			if let Content::Text(t) = c {
+				text.push_str(t)
+			}
+		}
+
+		text.split_whitespace().collect::<Vec<_>>().join(" ")
+	}
+}
+
+/// Iterator over the content of an element and of its descendants, in tree
+/// order.
+struct Contents<'a> {
+	stack: Vec<std::slice::Iter<'a, Content>>,
+}
+
+impl<'a> Iterator for Contents<'a> {
+	type Item = &'a Content;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		loop {
+			let children = self.stack.last_mut()?;
+			match children.next() {
+				Some(c) => {
+					if let Content::Element(e) = c {
+						self.stack.push(e.children.iter())
+					}
+
+					break Some(c);
+				}
+				None => {
+					self.stack.pop();
+				}
+			}
+		}
+	}
+}
+
+/// Parses the given HTML, returning a `#document` root element.
+pub(crate) fn parse(html: &str) -> Element {
+	let mut stack = vec![Element::new("#document".to_owned(), Vec::new())];
+	let mut rest = html;
+
+	while !rest.is_empty() {
+		if let Some(after) = rest.strip_prefix("<!--") {
+			rest = after.find("-->").map_or("", |i| &after[i + 3..])
+		} else if rest.starts_with("<!") || rest.starts_with("<?") {
+			rest = rest.find('>').map_or("", |i| &rest[i + 1..])
+		} else if let Some(after) = rest.strip_prefix("</") {
+			let end = after.find('>').unwrap_or(after.len());
+			let name = after[..end].trim().to_ascii_lowercase();
+			rest = after.get(end + 1..).unwrap_or("");
+			close(&mut stack, &name)
+		} else if rest
+			.strip_prefix('<')
+			.is_some_and(|after| after.starts_with(|c: char| c.is_ascii_alphabetic()))
+		{
+			let (name, attributes, self_closing, after) = parse_tag(&rest[1..]);
+			rest = after;
+
+			if SELF_CLOSING_SIBLINGS.contains(&name.as_str())
+				&& stack.last().is_some_and(|e| e.name == name)
+			{
+				close(&mut stack, &name)
+			}
+
+			let mut element = Element::new(name, attributes);
+
+			if RAW_TEXT_ELEMENTS.contains(&element.name.as_str()) && !self_closing {
+				let end_tag = format!("</{}", element.name);
+				let end = rest
+					.to_ascii_lowercase()
+					.find(&end_tag)
+					.unwrap_or(rest.len());
+				let text = if element.name == "script" || element.name == "style" {
+					rest[..end].to_owned()
+				} else {
+					decode(&rest[..end])
+				};
+				element.children.push(Content::Text(text));
+				rest = &rest[end..];
+				rest = rest.find('>').map_or("", |i| &rest[i + 1..]);
+				append(&mut stack, element)
+			} else if self_closing
+				|| VOID_ELEMENTS.contains(&element.name.as_str())
+				|| stack.len() > MAX_DEPTH
+			{
+				append(&mut stack, element)
+			} else {
+				stack.push(element)
+			}
+		} else {
+			let end = rest
+				.char_indices()
+				.skip(1)
+				.find(|(_, c)| *c == '<')
+				.map_or(rest.len(), |(i, _)| i);
+			let text = decode(&rest[..end]);
+			stack.last_mut().unwrap().children.push(Content::Text(text));
+			rest = &rest[end..]
+		}
+	}
+
+	while stack.len() > 1 {
+		let element = stack.pop().unwrap();
+		append(&mut stack, element)
+	}
+
+	stack.pop().unwrap()
+}
+
+/// Appends the given element to the current element.
+fn append(stack: &mut [Element], element: Element) {
+	stack
+		.last_mut()
+		.unwrap()
+		.children
+		.push(Content::Element(element))
+}
+
+/// Closes the innermost open element with the given name, and every element
+/// opened after it.
+///
+/// End tags without matching open element are ignored.
+fn close(stack: &mut Vec<Element>, name: &str) {
+	if let Some(i) = stack.iter().rposition(|e| e.name == name) {
+		if i > 0 {
+			while stack.len() > i {
+				let element = stack.pop().unwrap();
+				append(stack, element)
+			}
+		}
+	}
+}
+
+/// Parses a start tag, after its `<`.
+///
+/// Returns the tag name, its attributes, whether or not it is self-closing
+/// and the remaining input.
+fn parse_tag(input: &str) -> (String, Vec<(String, String)>, bool, &str) {
+	let is_name_end = |c: char| c.is_whitespace() || c == '/' || c == '>';
+	let end = input.find(is_name_end).unwrap_or(input.len());
+	let name = input[..end].to_ascii_lowercase();
+	let mut rest = &input[end..];
+	let mut attributes = Vec::new();
+
+	loop {
+		rest = rest.trim_start();
+
+		if let Some(after) = rest.strip_prefix("/>") {
+			break (name, attributes, true, after);
+		}
+
+		if let Some(after) = rest.strip_prefix('>') {
+			break (name, attributes, false, after);
+		}
+
+		if rest.is_empty() {
+			break (name, attributes, false, rest);
+		}
+
+		let end = rest
+			.find(|c: char| is_name_end(c) || c == '=')
+			.unwrap_or(rest.len());
+
+		if end == 0 {
+			// Stray `/` or `=`.
+			rest = &rest[1..];
+			continue;
+		}
+
+		let attribute = rest[..end].to_ascii_lowercase();
+		rest = rest[end..].trim_start();
+
+		let value = match rest.strip_prefix('=') {
+			Some(after) => {
+				let after = after.trim_start();
+				match after.chars().next() {
+					Some(quote @ ('"' | '\'')) => {
+						let after = &after[1..];
+						let end = after.find(quote).unwrap_or(after.len());
+						rest = after.get(end + 1..).unwrap_or("");
+						decode(&after[..end])
+					}
+					_ => {
+						let end = after
+							.find(|c: char| c.is_whitespace() || c == '>')
+							.unwrap_or(after.len());
+						rest = &after[end..];
+						decode(&after[..end])
+					}
+				}
+			}
+			None => String::new(),
+		};
+
+		attributes.push((attribute, value))
+	}
+}
+
+/// Decodes the character references of the given text.
+///
+/// Only numeric references and the most common named references are
+/// supported. Others are left as is.
+fn decode(text: &str) -> String {
+	let mut result = String::with_capacity(text.len());
+	let mut rest = text;
+
+	while let Some(i) = rest.find('&') {
+		result.push_str(&rest[..i]);
+		rest = &rest[i..];
+
+		let reference = rest[1..]
+			.find(';')
+			.and_then(|end| character_reference(&rest[1..1 + end]).map(|c| (c, end + 2)));
+
+		match reference {
+			Some((c, len)) => {
+				result.push(c);
+				rest = &rest[len..]
+			}
+			None => {
+				result.push('&');
+				rest = &rest[1..]
+			}
+		}
+	}
+
+	result.push_str(rest);
+	result
+}
+
+fn character_reference(name: &str) -> Option<char> {
+	match name {
+		"amp" => Some('&'),
+		"lt" => Some('<'),
+		"gt" => Some('>'),
+		"quot" => Some('"'),
+		"apos" => Some('\''),
+		"nbsp" => Some('\u{a0}'),
+		_ => {
+			let number = name.strip_prefix('#')?;
+			let code = match number.strip_prefix(|c| c == 'x' || c == 'X') {
+				Some(hex) => u32::from_str_radix(hex, 16).ok(),
+				None => number.parse().ok(),
+			};
+			code.and_then(char::from_u32)
+		}
+	}
+}
@@ -0,0 +1,182 @@
+//! Microdata extraction.
+//!
+//! Follows the [Microdata to RDF](https://www.w3.org/TR/microdata-rdf/)
+//! mapping, with the vocabulary of an item derived from its first type.
+use super::{dom::Element, fresh, resolve, string, url_value};
+use crate::{ExpandedDocument, Id, Indexed, IndexedObject, Node, Object};
+use iref::{Iri, IriBuf};
+use rdf_types::{BlankIdBuf, Generator};
+
+/// Extracts the top-level items of the document.
+pub(super) fn extract<G: Generator>(
+	root: &Element,
+	base: Option<&Iri>,
+	generator: &mut G,
+	document: &mut ExpandedDocument,
+) {
+	let mut extractor = Extractor {
+		root,
+		base,
+		generator,
+		items: Vec::new(),
+	};
+
+	let mut top_level = Vec::new();
+	root.visit(&mut |e| {
+		if e.has_attribute("itemscope") && !e.has_attribute("itemprop") {
+			top_level.push(e)
+		}
+	});
+
+	for e in top_level {
+		let node = extractor.item(e, None);
+		document.insert(Indexed::new(Object::node(node), None));
+	}
+}
+
+struct Extractor<'a, G> {
+	root: &'a Element,
+	base: Option<&'a Iri>,
+	generator: &'a mut G,
+
+	/// Items being extracted, used to break `itemref` cycles.
+	items: Vec<&'a Element>,
+}
+
+impl<'a, G: Generator> Extractor<'a, G> {
+	/// Extracts the item defined by the given `itemscope` element.
+	fn item(&mut self, e: &'a Element, vocabulary: Option<&str>) -> Node {
+		let id = match e.attribute("itemid").and_then(|id| resolve(id, self.base)) {
+			Some(iri) => Id::iri(iri),
+			None => fresh(self.generator),
+		};
+
+		let mut node = Node::with_id(id);
+
+		let types: Vec<IriBuf> = e
+			.attribute("itemtype")
+			.unwrap_or_default()
+			.split_whitespace()
+			.filter_map(|ty| Iri::new(ty).ok().map(ToOwned::to_owned))
+			.collect();
+
+		let vocabulary = match types.first() {
+			Some(ty) => Some(vocabulary_of(ty).to_owned()),
+			None => vocabulary.map(ToOwned::to_owned),
+		};
+
+		if !types.is_empty() {
+			node.types_mut_or_default()
+				.extend(types.into_iter().map(Id::iri))
+		}
+
+		self.items.push(e);
+
+		for property in self.properties(e) {
+			let names = property.attribute("itemprop").unwrap_or_default();
+			for name in names.split_whitespace() {
+				let iri = match Iri::new(name) {
+					Ok(iri) => iri.to_owned(),
+					Err(_) => match &vocabulary {
+						Some(vocabulary) => match IriBuf::new(format!("{vocabulary}{name}")) {
+							Ok(iri) => iri,
+							Err(_) => continue,
+						},
+						None => continue,
+					},
+				};
+
+				if let Some(value) = self.value(property, vocabulary.as_deref()) {
+					node.insert(Id::iri(iri), value)
+				}
+			}
+		}
+
+		self.items.pop();
+		node
+	}
+
+	/// Returns the property elements of the given item, in tree order,
+	/// followed by the ones found through `itemref`.
+	fn properties(&self, e: &'a Element) -> Vec<&'a Element> {
+		fn crawl<'a>(e: &'a Element, result: &mut Vec<&'a Element>) {
+			for child in e.elements() {
+				if child.has_attribute("itemprop") {
+					result.push(child)
+				}
+
+				if !child.has_attribute("itemscope") {
+					crawl(child, result)
+				}
+			}
+		}
+
+		let mut result = Vec::new();
+		crawl(e, &mut result);
+
+		let refs = e.attribute("itemref").unwrap_or_default();
+		for id in refs.split_whitespace() {
+			if let Some(referenced) = self.root.find_by_id(id) {
+				if referenced.has_attribute("itemprop") {
+					result.push(referenced)
+				}
+
+				if !referenced.has_attribute("itemscope") {
+					crawl(referenced, &mut result)
+				}
+			}
+		}
+
+		result
+	}
+
+	/// Returns the value of the given property element.
+	///
+	/// Returns `None` for nested items already being extracted.
+	fn value(
+		&mut self,
+		e: &'a Element,
+		vocabulary: Option<&str>,
+	) -> Option<IndexedObject<IriBuf, BlankIdBuf>> {
+		if e.has_attribute("itemscope") {
+			if self.items.iter().any(|item| std::ptr::eq(*item, e)) {
+				return None;
+			}
+
+			let node = self.item(e, vocabulary);
+			return Some(Indexed::new(Object::node(node), None));
+		}
+
+		let attribute = |name| e.attribute(name).unwrap_or_default();
+
+		let value = match e.name.as_str() {
+			"meta" => string(attribute("content").to_owned()),
+			"audio" | "embed" | "iframe" | "img" | "source" | "track" | "video" => {
+				url_value(attribute("src"), self.base)
+			}
+			"a" | "area" | "link" => url_value(attribute("href"), self.base),
+			"object" => url_value(attribute("data"), self.base),
+			"data" | "meter" => string(attribute("value").to_owned()),
+			"time" => match e.attribute("datetime") {
+				Some(datetime) => string(datetime.to_owned()),
+				None => string(e.text()),
+			},
+			_ => string(e.text()),
+		};
+
+		Some(value)
+	}
+}
+
+/// Returns the vocabulary of the given item type: the type IRI up to its
+/// fragment, or its last path segment.
+fn vocabulary_of(ty: &Iri) -> &str {
+	let ty = ty.as_str();
+	match ty.find('#') {
+		Some(i) => &ty[..=i],
+		None => match ty.rfind('/') {
+			Some(i) => &ty[..=i],
+			None => ty,
+		},
+	}
+}
@@ -0,0 +1,283 @@
+//! Microdata and RDFa extraction.
+//!
+//! Converts the [HTML Microdata](https://html.spec.whatwg.org/multipage/microdata.html)
+//! items and [RDFa Lite](https://www.w3.org/TR/rdfa-lite/) statements
+//! embedded in an HTML page into an [`ExpandedDocument`], so that structured
+//! data (typically schema.org) can be processed the same way whatever its
+//! embedding syntax:
+//!
+//! ```
+//! use json_ld::{html, rdf_types::generator};
+//! use static_iref::iri;
+//!
+//! let page = r#"<div itemscope itemtype="https://schema.org/Person">
+//!   <span itemprop="name">Alice</span>
+//! </div>"#;
+//!
+//! let document = html::extract(
+//!   page,
+//!   Some(iri!("https://example.com/")),
+//!   &mut generator::Blank::new(),
+//! );
+//!
+//! let node = document.iter().next().unwrap().as_node().unwrap();
+//! assert_eq!(
+//!   node.types()[0].as_iri().unwrap().as_str(),
+//!   "https://schema.org/Person"
+//! );
+//! ```
+//!
+//! Microdata items are extracted as nested node objects, while RDFa
+//! statements are extracted as one node object per subject.
+//!
+//! HTML is parsed by a small tolerant tree builder that only recovers what
+//! the extractors need. It is not a conforming HTML parser.
+use crate::{object::Literal, ExpandedDocument, Id, Indexed, IndexedObject, Node, Object, Value};
+use iref::{Iri, IriBuf, IriRef};
+use rdf_types::{BlankIdBuf, Generator};
+
+mod dom;
+mod microdata;
+mod rdfa;
+
+use dom::Element;
+
+/// Extracts both the Microdata items and RDFa statements of the given HTML
+/// page.
+///
+/// The `base` IRI is the URL of the page, used to resolve relative URLs. It
+/// is overridden by the `<base>` element of the page, if any.
+pub fn extract<G: Generator>(
+	html: &str,
+	base: Option<&Iri>,
+	generator: &mut G,
+) -> ExpandedDocument {
+	let root = dom::parse(html);
+	let base = document_base(&root, base);
+	let mut document = ExpandedDocument::new();
+	microdata::extract(&root, base.as_deref(), generator, &mut document);
+	rdfa::extract(&root, base.as_deref(), generator, &mut document);
+	document
+}
+
+/// Extracts the Microdata items of the given HTML page.
+///
+/// See [`extract`] for the meaning of `base`.
+pub fn extract_microdata<G: Generator>(
+	html: &str,
+	base: Option<&Iri>,
+	generator: &mut G,
+) -> ExpandedDocument {
+	let root = dom::parse(html);
+	let base = document_base(&root, base);
+	let mut document = ExpandedDocument::new();
+	microdata::extract(&root, base.as_deref(), generator, &mut document);
+	document
+}
+
+/// Extracts the RDFa Lite statements of the given HTML page.
+///
+/// See [`extract`] for the meaning of `base`.
+pub fn extract_rdfa<G: Generator>(
+	html: &str,
+	base: Option<&Iri>,
+	generator: &mut G,
+) -> ExpandedDocument {
+	let root = dom::parse(html);
+	let base = document_base(&root, base);
+	let mut document = ExpandedDocument::new();
+	rdfa::extract(&root, base.as_deref(), generator, &mut document);
+	document
+}
+
+/// Returns the base IRI of the document, taking its `<base>` element into
+/// account.
+fn document_base(root: &Element, base: Option<&Iri>) -> Option<IriBuf> {
+	let mut href = None;
+	root.visit(&mut |e| {
+		if href.is_none() && e.name == "base" {
+			href = e.attribute("href")
+		}
+	});
+
+	match href {
+		Some(href) => resolve(href, base).or_else(|| base.map(ToOwned::to_owned)),
+		None => base.map(ToOwned::to_owned),
+	}
+}
+
+/// Resolves the given URL against the base IRI.
+///
+/// Returns `None` if the URL is invalid, or is relative without base IRI.
+fn resolve(url: &str, base: Option<&Iri>) -> Option<IriBuf> {
+	let iri_ref = IriRef::new(url.trim()).ok()?;
+	match base {
+		Some(base) => Some(iri_ref.resolved(base)),
+		None => iri_ref.as_iri().map(ToOwned::to_owned),
+	}
+}
+
+/// Generates a fresh blank node identifier.
+fn fresh<G: Generator>(generator: &mut G) -> Id {
+	generator.next(&mut ()).into()
+}
+
+/// Creates a string value object.
+fn string(value: String) -> IndexedObject<IriBuf, BlankIdBuf> {
+	Indexed::new(
+		Object::Value(Value::Literal(Literal::String(value.into()), None)),
+		None,
+	)
+}
+
+/// Creates a node reference.
+fn reference(id: Id) -> IndexedObject<IriBuf, BlankIdBuf> {
+	Indexed::new(Object::node(Node::with_id(id)), None)
+}
+
+/// Creates an IRI node reference if the URL is valid, or a string value
+/// otherwise.
+fn url_value(url: &str, base: Option<&Iri>) -> IndexedObject<IriBuf, BlankIdBuf> {
+	match resolve(url, base) {
+		Some(iri) => reference(Id::iri(iri)),
+		None => string(url.to_owned()),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use rdf_types::generator;
+
+	fn id(iri: &str) -> Id {
+		Id::iri(IriBuf::new(iri.to_owned()).unwrap())
+	}
+
+	#[test]
+	fn microdata() {
+		let page = r#"<!DOCTYPE html>
+			<base href="/articles/">
+			<article itemscope itemtype="https://schema.org/Article" itemid="a1" itemref="extra">
+				<h1 itemprop="headline">Hello &amp; welcome</h1>
+				<a itemprop="url" href="a1.html">link</a>
+				<div itemprop="author" itemscope itemtype="https://schema.org/Person">
+					<span itemprop="name">Alice</span>
+				</div>
+				<meta itemprop="inLanguage" content="en">
+			</article>
+			<p id="extra"><time itemprop="datePublished" datetime="2024-01-01">Jan 1st</time></p>"#;
+
+		let document = extract_microdata(
+			page,
+			Some(static_iref::iri!("https://example.com/")),
+			&mut generator::Blank::new(),
+		);
+
+		assert_eq!(document.len(), 1);
+		let article = document.iter().next().unwrap().as_node().unwrap();
+		assert_eq!(article.id, Some(id("https://example.com/articles/a1")));
+		assert_eq!(article.types(), [id("https://schema.org/Article")]);
+
+		let property = |node: &Node, name: &str| {
+			let values: Vec<_> = node
+				.get(&id(&format!("https://schema.org/{name}")))
+				.cloned()
+				.collect();
+			assert_eq!(values.len(), 1);
+			values.into_iter().next().unwrap()
+		};
+
+		assert_eq!(
+			property(article, "headline").as_str(),
+			Some("Hello & welcome")
+		);
+		assert_eq!(
+			property(article, "url").as_node().unwrap().id,
+			Some(id("https://example.com/articles/a1.html"))
+		);
+		assert_eq!(property(article, "inLanguage").as_str(), Some("en"));
+		assert_eq!(
+			property(article, "datePublished").as_str(),
+			Some("2024-01-01")
+		);
+
+		let author = property(article, "author");
+		let author = author.as_node().unwrap();
+		assert_eq!(author.types(), [id("https://schema.org/Person")]);
+		assert_eq!(property(author, "name").as_str(), Some("Alice"))
+	}
+
+	#[test]
+	fn rdfa() {
+		let page = r##"<body vocab="http://schema.org/" prefix="ex: http://example.org/ns#">
+			<div typeof="Person" resource="#alice">
+				<span property="name">Alice</span>
+				<a property="knows" typeof="Person" href="#bob"><span property="name">Bob</span></a>
+				<span property="ex:nickname">Al</span>
+			</div>
+		</body>"##;
+
+		let document = extract_rdfa(
+			page,
+			Some(static_iref::iri!("https://example.com/page")),
+			&mut generator::Blank::new(),
+		);
+
+		assert_eq!(document.len(), 2);
+		let mut nodes = document.iter().map(|o| o.as_node().unwrap());
+		let alice = nodes.next().unwrap();
+		let bob = nodes.next().unwrap();
+
+		let property = |node: &Node, iri: &str| node.get(&id(iri)).next().unwrap().clone();
+
+		assert_eq!(alice.id, Some(id("https://example.com/page#alice")));
+		assert_eq!(alice.types(), [id("http://schema.org/Person")]);
+		assert_eq!(
+			property(alice, "http://schema.org/name").as_str(),
+			Some("Alice")
+		);
+		assert_eq!(
+			property(alice, "http://example.org/ns#nickname").as_str(),
+			Some("Al")
+		);
+		assert_eq!(
+			property(alice, "http://schema.org/knows")
+				.as_node()
+				.unwrap()
+				.id,
+			Some(id("https://example.com/page#bob"))
+		);
+
+		assert_eq!(bob.id, Some(id("https://example.com/page#bob")));
+		assert_eq!(
+			property(bob, "http://schema.org/name").as_str(),
+			Some("Bob")
+		)
+	}
+
+	#[test]
+	fn deep_nesting() {
+		let page = format!(
+			r#"<div itemscope itemtype="https://schema.org/Person" itemref="name"></div>{}<meta id="name" itemprop="name" content="Alice">"#,
+			"<div><span>".repeat(100_000)
+		);
+
+		let document = extract(
+			&page,
+			Some(static_iref::iri!("https://example.com/")),
+			&mut generator::Blank::new(),
+		);
+
+		assert_eq!(document.len(), 1);
+		let person = document.iter().next().unwrap().as_node().unwrap();
+		assert_eq!(person.types(), [id("https://schema.org/Person")]);
+		assert_eq!(
+			person
+				.get(&id("https://schema.org/name"))
+				.next()
+				.unwrap()
+				.as_str(),
+			Some("Alice")
+		)
+	}
+}
@@ -0,0 +1,237 @@
+//! RDFa Lite extraction.
+//!
+//! Only the five RDFa Lite attributes are supported: `vocab`, `typeof`,
+//! `property`, `resource` and `prefix`. Like in RDFa, `href` and `src` are
+//! used as resources when no `resource` is given.
+use super::{dom::Element, fresh, reference, resolve, string};
+use crate::{ExpandedDocument, Id, Indexed, IndexedObject, Node, Object};
+use iref::{Iri, IriBuf};
+use rdf_types::{BlankIdBuf, Generator};
+use std::collections::HashMap;
+
+/// Prefixes predefined by the RDFa initial context.
+const INITIAL_PREFIXES: &[(&str, &str)] = &[
+	("dc", "http://purl.org/dc/terms/"),
+	("foaf", "http://xmlns.com/foaf/0.1/"),
+	("og", "http://ogp.me/ns#"),
+	("owl", "http://www.w3.org/2002/07/owl#"),
+	("rdf", "http://www.w3.org/1999/02/22-rdf-syntax-ns#"),
+	("rdfs", "http://www.w3.org/2000/01/rdf-schema#"),
+	("schema", "http://schema.org/"),
+	("xsd", "http://www.w3.org/2001/XMLSchema#"),
+];
+
+/// Extracts the statements of the document.
+///
+/// Statements without explicit subject are about the document itself,
+/// identified by the base IRI.
+pub(super) fn extract<G: Generator>(
+	root: &Element,
+	base: Option<&Iri>,
+	generator: &mut G,
+	document: &mut ExpandedDocument,
+) {
+	let subject = match base {
+		Some(base) => Id::iri(base.to_owned()),
+		None => fresh(generator),
+	};
+
+	let mut extractor = Extractor {
+		base,
+		generator,
+		nodes: Vec::new(),
+		index: HashMap::new(),
+	};
+
+	let context = Context {
+		subject,
+		vocabulary: None,
+		prefixes: Vec::new(),
+	};
+
+	extractor.element(root, &context);
+
+	for node in extractor.nodes {
+		if !node.is_empty() {
+			document.insert(Indexed::new(Object::node(node), None));
+		}
+	}
+}
+
+/// Evaluation context, inherited by descendant elements.
+#[derive(Clone)]
+struct Context {
+	subject: Id,
+	vocabulary: Option<String>,
+	prefixes: Vec<(String, String)>,
+}
+
+impl Context {
+	/// Expands the given CURIE, if its prefix is defined.
+	fn curie(&self, curie: &str) -> Option<IriBuf> {
+		let (prefix, suffix) = curie.split_once(':')?;
+		let namespace = self
+			.prefixes
+			.iter()
+			.rev()
+			.find(|(p, _)| p == prefix)
+			.map(|(_, namespace)| namespace.as_str())
+			.or_else(|| {
+				INITIAL_PREFIXES
+					.iter()
+					.find(|(p, _)| *p == prefix)
+					.map(|(_, namespace)| *namespace)
+			})?;
+
+		IriBuf::new(format!("{namespace}{suffix}")).ok()
+	}
+
+	/// Expands the given term, CURIE or IRI.
+	fn expand(&self, term: &str) -> Option<IriBuf> {
+		if term.contains(':') {
+			self.curie(term)
+				.or_else(|| Iri::new(term).ok().map(ToOwned::to_owned))
+		} else {
+			let vocabulary = self.vocabulary.as_ref()?;
+			IriBuf::new(format!("{vocabulary}{term}")).ok()
+		}
+	}
+}
+
+struct Extractor<'a, G> {
+	base: Option<&'a Iri>,
+	generator: &'a mut G,
+
+	/// Subjects, in order of appearance.
+	nodes: Vec<Node>,
+
+	/// Position of each subject in `nodes`.
+	index: HashMap<Id, usize>,
+}
+
+impl<'a, G: Generator> Extractor<'a, G> {
+	/// Returns the node of the given subject.
+	fn node(&mut self, id: &Id) -> &mut Node {
+		let nodes = &mut self.nodes;
+		let i = *self.index.entry(id.clone()).or_insert_with(|| {
+			nodes.push(Node::with_id(id.clone()));
+			nodes.len() - 1
+		});
+
+		&mut self.nodes[i]
+	}
+
+	/// Returns the resource of the given element, if any.
+	fn resource(&self, e: &Element, context: &Context) -> Option<Id> {
+		if let Some(resource) = e.attribute("resource") {
+			if let Some(label) = resource.strip_prefix("_:") {
+				return Some(Id::blank(BlankIdBuf::from_suffix(label).ok()?));
+			}
+
+			return context
+				.curie(resource)
+				.or_else(|| resolve(resource, self.base))
+				.map(Id::iri);
+		}
+
+		e.attribute("href")
+			.or_else(|| e.attribute("src"))
+			.and_then(|url| resolve(url, self.base))
+			.map(Id::iri)
+	}
+
+	fn element(&mut self, e: &Element, parent: &Context) {
+		let mut context = parent.clone();
+
+		if let Some(vocabulary) = e.attribute("vocab") {
+			let vocabulary = vocabulary.trim();
+			context.vocabulary = if vocabulary.is_empty() {
+				None
+			} else {
+				Some(vocabulary.to_owned())
+			}
+		}
+
+		if let Some(prefixes) = e.attribute("prefix") {
+			let mut tokens = prefixes.split_whitespace();
+			while let Some(prefix) = tokens.next() {
+				if let (Some(prefix), Some(namespace)) = (prefix.strip_suffix(':'), tokens.next()) {
+					context
+						.prefixes
+						.push((prefix.to_owned(), namespace.to_owned()))
+				}
+			}
+		}
+
+		let properties: Vec<IriBuf> = e
+			.attribute("property")
+			.unwrap_or_default()
+			.split_whitespace()
+			.filter_map(|p| context.expand(p))
+			.collect();
+
+		match e.attribute("typeof") {
+			Some(types) => {
+				let subject = match self.resource(e, &context) {
+					Some(id) => id,
+					None => fresh(self.generator),
+				};
+
+				let types: Vec<Id> = types
+					.split_whitespace()
+					.filter_map(|ty| context.expand(ty))
+					.map(Id::iri)
+					.collect();
+
+				let node = self.node(&subject);
+				for ty in types {
+					let node_types = node.types_mut_or_default();
+					if !node_types.contains(&ty) {
+						node_types.push(ty)
+					}
+				}
+
+				self.add(&context.subject, &properties, || reference(subject.clone()));
+
+				context.subject = subject
+			}
+			None => {
+				if !properties.is_empty() {
+					let value = match self.resource(e, &context) {
+						Some(id) if !e.has_attribute("content") => reference(id),
+						_ => match e.attribute("content") {
+							Some(content) => string(content.to_owned()),
+							None => match e.attribute("datetime") {
+								Some(datetime) if e.name == "time" => string(datetime.to_owned()),
+								_ => string(e.text()),
+							},
+						},
+					};
+
+					self.add(&context.subject, &properties, || value.clone())
+				} else if let Some(subject) = self.resource(e, &context) {
+					context.subject = subject
+				}
+			}
+		}
+
+		for child in e.elements() {
+			self.element(child, &context)
+		}
+	}
+
+	/// Adds the given value to the properties of the subject.
+	fn add(
+		&mut self,
+		subject: &Id,
+		properties: &[IriBuf],
+		value: impl Fn() -> IndexedObject<IriBuf, BlankIdBuf>,
+	) {
+		if !properties.is_empty() {
+			let node = self.node(subject);
+			for property in properties {
+				node.insert(Id::iri(property.clone()), value())
+			}
+		}
+	}
+}
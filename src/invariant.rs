@@ -0,0 +1,233 @@
+//! Invariants of the JSON-LD algorithms, for property testing and fuzzing.
+//!
+//! Each check takes a random [`Document`] and returns a [`Violation`] if the
+//! invariant does not hold:
+//!   - expansion never panics;
+//!   - expanding a compacted document gives back the original expanded
+//!     document;
+//!   - flattening produces a document in expanded form, where every node is
+//!     identified and nested nodes are only references.
+//!
+//! Processing errors (invalid context, invalid document, etc.) do not
+//! violate any invariant: random documents are not expected to be valid.
+//! Panics are not caught, and are violations on their own.
+//!
+//! ## Example
+//!
+//! ```
+//! use arbitrary::{Arbitrary, Unstructured};
+//! use json_ld::{arbitrary::Document, invariant};
+//!
+//! let mut u = Unstructured::new(&[4, 8, 15, 16, 23, 42]);
+//! let document = Document::arbitrary(&mut u).unwrap();
+//! invariant::check(&document).unwrap()
+//! ```
+use crate::{
+	arbitrary::Document, object::InvalidExpandedJson, syntax::IntoJsonWithContext, BlankIdBuf,
+	ExpandError, ExpandedDocument, IndexedObject, IriBuf, JsonLdProcessor, NoLoader, Node, Object,
+	RemoteContextReference, RemoteDocument, TryFromJson,
+};
+use futures::executor::block_on;
+use json_syntax::Print;
+use rdf_types::generator;
+
+/// Base IRI of the checked documents.
+pub const BASE_IRI: &str = "https://example.org/document.jsonld";
+
+/// Invariant violation.
+#[derive(Debug, thiserror::Error)]
+pub enum Violation {
+	/// The expanded document could not be expanded again.
+	#[error("expanded document expansion failed: {0}")]
+	ExpandedExpansion(ExpandError),
+
+	/// The compacted document could not be expanded.
+	#[error("compacted document expansion failed: {0}")]
+	CompactedExpansion(ExpandError),
+
+	/// Expanding the compacted document does not give back the expanded
+	/// document.
+	#[error("unstable compaction\nexpanded: {expanded}\ncompacted: {compacted}\nre-expanded: {re_expanded}")]
+	UnstableCompaction {
+		expanded: String,
+		compacted: String,
+		re_expanded: String,
+	},
+
+	/// The flattened document is not in expanded form.
+	#[error("flattened document is not in expanded form: {0:?}")]
+	InvalidFlattenedJson(InvalidExpandedJson),
+
+	/// The flattened document has a top-level object that is not a node
+	/// object.
+	#[error("flattened document contains a top-level non-node object")]
+	FlattenedNonNode,
+
+	/// The flattened document has a node without identifier.
+	#[error("flattened document contains an anonymous node")]
+	FlattenedAnonymousNode,
+
+	/// The flattened document has a nested node that is not a reference.
+	#[error("flattened document contains a nested node")]
+	FlattenedNestedNode,
+}
+
+fn remote_document(json: json_syntax::Value) -> RemoteDocument {
+	RemoteDocument::new(Some(IriBuf::new(BASE_IRI.to_owned()).unwrap()), None, json)
+}
+
+fn to_string(document: ExpandedDocument) -> String {
+	document.into_json_with(&()).compact_print().to_string()
+}
+
+/// Expands the given document.
+///
+/// Returns `None` if the expansion failed. Never panics.
+pub fn expand(document: &Document) -> Option<ExpandedDocument> {
+	block_on(remote_document(document.to_json()).expand(&NoLoader)).ok()
+}
+
+/// Checks that expanding the compacted document gives back the expanded
+/// document.
+///
+/// The document is compacted using its own context (see
+/// [`Document::compaction_context`]). Documents whose expansion or
+/// compaction fails are ignored.
+///
+/// The expanded document is first converted back to JSON and expanded
+/// again, which must succeed. Documents made of a single anonymous graph object are
+/// ignored: such a graph is compacted into the top-level `@graph` entry, that
+/// is read back as the default graph.
+pub fn check_compaction(document: &Document) -> Result<(), Violation> {
+	let expanded = match expand(document) {
+		Some(expanded) => expanded,
+		None => return Ok(()),
+	};
+
+	if expanded.len() == 1
+		&& expanded
+			.iter()
+			.all(|object| object.inner().as_node().is_some_and(Node::is_simple_graph))
+	{
+		return Ok(());
+	}
+
+	let expanded_json = expanded.into_json_with(&());
+	let expanded = block_on(remote_document(expanded_json.clone()).expand(&NoLoader))
+		.map_err(Violation::ExpandedExpansion)?;
+
+	let context = match document.compaction_context().to_syntax() {
+		Ok(context) => RemoteContextReference::Loaded(RemoteDocument::new(None, None, context)),
+		Err(_) => return Ok(()),
+	};
+
+	let compacted = match block_on(remote_document(expanded_json).compact(context, &NoLoader)) {
+		Ok(compacted) => compacted,
+		Err(_) => return Ok(()),
+	};
+
+	let re_expanded = block_on(remote_document(compacted.clone()).expand(&NoLoader))
+		.map_err(Violation::CompactedExpansion)?;
+
+	if re_expanded == expanded {
+		Ok(())
+	} else {
+		Err(Violation::UnstableCompaction {
+			expanded: to_string(expanded),
+			compacted: compacted.compact_print().to_string(),
+			re_expanded: to_string(re_expanded),
+		})
+	}
+}
+
+/// Checks that the flattened document is in expanded form, that every node
+/// has an identifier and that nested nodes are only references.
+///
+/// Documents whose flattening fails are ignored.
+pub fn check_flattening(document: &Document) -> Result<(), Violation> {
+	let mut generator = generator::Blank::new();
+	let flattened =
+		match block_on(remote_document(document.to_json()).flatten(&mut generator, &NoLoader)) {
+			Ok(flattened) => flattened,
+			Err(_) => return Ok(()),
+		};
+
+	let flattened: ExpandedDocument = ExpandedDocument::try_from_json_in(&mut (), flattened)
+		.map_err(Violation::InvalidFlattenedJson)?;
+
+	check_flattened_nodes(flattened.objects())
+}
+
+fn check_flattened_nodes<'a>(
+	objects: impl IntoIterator<Item = &'a IndexedObject<IriBuf, BlankIdBuf>>,
+) -> Result<(), Violation> {
+	for object in objects {
+		let node = object
+			.inner()
+			.as_node()
+			.ok_or(Violation::FlattenedNonNode)?;
+
+		if node.id.is_none() {
+			return Err(Violation::FlattenedAnonymousNode);
+		}
+
+		if node.included().is_some() {
+			return Err(Violation::FlattenedNestedNode);
+		}
+
+		if let Some(graph) = node.graph() {
+			check_flattened_nodes(graph)?
+		}
+
+		for (_, values) in node.properties() {
+			check_references(values)?
+		}
+
+		if let Some(reverse_properties) = node.reverse_properties() {
+			for (_, nodes) in reverse_properties {
+				if nodes.iter().any(|node| !node.is_empty()) {
+					return Err(Violation::FlattenedNestedNode);
+				}
+			}
+		}
+	}
+
+	Ok(())
+}
+
+fn check_references<'a>(
+	objects: impl IntoIterator<Item = &'a IndexedObject<IriBuf, BlankIdBuf>>,
+) -> Result<(), Violation> {
+	for object in objects {
+		match object.inner() {
+			Object::Node(node) if !node.is_empty() => return Err(Violation::FlattenedNestedNode),
+			Object::List(list) => check_references(list)?,
+			_ => (),
+		}
+	}
+
+	Ok(())
+}
+
+/// Checks all the invariants on the given document.
+pub fn check(document: &Document) -> Result<(), Violation> {
+	check_compaction(document)?;
+	check_flattening(document)
+}
+
+#[cfg(all(test, feature = "proptest"))]
+mod tests {
+	use crate::arbitrary::strategy::document;
+	use proptest::prelude::*;
+
+	proptest! {
+		#![proptest_config(ProptestConfig::with_cases(128))]
+
+		#[test]
+		fn invariants(document in document()) {
+			if let Err(e) = super::check(&document) {
+				return Err(TestCaseError::fail(e.to_string()));
+			}
+		}
+	}
+}
@@ -309,11 +309,28 @@ pub use json_ld_expansion as expansion;
 pub use json_ld_serialization as ser;
 pub use json_ld_syntax as syntax;
 
-pub use compaction::Compact;
+pub use compaction::{Compact, CompactTerm};
 pub use context_processing::Process;
-pub use expansion::Expand;
+pub use expansion::{Expand, ExpandTerm};
 
+#[cfg(feature = "as2")]
+pub mod as2;
+
+#[cfg(feature = "did")]
+pub mod did;
+
+#[cfg(feature = "vc")]
+pub mod vc;
+
+#[cfg(feature = "html")]
+pub mod html;
+
+#[cfg(feature = "arbitrary")]
+pub mod invariant;
+
+mod dereference;
 mod processor;
+pub use dereference::*;
 pub use processor::*;
 
 #[doc(hidden)]
@@ -0,0 +1,128 @@
+use super::{ExpandResult, JsonLdProcessor, Options};
+use crate::context_processing::SharedContextCache;
+use futures::StreamExt;
+use json_ld_core::{Loader, RemoteDocumentReference};
+use rdf_types::{vocabulary, BlankIdBuf, VocabularyMut};
+use std::hash::Hash;
+
+/// Expands the given documents concurrently.
+///
+/// At most `concurrency` expansions are driven at the same time (at least
+/// one), all sharing the same `loader`. The
+/// [`expand_context`](Options::expand_context) of the `options`, if any, is
+/// loaded once for all the documents, and the remote contexts referenced by
+/// the documents are processed once for all of them using a
+/// [`SharedContextCache`].
+///
+/// The results are returned in the order of the input documents. An error
+/// only affects the document that caused it: the other documents are still
+/// expanded.
+pub async fn expand_all<D, I>(
+	documents: D,
+	loader: &impl Loader,
+	mut options: Options<I>,
+	concurrency: usize,
+) -> Vec<ExpandResult<I, BlankIdBuf>>
+where
+	D: IntoIterator,
+	D::Item: JsonLdProcessor<I>,
+	(): VocabularyMut<Iri = I>,
+	I: Clone + Eq + Hash,
+{
+	// If the expand context cannot be loaded, it is left as is so that the
+	// error is reported for every document.
+	if let Some(RemoteDocumentReference::Iri(iri)) = &options.expand_context {
+		if let Ok(context) = RemoteDocumentReference::Iri(iri.clone())
+			.load_context_with(vocabulary::no_vocabulary_mut(), loader)
+			.await
+		{
+			options.expand_context = Some(RemoteDocumentReference::Loaded(context))
+		}
+	}
+
+	let cache = &SharedContextCache::new();
+	futures::stream::iter(documents.into_iter().map(|document| {
+		let options = options.clone();
+		async move {
+			document
+				.expand_full_with_cache(vocabulary::no_vocabulary_mut(), loader, options, (), cache)
+				.await
+		}
+	}))
+	.buffered(concurrency.max(1))
+	.collect()
+	.await
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{NoLoader, RemoteDocument};
+
+	#[test]
+	fn expand_all() {
+		let documents = [
+			json_syntax::json!({ "@id": "https://example.com/a", "https://example.com/p": "A" }),
+			json_syntax::json!({ "@context": 42, "https://example.com/p": "B" }),
+			json_syntax::json!({ "@id": "https://example.com/c", "https://example.com/p": "C" }),
+		]
+		.map(|json| RemoteDocument::new(None, None, json));
+
+		let results = futures::executor::block_on(super::expand_all(
+			documents,
+			&NoLoader,
+			Options::default(),
+			2,
+		));
+
+		assert_eq!(results.len(), 3);
+		let id = |result: &ExpandResult<_, _>| {
+			let document = result.as_ref().unwrap();
+			let node = document.iter().next().unwrap().as_node().unwrap();
+			node.id.as_ref().unwrap().as_str().to_owned()
+		};
+		assert_eq!(id(&results[0]), "https://example.com/a");
+		assert!(results[1].is_err());
+		assert_eq!(id(&results[2]), "https://example.com/c")
+	}
+
+	#[test]
+	fn expand_all_shares_remote_contexts() {
+		use crate::{Iri, IriBuf, LoadError};
+		use std::sync::atomic::{AtomicUsize, Ordering};
+
+		struct CountingLoader(AtomicUsize);
+
+		impl Loader for CountingLoader {
+			async fn load(&self, url: &Iri) -> Result<RemoteDocument<IriBuf>, LoadError> {
+				self.0.fetch_add(1, Ordering::Relaxed);
+				Ok(RemoteDocument::new(
+					Some(url.to_owned()),
+					None,
+					json_syntax::json!({ "@context": { "name": "https://schema.org/name" } }),
+				))
+			}
+		}
+
+		let documents = ["Alice", "Bob", "Carol"].map(|name| {
+			RemoteDocument::new(
+				None,
+				None,
+				json_syntax::json!({ "@context": "https://example.com/context.jsonld", "name": name }),
+			)
+		});
+
+		let loader = CountingLoader(AtomicUsize::new(0));
+		let results = futures::executor::block_on(super::expand_all(
+			documents,
+			&loader,
+			Options::default(),
+			1,
+		));
+
+		assert!(results
+			.iter()
+			.all(|result| result.as_ref().unwrap().len() == 1));
+		assert_eq!(loader.0.load(Ordering::Relaxed), 1)
+	}
+}
@@ -2,16 +2,26 @@ use crate::compaction::{self, Compact};
 use crate::context_processing::{self, Process};
 use crate::expansion;
 use crate::syntax::ErrorCode;
-use crate::{flattening::ConflictingIndexes, Context, ExpandedDocument, Loader, ProcessingMode};
+use crate::{
+	flattening::{ConflictingIndexes, NodeMap},
+	Context, ExpandedDocument, InvalidIriPolicy, IriValidation, Loader, ProcessingMode,
+};
 use iref::IriBuf;
-use json_ld_core::rdf::RdfDirection;
+use json_ld_core::rdf::{RdfDirection, XsdStringPolicy};
+#[cfg(feature = "rdf")]
+use json_ld_core::RdfQuads;
 use json_ld_core::{ContextLoadError, LoadError};
-use json_ld_core::{Document, RdfQuads, RemoteContextReference};
-use rdf_types::{vocabulary, BlankIdBuf, Generator, Vocabulary, VocabularyMut};
+use json_ld_core::{Document, RemoteContextReference};
+#[cfg(feature = "rdf")]
+use rdf_types::Vocabulary;
+use rdf_types::{vocabulary, BlankIdBuf, Generator, VocabularyMut};
 use std::hash::Hash;
 
+mod batch;
 mod remote_document;
 
+pub use batch::*;
+
 /// JSON-LD Processor options.
 #[derive(Clone)]
 pub struct Options<I = IriBuf> {
@@ -64,10 +74,23 @@ pub struct Options<I = IriBuf> {
 	/// a value object containing `@language` (if present) and `@direction`.
 	pub rdf_direction: Option<RdfDirection>,
 
+	/// `xsd:string` datatype policy of the RDF output.
+	///
+	/// Decides whether [`ToRdf::write_turtle`] and [`ToRdf::write_trig`]
+	/// write out the `xsd:string` datatype of string literals.
+	///
+	/// Defaults to [`XsdStringPolicy::Implicit`].
+	pub xsd_string: XsdStringPolicy,
+
 	/// If set to `true`, the JSON-LD processor may emit blank nodes for triple
 	/// predicates, otherwise they will be omitted.
 	/// See <https://www.w3.org/TR/rdf11-concepts/>.
 	///
+	/// The resulting dataset is a generalized RDF dataset, which is not valid
+	/// RDF: such triples can only be consumed by tools accepting generalized
+	/// RDF. The reverse conversion always accepts blank node predicates, which
+	/// become blank node property identifiers.
+	///
 	/// The use of blank node identifiers to label properties is obsolete, and
 	/// may be removed in a future version of JSON-LD, as is the support for
 	/// generalized RDF Datasets and thus this option
@@ -76,9 +99,201 @@ pub struct Options<I = IriBuf> {
 
 	/// Term expansion policy, passed to the document expansion algorithm.
 	pub expansion_policy: expansion::Policy,
+
+	/// IRI validation level applied to the expanded document.
+	///
+	/// IRIs rejected by this level are preserved as
+	/// [`Id::Invalid`](crate::Id::Invalid) and reported as malformed IRI
+	/// warnings.
+	///
+	/// Defaults to [`IriValidation::Strict`].
+	pub iri_validation: IriValidation,
+
+	/// Determines what to do with invalid identifiers when compacting the
+	/// document or serializing it into RDF.
+	///
+	/// Defaults to [`InvalidIriPolicy::PassThrough`].
+	pub on_invalid_iri: InvalidIriPolicy,
+
+	/// If set to `true`, keywords are replaced by their aliases defined in the
+	/// context, if any, during compaction.
+	///
+	/// Defaults to `true`.
+	pub use_aliases: bool,
+
+	/// Determines how the `@version` entry of the context embedded in the
+	/// compacted document is handled.
+	///
+	/// In the JSON-LD 1.0 processing mode, compaction fails with a processing
+	/// mode conflict if the output requires JSON-LD 1.1.
+	///
+	/// Defaults to [`compaction::VersionEntry::Preserve`].
+	pub version_entry: compaction::VersionEntry,
+
+	/// If set to `true`, values typed `xsd:boolean`, `xsd:integer` or
+	/// `xsd:double` are compacted into native JSON booleans and numbers when
+	/// the type mapping of their term matches their type.
+	///
+	/// This is not part of the JSON-LD specification: expanding the compacted
+	/// document gives native values instead of the original strings.
+	///
+	/// Defaults to `false`.
+	pub compact_to_native: bool,
+
+	/// If set to `true`, compaction produces canonical output: the same
+	/// expanded document compacted with the same context always gives the
+	/// same bytes once printed with
+	/// [`compact_print`](json_syntax::Print::compact_print).
+	///
+	/// See [`compaction::Options::canonical`].
+	///
+	/// Defaults to `false`.
+	pub canonical_compaction: bool,
+
+	/// Enables the experimental JSON-LD-star support (`@annotation`) during
+	/// expansion.
+	///
+	/// Annotations are not preserved by compaction nor flattening. With the
+	/// `rdf-star` feature, use `ExpandedDocument::rdf_star_annotation_quads`
+	/// to turn them into RDF-star quads.
+	///
+	/// Defaults to `false`.
+	pub rdf_star: bool,
+
+	/// Maximum nesting depth of the documents to expand.
+	///
+	/// Expansion fails with a
+	/// [`MaximumDepthExceeded`](expansion::Error::MaximumDepthExceeded) error
+	/// if the input document is more deeply nested, instead of risking a stack
+	/// overflow. `None` means no limit.
+	///
+	/// Defaults to `None`. [`Options::offline_secure`] sets it to
+	/// [`expansion::DEFAULT_MAX_DEPTH`].
+	pub max_depth: Option<usize>,
+
+	/// Resource limits of the documents to expand.
+	///
+	/// Expansion fails with a
+	/// [`ResourceLimit`](expansion::Error::ResourceLimit) error if the
+	/// document exceeds one of them. See [`expansion::Limits`].
+	///
+	/// Defaults to no limit. [`Options::offline_secure`] sets it to
+	/// [`expansion::DEFAULT_LIMITS`].
+	pub limits: expansion::Limits,
+
+	/// Enables the lossy expansion mode, skipping the entries causing
+	/// recoverable errors instead of aborting.
+	///
+	/// Recovered errors are reported to the warning handler. See
+	/// [`expansion::Options::lossy`] and [`JsonLdProcessor::expand_lossy`].
+	///
+	/// Defaults to `false`.
+	pub lossy: bool,
+
+	/// If set to `true`, context processing fails with a
+	/// [`RecursiveContextInclusion`](context_processing::Error::RecursiveContextInclusion)
+	/// error describing the cycle when a remote context includes itself,
+	/// directly or through other remote contexts. Otherwise the recursive
+	/// inclusion is ignored.
+	///
+	/// Defaults to `false`.
+	pub reject_recursive_contexts: bool,
+
+	/// If set to `true`, the scoped contexts of term definitions are only
+	/// processed when the term is used during expansion, instead of when the
+	/// context is processed.
+	///
+	/// See
+	/// [`context_processing::Options::lazy_scoped_contexts`](crate::context_processing::Options::lazy_scoped_contexts).
+	///
+	/// Defaults to `false`.
+	pub lazy_scoped_contexts: bool,
+
+	/// If set, expansion, flattening and compaction yield to the async
+	/// executor every time this number of items is processed, so that
+	/// processing very large documents does not starve the other tasks of the
+	/// executor.
+	///
+	/// See [`expansion::Options::yield_every`],
+	/// [`compaction::Options::yield_every`] and
+	/// [`ExpandedDocument::flatten_yielding`](crate::ExpandedDocument::flatten_yielding).
+	///
+	/// Defaults to `None` (never yield).
+	pub yield_every: Option<usize>,
+
+	/// Normalization of the case of the language tags of the expanded value
+	/// objects.
+	///
+	/// See [`expansion::Options::language_case`].
+	///
+	/// Defaults to [`LanguageCase::Preserve`](expansion::LanguageCase::Preserve).
+	pub language_case: expansion::LanguageCase,
 }
 
 impl<I> Options<I> {
+	/// Options strictly following the JSON-LD 1.1 specification.
+	///
+	/// Keys and types that cannot be expanded into valid IRIs are dropped, as
+	/// required by the expansion algorithm.
+	pub fn strict_spec() -> Self {
+		Self {
+			expansion_policy: expansion::Policy {
+				invalid: expansion::Action::Drop,
+				vocab: expansion::Action::Keep,
+				allow_undefined: true,
+				keyword_like: expansion::Action::Drop,
+			},
+			..Self::default()
+		}
+	}
+
+	/// Options suited to process documents found in the wild.
+	///
+	/// Keys and types that cannot be expanded into valid IRIs, including
+	/// keyword-like keys (e.g. `@foo`), are kept as invalid identifiers instead
+	/// of being dropped, so no information is lost.
+	/// Malformed IRIs starting with a scheme are repaired when possible (see
+	/// [`IriValidation::Lenient`]).
+	pub fn lenient_web() -> Self {
+		Self {
+			expansion_policy: expansion::Policy {
+				keyword_like: expansion::Action::Keep,
+				..expansion::Policy::default()
+			},
+			iri_validation: IriValidation::Lenient,
+			..Self::default()
+		}
+	}
+
+	/// Options suited to process untrusted documents without relying on
+	/// external resources.
+	///
+	/// Any key or type that cannot be expanded into a valid IRI, or that is
+	/// not defined by the context, raises an error instead of being silently
+	/// kept or dropped.
+	///
+	/// Remote loading is not controlled by the options but by the
+	/// [`Loader`] passed to the processor: these options are meant to be used
+	/// with a loader that never reaches the network, such as
+	/// [`NoLoader`](crate::NoLoader) or [`FsLoader`](crate::FsLoader).
+	///
+	/// The nesting depth of the documents is limited to
+	/// [`expansion::DEFAULT_MAX_DEPTH`], and their size to
+	/// [`expansion::DEFAULT_LIMITS`].
+	pub fn offline_secure() -> Self {
+		Self {
+			max_depth: Some(expansion::DEFAULT_MAX_DEPTH),
+			limits: expansion::DEFAULT_LIMITS,
+			expansion_policy: expansion::Policy {
+				invalid: expansion::Action::Reject,
+				vocab: expansion::Action::Keep,
+				allow_undefined: false,
+				keyword_like: expansion::Action::Reject,
+			},
+			..Self::default()
+		}
+	}
+
 	/// Returns these options with the `ordered` flag set to `false`.
 	///
 	/// This means entries will not be ordered by keys before being processed.
@@ -89,6 +304,14 @@ impl<I> Options<I> {
 		}
 	}
 
+	/// Returns these options with the given IRI validation level.
+	pub fn with_iri_validation(self, iri_validation: IriValidation) -> Self {
+		Self {
+			iri_validation,
+			..self
+		}
+	}
+
 	/// Returns these options with the `expand_context` set to the given
 	/// `context`.
 	pub fn with_expand_context(self, context: RemoteContextReference<I>) -> Self {
@@ -100,10 +323,11 @@ impl<I> Options<I> {
 
 	/// Builds options for the context processing algorithm from these options.
 	pub fn context_processing_options(&self) -> context_processing::Options {
-		context_processing::Options {
-			processing_mode: self.processing_mode,
-			..Default::default()
-		}
+		let mut result = context_processing::Options::default()
+			.with_reject_recursive_contexts(self.reject_recursive_contexts)
+			.with_lazy_scoped_contexts(self.lazy_scoped_contexts);
+		result.processing_mode = self.processing_mode;
+		result
 	}
 
 	/// Builds options for the expansion algorithm from these options.
@@ -112,6 +336,14 @@ impl<I> Options<I> {
 			processing_mode: self.processing_mode,
 			ordered: self.ordered,
 			policy: self.expansion_policy,
+			rdf_star: self.rdf_star,
+			max_depth: self.max_depth,
+			limits: self.limits,
+			lossy: self.lossy,
+			reject_recursive_contexts: self.reject_recursive_contexts,
+			lazy_scoped_contexts: self.lazy_scoped_contexts,
+			yield_every: self.yield_every,
+			language_case: self.language_case,
 		}
 	}
 
@@ -122,6 +354,12 @@ impl<I> Options<I> {
 			compact_to_relative: self.compact_to_relative,
 			compact_arrays: self.compact_arrays,
 			ordered: self.ordered,
+			on_invalid_iri: self.on_invalid_iri,
+			use_aliases: self.use_aliases,
+			version_entry: self.version_entry,
+			compact_to_native: self.compact_to_native,
+			canonical: self.canonical_compaction,
+			yield_every: self.yield_every,
 		}
 	}
 }
@@ -136,8 +374,23 @@ impl<I> Default for Options<I> {
 			ordered: false,
 			processing_mode: ProcessingMode::JsonLd1_1,
 			rdf_direction: None,
+			xsd_string: XsdStringPolicy::Implicit,
 			produce_generalized_rdf: false,
 			expansion_policy: expansion::Policy::default(),
+			iri_validation: IriValidation::Strict,
+			on_invalid_iri: InvalidIriPolicy::PassThrough,
+			use_aliases: true,
+			version_entry: compaction::VersionEntry::Preserve,
+			compact_to_native: false,
+			canonical_compaction: false,
+			rdf_star: false,
+			max_depth: None,
+			limits: expansion::Limits::default(),
+			lossy: false,
+			reject_recursive_contexts: false,
+			lazy_scoped_contexts: false,
+			yield_every: None,
+			language_case: expansion::LanguageCase::Preserve,
 		}
 	}
 }
@@ -173,9 +426,19 @@ impl ExpandError {
 	}
 }
 
+#[cfg(feature = "miette")]
+json_ld_syntax::impl_diagnostic!(ExpandError);
+
 /// Result returned by the [`JsonLdProcessor::expand`] function.
 pub type ExpandResult<I, B> = Result<ExpandedDocument<I, B>, ExpandError>;
 
+/// Result returned by the [`JsonLdProcessor::expand_lossy`] function.
+///
+/// On success, the partially expanded document is returned along with the
+/// errors recovered from.
+pub type LossyExpandResult<I, B> =
+	Result<(ExpandedDocument<I, B>, Vec<expansion::Error>), ExpandError>;
+
 /// Result returned by the [`JsonLdProcessor::into_document`] function.
 pub type IntoDocumentResult<I, B> = Result<Document<I, B>, ExpandError>;
 
@@ -215,6 +478,9 @@ impl CompactError {
 	}
 }
 
+#[cfg(feature = "miette")]
+json_ld_syntax::impl_diagnostic!(CompactError);
+
 /// Result of the [`JsonLdProcessor::compact`] function.
 pub type CompactResult = Result<json_syntax::Value, CompactError>;
 
@@ -250,27 +516,67 @@ impl<I, B> FlattenError<I, B> {
 	}
 }
 
+#[cfg(feature = "miette")]
+json_ld_syntax::impl_diagnostic!(impl<I, B> FlattenError<I, B>);
+
 /// Result of the [`JsonLdProcessor::flatten`] function.
 pub type FlattenResult<I, B> = Result<json_syntax::Value, FlattenError<I, B>>;
 
+/// Error that can be raised by the [`JsonLdProcessor::node_map`] function.
+#[derive(Debug, thiserror::Error)]
+pub enum NodeMapError<I, B> {
+	#[error("Expansion failed: {0}")]
+	Expand(ExpandError),
+
+	#[error("Conflicting indexes: {0}")]
+	ConflictingIndexes(ConflictingIndexes<I, B>),
+}
+
+impl<I, B> NodeMapError<I, B> {
+	/// Returns the code of this error.
+	pub fn code(&self) -> ErrorCode {
+		match self {
+			Self::Expand(e) => e.code(),
+			Self::ConflictingIndexes(_) => ErrorCode::ConflictingIndexes,
+		}
+	}
+}
+
+#[cfg(feature = "miette")]
+json_ld_syntax::impl_diagnostic!(impl<I, B> NodeMapError<I, B>);
+
+/// Result of the [`JsonLdProcessor::node_map`] function.
+pub type NodeMapResult<I, B> = Result<NodeMap<I, B>, NodeMapError<I, B>>;
+
 /// Error that can be raised by the [`JsonLdProcessor::to_rdf`] function.
+#[cfg(feature = "rdf")]
 #[derive(Debug, thiserror::Error)]
 pub enum ToRdfError {
 	/// Document expansion failed.
 	#[error("Expansion failed: {0}")]
 	Expand(ExpandError),
+
+	/// The expanded document contains an invalid IRI.
+	#[error("Invalid IRI `{0}`")]
+	InvalidIri(String),
 }
 
+#[cfg(feature = "rdf")]
 impl ToRdfError {
 	/// Returns the code of this error.
 	pub fn code(&self) -> ErrorCode {
 		match self {
 			Self::Expand(e) => e.code(),
+			Self::InvalidIri(_) => ErrorCode::InvalidIdValue,
 		}
 	}
 }
 
+#[cfg(all(feature = "rdf", feature = "miette"))]
+json_ld_syntax::impl_diagnostic!(ToRdfError);
+
 /// Error that can be raised by the [`JsonLdProcessor::to_rdf`] function.
+#[cfg(feature = "rdf")]
 pub type ToRdfResult<V, G> = Result<ToRdf<V, G>, ToRdfError>;
 
 /// Result of the [`JsonLdProcessor::compare`] function.
@@ -605,6 +911,34 @@ pub trait JsonLdProcessor<Iri>: Sized {
 		Iri: Clone + Eq + Hash,
 		N::BlankId: Clone + Eq + Hash;
 
+	/// Expand the document with the given `vocabulary`, `loader` and
+	/// `options`, looking up the processed remote contexts in (and adding
+	/// them to) the given `cache`.
+	///
+	/// Sharing a cache between the expansions of documents referencing the
+	/// same remote contexts avoids processing those contexts again for each
+	/// document. The default implementation ignores the cache.
+	///
+	/// See [`JsonLdProcessor::expand_full`].
+	#[allow(async_fn_in_trait)]
+	async fn expand_full_with_cache<N>(
+		&self,
+		vocabulary: &mut N,
+		loader: &impl Loader,
+		options: Options<Iri>,
+		warnings: impl context_processing::WarningHandler<N> + expansion::WarningHandler<N>,
+		cache: &context_processing::SharedContextCache<Iri, N::BlankId>,
+	) -> ExpandResult<Iri, N::BlankId>
+	where
+		N: VocabularyMut<Iri = Iri>,
+		Iri: Clone + Eq + Hash,
+		N::BlankId: Clone + Eq + Hash,
+	{
+		let _ = cache;
+		self.expand_full(vocabulary, loader, options, warnings)
+			.await
+	}
+
 	/// Expand the document with the given `vocabulary` and `loader`, using
 	/// the given `options`.
 	///
@@ -788,6 +1122,106 @@ pub trait JsonLdProcessor<Iri>: Sized {
 			.await
 	}
 
+	/// Expand the document in lossy mode with the given `vocabulary` and
+	/// `loader`, using the given `options`.
+	///
+	/// The [`lossy`](Options::lossy) option is forced. Instead of aborting
+	/// on the first recoverable error (such as an invalid `@index` or typed
+	/// value), the offending entries are skipped and the recovered errors
+	/// are returned alongside the partially expanded document.
+	/// Other warnings are ignored.
+	#[allow(async_fn_in_trait)]
+	async fn expand_lossy_with_using<'a, N>(
+		&'a self,
+		vocabulary: &'a mut N,
+		loader: &'a impl Loader,
+		options: Options<Iri>,
+	) -> LossyExpandResult<Iri, N::BlankId>
+	where
+		N: VocabularyMut<Iri = Iri>,
+		Iri: Clone + Eq + Hash,
+		N::BlankId: 'a + Clone + Eq + Hash,
+	{
+		let mut errors = expansion::RecoveredErrors::new();
+		let expanded = self
+			.expand_full(
+				vocabulary,
+				loader,
+				Options {
+					lossy: true,
+					..options
+				},
+				&mut errors,
+			)
+			.await?;
+
+		Ok((expanded, errors.into_errors()))
+	}
+
+	/// Expand the document in lossy mode with the given `vocabulary` and
+	/// `loader`.
+	///
+	/// Default options are used.
+	/// See [`JsonLdProcessor::expand_lossy_with_using`].
+	#[allow(async_fn_in_trait)]
+	async fn expand_lossy_with<'a, N>(
+		&'a self,
+		vocabulary: &'a mut N,
+		loader: &'a impl Loader,
+	) -> LossyExpandResult<Iri, N::BlankId>
+	where
+		N: VocabularyMut<Iri = Iri>,
+		Iri: Clone + Eq + Hash,
+		N::BlankId: 'a + Clone + Eq + Hash,
+	{
+		self.expand_lossy_with_using(vocabulary, loader, Options::default())
+			.await
+	}
+
+	/// Expand the document in lossy mode with the given `loader`.
+	///
+	/// Default options are used.
+	/// See [`JsonLdProcessor::expand_lossy_with_using`].
+	///
+	/// # Example
+	///
+	/// ```
+	/// use json_ld::{JsonLdProcessor, RemoteDocument, syntax::Parse};
+	///
+	/// # #[async_std::main]
+	/// # async fn main() {
+	/// let (json, _) = json_ld::syntax::Value::parse_str(
+	///     r#"{
+	///         "@id": "https://example.com/a",
+	///         "https://example.com/name": "A",
+	///         "https://example.com/age": { "@value": "12", "@language": "en", "@type": "https://example.com/T" }
+	///     }"#,
+	/// )
+	/// .unwrap();
+	///
+	/// let input = RemoteDocument::new(None, None, json);
+	/// let (expanded, errors) = input
+	///     .expand_lossy(&json_ld::NoLoader)
+	///     .await
+	///     .unwrap();
+	///
+	/// assert_eq!(expanded.len(), 1);
+	/// assert_eq!(errors.len(), 1);
+	/// # }
+	/// ```
+	#[allow(async_fn_in_trait)]
+	async fn expand_lossy<'a>(
+		&'a self,
+		loader: &'a impl Loader,
+	) -> LossyExpandResult<Iri, BlankIdBuf>
+	where
+		(): VocabularyMut<Iri = Iri>,
+		Iri: Clone + Eq + Hash,
+	{
+		self.expand_lossy_with(vocabulary::no_vocabulary_mut(), loader)
+			.await
+	}
+
 	#[allow(async_fn_in_trait)]
 	async fn into_document_full<'a, N>(
 		self,
@@ -1112,6 +1546,71 @@ pub trait JsonLdProcessor<Iri>: Sized {
 			.await
 	}
 
+	/// Compact the document relative to an already processed `context` with
+	/// the given `vocabulary` and `loader`, using the given `options`.
+	///
+	/// Contrarily to [`Self::compact_full`], the context is not loaded nor
+	/// processed again. This is useful to compact many documents against the
+	/// same context: the context is processed once (for instance with
+	/// [`Process::process`]), and its inverse context is computed only once
+	/// since it is cached by the processed context. The processed context is
+	/// only cloned if the compaction base IRI (see [`Options::base`] and
+	/// [`Options::compact_to_relative`]) differs from its base IRI, in which
+	/// case the clone shares the cached inverse context.
+	///
+	/// The `compact` benchmark compares this method with
+	/// [`Self::compact_full`].
+	///
+	/// Warnings are ignored.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use static_iref::iri;
+	/// use json_ld::{JsonLdProcessor, Options, Process, RemoteDocumentReference, RemoteContextReference};
+	///
+	/// # #[async_std::main]
+	/// # async fn main() {
+	/// // Use `FsLoader` to redirect any URL starting with `https://example.com/` to
+	/// // the local `example` directory. No HTTP query.
+	/// let mut loader = json_ld::FsLoader::default();
+	/// loader.mount(iri!("https://example.com/").to_owned(), "examples");
+	///
+	/// let context_iri = iri!("https://example.com/context.jsonld").to_owned();
+	/// let context = RemoteContextReference::iri(context_iri)
+	///   .load_context_with(&mut (), &loader)
+	///   .await
+	///   .expect("loading failed")
+	///   .into_document();
+	///
+	/// // The context is processed once.
+	/// let processed = context
+	///   .process(&mut (), &loader, None)
+	///   .await
+	///   .expect("processing failed");
+	///
+	/// for _ in 0..10 {
+	///   let input = RemoteDocumentReference::iri(iri!("https://example.com/sample.jsonld").to_owned());
+	///   let compact = input
+	///     .compact_with_processed(&mut (), processed.as_ref(), &loader, Options::default())
+	///     .await
+	///     .expect("compaction failed");
+	/// }
+	/// # }
+	/// ```
+	#[allow(async_fn_in_trait)]
+	async fn compact_with_processed<'a, N>(
+		&'a self,
+		vocabulary: &'a mut N,
+		context: context_processing::ProcessedRef<'a, 'a, Iri, N::BlankId>,
+		loader: &'a impl Loader,
+		options: Options<Iri>,
+	) -> CompactResult
+	where
+		N: VocabularyMut<Iri = Iri>,
+		Iri: Clone + Eq + Hash,
+		N::BlankId: 'a + Clone + Eq + Hash;
+
 	/// Flatten the document with the given `vocabulary`, `generator`
 	/// and `loader`, using the given `options` and warning handler.
 	///
@@ -1419,6 +1918,124 @@ pub trait JsonLdProcessor<Iri>: Sized {
 			.await
 	}
 
+	/// Generates the node map of the document with the given `vocabulary`,
+	/// `generator` and `loader`, using the given `options` and warning
+	/// handler.
+	///
+	/// This is the flattening algorithm without its final step: instead of
+	/// being serialized, the [`NodeMap`] is returned as is, so that it can be
+	/// used as an in-memory graph store. Each node of the document is
+	/// available through its identifier (nested anonymous nodes are assigned
+	/// an identifier using the `generator`), in the graph it belongs to.
+	///
+	/// The node map follows the structure defined by the
+	/// [Node Map Generation algorithm](https://www.w3.org/TR/json-ld11-api/#node-map-generation),
+	/// and is part of the stable API: the nodes of the map are flattened
+	/// nodes whose property values refer to other nodes by identifier. It can
+	/// be turned back into a flattened [`ExpandedDocument`] with
+	/// [`ExpandedDocument::from`].
+	///
+	/// # Example
+	///
+	/// ```
+	/// use static_iref::iri;
+	/// use json_ld::{JsonLdProcessor, Options, RemoteDocumentReference, warning};
+	/// use rdf_types::vocabulary::{IriVocabularyMut, IndexVocabulary};
+	///
+	/// # #[async_std::main]
+	/// # async fn main() {
+	/// let mut vocabulary: IndexVocabulary = IndexVocabulary::new();
+	///
+	/// let iri_index = vocabulary.insert(iri!("https://example.com/sample.jsonld"));
+	/// let input = RemoteDocumentReference::iri(iri_index);
+	///
+	/// let mut loader = json_ld::FsLoader::default();
+	/// loader.mount(iri!("https://example.com/").to_owned(), "examples");
+	///
+	/// let mut generator = rdf_types::generator::Blank::new();
+	///
+	/// let node_map = input
+	///   .node_map_full(
+	///     &mut vocabulary,
+	///     &mut generator,
+	///     &loader,
+	///     Options::default(),
+	///     warning::PrintWith
+	///   )
+	///   .await
+	///   .expect("flattening failed");
+	///
+	/// for (graph, nodes) in &node_map {
+	///   // ...
+	/// }
+	/// # }
+	/// ```
+	#[allow(async_fn_in_trait)]
+	async fn node_map_full<N>(
+		&self,
+		vocabulary: &mut N,
+		generator: &mut impl Generator<N>,
+		loader: &impl Loader,
+		options: Options<Iri>,
+		warnings: impl context_processing::WarningHandler<N> + expansion::WarningHandler<N>,
+	) -> NodeMapResult<Iri, N::BlankId>
+	where
+		N: VocabularyMut<Iri = Iri>,
+		Iri: Clone + Eq + Hash,
+		N::BlankId: Clone + Eq + Hash,
+	{
+		let expanded_input = self
+			.expand_full(vocabulary, loader, options.unordered(), warnings)
+			.await
+			.map_err(NodeMapError::Expand)?;
+
+		expanded_input
+			.into_node_map_with(vocabulary, generator)
+			.map_err(NodeMapError::ConflictingIndexes)
+	}
+
+	/// Generates the node map of the document with the given `vocabulary`,
+	/// `generator` and `loader`.
+	///
+	/// Default options are used.
+	/// Warnings are ignored.
+	/// See [`Self::node_map_full`].
+	#[allow(async_fn_in_trait)]
+	async fn node_map_with<N>(
+		&self,
+		vocabulary: &mut N,
+		generator: &mut impl Generator<N>,
+		loader: &impl Loader,
+	) -> NodeMapResult<Iri, N::BlankId>
+	where
+		N: VocabularyMut<Iri = Iri>,
+		Iri: Clone + Eq + Hash,
+		N::BlankId: Clone + Eq + Hash,
+	{
+		self.node_map_full(vocabulary, generator, loader, Options::default(), ())
+			.await
+	}
+
+	/// Generates the node map of the document with the given `generator` and
+	/// `loader`.
+	///
+	/// Default options are used.
+	/// Warnings are ignored.
+	/// See [`Self::node_map_full`].
+	#[allow(async_fn_in_trait)]
+	async fn node_map(
+		&self,
+		generator: &mut impl Generator,
+		loader: &impl Loader,
+	) -> NodeMapResult<Iri, BlankIdBuf>
+	where
+		(): VocabularyMut<Iri = Iri>,
+		Iri: Clone + Eq + Hash,
+	{
+		self.node_map_with(vocabulary::no_vocabulary_mut(), generator, loader)
+			.await
+	}
+
 	/// Serializes the document into an RDF dataset with a custom vocabulary
 	/// using the given `options` and warnings handler.
 	///
@@ -1475,6 +2092,7 @@ pub trait JsonLdProcessor<Iri>: Sized {
 	/// }
 	/// # }
 	/// ```
+	#[cfg(feature = "rdf")]
 	#[allow(async_fn_in_trait)]
 	async fn to_rdf_full<N, G>(
 		&self,
@@ -1491,16 +2109,26 @@ pub trait JsonLdProcessor<Iri>: Sized {
 		G: Generator<N>,
 	{
 		let rdf_direction = options.rdf_direction;
+		let xsd_string = options.xsd_string;
 		let produce_generalized_rdf = options.produce_generalized_rdf;
+		let on_invalid_iri = options.on_invalid_iri;
 		let expanded_input = self
 			.expand_full(&mut vocabulary, loader, options.unordered(), warnings)
 			.await
 			.map_err(ToRdfError::Expand)?;
+
+		if on_invalid_iri == InvalidIriPolicy::Error {
+			if let Some(id) = expanded_input.find_invalid_id() {
+				return Err(ToRdfError::InvalidIri(id));
+			}
+		}
+
 		Ok(ToRdf::new(
 			vocabulary,
 			generator,
 			expanded_input,
 			rdf_direction,
+			xsd_string,
 			produce_generalized_rdf,
 		))
 	}
@@ -1560,6 +2188,7 @@ pub trait JsonLdProcessor<Iri>: Sized {
 	/// }
 	/// # }
 	/// ```
+	#[cfg(feature = "rdf")]
 	#[allow(async_fn_in_trait)]
 	async fn to_rdf_with_using<N, G>(
 		&self,
@@ -1633,6 +2262,7 @@ pub trait JsonLdProcessor<Iri>: Sized {
 	/// }
 	/// # }
 	/// ```
+	#[cfg(feature = "rdf")]
 	#[allow(async_fn_in_trait)]
 	async fn to_rdf_with<N, G>(
 		&self,
@@ -1706,6 +2336,7 @@ pub trait JsonLdProcessor<Iri>: Sized {
 	/// }
 	/// # }
 	/// ```
+	#[cfg(feature = "rdf")]
 	#[allow(async_fn_in_trait)]
 	async fn to_rdf_using<G>(
 		&self,
@@ -1778,6 +2409,7 @@ pub trait JsonLdProcessor<Iri>: Sized {
 	/// }
 	/// # }
 	/// ```
+	#[cfg(feature = "rdf")]
 	#[allow(async_fn_in_trait)]
 	async fn to_rdf<G>(&self, generator: G, loader: &impl Loader) -> ToRdfResult<(), G>
 	where
@@ -1790,20 +2422,24 @@ pub trait JsonLdProcessor<Iri>: Sized {
 	}
 }
 
+#[cfg(feature = "rdf")]
 pub struct ToRdf<V: Vocabulary, G> {
 	vocabulary: V,
 	generator: G,
 	doc: ExpandedDocument<V::Iri, V::BlankId>,
 	rdf_direction: Option<RdfDirection>,
+	xsd_string: XsdStringPolicy,
 	produce_generalized_rdf: bool,
 }
 
+#[cfg(feature = "rdf")]
 impl<V: Vocabulary, G: rdf_types::Generator<V>> ToRdf<V, G> {
 	fn new(
 		mut vocabulary: V,
 		mut generator: G,
 		mut doc: ExpandedDocument<V::Iri, V::BlankId>,
 		rdf_direction: Option<RdfDirection>,
+		xsd_string: XsdStringPolicy,
 		produce_generalized_rdf: bool,
 	) -> Self
 	where
@@ -1816,6 +2452,7 @@ impl<V: Vocabulary, G: rdf_types::Generator<V>> ToRdf<V, G> {
 			generator,
 			doc,
 			rdf_direction,
+			xsd_string,
 			produce_generalized_rdf,
 		}
 	}
@@ -1871,7 +2508,42 @@ impl<V: Vocabulary, G: rdf_types::Generator<V>> ToRdf<V, G> {
 	}
 }
 
-async fn compact_expanded_full<'a, T, N, L>(
+#[cfg(feature = "rdf")]
+impl<V, G: rdf_types::Generator<V>> ToRdf<V, G>
+where
+	V: VocabularyMut,
+	V::Iri: Clone,
+	V::BlankId: Clone,
+	V::Literal: Clone,
+{
+	/// Writes the default graph of the RDF dataset in Turtle, following the
+	/// [`Options::xsd_string`] policy.
+	///
+	/// See [`json_ld_core::rdf::write_turtle`] for details.
+	pub fn write_turtle(
+		&mut self,
+		prefixes: &json_ld_core::rdf::Prefixes,
+		out: &mut impl std::fmt::Write,
+	) -> std::fmt::Result {
+		let quads: Vec<_> = self.cloned_quads().collect();
+		json_ld_core::rdf::write_turtle(&self.vocabulary, prefixes, self.xsd_string, quads, out)
+	}
+
+	/// Writes the RDF dataset in TriG, following the [`Options::xsd_string`]
+	/// policy.
+	///
+	/// See [`json_ld_core::rdf::write_trig`] for details.
+	pub fn write_trig(
+		&mut self,
+		prefixes: &json_ld_core::rdf::Prefixes,
+		out: &mut impl std::fmt::Write,
+	) -> std::fmt::Result {
+		let quads: Vec<_> = self.cloned_quads().collect();
+		json_ld_core::rdf::write_trig(&self.vocabulary, prefixes, self.xsd_string, quads, out)
+	}
+}
+
+pub(crate) async fn compact_expanded_full<'a, T, N, L>(
 	expanded_input: &'a T,
 	url: Option<&'a N::Iri>,
 	vocabulary: &'a mut N,
@@ -1907,13 +2579,8 @@ where
 		.await
 		.map_err(CompactError::ContextProcessing)?;
 
-	match options.base.as_ref() {
-		Some(base) => active_context.set_base_iri(Some(base.clone())),
-		None => {
-			if options.compact_to_relative && active_context.base_iri().is_none() {
-				active_context.set_base_iri(url.cloned());
-			}
-		}
+	if let Some(base) = compaction_base_iri(&active_context, url, &options) {
+		active_context.set_base_iri(Some(base.clone()))
 	}
 
 	expanded_input
@@ -1927,6 +2594,57 @@ where
 		.map_err(CompactError::Compaction)
 }
 
+async fn compact_expanded_with_processed<'a, T, N, L>(
+	expanded_input: &'a T,
+	url: Option<&'a N::Iri>,
+	vocabulary: &'a mut N,
+	context: context_processing::ProcessedRef<'a, 'a, N::Iri, N::BlankId>,
+	loader: &'a L,
+	options: Options<N::Iri>,
+) -> Result<json_syntax::Value, CompactError>
+where
+	N: VocabularyMut,
+	N::Iri: Clone + Eq + Hash,
+	N::BlankId: 'a + Clone + Eq + Hash,
+	T: Compact<N::Iri, N::BlankId>,
+	L: Loader,
+{
+	let rebased = compaction_base_iri(context.processed(), url, &options).map(|base| {
+		// Compute the inverse context before cloning, so that it is cached
+		// by the given context and shared with the rebased one.
+		context.processed().inverse();
+		let mut rebased = context.processed().clone();
+		rebased.set_base_iri(Some(base.clone()));
+		rebased
+	});
+
+	let context = match &rebased {
+		Some(rebased) => context_processing::ProcessedRef::new(context.unprocessed(), rebased),
+		None => context,
+	};
+
+	expanded_input
+		.compact_full(vocabulary, context, loader, options.compaction_options())
+		.await
+		.map_err(CompactError::Compaction)
+}
+
+/// Returns the base IRI that must be set on the active context before
+/// compaction, if it differs from the current one.
+fn compaction_base_iri<'a, I: PartialEq, B>(
+	active_context: &Context<I, B>,
+	url: Option<&'a I>,
+	options: &'a Options<I>,
+) -> Option<&'a I> {
+	let base = match options.base.as_ref() {
+		Some(base) => Some(base),
+		None if options.compact_to_relative && active_context.base_iri().is_none() => url,
+		None => None,
+	};
+
+	base.filter(|base| active_context.base_iri() != Some(*base))
+}
+
 #[cfg(test)]
 mod tests {
 	use futures::Future;
@@ -1940,6 +2658,7 @@ mod tests {
 		f.await
 	}
 
+	#[cfg(feature = "rdf")]
 	#[async_std::test]
 	async fn to_rdf_is_send() {
 		let generator = generator::Blank::new();
@@ -1947,4 +2666,127 @@ mod tests {
 		let f = document.to_rdf(generator, &NoLoader);
 		let _ = assert_send(f).await;
 	}
+
+	#[cfg(feature = "rdf")]
+	#[async_std::test]
+	async fn to_rdf_invalid_iri_policies() {
+		use crate::{InvalidIriPolicy, Options, ToRdfError};
+
+		let document = RemoteDocument::new(
+			None,
+			None,
+			json_syntax::json!([
+				{
+					"@id": "a b",
+					"https://example.com/p": true
+				},
+				{
+					"@id": "https://example.com/a",
+					"https://example.com/p": [{ "@id": "a b" }, true]
+				}
+			]),
+		);
+
+		let to_rdf = |on_invalid_iri| {
+			document.to_rdf_full(
+				(),
+				generator::Blank::new(),
+				&NoLoader,
+				Options {
+					on_invalid_iri,
+					..Options::default()
+				},
+				(),
+			)
+		};
+
+		assert!(matches!(
+			to_rdf(InvalidIriPolicy::Error).await,
+			Err(ToRdfError::InvalidIri(id)) if id == "a b"
+		));
+
+		for policy in [InvalidIriPolicy::Skip, InvalidIriPolicy::PassThrough] {
+			let mut rdf = to_rdf(policy).await.unwrap();
+			let quads: Vec<_> = rdf.cloned_quads().collect();
+			assert_eq!(quads.len(), 1);
+			assert_eq!(quads[0].0.as_str(), "https://example.com/a")
+		}
+	}
+
+	#[async_std::test]
+	async fn offline_secure() {
+		use crate::{expansion, expansion::ResourceLimit, ExpandError, Options};
+
+		let options: Options = Options::offline_secure();
+		assert_eq!(options.max_depth, Some(expansion::DEFAULT_MAX_DEPTH));
+		assert_eq!(options.limits, expansion::DEFAULT_LIMITS);
+		assert_eq!(
+			options.expansion_options().limits,
+			expansion::DEFAULT_LIMITS
+		);
+
+		let max = expansion::DEFAULT_LIMITS.max_literal_length.unwrap();
+		let literal = "x".repeat(max + 1);
+		let document = RemoteDocument::new(
+			None,
+			None,
+			json_syntax::json!({ "https://example.com/p": literal }),
+		);
+
+		assert!(matches!(
+			document
+				.expand_full(&mut (), &NoLoader, options, ())
+				.await,
+			Err(ExpandError::Expansion(expansion::Error::ResourceLimit(
+				ResourceLimit::LiteralLength(m)
+			))) if m == max
+		))
+	}
+
+	#[async_std::test]
+	async fn compact_with_processed() {
+		use crate::{syntax::TryFromJson, Options, Process, RemoteContextReference};
+		use iref::IriBuf;
+
+		let context = crate::syntax::context::Context::try_from_json(json_syntax::json!({
+			"@vocab": "https://example.com/vocab#",
+			"knows": { "@type": "@id" }
+		}))
+		.unwrap();
+		let processed = context.process(&mut (), &NoLoader, None).await.unwrap();
+
+		for url in ["https://example.com/a", "https://example.org/b"] {
+			let document = RemoteDocument::new(
+				Some(IriBuf::new(url.to_owned()).unwrap()),
+				None,
+				json_syntax::json!({
+					"@id": "https://example.com/alice",
+					"https://example.com/vocab#knows": { "@id": "https://example.com/bob" }
+				}),
+			);
+
+			let expected = document
+				.compact(
+					RemoteContextReference::Loaded(RemoteDocument::new(
+						None,
+						None,
+						context.clone(),
+					)),
+					&NoLoader,
+				)
+				.await
+				.unwrap();
+
+			// The context base IRI is set for the first document, and
+			// changed for the second one.
+			let compacted = document
+				.compact_with_processed(&mut (), processed.as_ref(), &NoLoader, Options::default())
+				.await
+				.unwrap();
+
+			assert_eq!(compacted, expected)
+		}
+
+		assert!(processed.base_iri().is_none())
+	}
 }
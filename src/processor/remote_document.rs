@@ -1,13 +1,14 @@
 use super::{
-	compact_expanded_full, CompactError, CompactResult, CompareResult, ExpandError, ExpandResult,
-	FlattenError, FlattenResult, JsonLdProcessor, Options,
+	compact_expanded_full, compact_expanded_with_processed, CompactError, CompactResult,
+	CompareResult, ExpandError, ExpandResult, FlattenError, FlattenResult, JsonLdProcessor,
+	Options,
 };
-use crate::context_processing::{self, Process};
+use crate::context_processing::{self, Process, SharedContextCache};
 use crate::expansion::{self, Expand};
 use crate::IntoDocumentResult;
-use crate::{Context, Flatten, Loader, RemoteDocument, RemoteDocumentReference};
+use crate::{Context, Loader, RemoteDocument, RemoteDocumentReference};
 use contextual::WithContext;
-use json_ld_core::{Document, RemoteContextReference};
+use json_ld_core::{progress::Yielder, Document, RemoteContextReference};
 use rdf_types::{Generator, VocabularyMut};
 use std::hash::Hash;
 
@@ -46,65 +47,31 @@ impl<I> JsonLdProcessor<I> for RemoteDocument<I> {
 		&self,
 		vocabulary: &mut N,
 		loader: &impl Loader,
-		mut options: Options<I>,
-		mut warnings: impl context_processing::WarningHandler<N> + expansion::WarningHandler<N>,
+		options: Options<I>,
+		warnings: impl context_processing::WarningHandler<N> + expansion::WarningHandler<N>,
 	) -> ExpandResult<I, N::BlankId>
 	where
 		N: VocabularyMut<Iri = I>,
 		I: Clone + Eq + Hash,
 		N::BlankId: Clone + Eq + Hash,
 	{
-		let mut active_context = Context::new(options.base.clone().or_else(|| self.url().cloned()));
-
-		if let Some(expand_context) = options.expand_context.take() {
-			active_context = expand_context
-				.load_context_with(vocabulary, loader)
-				.await
-				.map_err(ExpandError::ContextLoading)?
-				.into_document()
-				.process_full(
-					vocabulary,
-					&active_context,
-					loader,
-					active_context.original_base_url().cloned(),
-					options.context_processing_options(),
-					&mut warnings,
-				)
-				.await
-				.map_err(ExpandError::ContextProcessing)?
-				.into_processed()
-		};
-
-		if let Some(context_url) = self.context_url() {
-			active_context = RemoteDocumentReference::Iri(context_url.clone())
-				.load_context_with(vocabulary, loader)
-				.await
-				.map_err(ExpandError::ContextLoading)?
-				.into_document()
-				.process_full(
-					vocabulary,
-					&active_context,
-					loader,
-					Some(context_url.clone()),
-					options.context_processing_options(),
-					&mut warnings,
-				)
-				.await
-				.map_err(ExpandError::ContextProcessing)?
-				.into_processed()
-		}
+		expand_remote_document(self, vocabulary, loader, options, warnings, None).await
+	}
 
-		self.document()
-			.expand_full(
-				vocabulary,
-				active_context,
-				self.url().or(options.base.as_ref()),
-				loader,
-				options.expansion_options(),
-				warnings,
-			)
-			.await
-			.map_err(ExpandError::Expansion)
+	async fn expand_full_with_cache<N>(
+		&self,
+		vocabulary: &mut N,
+		loader: &impl Loader,
+		options: Options<I>,
+		warnings: impl context_processing::WarningHandler<N> + expansion::WarningHandler<N>,
+		cache: &SharedContextCache<I, N::BlankId>,
+	) -> ExpandResult<I, N::BlankId>
+	where
+		N: VocabularyMut<Iri = I>,
+		I: Clone + Eq + Hash,
+		N::BlankId: Clone + Eq + Hash,
+	{
+		expand_remote_document(self, vocabulary, loader, options, warnings, Some(cache)).await
 	}
 
 	async fn into_document_full<'a, N>(
@@ -159,6 +126,34 @@ impl<I> JsonLdProcessor<I> for RemoteDocument<I> {
 		.await
 	}
 
+	async fn compact_with_processed<'a, N>(
+		&'a self,
+		vocabulary: &'a mut N,
+		context: context_processing::ProcessedRef<'a, 'a, I, N::BlankId>,
+		loader: &'a impl Loader,
+		options: Options<I>,
+	) -> CompactResult
+	where
+		N: VocabularyMut<Iri = I>,
+		I: Clone + Eq + Hash,
+		N::BlankId: 'a + Clone + Eq + Hash,
+	{
+		let expanded_input =
+			JsonLdProcessor::expand_full(self, vocabulary, loader, options.clone().unordered(), ())
+				.await
+				.map_err(CompactError::Expand)?;
+
+		compact_expanded_with_processed(
+			&expanded_input,
+			self.url(),
+			vocabulary,
+			context,
+			loader,
+			options,
+		)
+		.await
+	}
+
 	async fn flatten_full<'a, N>(
 		&'a self,
 		vocabulary: &'a mut N,
@@ -183,9 +178,15 @@ impl<I> JsonLdProcessor<I> for RemoteDocument<I> {
 		.await
 		.map_err(FlattenError::Expand)?;
 
-		let flattened_output =
-			Flatten::flatten_with(expanded_input, vocabulary, generator, options.ordered)
-				.map_err(FlattenError::ConflictingIndexes)?;
+		let flattened_output = expanded_input
+			.flatten_yielding(
+				vocabulary,
+				generator,
+				options.ordered,
+				&Yielder::new(options.yield_every),
+			)
+			.await
+			.map_err(FlattenError::ConflictingIndexes)?;
 
 		match context {
 			Some(context) => compact_expanded_full(
@@ -206,6 +207,154 @@ impl<I> JsonLdProcessor<I> for RemoteDocument<I> {
 	}
 }
 
+async fn expand_remote_document<I, N>(
+	document: &RemoteDocument<I>,
+	vocabulary: &mut N,
+	loader: &impl Loader,
+	mut options: Options<I>,
+	mut warnings: impl context_processing::WarningHandler<N> + expansion::WarningHandler<N>,
+	cache: Option<&SharedContextCache<I, N::BlankId>>,
+) -> ExpandResult<I, N::BlankId>
+where
+	N: VocabularyMut<Iri = I>,
+	I: Clone + Eq + Hash,
+	N::BlankId: Clone + Eq + Hash,
+{
+	let mut active_context = Context::new(options.base.clone().or_else(|| document.url().cloned()));
+
+	if let Some(expand_context) = options.expand_context.take() {
+		let context = expand_context
+			.load_context_with(vocabulary, loader)
+			.await
+			.map_err(ExpandError::ContextLoading)?
+			.into_document();
+		let base_url = active_context.original_base_url().cloned();
+		active_context = process_context(
+			&context,
+			vocabulary,
+			&active_context,
+			loader,
+			base_url,
+			&options,
+			&mut warnings,
+			cache,
+		)
+		.await?
+	};
+
+	if let Some(context_url) = document.context_url() {
+		let context = RemoteDocumentReference::Iri(context_url.clone())
+			.load_context_with(vocabulary, loader)
+			.await
+			.map_err(ExpandError::ContextLoading)?
+			.into_document();
+		active_context = process_context(
+			&context,
+			vocabulary,
+			&active_context,
+			loader,
+			Some(context_url.clone()),
+			&options,
+			&mut warnings,
+			cache,
+		)
+		.await?
+	}
+
+	let base_url = document.url().or(options.base.as_ref());
+	let expanded = match cache {
+		Some(cache) => {
+			document
+				.document()
+				.expand_full_with_cache(
+					vocabulary,
+					active_context,
+					base_url,
+					loader,
+					options.expansion_options(),
+					&mut warnings,
+					cache,
+				)
+				.await
+		}
+		None => {
+			document
+				.document()
+				.expand_full(
+					vocabulary,
+					active_context,
+					base_url,
+					loader,
+					options.expansion_options(),
+					&mut warnings,
+				)
+				.await
+		}
+	};
+	let mut expanded = expanded.map_err(ExpandError::Expansion)?;
+
+	for iri in expanded.validate_iris_with(vocabulary, options.iri_validation) {
+		json_ld_core::warning::Handler::<N, expansion::Warning<N::BlankId>>::handle(
+			&mut warnings,
+			vocabulary,
+			expansion::Warning::MalformedIri(iri),
+		)
+	}
+
+	Ok(expanded)
+}
+
+/// Processes a context loaded before expansion, using the given `cache` if
+/// any.
+#[allow(clippy::too_many_arguments)]
+async fn process_context<I, N>(
+	context: &json_ld_syntax::context::Context,
+	vocabulary: &mut N,
+	active_context: &Context<I, N::BlankId>,
+	loader: &impl Loader,
+	base_url: Option<I>,
+	options: &Options<I>,
+	warnings: impl context_processing::WarningHandler<N>,
+	cache: Option<&SharedContextCache<I, N::BlankId>>,
+) -> Result<Context<I, N::BlankId>, ExpandError>
+where
+	N: VocabularyMut<Iri = I>,
+	I: Clone + Eq + Hash,
+	N::BlankId: Clone + Eq + Hash,
+{
+	let processed = match cache {
+		Some(cache) => {
+			cache
+				.process_full(
+					context,
+					vocabulary,
+					active_context,
+					loader,
+					base_url,
+					options.context_processing_options(),
+					warnings,
+				)
+				.await
+		}
+		None => {
+			context
+				.process_full(
+					vocabulary,
+					active_context,
+					loader,
+					base_url,
+					options.context_processing_options(),
+					warnings,
+				)
+				.await
+		}
+	};
+
+	processed
+		.map(|processed| processed.into_processed())
+		.map_err(ExpandError::ContextProcessing)
+}
+
 impl<I> JsonLdProcessor<I> for RemoteDocumentReference<I, json_syntax::Value> {
 	async fn compare_full<N>(
 		&self,
@@ -249,6 +398,31 @@ impl<I> JsonLdProcessor<I> for RemoteDocumentReference<I, json_syntax::Value> {
 		JsonLdProcessor::expand_full(doc.as_ref(), vocabulary, loader, options, warnings).await
 	}
 
+	async fn expand_full_with_cache<N>(
+		&self,
+		vocabulary: &mut N,
+		loader: &impl Loader,
+		options: Options<I>,
+		warnings: impl context_processing::WarningHandler<N> + expansion::WarningHandler<N>,
+		cache: &SharedContextCache<I, N::BlankId>,
+	) -> ExpandResult<I, N::BlankId>
+	where
+		N: VocabularyMut<Iri = I>,
+		I: Clone + Eq + Hash,
+		N::BlankId: Clone + Eq + Hash,
+	{
+		let doc = self.loaded_with(vocabulary, loader).await?;
+		JsonLdProcessor::expand_full_with_cache(
+			doc.as_ref(),
+			vocabulary,
+			loader,
+			options,
+			warnings,
+			cache,
+		)
+		.await
+	}
+
 	async fn into_document_full<'a, N>(
 		self,
 		vocabulary: &'a mut N,
@@ -283,6 +457,23 @@ impl<I> JsonLdProcessor<I> for RemoteDocumentReference<I, json_syntax::Value> {
 			.await
 	}
 
+	async fn compact_with_processed<'a, N>(
+		&'a self,
+		vocabulary: &'a mut N,
+		context: context_processing::ProcessedRef<'a, 'a, I, N::BlankId>,
+		loader: &'a impl Loader,
+		options: Options<I>,
+	) -> CompactResult
+	where
+		N: VocabularyMut<Iri = I>,
+		I: Clone + Eq + Hash,
+		N::BlankId: 'a + Clone + Eq + Hash,
+	{
+		let doc = self.loaded_with(vocabulary, loader).await?;
+		JsonLdProcessor::compact_with_processed(doc.as_ref(), vocabulary, context, loader, options)
+			.await
+	}
+
 	async fn flatten_full<'a, N>(
 		&'a self,
 		vocabulary: &'a mut N,
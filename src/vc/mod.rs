@@ -0,0 +1,431 @@
+//! Verifiable Credentials validation helpers.
+//!
+//! This module bundles the [Verifiable Credentials](https://www.w3.org/TR/vc-data-model-2.0/)
+//! v1.1 and v2 contexts and provides structural checks of credentials, on
+//! top of the expansion algorithm:
+//!   - the first `@context` is a VC context, selecting the [`Version`] of the
+//!     data model;
+//!   - the credential has the `VerifiableCredential` type;
+//!   - the credential has exactly one issuer, identified by an IRI;
+//!   - the credential has at least one credential subject;
+//!   - a v1.1 credential has an issuance date.
+//!
+//! Every failed check is reported as an [`Invalid`] value, with a stable
+//! [code](Invalid::code) that can be returned by issuer and verifier
+//! services. Proofs are not verified.
+//!
+//! ## Example
+//!
+//! ```
+//! # #[async_std::main]
+//! # async fn main() {
+//! use json_ld::{vc, NoLoader};
+//!
+//! let json = json_syntax::json!({
+//!   "@context": ["https://www.w3.org/ns/credentials/v2"],
+//!   "type": ["VerifiableCredential"],
+//!   "issuer": "did:example:issuer",
+//!   "credentialSubject": { "id": "did:example:subject" }
+//! });
+//!
+//! let credential = vc::validate(json, &NoLoader).await.unwrap();
+//! assert_eq!(credential.version(), vc::Version::V2);
+//! assert_eq!(credential.issuer().unwrap(), "did:example:issuer");
+//! # }
+//! ```
+use crate::{
+	ExpandError, Id, IndexedObject, JsonLdProcessor, LoadError, Loader, LoadingResult, NoLoader,
+	Node, RemoteDocument, Value,
+};
+use iref::{Iri, IriBuf};
+use json_syntax::Parse;
+use rdf_types::BlankIdBuf;
+use std::fmt;
+
+/// URL of the VC v1.1 context.
+pub const V1_CONTEXT_IRI: &str = "https://www.w3.org/2018/credentials/v1";
+
+/// Bundled VC v1.1 context document.
+pub const V1_CONTEXT: &str = include_str!("credentials-v1.jsonld");
+
+/// URL of the VC v2 context.
+pub const V2_CONTEXT_IRI: &str = "https://www.w3.org/ns/credentials/v2";
+
+/// Bundled VC v2 context document.
+pub const V2_CONTEXT: &str = include_str!("credentials-v2.jsonld");
+
+const VERIFIABLE_CREDENTIAL: &str = "https://www.w3.org/2018/credentials#VerifiableCredential";
+const ISSUER: &str = "https://www.w3.org/2018/credentials#issuer";
+const CREDENTIAL_SUBJECT: &str = "https://www.w3.org/2018/credentials#credentialSubject";
+const ISSUANCE_DATE: &str = "https://www.w3.org/2018/credentials#issuanceDate";
+const EXPIRATION_DATE: &str = "https://www.w3.org/2018/credentials#expirationDate";
+const VALID_FROM: &str = "https://www.w3.org/2018/credentials#validFrom";
+const VALID_UNTIL: &str = "https://www.w3.org/2018/credentials#validUntil";
+
+/// Version of the Verifiable Credentials data model.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Version {
+	/// Verifiable Credentials Data Model v1.1.
+	V1,
+
+	/// Verifiable Credentials Data Model v2.0.
+	V2,
+}
+
+impl Version {
+	/// Returns the IRI of the context of this version.
+	pub fn context_iri(&self) -> &'static str {
+		match self {
+			Self::V1 => V1_CONTEXT_IRI,
+			Self::V2 => V2_CONTEXT_IRI,
+		}
+	}
+
+	/// Returns the bundled context document of this version.
+	pub fn context(&self) -> &'static str {
+		match self {
+			Self::V1 => V1_CONTEXT,
+			Self::V2 => V2_CONTEXT,
+		}
+	}
+
+	/// Returns the version whose context has the given IRI, if any.
+	pub fn from_context_iri(iri: &str) -> Option<Self> {
+		match iri {
+			V1_CONTEXT_IRI => Some(Self::V1),
+			V2_CONTEXT_IRI => Some(Self::V2),
+			_ => None,
+		}
+	}
+
+	/// Returns the version of the given credential, given by the first
+	/// entry of its `@context`.
+	pub fn of(json: &json_syntax::Value) -> Option<Self> {
+		let context = json.as_object()?.get_unique("@context").ok()??;
+		let first = match context {
+			json_syntax::Value::Array(array) => array.first()?,
+			other => other,
+		};
+
+		Self::from_context_iri(first.as_str()?)
+	}
+}
+
+/// Loader serving the bundled VC contexts.
+///
+/// The VC v1.1 and v2 context IRIs are served from the bundled
+/// [`V1_CONTEXT`] and [`V2_CONTEXT`]. Other IRIs are loaded with the inner
+/// loader.
+#[derive(Debug, Default, Clone)]
+pub struct VcLoader<L = NoLoader>(L);
+
+impl<L> VcLoader<L> {
+	/// Wraps the given loader.
+	pub fn new(loader: L) -> Self {
+		Self(loader)
+	}
+
+	/// Returns the inner loader.
+	pub fn into_inner(self) -> L {
+		self.0
+	}
+}
+
+impl<L: Loader> Loader for VcLoader<L> {
+	async fn load(&self, url: &Iri) -> LoadingResult<IriBuf> {
+		match Version::from_context_iri(url.as_str()) {
+			Some(version) => {
+				let (doc, _) = json_syntax::Value::parse_str(version.context())
+					.map_err(|e| LoadError::new(url.to_owned(), e))?;
+				Ok(RemoteDocument::new(
+					Some(url.to_owned()),
+					Some("application/ld+json".parse().unwrap()),
+					doc,
+				))
+			}
+			None => self.0.load(url).await,
+		}
+	}
+}
+
+/// Structural error of a credential.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, thiserror::Error)]
+pub enum Invalid {
+	/// The first `@context` entry is not a VC context IRI.
+	#[error("the first `@context` entry must be a Verifiable Credentials context IRI")]
+	Context,
+
+	/// The credential does not have the `VerifiableCredential` type.
+	#[error("the credential must have the `VerifiableCredential` type")]
+	MissingType,
+
+	/// The credential has no issuer.
+	#[error("the credential must have an issuer")]
+	MissingIssuer,
+
+	/// The credential has more than one issuer.
+	#[error("the credential must have exactly one issuer")]
+	MultipleIssuers,
+
+	/// The issuer is not identified by an IRI.
+	#[error("the issuer must be identified by an IRI")]
+	InvalidIssuer,
+
+	/// The credential has no credential subject.
+	#[error("the credential must have a credential subject")]
+	MissingCredentialSubject,
+
+	/// The v1.1 credential has no issuance date.
+	#[error("the credential must have an issuance date")]
+	MissingIssuanceDate,
+}
+
+impl Invalid {
+	/// Returns the stable code of this error.
+	pub fn code(&self) -> &'static str {
+		match self {
+			Self::Context => "invalid context",
+			Self::MissingType => "missing type",
+			Self::MissingIssuer => "missing issuer",
+			Self::MultipleIssuers => "multiple issuers",
+			Self::InvalidIssuer => "invalid issuer",
+			Self::MissingCredentialSubject => "missing credential subject",
+			Self::MissingIssuanceDate => "missing issuance date",
+		}
+	}
+}
+
+/// Error raised by [`validate`].
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+	/// Document expansion failed.
+	#[error("Expansion failed: {0}")]
+	Expand(ExpandError),
+
+	/// The document does not contain exactly one top-level node object.
+	#[error("expected one top-level object, found {0}")]
+	NotOneObject(usize),
+
+	/// The credential is invalid.
+	#[error("invalid credential: {}", Errors(.0))]
+	Invalid(Vec<Invalid>),
+}
+
+/// Displays a list of errors.
+struct Errors<'a>(&'a [Invalid]);
+
+impl<'a> fmt::Display for Errors<'a> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		for (i, e) in self.0.iter().enumerate() {
+			if i > 0 {
+				f.write_str(", ")?;
+			}
+
+			e.fmt(f)?;
+		}
+
+		Ok(())
+	}
+}
+
+/// Validates the structure of a credential.
+///
+/// The version of the data model is given by the first `@context` entry (see
+/// [`Version::of`]). The credential is then expanded using the given loader
+/// wrapped in a [`VcLoader`], and checked. Every structural error found is
+/// returned in [`Error::Invalid`].
+pub async fn validate(json: json_syntax::Value, loader: &impl Loader) -> Result<Credential, Error> {
+	let version = match Version::of(&json) {
+		Some(version) => version,
+		None => return Err(Error::Invalid(vec![Invalid::Context])),
+	};
+
+	let document = RemoteDocument::new(None, None, json)
+		.expand(&VcLoader::new(loader))
+		.await
+		.map_err(Error::Expand)?;
+
+	let len = document.len();
+	let mut nodes = document
+		.into_iter()
+		.filter_map(|object| object.into_inner().into_node());
+	let credential = match (nodes.next(), len) {
+		(Some(node), 1) => Credential { version, node },
+		_ => return Err(Error::NotOneObject(len)),
+	};
+
+	let errors = credential.check();
+	if errors.is_empty() {
+		Ok(credential)
+	} else {
+		Err(Error::Invalid(errors))
+	}
+}
+
+/// Returns the objects of `node` for the given property.
+fn get<'a>(
+	node: &'a Node,
+	property: &str,
+) -> impl Iterator<Item = &'a IndexedObject<IriBuf, BlankIdBuf>> {
+	node.get(&Iri::new(property).unwrap())
+}
+
+/// Returns the first string value of `node` for the given property.
+fn get_str<'a>(node: &'a Node, property: &str) -> Option<&'a str> {
+	get(node, property)
+		.filter_map(|object| object.as_value())
+		.find_map(Value::as_str)
+}
+
+/// Expanded verifiable credential.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Credential {
+	version: Version,
+	node: Node,
+}
+
+impl Credential {
+	/// Returns the version of the data model used by the credential.
+	pub fn version(&self) -> Version {
+		self.version
+	}
+
+	/// Returns the underlying node object.
+	pub fn as_node(&self) -> &Node {
+		&self.node
+	}
+
+	/// Returns the underlying node object.
+	pub fn into_node(self) -> Node {
+		self.node
+	}
+
+	/// Returns the identifier of the credential, if it is an IRI.
+	pub fn id(&self) -> Option<&Iri> {
+		self.node
+			.id
+			.as_ref()
+			.and_then(Id::as_iri)
+			.map(IriBuf::as_iri)
+	}
+
+	/// Returns the IRIs of the credential types.
+	pub fn types(&self) -> impl Iterator<Item = &Iri> {
+		self.node
+			.types()
+			.iter()
+			.filter_map(Id::as_iri)
+			.map(IriBuf::as_iri)
+	}
+
+	/// Returns the issuer of the credential.
+	pub fn issuer(&self) -> Option<&Iri> {
+		get(&self.node, ISSUER)
+			.filter_map(|object| object.as_node())
+			.find_map(|node| node.id.as_ref().and_then(Id::as_iri))
+			.map(IriBuf::as_iri)
+	}
+
+	/// Returns the credential subjects.
+	pub fn subjects(&self) -> impl Iterator<Item = &Node> {
+		get(&self.node, CREDENTIAL_SUBJECT).filter_map(|object| object.as_node())
+	}
+
+	/// Returns the `issuanceDate` of a v1.1 credential.
+	pub fn issuance_date(&self) -> Option<&str> {
+		get_str(&self.node, ISSUANCE_DATE)
+	}
+
+	/// Returns the `expirationDate` of a v1.1 credential.
+	pub fn expiration_date(&self) -> Option<&str> {
+		get_str(&self.node, EXPIRATION_DATE)
+	}
+
+	/// Returns the `validFrom` date of the credential.
+	pub fn valid_from(&self) -> Option<&str> {
+		get_str(&self.node, VALID_FROM)
+	}
+
+	/// Returns the `validUntil` date of the credential.
+	pub fn valid_until(&self) -> Option<&str> {
+		get_str(&self.node, VALID_UNTIL)
+	}
+
+	/// Returns every structural error of the credential.
+	fn check(&self) -> Vec<Invalid> {
+		let mut errors = Vec::new();
+
+		if !self.types().any(|ty| ty == VERIFIABLE_CREDENTIAL) {
+			errors.push(Invalid::MissingType)
+		}
+
+		let issuers: Vec<_> = get(&self.node, ISSUER).collect();
+		match issuers.as_slice() {
+			[] => errors.push(Invalid::MissingIssuer),
+			[issuer] => {
+				let iri = issuer
+					.as_node()
+					.and_then(|node| node.id.as_ref())
+					.and_then(Id::as_iri);
+				if iri.is_none() {
+					errors.push(Invalid::InvalidIssuer)
+				}
+			}
+			_ => errors.push(Invalid::MultipleIssuers),
+		}
+
+		if self.subjects().next().is_none() {
+			errors.push(Invalid::MissingCredentialSubject)
+		}
+
+		if self.version == Version::V1 && self.issuance_date().is_none() {
+			errors.push(Invalid::MissingIssuanceDate)
+		}
+
+		errors
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[async_std::test]
+	async fn structural_errors() {
+		let json = json_syntax::json!({
+			"@context": ["https://www.w3.org/2018/credentials/v1"],
+			"type": ["VerifiableCredential"],
+			"issuer": "did:example:issuer",
+			"issuanceDate": "2024-01-01T00:00:00Z",
+			"credentialSubject": { "id": "did:example:subject" }
+		});
+
+		let credential = validate(json, &NoLoader).await.unwrap();
+		assert_eq!(credential.version(), Version::V1);
+		assert_eq!(credential.issuance_date(), Some("2024-01-01T00:00:00Z"));
+
+		let json = json_syntax::json!({
+			"@context": ["https://www.w3.org/2018/credentials/v1"],
+			"type": ["VerifiableCredential"],
+			"issuer": ["did:example:a", "did:example:b"]
+		});
+
+		match validate(json, &NoLoader).await {
+			Err(Error::Invalid(errors)) => assert_eq!(
+				errors,
+				[
+					Invalid::MultipleIssuers,
+					Invalid::MissingCredentialSubject,
+					Invalid::MissingIssuanceDate
+				]
+			),
+			_ => panic!("expected structural errors"),
+		}
+
+		let json = json_syntax::json!({ "type": ["VerifiableCredential"] });
+		assert!(matches!(
+			validate(json, &NoLoader).await,
+			Err(Error::Invalid(errors)) if errors == [Invalid::Context]
+		))
+	}
+}